@@ -0,0 +1,4 @@
+midl "meshx.component.decl" {
+  sources = glob("**/*.midl", current)
+  deps    = ["sdk/midl/meshx.component:meshx.component", "sdk/midl/meshx.url:meshx.url"]
+}
@@ -0,0 +1,914 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Conversion of [`ComponentDecl`]s to and from the `meshx.component.decl` MIDL types, and their
+//! persistent encoding, which is the format of the `.cm` files shipped in packages.
+
+use crate::{
+    Availability, CapabilityDecl, ChildDecl, CollectionDecl, ComponentDecl, DependencyType, DictionaryValue,
+    DirectoryDecl, Durability, EnvironmentDecl, EnvironmentExtends, ExposeDecl, ExposeDirectoryDecl,
+    ExposeProtocolDecl, ExposeResolverDecl, ExposeRunnerDecl, ExposeServiceDecl, ExposeSource, ExposeTarget, OfferDecl,
+    OfferDirectoryDecl, OfferProtocolDecl, OfferResolverDecl, OfferRunnerDecl, OfferServiceDecl, OfferSource,
+    OfferStorageDecl, OfferTarget, OnTerminate, ProgramDecl, ProtocolDecl, RegistrationSource, ResolverDecl,
+    ResolverRegistration, RunnerDecl, RunnerRegistration, ServiceDecl, StartupMode, StorageDecl,
+    StorageDirectorySource, UseDecl, UseDirectoryDecl, UseProtocolDecl, UseServiceDecl, UseSource, UseStorageDecl,
+};
+
+use cm_types::{Name, ParseError, Path, Url};
+use midl_meshx_component_decl as mdecl;
+use thiserror::Error;
+
+/// Errors produced by [`decode`].
+#[derive(Debug, Clone, Error)]
+pub enum DecodeError {
+    #[error("invalid MIDL encoding: {0}")]
+    Midl(#[from] midl::Error),
+    #[error("{decl}.{field} is missing")]
+    MissingField { decl: &'static str, field: &'static str },
+    #[error("{decl}.{field} is invalid: {err}")]
+    InvalidField {
+        decl: &'static str,
+        field: &'static str,
+        #[source]
+        err: ParseError,
+    },
+    #[error("{decl}.{field} has an unsupported value")]
+    UnsupportedValue { decl: &'static str, field: &'static str },
+}
+
+/// Encodes `decl` in the persistent MIDL format of `.cm` files.
+pub fn encode(decl: ComponentDecl) -> Result<Vec<u8>, midl::Error> {
+    midl::encoding::persist(&mdecl::Component::from(decl))
+}
+
+/// Decodes a `.cm` file.  The declaration is not validated, see [`crate::validate::validate`].
+pub fn decode(bytes: &[u8]) -> Result<ComponentDecl, DecodeError> {
+    midl::encoding::unpersist::<mdecl::Component>(bytes)?.try_into()
+}
+
+fn required<T>(value: Option<T>, decl: &'static str, field: &'static str) -> Result<T, DecodeError> {
+    value.ok_or(DecodeError::MissingField { decl, field })
+}
+
+fn parse<T>(
+    value: Option<String>,
+    decl: &'static str,
+    field: &'static str,
+    parse: impl FnOnce(String) -> Result<T, ParseError>,
+) -> Result<Option<T>, DecodeError> {
+    value
+        .map(|value| parse(value).map_err(|err| DecodeError::InvalidField { decl, field, err }))
+        .transpose()
+}
+
+fn name(value: Option<String>, decl: &'static str, field: &'static str) -> Result<Name, DecodeError> {
+    required(parse(value, decl, field, Name::new)?, decl, field)
+}
+
+fn optional_name(value: Option<String>, decl: &'static str, field: &'static str) -> Result<Option<Name>, DecodeError> {
+    parse(value, decl, field, Name::new)
+}
+
+fn path(value: Option<String>, decl: &'static str, field: &'static str) -> Result<Path, DecodeError> {
+    required(parse(value, decl, field, Path::new)?, decl, field)
+}
+
+fn optional_path(value: Option<String>, decl: &'static str, field: &'static str) -> Result<Option<Path>, DecodeError> {
+    parse(value, decl, field, Path::new)
+}
+
+fn vec<T, U: TryFrom<T, Error = DecodeError>>(values: Option<Vec<T>>) -> Result<Vec<U>, DecodeError> {
+    values.unwrap_or_default().into_iter().map(U::try_from).collect()
+}
+
+fn into_vec<T, U: From<T>>(values: Vec<T>) -> Option<Vec<U>> {
+    (!values.is_empty()).then(|| values.into_iter().map(U::from).collect())
+}
+
+fn child_ref(name: Name) -> mdecl::Ref {
+    mdecl::Ref::Child(mdecl::ChildRef { name: name.to_string() })
+}
+
+fn collection_ref(name: Name) -> mdecl::Ref {
+    mdecl::Ref::Collection(mdecl::CollectionRef { name: name.to_string() })
+}
+
+fn capability_ref(name: Name) -> mdecl::Ref {
+    mdecl::Ref::Capability(mdecl::CapabilityRef { name: name.to_string() })
+}
+
+/// A [`mdecl::Ref`] that was decoded, but not yet restricted to the references valid in a
+/// particular field.
+enum Ref {
+    Parent,
+    Self_,
+    Child(Name),
+    Collection(Name),
+    Framework,
+    Capability(Name),
+    Void,
+}
+
+fn decode_ref(value: Option<mdecl::Ref>, decl: &'static str, field: &'static str) -> Result<Ref, DecodeError> {
+    let name = |name: String| Name::new(name).map_err(|err| DecodeError::InvalidField { decl, field, err });
+    Ok(match required(value, decl, field)? {
+        mdecl::Ref::Parent(_) => Ref::Parent,
+        mdecl::Ref::Self_(_) => Ref::Self_,
+        mdecl::Ref::Child(child) => Ref::Child(name(child.name)?),
+        mdecl::Ref::Collection(collection) => Ref::Collection(name(collection.name)?),
+        mdecl::Ref::Framework(_) => Ref::Framework,
+        mdecl::Ref::Capability(capability) => Ref::Capability(name(capability.name)?),
+        mdecl::Ref::Void(_) => Ref::Void,
+        mdecl::Ref::__SourceBreaking { .. } => return Err(DecodeError::UnsupportedValue { decl, field }),
+    })
+}
+
+fn use_source(value: Option<mdecl::Ref>, decl: &'static str) -> Result<UseSource, DecodeError> {
+    Ok(match decode_ref(value, decl, "source")? {
+        Ref::Parent => UseSource::Parent,
+        Ref::Framework => UseSource::Framework,
+        Ref::Self_ => UseSource::Self_,
+        Ref::Child(name) => UseSource::Child(name),
+        Ref::Capability(name) => UseSource::Capability(name),
+        Ref::Collection(_) | Ref::Void => return Err(DecodeError::UnsupportedValue { decl, field: "source" }),
+    })
+}
+
+fn offer_source(value: Option<mdecl::Ref>, decl: &'static str) -> Result<OfferSource, DecodeError> {
+    Ok(match decode_ref(value, decl, "source")? {
+        Ref::Parent => OfferSource::Parent,
+        Ref::Framework => OfferSource::Framework,
+        Ref::Self_ => OfferSource::Self_,
+        Ref::Child(name) => OfferSource::Child(name),
+        Ref::Collection(name) => OfferSource::Collection(name),
+        Ref::Capability(name) => OfferSource::Capability(name),
+        Ref::Void => OfferSource::Void,
+    })
+}
+
+fn offer_target(value: Option<mdecl::Ref>, decl: &'static str) -> Result<OfferTarget, DecodeError> {
+    Ok(match decode_ref(value, decl, "target")? {
+        Ref::Child(name) => OfferTarget::Child(name),
+        Ref::Collection(name) => OfferTarget::Collection(name),
+        _ => return Err(DecodeError::UnsupportedValue { decl, field: "target" }),
+    })
+}
+
+fn expose_source(value: Option<mdecl::Ref>, decl: &'static str) -> Result<ExposeSource, DecodeError> {
+    Ok(match decode_ref(value, decl, "source")? {
+        Ref::Framework => ExposeSource::Framework,
+        Ref::Self_ => ExposeSource::Self_,
+        Ref::Child(name) => ExposeSource::Child(name),
+        Ref::Collection(name) => ExposeSource::Collection(name),
+        Ref::Capability(name) => ExposeSource::Capability(name),
+        Ref::Void => ExposeSource::Void,
+        Ref::Parent => return Err(DecodeError::UnsupportedValue { decl, field: "source" }),
+    })
+}
+
+fn expose_target(value: Option<mdecl::Ref>, decl: &'static str) -> Result<ExposeTarget, DecodeError> {
+    if value.is_none() {
+        return Ok(ExposeTarget::Parent);
+    }
+    Ok(match decode_ref(value, decl, "target")? {
+        Ref::Parent => ExposeTarget::Parent,
+        Ref::Framework => ExposeTarget::Framework,
+        _ => return Err(DecodeError::UnsupportedValue { decl, field: "target" }),
+    })
+}
+
+fn registration_source(value: Option<mdecl::Ref>, decl: &'static str) -> Result<RegistrationSource, DecodeError> {
+    Ok(match decode_ref(value, decl, "source")? {
+        Ref::Parent => RegistrationSource::Parent,
+        Ref::Self_ => RegistrationSource::Self_,
+        Ref::Child(name) => RegistrationSource::Child(name),
+        _ => return Err(DecodeError::UnsupportedValue { decl, field: "source" }),
+    })
+}
+
+fn storage_source(value: Option<mdecl::Ref>, decl: &'static str) -> Result<StorageDirectorySource, DecodeError> {
+    Ok(match decode_ref(value, decl, "source")? {
+        Ref::Parent => StorageDirectorySource::Parent,
+        Ref::Self_ => StorageDirectorySource::Self_,
+        Ref::Child(name) => StorageDirectorySource::Child(name),
+        _ => return Err(DecodeError::UnsupportedValue { decl, field: "source" }),
+    })
+}
+
+impl From<UseSource> for mdecl::Ref {
+    fn from(source: UseSource) -> Self {
+        match source {
+            UseSource::Parent => mdecl::Ref::Parent(mdecl::ParentRef),
+            UseSource::Framework => mdecl::Ref::Framework(mdecl::FrameworkRef),
+            UseSource::Self_ => mdecl::Ref::Self_(mdecl::SelfRef),
+            UseSource::Child(name) => child_ref(name),
+            UseSource::Capability(name) => capability_ref(name),
+        }
+    }
+}
+
+impl From<OfferSource> for mdecl::Ref {
+    fn from(source: OfferSource) -> Self {
+        match source {
+            OfferSource::Parent => mdecl::Ref::Parent(mdecl::ParentRef),
+            OfferSource::Framework => mdecl::Ref::Framework(mdecl::FrameworkRef),
+            OfferSource::Self_ => mdecl::Ref::Self_(mdecl::SelfRef),
+            OfferSource::Child(name) => child_ref(name),
+            OfferSource::Collection(name) => collection_ref(name),
+            OfferSource::Capability(name) => capability_ref(name),
+            OfferSource::Void => mdecl::Ref::Void(mdecl::VoidRef),
+        }
+    }
+}
+
+impl From<OfferTarget> for mdecl::Ref {
+    fn from(target: OfferTarget) -> Self {
+        match target {
+            OfferTarget::Child(name) => child_ref(name),
+            OfferTarget::Collection(name) => collection_ref(name),
+        }
+    }
+}
+
+impl From<ExposeSource> for mdecl::Ref {
+    fn from(source: ExposeSource) -> Self {
+        match source {
+            ExposeSource::Framework => mdecl::Ref::Framework(mdecl::FrameworkRef),
+            ExposeSource::Self_ => mdecl::Ref::Self_(mdecl::SelfRef),
+            ExposeSource::Child(name) => child_ref(name),
+            ExposeSource::Collection(name) => collection_ref(name),
+            ExposeSource::Capability(name) => capability_ref(name),
+            ExposeSource::Void => mdecl::Ref::Void(mdecl::VoidRef),
+        }
+    }
+}
+
+impl From<ExposeTarget> for mdecl::Ref {
+    fn from(target: ExposeTarget) -> Self {
+        match target {
+            ExposeTarget::Parent => mdecl::Ref::Parent(mdecl::ParentRef),
+            ExposeTarget::Framework => mdecl::Ref::Framework(mdecl::FrameworkRef),
+        }
+    }
+}
+
+impl From<RegistrationSource> for mdecl::Ref {
+    fn from(source: RegistrationSource) -> Self {
+        match source {
+            RegistrationSource::Parent => mdecl::Ref::Parent(mdecl::ParentRef),
+            RegistrationSource::Self_ => mdecl::Ref::Self_(mdecl::SelfRef),
+            RegistrationSource::Child(name) => child_ref(name),
+        }
+    }
+}
+
+impl From<StorageDirectorySource> for mdecl::Ref {
+    fn from(source: StorageDirectorySource) -> Self {
+        match source {
+            StorageDirectorySource::Parent => mdecl::Ref::Parent(mdecl::ParentRef),
+            StorageDirectorySource::Self_ => mdecl::Ref::Self_(mdecl::SelfRef),
+            StorageDirectorySource::Child(name) => child_ref(name),
+        }
+    }
+}
+
+/// Implements the conversions between a native enum and a MIDL enum with the same members.
+macro_rules! convert_enum {
+    ($native:ident, $midl:ident, [$($member:ident),*]) => {
+        impl From<$native> for mdecl::$midl {
+            fn from(value: $native) -> Self {
+                match value {
+                    $($native::$member => mdecl::$midl::$member,)*
+                }
+            }
+        }
+
+        impl From<mdecl::$midl> for $native {
+            fn from(value: mdecl::$midl) -> Self {
+                match value {
+                    $(mdecl::$midl::$member => $native::$member,)*
+                }
+            }
+        }
+    };
+}
+
+convert_enum!(DependencyType, DependencyType, [Strong, Weak]);
+convert_enum!(Availability, Availability, [Required, Optional]);
+convert_enum!(StartupMode, StartupMode, [Lazy, Eager]);
+convert_enum!(OnTerminate, OnTerminate, [None, Reboot]);
+convert_enum!(Durability, Durability, [Transient, SingleRun]);
+convert_enum!(EnvironmentExtends, EnvironmentExtends, [None, Realm]);
+
+impl From<ComponentDecl> for mdecl::Component {
+    fn from(decl: ComponentDecl) -> Self {
+        mdecl::Component {
+            program: decl.program.map(Into::into),
+            uses: into_vec(decl.uses),
+            exposes: into_vec(decl.exposes),
+            offers: into_vec(decl.offers),
+            capabilities: into_vec(decl.capabilities),
+            children: into_vec(decl.children),
+            collections: into_vec(decl.collections),
+            environments: into_vec(decl.environments),
+        }
+    }
+}
+
+impl TryFrom<mdecl::Component> for ComponentDecl {
+    type Error = DecodeError;
+
+    fn try_from(component: mdecl::Component) -> Result<Self, DecodeError> {
+        Ok(ComponentDecl {
+            program: component.program.map(TryInto::try_into).transpose()?,
+            uses: vec(component.uses)?,
+            exposes: vec(component.exposes)?,
+            offers: vec(component.offers)?,
+            capabilities: vec(component.capabilities)?,
+            children: vec(component.children)?,
+            collections: vec(component.collections)?,
+            environments: vec(component.environments)?,
+        })
+    }
+}
+
+impl From<ProgramDecl> for mdecl::Program {
+    fn from(program: ProgramDecl) -> Self {
+        let info = program.info.into_iter().map(|(key, value)| mdecl::ProgramProperty {
+            key: Some(key),
+            value: Some(match value {
+                DictionaryValue::Str(value) => mdecl::ProgramValue::Str(value),
+                DictionaryValue::StrVec(values) => mdecl::ProgramValue::StrVec(values),
+            }),
+        });
+        mdecl::Program {
+            runner: program.runner.map(|runner| runner.to_string()),
+            info: Some(info.collect()),
+        }
+    }
+}
+
+impl TryFrom<mdecl::Program> for ProgramDecl {
+    type Error = DecodeError;
+
+    fn try_from(program: mdecl::Program) -> Result<Self, DecodeError> {
+        let info = program.info.unwrap_or_default().into_iter().map(|property| {
+            let key = required(property.key, "ProgramProperty", "key")?;
+            let value = match required(property.value, "ProgramProperty", "value")? {
+                mdecl::ProgramValue::Str(value) => DictionaryValue::Str(value),
+                mdecl::ProgramValue::StrVec(values) => DictionaryValue::StrVec(values),
+                mdecl::ProgramValue::__SourceBreaking { .. } => {
+                    return Err(DecodeError::UnsupportedValue {
+                        decl: "ProgramProperty",
+                        field: "value",
+                    })
+                }
+            };
+            Ok((key, value))
+        });
+        Ok(ProgramDecl {
+            runner: optional_name(program.runner, "Program", "runner")?,
+            info: info.collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<CapabilityDecl> for mdecl::Capability {
+    fn from(capability: CapabilityDecl) -> Self {
+        match capability {
+            CapabilityDecl::Protocol(decl) => mdecl::Capability::Protocol(mdecl::Protocol {
+                name: Some(decl.name.to_string()),
+                source_path: decl.source_path.map(|path| path.to_string()),
+            }),
+            CapabilityDecl::Directory(decl) => mdecl::Capability::Directory(mdecl::Directory {
+                name: Some(decl.name.to_string()),
+                source_path: decl.source_path.map(|path| path.to_string()),
+            }),
+            CapabilityDecl::Storage(decl) => mdecl::Capability::Storage(mdecl::Storage {
+                name: Some(decl.name.to_string()),
+                source: Some(decl.source.into()),
+                backing_dir: Some(decl.backing_dir.to_string()),
+            }),
+            CapabilityDecl::Service(decl) => mdecl::Capability::Service(mdecl::Service {
+                name: Some(decl.name.to_string()),
+                source_path: decl.source_path.map(|path| path.to_string()),
+            }),
+            CapabilityDecl::Runner(decl) => mdecl::Capability::Runner(mdecl::Runner {
+                name: Some(decl.name.to_string()),
+                source_path: decl.source_path.map(|path| path.to_string()),
+            }),
+            CapabilityDecl::Resolver(decl) => mdecl::Capability::Resolver(mdecl::Resolver {
+                name: Some(decl.name.to_string()),
+                source_path: decl.source_path.map(|path| path.to_string()),
+            }),
+        }
+    }
+}
+
+impl TryFrom<mdecl::Capability> for CapabilityDecl {
+    type Error = DecodeError;
+
+    fn try_from(capability: mdecl::Capability) -> Result<Self, DecodeError> {
+        Ok(match capability {
+            mdecl::Capability::Protocol(decl) => CapabilityDecl::Protocol(ProtocolDecl {
+                name: name(decl.name, "Protocol", "name")?,
+                source_path: optional_path(decl.source_path, "Protocol", "source_path")?,
+            }),
+            mdecl::Capability::Directory(decl) => CapabilityDecl::Directory(DirectoryDecl {
+                name: name(decl.name, "Directory", "name")?,
+                source_path: optional_path(decl.source_path, "Directory", "source_path")?,
+            }),
+            mdecl::Capability::Storage(decl) => CapabilityDecl::Storage(StorageDecl {
+                name: name(decl.name, "Storage", "name")?,
+                source: storage_source(decl.source, "Storage")?,
+                backing_dir: name(decl.backing_dir, "Storage", "backing_dir")?,
+            }),
+            mdecl::Capability::Service(decl) => CapabilityDecl::Service(ServiceDecl {
+                name: name(decl.name, "Service", "name")?,
+                source_path: optional_path(decl.source_path, "Service", "source_path")?,
+            }),
+            mdecl::Capability::Runner(decl) => CapabilityDecl::Runner(RunnerDecl {
+                name: name(decl.name, "Runner", "name")?,
+                source_path: optional_path(decl.source_path, "Runner", "source_path")?,
+            }),
+            mdecl::Capability::Resolver(decl) => CapabilityDecl::Resolver(ResolverDecl {
+                name: name(decl.name, "Resolver", "name")?,
+                source_path: optional_path(decl.source_path, "Resolver", "source_path")?,
+            }),
+            mdecl::Capability::__SourceBreaking { .. } => {
+                return Err(DecodeError::UnsupportedValue {
+                    decl: "Component",
+                    field: "capabilities",
+                })
+            }
+        })
+    }
+}
+
+impl From<UseDecl> for mdecl::Use {
+    fn from(use_: UseDecl) -> Self {
+        match use_ {
+            UseDecl::Protocol(decl) => mdecl::Use::Protocol(mdecl::UseProtocol {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target_path: Some(decl.target_path.to_string()),
+                dependency_type: Some(decl.dependency_type.into()),
+                availability: Some(decl.availability.into()),
+            }),
+            UseDecl::Directory(decl) => mdecl::Use::Directory(mdecl::UseDirectory {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target_path: Some(decl.target_path.to_string()),
+                dependency_type: Some(decl.dependency_type.into()),
+                availability: Some(decl.availability.into()),
+            }),
+            UseDecl::Storage(decl) => mdecl::Use::Storage(mdecl::UseStorage {
+                source_name: Some(decl.source_name.to_string()),
+                target_path: Some(decl.target_path.to_string()),
+                availability: Some(decl.availability.into()),
+            }),
+            UseDecl::Service(decl) => mdecl::Use::Service(mdecl::UseService {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target_path: Some(decl.target_path.to_string()),
+                dependency_type: Some(decl.dependency_type.into()),
+                availability: Some(decl.availability.into()),
+            }),
+        }
+    }
+}
+
+impl TryFrom<mdecl::Use> for UseDecl {
+    type Error = DecodeError;
+
+    fn try_from(use_: mdecl::Use) -> Result<Self, DecodeError> {
+        Ok(match use_ {
+            mdecl::Use::Protocol(decl) => UseDecl::Protocol(UseProtocolDecl {
+                source: use_source(decl.source, "UseProtocol")?,
+                source_name: name(decl.source_name, "UseProtocol", "source_name")?,
+                target_path: path(decl.target_path, "UseProtocol", "target_path")?,
+                dependency_type: decl.dependency_type.map(Into::into).unwrap_or_default(),
+                availability: decl.availability.map(Into::into).unwrap_or_default(),
+            }),
+            mdecl::Use::Directory(decl) => UseDecl::Directory(UseDirectoryDecl {
+                source: use_source(decl.source, "UseDirectory")?,
+                source_name: name(decl.source_name, "UseDirectory", "source_name")?,
+                target_path: path(decl.target_path, "UseDirectory", "target_path")?,
+                dependency_type: decl.dependency_type.map(Into::into).unwrap_or_default(),
+                availability: decl.availability.map(Into::into).unwrap_or_default(),
+            }),
+            mdecl::Use::Storage(decl) => UseDecl::Storage(UseStorageDecl {
+                source_name: name(decl.source_name, "UseStorage", "source_name")?,
+                target_path: path(decl.target_path, "UseStorage", "target_path")?,
+                availability: decl.availability.map(Into::into).unwrap_or_default(),
+            }),
+            mdecl::Use::Service(decl) => UseDecl::Service(UseServiceDecl {
+                source: use_source(decl.source, "UseService")?,
+                source_name: name(decl.source_name, "UseService", "source_name")?,
+                target_path: path(decl.target_path, "UseService", "target_path")?,
+                dependency_type: decl.dependency_type.map(Into::into).unwrap_or_default(),
+                availability: decl.availability.map(Into::into).unwrap_or_default(),
+            }),
+            mdecl::Use::__SourceBreaking { .. } => {
+                return Err(DecodeError::UnsupportedValue {
+                    decl: "Component",
+                    field: "uses",
+                })
+            }
+        })
+    }
+}
+
+impl From<OfferDecl> for mdecl::Offer {
+    fn from(offer: OfferDecl) -> Self {
+        match offer {
+            OfferDecl::Protocol(decl) => mdecl::Offer::Protocol(mdecl::OfferProtocol {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target: Some(decl.target.into()),
+                target_name: Some(decl.target_name.to_string()),
+                dependency_type: Some(decl.dependency_type.into()),
+                availability: Some(decl.availability.into()),
+            }),
+            OfferDecl::Directory(decl) => mdecl::Offer::Directory(mdecl::OfferDirectory {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target: Some(decl.target.into()),
+                target_name: Some(decl.target_name.to_string()),
+                dependency_type: Some(decl.dependency_type.into()),
+                availability: Some(decl.availability.into()),
+            }),
+            OfferDecl::Storage(decl) => mdecl::Offer::Storage(mdecl::OfferStorage {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target: Some(decl.target.into()),
+                target_name: Some(decl.target_name.to_string()),
+                availability: Some(decl.availability.into()),
+            }),
+            OfferDecl::Service(decl) => mdecl::Offer::Service(mdecl::OfferService {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target: Some(decl.target.into()),
+                target_name: Some(decl.target_name.to_string()),
+                dependency_type: Some(decl.dependency_type.into()),
+                availability: Some(decl.availability.into()),
+            }),
+            OfferDecl::Runner(decl) => mdecl::Offer::Runner(mdecl::OfferRunner {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target: Some(decl.target.into()),
+                target_name: Some(decl.target_name.to_string()),
+            }),
+            OfferDecl::Resolver(decl) => mdecl::Offer::Resolver(mdecl::OfferResolver {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target: Some(decl.target.into()),
+                target_name: Some(decl.target_name.to_string()),
+            }),
+        }
+    }
+}
+
+impl TryFrom<mdecl::Offer> for OfferDecl {
+    type Error = DecodeError;
+
+    fn try_from(offer: mdecl::Offer) -> Result<Self, DecodeError> {
+        Ok(match offer {
+            mdecl::Offer::Protocol(decl) => OfferDecl::Protocol(OfferProtocolDecl {
+                source: offer_source(decl.source, "OfferProtocol")?,
+                source_name: name(decl.source_name, "OfferProtocol", "source_name")?,
+                target: offer_target(decl.target, "OfferProtocol")?,
+                target_name: name(decl.target_name, "OfferProtocol", "target_name")?,
+                dependency_type: decl.dependency_type.map(Into::into).unwrap_or_default(),
+                availability: decl.availability.map(Into::into).unwrap_or_default(),
+            }),
+            mdecl::Offer::Directory(decl) => OfferDecl::Directory(OfferDirectoryDecl {
+                source: offer_source(decl.source, "OfferDirectory")?,
+                source_name: name(decl.source_name, "OfferDirectory", "source_name")?,
+                target: offer_target(decl.target, "OfferDirectory")?,
+                target_name: name(decl.target_name, "OfferDirectory", "target_name")?,
+                dependency_type: decl.dependency_type.map(Into::into).unwrap_or_default(),
+                availability: decl.availability.map(Into::into).unwrap_or_default(),
+            }),
+            mdecl::Offer::Storage(decl) => OfferDecl::Storage(OfferStorageDecl {
+                source: offer_source(decl.source, "OfferStorage")?,
+                source_name: name(decl.source_name, "OfferStorage", "source_name")?,
+                target: offer_target(decl.target, "OfferStorage")?,
+                target_name: name(decl.target_name, "OfferStorage", "target_name")?,
+                availability: decl.availability.map(Into::into).unwrap_or_default(),
+            }),
+            mdecl::Offer::Service(decl) => OfferDecl::Service(OfferServiceDecl {
+                source: offer_source(decl.source, "OfferService")?,
+                source_name: name(decl.source_name, "OfferService", "source_name")?,
+                target: offer_target(decl.target, "OfferService")?,
+                target_name: name(decl.target_name, "OfferService", "target_name")?,
+                dependency_type: decl.dependency_type.map(Into::into).unwrap_or_default(),
+                availability: decl.availability.map(Into::into).unwrap_or_default(),
+            }),
+            mdecl::Offer::Runner(decl) => OfferDecl::Runner(OfferRunnerDecl {
+                source: offer_source(decl.source, "OfferRunner")?,
+                source_name: name(decl.source_name, "OfferRunner", "source_name")?,
+                target: offer_target(decl.target, "OfferRunner")?,
+                target_name: name(decl.target_name, "OfferRunner", "target_name")?,
+            }),
+            mdecl::Offer::Resolver(decl) => OfferDecl::Resolver(OfferResolverDecl {
+                source: offer_source(decl.source, "OfferResolver")?,
+                source_name: name(decl.source_name, "OfferResolver", "source_name")?,
+                target: offer_target(decl.target, "OfferResolver")?,
+                target_name: name(decl.target_name, "OfferResolver", "target_name")?,
+            }),
+            mdecl::Offer::__SourceBreaking { .. } => {
+                return Err(DecodeError::UnsupportedValue {
+                    decl: "Component",
+                    field: "offers",
+                })
+            }
+        })
+    }
+}
+
+impl From<ExposeDecl> for mdecl::Expose {
+    fn from(expose: ExposeDecl) -> Self {
+        match expose {
+            ExposeDecl::Protocol(decl) => mdecl::Expose::Protocol(mdecl::ExposeProtocol {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target: Some(decl.target.into()),
+                target_name: Some(decl.target_name.to_string()),
+            }),
+            ExposeDecl::Directory(decl) => mdecl::Expose::Directory(mdecl::ExposeDirectory {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target: Some(decl.target.into()),
+                target_name: Some(decl.target_name.to_string()),
+            }),
+            ExposeDecl::Service(decl) => mdecl::Expose::Service(mdecl::ExposeService {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target: Some(decl.target.into()),
+                target_name: Some(decl.target_name.to_string()),
+            }),
+            ExposeDecl::Runner(decl) => mdecl::Expose::Runner(mdecl::ExposeRunner {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target: Some(decl.target.into()),
+                target_name: Some(decl.target_name.to_string()),
+            }),
+            ExposeDecl::Resolver(decl) => mdecl::Expose::Resolver(mdecl::ExposeResolver {
+                source: Some(decl.source.into()),
+                source_name: Some(decl.source_name.to_string()),
+                target: Some(decl.target.into()),
+                target_name: Some(decl.target_name.to_string()),
+            }),
+        }
+    }
+}
+
+impl TryFrom<mdecl::Expose> for ExposeDecl {
+    type Error = DecodeError;
+
+    fn try_from(expose: mdecl::Expose) -> Result<Self, DecodeError> {
+        Ok(match expose {
+            mdecl::Expose::Protocol(decl) => ExposeDecl::Protocol(ExposeProtocolDecl {
+                source: expose_source(decl.source, "ExposeProtocol")?,
+                source_name: name(decl.source_name, "ExposeProtocol", "source_name")?,
+                target: expose_target(decl.target, "ExposeProtocol")?,
+                target_name: name(decl.target_name, "ExposeProtocol", "target_name")?,
+            }),
+            mdecl::Expose::Directory(decl) => ExposeDecl::Directory(ExposeDirectoryDecl {
+                source: expose_source(decl.source, "ExposeDirectory")?,
+                source_name: name(decl.source_name, "ExposeDirectory", "source_name")?,
+                target: expose_target(decl.target, "ExposeDirectory")?,
+                target_name: name(decl.target_name, "ExposeDirectory", "target_name")?,
+            }),
+            mdecl::Expose::Service(decl) => ExposeDecl::Service(ExposeServiceDecl {
+                source: expose_source(decl.source, "ExposeService")?,
+                source_name: name(decl.source_name, "ExposeService", "source_name")?,
+                target: expose_target(decl.target, "ExposeService")?,
+                target_name: name(decl.target_name, "ExposeService", "target_name")?,
+            }),
+            mdecl::Expose::Runner(decl) => ExposeDecl::Runner(ExposeRunnerDecl {
+                source: expose_source(decl.source, "ExposeRunner")?,
+                source_name: name(decl.source_name, "ExposeRunner", "source_name")?,
+                target: expose_target(decl.target, "ExposeRunner")?,
+                target_name: name(decl.target_name, "ExposeRunner", "target_name")?,
+            }),
+            mdecl::Expose::Resolver(decl) => ExposeDecl::Resolver(ExposeResolverDecl {
+                source: expose_source(decl.source, "ExposeResolver")?,
+                source_name: name(decl.source_name, "ExposeResolver", "source_name")?,
+                target: expose_target(decl.target, "ExposeResolver")?,
+                target_name: name(decl.target_name, "ExposeResolver", "target_name")?,
+            }),
+            mdecl::Expose::__SourceBreaking { .. } => {
+                return Err(DecodeError::UnsupportedValue {
+                    decl: "Component",
+                    field: "exposes",
+                })
+            }
+        })
+    }
+}
+
+impl From<ChildDecl> for mdecl::Child {
+    fn from(child: ChildDecl) -> Self {
+        mdecl::Child {
+            name: Some(child.name.to_string()),
+            url: Some(child.url.to_string()),
+            startup: Some(child.startup.into()),
+            on_terminate: child.on_terminate.map(Into::into),
+            environment: child.environment.map(|environment| environment.to_string()),
+        }
+    }
+}
+
+impl TryFrom<mdecl::Child> for ChildDecl {
+    type Error = DecodeError;
+
+    fn try_from(child: mdecl::Child) -> Result<Self, DecodeError> {
+        Ok(ChildDecl {
+            name: name(child.name, "Child", "name")?,
+            url: required(parse(child.url, "Child", "url", Url::new)?, "Child", "url")?,
+            startup: child.startup.map(Into::into).unwrap_or_default(),
+            on_terminate: child.on_terminate.map(Into::into),
+            environment: optional_name(child.environment, "Child", "environment")?,
+        })
+    }
+}
+
+impl From<CollectionDecl> for mdecl::Collection {
+    fn from(collection: CollectionDecl) -> Self {
+        mdecl::Collection {
+            name: Some(collection.name.to_string()),
+            durability: Some(collection.durability.into()),
+            environment: collection.environment.map(|environment| environment.to_string()),
+        }
+    }
+}
+
+impl TryFrom<mdecl::Collection> for CollectionDecl {
+    type Error = DecodeError;
+
+    fn try_from(collection: mdecl::Collection) -> Result<Self, DecodeError> {
+        Ok(CollectionDecl {
+            name: name(collection.name, "Collection", "name")?,
+            durability: collection.durability.map(Into::into).unwrap_or_default(),
+            environment: optional_name(collection.environment, "Collection", "environment")?,
+        })
+    }
+}
+
+impl From<EnvironmentDecl> for mdecl::Environment {
+    fn from(environment: EnvironmentDecl) -> Self {
+        mdecl::Environment {
+            name: Some(environment.name.to_string()),
+            extends: Some(environment.extends.into()),
+            runners: into_vec(environment.runners),
+            resolvers: into_vec(environment.resolvers),
+            stop_timeout_ms: environment.stop_timeout_ms,
+        }
+    }
+}
+
+impl TryFrom<mdecl::Environment> for EnvironmentDecl {
+    type Error = DecodeError;
+
+    fn try_from(environment: mdecl::Environment) -> Result<Self, DecodeError> {
+        Ok(EnvironmentDecl {
+            name: name(environment.name, "Environment", "name")?,
+            extends: environment.extends.map(Into::into).unwrap_or_default(),
+            runners: vec(environment.runners)?,
+            resolvers: vec(environment.resolvers)?,
+            stop_timeout_ms: environment.stop_timeout_ms,
+        })
+    }
+}
+
+impl From<RunnerRegistration> for mdecl::RunnerRegistration {
+    fn from(runner: RunnerRegistration) -> Self {
+        mdecl::RunnerRegistration {
+            source_name: Some(runner.source_name.to_string()),
+            source: Some(runner.source.into()),
+            target_name: Some(runner.target_name.to_string()),
+        }
+    }
+}
+
+impl TryFrom<mdecl::RunnerRegistration> for RunnerRegistration {
+    type Error = DecodeError;
+
+    fn try_from(runner: mdecl::RunnerRegistration) -> Result<Self, DecodeError> {
+        Ok(RunnerRegistration {
+            source_name: name(runner.source_name, "RunnerRegistration", "source_name")?,
+            source: registration_source(runner.source, "RunnerRegistration")?,
+            target_name: name(runner.target_name, "RunnerRegistration", "target_name")?,
+        })
+    }
+}
+
+impl From<ResolverRegistration> for mdecl::ResolverRegistration {
+    fn from(resolver: ResolverRegistration) -> Self {
+        mdecl::ResolverRegistration {
+            resolver: Some(resolver.resolver.to_string()),
+            source: Some(resolver.source.into()),
+            scheme: Some(resolver.scheme),
+        }
+    }
+}
+
+impl TryFrom<mdecl::ResolverRegistration> for ResolverRegistration {
+    type Error = DecodeError;
+
+    fn try_from(resolver: mdecl::ResolverRegistration) -> Result<Self, DecodeError> {
+        Ok(ResolverRegistration {
+            resolver: name(resolver.resolver, "ResolverRegistration", "resolver")?,
+            source: registration_source(resolver.source, "ResolverRegistration")?,
+            scheme: required(resolver.scheme, "ResolverRegistration", "scheme")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let decl: ComponentDecl = serde_yml::from_str(
+            r#"
+program:
+  runner: wasm
+  binary: bin/echo
+  args: [--verbose]
+use:
+  - type: protocol
+    source:
+      child: server
+    source_name: echo.Echo
+    target_path: /svc/echo.Echo
+    dependency_type: weak
+  - type: storage
+    source_name: data
+    target_path: /data
+    availability: optional
+capabilities:
+  - type: storage
+    name: data
+    source: parent
+    backing_dir: minfs
+children:
+  - name: server
+    url: mxpkg://meshx.co/echo#meta/echo.cm
+    on_terminate: reboot
+environments:
+  - name: env
+    extends: realm
+    runners:
+      - source_name: wasm
+        source: self
+        target_name: wasm
+    stop_timeout_ms: 1000
+offer:
+  - type: service
+    source: void
+    source_name: echo.Service
+    target:
+      child: server
+    target_name: echo.Service
+    availability: optional
+expose:
+  - type: runner
+    source:
+      child: server
+    source_name: wasm
+    target: framework
+    target_name: wasm
+"#,
+        )
+        .unwrap();
+
+        let bytes = encode(decl.clone()).unwrap();
+        assert_eq!(decode(&bytes).unwrap(), decl);
+    }
+
+    #[test]
+    fn decode_rejects_invalid_names() {
+        let component = mdecl::Component {
+            children: Some(vec![mdecl::Child {
+                name: Some("not a name".to_string()),
+                url: Some("mxpkg://meshx.co/echo#meta/echo.cm".to_string()),
+                ..mdecl::Child::EMPTY
+            }]),
+            ..mdecl::Component::EMPTY
+        };
+        let bytes = midl::encoding::persist(&component).unwrap();
+        assert!(matches!(
+            decode(&bytes),
+            Err(DecodeError::InvalidField {
+                decl: "Child",
+                field: "name",
+                err: ParseError::InvalidValue
+            })
+        ));
+    }
+}
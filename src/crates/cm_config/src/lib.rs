@@ -1,29 +1,30 @@
 // Copyright 2020 The Fuchsia Authors. All rights reserved.
 
 use cm_types::{CapabilityName, CapabilityTypeName};
-use cm_types::{LongName, Name, Path, Url};
+use cm_types::{Name, Path, Url};
 use moniker::{AbsoluteMoniker, ExtendedMoniker};
-use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::rc::Rc;
 
-//#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+pub mod encoding;
+pub mod validate;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 //#[fidl_decl(fidl_table = "fdecl::Resolver")]
 pub struct ResolverDecl {
     pub name: Name,
     pub source_path: Option<Path>,
 }
 
-//#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 //#[fidl_decl(fidl_table = "fdecl::Runner")]
 pub struct RunnerDecl {
     pub name: Name,
     pub source_path: Option<Path>,
 }
 
-//#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 //#[fidl_decl(fidl_table = "fdecl::Protocol")]
 pub struct ProtocolDecl {
     pub name: Name,
@@ -33,15 +34,684 @@ pub struct ProtocolDecl {
     pub delivery: DeliveryType,
 }
 
-//#[serde(tag = "type", rename_all = "snake_case")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::Directory")]
+pub struct DirectoryDecl {
+    pub name: Name,
+    pub source_path: Option<Path>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::Service")]
+pub struct ServiceDecl {
+    pub name: Name,
+    pub source_path: Option<Path>,
+}
+
+/// A storage capability: isolated per-component storage, carved out of the directory capability
+/// `backing_dir` that is routed to this component from `source`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::Storage")]
+pub struct StorageDecl {
+    pub name: Name,
+    pub source: StorageDirectorySource,
+    pub backing_dir: Name,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 //#[fidl_decl(fidl_union = "fdecl::Capability")]
 pub enum CapabilityDecl {
     Protocol(ProtocolDecl),
+    Directory(DirectoryDecl),
+    Storage(StorageDecl),
+    Service(ServiceDecl),
     Runner(RunnerDecl),
     Resolver(ResolverDecl),
 }
 
+impl CapabilityDecl {
+    pub fn name(&self) -> &Name {
+        match self {
+            CapabilityDecl::Protocol(decl) => &decl.name,
+            CapabilityDecl::Directory(decl) => &decl.name,
+            CapabilityDecl::Storage(decl) => &decl.name,
+            CapabilityDecl::Service(decl) => &decl.name,
+            CapabilityDecl::Runner(decl) => &decl.name,
+            CapabilityDecl::Resolver(decl) => &decl.name,
+        }
+    }
+
+    pub fn type_name(&self) -> CapabilityTypeName {
+        match self {
+            CapabilityDecl::Protocol(_) => CapabilityTypeName::Protocol,
+            CapabilityDecl::Directory(_) => CapabilityTypeName::Directory,
+            CapabilityDecl::Storage(_) => CapabilityTypeName::Storage,
+            CapabilityDecl::Service(_) => CapabilityTypeName::Service,
+            CapabilityDecl::Runner(_) => CapabilityTypeName::Runner,
+            CapabilityDecl::Resolver(_) => CapabilityTypeName::Resolver,
+        }
+    }
+}
+
+/// A component manifest.  This is the compiled form of the manifests shipped in packages, and
+/// declares the component's program, the capabilities it uses, declares, offers to its children
+/// and exposes to its parent, and the children, collections and environments it defines.
+///
+/// Declarations are only meaningful once they passed [`validate::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+//#[fidl_decl(fidl_table = "fdecl::Component")]
+pub struct ComponentDecl {
+    pub program: Option<ProgramDecl>,
+    #[serde(rename = "use")]
+    pub uses: Vec<UseDecl>,
+    #[serde(rename = "expose")]
+    pub exposes: Vec<ExposeDecl>,
+    #[serde(rename = "offer")]
+    pub offers: Vec<OfferDecl>,
+    pub capabilities: Vec<CapabilityDecl>,
+    pub children: Vec<ChildDecl>,
+    pub collections: Vec<CollectionDecl>,
+    pub environments: Vec<EnvironmentDecl>,
+}
+
+impl ComponentDecl {
+    /// Returns the child named `name`, if declared.
+    pub fn find_child(&self, name: &Name) -> Option<&ChildDecl> {
+        self.children.iter().find(|child| &child.name == name)
+    }
+
+    /// Returns the collection named `name`, if declared.
+    pub fn find_collection(&self, name: &Name) -> Option<&CollectionDecl> {
+        self.collections.iter().find(|collection| &collection.name == name)
+    }
+
+    /// Returns the environment named `name`, if declared.
+    pub fn find_environment(&self, name: &Name) -> Option<&EnvironmentDecl> {
+        self.environments.iter().find(|environment| &environment.name == name)
+    }
+
+    /// Returns the capability named `name`, if declared.
+    pub fn find_capability(&self, name: &Name) -> Option<&CapabilityDecl> {
+        self.capabilities.iter().find(|capability| capability.name() == name)
+    }
+}
+
+/// The program a component runs, and the runner that runs it.  Everything but `runner` is
+/// passed to the runner as is.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ProgramDecl {
+    pub runner: Option<Name>,
+    #[serde(flatten)]
+    pub info: BTreeMap<String, DictionaryValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum DictionaryValue {
+    Str(String),
+    StrVec(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+//#[fidl_decl(fidl_union = "fdecl::Use")]
+pub enum UseDecl {
+    Protocol(UseProtocolDecl),
+    Directory(UseDirectoryDecl),
+    Storage(UseStorageDecl),
+    Service(UseServiceDecl),
+}
+
+impl UseDecl {
+    pub fn type_name(&self) -> CapabilityTypeName {
+        match self {
+            UseDecl::Protocol(_) => CapabilityTypeName::Protocol,
+            UseDecl::Directory(_) => CapabilityTypeName::Directory,
+            UseDecl::Storage(_) => CapabilityTypeName::Storage,
+            UseDecl::Service(_) => CapabilityTypeName::Service,
+        }
+    }
+
+    /// Where the capability comes from.  Storage is always used from the parent.
+    pub fn source(&self) -> UseSource {
+        match self {
+            UseDecl::Protocol(decl) => decl.source.clone(),
+            UseDecl::Directory(decl) => decl.source.clone(),
+            UseDecl::Storage(_) => UseSource::Parent,
+            UseDecl::Service(decl) => decl.source.clone(),
+        }
+    }
+
+    pub fn source_name(&self) -> &Name {
+        match self {
+            UseDecl::Protocol(decl) => &decl.source_name,
+            UseDecl::Directory(decl) => &decl.source_name,
+            UseDecl::Storage(decl) => &decl.source_name,
+            UseDecl::Service(decl) => &decl.source_name,
+        }
+    }
+
+    /// The path the capability is installed at in the component's namespace.
+    pub fn target_path(&self) -> &Path {
+        match self {
+            UseDecl::Protocol(decl) => &decl.target_path,
+            UseDecl::Directory(decl) => &decl.target_path,
+            UseDecl::Storage(decl) => &decl.target_path,
+            UseDecl::Service(decl) => &decl.target_path,
+        }
+    }
+
+    pub fn dependency_type(&self) -> DependencyType {
+        match self {
+            UseDecl::Protocol(decl) => decl.dependency_type,
+            UseDecl::Directory(decl) => decl.dependency_type,
+            UseDecl::Storage(_) => DependencyType::Strong,
+            UseDecl::Service(decl) => decl.dependency_type,
+        }
+    }
+
+    pub fn availability(&self) -> Availability {
+        match self {
+            UseDecl::Protocol(decl) => decl.availability,
+            UseDecl::Directory(decl) => decl.availability,
+            UseDecl::Storage(decl) => decl.availability,
+            UseDecl::Service(decl) => decl.availability,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::UseProtocol")]
+pub struct UseProtocolDecl {
+    pub source: UseSource,
+    pub source_name: Name,
+    pub target_path: Path,
+    #[serde(default)]
+    pub dependency_type: DependencyType,
+    #[serde(default)]
+    pub availability: Availability,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::UseDirectory")]
+pub struct UseDirectoryDecl {
+    pub source: UseSource,
+    pub source_name: Name,
+    pub target_path: Path,
+    #[serde(default)]
+    pub dependency_type: DependencyType,
+    #[serde(default)]
+    pub availability: Availability,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::UseStorage")]
+pub struct UseStorageDecl {
+    pub source_name: Name,
+    pub target_path: Path,
+    #[serde(default)]
+    pub availability: Availability,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::UseService")]
+pub struct UseServiceDecl {
+    pub source: UseSource,
+    pub source_name: Name,
+    pub target_path: Path,
+    #[serde(default)]
+    pub dependency_type: DependencyType,
+    #[serde(default)]
+    pub availability: Availability,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+//#[fidl_decl(fidl_union = "fdecl::Offer")]
+pub enum OfferDecl {
+    Protocol(OfferProtocolDecl),
+    Directory(OfferDirectoryDecl),
+    Storage(OfferStorageDecl),
+    Service(OfferServiceDecl),
+    Runner(OfferRunnerDecl),
+    Resolver(OfferResolverDecl),
+}
+
+impl OfferDecl {
+    pub fn type_name(&self) -> CapabilityTypeName {
+        match self {
+            OfferDecl::Protocol(_) => CapabilityTypeName::Protocol,
+            OfferDecl::Directory(_) => CapabilityTypeName::Directory,
+            OfferDecl::Storage(_) => CapabilityTypeName::Storage,
+            OfferDecl::Service(_) => CapabilityTypeName::Service,
+            OfferDecl::Runner(_) => CapabilityTypeName::Runner,
+            OfferDecl::Resolver(_) => CapabilityTypeName::Resolver,
+        }
+    }
+
+    pub fn source(&self) -> &OfferSource {
+        match self {
+            OfferDecl::Protocol(decl) => &decl.source,
+            OfferDecl::Directory(decl) => &decl.source,
+            OfferDecl::Storage(decl) => &decl.source,
+            OfferDecl::Service(decl) => &decl.source,
+            OfferDecl::Runner(decl) => &decl.source,
+            OfferDecl::Resolver(decl) => &decl.source,
+        }
+    }
+
+    pub fn source_name(&self) -> &Name {
+        match self {
+            OfferDecl::Protocol(decl) => &decl.source_name,
+            OfferDecl::Directory(decl) => &decl.source_name,
+            OfferDecl::Storage(decl) => &decl.source_name,
+            OfferDecl::Service(decl) => &decl.source_name,
+            OfferDecl::Runner(decl) => &decl.source_name,
+            OfferDecl::Resolver(decl) => &decl.source_name,
+        }
+    }
+
+    pub fn target(&self) -> &OfferTarget {
+        match self {
+            OfferDecl::Protocol(decl) => &decl.target,
+            OfferDecl::Directory(decl) => &decl.target,
+            OfferDecl::Storage(decl) => &decl.target,
+            OfferDecl::Service(decl) => &decl.target,
+            OfferDecl::Runner(decl) => &decl.target,
+            OfferDecl::Resolver(decl) => &decl.target,
+        }
+    }
+
+    pub fn target_name(&self) -> &Name {
+        match self {
+            OfferDecl::Protocol(decl) => &decl.target_name,
+            OfferDecl::Directory(decl) => &decl.target_name,
+            OfferDecl::Storage(decl) => &decl.target_name,
+            OfferDecl::Service(decl) => &decl.target_name,
+            OfferDecl::Runner(decl) => &decl.target_name,
+            OfferDecl::Resolver(decl) => &decl.target_name,
+        }
+    }
+
+    pub fn dependency_type(&self) -> DependencyType {
+        match self {
+            OfferDecl::Protocol(decl) => decl.dependency_type,
+            OfferDecl::Directory(decl) => decl.dependency_type,
+            OfferDecl::Service(decl) => decl.dependency_type,
+            OfferDecl::Storage(_) | OfferDecl::Runner(_) | OfferDecl::Resolver(_) => DependencyType::Strong,
+        }
+    }
+
+    pub fn availability(&self) -> Availability {
+        match self {
+            OfferDecl::Protocol(decl) => decl.availability,
+            OfferDecl::Directory(decl) => decl.availability,
+            OfferDecl::Storage(decl) => decl.availability,
+            OfferDecl::Service(decl) => decl.availability,
+            OfferDecl::Runner(_) | OfferDecl::Resolver(_) => Availability::Required,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::OfferProtocol")]
+pub struct OfferProtocolDecl {
+    pub source: OfferSource,
+    pub source_name: Name,
+    pub target: OfferTarget,
+    pub target_name: Name,
+    #[serde(default)]
+    pub dependency_type: DependencyType,
+    #[serde(default)]
+    pub availability: Availability,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::OfferDirectory")]
+pub struct OfferDirectoryDecl {
+    pub source: OfferSource,
+    pub source_name: Name,
+    pub target: OfferTarget,
+    pub target_name: Name,
+    #[serde(default)]
+    pub dependency_type: DependencyType,
+    #[serde(default)]
+    pub availability: Availability,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::OfferStorage")]
+pub struct OfferStorageDecl {
+    pub source: OfferSource,
+    pub source_name: Name,
+    pub target: OfferTarget,
+    pub target_name: Name,
+    #[serde(default)]
+    pub availability: Availability,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::OfferService")]
+pub struct OfferServiceDecl {
+    pub source: OfferSource,
+    pub source_name: Name,
+    pub target: OfferTarget,
+    pub target_name: Name,
+    #[serde(default)]
+    pub dependency_type: DependencyType,
+    #[serde(default)]
+    pub availability: Availability,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::OfferRunner")]
+pub struct OfferRunnerDecl {
+    pub source: OfferSource,
+    pub source_name: Name,
+    pub target: OfferTarget,
+    pub target_name: Name,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::OfferResolver")]
+pub struct OfferResolverDecl {
+    pub source: OfferSource,
+    pub source_name: Name,
+    pub target: OfferTarget,
+    pub target_name: Name,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+//#[fidl_decl(fidl_union = "fdecl::Expose")]
+pub enum ExposeDecl {
+    Protocol(ExposeProtocolDecl),
+    Directory(ExposeDirectoryDecl),
+    Service(ExposeServiceDecl),
+    Runner(ExposeRunnerDecl),
+    Resolver(ExposeResolverDecl),
+}
+
+impl ExposeDecl {
+    pub fn type_name(&self) -> CapabilityTypeName {
+        match self {
+            ExposeDecl::Protocol(_) => CapabilityTypeName::Protocol,
+            ExposeDecl::Directory(_) => CapabilityTypeName::Directory,
+            ExposeDecl::Service(_) => CapabilityTypeName::Service,
+            ExposeDecl::Runner(_) => CapabilityTypeName::Runner,
+            ExposeDecl::Resolver(_) => CapabilityTypeName::Resolver,
+        }
+    }
+
+    pub fn source(&self) -> &ExposeSource {
+        match self {
+            ExposeDecl::Protocol(decl) => &decl.source,
+            ExposeDecl::Directory(decl) => &decl.source,
+            ExposeDecl::Service(decl) => &decl.source,
+            ExposeDecl::Runner(decl) => &decl.source,
+            ExposeDecl::Resolver(decl) => &decl.source,
+        }
+    }
+
+    pub fn source_name(&self) -> &Name {
+        match self {
+            ExposeDecl::Protocol(decl) => &decl.source_name,
+            ExposeDecl::Directory(decl) => &decl.source_name,
+            ExposeDecl::Service(decl) => &decl.source_name,
+            ExposeDecl::Runner(decl) => &decl.source_name,
+            ExposeDecl::Resolver(decl) => &decl.source_name,
+        }
+    }
+
+    pub fn target(&self) -> &ExposeTarget {
+        match self {
+            ExposeDecl::Protocol(decl) => &decl.target,
+            ExposeDecl::Directory(decl) => &decl.target,
+            ExposeDecl::Service(decl) => &decl.target,
+            ExposeDecl::Runner(decl) => &decl.target,
+            ExposeDecl::Resolver(decl) => &decl.target,
+        }
+    }
+
+    pub fn target_name(&self) -> &Name {
+        match self {
+            ExposeDecl::Protocol(decl) => &decl.target_name,
+            ExposeDecl::Directory(decl) => &decl.target_name,
+            ExposeDecl::Service(decl) => &decl.target_name,
+            ExposeDecl::Runner(decl) => &decl.target_name,
+            ExposeDecl::Resolver(decl) => &decl.target_name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::ExposeProtocol")]
+pub struct ExposeProtocolDecl {
+    pub source: ExposeSource,
+    pub source_name: Name,
+    #[serde(default)]
+    pub target: ExposeTarget,
+    pub target_name: Name,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::ExposeDirectory")]
+pub struct ExposeDirectoryDecl {
+    pub source: ExposeSource,
+    pub source_name: Name,
+    #[serde(default)]
+    pub target: ExposeTarget,
+    pub target_name: Name,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::ExposeService")]
+pub struct ExposeServiceDecl {
+    pub source: ExposeSource,
+    pub source_name: Name,
+    #[serde(default)]
+    pub target: ExposeTarget,
+    pub target_name: Name,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::ExposeRunner")]
+pub struct ExposeRunnerDecl {
+    pub source: ExposeSource,
+    pub source_name: Name,
+    #[serde(default)]
+    pub target: ExposeTarget,
+    pub target_name: Name,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::ExposeResolver")]
+pub struct ExposeResolverDecl {
+    pub source: ExposeSource,
+    pub source_name: Name,
+    #[serde(default)]
+    pub target: ExposeTarget,
+    pub target_name: Name,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::Child")]
+pub struct ChildDecl {
+    pub name: Name,
+    pub url: Url,
+    #[serde(default)]
+    pub startup: StartupMode,
+    pub on_terminate: Option<OnTerminate>,
+    pub environment: Option<Name>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::Collection")]
+pub struct CollectionDecl {
+    pub name: Name,
+    #[serde(default)]
+    pub durability: Durability,
+    pub environment: Option<Name>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::Environment")]
+pub struct EnvironmentDecl {
+    pub name: Name,
+    #[serde(default)]
+    pub extends: EnvironmentExtends,
+    #[serde(default)]
+    pub runners: Vec<RunnerRegistration>,
+    #[serde(default)]
+    pub resolvers: Vec<ResolverRegistration>,
+    pub stop_timeout_ms: Option<u32>,
+}
+
+/// Makes the runner `source_name` from `source` available as `target_name` to the components
+/// that run in an environment.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::RunnerRegistration")]
+pub struct RunnerRegistration {
+    pub source_name: Name,
+    pub target_name: Name,
+    pub source: RegistrationSource,
+}
+
+/// Makes the resolver `resolver` from `source` resolve the URLs with the `scheme` scheme in an
+/// environment.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+//#[fidl_decl(fidl_table = "fdecl::ResolverRegistration")]
+pub struct ResolverRegistration {
+    pub resolver: Name,
+    pub source: RegistrationSource,
+    pub scheme: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UseSource {
+    Parent,
+    Framework,
+    #[serde(rename = "self")]
+    Self_,
+    Child(Name),
+    Capability(Name),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OfferSource {
+    Parent,
+    Framework,
+    #[serde(rename = "self")]
+    Self_,
+    Child(Name),
+    Collection(Name),
+    Capability(Name),
+    /// The capability is not provided.  Only valid for optional capabilities.
+    Void,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OfferTarget {
+    Child(Name),
+    Collection(Name),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExposeSource {
+    Framework,
+    #[serde(rename = "self")]
+    Self_,
+    Child(Name),
+    Collection(Name),
+    Capability(Name),
+    /// The capability is not provided.
+    Void,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExposeTarget {
+    #[default]
+    Parent,
+    Framework,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageDirectorySource {
+    Parent,
+    #[serde(rename = "self")]
+    Self_,
+    Child(Name),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistrationSource {
+    Parent,
+    #[serde(rename = "self")]
+    Self_,
+    Child(Name),
+}
+
+/// Whether a dependency orders the start and shutdown of the components it connects.  Weak
+/// dependencies are how cycles between components are broken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyType {
+    #[default]
+    Strong,
+    Weak,
+}
+
+/// Whether routing a capability must succeed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Availability {
+    #[default]
+    Required,
+    Optional,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupMode {
+    #[default]
+    Lazy,
+    Eager,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnTerminate {
+    None,
+    Reboot,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Durability {
+    #[default]
+    Transient,
+    SingleRun,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvironmentExtends {
+    /// The environment starts out empty.
+    #[default]
+    None,
+    /// The environment inherits the runners and resolvers of the environment of the component.
+    Realm,
+}
+
 /// The builtin resolver to use for the fuchsia-boot scheme, if any.
 #[derive(Debug, PartialEq, Eq)]
 pub enum BuiltinBootResolver {
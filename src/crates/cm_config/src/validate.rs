@@ -0,0 +1,959 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Validation of [`ComponentDecl`]s.
+//!
+//! Names, paths and URLs are checked when a declaration is constructed, by the `cm_types` types
+//! that hold them.  What is left to check is the declaration as a whole: that every child,
+//! collection, environment and capability it references is declared, that nothing is declared
+//! twice, that the namespace paths of its `use` declarations do not overlap, and that the strong
+//! dependencies between the component and its children do not form a cycle.
+
+use crate::{
+    Availability, CapabilityDecl, ComponentDecl, DependencyType, EnvironmentDecl, ExposeDecl, ExposeSource, OfferDecl,
+    OfferSource, OfferTarget, RegistrationSource, StorageDirectorySource, UseDecl, UseSource,
+};
+
+use cm_types::{CapabilityTypeName, Name, Path};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use thiserror::Error;
+
+/// A field of a declaration, such as `OfferProtocol.source`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeclField {
+    pub decl: String,
+    pub field: String,
+}
+
+impl DeclField {
+    fn new(decl: impl Into<String>, field: impl Into<String>) -> Self {
+        Self {
+            decl: decl.into(),
+            field: field.into(),
+        }
+    }
+}
+
+impl fmt::Display for DeclField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.decl, self.field)
+    }
+}
+
+/// A problem found in a [`ComponentDecl`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Error {
+    #[error("duplicate {0}: \"{1}\"")]
+    DuplicateField(DeclField, String),
+    #[error("invalid {0}: {1}")]
+    InvalidField(DeclField, String),
+    #[error("\"{1}\" is referenced in {0} but it does not appear in children")]
+    InvalidChild(DeclField, String),
+    #[error("\"{1}\" is referenced in {0} but it does not appear in collections")]
+    InvalidCollection(DeclField, String),
+    #[error("\"{1}\" is referenced in {0} but it does not appear in environments")]
+    InvalidEnvironment(DeclField, String),
+    #[error("\"{1}\" is referenced in {0} but it does not appear in capabilities")]
+    InvalidCapability(DeclField, String),
+    #[error("{decl} \"{path}\" overlaps with \"{other_path}\"")]
+    InvalidPathOverlap {
+        decl: DeclField,
+        path: Path,
+        other_path: Path,
+    },
+    #[error("\"{1}\" is both the source and the target of {0}")]
+    OfferTargetEqualsSource(String, String),
+    #[error(
+        "strong dependency cycle found: {0}.  Break the cycle by removing a dependency or marking an \
+         offer as weak"
+    )]
+    DependencyCycle(String),
+}
+
+/// All the problems found in a [`ComponentDecl`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub struct ErrorList {
+    pub errs: Vec<Error>,
+}
+
+impl fmt::Display for ErrorList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errs = self.errs.iter().map(|err| err.to_string()).collect::<Vec<_>>();
+        write!(f, "{}", errs.join(", "))
+    }
+}
+
+/// Validates a component declaration, returning all the problems found in it.
+pub fn validate(decl: &ComponentDecl) -> Result<(), ErrorList> {
+    let mut ctx = ValidationContext::new(decl);
+    ctx.validate();
+    if ctx.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ErrorList { errs: ctx.errors })
+    }
+}
+
+/// A participant of the strong dependency graph of a component, as its declaration refers to it.
+/// Collections stand for all of their instances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DependencyNode<'a> {
+    Self_,
+    Child(&'a Name),
+    Collection(&'a Name),
+    Environment(&'a Name),
+}
+
+impl fmt::Display for DependencyNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DependencyNode::Self_ => write!(f, "self"),
+            DependencyNode::Child(name) => write!(f, "child {name}"),
+            DependencyNode::Collection(name) => write!(f, "collection {name}"),
+            DependencyNode::Environment(name) => write!(f, "environment {name}"),
+        }
+    }
+}
+
+/// Returns the strong dependencies between the component declared by `decl` and its children, as
+/// edges from a dependency to a node that depends on it.  References are not checked, so edges may
+/// name children, collections and environments that `decl` does not declare.
+pub fn dependency_edges(decl: &ComponentDecl) -> Vec<(DependencyNode<'_>, DependencyNode<'_>)> {
+    let mut edges = Vec::new();
+
+    // Children and collections depend on their environment, which depends on the children
+    // providing the runners and resolvers registered in it.
+    for child in &decl.children {
+        if let Some(environment) = &child.environment {
+            edges.push((
+                DependencyNode::Environment(environment),
+                DependencyNode::Child(&child.name),
+            ));
+        }
+    }
+    for collection in &decl.collections {
+        if let Some(environment) = &collection.environment {
+            edges.push((
+                DependencyNode::Environment(environment),
+                DependencyNode::Collection(&collection.name),
+            ));
+        }
+    }
+    for environment in &decl.environments {
+        let sources = environment
+            .runners
+            .iter()
+            .map(|runner| &runner.source)
+            .chain(environment.resolvers.iter().map(|resolver| &resolver.source));
+        for source in sources {
+            if let RegistrationSource::Child(child) = source {
+                edges.push((
+                    DependencyNode::Child(child),
+                    DependencyNode::Environment(&environment.name),
+                ));
+            }
+        }
+    }
+
+    for use_ in &decl.uses {
+        let source = match use_ {
+            UseDecl::Protocol(decl) => &decl.source,
+            UseDecl::Directory(decl) => &decl.source,
+            UseDecl::Service(decl) => &decl.source,
+            UseDecl::Storage(_) => continue,
+        };
+        if let UseSource::Child(child) = source {
+            if use_.dependency_type() == DependencyType::Strong {
+                edges.push((DependencyNode::Child(child), DependencyNode::Self_));
+            }
+        }
+    }
+
+    for offer in &decl.offers {
+        if offer.dependency_type() != DependencyType::Strong {
+            continue;
+        }
+        let source = match offer.source() {
+            OfferSource::Self_ => match decl.find_capability(offer.source_name()) {
+                // Storage backed by a directory of a child depends on that child.
+                Some(CapabilityDecl::Storage(storage)) => match &storage.source {
+                    StorageDirectorySource::Child(child) => DependencyNode::Child(child),
+                    _ => continue,
+                },
+                _ => DependencyNode::Self_,
+            },
+            OfferSource::Child(child) => DependencyNode::Child(child),
+            OfferSource::Collection(collection) => DependencyNode::Collection(collection),
+            OfferSource::Parent | OfferSource::Framework | OfferSource::Capability(_) | OfferSource::Void => continue,
+        };
+        let target = match offer.target() {
+            OfferTarget::Child(child) => DependencyNode::Child(child),
+            OfferTarget::Collection(collection) => DependencyNode::Collection(collection),
+        };
+        // An offer to its own source is invalid rather than a cycle.
+        if source != target {
+            edges.push((source, target));
+        }
+    }
+    edges
+}
+
+struct ValidationContext<'a> {
+    decl: &'a ComponentDecl,
+    children: HashSet<&'a Name>,
+    collections: HashSet<&'a Name>,
+    environments: HashSet<&'a Name>,
+    capabilities: HashMap<&'a Name, &'a CapabilityDecl>,
+    /// Edges go from a dependency to the nodes that depend on it.
+    dependencies: BTreeMap<DependencyNode<'a>, BTreeSet<DependencyNode<'a>>>,
+    errors: Vec<Error>,
+}
+
+impl<'a> ValidationContext<'a> {
+    fn new(decl: &'a ComponentDecl) -> Self {
+        Self {
+            decl,
+            children: HashSet::new(),
+            collections: HashSet::new(),
+            environments: HashSet::new(),
+            capabilities: HashMap::new(),
+            dependencies: BTreeMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn validate(&mut self) {
+        let decl = self.decl;
+
+        // Collect the names everything else refers to first.
+        for child in &decl.children {
+            if !self.children.insert(&child.name) {
+                self.errors.push(Error::DuplicateField(
+                    DeclField::new("Child", "name"),
+                    child.name.to_string(),
+                ));
+            }
+        }
+        for collection in &decl.collections {
+            if !self.collections.insert(&collection.name) {
+                self.errors.push(Error::DuplicateField(
+                    DeclField::new("Collection", "name"),
+                    collection.name.to_string(),
+                ));
+            }
+        }
+        for environment in &decl.environments {
+            if !self.environments.insert(&environment.name) {
+                self.errors.push(Error::DuplicateField(
+                    DeclField::new("Environment", "name"),
+                    environment.name.to_string(),
+                ));
+            }
+        }
+        for capability in &decl.capabilities {
+            if self.capabilities.insert(capability.name(), capability).is_some() {
+                self.errors.push(Error::DuplicateField(
+                    DeclField::new(capability_decl_name(capability), "name"),
+                    capability.name().to_string(),
+                ));
+            }
+        }
+
+        for capability in &decl.capabilities {
+            self.validate_capability(capability);
+        }
+        for environment in &decl.environments {
+            self.validate_environment(environment);
+        }
+        for child in &decl.children {
+            if let Some(environment) = &child.environment {
+                self.check_environment(DeclField::new("Child", "environment"), environment);
+            }
+        }
+        for collection in &decl.collections {
+            if let Some(environment) = &collection.environment {
+                self.check_environment(DeclField::new("Collection", "environment"), environment);
+            }
+        }
+
+        self.validate_uses();
+        self.validate_offers();
+        self.validate_exposes();
+
+        // Dependencies on what is not declared were reported above.
+        for (dependency, dependent) in dependency_edges(decl) {
+            if self.is_declared(dependency) && self.is_declared(dependent) {
+                self.add_dependency(dependency, dependent);
+            }
+        }
+        self.check_dependency_cycles();
+    }
+
+    fn validate_capability(&mut self, capability: &'a CapabilityDecl) {
+        let CapabilityDecl::Storage(storage) = capability else {
+            return;
+        };
+        match &storage.source {
+            StorageDirectorySource::Parent => {}
+            StorageDirectorySource::Self_ => {
+                self.check_self_capability(
+                    DeclField::new("Storage", "backing_dir"),
+                    &storage.backing_dir,
+                    CapabilityTypeName::Directory,
+                );
+            }
+            StorageDirectorySource::Child(child) => {
+                self.check_child(DeclField::new("Storage", "source"), child);
+            }
+        }
+    }
+
+    fn validate_environment(&mut self, environment: &'a EnvironmentDecl) {
+        let mut runners = HashSet::new();
+        for runner in &environment.runners {
+            if !runners.insert(&runner.target_name) {
+                self.errors.push(Error::DuplicateField(
+                    DeclField::new("RunnerRegistration", "target_name"),
+                    runner.target_name.to_string(),
+                ));
+            }
+            let field = DeclField::new("RunnerRegistration", "source");
+            self.validate_registration_source(field, &runner.source, &runner.source_name, CapabilityTypeName::Runner);
+        }
+
+        let mut schemes = HashSet::new();
+        for resolver in &environment.resolvers {
+            if !is_valid_scheme(&resolver.scheme) {
+                self.errors.push(Error::InvalidField(
+                    DeclField::new("ResolverRegistration", "scheme"),
+                    format!("\"{}\" is not a URL scheme", resolver.scheme),
+                ));
+            } else if !schemes.insert(resolver.scheme.as_str()) {
+                self.errors.push(Error::DuplicateField(
+                    DeclField::new("ResolverRegistration", "scheme"),
+                    resolver.scheme.clone(),
+                ));
+            }
+            let field = DeclField::new("ResolverRegistration", "source");
+            self.validate_registration_source(
+                field,
+                &resolver.source,
+                &resolver.resolver,
+                CapabilityTypeName::Resolver,
+            );
+        }
+    }
+
+    fn validate_registration_source(
+        &mut self,
+        field: DeclField,
+        source: &'a RegistrationSource,
+        source_name: &Name,
+        type_name: CapabilityTypeName,
+    ) {
+        match source {
+            RegistrationSource::Parent => {}
+            RegistrationSource::Self_ => {
+                self.check_self_capability(field, source_name, type_name);
+            }
+            RegistrationSource::Child(child) => {
+                self.check_child(field, child);
+            }
+        }
+    }
+
+    fn validate_uses(&mut self) {
+        let mut paths: Vec<&'a Path> = Vec::new();
+        for use_ in &self.decl.uses {
+            let decl = use_decl_name(use_);
+            match use_ {
+                UseDecl::Protocol(decl) => self.validate_use_source(use_, &decl.source),
+                UseDecl::Directory(decl) => self.validate_use_source(use_, &decl.source),
+                UseDecl::Service(decl) => self.validate_use_source(use_, &decl.source),
+                UseDecl::Storage(_) => {}
+            }
+
+            let path = use_.target_path();
+            for &other_path in &paths {
+                if path == other_path {
+                    self.errors.push(Error::DuplicateField(
+                        DeclField::new(decl, "target_path"),
+                        path.to_string(),
+                    ));
+                } else if path.is_prefix_of(other_path) || other_path.is_prefix_of(path) {
+                    self.errors.push(Error::InvalidPathOverlap {
+                        decl: DeclField::new(decl, "target_path"),
+                        path: path.clone(),
+                        other_path: other_path.clone(),
+                    });
+                }
+            }
+            paths.push(path);
+        }
+    }
+
+    fn validate_use_source(&mut self, use_: &'a UseDecl, source: &'a UseSource) {
+        let field = DeclField::new(use_decl_name(use_), "source");
+        match source {
+            UseSource::Parent | UseSource::Framework => {}
+            UseSource::Self_ => {
+                self.check_self_capability(field, use_.source_name(), use_.type_name());
+            }
+            UseSource::Child(child) => {
+                self.check_child(field, child);
+            }
+            UseSource::Capability(name) => self.check_capability(field, name),
+        }
+    }
+
+    fn validate_offers(&mut self) {
+        let mut targets = HashSet::new();
+        for offer in &self.decl.offers {
+            let decl = offer_decl_name(offer);
+
+            let target = match offer.target() {
+                OfferTarget::Child(child) => self
+                    .check_child(DeclField::new(decl, "target"), child)
+                    .then_some(DependencyNode::Child(child)),
+                OfferTarget::Collection(collection) => self
+                    .check_collection(DeclField::new(decl, "target"), collection)
+                    .then_some(DependencyNode::Collection(collection)),
+            };
+            if !targets.insert((offer.target(), offer.type_name().to_string(), offer.target_name())) {
+                self.errors.push(Error::DuplicateField(
+                    DeclField::new(decl, "target_name"),
+                    offer.target_name().to_string(),
+                ));
+            }
+
+            let source = self.validate_offer_source(offer);
+            if let (Some(source), Some(target)) = (source, target) {
+                if source == target {
+                    let name = match target {
+                        DependencyNode::Child(name) | DependencyNode::Collection(name) => name.to_string(),
+                        _ => unreachable!("offers target children and collections"),
+                    };
+                    self.errors.push(Error::OfferTargetEqualsSource(decl.to_string(), name));
+                }
+            }
+        }
+    }
+
+    /// Checks the source of `offer`, returning the node the target of the offer depends on, if
+    /// any.
+    fn validate_offer_source(&mut self, offer: &'a OfferDecl) -> Option<DependencyNode<'a>> {
+        let decl = offer_decl_name(offer);
+        let field = DeclField::new(decl, "source");
+        let source = offer.source();
+
+        if let OfferDecl::Storage(_) = offer {
+            if !matches!(source, OfferSource::Parent | OfferSource::Self_) {
+                self.errors.push(Error::InvalidField(
+                    field,
+                    "storage can only be offered from parent or self".into(),
+                ));
+                return None;
+            }
+        }
+
+        match source {
+            OfferSource::Parent | OfferSource::Framework => None,
+            OfferSource::Self_ => {
+                if !self.check_self_capability(field, offer.source_name(), offer.type_name()) {
+                    return None;
+                }
+                // Storage backed by a directory of a child depends on that child.
+                match self.capabilities.get(offer.source_name()) {
+                    Some(CapabilityDecl::Storage(storage)) => match &storage.source {
+                        StorageDirectorySource::Child(child) => Some(DependencyNode::Child(child)),
+                        _ => None,
+                    },
+                    _ => Some(DependencyNode::Self_),
+                }
+            }
+            OfferSource::Child(child) => self.check_child(field, child).then_some(DependencyNode::Child(child)),
+            OfferSource::Collection(collection) => {
+                if !matches!(offer, OfferDecl::Service(_)) {
+                    self.errors.push(Error::InvalidField(
+                        field,
+                        "only services can be offered from a collection".into(),
+                    ));
+                    return None;
+                }
+                self.check_collection(field, collection)
+                    .then_some(DependencyNode::Collection(collection))
+            }
+            OfferSource::Capability(name) => {
+                self.check_capability(field, name);
+                None
+            }
+            OfferSource::Void => {
+                if offer.availability() != Availability::Optional {
+                    self.errors.push(Error::InvalidField(
+                        field,
+                        "only optional capabilities can be offered from void".into(),
+                    ));
+                }
+                None
+            }
+        }
+    }
+
+    fn validate_exposes(&mut self) {
+        let mut targets = HashSet::new();
+        for expose in &self.decl.exposes {
+            let decl = expose_decl_name(expose);
+            let field = DeclField::new(decl, "source");
+            match expose.source() {
+                ExposeSource::Framework | ExposeSource::Void => {}
+                ExposeSource::Self_ => {
+                    self.check_self_capability(field, expose.source_name(), expose.type_name());
+                }
+                ExposeSource::Child(child) => {
+                    self.check_child(field, child);
+                }
+                ExposeSource::Collection(collection) => {
+                    if matches!(expose, ExposeDecl::Service(_)) {
+                        self.check_collection(field, collection);
+                    } else {
+                        self.errors.push(Error::InvalidField(
+                            field,
+                            "only services can be exposed from a collection".into(),
+                        ));
+                    }
+                }
+                ExposeSource::Capability(name) => self.check_capability(field, name),
+            }
+
+            if !targets.insert((expose.target(), expose.type_name().to_string(), expose.target_name())) {
+                self.errors.push(Error::DuplicateField(
+                    DeclField::new(decl, "target_name"),
+                    expose.target_name().to_string(),
+                ));
+            }
+        }
+    }
+
+    fn check_child(&mut self, field: DeclField, name: &Name) -> bool {
+        let found = self.children.contains(name);
+        if !found {
+            self.errors.push(Error::InvalidChild(field, name.to_string()));
+        }
+        found
+    }
+
+    fn check_collection(&mut self, field: DeclField, name: &Name) -> bool {
+        let found = self.collections.contains(name);
+        if !found {
+            self.errors.push(Error::InvalidCollection(field, name.to_string()));
+        }
+        found
+    }
+
+    fn check_environment(&mut self, field: DeclField, name: &Name) -> bool {
+        let found = self.environments.contains(name);
+        if !found {
+            self.errors.push(Error::InvalidEnvironment(field, name.to_string()));
+        }
+        found
+    }
+
+    fn check_capability(&mut self, field: DeclField, name: &Name) {
+        if !self.capabilities.contains_key(name) {
+            self.errors.push(Error::InvalidCapability(field, name.to_string()));
+        }
+    }
+
+    /// Checks that the component declares a capability of type `type_name` named `name`.
+    fn check_self_capability(&mut self, field: DeclField, name: &Name, type_name: CapabilityTypeName) -> bool {
+        match self.capabilities.get(name) {
+            Some(capability) if capability.type_name() == type_name => true,
+            Some(capability) => {
+                self.errors.push(Error::InvalidField(
+                    field,
+                    format!(
+                        "\"{name}\" is a {} capability, not a {type_name}",
+                        capability.type_name()
+                    ),
+                ));
+                false
+            }
+            None => {
+                self.errors.push(Error::InvalidCapability(field, name.to_string()));
+                false
+            }
+        }
+    }
+
+    fn is_declared(&self, node: DependencyNode<'_>) -> bool {
+        match node {
+            DependencyNode::Self_ => true,
+            DependencyNode::Child(name) => self.children.contains(name),
+            DependencyNode::Collection(name) => self.collections.contains(name),
+            DependencyNode::Environment(name) => self.environments.contains(name),
+        }
+    }
+
+    fn add_dependency(&mut self, dependency: DependencyNode<'a>, dependent: DependencyNode<'a>) {
+        self.dependencies.entry(dependency).or_default().insert(dependent);
+    }
+
+    /// Reports the first cycle found in the strong dependency graph.
+    fn check_dependency_cycles(&mut self) {
+        let mut done = BTreeSet::new();
+        for &node in self.dependencies.keys() {
+            let mut path = Vec::new();
+            if let Some(cycle) = self.find_cycle(node, &mut path, &mut done) {
+                let cycle = cycle.iter().map(ToString::to_string).collect::<Vec<_>>();
+                self.errors
+                    .push(Error::DependencyCycle(format!("{{{{{}}}}}", cycle.join(" -> "))));
+                return;
+            }
+        }
+    }
+
+    /// Depth first search from `node`.  `path` holds the nodes being visited, and `done` the nodes
+    /// known not to be part of a cycle.
+    fn find_cycle(
+        &self,
+        node: DependencyNode<'a>,
+        path: &mut Vec<DependencyNode<'a>>,
+        done: &mut BTreeSet<DependencyNode<'a>>,
+    ) -> Option<Vec<DependencyNode<'a>>> {
+        if let Some(start) = path.iter().position(|visited| *visited == node) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(node);
+            return Some(cycle);
+        }
+        if done.contains(&node) {
+            return None;
+        }
+
+        path.push(node);
+        for &next in self.dependencies.get(&node).into_iter().flatten() {
+            if let Some(cycle) = self.find_cycle(next, path, done) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        done.insert(node);
+        None
+    }
+}
+
+/// Whether `scheme` is a valid URL scheme, as defined by RFC 3986.
+fn is_valid_scheme(scheme: &str) -> bool {
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+fn capability_decl_name(capability: &CapabilityDecl) -> &'static str {
+    match capability {
+        CapabilityDecl::Protocol(_) => "Protocol",
+        CapabilityDecl::Directory(_) => "Directory",
+        CapabilityDecl::Storage(_) => "Storage",
+        CapabilityDecl::Service(_) => "Service",
+        CapabilityDecl::Runner(_) => "Runner",
+        CapabilityDecl::Resolver(_) => "Resolver",
+    }
+}
+
+fn use_decl_name(use_: &UseDecl) -> &'static str {
+    match use_ {
+        UseDecl::Protocol(_) => "UseProtocol",
+        UseDecl::Directory(_) => "UseDirectory",
+        UseDecl::Storage(_) => "UseStorage",
+        UseDecl::Service(_) => "UseService",
+    }
+}
+
+fn offer_decl_name(offer: &OfferDecl) -> &'static str {
+    match offer {
+        OfferDecl::Protocol(_) => "OfferProtocol",
+        OfferDecl::Directory(_) => "OfferDirectory",
+        OfferDecl::Storage(_) => "OfferStorage",
+        OfferDecl::Service(_) => "OfferService",
+        OfferDecl::Runner(_) => "OfferRunner",
+        OfferDecl::Resolver(_) => "OfferResolver",
+    }
+}
+
+fn expose_decl_name(expose: &ExposeDecl) -> &'static str {
+    match expose {
+        ExposeDecl::Protocol(_) => "ExposeProtocol",
+        ExposeDecl::Directory(_) => "ExposeDirectory",
+        ExposeDecl::Service(_) => "ExposeService",
+        ExposeDecl::Runner(_) => "ExposeRunner",
+        ExposeDecl::Resolver(_) => "ExposeResolver",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(manifest: &str) -> ComponentDecl {
+        serde_yml::from_str(manifest).unwrap()
+    }
+
+    fn errors(manifest: &str) -> Vec<Error> {
+        validate(&parse(manifest)).unwrap_err().errs
+    }
+
+    fn name(name: &str) -> String {
+        name.to_string()
+    }
+
+    #[test]
+    fn valid_manifest() {
+        let decl = parse(
+            r#"
+program:
+  runner: wasm
+  binary: bin/echo
+  args: [--verbose]
+use:
+  - type: protocol
+    source: parent
+    source_name: meshx.logger.LogSink
+    target_path: /svc/meshx.logger.LogSink
+  - type: storage
+    source_name: data
+    target_path: /data
+capabilities:
+  - type: protocol
+    name: echo.Echo
+    source_path: /svc/echo.Echo
+children:
+  - name: server
+    url: mxpkg://meshx.co/echo-server#meta/echo-server.cm
+    startup: eager
+    environment: env
+collections:
+  - name: workers
+environments:
+  - name: env
+    extends: realm
+    resolvers:
+      - resolver: pkg-resolver
+        source: parent
+        scheme: mxpkg
+offer:
+  - type: protocol
+    source: self
+    source_name: echo.Echo
+    target:
+      child: server
+    target_name: echo.Echo
+  - type: service
+    source:
+      child: server
+    source_name: echo.Service
+    target:
+      collection: workers
+    target_name: echo.Service
+expose:
+  - type: protocol
+    source: self
+    source_name: echo.Echo
+    target_name: echo.Echo
+"#,
+        );
+        assert_eq!(validate(&decl), Ok(()));
+        assert_eq!(decl.program.unwrap().runner, Some(Name::new("wasm").unwrap()));
+        assert_eq!(decl.uses[1].source(), UseSource::Parent);
+    }
+
+    #[test]
+    fn invalid_values_are_rejected_when_parsing() {
+        let err = serde_yml::from_str::<ComponentDecl>(
+            r#"
+children:
+  - name: "not a name"
+    url: mxpkg://meshx.co/echo#meta/echo.cm
+"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid name \"not a name\""), "{err}");
+
+        let err = serde_yml::from_str::<ComponentDecl>(
+            r#"
+use:
+  - type: protocol
+    source: parent
+    source_name: echo.Echo
+    target_path: svc/echo.Echo
+"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid path \"svc/echo.Echo\""), "{err}");
+    }
+
+    #[test]
+    fn dangling_references() {
+        assert_eq!(
+            errors(
+                r#"
+children:
+  - name: server
+    url: mxpkg://meshx.co/echo#meta/echo.cm
+    environment: missing-env
+offer:
+  - type: protocol
+    source:
+      child: missing-child
+    source_name: echo.Echo
+    target:
+      collection: missing-collection
+    target_name: echo.Echo
+expose:
+  - type: directory
+    source: self
+    source_name: missing-dir
+    target_name: data
+"#
+            ),
+            vec![
+                Error::InvalidEnvironment(DeclField::new("Child", "environment"), name("missing-env")),
+                Error::InvalidCollection(DeclField::new("OfferProtocol", "target"), name("missing-collection")),
+                Error::InvalidChild(DeclField::new("OfferProtocol", "source"), name("missing-child")),
+                Error::InvalidCapability(DeclField::new("ExposeDirectory", "source"), name("missing-dir")),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicates_and_overlapping_paths() {
+        assert_eq!(
+            errors(
+                r#"
+use:
+  - type: directory
+    source: parent
+    source_name: config
+    target_path: /config
+  - type: protocol
+    source: parent
+    source_name: echo.Echo
+    target_path: /config/echo.Echo
+  - type: protocol
+    source: framework
+    source_name: echo.Echo
+    target_path: /config
+children:
+  - name: server
+    url: mxpkg://meshx.co/echo#meta/echo.cm
+  - name: server
+    url: mxpkg://meshx.co/echo#meta/echo.cm
+"#
+            ),
+            vec![
+                Error::DuplicateField(DeclField::new("Child", "name"), name("server")),
+                Error::InvalidPathOverlap {
+                    decl: DeclField::new("UseProtocol", "target_path"),
+                    path: Path::new("/config/echo.Echo").unwrap(),
+                    other_path: Path::new("/config").unwrap(),
+                },
+                Error::DuplicateField(DeclField::new("UseProtocol", "target_path"), name("/config")),
+                Error::InvalidPathOverlap {
+                    decl: DeclField::new("UseProtocol", "target_path"),
+                    path: Path::new("/config").unwrap(),
+                    other_path: Path::new("/config/echo.Echo").unwrap(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn strong_dependency_cycles() {
+        let manifest = |dependency_type: &str| {
+            format!(
+                r#"
+children:
+  - name: a
+    url: mxpkg://meshx.co/a#meta/a.cm
+  - name: b
+    url: mxpkg://meshx.co/b#meta/b.cm
+offer:
+  - type: protocol
+    source:
+      child: a
+    source_name: a.A
+    target:
+      child: b
+    target_name: a.A
+  - type: protocol
+    source:
+      child: b
+    source_name: b.B
+    target:
+      child: a
+    target_name: b.B
+    dependency_type: {dependency_type}
+"#
+            )
+        };
+        assert_eq!(
+            errors(&manifest("strong")),
+            vec![Error::DependencyCycle(name("{{child a -> child b -> child a}}"))]
+        );
+        assert_eq!(validate(&parse(&manifest("weak"))), Ok(()));
+    }
+
+    #[test]
+    fn use_from_child_offered_from_self_is_a_cycle() {
+        assert_eq!(
+            errors(
+                r#"
+use:
+  - type: protocol
+    source:
+      child: server
+    source_name: echo.Echo
+    target_path: /svc/echo.Echo
+capabilities:
+  - type: protocol
+    name: config.Config
+offer:
+  - type: protocol
+    source: self
+    source_name: config.Config
+    target:
+      child: server
+    target_name: config.Config
+children:
+  - name: server
+    url: mxpkg://meshx.co/echo#meta/echo.cm
+"#
+            ),
+            vec![Error::DependencyCycle(name("{{self -> child server -> self}}"))]
+        );
+    }
+
+    #[test]
+    fn void_offers_must_be_optional() {
+        assert_eq!(
+            errors(
+                r#"
+children:
+  - name: server
+    url: mxpkg://meshx.co/echo#meta/echo.cm
+offer:
+  - type: protocol
+    source: void
+    source_name: echo.Echo
+    target:
+      child: server
+    target_name: echo.Echo
+"#
+            ),
+            vec![Error::InvalidField(
+                DeclField::new("OfferProtocol", "source"),
+                name("only optional capabilities can be offered from void")
+            )]
+        );
+    }
+}
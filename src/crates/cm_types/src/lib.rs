@@ -6,14 +6,16 @@
 //! (`.cml` files and binary `.cm` files). These types come with `serde` serialization
 //! and deserialization implementations that perform the required validation.
 use lazy_static::lazy_static;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
-use std::path::PathBuf;
 use std::str::FromStr;
 use thiserror::Error;
 
 pub const MAX_NAME_LENGTH: usize = 255;
 pub const MAX_LONG_NAME_LENGTH: usize = 1024;
 pub const MAX_URL_LENGTH: usize = 4096;
+pub const MAX_PATH_LENGTH: usize = 1024;
 
 lazy_static! {
     /// A default base URL from which to parse relative component URL
@@ -87,6 +89,21 @@ impl<const N: usize> FromStr for BoundedName<N> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<const N: usize> Serialize for BoundedName<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> Deserialize<'de> for BoundedName<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::new(name.as_str()).map_err(|err| de::Error::custom(format!("invalid name \"{name}\": {err}")))
+    }
+}
+
 /// The error representing a failure to parse a type from string.
 #[derive(Clone, Debug, Error, PartialEq, Eq)]
 pub enum ParseError {
@@ -177,6 +194,39 @@ impl Url {
         // Use the unparsed URL string so that the original format is preserved.
         Ok(())
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <String as fmt::Display>::fmt(&self.0, f)
+    }
+}
+
+impl FromStr for Url {
+    type Err = ParseError;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        Self::new(url)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Url {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Url {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let url = String::deserialize(deserializer)?;
+        Self::new(url.as_str()).map_err(|err| de::Error::custom(format!("invalid URL \"{url}\": {err}")))
+    }
 }
 
 /// Same as [Path] except the path does not begin with `/`.
@@ -193,6 +243,10 @@ impl RelativePath {
     pub fn is_dot(&self) -> bool {
         self.segments.is_empty()
     }
+
+    pub fn segments(&self) -> &[Name] {
+        &self.segments
+    }
 }
 
 impl fmt::Debug for RelativePath {
@@ -233,6 +287,71 @@ impl fmt::Display for RelativePath {
 #[derive(Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
 pub struct Path(RelativePath);
 
+impl Path {
+    /// Creates a `Path` from a `String`, returning an `Err` if the string fails
+    /// validation. The string must be non-empty, no more than `MAX_PATH_LENGTH`
+    /// characters in length, start with `/`, and consist of one or more
+    /// segments separated by `/`, each of which is a valid [Name].
+    pub fn new(path: impl AsRef<str>) -> Result<Self, ParseError> {
+        let path = path.as_ref();
+        if path.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        if path.len() > MAX_PATH_LENGTH {
+            return Err(ParseError::TooLong);
+        }
+        let Some(path) = path.strip_prefix('/') else {
+            return Err(ParseError::NoLeadingSlash);
+        };
+        if path.is_empty() {
+            return Err(ParseError::InvalidValue);
+        }
+        let segments = path
+            .split('/')
+            .map(|segment| Name::new(segment).map_err(|_| ParseError::InvalidSegment))
+            .collect::<Result<_, _>>()?;
+        Ok(Self(RelativePath { segments }))
+    }
+
+    /// The segments of the path, in order.
+    pub fn segments(&self) -> &[Name] {
+        self.0.segments()
+    }
+
+    /// The last segment of the path.
+    pub fn basename(&self) -> &Name {
+        self.0.segments.last().expect("a path has at least one segment")
+    }
+
+    /// Returns `true` if `self` is `other`, or one of its ancestors.
+    pub fn is_prefix_of(&self, other: &Path) -> bool {
+        other.segments().starts_with(self.segments())
+    }
+}
+
+impl FromStr for Path {
+    type Err = ParseError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Self::new(path)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Path {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Path {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let path = String::deserialize(deserializer)?;
+        Self::new(&path).map_err(|err| de::Error::custom(format!("invalid path \"{path}\": {err}")))
+    }
+}
+
 impl fmt::Debug for Path {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self)
@@ -245,7 +364,6 @@ impl fmt::Display for Path {
     }
 }
 
-
 /// A named capability.
 ///
 /// Unlike a `CapabilityPath`, a `CapabilityName` doesn't encode any form
@@ -143,15 +143,27 @@ pub struct ChildMarker;
 impl midl::endpoints::ProtocolMarker for ChildMarker {
     type Proxy = ChildProxy;
     type RequestStream = ChildRequestStream;
+    type SynchronousProxy = ChildSynchronousProxy;
     const DEBUG_NAME: &'static str = "(anonymous) Child";
 }
 pub trait ChildProxyInterface: Send + Sync {}
 #[derive(Debug)]
-#[cfg(target_os = "fuchsia")]
 pub struct ChildSynchronousProxy {
     client: midl::client::sync::Client,
 }
-#[cfg(target_os = "fuchsia")]
+impl midl::endpoints::SynchronousProxy for ChildSynchronousProxy {
+    type Proxy = ChildProxy;
+    type Protocol = ChildMarker;
+    fn from_channel(inner: midl::Channel) -> Self {
+        Self::new(inner)
+    }
+    fn into_channel(self) -> midl::Channel {
+        self.client.into_channel()
+    }
+    fn as_channel(&self) -> &midl::Channel {
+        self.client.as_channel()
+    }
+}
 impl ChildSynchronousProxy {
     pub fn new(channel: midl::Channel) -> Self {
         let protocol_name = <ChildMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME;
@@ -164,7 +176,7 @@ impl ChildSynchronousProxy {
     }
     /// Waits until an event arrives and returns it. It is safe for other
     /// threads to make concurrent requests while waiting for an event.
-    pub fn wait_for_event(&self, deadline: zx::Time) -> Result<ChildEvent, midl::Error> {
+    pub fn wait_for_event(&self, deadline: fx::Time) -> Result<ChildEvent, midl::Error> {
         ChildEvent::decode(self.client.wait_for_event(deadline)?)
     }
 }
@@ -261,7 +273,10 @@ impl midl::endpoints::RequestStream for ChildRequestStream {
     type ControlHandle = ChildControlHandle;
     fn from_channel(channel: midl::AsyncChannel) -> Self {
         Self {
-            inner: std::sync::Arc::new(midl::ServeInner::new(channel)),
+            inner: std::sync::Arc::new(midl::ServeInner::new(
+                channel,
+                <ChildMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME,
+            )),
             is_terminated: false,
         }
     }
@@ -292,7 +307,7 @@ impl futures::Stream for ChildRequestStream {
             panic!("polled ChildRequestStream after completion");
         }
         midl::encoding::with_tls_decode_buf(|bytes, handles| {
-            match this.inner.channel().read_etc(cx, bytes, handles) {
+            match this.inner.poll_read_request(cx, bytes, handles) {
                 std::task::Poll::Ready(Ok(())) => {}
                 std::task::Poll::Pending => return std::task::Poll::Pending,
                 std::task::Poll::Ready(Err(fx_status::Status::PEER_CLOSED)) => {
@@ -349,6 +364,7 @@ pub struct ParentMarker;
 impl midl::endpoints::ProtocolMarker for ParentMarker {
     type Proxy = ParentProxy;
     type RequestStream = ParentRequestStream;
+    type SynchronousProxy = ParentSynchronousProxy;
     const DEBUG_NAME: &'static str = "(anonymous) Parent";
 }
 pub trait ParentProxyInterface: Send + Sync {
@@ -362,11 +378,22 @@ pub trait ParentProxyInterface: Send + Sync {
     fn r#take_child_request(&self, r: midl::endpoints::ServerEnd<ChildMarker>) -> Result<(), midl::Error>;
 }
 #[derive(Debug)]
-#[cfg(target_os = "fuchsia")]
 pub struct ParentSynchronousProxy {
     client: midl::client::sync::Client,
 }
-#[cfg(target_os = "fuchsia")]
+impl midl::endpoints::SynchronousProxy for ParentSynchronousProxy {
+    type Proxy = ParentProxy;
+    type Protocol = ParentMarker;
+    fn from_channel(inner: midl::Channel) -> Self {
+        Self::new(inner)
+    }
+    fn into_channel(self) -> midl::Channel {
+        self.client.into_channel()
+    }
+    fn as_channel(&self) -> &midl::Channel {
+        self.client.as_channel()
+    }
+}
 impl ParentSynchronousProxy {
     pub fn new(channel: midl::Channel) -> Self {
         let protocol_name = <ParentMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME;
@@ -379,10 +406,10 @@ impl ParentSynchronousProxy {
     }
     /// Waits until an event arrives and returns it. It is safe for other
     /// threads to make concurrent requests while waiting for an event.
-    pub fn wait_for_event(&self, deadline: zx::Time) -> Result<ParentEvent, midl::Error> {
+    pub fn wait_for_event(&self, deadline: fx::Time) -> Result<ParentEvent, midl::Error> {
         ParentEvent::decode(self.client.wait_for_event(deadline)?)
     }
-    pub fn r#get_child(&self, ___deadline: zx::Time) -> Result<midl::endpoints::ClientEnd<ChildMarker>, midl::Error> {
+    pub fn r#get_child(&self, ___deadline: fx::Time) -> Result<midl::endpoints::ClientEnd<ChildMarker>, midl::Error> {
         let _response = self
             .client
             .send_query::<midl::encoding::EmptyPayload, ParentGetChildResponse, false, false>(
@@ -395,7 +422,7 @@ impl ParentSynchronousProxy {
     }
     pub fn r#get_child_request(
         &self,
-        ___deadline: zx::Time,
+        ___deadline: fx::Time,
     ) -> Result<midl::endpoints::ServerEnd<ChildMarker>, midl::Error> {
         let _response = self
             .client
@@ -574,7 +601,10 @@ impl midl::endpoints::RequestStream for ParentRequestStream {
     type ControlHandle = ParentControlHandle;
     fn from_channel(channel: midl::AsyncChannel) -> Self {
         Self {
-            inner: std::sync::Arc::new(midl::ServeInner::new(channel)),
+            inner: std::sync::Arc::new(midl::ServeInner::new(
+                channel,
+                <ParentMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME,
+            )),
             is_terminated: false,
         }
     }
@@ -605,7 +635,7 @@ impl futures::Stream for ParentRequestStream {
             panic!("polled ParentRequestStream after completion");
         }
         midl::encoding::with_tls_decode_buf(|bytes, handles| {
-            match this.inner.channel().read_etc(cx, bytes, handles) {
+            match this.inner.poll_read_request(cx, bytes, handles) {
                 std::task::Poll::Ready(Ok(())) => {}
                 std::task::Poll::Pending => return std::task::Poll::Pending,
                 std::task::Poll::Ready(Err(fx_status::Status::PEER_CLOSED)) => {
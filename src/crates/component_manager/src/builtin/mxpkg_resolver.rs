@@ -0,0 +1,313 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use anyhow::format_err;
+use async_trait::async_trait;
+use meshx_pkg::MetaSubpackages;
+use meshx_url::{
+    AbsolutePackageUrl, PackageName, PinnedAbsolutePackageUrl, RelativePackageUrl, UnpinnedAbsolutePackageUrl,
+};
+use package_directory::{BlobStore, Hash, PackageDir};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::model::resolver::Resolver;
+use crate::routing::resolving::{
+    ComponentAddress, ComponentResolutionContext, ResolvedComponent, ResolvedPackage, ResolverError,
+};
+
+pub const SCHEME: &str = "mxpkg";
+
+/// Resolves `mxpkg://` component URLs to the components of the packages in a blob store.
+///
+/// A pinned package URL, such as `mxpkg://meshx.co/echo?hash=<hash>#meta/echo.cm`, names its
+/// package by the hash of its meta.far.  The hash of the package of an unpinned URL is looked up
+/// in the packages added with [`MxpkgResolver::add_package`].
+///
+/// The resolution context given to the children of a component is the pinned URL of its
+/// package, so that relative URLs, such as `echo#meta/echo.cm`, resolve to the components of its
+/// subpackages.  The components of subpackages are resolved to pinned URLs.
+pub struct MxpkgResolver {
+    blob_store: Arc<dyn BlobStore>,
+    packages: HashMap<UnpinnedAbsolutePackageUrl, Hash>,
+}
+
+impl MxpkgResolver {
+    pub fn new(blob_store: Arc<dyn BlobStore>) -> Self {
+        MxpkgResolver {
+            blob_store,
+            packages: HashMap::new(),
+        }
+    }
+
+    /// Resolves the unpinned URL `url` to the package with the meta.far `hash`.
+    pub fn add_package(&mut self, url: UnpinnedAbsolutePackageUrl, hash: Hash) -> &mut Self {
+        self.packages.insert(url, hash);
+        self
+    }
+
+    /// The pinned URL of the package of the absolute component URL `url`.
+    fn pin_absolute(&self, url: &url::Url) -> Result<PinnedAbsolutePackageUrl, ResolverError> {
+        let mut package_url = url.clone();
+        package_url.set_fragment(None);
+        match AbsolutePackageUrl::parse(package_url.as_str()).map_err(ResolverError::malformed_url)? {
+            AbsolutePackageUrl::Pinned(pinned) => Ok(pinned),
+            AbsolutePackageUrl::Unpinned(unpinned) => {
+                let hash =
+                    self.packages.get(&unpinned).copied().ok_or_else(|| {
+                        ResolverError::package_not_found(format_err!("`{unpinned}` is not in the index"))
+                    })?;
+                Ok(PinnedAbsolutePackageUrl::from_unpinned(unpinned, hash))
+            }
+        }
+    }
+
+    /// The pinned URL of the subpackage `url` of the package pinned by `context`.
+    async fn pin_relative(
+        &self,
+        url: &url::Url,
+        context: &ComponentResolutionContext,
+    ) -> Result<PinnedAbsolutePackageUrl, ResolverError> {
+        let subpackage =
+            RelativePackageUrl::parse(url.path().trim_start_matches('/')).map_err(ResolverError::malformed_url)?;
+        let parent = std::str::from_utf8(&context.bytes)
+            .map_err(ResolverError::internal)
+            .and_then(|context| PinnedAbsolutePackageUrl::parse(context).map_err(ResolverError::internal))?;
+
+        let parent_dir = self.open_package(&parent).await?;
+        let subpackages = parent_dir.read_file(MetaSubpackages::PATH).await.map_err(|status| {
+            ResolverError::package_not_found(format_err!(
+                "`{parent}` has no subpackages, reading `{}` failed: {status}",
+                MetaSubpackages::PATH
+            ))
+        })?;
+        let hash = MetaSubpackages::deserialize(&subpackages[..])
+            .map_err(ResolverError::internal)?
+            .subpackages()
+            .get(&subpackage)
+            .copied()
+            .ok_or_else(|| {
+                ResolverError::package_not_found(format_err!("`{parent}` has no subpackage `{subpackage}`"))
+            })?;
+        Ok(PinnedAbsolutePackageUrl::new(
+            parent.repository().clone(),
+            PackageName::from(subpackage),
+            None,
+            hash,
+        ))
+    }
+
+    async fn open_package(&self, url: &PinnedAbsolutePackageUrl) -> Result<Arc<PackageDir>, ResolverError> {
+        PackageDir::new(self.blob_store.clone(), url.hash())
+            .await
+            .map_err(|err| {
+                ResolverError::package_not_found(anyhow::Error::new(err).context(format!("loading `{url}`")))
+            })
+    }
+}
+
+impl fmt::Debug for MxpkgResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MxpkgResolver")
+            .field("packages", &self.packages)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Resolver for MxpkgResolver {
+    async fn resolve(&self, component_address: &ComponentAddress) -> Result<ResolvedComponent, ResolverError> {
+        let package_url = match component_address {
+            ComponentAddress::Absolute { url } => self.pin_absolute(url)?,
+            ComponentAddress::RelativePath { url, context, .. } => self.pin_relative(url, context).await?,
+        };
+        let resource = component_address.resource().ok_or_else(|| {
+            ResolverError::malformed_url(format_err!("`{}` has no resource fragment", component_address.url()))
+        })?;
+        meshx_url::validate_resource_path(resource).map_err(ResolverError::malformed_url)?;
+
+        let directory = self.open_package(&package_url).await?;
+        let manifest = directory
+            .read_file(resource)
+            .await
+            .map_err(|status| ResolverError::manifest_not_found(format_err!("`{resource}`: {status}")))?;
+        let decl = cm_config::encoding::decode(&manifest).map_err(ResolverError::manifest_invalid)?;
+        cm_config::validate::validate(&decl).map_err(ResolverError::manifest_invalid)?;
+
+        Ok(ResolvedComponent {
+            resolved_url: format!("{package_url}#{resource}"),
+            context_to_resolve_children: Some(ComponentResolutionContext::new(package_url.to_string().into_bytes())),
+            decl,
+            package: Some(ResolvedPackage {
+                url: package_url.to_string(),
+                directory,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fiber_rust as fx;
+    use meshx_pkg::MetaContents;
+    use std::collections::BTreeMap;
+    use std::future::Future;
+    use std::io::Read;
+    use std::sync::Mutex;
+    use std::task::{Context, Poll, Waker};
+
+    /// Runs `future` to completion.  The blob store is in memory, so nothing waits.
+    fn run<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("the future is not ready"),
+        }
+    }
+
+    #[derive(Default)]
+    struct MemoryBlobStore {
+        blobs: Mutex<HashMap<Hash, Vec<u8>>>,
+    }
+
+    impl MemoryBlobStore {
+        fn add_blob(&self, content: Vec<u8>) -> Hash {
+            let hash = meshx_merkle::from_slice(&content).root();
+            self.blobs.lock().unwrap().insert(hash, content);
+            hash
+        }
+
+        /// Adds a package made of the `meta/` files `meta`, and returns the hash of its meta.far.
+        fn add_package(&self, meta: &[(&str, Vec<u8>)]) -> Hash {
+            let mut meta_contents = Vec::new();
+            MetaContents::from_map(HashMap::new())
+                .unwrap()
+                .serialize(&mut meta_contents)
+                .unwrap();
+            let mut far_content: BTreeMap<&str, (u64, Box<dyn Read + '_>)> = BTreeMap::new();
+            far_content.insert(
+                "meta/contents",
+                (meta_contents.len() as u64, Box::new(&meta_contents[..])),
+            );
+            for (path, content) in meta {
+                far_content.insert(path, (content.len() as u64, Box::new(&content[..])));
+            }
+            let mut meta_far = Vec::new();
+            meshx_archive::write(&mut meta_far, far_content).unwrap();
+            self.add_blob(meta_far)
+        }
+    }
+
+    #[async_trait]
+    impl BlobStore for MemoryBlobStore {
+        async fn read_blob(&self, hash: &Hash) -> Result<Vec<u8>, fx::Status> {
+            self.blobs
+                .lock()
+                .unwrap()
+                .get(hash)
+                .cloned()
+                .ok_or(fx::Status::NOT_FOUND)
+        }
+    }
+
+    fn manifest(yaml: &str) -> Vec<u8> {
+        cm_config::encoding::encode(serde_yml::from_str(yaml).unwrap()).unwrap()
+    }
+
+    fn address(url: &str, context: Option<&ComponentResolutionContext>) -> ComponentAddress {
+        let url = cm_types::Url::new(url).unwrap();
+        ComponentAddress::from_url(&url, Some("mxpkg://meshx.co/parent#meta/parent.cm"), context).unwrap()
+    }
+
+    /// A resolver with the package `mxpkg://meshx.co/parent`, that has the subpackage `echo`.
+    fn resolver() -> (MxpkgResolver, Hash, Hash) {
+        let store = Arc::new(MemoryBlobStore::default());
+        let echo = store.add_package(&[(
+            "meta/echo.cm",
+            manifest("program:\n  runner: wasm\n  binary: bin/echo.wasm\n"),
+        )]);
+        let mut subpackages = Vec::new();
+        MetaSubpackages::from_iter([(RelativePackageUrl::parse("echo").unwrap(), echo)])
+            .serialize(&mut subpackages)
+            .unwrap();
+        let parent = store.add_package(&[
+            (
+                "meta/parent.cm",
+                manifest("children:\n  - name: echo\n    url: echo#meta/echo.cm\n"),
+            ),
+            ("meta/invalid.cm", b"not a manifest".to_vec()),
+            (MetaSubpackages::PATH, subpackages),
+        ]);
+        let mut resolver = MxpkgResolver::new(store);
+        resolver.add_package(
+            UnpinnedAbsolutePackageUrl::parse("mxpkg://meshx.co/parent").unwrap(),
+            parent,
+        );
+        (resolver, parent, echo)
+    }
+
+    #[test]
+    fn resolves_packages_and_subpackages() {
+        let (resolver, parent_hash, echo_hash) = resolver();
+
+        let parent = run(resolver.resolve(&address("mxpkg://meshx.co/parent#meta/parent.cm", None))).unwrap();
+        let pinned_parent = format!("mxpkg://meshx.co/parent?hash={parent_hash}");
+        assert_eq!(parent.resolved_url, format!("{pinned_parent}#meta/parent.cm"));
+        assert_eq!(parent.decl.children.len(), 1);
+        let package = parent.package.unwrap();
+        assert_eq!(package.url, pinned_parent);
+        assert_eq!(*package.directory.hash(), parent_hash);
+        let context = parent.context_to_resolve_children.unwrap();
+        assert_eq!(context.bytes, pinned_parent.as_bytes());
+
+        let echo = run(resolver.resolve(&address("echo#meta/echo.cm", Some(&context)))).unwrap();
+        assert_eq!(
+            echo.resolved_url,
+            format!("mxpkg://meshx.co/echo?hash={echo_hash}#meta/echo.cm")
+        );
+        assert_eq!(*echo.package.unwrap().directory.hash(), echo_hash);
+
+        // Pinned URLs do not need the index.
+        let pinned = run(resolver.resolve(&address(&format!("{pinned_parent}#meta/parent.cm"), None))).unwrap();
+        assert_eq!(pinned.resolved_url, parent.resolved_url);
+    }
+
+    #[test]
+    fn resolve_errors() {
+        let (resolver, _, _) = resolver();
+        let resolve = |url: &str, context: Option<&ComponentResolutionContext>| {
+            run(resolver.resolve(&address(url, context))).unwrap_err()
+        };
+        let context = ComponentResolutionContext::new(
+            run(resolver.resolve(&address("mxpkg://meshx.co/parent#meta/parent.cm", None)))
+                .unwrap()
+                .context_to_resolve_children
+                .unwrap()
+                .bytes,
+        );
+
+        assert!(matches!(
+            resolve("mxpkg://meshx.co/unknown#meta/unknown.cm", None),
+            ResolverError::PackageNotFound(_)
+        ));
+        assert!(matches!(
+            resolve("mxpkg://meshx.co/parent#meta/missing.cm", None),
+            ResolverError::ManifestNotFound(_)
+        ));
+        assert!(matches!(
+            resolve("mxpkg://meshx.co/parent#meta/invalid.cm", None),
+            ResolverError::ManifestInvalid(_)
+        ));
+        assert!(matches!(
+            resolve("mxpkg://meshx.co/parent", None),
+            ResolverError::MalformedUrl(_)
+        ));
+        assert!(matches!(
+            resolve("unknown#meta/unknown.cm", Some(&context)),
+            ResolverError::PackageNotFound(_)
+        ));
+    }
+}
@@ -3,19 +3,28 @@ use anyhow::format_err;
 use anyhow::Error;
 use cm_config::RuntimeConfig;
 use cm_types::Name;
+use package_directory::BlobDirectory;
+use std::path::Path;
 use std::rc::Rc;
+use std::sync::Arc;
 
+use crate::builtin::mxpkg_resolver::{self, MxpkgResolver};
 use crate::builtin::runner::{BuiltinRunner, BuiltinRunnerFactory};
 use crate::model::component::manager::ComponentManagerInstance;
+use crate::model::context::ModelContext;
+use crate::model::resolver::{Resolver, ResolverRegistry};
 use crate::model::token::InstanceRegistry;
 
+/// The blob store the packages of the mxpkg resolver are read from.
+const BLOB_DIRECTORY: &str = "/blob";
+
 pub struct BuiltinEnvironmentBuilder {
     // TODO(60804): Make component manager's namespace injectable here.
     runtime_config: Option<RuntimeConfig>,
     top_instance: Option<Rc<ComponentManagerInstance>>,
     //bootfs_svc: Option<BootfsSvc>,
     runners: Vec<(Name, Rc<dyn BuiltinRunnerFactory>)>,
-    //resolvers: ResolverRegistry,
+    resolvers: ResolverRegistry,
     //utc_clock: Option<Arc<Clock>>,
     add_environment_resolvers: bool,
     //inspector: Option<Inspector>,
@@ -30,7 +39,7 @@ impl Default for BuiltinEnvironmentBuilder {
             top_instance: None,
             //bootfs_svc: None,
             runners: vec![],
-            //resolvers: ResolverRegistry::default(),
+            resolvers: ResolverRegistry::default(),
             //utc_clock: None,
             add_environment_resolvers: false,
             //inspector: None,
@@ -49,7 +58,7 @@ impl BuiltinEnvironmentBuilder {
         assert!(self.runtime_config.is_none());
         let top_instance = Rc::new(ComponentManagerInstance::new(
            // runtime_config.namespace_capabilities.clone(),
-           // runtime_config.builtin_capabilities.clone(),
+            runtime_config.builtin_capabilities.clone(),
         ));
         self.runtime_config = Some(runtime_config);
         self.top_instance = Some(top_instance);
@@ -81,7 +90,7 @@ impl BuiltinEnvironmentBuilder {
     /// Adds standard resolvers whose dependencies are available in the process's namespace and for
     /// whose scheme no resolver is registered through `add_resolver` by the time `build()` is
     /// is called. This includes:
-    ///   - A fuchsia-boot resolver if /boot is available.
+    ///   - An mxpkg resolver if /blob is available.
     pub fn include_namespace_resolvers(mut self) -> Self {
         self.add_environment_resolvers = true;
         self
@@ -94,10 +103,7 @@ impl BuiltinEnvironmentBuilder {
         self
     }
 
-    #[cfg(test)]
     pub fn add_resolver(mut self, scheme: String, resolver: Box<dyn Resolver + Send + Sync + 'static>) -> Self {
-        use crate::model::resolver::Resolver;
-
         self.resolvers.register(scheme, resolver);
         self
     }
@@ -121,11 +127,28 @@ impl BuiltinEnvironmentBuilder {
             .map(|(name, runner)| BuiltinRunner::new(name, runner, runtime_config.security_policy.clone()))
             .collect();
 
+        if self.add_environment_resolvers
+            && !self.resolvers.is_registered(mxpkg_resolver::SCHEME)
+            && Path::new(BLOB_DIRECTORY).exists()
+        {
+            let blob_store = Arc::new(BlobDirectory::new(BLOB_DIRECTORY));
+            self.resolvers.register(
+                mxpkg_resolver::SCHEME.to_string(),
+                Box::new(MxpkgResolver::new(blob_store)),
+            );
+        }
+
         let runtime_config = Rc::new(runtime_config);
+        let context = Rc::new(ModelContext::new(
+            self.top_instance.unwrap(),
+            self.resolvers,
+            runtime_config.security_policy.clone(),
+        ));
 
         Ok(BuiltinEnvironment::new(
             //params,
             runtime_config,
+            context,
             //system_resource_handle,
             builtin_runners,
             false
@@ -148,6 +171,8 @@ impl BuiltinEnvironmentBuilder {
 /// * If [RuntimeConfig::maintain_utc_clock] is true, a fuchsia.time.Maintenance service is
 ///   available.
 pub struct BuiltinEnvironment {
+    /// The state shared by the component instances, with the resolvers of the environment.
+    pub context: Rc<ModelContext>,
     pub debug: bool,
 }
 
@@ -155,6 +180,7 @@ impl BuiltinEnvironment {
     async fn new(
         //arams: ModelParams,
         runtime_config: Rc<RuntimeConfig>,
+        context: Rc<ModelContext>,
         //system_resource_handle: Option<Resource>,
         builtin_runners: Vec<BuiltinRunner>,
         //boot_resolver: Option<FuchsiaBootResolver>,
@@ -168,6 +194,7 @@ impl BuiltinEnvironment {
 
         Ok(BuiltinEnvironment {
             // model,
+            context,
             // realm_query,
             // lifecycle_controller,
             // event_registry,
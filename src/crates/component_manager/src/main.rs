@@ -21,7 +21,7 @@ fn write(_value: String) {
     unsafe {
         // let (data, len, _) = _value.into_raw_parts();
         let c_string: CString = CString::new(_value).expect("CString::new failed");
-        fiber_sys::fx_debug(c_string.as_ptr() , c_string.count_bytes());
+        fiber_sys::fx_debug(c_string.as_ptr() as _, c_string.count_bytes());
     }
 }
 
@@ -0,0 +1,134 @@
+// Copyright 2023 The Fuchsia Authors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use cm_config::ComponentDecl;
+use moniker::{ChildMoniker, InstanceId};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use crate::model::component::ComponentInstance;
+use crate::routing::resolving::ComponentResolutionContext;
+
+/// The lifecycle state of a component instance.
+///
+/// Instances start out `Unresolved`.  Resolving them moves them to `Resolved`, and starting them
+/// to `Started`.  Once `Stopped`, they can be started again.  Any of these states can move to
+/// `Destroyed`, which is final.
+#[derive(Debug)]
+pub enum InstanceState {
+    /// The instance has been discovered, but its declaration has not been resolved yet.
+    Unresolved,
+    /// The declaration of the instance has been resolved, and its static children discovered.
+    Resolved(ResolvedInstanceState),
+    /// The instance is running.
+    Started(ResolvedInstanceState),
+    /// The instance was started, and has been stopped since.
+    Stopped(ResolvedInstanceState),
+    /// The instance has been destroyed, along with its children.
+    Destroyed,
+}
+
+impl InstanceState {
+    pub fn get_resolved_state(&self) -> Option<&ResolvedInstanceState> {
+        match self {
+            InstanceState::Resolved(state) | InstanceState::Started(state) | InstanceState::Stopped(state) => {
+                Some(state)
+            }
+            InstanceState::Unresolved | InstanceState::Destroyed => None,
+        }
+    }
+
+    pub fn get_resolved_state_mut(&mut self) -> Option<&mut ResolvedInstanceState> {
+        match self {
+            InstanceState::Resolved(state) | InstanceState::Started(state) | InstanceState::Stopped(state) => {
+                Some(state)
+            }
+            InstanceState::Unresolved | InstanceState::Destroyed => None,
+        }
+    }
+
+    pub fn is_started(&self) -> bool {
+        matches!(self, InstanceState::Started(_))
+    }
+
+    /// Moves a resolved instance to the state built by `state`, such as `InstanceState::Started`,
+    /// keeping its resolved state.  Does nothing for unresolved and destroyed instances.
+    pub(super) fn transition(&mut self, state: fn(ResolvedInstanceState) -> InstanceState) {
+        *self = match std::mem::replace(self, InstanceState::Destroyed) {
+            InstanceState::Resolved(resolved) | InstanceState::Started(resolved) | InstanceState::Stopped(resolved) => {
+                state(resolved)
+            }
+            state @ (InstanceState::Unresolved | InstanceState::Destroyed) => state,
+        };
+    }
+}
+
+/// The state of a resolved instance: its declaration, and its children.
+#[derive(Debug)]
+pub struct ResolvedInstanceState {
+    decl: Rc<ComponentDecl>,
+    resolved_url: String,
+    context_to_resolve_children: Option<ComponentResolutionContext>,
+    children: BTreeMap<ChildMoniker, Rc<ComponentInstance>>,
+    /// Static children have the instance id 0.  Dynamic children get increasing ids, so that a
+    /// child replacing a destroyed one of the same name gets a different moniker.
+    next_dynamic_instance_id: InstanceId,
+}
+
+impl ResolvedInstanceState {
+    pub fn new(
+        decl: Rc<ComponentDecl>,
+        resolved_url: String,
+        context_to_resolve_children: Option<ComponentResolutionContext>,
+    ) -> Self {
+        Self {
+            decl,
+            resolved_url,
+            context_to_resolve_children,
+            children: BTreeMap::new(),
+            next_dynamic_instance_id: 1,
+        }
+    }
+
+    pub fn decl(&self) -> &Rc<ComponentDecl> {
+        &self.decl
+    }
+
+    /// The URL the instance was resolved from, which relative child URLs are resolved against.
+    pub fn resolved_url(&self) -> &str {
+        &self.resolved_url
+    }
+
+    /// The context given by the resolver of the instance, to resolve relative child URLs.
+    pub fn context_to_resolve_children(&self) -> Option<&ComponentResolutionContext> {
+        self.context_to_resolve_children.as_ref()
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = (&ChildMoniker, &Rc<ComponentInstance>)> {
+        self.children.iter()
+    }
+
+    /// The child with the given child moniker, ignoring instance ids.
+    pub fn get_child(&self, moniker: &ChildMoniker) -> Option<&Rc<ComponentInstance>> {
+        let moniker = moniker.to_partial();
+        self.children
+            .iter()
+            .find(|(child, _)| child.to_partial() == moniker)
+            .map(|(_, child)| child)
+    }
+
+    pub(super) fn add_child(&mut self, moniker: ChildMoniker, child: Rc<ComponentInstance>) {
+        self.children.insert(moniker, child);
+    }
+
+    pub(super) fn remove_child(&mut self, moniker: &ChildMoniker) -> Option<Rc<ComponentInstance>> {
+        self.children.remove(moniker)
+    }
+
+    pub(super) fn next_dynamic_instance_id(&mut self) -> InstanceId {
+        let id = self.next_dynamic_instance_id;
+        self.next_dynamic_instance_id += 1;
+        id
+    }
+}
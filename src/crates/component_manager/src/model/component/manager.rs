@@ -1,19 +1,30 @@
+use cm_config::CapabilityDecl;
+
+use crate::routing::component_instance::TopInstanceInterface;
+
 /// A special instance identified with component manager, at the top of the tree.
 #[derive(Debug)]
 pub struct ComponentManagerInstance {
-
+    //namespace_capabilities: NamespaceCapabilities,
+    builtin_capabilities: Vec<CapabilityDecl>,
 }
 
 impl ComponentManagerInstance {
     pub fn new(
         //namespace_capabilities: NamespaceCapabilities,
-        //builtin_capabilities: BuiltinCapabilities,
+        builtin_capabilities: Vec<CapabilityDecl>,
     ) -> Self {
         Self {
             //namespace_capabilities,
-            //builtin_capabilities,
+            builtin_capabilities,
             //state: Mutex::new(ComponentManagerInstanceState::new()),
             //task_group: TaskGroup::new(),
         }
     }
-}
\ No newline at end of file
+}
+
+impl TopInstanceInterface for ComponentManagerInstance {
+    fn builtin_capabilities(&self) -> &[CapabilityDecl] {
+        &self.builtin_capabilities
+    }
+}
@@ -2,5 +2,680 @@
 // Use of this source code is governed by a BSD-style license that can be
 // found in the LICENSE file.
 
-//pub mod instance;
-pub mod manager;
\ No newline at end of file
+pub mod instance;
+pub mod manager;
+mod shutdown;
+
+use cm_config::{ChildDecl, ComponentDecl, Durability, OnTerminate, StartupMode};
+use cm_types::{Name, Url};
+use moniker::{AbsoluteMoniker, ChildMoniker, PartialMoniker};
+use std::cell::{Ref, RefCell};
+use std::fmt;
+use std::rc::{Rc, Weak};
+
+use crate::model::component::instance::{InstanceState, ResolvedInstanceState};
+use crate::model::component::manager::ComponentManagerInstance;
+use crate::model::component::shutdown::{shutdown_order, ShutdownNode};
+use crate::model::context::ModelContext;
+use crate::model::error::ModelError;
+use crate::model::hooks::{Event, EventPayload};
+use crate::model::resolver::Resolver;
+use crate::routing::component_instance::{
+    static_child, ComponentInstanceError, ComponentInstanceInterface, ExtendedInstance,
+};
+use crate::routing::resolving::ComponentAddress;
+
+/// A component instance, and its place in the instance tree.  Instances own their children, and
+/// refer to their parent weakly.
+pub struct ComponentInstance {
+    /// The moniker of this instance.
+    pub moniker: AbsoluteMoniker,
+    /// The URL of this instance's declaration.
+    pub component_url: Url,
+    /// Whether this instance is started along with its parent.
+    pub startup: StartupMode,
+    /// What happens when this instance terminates.
+    pub on_terminate: OnTerminate,
+    /// The durability of the collection this instance is in, for dynamic instances.
+    pub durability: Option<Durability>,
+    context: Rc<ModelContext>,
+    /// The parent of this instance, or `None` for the root instance.
+    parent: Option<Weak<ComponentInstance>>,
+    state: RefCell<InstanceState>,
+}
+
+impl ComponentInstance {
+    /// Creates the root instance of the tree, the component `component_url`.
+    pub async fn new_root(context: Rc<ModelContext>, component_url: Url) -> Result<Rc<Self>, ModelError> {
+        let root = Rc::new(Self {
+            moniker: AbsoluteMoniker::root(),
+            component_url,
+            startup: StartupMode::Lazy,
+            on_terminate: OnTerminate::None,
+            durability: None,
+            context,
+            parent: None,
+            state: RefCell::new(InstanceState::Unresolved),
+        });
+        root.dispatch(EventPayload::Discovered).await?;
+        Ok(root)
+    }
+
+    fn new_child(
+        self: &Rc<Self>,
+        moniker: ChildMoniker,
+        decl: &ChildDecl,
+        durability: Option<Durability>,
+    ) -> Result<Rc<Self>, ModelError> {
+        let on_terminate = decl.on_terminate.unwrap_or(OnTerminate::None);
+        if on_terminate == OnTerminate::Reboot {
+            self.context.policy().reboot_on_terminate_allowed(&self.moniker)?;
+        }
+        Ok(Rc::new(Self {
+            moniker: self.moniker.child(moniker),
+            component_url: decl.url.clone(),
+            startup: decl.startup,
+            on_terminate,
+            durability,
+            context: self.context.clone(),
+            parent: Some(Rc::downgrade(self)),
+            state: RefCell::new(InstanceState::Unresolved),
+        }))
+    }
+
+    pub fn state(&self) -> Ref<'_, InstanceState> {
+        self.state.borrow()
+    }
+
+    fn parent_instance(&self) -> Option<Rc<ComponentInstance>> {
+        self.parent.as_ref().and_then(Weak::upgrade)
+    }
+
+    async fn dispatch(&self, payload: EventPayload) -> Result<(), ModelError> {
+        let event = Event {
+            target_moniker: self.moniker.clone(),
+            component_url: self.component_url.clone(),
+            payload,
+        };
+        self.context.hooks().dispatch(&event).await
+    }
+
+    /// Resolves the declaration of this instance, if it is not resolved yet, and discovers its
+    /// static children.
+    pub async fn resolve(self: &Rc<Self>) -> Result<Rc<ComponentDecl>, ModelError> {
+        match &*self.state.borrow() {
+            InstanceState::Unresolved => {}
+            InstanceState::Destroyed => return Err(ModelError::instance_destroyed(self.moniker.clone())),
+            state => return Ok(state.get_resolved_state().unwrap().decl().clone()),
+        }
+
+        // Children are discovered when their parent is resolved, so the parent of an unresolved
+        // instance is resolved.
+        let parent = self.parent_instance().and_then(|parent| {
+            parent.state().get_resolved_state().map(|state| {
+                (
+                    state.resolved_url().to_string(),
+                    state.context_to_resolve_children().cloned(),
+                )
+            })
+        });
+        let (parent_url, parent_context) = parent.unzip();
+        let resolve_failed = |err| ComponentInstanceError::resolve_failed(self.moniker.clone(), err);
+        let address = ComponentAddress::from_url(
+            &self.component_url,
+            parent_url.as_deref(),
+            parent_context.flatten().as_ref(),
+        )
+        .map_err(resolve_failed)?;
+        let resolved = self
+            .context
+            .resolvers()
+            .resolve(&address)
+            .await
+            .map_err(resolve_failed)?;
+        let decl = Rc::new(resolved.decl);
+
+        let mut resolved_state = ResolvedInstanceState::new(
+            decl.clone(),
+            resolved.resolved_url.clone(),
+            resolved.context_to_resolve_children,
+        );
+        let mut children = vec![];
+        for child_decl in &decl.children {
+            let moniker = static_child(&child_decl.name);
+            let child = self.new_child(moniker.clone(), child_decl, None)?;
+            resolved_state.add_child(moniker, child.clone());
+            children.push(child);
+        }
+
+        {
+            // The instance may have been resolved or destroyed while the resolver was running.
+            let mut state = self.state.borrow_mut();
+            match &*state {
+                InstanceState::Unresolved => *state = InstanceState::Resolved(resolved_state),
+                InstanceState::Destroyed => return Err(ModelError::instance_destroyed(self.moniker.clone())),
+                state => return Ok(state.get_resolved_state().unwrap().decl().clone()),
+            }
+        }
+        for child in children {
+            child.dispatch(EventPayload::Discovered).await?;
+        }
+        self.dispatch(EventPayload::Resolved {
+            decl: decl.clone(),
+            resolved_url: resolved.resolved_url,
+        })
+        .await?;
+        Ok(decl)
+    }
+
+    /// Starts this instance, resolving it first if needed, then starts its eager children, in
+    /// dependency order.  Starting a started instance does nothing.
+    pub async fn start(self: &Rc<Self>) -> Result<(), ModelError> {
+        self.resolve().await?;
+        let eager_children = {
+            let mut state = self.state.borrow_mut();
+            match &*state {
+                InstanceState::Started(_) => return Ok(()),
+                InstanceState::Destroyed => return Err(ModelError::instance_destroyed(self.moniker.clone())),
+                InstanceState::Unresolved => {
+                    return Err(ComponentInstanceError::not_resolved(self.moniker.clone()).into())
+                }
+                InstanceState::Resolved(_) | InstanceState::Stopped(_) => {}
+            }
+            state.transition(InstanceState::Started);
+            self.shutdown_order(state.get_resolved_state().unwrap())
+                .into_iter()
+                .rev()
+                .filter_map(|node| match node {
+                    (ShutdownNode::Child(_), Some(child)) if child.startup == StartupMode::Eager => Some(child),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        self.dispatch(EventPayload::Started).await?;
+        for child in eager_children {
+            Box::pin(child.start()).await?;
+        }
+        Ok(())
+    }
+
+    /// Stops this instance and every instance in its realm, each after the instances that depend
+    /// on it.  Instances of single run collections are destroyed once stopped.
+    pub async fn stop(self: &Rc<Self>) -> Result<(), ModelError> {
+        self.stop_realm().await?;
+        if self.durability == Some(Durability::SingleRun) {
+            if let Some(parent) = self.parent_instance() {
+                Box::pin(parent.destroy_dynamic_child(self.moniker.leaf().unwrap())).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn stop_realm(self: &Rc<Self>) -> Result<(), ModelError> {
+        let order = match self.state().get_resolved_state() {
+            Some(state) => self.shutdown_order(state),
+            None => return Ok(()),
+        };
+        for node in order {
+            match node {
+                (ShutdownNode::Self_, _) => {
+                    let was_started = {
+                        let mut state = self.state.borrow_mut();
+                        let was_started = state.is_started();
+                        if was_started {
+                            state.transition(InstanceState::Stopped);
+                        }
+                        was_started
+                    };
+                    if was_started {
+                        self.dispatch(EventPayload::Stopped).await?;
+                    }
+                }
+                (ShutdownNode::Child(_), Some(child)) => Box::pin(child.stop()).await?,
+                (ShutdownNode::Child(_), None) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Stops this instance, then destroys its children and the instance itself.  A destroyed
+    /// instance cannot be resolved nor started anymore.
+    pub async fn destroy(self: &Rc<Self>) -> Result<(), ModelError> {
+        self.stop_realm().await?;
+        let children = match &*self.state() {
+            InstanceState::Destroyed => return Ok(()),
+            state => state
+                .get_resolved_state()
+                .map(|state| state.children().map(|(_, child)| child.clone()).collect::<Vec<_>>())
+                .unwrap_or_default(),
+        };
+        for child in children {
+            Box::pin(child.destroy()).await?;
+        }
+        *self.state.borrow_mut() = InstanceState::Destroyed;
+        self.dispatch(EventPayload::Destroyed).await
+    }
+
+    /// Adds the child `decl` to the collection `collection` of this instance.  The child gets the
+    /// next dynamic instance id of this instance.  Children of single run collections are started
+    /// right away.
+    pub async fn add_dynamic_child(
+        self: &Rc<Self>,
+        collection: &Name,
+        decl: &ChildDecl,
+    ) -> Result<Rc<ComponentInstance>, ModelError> {
+        let child = {
+            let mut state = self.state.borrow_mut();
+            let state = match &mut *state {
+                InstanceState::Unresolved => {
+                    return Err(ComponentInstanceError::not_resolved(self.moniker.clone()).into())
+                }
+                InstanceState::Destroyed => return Err(ModelError::instance_destroyed(self.moniker.clone())),
+                state => state.get_resolved_state_mut().unwrap(),
+            };
+            let durability = match state.decl().find_collection(collection) {
+                Some(collection) => collection.durability,
+                None => {
+                    return Err(ModelError::CollectionNotFound {
+                        moniker: self.moniker.clone(),
+                        collection: collection.clone(),
+                    })
+                }
+            };
+            let partial = PartialMoniker::new(decl.name.to_string(), Some(collection.to_string()));
+            if state.get_child(&ChildMoniker::from_partial(&partial, 0)).is_some() {
+                return Err(ModelError::InstanceAlreadyExists {
+                    moniker: self.moniker.clone(),
+                    child: partial,
+                });
+            }
+            let moniker = ChildMoniker::from_partial(&partial, state.next_dynamic_instance_id());
+            let child = self.new_child(moniker.clone(), decl, Some(durability))?;
+            state.add_child(moniker, child.clone());
+            child
+        };
+        child.dispatch(EventPayload::Discovered).await?;
+        if child.durability == Some(Durability::SingleRun) {
+            child.start().await?;
+        }
+        Ok(child)
+    }
+
+    /// Destroys the dynamic child `moniker` of this instance, ignoring instance ids, and removes
+    /// it from the tree.
+    pub async fn destroy_dynamic_child(self: &Rc<Self>, moniker: &ChildMoniker) -> Result<(), ModelError> {
+        let child = self
+            .state()
+            .get_resolved_state()
+            .and_then(|state| state.get_child(moniker))
+            .filter(|child| child.durability.is_some())
+            .cloned()
+            .ok_or_else(|| ComponentInstanceError::instance_not_found(self.moniker.child(moniker.clone())))?;
+        Box::pin(child.destroy()).await?;
+        if let Some(state) = self.state.borrow_mut().get_resolved_state_mut() {
+            state.remove_child(child.moniker.leaf().unwrap());
+        }
+        Ok(())
+    }
+
+    /// The shutdown order of this instance and its current children.
+    fn shutdown_order(&self, state: &ResolvedInstanceState) -> Vec<(ShutdownNode, Option<Rc<ComponentInstance>>)> {
+        let children = state.children().map(|(moniker, _)| moniker.clone()).collect::<Vec<_>>();
+        shutdown_order(state.decl(), &children)
+            .into_iter()
+            .map(|node| {
+                let child = match &node {
+                    ShutdownNode::Self_ => None,
+                    ShutdownNode::Child(moniker) => state.get_child(moniker).cloned(),
+                };
+                (node, child)
+            })
+            .collect()
+    }
+}
+
+impl ComponentInstanceInterface for ComponentInstance {
+    type TopInstance = ComponentManagerInstance;
+
+    fn moniker(&self) -> &AbsoluteMoniker {
+        &self.moniker
+    }
+
+    fn decl(&self) -> Result<Rc<ComponentDecl>, ComponentInstanceError> {
+        match &*self.state() {
+            InstanceState::Unresolved => Err(ComponentInstanceError::not_resolved(self.moniker.clone())),
+            InstanceState::Destroyed => Err(ComponentInstanceError::instance_not_found(self.moniker.clone())),
+            state => Ok(state.get_resolved_state().unwrap().decl().clone()),
+        }
+    }
+
+    fn parent(&self) -> Result<ExtendedInstance<Self>, ComponentInstanceError> {
+        match &self.parent {
+            None => Ok(ExtendedInstance::AboveRoot(self.context.top_instance().clone())),
+            Some(parent) => parent
+                .upgrade()
+                .map(ExtendedInstance::Component)
+                .ok_or_else(|| ComponentInstanceError::instance_not_found(self.moniker.parent().unwrap())),
+        }
+    }
+
+    fn find_child(&self, moniker: &ChildMoniker) -> Result<Option<Rc<Self>>, ComponentInstanceError> {
+        match &*self.state() {
+            InstanceState::Unresolved => Err(ComponentInstanceError::not_resolved(self.moniker.clone())),
+            InstanceState::Destroyed => Err(ComponentInstanceError::instance_not_found(self.moniker.clone())),
+            state => Ok(state.get_resolved_state().unwrap().get_child(moniker).cloned()),
+        }
+    }
+}
+
+impl fmt::Debug for ComponentInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ComponentInstance")
+            .field("moniker", &self.moniker)
+            .field("component_url", &self.component_url)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::hooks::{EventType, Hook, HooksRegistration};
+    use crate::model::resolver::ResolverRegistry;
+    use crate::routing::capability_source::CapabilitySource;
+    use crate::routing::policy::{GlobalPolicyChecker, PolicyError};
+    use crate::routing::resolving::{ResolvedComponent, ResolverError};
+    use crate::routing::route::route_capability;
+    use async_trait::async_trait;
+    use cm_config::{AllowlistEntry, ChildPolicyAllowlists, SecurityPolicy};
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::task::{Context, Poll, Waker};
+
+    /// Runs `future` to completion.  Nothing in these tests waits: the resolver and hooks are
+    /// ready right away.
+    fn run<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("the future is not ready"),
+        }
+    }
+
+    /// Resolves the URLs of `components` to their manifests.
+    #[derive(Debug)]
+    struct MockResolver {
+        components: HashMap<String, ComponentDecl>,
+    }
+
+    #[async_trait]
+    impl Resolver for MockResolver {
+        async fn resolve(
+            &self,
+            component_address: &ComponentAddress,
+        ) -> Result<crate::routing::resolving::ResolvedComponent, ResolverError> {
+            let url = component_address.url().to_string();
+            let decl = self
+                .components
+                .get(&url)
+                .cloned()
+                .ok_or_else(|| ResolverError::package_not_found(anyhow::format_err!("`{url}` not found")))?;
+            Ok(ResolvedComponent {
+                resolved_url: url,
+                context_to_resolve_children: None,
+                decl,
+                package: None,
+            })
+        }
+    }
+
+    /// Records the events it receives as "<event type> <moniker>".
+    #[derive(Default)]
+    struct EventLog {
+        events: RefCell<Vec<String>>,
+    }
+
+    impl EventLog {
+        fn take(&self) -> Vec<String> {
+            std::mem::take(&mut *self.events.borrow_mut())
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl Hook for EventLog {
+        async fn on(self: Rc<Self>, event: &Event) -> Result<(), ModelError> {
+            self.events
+                .borrow_mut()
+                .push(format!("{:?} {}", event.event_type(), event.target_moniker));
+            Ok(())
+        }
+    }
+
+    const ROOT_URL: &str = "mxpkg://meshx.co/root#meta/root.cm";
+
+    const ROOT: &str = r##"
+children:
+  - name: server
+    url: "#meta/server.cm"
+    startup: eager
+  - name: client
+    url: mxpkg://meshx.co/client#meta/client.cm
+    startup: eager
+  - name: lazy
+    url: mxpkg://meshx.co/client#meta/client.cm
+collections:
+  - name: workers
+  - name: tasks
+    durability: single_run
+offer:
+  - type: protocol
+    source:
+      child: server
+    source_name: echo.Echo
+    target:
+      child: client
+    target_name: echo.Echo
+  - type: protocol
+    source:
+      child: server
+    source_name: echo.Echo
+    target:
+      collection: workers
+    target_name: echo.Echo
+"##;
+
+    const SERVER: &str = r#"
+capabilities:
+  - type: protocol
+    name: echo.Echo
+expose:
+  - type: protocol
+    source: self
+    source_name: echo.Echo
+    target_name: echo.Echo
+"#;
+
+    const CLIENT: &str = r#"
+use:
+  - type: protocol
+    source: parent
+    source_name: echo.Echo
+    target_path: /svc/echo.Echo
+"#;
+
+    fn model(root: &str, policy: SecurityPolicy) -> (Rc<ComponentInstance>, Rc<EventLog>) {
+        let components = [
+            (ROOT_URL, root),
+            ("mxpkg://meshx.co/root#meta/server.cm", SERVER),
+            ("mxpkg://meshx.co/client#meta/client.cm", CLIENT),
+        ]
+        .into_iter()
+        .map(|(url, manifest)| (url.to_string(), serde_yml::from_str(manifest).unwrap()))
+        .collect();
+        let mut resolvers = ResolverRegistry::new();
+        resolvers.register("mxpkg".to_string(), Box::new(MockResolver { components }));
+        let context = Rc::new(ModelContext::new(
+            Rc::new(ComponentManagerInstance::new(vec![])),
+            resolvers,
+            Rc::new(policy),
+        ));
+
+        let log = Rc::new(EventLog::default());
+        let hook: Rc<dyn Hook> = log.clone();
+        context.hooks().install(vec![HooksRegistration::new(
+            vec![
+                EventType::Discovered,
+                EventType::Resolved,
+                EventType::Started,
+                EventType::Stopped,
+                EventType::Destroyed,
+            ],
+            Rc::downgrade(&hook),
+        )]);
+        let root = run(ComponentInstance::new_root(context, Url::new(ROOT_URL).unwrap())).unwrap();
+        (root, log)
+    }
+
+    fn child(instance: &ComponentInstance, moniker: &str) -> Rc<ComponentInstance> {
+        instance.find_child(&moniker.into()).unwrap().unwrap()
+    }
+
+    fn name(name: &str) -> Name {
+        Name::new(name).unwrap()
+    }
+
+    fn child_decl(name: &str) -> ChildDecl {
+        ChildDecl {
+            name: Name::new(name).unwrap(),
+            url: Url::new("mxpkg://meshx.co/client#meta/client.cm").unwrap(),
+            startup: StartupMode::Lazy,
+            on_terminate: None,
+            environment: None,
+        }
+    }
+
+    #[test]
+    fn lifecycle() {
+        let (root, log) = model(ROOT, SecurityPolicy::default());
+        assert_eq!(log.take(), vec!["Discovered /"]);
+        assert!(matches!(*root.state(), InstanceState::Unresolved));
+
+        run(root.start()).unwrap();
+        assert_eq!(
+            log.take(),
+            vec![
+                "Discovered /server:0",
+                "Discovered /client:0",
+                "Discovered /lazy:0",
+                "Resolved /",
+                "Started /",
+                "Resolved /server:0",
+                "Started /server:0",
+                "Resolved /client:0",
+                "Started /client:0",
+            ]
+        );
+        assert!(matches!(*child(&root, "lazy:0").state(), InstanceState::Unresolved));
+
+        // The client uses the server, so it is stopped first.
+        run(root.stop()).unwrap();
+        assert_eq!(log.take(), vec!["Stopped /", "Stopped /client:0", "Stopped /server:0"]);
+        assert!(matches!(*root.state(), InstanceState::Stopped(_)));
+
+        run(root.start()).unwrap();
+        assert_eq!(log.take(), vec!["Started /", "Started /server:0", "Started /client:0"]);
+
+        run(root.destroy()).unwrap();
+        assert_eq!(
+            log.take(),
+            vec![
+                "Stopped /",
+                "Stopped /client:0",
+                "Stopped /server:0",
+                "Destroyed /client:0",
+                "Destroyed /lazy:0",
+                "Destroyed /server:0",
+                "Destroyed /",
+            ]
+        );
+        assert!(matches!(run(root.start()), Err(ModelError::InstanceDestroyed { .. })));
+    }
+
+    #[test]
+    fn dynamic_children() {
+        let (root, log) = model(ROOT, SecurityPolicy::default());
+        run(root.resolve()).unwrap();
+        log.take();
+
+        let a = run(root.add_dynamic_child(&name("workers"), &child_decl("a"))).unwrap();
+        let b = run(root.add_dynamic_child(&name("workers"), &child_decl("b"))).unwrap();
+        assert_eq!(a.moniker.to_string(), "/workers:a:1");
+        assert_eq!(b.moniker.to_string(), "/workers:b:2");
+        assert!(matches!(
+            run(root.add_dynamic_child(&name("workers"), &child_decl("a"))),
+            Err(ModelError::InstanceAlreadyExists { .. })
+        ));
+        assert!(matches!(
+            run(root.add_dynamic_child(&name("missing"), &child_decl("a"))),
+            Err(ModelError::CollectionNotFound { .. })
+        ));
+
+        // Destroyed children are replaced by new instances.
+        run(root.destroy_dynamic_child(&"workers:a:0".into())).unwrap();
+        assert!(matches!(*a.state(), InstanceState::Destroyed));
+        let a = run(root.add_dynamic_child(&name("workers"), &child_decl("a"))).unwrap();
+        assert_eq!(a.moniker.to_string(), "/workers:a:3");
+        assert!(matches!(
+            run(root.destroy_dynamic_child(&"server:0".into())),
+            Err(ModelError::ComponentInstance(
+                ComponentInstanceError::InstanceNotFound { .. }
+            ))
+        ));
+
+        // Children of single run collections are started right away, and destroyed once stopped.
+        let task = run(root.add_dynamic_child(&name("tasks"), &child_decl("t"))).unwrap();
+        assert!(task.state().is_started());
+        log.take();
+        run(task.stop()).unwrap();
+        assert_eq!(log.take(), vec!["Stopped /tasks:t:4", "Destroyed /tasks:t:4"]);
+        assert!(root.find_child(&"tasks:t:0".into()).unwrap().is_none());
+    }
+
+    #[test]
+    fn routes_between_instances() {
+        let (root, _log) = model(ROOT, SecurityPolicy::default());
+        run(root.start()).unwrap();
+        let client = child(&root, "client:0");
+        let use_decl = client.decl().unwrap().uses[0].clone();
+        let source = route_capability(&use_decl, &client, &GlobalPolicyChecker::default()).unwrap();
+        match source.source {
+            CapabilitySource::Component { component, .. } => assert_eq!(component.moniker.to_string(), "/server:0"),
+            source => panic!("unexpected source {source:?}"),
+        }
+    }
+
+    #[test]
+    fn reboot_on_terminate_policy() {
+        let root = r#"
+children:
+  - name: critical
+    url: mxpkg://meshx.co/client#meta/client.cm
+    on_terminate: reboot
+"#;
+        let (instance, _log) = model(root, SecurityPolicy::default());
+        assert!(matches!(
+            run(instance.resolve()),
+            Err(ModelError::Policy(PolicyError::ChildPolicyDisallowed { .. }))
+        ));
+
+        let policy = SecurityPolicy {
+            child_policy: ChildPolicyAllowlists {
+                reboot_on_terminate: vec![AllowlistEntry::Exact(AbsoluteMoniker::root())],
+            },
+            ..Default::default()
+        };
+        let (instance, _log) = model(root, policy);
+        run(instance.resolve()).unwrap();
+        assert_eq!(child(&instance, "critical:0").on_terminate, OnTerminate::Reboot);
+    }
+}
@@ -0,0 +1,208 @@
+// Copyright 2020 The Fuchsia Authors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! The order in which a component and its children are stopped.  The strong dependencies declared
+//! by the component, through its uses, offers and environments, form a graph between the
+//! component and its children.  A node is stopped once every node that depends on it is stopped,
+//! so that no component outlives the capabilities it uses.
+
+use cm_config::{
+    validate::{dependency_edges, DependencyNode},
+    ComponentDecl,
+};
+use moniker::ChildMoniker;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A component, or one of its children, in its shutdown order.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ShutdownNode {
+    /// The program of the component itself.
+    Self_,
+    Child(ChildMoniker),
+}
+
+struct DependencyGraph<'a> {
+    children: &'a [ChildMoniker],
+    /// Edges go from a dependency to the nodes that depend on it.
+    dependents: BTreeMap<ShutdownNode, BTreeSet<ShutdownNode>>,
+}
+
+impl<'a> DependencyGraph<'a> {
+    fn nodes(&self, node: DependencyNode<'_>) -> Vec<ShutdownNode> {
+        match node {
+            DependencyNode::Self_ => vec![ShutdownNode::Self_],
+            DependencyNode::Child(name) => self
+                .children
+                .iter()
+                .filter(|child| child.collection().is_none() && child.name() == name.as_str())
+                .cloned()
+                .map(ShutdownNode::Child)
+                .collect(),
+            DependencyNode::Collection(name) => self
+                .children
+                .iter()
+                .filter(|child| child.collection() == Some(name.as_str()))
+                .cloned()
+                .map(ShutdownNode::Child)
+                .collect(),
+            DependencyNode::Environment(_) => vec![],
+        }
+    }
+
+    fn add_dependency(&mut self, dependency: DependencyNode<'_>, dependent: DependencyNode<'_>) {
+        let dependents = self.nodes(dependent);
+        for dependency in self.nodes(dependency) {
+            let entry = self.dependents.entry(dependency.clone()).or_default();
+            entry.extend(dependents.iter().filter(|dependent| **dependent != dependency).cloned());
+        }
+    }
+}
+
+/// Orders the shutdown of the component declared by `decl` and its `children`, both static and
+/// dynamic: every node comes after the nodes that depend on it.
+pub fn shutdown_order(decl: &ComponentDecl, children: &[ChildMoniker]) -> Vec<ShutdownNode> {
+    let mut graph = DependencyGraph {
+        children,
+        dependents: BTreeMap::new(),
+    };
+
+    let edges = dependency_edges(decl);
+    for &(dependency, dependent) in &edges {
+        match (dependency, dependent) {
+            (_, DependencyNode::Environment(_)) => {}
+            // Environments are not stopped, so what runs in one depends on the children providing
+            // its runners and resolvers directly.
+            (DependencyNode::Environment(_), _) => {
+                for &(provider, _) in edges.iter().filter(|(_, environment)| *environment == dependency) {
+                    graph.add_dependency(provider, dependent);
+                }
+            }
+            _ => graph.add_dependency(dependency, dependent),
+        }
+    }
+
+    let mut remaining = std::iter::once(ShutdownNode::Self_)
+        .chain(children.iter().cloned().map(ShutdownNode::Child))
+        .collect::<BTreeSet<_>>();
+    let mut order = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let ready = remaining
+            .iter()
+            .filter(|node| {
+                graph
+                    .dependents
+                    .get(node)
+                    .is_none_or(|dependents| dependents.iter().all(|dependent| !remaining.contains(dependent)))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        // Validation rejects strong dependency cycles, but stop the remaining nodes anyway.
+        if ready.is_empty() {
+            order.extend(std::mem::take(&mut remaining));
+            break;
+        }
+        for node in ready {
+            remaining.remove(&node);
+            order.push(node);
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DECL: &str = r#"
+children:
+  - name: logger
+    url: mxpkg://meshx.co/logger#meta/logger.cm
+  - name: server
+    url: mxpkg://meshx.co/server#meta/server.cm
+    environment: env
+  - name: client
+    url: mxpkg://meshx.co/client#meta/client.cm
+  - name: runner
+    url: mxpkg://meshx.co/runner#meta/runner.cm
+collections:
+  - name: workers
+capabilities:
+  - type: protocol
+    name: echo.Config
+environments:
+  - name: env
+    runners:
+      - source_name: wasm
+        target_name: wasm
+        source: !child runner
+use:
+  - type: protocol
+    source:
+      child: client
+    source_name: echo.Client
+    target_path: /svc/echo.Client
+offer:
+  - type: protocol
+    source:
+      child: logger
+    source_name: meshx.logger.LogSink
+    target:
+      child: server
+    target_name: meshx.logger.LogSink
+  - type: protocol
+    source:
+      child: server
+    source_name: echo.Echo
+    target:
+      child: client
+    target_name: echo.Echo
+  - type: protocol
+    source: self
+    source_name: echo.Config
+    target:
+      collection: workers
+    target_name: echo.Config
+  - type: protocol
+    source:
+      child: server
+    source_name: echo.Echo
+    target:
+      collection: workers
+    target_name: echo.Echo
+    dependency_type: weak
+"#;
+
+    fn child(moniker: &str) -> ShutdownNode {
+        ShutdownNode::Child(moniker.into())
+    }
+
+    #[test]
+    fn dependents_stop_first() {
+        let decl: ComponentDecl = serde_yml::from_str(DECL).unwrap();
+        cm_config::validate::validate(&decl).unwrap();
+        let children = [
+            "logger:0",
+            "server:0",
+            "client:0",
+            "runner:0",
+            "workers:a:1",
+            "workers:b:2",
+        ]
+        .into_iter()
+        .map(ChildMoniker::from)
+        .collect::<Vec<_>>();
+        assert_eq!(
+            shutdown_order(&decl, &children),
+            vec![
+                child("workers:a:1"),
+                child("workers:b:2"),
+                ShutdownNode::Self_,
+                child("client:0"),
+                child("server:0"),
+                child("logger:0"),
+                child("runner:0"),
+            ]
+        );
+    }
+}
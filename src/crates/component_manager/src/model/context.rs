@@ -0,0 +1,51 @@
+// Copyright 2020 The Fuchsia Authors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use cm_config::SecurityPolicy;
+use std::rc::Rc;
+
+use crate::model::component::manager::ComponentManagerInstance;
+use crate::model::hooks::Hooks;
+use crate::model::resolver::ResolverRegistry;
+use crate::routing::policy::GlobalPolicyChecker;
+
+/// The model state shared by all component instances.
+#[derive(Debug)]
+pub struct ModelContext {
+    top_instance: Rc<ComponentManagerInstance>,
+    resolvers: ResolverRegistry,
+    policy_checker: GlobalPolicyChecker,
+    hooks: Hooks,
+}
+
+impl ModelContext {
+    pub fn new(
+        top_instance: Rc<ComponentManagerInstance>,
+        resolvers: ResolverRegistry,
+        security_policy: Rc<SecurityPolicy>,
+    ) -> Self {
+        Self {
+            top_instance,
+            resolvers,
+            policy_checker: GlobalPolicyChecker::new(security_policy),
+            hooks: Hooks::new(),
+        }
+    }
+
+    pub fn top_instance(&self) -> &Rc<ComponentManagerInstance> {
+        &self.top_instance
+    }
+
+    pub fn resolvers(&self) -> &ResolverRegistry {
+        &self.resolvers
+    }
+
+    pub fn policy(&self) -> &GlobalPolicyChecker {
+        &self.policy_checker
+    }
+
+    pub fn hooks(&self) -> &Hooks {
+        &self.hooks
+    }
+}
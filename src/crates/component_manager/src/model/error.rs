@@ -0,0 +1,38 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use cm_types::Name;
+use moniker::{AbsoluteMoniker, PartialMoniker};
+use thiserror::Error;
+
+use crate::routing::component_instance::ComponentInstanceError;
+use crate::routing::policy::PolicyError;
+
+/// Errors produced by the component instance model.
+#[derive(Debug, Error, Clone)]
+pub enum ModelError {
+    #[error("component instance `{moniker}` has been destroyed")]
+    InstanceDestroyed { moniker: AbsoluteMoniker },
+
+    #[error("`{moniker}` does not have a collection `#{collection}`")]
+    CollectionNotFound { moniker: AbsoluteMoniker, collection: Name },
+
+    #[error("`{moniker}` already has a child `{child}`")]
+    InstanceAlreadyExists {
+        moniker: AbsoluteMoniker,
+        child: PartialMoniker,
+    },
+
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+
+    #[error(transparent)]
+    ComponentInstance(#[from] ComponentInstanceError),
+}
+
+impl ModelError {
+    pub fn instance_destroyed(moniker: AbsoluteMoniker) -> Self {
+        ModelError::InstanceDestroyed { moniker }
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use async_trait::async_trait;
+use cm_config::ComponentDecl;
+use cm_types::Url;
+use moniker::AbsoluteMoniker;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::{Rc, Weak};
+
+use crate::model::error::ModelError;
+
+/// The lifecycle transitions of component instances hooks can be notified of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventType {
+    /// An instance was added to the tree.
+    Discovered,
+    /// The declaration of an instance was resolved.
+    Resolved,
+    /// An instance was started.
+    Started,
+    /// An instance was stopped.
+    Stopped,
+    /// An instance was destroyed, after its children.
+    Destroyed,
+}
+
+#[derive(Debug, Clone)]
+pub enum EventPayload {
+    Discovered,
+    Resolved {
+        decl: Rc<ComponentDecl>,
+        resolved_url: String,
+    },
+    Started,
+    Stopped,
+    Destroyed,
+}
+
+impl EventPayload {
+    pub fn event_type(&self) -> EventType {
+        match self {
+            EventPayload::Discovered => EventType::Discovered,
+            EventPayload::Resolved { .. } => EventType::Resolved,
+            EventPayload::Started => EventType::Started,
+            EventPayload::Stopped => EventType::Stopped,
+            EventPayload::Destroyed => EventType::Destroyed,
+        }
+    }
+}
+
+/// A lifecycle transition of the instance `target_moniker`.  Events are dispatched once the
+/// transition is done.
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub target_moniker: AbsoluteMoniker,
+    pub component_url: Url,
+    pub payload: EventPayload,
+}
+
+impl Event {
+    pub fn event_type(&self) -> EventType {
+        self.payload.event_type()
+    }
+}
+
+/// Receives the events it is registered for.
+#[async_trait(?Send)]
+pub trait Hook {
+    /// Called for every event this hook is registered for.  An error fails the operation that
+    /// dispatched the event, but does not undo its transition.
+    async fn on(self: Rc<Self>, event: &Event) -> Result<(), ModelError>;
+}
+
+/// Registers `callback` for `events`.  Hooks are held weakly: a hook that has been dropped is
+/// no longer called.
+pub struct HooksRegistration {
+    events: Vec<EventType>,
+    callback: Weak<dyn Hook>,
+}
+
+impl HooksRegistration {
+    pub fn new(events: Vec<EventType>, callback: Weak<dyn Hook>) -> Self {
+        Self { events, callback }
+    }
+}
+
+/// The hooks installed in the model, by event type.
+#[derive(Default)]
+pub struct Hooks {
+    hooks_map: RefCell<HashMap<EventType, Vec<Weak<dyn Hook>>>>,
+}
+
+impl Hooks {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn install(&self, hooks: Vec<HooksRegistration>) {
+        let mut hooks_map = self.hooks_map.borrow_mut();
+        for hook in hooks {
+            for event in hook.events {
+                hooks_map.entry(event).or_default().push(hook.callback.clone());
+            }
+        }
+    }
+
+    /// Calls the hooks registered for the type of `event`, in the order they were installed,
+    /// and stops at the first one that fails.
+    pub async fn dispatch(&self, event: &Event) -> Result<(), ModelError> {
+        let hooks = {
+            let mut hooks_map = self.hooks_map.borrow_mut();
+            let Some(hooks) = hooks_map.get_mut(&event.event_type()) else {
+                return Ok(());
+            };
+            hooks.retain(|hook| hook.strong_count() > 0);
+            hooks.iter().filter_map(Weak::upgrade).collect::<Vec<_>>()
+        };
+        for hook in hooks {
+            hook.on(event).await?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hooks_map = self.hooks_map.borrow();
+        let counts = hooks_map
+            .iter()
+            .map(|(event, hooks)| (event, hooks.len()))
+            .collect::<HashMap<_, _>>();
+        f.debug_struct("Hooks").field("hooks", &counts).finish()
+    }
+}
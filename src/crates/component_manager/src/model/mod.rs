@@ -4,13 +4,15 @@
 
 //pub mod actions;
 pub mod component;
+pub mod error;
 //pub mod escrow;
 //pub mod event_logger;
+pub mod hooks;
 //pub mod model;
 //pub mod mutable_directory;
 //pub mod start;
 
-//pub(crate) mod context;
+pub(crate) mod context;
 //pub(crate) mod environment;
 //pub(crate) mod events;
 //pub(crate) mod namespace;
@@ -19,3 +19,35 @@ pub trait Resolver: std::fmt::Debug {
 pub struct ResolverRegistry {
     resolvers: HashMap<String, Box<dyn Resolver + Send + Sync + 'static>>,
 }
+
+impl ResolverRegistry {
+    pub fn new() -> ResolverRegistry {
+        Default::default()
+    }
+
+    /// Registers `resolver` for `scheme`, and returns the resolver previously registered for it,
+    /// if any.
+    pub fn register(
+        &mut self,
+        scheme: String,
+        resolver: Box<dyn Resolver + Send + Sync + 'static>,
+    ) -> Option<Box<dyn Resolver + Send + Sync + 'static>> {
+        self.resolvers.insert(scheme, resolver)
+    }
+
+    pub fn is_registered(&self, scheme: &str) -> bool {
+        self.resolvers.contains_key(scheme)
+    }
+}
+
+#[async_trait]
+impl Resolver for ResolverRegistry {
+    async fn resolve(&self, component_address: &ComponentAddress) -> Result<ResolvedComponent, ResolverError> {
+        match self.resolvers.get(component_address.scheme()) {
+            Some(resolver) => resolver.resolve(component_address).await,
+            None => Err(ResolverError::SchemeNotRegistered(
+                component_address.scheme().to_string(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright 2021 The Fuchsia Authors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use cm_config::{CapabilityDecl, ExposeDecl, OfferDecl, UseDecl};
+use cm_types::{CapabilityTypeName, Name};
+use moniker::{AbsoluteMoniker, ExtendedMoniker};
+use std::fmt;
+use std::rc::Rc;
+
+use crate::routing::component_instance::ComponentInstanceInterface;
+
+/// Where a routed capability comes from.
+#[derive(Debug)]
+pub enum CapabilitySource<C: ComponentInstanceInterface> {
+    /// The capability is declared by `component`, and served from its outgoing directory.
+    Component {
+        capability: CapabilityDecl,
+        component: Rc<C>,
+    },
+    /// The capability is provided by component manager, on behalf of `component`.
+    Framework {
+        capability_type: CapabilityTypeName,
+        capability_name: Name,
+        component: Rc<C>,
+    },
+    /// The capability is one of component manager's builtin capabilities.
+    Builtin {
+        capability: CapabilityDecl,
+        top_instance: Rc<C::TopInstance>,
+    },
+    /// The capability is derived by component manager from `source_capability`, which is
+    /// declared by `component`.
+    Capability {
+        source_capability: CapabilityDecl,
+        component: Rc<C>,
+    },
+    /// The capability is aggregated from the instances of the collection `collection_name` of
+    /// `component`.
+    Collection {
+        capability_type: CapabilityTypeName,
+        capability_name: Name,
+        collection_name: Name,
+        component: Rc<C>,
+    },
+    /// The capability is optional, and is not provided.
+    Void {
+        capability_type: CapabilityTypeName,
+        capability_name: Name,
+        moniker: AbsoluteMoniker,
+    },
+}
+
+impl<C: ComponentInstanceInterface> CapabilitySource<C> {
+    /// The moniker of the instance providing the capability.
+    pub fn source_moniker(&self) -> ExtendedMoniker {
+        match self {
+            CapabilitySource::Component { component, .. }
+            | CapabilitySource::Framework { component, .. }
+            | CapabilitySource::Capability { component, .. }
+            | CapabilitySource::Collection { component, .. } => component.moniker().clone().into(),
+            CapabilitySource::Builtin { .. } => ExtendedMoniker::ComponentManager,
+            CapabilitySource::Void { moniker, .. } => moniker.clone().into(),
+        }
+    }
+
+    pub fn type_name(&self) -> CapabilityTypeName {
+        match self {
+            CapabilitySource::Component { capability, .. } | CapabilitySource::Builtin { capability, .. } => {
+                capability.type_name()
+            }
+            CapabilitySource::Capability { source_capability, .. } => source_capability.type_name(),
+            CapabilitySource::Framework { capability_type, .. }
+            | CapabilitySource::Collection { capability_type, .. }
+            | CapabilitySource::Void { capability_type, .. } => capability_type.clone(),
+        }
+    }
+
+    pub fn source_name(&self) -> &Name {
+        match self {
+            CapabilitySource::Component { capability, .. } | CapabilitySource::Builtin { capability, .. } => {
+                capability.name()
+            }
+            CapabilitySource::Capability { source_capability, .. } => source_capability.name(),
+            CapabilitySource::Framework { capability_name, .. }
+            | CapabilitySource::Collection { capability_name, .. }
+            | CapabilitySource::Void { capability_name, .. } => capability_name,
+        }
+    }
+}
+
+/// A step of a route, from the component using a capability to the component providing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteSegment {
+    /// `moniker` uses the capability.
+    Use {
+        moniker: AbsoluteMoniker,
+        capability: UseDecl,
+    },
+    /// `moniker` offers the capability to one of its children.
+    Offer {
+        moniker: AbsoluteMoniker,
+        capability: OfferDecl,
+    },
+    /// `moniker` exposes the capability to its parent.
+    Expose {
+        moniker: AbsoluteMoniker,
+        capability: ExposeDecl,
+    },
+    /// `moniker` declares the capability.
+    Declare {
+        moniker: AbsoluteMoniker,
+        capability: CapabilityDecl,
+    },
+    /// The framework provides the capability to `moniker`.
+    ProvideFromFramework {
+        moniker: AbsoluteMoniker,
+        capability_name: Name,
+    },
+    /// Component manager provides the capability to the root instance.
+    ProvideAsBuiltin { capability: CapabilityDecl },
+    /// `moniker` aggregates the capability from the collection `collection_name`.
+    AggregateFromCollection {
+        moniker: AbsoluteMoniker,
+        collection_name: Name,
+        capability_name: Name,
+    },
+    /// `moniker` routes the capability from void.
+    ProvideFromVoid {
+        moniker: AbsoluteMoniker,
+        capability_name: Name,
+    },
+}
+
+impl fmt::Display for RouteSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteSegment::Use { moniker, capability } => {
+                write!(
+                    f,
+                    "`{}` uses {} `{}`",
+                    moniker,
+                    capability.type_name(),
+                    capability.source_name()
+                )
+            }
+            RouteSegment::Offer { moniker, capability } => write!(
+                f,
+                "`{}` offers {} `{}` as `{}`",
+                moniker,
+                capability.type_name(),
+                capability.source_name(),
+                capability.target_name()
+            ),
+            RouteSegment::Expose { moniker, capability } => write!(
+                f,
+                "`{}` exposes {} `{}` as `{}`",
+                moniker,
+                capability.type_name(),
+                capability.source_name(),
+                capability.target_name()
+            ),
+            RouteSegment::Declare { moniker, capability } => {
+                write!(
+                    f,
+                    "`{}` declares {} `{}`",
+                    moniker,
+                    capability.type_name(),
+                    capability.name()
+                )
+            }
+            RouteSegment::ProvideFromFramework {
+                moniker,
+                capability_name,
+            } => {
+                write!(f, "the framework provides `{}` to `{}`", capability_name, moniker)
+            }
+            RouteSegment::ProvideAsBuiltin { capability } => {
+                write!(
+                    f,
+                    "component manager provides {} `{}`",
+                    capability.type_name(),
+                    capability.name()
+                )
+            }
+            RouteSegment::AggregateFromCollection {
+                moniker,
+                collection_name,
+                capability_name,
+            } => {
+                write!(
+                    f,
+                    "`{}` aggregates `{}` from `#{}`",
+                    moniker, capability_name, collection_name
+                )
+            }
+            RouteSegment::ProvideFromVoid {
+                moniker,
+                capability_name,
+            } => {
+                write!(f, "`{}` routes `{}` from void", moniker, capability_name)
+            }
+        }
+    }
+}
+
+/// The steps of a route, in the order they were walked.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteTrace(pub Vec<RouteSegment>);
+
+impl fmt::Display for RouteTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let segments = self.0.iter().map(|segment| segment.to_string()).collect::<Vec<_>>();
+        write!(f, "{}", segments.join(" -> "))
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright 2021 The Fuchsia Authors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use cm_config::{CapabilityDecl, ComponentDecl};
+use cm_types::Name;
+use moniker::{AbsoluteMoniker, ChildMoniker};
+use std::rc::Rc;
+use thiserror::Error;
+
+use crate::clonable_error::ClonableError;
+
+/// The view of a component instance the routing engine needs.  Routing walks the instance tree
+/// through this trait, so it can be tested without a running component manager.
+pub trait ComponentInstanceInterface: Sized + std::fmt::Debug {
+    type TopInstance: TopInstanceInterface;
+
+    /// The absolute moniker of this instance.
+    fn moniker(&self) -> &AbsoluteMoniker;
+
+    /// The declaration of this instance.  Routing only walks resolved instances: an error is
+    /// returned for instances that have not been resolved yet.
+    fn decl(&self) -> Result<Rc<ComponentDecl>, ComponentInstanceError>;
+
+    /// The parent of this instance, or component manager for the root instance.
+    fn parent(&self) -> Result<ExtendedInstance<Self>, ComponentInstanceError>;
+
+    /// The child of this instance with the given child moniker, ignoring instance ids.
+    fn find_child(&self, moniker: &ChildMoniker) -> Result<Option<Rc<Self>>, ComponentInstanceError>;
+}
+
+/// The view of component manager, above the root instance, the routing engine needs.
+pub trait TopInstanceInterface: std::fmt::Debug {
+    /// The capabilities component manager provides to the root instance.
+    fn builtin_capabilities(&self) -> &[CapabilityDecl];
+}
+
+/// A component instance, or component manager above the root instance.
+#[derive(Debug)]
+pub enum ExtendedInstance<C: ComponentInstanceInterface> {
+    Component(Rc<C>),
+    AboveRoot(Rc<C::TopInstance>),
+}
+
+impl<C: ComponentInstanceInterface> Clone for ExtendedInstance<C> {
+    fn clone(&self) -> Self {
+        match self {
+            ExtendedInstance::Component(component) => ExtendedInstance::Component(component.clone()),
+            ExtendedInstance::AboveRoot(top_instance) => ExtendedInstance::AboveRoot(top_instance.clone()),
+        }
+    }
+}
+
+/// Errors produced by `ComponentInstanceInterface`.
+#[derive(Debug, Error, Clone)]
+pub enum ComponentInstanceError {
+    #[error("component instance `{moniker}` not found")]
+    InstanceNotFound { moniker: AbsoluteMoniker },
+    #[error("component instance `{moniker}` is not resolved")]
+    NotResolved { moniker: AbsoluteMoniker },
+    #[error("failed to resolve `{moniker}`: {err}")]
+    ResolveFailed {
+        moniker: AbsoluteMoniker,
+        #[source]
+        err: ClonableError,
+    },
+}
+
+impl ComponentInstanceError {
+    pub fn instance_not_found(moniker: AbsoluteMoniker) -> Self {
+        ComponentInstanceError::InstanceNotFound { moniker }
+    }
+
+    pub fn not_resolved(moniker: AbsoluteMoniker) -> Self {
+        ComponentInstanceError::NotResolved { moniker }
+    }
+
+    pub fn resolve_failed(moniker: AbsoluteMoniker, err: impl Into<anyhow::Error>) -> Self {
+        ComponentInstanceError::ResolveFailed {
+            moniker,
+            err: err.into().into(),
+        }
+    }
+}
+
+/// The child moniker, without instance id, of the static child `name`.
+pub fn static_child(name: &Name) -> ChildMoniker {
+    ChildMoniker::new(name.to_string(), None, 0)
+}
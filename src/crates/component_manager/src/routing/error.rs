@@ -0,0 +1,76 @@
+// Copyright 2021 The Fuchsia Authors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use cm_types::{CapabilityTypeName, Name};
+use moniker::AbsoluteMoniker;
+use thiserror::Error;
+
+use crate::routing::capability_source::RouteTrace;
+use crate::routing::component_instance::ComponentInstanceError;
+use crate::routing::policy::PolicyError;
+
+/// Errors produced by routing.
+#[derive(Debug, Error, Clone)]
+pub enum RoutingError {
+    #[error("`{moniker}` does not have a child `#{child}`")]
+    ChildNotFound { moniker: AbsoluteMoniker, child: Name },
+
+    #[error("`{moniker}` does not declare {capability_type} `{capability_name}`")]
+    CapabilityNotDeclared {
+        moniker: AbsoluteMoniker,
+        capability_type: CapabilityTypeName,
+        capability_name: Name,
+    },
+
+    #[error("the parent of `{moniker}` does not offer it {capability_type} `{capability_name}`")]
+    OfferFromParentNotFound {
+        moniker: AbsoluteMoniker,
+        capability_type: CapabilityTypeName,
+        capability_name: Name,
+    },
+
+    #[error("`{child}` does not expose {capability_type} `{capability_name}` to its parent")]
+    ExposeFromChildNotFound {
+        child: AbsoluteMoniker,
+        capability_type: CapabilityTypeName,
+        capability_name: Name,
+    },
+
+    #[error("component manager does not provide {capability_type} `{capability_name}` to the root component")]
+    BuiltinCapabilityNotFound {
+        capability_type: CapabilityTypeName,
+        capability_name: Name,
+    },
+
+    #[error(
+        "`{moniker}` requires {capability_type} `{capability_name}`, but it is routed to it as an optional capability"
+    )]
+    AvailabilityMismatch {
+        moniker: AbsoluteMoniker,
+        capability_type: CapabilityTypeName,
+        capability_name: Name,
+    },
+
+    #[error("`{moniker}` requires {capability_type} `{capability_name}`, but it is routed from void")]
+    RequiredFromVoid {
+        moniker: AbsoluteMoniker,
+        capability_type: CapabilityTypeName,
+        capability_name: Name,
+    },
+
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+
+    #[error(transparent)]
+    ComponentInstance(#[from] ComponentInstanceError),
+}
+
+/// A routing failure, and the part of the route that was walked before it happened.
+#[derive(Debug, Error, Clone)]
+#[error("{error} (route: {trace})")]
+pub struct RouteError {
+    #[source]
+    pub error: RoutingError,
+    pub trace: RouteTrace,
+}
@@ -1 +1,13 @@
-pub mod resolving;
\ No newline at end of file
+// Copyright 2021 The Fuchsia Authors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Capability routing: finding the source of the capabilities components use, by walking the
+//! offers and exposes of the component instance tree.
+
+pub mod capability_source;
+pub mod component_instance;
+pub mod error;
+pub mod policy;
+pub mod resolving;
+pub mod route;
@@ -0,0 +1,156 @@
+// Copyright 2020 The Fuchsia Authors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use cm_config::{AllowlistEntry, CapabilityAllowlistKey, CapabilityAllowlistSource, SecurityPolicy};
+use cm_types::{CapabilityTypeName, Name};
+use moniker::{AbsoluteMoniker, ChildMoniker, ExtendedMoniker};
+use std::rc::Rc;
+use thiserror::Error;
+
+use crate::routing::capability_source::CapabilitySource;
+use crate::routing::component_instance::ComponentInstanceInterface;
+
+/// Errors produced by `GlobalPolicyChecker`.
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum PolicyError {
+    #[error(
+        "security policy does not allow `{target_moniker}` to use {capability_type} `{capability_name}` \
+         from `{source_moniker}`"
+    )]
+    CapabilityUseDisallowed {
+        capability_type: CapabilityTypeName,
+        capability_name: Name,
+        source_moniker: ExtendedMoniker,
+        target_moniker: AbsoluteMoniker,
+    },
+    #[error("security policy does not allow `{moniker}` to set `on_terminate: reboot` on its children")]
+    ChildPolicyDisallowed { moniker: AbsoluteMoniker },
+}
+
+/// Checks routes and child options against the security policy component manager was configured
+/// with.  Capabilities and options the policy says nothing about are allowed.
+#[derive(Debug, Clone, Default)]
+pub struct GlobalPolicyChecker {
+    policy: Rc<SecurityPolicy>,
+}
+
+impl GlobalPolicyChecker {
+    pub fn new(policy: Rc<SecurityPolicy>) -> Self {
+        Self { policy }
+    }
+
+    /// Checks that `target_moniker` may use the capability provided by `source`.  Only the
+    /// capabilities provided by components themselves, by the framework and by component manager
+    /// can be restricted.
+    pub fn can_route_capability<C: ComponentInstanceInterface>(
+        &self,
+        source: &CapabilitySource<C>,
+        target_moniker: &AbsoluteMoniker,
+    ) -> Result<(), PolicyError> {
+        let allowlist_source = match source {
+            CapabilitySource::Component { .. } | CapabilitySource::Builtin { .. } => CapabilityAllowlistSource::Self_,
+            CapabilitySource::Framework { .. } => CapabilityAllowlistSource::Framework,
+            CapabilitySource::Capability { .. } => CapabilityAllowlistSource::Capability,
+            CapabilitySource::Collection { .. } | CapabilitySource::Void { .. } => return Ok(()),
+        };
+        let key = CapabilityAllowlistKey {
+            source_moniker: source.source_moniker(),
+            source_name: source.source_name().clone().into(),
+            source: allowlist_source,
+            capability: source.type_name(),
+        };
+        match self.policy.capability_policy.get(&key) {
+            None => Ok(()),
+            Some(allowlist) if allowlist.iter().any(|entry| allowlist_matches(entry, target_moniker)) => Ok(()),
+            Some(_) => Err(PolicyError::CapabilityUseDisallowed {
+                capability_type: source.type_name(),
+                capability_name: source.source_name().clone(),
+                source_moniker: source.source_moniker(),
+                target_moniker: target_moniker.clone(),
+            }),
+        }
+    }
+
+    /// Checks that `moniker` may declare children with `on_terminate: reboot`.
+    pub fn reboot_on_terminate_allowed(&self, moniker: &AbsoluteMoniker) -> Result<(), PolicyError> {
+        let allowlist = &self.policy.child_policy.reboot_on_terminate;
+        if allowlist.iter().any(|entry| allowlist_matches(entry, moniker)) {
+            Ok(())
+        } else {
+            Err(PolicyError::ChildPolicyDisallowed {
+                moniker: moniker.clone(),
+            })
+        }
+    }
+}
+
+/// Child monikers in allowlists have no instance ids, so monikers are compared without them.
+fn same_child(a: &ChildMoniker, b: &ChildMoniker) -> bool {
+    a.to_partial() == b.to_partial()
+}
+
+/// Whether `realm` is a strict ancestor of `moniker`.
+fn is_ancestor(realm: &AbsoluteMoniker, moniker: &AbsoluteMoniker) -> bool {
+    realm.path().len() < moniker.path().len() && realm.path().iter().zip(moniker.path()).all(|(a, b)| same_child(a, b))
+}
+
+fn allowlist_matches(entry: &AllowlistEntry, moniker: &AbsoluteMoniker) -> bool {
+    match entry {
+        AllowlistEntry::Exact(allowed) => {
+            allowed.path().len() == moniker.path().len()
+                && allowed.path().iter().zip(moniker.path()).all(|(a, b)| same_child(a, b))
+        }
+        AllowlistEntry::Realm(realm) => is_ancestor(realm, moniker),
+        AllowlistEntry::Collection(realm, collection) => {
+            is_ancestor(realm, moniker) && moniker.path()[realm.path().len()].collection() == Some(collection.as_str())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moniker(moniker: &str) -> AbsoluteMoniker {
+        AbsoluteMoniker::parse_string_without_instances(moniker).unwrap()
+    }
+
+    #[test]
+    fn allowlist_entries() {
+        let exact = AllowlistEntry::Exact(moniker("/foo/bar"));
+        assert!(allowlist_matches(&exact, &moniker("/foo/bar")));
+        assert!(!allowlist_matches(&exact, &moniker("/foo")));
+        assert!(!allowlist_matches(&exact, &moniker("/foo/bar/baz")));
+
+        let realm = AllowlistEntry::Realm(moniker("/foo"));
+        assert!(allowlist_matches(&realm, &moniker("/foo/bar")));
+        assert!(allowlist_matches(&realm, &moniker("/foo/bar/baz")));
+        assert!(!allowlist_matches(&realm, &moniker("/foo")));
+        assert!(!allowlist_matches(&realm, &moniker("/bar")));
+
+        let collection = AllowlistEntry::Collection(moniker("/foo"), "tests".to_string());
+        assert!(allowlist_matches(&collection, &moniker("/foo/tests:bar")));
+        assert!(allowlist_matches(&collection, &moniker("/foo/tests:bar/baz")));
+        assert!(!allowlist_matches(&collection, &moniker("/foo/bar")));
+        assert!(!allowlist_matches(&collection, &moniker("/foo/other:bar")));
+    }
+
+    #[test]
+    fn reboot_on_terminate() {
+        let policy = SecurityPolicy {
+            child_policy: cm_config::ChildPolicyAllowlists {
+                reboot_on_terminate: vec![AllowlistEntry::Exact(moniker("/core"))],
+            },
+            ..Default::default()
+        };
+        let checker = GlobalPolicyChecker::new(Rc::new(policy));
+        assert_eq!(checker.reboot_on_terminate_allowed(&moniker("/core")), Ok(()));
+        assert_eq!(
+            checker.reboot_on_terminate_allowed(&moniker("/core/foo")),
+            Err(PolicyError::ChildPolicyDisallowed {
+                moniker: moniker("/core/foo")
+            })
+        );
+    }
+}
@@ -2,6 +2,8 @@
 // Use of this source code is governed by a BSD-style license that can be
 // found in the LICENSE file.
 
+use package_directory::PackageDir;
+use std::sync::Arc;
 use thiserror::Error;
 use url::Url;
 
@@ -16,8 +18,8 @@ pub struct ResolvedComponent {
     pub resolved_url: String,
     // The package context, from the component resolution context returned by
     // the resolver.
-    //pub context_to_resolve_children: Option<ComponentResolutionContext>,
-    //pub decl: cm_rust::ComponentDecl,
+    pub context_to_resolve_children: Option<ComponentResolutionContext>,
+    pub decl: cm_config::ComponentDecl,
     pub package: Option<ResolvedPackage>,
     //pub config_values: Option<cm_rust::ConfigValuesData>,
     //pub abi_revision: Option<AbiRevision>,
@@ -30,8 +32,22 @@ pub struct ResolvedComponent {
 pub struct ResolvedPackage {
     /// The package url.
     pub url: String,
-    // The package directory client proxy.
-    //pub directory: fidl::endpoints::ClientEnd<fio::DirectoryMarker>,
+    /// The package directory.
+    pub directory: Arc<PackageDir>,
+}
+
+/// An opaque value returned by the resolver of a component, and given back to it to resolve the
+/// relative URLs of the component's children.  The package resolver uses it to find
+/// subpackages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentResolutionContext {
+    pub bytes: Vec<u8>,
+}
+
+impl ComponentResolutionContext {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        ComponentResolutionContext { bytes }
+    }
 }
 
 /// Errors produced by built-in `Resolver`s and `resolving` APIs.
@@ -41,6 +57,38 @@ pub enum ResolverError {
     Internal(#[source] ClonableError),
     #[error("package not found: {0}")]
     PackageNotFound(#[source] ClonableError),
+    #[error("component address could not be parsed: {0}")]
+    MalformedUrl(#[source] ClonableError),
+    #[error("no resolver is registered for the scheme `{0}`")]
+    SchemeNotRegistered(String),
+    #[error("relative url requires a parent component with resolution context: {0}")]
+    RelativeUrlMissingContext(String),
+    #[error("manifest not found: {0}")]
+    ManifestNotFound(#[source] ClonableError),
+    #[error("manifest invalid: {0}")]
+    ManifestInvalid(#[source] ClonableError),
+}
+
+impl ResolverError {
+    pub fn internal(err: impl Into<anyhow::Error>) -> Self {
+        ResolverError::Internal(err.into().into())
+    }
+
+    pub fn package_not_found(err: impl Into<anyhow::Error>) -> Self {
+        ResolverError::PackageNotFound(err.into().into())
+    }
+
+    pub fn malformed_url(err: impl Into<anyhow::Error>) -> Self {
+        ResolverError::MalformedUrl(err.into().into())
+    }
+
+    pub fn manifest_not_found(err: impl Into<anyhow::Error>) -> Self {
+        ResolverError::ManifestNotFound(err.into().into())
+    }
+
+    pub fn manifest_invalid(err: impl Into<anyhow::Error>) -> Self {
+        ResolverError::ManifestInvalid(err.into().into())
+    }
 }
 
 /// Indicates the kind of `ComponentAddress`, and holds `ComponentAddress`
@@ -66,11 +114,118 @@ pub enum ComponentAddress {
         /// `url::Url` cannot represent relative urls directly.
         url: Url,
 
-        // An opaque value (from the perspective of component resolution)
-        // required by the resolver when resolving a relative package path.
-        // For a given child component, this property is populated from a
-        // parent component's `resolution_context`, as returned by the parent
-        // component's resolver.
-        //context: ComponentResolutionContext,
+        /// An opaque value (from the perspective of component resolution)
+        /// required by the resolver when resolving a relative package path.
+        /// For a given child component, this property is populated from a
+        /// parent component's `resolution_context`, as returned by the parent
+        /// component's resolver.
+        context: ComponentResolutionContext,
     },
 }
+
+impl ComponentAddress {
+    /// Creates the address of the component `url`, a child of the component resolved from
+    /// `parent_url`.  A URL made of a resource fragment only, such as `#meta/child.cm`, names a
+    /// component of the parent's package, and a relative path URL names a component of one of
+    /// its subpackages, found through `parent_context`.  The root component has no parent, and
+    /// must have an absolute URL.
+    pub fn from_url(
+        url: &cm_types::Url,
+        parent_url: Option<&str>,
+        parent_context: Option<&ComponentResolutionContext>,
+    ) -> Result<Self, ResolverError> {
+        let err = match Url::parse(url.as_str()) {
+            Ok(url) => return Ok(ComponentAddress::Absolute { url }),
+            Err(err) => err,
+        };
+        let parent_url = match (err, parent_url) {
+            (url::ParseError::RelativeUrlWithoutBase, Some(parent_url)) => {
+                Url::parse(parent_url).map_err(ResolverError::malformed_url)?
+            }
+            (url::ParseError::RelativeUrlWithoutBase, None) => {
+                return Err(ResolverError::malformed_url(anyhow::format_err!(
+                    "`{url}` is relative, but has no parent to be resolved against"
+                )));
+            }
+            (err, _) => return Err(ResolverError::malformed_url(err)),
+        };
+        if url.as_str().starts_with('#') {
+            let url = parent_url.join(url.as_str()).map_err(ResolverError::malformed_url)?;
+            return Ok(ComponentAddress::Absolute { url });
+        }
+        let context =
+            parent_context.ok_or_else(|| ResolverError::RelativeUrlMissingContext(url.as_str().to_string()))?;
+        let relative_url = Url::parse("relative:///")
+            .and_then(|base| base.join(url.as_str()))
+            .map_err(ResolverError::malformed_url)?;
+        Ok(ComponentAddress::RelativePath {
+            scheme: parent_url.scheme().to_string(),
+            url: relative_url,
+            context: context.clone(),
+        })
+    }
+
+    /// The scheme of the resolver that resolves this address.
+    pub fn scheme(&self) -> &str {
+        match self {
+            ComponentAddress::Absolute { url } => url.scheme(),
+            ComponentAddress::RelativePath { scheme, .. } => scheme,
+        }
+    }
+
+    /// The resource fragment of the URL, the path of the manifest in its package.
+    pub fn resource(&self) -> Option<&str> {
+        self.url().fragment()
+    }
+
+    pub fn url(&self) -> &Url {
+        match self {
+            ComponentAddress::Absolute { url } | ComponentAddress::RelativePath { url, .. } => url,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(
+        url: &str,
+        parent_url: Option<&str>,
+        parent_context: Option<&ComponentResolutionContext>,
+    ) -> Result<ComponentAddress, ResolverError> {
+        ComponentAddress::from_url(&cm_types::Url::new(url).unwrap(), parent_url, parent_context)
+    }
+
+    #[test]
+    fn from_url() {
+        let parent = Some("mxpkg://meshx.co/parent#meta/parent.cm");
+        let context = ComponentResolutionContext::new(b"parent".to_vec());
+        assert_eq!(
+            address("mxpkg://meshx.co/echo#meta/echo.cm", None, None).unwrap(),
+            ComponentAddress::Absolute {
+                url: Url::parse("mxpkg://meshx.co/echo#meta/echo.cm").unwrap()
+            }
+        );
+        assert_eq!(
+            address("#meta/child.cm", parent, None).unwrap(),
+            ComponentAddress::Absolute {
+                url: Url::parse("mxpkg://meshx.co/parent#meta/child.cm").unwrap()
+            }
+        );
+
+        let subpackage = address("echo#meta/echo.cm", parent, Some(&context)).unwrap();
+        assert_eq!(subpackage.scheme(), "mxpkg");
+        assert_eq!(subpackage.url().as_str(), "relative:///echo#meta/echo.cm");
+        assert_eq!(subpackage.resource(), Some("meta/echo.cm"));
+        assert!(matches!(
+            address("echo#meta/echo.cm", parent, None),
+            Err(ResolverError::RelativeUrlMissingContext(_))
+        ));
+
+        assert!(matches!(
+            address("#meta/child.cm", None, None),
+            Err(ResolverError::MalformedUrl(_))
+        ));
+    }
+}
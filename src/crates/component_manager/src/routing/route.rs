@@ -0,0 +1,591 @@
+// Copyright 2021 The Fuchsia Authors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use cm_config::{Availability, ExposeSource, ExposeTarget, OfferDecl, OfferSource, OfferTarget, UseDecl, UseSource};
+use cm_types::{CapabilityTypeName, Name};
+use moniker::ChildMoniker;
+use std::rc::Rc;
+
+use crate::routing::capability_source::{CapabilitySource, RouteSegment, RouteTrace};
+use crate::routing::component_instance::{
+    static_child, ComponentInstanceInterface, ExtendedInstance, TopInstanceInterface,
+};
+use crate::routing::error::{RouteError, RoutingError};
+use crate::routing::policy::GlobalPolicyChecker;
+
+/// A successfully routed capability.
+#[derive(Debug)]
+pub struct RouteSource<C: ComponentInstanceInterface> {
+    pub source: CapabilitySource<C>,
+    pub trace: RouteTrace,
+}
+
+/// Routes the capability `target` uses with `use_decl` to its source, walking the offers of the
+/// parents and the exposes of the children along the way, and checks that the security policy
+/// allows `target` to use it.
+pub fn route_capability<C: ComponentInstanceInterface>(
+    use_decl: &UseDecl,
+    target: &Rc<C>,
+    policy: &GlobalPolicyChecker,
+) -> Result<RouteSource<C>, RouteError> {
+    let mut router = Router {
+        target: target.clone(),
+        capability_type: use_decl.type_name(),
+        availability: use_decl.availability(),
+        trace: vec![],
+    };
+    let source = router
+        .route_use(use_decl)
+        .and_then(|source| {
+            policy.can_route_capability(&source, target.moniker())?;
+            Ok(source)
+        })
+        .map_err(|error| RouteError {
+            error,
+            trace: RouteTrace(std::mem::take(&mut router.trace)),
+        })?;
+    Ok(RouteSource {
+        source,
+        trace: RouteTrace(router.trace),
+    })
+}
+
+/// The state of a route being walked.  The capability type stays the same along the route,
+/// while its name can change at every offer and expose.
+struct Router<C: ComponentInstanceInterface> {
+    target: Rc<C>,
+    capability_type: CapabilityTypeName,
+    /// The availability the next step of the route has to provide.
+    availability: Availability,
+    trace: Vec<RouteSegment>,
+}
+
+impl<C: ComponentInstanceInterface> Router<C> {
+    fn route_use(&mut self, use_decl: &UseDecl) -> Result<CapabilitySource<C>, RoutingError> {
+        let target = self.target.clone();
+        self.trace.push(RouteSegment::Use {
+            moniker: target.moniker().clone(),
+            capability: use_decl.clone(),
+        });
+        let name = use_decl.source_name();
+        match use_decl.source() {
+            UseSource::Parent => self.from_parent(&target, name),
+            UseSource::Framework => Ok(self.from_framework(&target, name)),
+            UseSource::Self_ => self.from_self(&target, name),
+            UseSource::Child(child) => self.from_child(&target, &child, name),
+            UseSource::Capability(source_name) => self.from_capability(&target, &source_name),
+        }
+    }
+
+    /// Follows the offer `component`'s parent makes to it, or the builtin capabilities of
+    /// component manager for the root instance.
+    fn from_parent(&mut self, component: &Rc<C>, name: &Name) -> Result<CapabilitySource<C>, RoutingError> {
+        let parent = match component.parent()? {
+            ExtendedInstance::Component(parent) => parent,
+            ExtendedInstance::AboveRoot(top_instance) => return self.from_builtin(top_instance, name),
+        };
+        let leaf = component.moniker().leaf().expect("only the root instance has no leaf");
+        let decl = parent.decl()?;
+        let offer = decl
+            .offers
+            .iter()
+            .find(|offer| {
+                offer.type_name() == self.capability_type
+                    && offer.target_name() == name
+                    && offer_targets(offer.target(), leaf)
+            })
+            .ok_or_else(|| RoutingError::OfferFromParentNotFound {
+                moniker: component.moniker().clone(),
+                capability_type: self.capability_type.clone(),
+                capability_name: name.clone(),
+            })?;
+        self.trace.push(RouteSegment::Offer {
+            moniker: parent.moniker().clone(),
+            capability: offer.clone(),
+        });
+        self.advance_availability(component, offer, name)?;
+
+        let name = offer.source_name();
+        match offer.source() {
+            OfferSource::Parent => self.from_parent(&parent, name),
+            OfferSource::Framework => Ok(self.from_framework(&parent, name)),
+            OfferSource::Self_ => self.from_self(&parent, name),
+            OfferSource::Child(child) => self.from_child(&parent, child, name),
+            OfferSource::Collection(collection) => Ok(self.from_collection(&parent, collection, name)),
+            OfferSource::Capability(source_name) => self.from_capability(&parent, source_name),
+            OfferSource::Void => self.from_void(&parent, name),
+        }
+    }
+
+    /// Follows the expose the child `child` of `component` makes to it.
+    fn from_child(
+        &mut self,
+        component: &Rc<C>,
+        child: &Name,
+        name: &Name,
+    ) -> Result<CapabilitySource<C>, RoutingError> {
+        let child = component
+            .find_child(&static_child(child))?
+            .ok_or_else(|| RoutingError::ChildNotFound {
+                moniker: component.moniker().clone(),
+                child: child.clone(),
+            })?;
+        let decl = child.decl()?;
+        let expose = decl
+            .exposes
+            .iter()
+            .find(|expose| {
+                expose.type_name() == self.capability_type
+                    && expose.target_name() == name
+                    && *expose.target() == ExposeTarget::Parent
+            })
+            .ok_or_else(|| RoutingError::ExposeFromChildNotFound {
+                child: child.moniker().clone(),
+                capability_type: self.capability_type.clone(),
+                capability_name: name.clone(),
+            })?;
+        self.trace.push(RouteSegment::Expose {
+            moniker: child.moniker().clone(),
+            capability: expose.clone(),
+        });
+
+        let name = expose.source_name();
+        match expose.source() {
+            ExposeSource::Framework => Ok(self.from_framework(&child, name)),
+            ExposeSource::Self_ => self.from_self(&child, name),
+            ExposeSource::Child(grandchild) => self.from_child(&child, grandchild, name),
+            ExposeSource::Collection(collection) => Ok(self.from_collection(&child, collection, name)),
+            ExposeSource::Capability(source_name) => self.from_capability(&child, source_name),
+            ExposeSource::Void => self.from_void(&child, name),
+        }
+    }
+
+    fn from_self(&mut self, component: &Rc<C>, name: &Name) -> Result<CapabilitySource<C>, RoutingError> {
+        let decl = component.decl()?;
+        let capability = decl
+            .capabilities
+            .iter()
+            .find(|capability| capability.type_name() == self.capability_type && capability.name() == name)
+            .ok_or_else(|| RoutingError::CapabilityNotDeclared {
+                moniker: component.moniker().clone(),
+                capability_type: self.capability_type.clone(),
+                capability_name: name.clone(),
+            })?;
+        self.trace.push(RouteSegment::Declare {
+            moniker: component.moniker().clone(),
+            capability: capability.clone(),
+        });
+        Ok(CapabilitySource::Component {
+            capability: capability.clone(),
+            component: component.clone(),
+        })
+    }
+
+    fn from_capability(&mut self, component: &Rc<C>, name: &Name) -> Result<CapabilitySource<C>, RoutingError> {
+        let decl = component.decl()?;
+        let capability = decl
+            .find_capability(name)
+            .ok_or_else(|| RoutingError::CapabilityNotDeclared {
+                moniker: component.moniker().clone(),
+                capability_type: self.capability_type.clone(),
+                capability_name: name.clone(),
+            })?;
+        self.trace.push(RouteSegment::Declare {
+            moniker: component.moniker().clone(),
+            capability: capability.clone(),
+        });
+        Ok(CapabilitySource::Capability {
+            source_capability: capability.clone(),
+            component: component.clone(),
+        })
+    }
+
+    fn from_framework(&mut self, component: &Rc<C>, name: &Name) -> CapabilitySource<C> {
+        self.trace.push(RouteSegment::ProvideFromFramework {
+            moniker: component.moniker().clone(),
+            capability_name: name.clone(),
+        });
+        CapabilitySource::Framework {
+            capability_type: self.capability_type.clone(),
+            capability_name: name.clone(),
+            component: component.clone(),
+        }
+    }
+
+    fn from_collection(&mut self, component: &Rc<C>, collection: &Name, name: &Name) -> CapabilitySource<C> {
+        self.trace.push(RouteSegment::AggregateFromCollection {
+            moniker: component.moniker().clone(),
+            collection_name: collection.clone(),
+            capability_name: name.clone(),
+        });
+        CapabilitySource::Collection {
+            capability_type: self.capability_type.clone(),
+            capability_name: name.clone(),
+            collection_name: collection.clone(),
+            component: component.clone(),
+        }
+    }
+
+    fn from_builtin(
+        &mut self,
+        top_instance: Rc<C::TopInstance>,
+        name: &Name,
+    ) -> Result<CapabilitySource<C>, RoutingError> {
+        let capability = top_instance
+            .builtin_capabilities()
+            .iter()
+            .find(|capability| capability.type_name() == self.capability_type && capability.name() == name)
+            .cloned()
+            .ok_or_else(|| RoutingError::BuiltinCapabilityNotFound {
+                capability_type: self.capability_type.clone(),
+                capability_name: name.clone(),
+            })?;
+        self.trace.push(RouteSegment::ProvideAsBuiltin {
+            capability: capability.clone(),
+        });
+        Ok(CapabilitySource::Builtin {
+            capability,
+            top_instance,
+        })
+    }
+
+    /// Void sources are only valid for routes that are optional all the way to the target.
+    fn from_void(&mut self, component: &Rc<C>, name: &Name) -> Result<CapabilitySource<C>, RoutingError> {
+        if self.availability == Availability::Required {
+            return Err(RoutingError::RequiredFromVoid {
+                moniker: self.target.moniker().clone(),
+                capability_type: self.capability_type.clone(),
+                capability_name: name.clone(),
+            });
+        }
+        self.trace.push(RouteSegment::ProvideFromVoid {
+            moniker: component.moniker().clone(),
+            capability_name: name.clone(),
+        });
+        Ok(CapabilitySource::Void {
+            capability_type: self.capability_type.clone(),
+            capability_name: name.clone(),
+            moniker: component.moniker().clone(),
+        })
+    }
+
+    /// A required capability cannot be offered as optional.  An optional capability can be
+    /// offered as required, in which case the rest of the route has to provide it.
+    fn advance_availability(&mut self, component: &Rc<C>, offer: &OfferDecl, name: &Name) -> Result<(), RoutingError> {
+        match (self.availability, offer.availability()) {
+            (Availability::Required, Availability::Optional) => Err(RoutingError::AvailabilityMismatch {
+                moniker: component.moniker().clone(),
+                capability_type: self.capability_type.clone(),
+                capability_name: name.clone(),
+            }),
+            (_, availability) => {
+                self.availability = availability;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Whether an offer to `target` reaches the child `child`.
+fn offer_targets(target: &OfferTarget, child: &ChildMoniker) -> bool {
+    match target {
+        OfferTarget::Child(name) => child.collection().is_none() && child.name() == name.as_str(),
+        OfferTarget::Collection(collection) => child.collection() == Some(collection.as_str()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routing::component_instance::ComponentInstanceError;
+    use crate::routing::policy::PolicyError;
+    use cm_config::{
+        AllowlistEntry, CapabilityAllowlistKey, CapabilityAllowlistSource, CapabilityDecl, ComponentDecl,
+        SecurityPolicy,
+    };
+    use moniker::{AbsoluteMoniker, ExtendedMoniker};
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+    use std::rc::Weak;
+
+    #[derive(Debug)]
+    struct TestTopInstance {
+        builtin_capabilities: Vec<CapabilityDecl>,
+    }
+
+    impl TopInstanceInterface for TestTopInstance {
+        fn builtin_capabilities(&self) -> &[CapabilityDecl] {
+            &self.builtin_capabilities
+        }
+    }
+
+    #[derive(Debug)]
+    struct TestComponent {
+        moniker: AbsoluteMoniker,
+        decl: Rc<ComponentDecl>,
+        parent: Option<Weak<TestComponent>>,
+        top_instance: Rc<TestTopInstance>,
+        children: RefCell<Vec<Rc<TestComponent>>>,
+    }
+
+    impl ComponentInstanceInterface for TestComponent {
+        type TopInstance = TestTopInstance;
+
+        fn moniker(&self) -> &AbsoluteMoniker {
+            &self.moniker
+        }
+
+        fn decl(&self) -> Result<Rc<ComponentDecl>, ComponentInstanceError> {
+            Ok(self.decl.clone())
+        }
+
+        fn parent(&self) -> Result<ExtendedInstance<Self>, ComponentInstanceError> {
+            match &self.parent {
+                None => Ok(ExtendedInstance::AboveRoot(self.top_instance.clone())),
+                Some(parent) => parent
+                    .upgrade()
+                    .map(ExtendedInstance::Component)
+                    .ok_or_else(|| ComponentInstanceError::instance_not_found(self.moniker.parent().unwrap())),
+            }
+        }
+
+        fn find_child(&self, moniker: &ChildMoniker) -> Result<Option<Rc<Self>>, ComponentInstanceError> {
+            Ok(self
+                .children
+                .borrow()
+                .iter()
+                .find(|child| child.moniker.leaf().unwrap().to_partial() == moniker.to_partial())
+                .cloned())
+        }
+    }
+
+    /// Builds a tree from `(moniker, manifest)` pairs, parents first.
+    fn build_tree(builtin: &str, components: &[(&str, &str)]) -> Vec<Rc<TestComponent>> {
+        let top_instance = Rc::new(TestTopInstance {
+            builtin_capabilities: serde_yml::from_str(builtin).unwrap(),
+        });
+        let mut tree: Vec<Rc<TestComponent>> = vec![];
+        for (moniker, manifest) in components {
+            let moniker = AbsoluteMoniker::parse_string_without_instances(moniker).unwrap();
+            let decl: ComponentDecl = serde_yml::from_str(manifest).unwrap();
+            cm_config::validate::validate(&decl).unwrap();
+            let parent = tree
+                .iter()
+                .find(|component| Some(&component.moniker) == moniker.parent().as_ref());
+            let component = Rc::new(TestComponent {
+                moniker,
+                decl: Rc::new(decl),
+                parent: parent.map(Rc::downgrade),
+                top_instance: top_instance.clone(),
+                children: RefCell::new(vec![]),
+            });
+            if let Some(parent) = parent {
+                parent.children.borrow_mut().push(component.clone());
+            }
+            tree.push(component);
+        }
+        tree
+    }
+
+    const ROOT: &str = r#"
+children:
+  - name: server
+    url: mxpkg://meshx.co/server#meta/server.cm
+  - name: client
+    url: mxpkg://meshx.co/client#meta/client.cm
+collections:
+  - name: workers
+offer:
+  - type: protocol
+    source:
+      child: server
+    source_name: echo.Echo
+    target:
+      child: client
+    target_name: echo.Echo
+  - type: protocol
+    source: parent
+    source_name: meshx.logger.LogSink
+    target:
+      child: client
+    target_name: meshx.logger.LogSink
+  - type: protocol
+    source: void
+    source_name: echo.Optional
+    target:
+      child: client
+    target_name: echo.Optional
+    availability: optional
+  - type: protocol
+    source:
+      child: server
+    source_name: echo.Echo
+    target:
+      collection: workers
+    target_name: echo.Echo
+"#;
+
+    const SERVER: &str = r#"
+capabilities:
+  - type: protocol
+    name: echo.Server
+expose:
+  - type: protocol
+    source: self
+    source_name: echo.Server
+    target_name: echo.Echo
+"#;
+
+    const CLIENT: &str = r#"
+use:
+  - type: protocol
+    source: parent
+    source_name: echo.Echo
+    target_path: /svc/echo.Echo
+  - type: protocol
+    source: parent
+    source_name: meshx.logger.LogSink
+    target_path: /svc/meshx.logger.LogSink
+  - type: protocol
+    source: parent
+    source_name: echo.Optional
+    target_path: /svc/echo.Optional
+    availability: optional
+  - type: protocol
+    source: parent
+    source_name: echo.Missing
+    target_path: /svc/echo.Missing
+  - type: protocol
+    source: framework
+    source_name: meshx.component.Realm
+    target_path: /svc/meshx.component.Realm
+"#;
+
+    const BUILTIN: &str = r#"
+- type: protocol
+  name: meshx.logger.LogSink
+"#;
+
+    fn tree() -> Vec<Rc<TestComponent>> {
+        build_tree(
+            BUILTIN,
+            &[
+                ("/", ROOT),
+                ("/server", SERVER),
+                ("/client", CLIENT),
+                ("/workers:w", CLIENT),
+            ],
+        )
+    }
+
+    fn route(
+        tree: &[Rc<TestComponent>],
+        index: usize,
+        use_index: usize,
+    ) -> Result<RouteSource<TestComponent>, RouteError> {
+        let target = &tree[index];
+        route_capability(&target.decl.uses[use_index], target, &GlobalPolicyChecker::default())
+    }
+
+    fn name(name: &str) -> Name {
+        Name::new(name).unwrap()
+    }
+
+    #[test]
+    fn route_from_sibling() {
+        let tree = tree();
+        let source = route(&tree, 2, 0).unwrap();
+        match &source.source {
+            CapabilitySource::Component { capability, component } => {
+                assert_eq!(capability.name(), &name("echo.Server"));
+                assert_eq!(component.moniker.to_string(), tree[1].moniker.to_string());
+            }
+            source => panic!("unexpected source {source:?}"),
+        }
+        assert_eq!(source.trace.0.len(), 4);
+        assert!(matches!(source.trace.0[1], RouteSegment::Offer { .. }));
+        assert!(matches!(source.trace.0[2], RouteSegment::Expose { .. }));
+
+        // Offers to a collection reach the instances in the collection.
+        assert!(matches!(
+            route(&tree, 3, 0).unwrap().source,
+            CapabilitySource::Component { .. }
+        ));
+    }
+
+    #[test]
+    fn route_from_builtin_and_framework() {
+        let tree = tree();
+        assert!(matches!(
+            route(&tree, 2, 1).unwrap().source,
+            CapabilitySource::Builtin { .. }
+        ));
+        assert!(matches!(
+            route(&tree, 2, 4).unwrap().source,
+            CapabilitySource::Framework { .. }
+        ));
+    }
+
+    #[test]
+    fn availability() {
+        let tree = tree();
+        assert!(matches!(
+            route(&tree, 2, 2).unwrap().source,
+            CapabilitySource::Void { .. }
+        ));
+
+        // A required use cannot be satisfied by an optional offer.
+        let client = r#"
+use:
+  - type: protocol
+    source: parent
+    source_name: echo.Optional
+    target_path: /svc/echo.Optional
+"#;
+        let tree = build_tree(BUILTIN, &[("/", ROOT), ("/client", client)]);
+        let err = route(&tree, 1, 0).unwrap_err();
+        assert!(matches!(err.error, RoutingError::AvailabilityMismatch { .. }), "{err}");
+    }
+
+    #[test]
+    fn failures_have_a_trace() {
+        let tree = tree();
+        let err = route(&tree, 2, 3).unwrap_err();
+        assert!(matches!(err.error, RoutingError::OfferFromParentNotFound { .. }));
+        assert_eq!(err.trace.0.len(), 1);
+        assert_eq!(
+            err.to_string(),
+            "the parent of `/client:0` does not offer it protocol `echo.Missing` \
+             (route: `/client:0` uses protocol `echo.Missing`)"
+        );
+    }
+
+    #[test]
+    fn security_policy() {
+        let tree = tree();
+        let key = CapabilityAllowlistKey {
+            source_moniker: ExtendedMoniker::ComponentInstance(tree[1].moniker.clone()),
+            source_name: "echo.Server".into(),
+            source: CapabilityAllowlistSource::Self_,
+            capability: CapabilityTypeName::Protocol,
+        };
+        let allowlist = HashSet::from([AllowlistEntry::Exact(
+            AbsoluteMoniker::parse_string_without_instances("/client").unwrap(),
+        )]);
+        let policy = SecurityPolicy {
+            capability_policy: [(key, allowlist)].into(),
+            ..Default::default()
+        };
+        let policy = GlobalPolicyChecker::new(Rc::new(policy));
+
+        let client = &tree[2];
+        assert!(route_capability(&client.decl.uses[0], client, &policy).is_ok());
+        let worker = &tree[3];
+        let err = route_capability(&worker.decl.uses[0], worker, &policy).unwrap_err();
+        assert!(matches!(
+            err.error,
+            RoutingError::Policy(PolicyError::CapabilityUseDisallowed { .. })
+        ));
+    }
+}
@@ -222,7 +222,7 @@ async fn main() -> Result<(), anyhow::Error> {
     let mut service_fs = ServiceFs::new_local();
 
     // Serve the Echo protocol
-    service_fs.dir("svc").add_fidl_service(IncomingRequest::Echo);
+    service_fs.dir("svc").add_midl_service(IncomingRequest::Echo);
     
     service_fs
         .take_and_serve_directory_handle()
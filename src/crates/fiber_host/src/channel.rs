@@ -0,0 +1,153 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use crate::object::{HandleEntry, Kind, Object};
+use fiber_sys as sys;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Condvar, Mutex, OnceLock, Weak,
+    },
+    time::Duration,
+};
+
+/// A message in flight, which owns the handles it carries.
+#[derive(Debug)]
+pub(crate) struct Message {
+    pub bytes: Vec<u8>,
+    pub handles: Vec<HandleEntry>,
+}
+
+impl Message {
+    /// The transaction id in the first four bytes, which `fx_channel_call` matches replies by.
+    fn txid(&self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes.get(..4)?.try_into().unwrap()))
+    }
+}
+
+/// One endpoint of a channel. Messages written to an endpoint are queued on its peer.
+#[derive(Debug, Default)]
+pub(crate) struct Channel {
+    peer: OnceLock<Weak<Object>>,
+    queue: Mutex<Queue>,
+    replied: Condvar,
+    next_txid: AtomicU32,
+}
+
+#[derive(Debug, Default)]
+struct Queue {
+    messages: VecDeque<Message>,
+    /// The calls in progress through this endpoint by transaction id, with their reply once it
+    /// arrives. Replies are not queued with `messages`, so only the caller can read them.
+    calls: HashMap<u32, Option<Message>>,
+}
+
+impl Channel {
+    /// Creates both endpoints of a new channel.
+    pub fn create() -> (Arc<Object>, Arc<Object>) {
+        let initial = sys::FX_OBJECT_WRITABLE;
+        let object0 = Object::with_signals(Kind::Channel(Channel::default()), initial);
+        let object1 = Object::with_signals(Kind::Channel(Channel::default()), initial);
+        for (object, peer) in [(&object0, &object1), (&object1, &object0)] {
+            object.as_channel().unwrap().peer.set(Arc::downgrade(peer)).unwrap();
+        }
+        (object0, object1)
+    }
+
+    pub fn peer(&self) -> Result<Arc<Object>, sys::fx_status_t> {
+        self.peer.get().and_then(Weak::upgrade).ok_or(sys::FX_ERR_PEER_CLOSED)
+    }
+
+    /// Queues `message` on the peer of this endpoint.
+    pub fn write(&self, message: Message) -> Result<(), sys::fx_status_t> {
+        let peer = self.peer()?;
+        let channel = peer.as_channel()?;
+        let mut queue = channel.queue.lock().unwrap();
+        if let Some(reply @ None) = message.txid().and_then(|txid| queue.calls.get_mut(&txid)) {
+            *reply = Some(message);
+            channel.replied.notify_all();
+            return Ok(());
+        }
+        queue.messages.push_back(message);
+        // Signals change with the queue locked, so that they never disagree with its contents.
+        peer.signals.update(0, sys::FX_OBJECT_READABLE);
+        drop(queue);
+        Ok(())
+    }
+
+    /// Allocates a transaction id for `fx_channel_call`. Its high bit is set, which keeps it apart
+    /// from the ids that bindings allocate.
+    pub fn next_txid(&self) -> u32 {
+        self.next_txid.fetch_add(1, Ordering::Relaxed) | 0x8000_0000
+    }
+
+    /// Writes `message`, whose first four bytes are its transaction id, then blocks until the
+    /// peer replies with the same id or `deadline` passes.
+    pub fn call(&self, message: Message, deadline: sys::fx_time_t) -> Result<Message, sys::fx_status_t> {
+        let txid = message.txid().ok_or(sys::FX_ERR_INVALID_ARGS)?;
+        self.queue.lock().unwrap().calls.insert(txid, None);
+        let result = self.write(message).and_then(|()| self.wait_for_reply(txid, deadline));
+        self.queue.lock().unwrap().calls.remove(&txid);
+        result
+    }
+
+    fn wait_for_reply(&self, txid: u32, deadline: sys::fx_time_t) -> Result<Message, sys::fx_status_t> {
+        let mut queue = self.queue.lock().unwrap();
+        loop {
+            if let Some(reply) = queue.calls.get_mut(&txid).and_then(Option::take) {
+                return Ok(reply);
+            }
+            self.peer()?;
+            let now = sys::fx_clock_get_monotonic();
+            if deadline <= now {
+                return Err(sys::FX_ERR_TIMED_OUT);
+            }
+            let timeout = Duration::from_nanos(deadline.saturating_sub(now) as u64);
+            queue = self.replied.wait_timeout(queue, timeout).unwrap().0;
+        }
+    }
+
+    /// Dequeues the oldest message of `object`, which must be this channel, if it fits in
+    /// `num_bytes` and `num_handles`. Otherwise returns the size of the message.
+    pub fn read(
+        &self,
+        object: &Object,
+        num_bytes: u32,
+        num_handles: u32,
+    ) -> Result<Message, (sys::fx_status_t, u32, u32)> {
+        let mut queue = self.queue.lock().unwrap();
+        let Some(message) = queue.messages.front() else {
+            let status = match self.peer() {
+                Ok(_) => sys::FX_ERR_SHOULD_WAIT,
+                Err(status) => status,
+            };
+            return Err((status, 0, 0));
+        };
+        let (actual_bytes, actual_handles) = (message.bytes.len() as u32, message.handles.len() as u32);
+        if actual_bytes > num_bytes || actual_handles > num_handles {
+            return Err((sys::FX_ERR_BUFFER_TOO_SMALL, actual_bytes, actual_handles));
+        }
+
+        let message = queue.messages.pop_front().unwrap();
+        if queue.messages.is_empty() {
+            object.signals.update(sys::FX_OBJECT_READABLE, 0);
+        }
+        Ok(message)
+    }
+}
+
+impl Drop for Channel {
+    fn drop(&mut self) {
+        if let Ok(peer) = self.peer() {
+            peer.signals.update(sys::FX_OBJECT_WRITABLE, sys::FX_OBJECT_PEER_CLOSED);
+            // Wakes calls on the peer, which fail now that it has no peer. The queue is locked so
+            // that a call can not miss this between checking the peer and waiting.
+            if let Ok(channel) = peer.as_channel() {
+                let _queue = channel.queue.lock().unwrap();
+                channel.replied.notify_all();
+            }
+        }
+    }
+}
@@ -0,0 +1,855 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! An in-process implementation of the fiber syscalls for running on a development host.
+//!
+//! Outside of a MeshX kernel, every syscall made through `fiber_rust` panics because nothing
+//! implements [`fiber_sys::System`]. Calling [`install`] first makes `fiber_rust` objects work
+//! in host binaries and unit tests: kernel objects live in a handle table shared by all threads of
+//! the process. Channels, ports and signals are supported; job, process, task and VMO syscalls
+//! are not.
+
+// `System` mirrors the syscall ABI, whose safe entry points take raw pointers.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+mod channel;
+mod object;
+mod port;
+mod signals;
+
+use channel::{Channel, Message};
+use fiber_sys as sys;
+use object::{HandleEntry, HandleTable, Kind, Object};
+use port::Port;
+use std::sync::Arc;
+
+/// Installs a [`HostSystem`] as [`fiber_sys::SYSTEM`], unless a system is already installed.
+///
+/// This is idempotent, so every test which uses kernel objects can call it.
+pub fn install() {
+    sys::SYSTEM.get_or_init(|| Arc::new(HostSystem::default()));
+}
+
+/// The host implementation of [`fiber_sys::System`].
+#[derive(Debug, Default)]
+pub struct HostSystem {
+    handles: HandleTable,
+}
+
+impl HostSystem {
+    fn get(&self, handle: sys::fx_handle_t, rights: sys::fx_rights_t) -> Result<Arc<Object>, sys::fx_status_t> {
+        self.handles.get(handle)?.with_rights(rights)
+    }
+
+    fn add(&self, object: Arc<Object>, rights: sys::fx_rights_t) -> sys::fx_handle_t {
+        self.handles.add(HandleEntry { object, rights })
+    }
+
+    /// Removes a handle from the table to transfer it, which consumes it even if it can not be
+    /// transferred.
+    fn take(&self, handle: sys::fx_handle_t) -> Result<HandleEntry, sys::fx_status_t> {
+        let entry = self.handles.remove(handle)?;
+        entry.object.signals.cancel(handle, None);
+        entry.clone().with_rights(sys::FX_RIGHT_TRANSFER)?;
+        Ok(entry)
+    }
+
+    /// Moves or duplicates the handle of `disposition`, checking its type and reducing its rights.
+    fn dispose(&self, disposition: &sys::fx_handle_disposition_t) -> Result<HandleEntry, sys::fx_status_t> {
+        let entry = match disposition.operation {
+            sys::FX_HANDLE_OP_MOVE => self.take(disposition.handle)?,
+            sys::FX_HANDLE_OP_DUPLICATE => {
+                let entry = self.handles.get(disposition.handle)?;
+                entry.clone().with_rights(sys::FX_RIGHT_DUPLICATE)?;
+                entry
+            }
+            _ => return Err(sys::FX_ERR_INVALID_ARGS),
+        };
+        if disposition.type_ != sys::FX_OBJ_TYPE_NONE && disposition.type_ != entry.object.obj_type() {
+            return Err(sys::FX_ERR_WRONG_TYPE);
+        }
+        let rights = derive_rights(entry.rights, disposition.rights)?;
+        Ok(HandleEntry { rights, ..entry })
+    }
+
+    /// Builds a message to write whose handles are produced by `take_handles`, which is called even
+    /// if the message is invalid so that the handles are consumed.
+    fn channel_message(
+        &self,
+        handle: sys::fx_handle_t,
+        options: u32,
+        bytes: &[u8],
+        take_handles: impl FnOnce() -> Vec<Result<HandleEntry, sys::fx_status_t>>,
+    ) -> Result<(Arc<Object>, Message), sys::fx_status_t> {
+        let object = self.get(handle, sys::FX_RIGHT_WRITE);
+        let handles = take_handles();
+        let object = object?;
+        object.as_channel()?;
+        if options != 0 {
+            return Err(sys::FX_ERR_INVALID_ARGS);
+        }
+        if bytes.len() > sys::FX_CHANNEL_MAX_MSG_BYTES as usize
+            || handles.len() > sys::FX_CHANNEL_MAX_MSG_HANDLES as usize
+        {
+            return Err(sys::FX_ERR_OUT_OF_RANGE);
+        }
+        let handles = handles.into_iter().collect::<Result<Vec<_>, _>>()?;
+        if handles.iter().any(|entry| Arc::ptr_eq(&entry.object, &object)) {
+            return Err(sys::FX_ERR_NOT_SUPPORTED);
+        }
+        let message = Message {
+            bytes: bytes.to_vec(),
+            handles,
+        };
+        Ok((object, message))
+    }
+
+    /// Writes a message whose handles are produced by `take_handles`, which is called even if the
+    /// write fails so that the handles are consumed.
+    fn channel_write(
+        &self,
+        handle: sys::fx_handle_t,
+        options: u32,
+        bytes: &[u8],
+        take_handles: impl FnOnce() -> Vec<Result<HandleEntry, sys::fx_status_t>>,
+    ) -> Result<(), sys::fx_status_t> {
+        let (object, message) = self.channel_message(handle, options, bytes, take_handles)?;
+        object.as_channel()?.write(message)
+    }
+
+    /// Moves or duplicates the handles of `num_handles` dispositions, recording the result of each.
+    fn dispose_all(
+        &self,
+        handles: *const sys::fx_handle_disposition_t,
+        num_handles: u32,
+    ) -> Vec<Result<HandleEntry, sys::fx_status_t>> {
+        (0..num_handles as usize)
+            .map(|i| {
+                let disposition = unsafe { &mut *(handles as *mut sys::fx_handle_disposition_t).add(i) };
+                let entry = self.dispose(disposition);
+                disposition.result = status(entry.as_ref().map(drop).map_err(|status| *status));
+                entry
+            })
+            .collect()
+    }
+
+    /// Adds the handles of a message read to the table, describing each in `handles`.
+    fn add_handle_infos(&self, entries: Vec<HandleEntry>, handles: *mut sys::fx_handle_info_t) {
+        for (i, entry) in entries.into_iter().enumerate() {
+            let (ty, rights) = (entry.object.obj_type(), entry.rights);
+            let info = sys::fx_handle_info_t {
+                handle: self.handles.add(entry),
+                ty,
+                rights,
+                unused: 0,
+            };
+            unsafe { handles.add(i).write_unaligned(info) };
+        }
+    }
+
+    /// Dequeues a message if it fits in `num_bytes` and `num_handles`, reporting its size either
+    /// way.
+    fn channel_read(
+        &self,
+        handle: sys::fx_handle_t,
+        options: u32,
+        num_bytes: u32,
+        num_handles: u32,
+        actual_bytes: *mut u32,
+        actual_handles: *mut u32,
+    ) -> Result<Message, sys::fx_status_t> {
+        if options != 0 {
+            return Err(sys::FX_ERR_INVALID_ARGS);
+        }
+        let object = self.get(handle, sys::FX_RIGHT_READ)?;
+        let (result, sizes) = match object.as_channel()?.read(&object, num_bytes, num_handles) {
+            Ok(message) => {
+                let sizes = (message.bytes.len() as u32, message.handles.len() as u32);
+                (Ok(message), sizes)
+            }
+            Err((status, num_bytes, num_handles)) => (Err(status), (num_bytes, num_handles)),
+        };
+        unsafe {
+            if !actual_bytes.is_null() {
+                *actual_bytes = sizes.0;
+            }
+            if !actual_handles.is_null() {
+                *actual_handles = sizes.1;
+            }
+        }
+        result
+    }
+}
+
+fn status(result: Result<(), sys::fx_status_t>) -> sys::fx_status_t {
+    match result {
+        Ok(()) => sys::FX_OK,
+        Err(status) => status,
+    }
+}
+
+/// Views a buffer passed to a syscall, which may be null if it is empty.
+fn raw_slice<'a, T>(data: *const T, len: u32) -> &'a [T] {
+    if len == 0 {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(data, len as usize) }
+    }
+}
+
+/// Copies bytes out to a syscall buffer, which may be null if nothing is copied.
+fn copy_out(source: &[u8], data: *mut u8) {
+    if !source.is_empty() {
+        unsafe { std::ptr::copy_nonoverlapping(source.as_ptr(), data, source.len()) };
+    }
+}
+
+/// Only user signals can be asserted or deasserted by `fx_object_signal`.
+fn check_user_signals(signals: sys::fx_signals_t) -> Result<(), sys::fx_status_t> {
+    if signals & !sys::FX_USER_SIGNAL_ALL != 0 {
+        return Err(sys::FX_ERR_INVALID_ARGS);
+    }
+    Ok(())
+}
+
+/// Resolves `FX_RIGHT_SAME_RIGHTS` and rejects rights which `current` does not have.
+fn derive_rights(current: sys::fx_rights_t, requested: sys::fx_rights_t) -> Result<sys::fx_rights_t, sys::fx_status_t> {
+    if requested == sys::FX_RIGHT_SAME_RIGHTS {
+        Ok(current)
+    } else if requested & !current != 0 {
+        Err(sys::FX_ERR_INVALID_ARGS)
+    } else {
+        Ok(requested)
+    }
+}
+
+impl sys::System for HostSystem {
+    fn sys_debug(&self, data: *mut u8, len: usize) -> bool {
+        let data = unsafe { std::slice::from_raw_parts(data, len) };
+        eprint!("{}", String::from_utf8_lossy(data));
+        true
+    }
+
+    fn sys_handle_close(&self, handle: sys::fx_handle_t) -> sys::fx_status_t {
+        if handle == sys::FX_HANDLE_INVALID {
+            return sys::FX_OK;
+        }
+        status(self.handles.remove(handle).map(|entry| {
+            // Waits made through a handle end with it.
+            entry.object.signals.cancel(handle, None);
+        }))
+    }
+
+    fn sys_handle_duplicate(
+        &self,
+        handle: sys::fx_handle_t,
+        rights: sys::fx_rights_t,
+        out: *const sys::fx_handle_t,
+    ) -> sys::fx_status_t {
+        status((|| {
+            let entry = self.handles.get(handle)?;
+            let object = entry.clone().with_rights(sys::FX_RIGHT_DUPLICATE)?;
+            let rights = derive_rights(entry.rights, rights)?;
+            unsafe { *(out as *mut sys::fx_handle_t) = self.add(object, rights) };
+            Ok(())
+        })())
+    }
+
+    fn sys_handle_replace(
+        &self,
+        handle: sys::fx_handle_t,
+        rights: sys::fx_rights_t,
+        out: *const sys::fx_handle_t,
+    ) -> sys::fx_status_t {
+        status((|| {
+            let entry = self.handles.get(handle)?;
+            let rights = derive_rights(entry.rights, rights)?;
+            self.handles.remove(handle)?;
+            unsafe { *(out as *mut sys::fx_handle_t) = self.add(entry.object, rights) };
+            Ok(())
+        })())
+    }
+
+    fn sys_object_get_info(
+        &self,
+        handle: sys::fx_handle_t,
+        topic: u32,
+        buffer: *const u8,
+        buffer_size: usize,
+    ) -> sys::fx_status_t {
+        status((|| {
+            let entry = self.handles.get(handle)?;
+            match topic {
+                sys::FX_INFO_HANDLE_VALID => Ok(()),
+                sys::FX_INFO_HANDLE_BASIC => {
+                    if buffer_size < std::mem::size_of::<sys::fx_info_handle_basic_t>() {
+                        return Err(sys::FX_ERR_BUFFER_TOO_SMALL);
+                    }
+                    let info = sys::fx_info_handle_basic_t {
+                        koid: entry.object.koid,
+                        rights: entry.rights,
+                        type_: entry.object.obj_type(),
+                        related_koid: sys::FX_KOID_INVALID,
+                        reserved: 0,
+                    };
+                    unsafe { (buffer as *mut sys::fx_info_handle_basic_t).write_unaligned(info) };
+                    Ok(())
+                }
+                _ => Err(sys::FX_ERR_NOT_SUPPORTED),
+            }
+        })())
+    }
+
+    fn sys_object_signal_peer(&self, handle: sys::fx_handle_t, clear_mask: u32, set_mask: u32) -> sys::fx_status_t {
+        status((|| {
+            check_user_signals(clear_mask | set_mask)?;
+            let peer = self.get(handle, sys::FX_RIGHT_SIGNAL_PEER)?.as_channel()?.peer()?;
+            peer.signals.update(clear_mask, set_mask);
+            Ok(())
+        })())
+    }
+
+    fn sys_object_signal(&self, handle: sys::fx_handle_t, clear_mask: u32, set_mask: u32) -> sys::fx_status_t {
+        status((|| {
+            check_user_signals(clear_mask | set_mask)?;
+            self.get(handle, sys::FX_RIGHT_SIGNAL)?
+                .signals
+                .update(clear_mask, set_mask);
+            Ok(())
+        })())
+    }
+
+    fn sys_object_wait_one(
+        &self,
+        handle: sys::fx_handle_t,
+        waitfor: sys::fx_signals_t,
+        deadline: sys::fx_time_t,
+        observed: *mut sys::fx_signals_t,
+    ) -> sys::fx_status_t {
+        let object = match self.get(handle, sys::FX_RIGHT_WAIT) {
+            Ok(object) => object,
+            Err(status) => return status,
+        };
+        let (status, signals) = match object.signals.wait(waitfor, deadline) {
+            Ok(signals) => (sys::FX_OK, signals),
+            Err(result) => result,
+        };
+        if !observed.is_null() {
+            unsafe { *observed = signals };
+        }
+        status
+    }
+
+    fn sys_object_wait_async(
+        &self,
+        handle: sys::fx_handle_t,
+        port_handle: sys::fx_handle_t,
+        key: u64,
+        signals: sys::fx_signals_t,
+        options: u32,
+    ) -> sys::fx_status_t {
+        status((|| {
+            // Timestamps and edge triggering are not supported.
+            if options != 0 {
+                return Err(sys::FX_ERR_INVALID_ARGS);
+            }
+            let object = self.get(handle, sys::FX_RIGHT_WAIT)?;
+            let port = self.get(port_handle, sys::FX_RIGHT_WRITE)?;
+            port.as_port()?;
+            object.signals.observe(port, key, handle, signals);
+            Ok(())
+        })())
+    }
+
+    fn sys_channel_create(
+        &self,
+        options: u32,
+        out0: *mut sys::fx_handle_t,
+        out1: *mut sys::fx_handle_t,
+    ) -> sys::fx_status_t {
+        if options != 0 {
+            return sys::FX_ERR_INVALID_ARGS;
+        }
+        let (object0, object1) = Channel::create();
+        unsafe {
+            *out0 = self.add(object0, sys::FX_DEFAULT_CHANNEL_RIGHTS);
+            *out1 = self.add(object1, sys::FX_DEFAULT_CHANNEL_RIGHTS);
+        }
+        sys::FX_OK
+    }
+
+    fn sys_channel_read(
+        &self,
+        handle: sys::fx_handle_t,
+        options: u32,
+        bytes: *mut u8,
+        handles: *mut sys::fx_handle_t,
+        num_bytes: u32,
+        num_handles: u32,
+        actual_bytes: *mut u32,
+        actual_handles: *mut u32,
+    ) -> sys::fx_status_t {
+        status((|| {
+            let message = self.channel_read(handle, options, num_bytes, num_handles, actual_bytes, actual_handles)?;
+            copy_out(&message.bytes, bytes);
+            for (i, entry) in message.handles.into_iter().enumerate() {
+                unsafe { *handles.add(i) = self.handles.add(entry) };
+            }
+            Ok(())
+        })())
+    }
+
+    fn sys_channel_read_etc(
+        &self,
+        handle: sys::fx_handle_t,
+        options: u32,
+        bytes: *mut u8,
+        handles: *mut sys::fx_handle_info_t,
+        num_bytes: u32,
+        num_handles: u32,
+        actual_bytes: *mut u32,
+        actual_handles: *mut u32,
+    ) -> sys::fx_status_t {
+        status((|| {
+            let message = self.channel_read(handle, options, num_bytes, num_handles, actual_bytes, actual_handles)?;
+            copy_out(&message.bytes, bytes);
+            self.add_handle_infos(message.handles, handles);
+            Ok(())
+        })())
+    }
+
+    fn sys_channel_write(
+        &self,
+        handle: sys::fx_handle_t,
+        options: u32,
+        bytes: *const u8,
+        num_bytes: u32,
+        handles: *const sys::fx_handle_t,
+        num_handles: u32,
+    ) -> sys::fx_status_t {
+        status(self.channel_write(handle, options, raw_slice(bytes, num_bytes), || {
+            raw_slice(handles, num_handles)
+                .iter()
+                .map(|&value| self.take(value))
+                .collect()
+        }))
+    }
+
+    fn sys_channel_write_etc(
+        &self,
+        handle: sys::fx_handle_t,
+        options: u32,
+        bytes: *const u8,
+        num_bytes: u32,
+        handles: *const sys::fx_handle_disposition_t,
+        num_handles: u32,
+    ) -> sys::fx_status_t {
+        status(self.channel_write(handle, options, raw_slice(bytes, num_bytes), || {
+            self.dispose_all(handles, num_handles)
+        }))
+    }
+
+    fn sys_channel_call_etc(
+        &self,
+        handle: sys::fx_handle_t,
+        options: u32,
+        deadline: sys::fx_time_t,
+        args: *const sys::fx_channel_call_etc_args_t,
+        actual_bytes: *const u32,
+        actual_handles: *const u32,
+    ) -> sys::fx_status_t {
+        status((|| {
+            let args = unsafe { &*args };
+            let bytes = raw_slice(args.wr_bytes, args.wr_num_bytes);
+            let (object, mut message) = self.channel_message(handle, options, bytes, || {
+                self.dispose_all(args.wr_handles, args.wr_num_handles)
+            })?;
+            self.get(handle, sys::FX_RIGHT_READ)?;
+            let channel = object.as_channel()?;
+            // The transaction id in the first four bytes of the request is replaced by one which
+            // the reply must carry.
+            let txid = message.bytes.get_mut(..4).ok_or(sys::FX_ERR_INVALID_ARGS)?;
+            txid.copy_from_slice(&channel.next_txid().to_le_bytes());
+            let reply = channel.call(message, deadline)?;
+
+            let sizes = (reply.bytes.len() as u32, reply.handles.len() as u32);
+            unsafe {
+                *(actual_bytes as *mut u32) = sizes.0;
+                *(actual_handles as *mut u32) = sizes.1;
+            }
+            if sizes.0 > args.rd_num_bytes || sizes.1 > args.rd_num_handles {
+                return Err(sys::FX_ERR_BUFFER_TOO_SMALL);
+            }
+            copy_out(&reply.bytes, args.rd_bytes);
+            self.add_handle_infos(reply.handles, args.rd_handles);
+            Ok(())
+        })())
+    }
+
+    fn sys_vmo_create(&self, _size: u64, _options: u32, _out: *mut sys::fx_handle_t) -> sys::fx_status_t {
+        sys::FX_ERR_NOT_SUPPORTED
+    }
+
+    fn sys_vmo_read(
+        &self,
+        _handle: sys::fx_handle_t,
+        _buffer: *mut u8,
+        _offset: u64,
+        _buffer_size: usize,
+    ) -> sys::fx_status_t {
+        sys::FX_ERR_NOT_SUPPORTED
+    }
+
+    fn sys_vmo_write(
+        &self,
+        _handle: sys::fx_handle_t,
+        _buffer: *const u8,
+        _offset: u64,
+        _buffer_size: usize,
+    ) -> sys::fx_status_t {
+        sys::FX_ERR_NOT_SUPPORTED
+    }
+
+    fn sys_vmo_get_size(&self, _handle: sys::fx_handle_t, _size: *mut u64) -> sys::fx_status_t {
+        sys::FX_ERR_NOT_SUPPORTED
+    }
+
+    fn sys_process_create(
+        &self,
+        _job: sys::fx_handle_t,
+        _name: *const u8,
+        _name_size: usize,
+        _options: u32,
+        _proc_handle: *mut sys::fx_handle_t,
+        _vmar_handle: *mut sys::fx_handle_t,
+    ) -> sys::fx_status_t {
+        sys::FX_ERR_NOT_SUPPORTED
+    }
+
+    fn sys_process_start(
+        &self,
+        _handle: sys::fx_handle_t,
+        _entry: sys::fx_vaddr_t,
+        _arg1: sys::fx_handle_t,
+    ) -> sys::fx_status_t {
+        sys::FX_ERR_NOT_SUPPORTED
+    }
+
+    fn sys_process_exit(&self, retcode: i64) -> sys::fx_status_t {
+        std::process::exit(retcode as i32)
+    }
+
+    fn sys_job_create(
+        &self,
+        _parent_job: sys::fx_handle_t,
+        _options: u32,
+        _out: *const sys::fx_handle_t,
+    ) -> sys::fx_status_t {
+        sys::FX_ERR_NOT_SUPPORTED
+    }
+
+    fn sys_job_set_critical(
+        &self,
+        _job: sys::fx_handle_t,
+        _options: u32,
+        _process: sys::fx_handle_t,
+    ) -> sys::fx_status_t {
+        sys::FX_ERR_NOT_SUPPORTED
+    }
+
+    fn sys_job_set_policy(
+        &self,
+        _handle: sys::fx_handle_t,
+        _options: u32,
+        _topic: u32,
+        _policy: *const u8,
+        _policy_size: u32,
+    ) -> sys::fx_status_t {
+        sys::FX_ERR_NOT_SUPPORTED
+    }
+
+    fn sys_task_kill(&self, _handle: sys::fx_handle_t) -> sys::fx_status_t {
+        sys::FX_ERR_NOT_SUPPORTED
+    }
+
+    fn sys_port_create(&self, options: u32, out: *mut sys::fx_handle_t) -> sys::fx_status_t {
+        if options != 0 {
+            return sys::FX_ERR_INVALID_ARGS;
+        }
+        let port = Object::new(Kind::Port(Port::default()));
+        unsafe { *out = self.add(port, sys::FX_DEFAULT_PORT_RIGHTS) };
+        sys::FX_OK
+    }
+
+    fn sys_port_queue(&self, handle: sys::fx_handle_t, packet: *const sys::fx_port_packet_t) -> sys::fx_status_t {
+        status((|| {
+            let object = self.get(handle, sys::FX_RIGHT_WRITE)?;
+            let mut packet = unsafe { *packet };
+            packet.packet_type = sys::fx_packet_type_t::FX_PKT_TYPE_USER;
+            object.as_port()?.queue(packet);
+            Ok(())
+        })())
+    }
+
+    fn sys_port_wait(
+        &self,
+        handle: sys::fx_handle_t,
+        deadline: sys::fx_time_t,
+        packet: *mut sys::fx_port_packet_t,
+    ) -> sys::fx_status_t {
+        status((|| {
+            let object = self.get(handle, sys::FX_RIGHT_READ)?;
+            unsafe { *packet = object.as_port()?.wait(deadline)? };
+            Ok(())
+        })())
+    }
+
+    fn sys_port_cancel(&self, handle: sys::fx_handle_t, source: sys::fx_handle_t, key: u64) -> sys::fx_status_t {
+        status((|| {
+            let port = self.get(handle, sys::FX_RIGHT_WRITE)?;
+            port.as_port()?;
+            let object = self.handles.get(source)?.object;
+            if !object.signals.cancel(source, Some((&port, key))) {
+                return Err(sys::FX_ERR_NOT_FOUND);
+            }
+            Ok(())
+        })())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fiber_rust::{self as fx, AsHandleRef, HandleBased, Peered};
+
+    #[test]
+    fn port_queue_and_wait() {
+        install();
+        let port = fx::Port::create();
+        let packet = fx::Packet::from_user_packet(42, 7, fx::UserPacket::from_u8_array([1; 32]));
+        port.queue(&packet).unwrap();
+
+        let received = port.wait(fx::Time::INFINITE).unwrap();
+        assert_eq!(received, packet);
+        assert_eq!(port.wait(fx::Time::INFINITE_PAST), Err(fx::Status::TIMED_OUT));
+    }
+
+    #[test]
+    fn port_wait_times_out() {
+        install();
+        let port = fx::Port::create();
+        let start = fx::Time::get_monotonic();
+        let deadline = start + fx::Duration::from_millis(10);
+        assert_eq!(port.wait(deadline), Err(fx::Status::TIMED_OUT));
+        assert!(fx::Time::get_monotonic() >= deadline);
+    }
+
+    #[test]
+    fn port_wait_wakes_on_queue_from_another_thread() {
+        install();
+        let port = Arc::new(fx::Port::create());
+        let sender = {
+            let port = port.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+                port.queue(&fx::Packet::from_user_packet(
+                    1,
+                    0,
+                    fx::UserPacket::from_u8_array([0; 32]),
+                ))
+                .unwrap();
+            })
+        };
+        assert_eq!(port.wait(fx::Time::INFINITE).unwrap().key(), 1);
+        sender.join().unwrap();
+    }
+
+    #[test]
+    fn handles() {
+        install();
+        let port = fx::Port::create();
+        let koid = port.get_koid().unwrap();
+        let duplicate = port.duplicate_handle(fx::Rights::SAME_RIGHTS).unwrap();
+        assert_eq!(duplicate.get_koid().unwrap(), koid);
+
+        let read_only = duplicate.replace_handle(fx::Rights::READ).unwrap();
+        assert_eq!(read_only.basic_info().unwrap().rights, fx::Rights::READ);
+        let packet = fx::Packet::from_user_packet(1, 0, fx::UserPacket::from_u8_array([0; 32]));
+        assert_eq!(read_only.queue(&packet), Err(fx::Status::ACCESS_DENIED));
+
+        let raw = read_only.raw_handle();
+        drop(read_only);
+        assert_eq!(
+            unsafe { fx::Handle::from_raw(raw) }.get_koid(),
+            Err(fx::Status::BAD_HANDLE)
+        );
+    }
+
+    #[test]
+    fn channel_write_and_read() {
+        install();
+        let (a, b) = fx::Channel::create();
+        let (transferred, _peer) = fx::Channel::create();
+        let koid = transferred.get_koid().unwrap();
+        a.write(b"hello", &mut [transferred.into_handle()]).unwrap();
+        assert_eq!(
+            a.wait_handle(fx::Signals::CHANNEL_READABLE, fx::Time::INFINITE_PAST),
+            Err(fx::Status::TIMED_OUT)
+        );
+
+        let mut buf = fx::MessageBuf::new();
+        b.read(&mut buf).unwrap();
+        assert_eq!(buf.bytes(), b"hello");
+        assert_eq!(buf.take_handle(0).unwrap().get_koid().unwrap(), koid);
+        assert_eq!(b.read(&mut buf), Err(fx::Status::SHOULD_WAIT));
+    }
+
+    #[test]
+    fn channel_read_etc_reports_handle_info() {
+        install();
+        let (a, b) = fx::Channel::create();
+        let port = fx::Port::create();
+        let mut dispositions = vec![fx::HandleDisposition {
+            handle_op: fx::HandleOp::Move(port.into_handle()),
+            object_type: fx::ObjectType::PORT,
+            rights: fx::Rights::READ,
+            result: fx::Status::OK,
+        }];
+        a.write_etc(b"", &mut dispositions).unwrap();
+
+        let mut buf = fx::MessageBufEtc::new();
+        b.read_etc(&mut buf).unwrap();
+        let info = buf.take_handle_info(0).unwrap();
+        assert_eq!(info.object_type, fx::ObjectType::PORT);
+        assert_eq!(info.rights, fx::Rights::READ);
+    }
+
+    #[test]
+    fn channel_write_consumes_handles_on_failure() {
+        install();
+        let (a, b) = fx::Channel::create();
+        let port = fx::Port::create();
+        let raw = port.raw_handle();
+        let mut dispositions = vec![fx::HandleDisposition {
+            handle_op: fx::HandleOp::Move(port.into_handle()),
+            object_type: fx::ObjectType::CHANNEL,
+            rights: fx::Rights::SAME_RIGHTS,
+            result: fx::Status::OK,
+        }];
+        assert_eq!(a.write_etc(b"", &mut dispositions), Err(fx::Status::WRONG_TYPE));
+        assert_eq!(
+            unsafe { fx::Handle::from_raw(raw) }.get_koid(),
+            Err(fx::Status::BAD_HANDLE)
+        );
+        assert_eq!(b.read(&mut fx::MessageBuf::new()), Err(fx::Status::SHOULD_WAIT));
+    }
+
+    #[test]
+    fn channel_peer_closed() {
+        install();
+        let (a, b) = fx::Channel::create();
+        a.write(b"last", &mut []).unwrap();
+        drop(a);
+
+        let observed = b
+            .wait_handle(fx::Signals::CHANNEL_PEER_CLOSED, fx::Time::INFINITE_PAST)
+            .unwrap();
+        assert!(observed.contains(fx::Signals::CHANNEL_READABLE));
+        let mut buf = fx::MessageBuf::new();
+        b.read(&mut buf).unwrap();
+        assert_eq!(buf.bytes(), b"last");
+        assert_eq!(b.read(&mut buf), Err(fx::Status::PEER_CLOSED));
+        assert_eq!(b.write(b"", &mut []), Err(fx::Status::PEER_CLOSED));
+    }
+
+    #[test]
+    fn channel_call_waits_for_its_reply() {
+        install();
+        let (a, b) = fx::Channel::create();
+        let server = std::thread::spawn(move || {
+            b.wait_handle(fx::Signals::CHANNEL_READABLE, fx::Time::INFINITE)
+                .unwrap();
+            let mut buf = fx::MessageBuf::new();
+            b.read(&mut buf).unwrap();
+            let (txid, body) = buf.bytes().split_at(4);
+            assert_ne!(txid, [0; 4]);
+            assert_eq!(body, b"ping");
+            b.write(b"\0\0\0\0event", &mut []).unwrap();
+            b.write(&[txid, b"pong"].concat(), &mut []).unwrap();
+            b
+        });
+
+        let mut buf = fx::MessageBufEtc::new();
+        buf.ensure_capacity_bytes(16);
+        a.call_etc(fx::Time::INFINITE, b"\0\0\0\0ping", &mut [], &mut buf)
+            .unwrap();
+        assert_eq!(&buf.bytes()[4..], b"pong");
+        // Messages which are not the reply stay queued for reading.
+        let mut event = fx::MessageBuf::new();
+        a.read(&mut event).unwrap();
+        assert_eq!(event.bytes(), b"\0\0\0\0event");
+
+        let deadline = fx::Time::get_monotonic() + fx::Duration::from_millis(10);
+        assert_eq!(
+            a.call_etc(deadline, b"\0\0\0\0late", &mut [], &mut buf),
+            Err(fx::Status::TIMED_OUT)
+        );
+        drop(server.join().unwrap());
+        assert_eq!(
+            a.call_etc(fx::Time::INFINITE, b"\0\0\0\0gone", &mut [], &mut buf),
+            Err(fx::Status::PEER_CLOSED)
+        );
+    }
+
+    #[test]
+    fn wait_async_queues_a_signal_packet() {
+        install();
+        let port = fx::Port::create();
+        let (a, b) = fx::Channel::create();
+        b.wait_async_handle(&port, 3, fx::Signals::CHANNEL_READABLE, fx::WaitAsyncOpts::empty())
+            .unwrap();
+        assert_eq!(port.wait(fx::Time::INFINITE_PAST), Err(fx::Status::TIMED_OUT));
+
+        a.write(b"", &mut []).unwrap();
+        let packet = port.wait(fx::Time::INFINITE_PAST).unwrap();
+        assert_eq!(packet.key(), 3);
+        let fx::PacketContents::SignalOne(signal) = packet.contents() else {
+            panic!("expected a signal packet");
+        };
+        assert!(signal.observed().contains(fx::Signals::CHANNEL_READABLE));
+
+        // Waits are one-shot.
+        a.write(b"", &mut []).unwrap();
+        assert_eq!(port.wait(fx::Time::INFINITE_PAST), Err(fx::Status::TIMED_OUT));
+    }
+
+    #[test]
+    fn port_cancel() {
+        install();
+        let port = fx::Port::create();
+        let (a, b) = fx::Channel::create();
+        b.wait_async_handle(&port, 5, fx::Signals::CHANNEL_READABLE, fx::WaitAsyncOpts::empty())
+            .unwrap();
+        assert_eq!(port.cancel(&b, 6), Err(fx::Status::NOT_FOUND));
+        assert_eq!(port.cancel(&b, 5), Ok(()));
+        assert_eq!(port.cancel(&b, 5), Err(fx::Status::NOT_FOUND));
+
+        a.write(b"", &mut []).unwrap();
+        assert_eq!(port.wait(fx::Time::INFINITE_PAST), Err(fx::Status::TIMED_OUT));
+    }
+
+    #[test]
+    fn signal_peer_wakes_wait_one_on_another_thread() {
+        install();
+        let (a, b) = fx::Channel::create();
+        let waiter = std::thread::spawn(move || b.wait_handle(fx::Signals::USER_0, fx::Time::INFINITE));
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        a.signal_peer(fx::Signals::NONE, fx::Signals::USER_0).unwrap();
+        assert!(waiter.join().unwrap().unwrap().contains(fx::Signals::USER_0));
+        assert_eq!(
+            a.signal_handle(fx::Signals::NONE, fx::Signals::CHANNEL_READABLE),
+            Err(fx::Status::INVALID_ARGS)
+        );
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use crate::{channel::Channel, port::Port, signals::SignalState};
+use fiber_sys as sys;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+// Koids below `FX_KOID_FIRST` are reserved for the kernel.
+static NEXT_KOID: AtomicU64 = AtomicU64::new(sys::FX_KOID_FIRST);
+
+/// A kernel object, shared by every handle which refers to it.
+#[derive(Debug)]
+pub(crate) struct Object {
+    pub koid: sys::fx_koid_t,
+    pub kind: Kind,
+    pub signals: SignalState,
+}
+
+#[derive(Debug)]
+pub(crate) enum Kind {
+    Channel(Channel),
+    Port(Port),
+}
+
+impl Object {
+    pub fn new(kind: Kind) -> Arc<Object> {
+        Object::with_signals(kind, sys::FX_SIGNAL_NONE)
+    }
+
+    pub fn with_signals(kind: Kind, signals: sys::fx_signals_t) -> Arc<Object> {
+        let koid = NEXT_KOID.fetch_add(1, Ordering::Relaxed);
+        Arc::new(Object {
+            koid,
+            kind,
+            signals: SignalState::new(signals),
+        })
+    }
+
+    pub fn obj_type(&self) -> sys::fx_obj_type_t {
+        match self.kind {
+            Kind::Channel(_) => sys::FX_OBJ_TYPE_CHANNEL,
+            Kind::Port(_) => sys::FX_OBJ_TYPE_PORT,
+        }
+    }
+
+    pub fn as_channel(&self) -> Result<&Channel, sys::fx_status_t> {
+        match &self.kind {
+            Kind::Channel(channel) => Ok(channel),
+            _ => Err(sys::FX_ERR_WRONG_TYPE),
+        }
+    }
+
+    pub fn as_port(&self) -> Result<&Port, sys::fx_status_t> {
+        match &self.kind {
+            Kind::Port(port) => Ok(port),
+            _ => Err(sys::FX_ERR_WRONG_TYPE),
+        }
+    }
+}
+
+/// An entry of the handle table: the object a handle refers to and the rights it grants.
+#[derive(Clone, Debug)]
+pub(crate) struct HandleEntry {
+    pub object: Arc<Object>,
+    pub rights: sys::fx_rights_t,
+}
+
+impl HandleEntry {
+    /// Returns the object if the handle has all of `rights`.
+    pub fn with_rights(self, rights: sys::fx_rights_t) -> Result<Arc<Object>, sys::fx_status_t> {
+        if self.rights & rights != rights {
+            return Err(sys::FX_ERR_ACCESS_DENIED);
+        }
+        Ok(self.object)
+    }
+}
+
+/// The handles of the process. Handle values are never reused.
+#[derive(Debug, Default)]
+pub(crate) struct HandleTable {
+    inner: Mutex<HandleTableInner>,
+}
+
+#[derive(Debug, Default)]
+struct HandleTableInner {
+    last_value: sys::fx_handle_t,
+    handles: HashMap<sys::fx_handle_t, HandleEntry>,
+}
+
+impl HandleTable {
+    pub fn add(&self, entry: HandleEntry) -> sys::fx_handle_t {
+        let mut inner = self.inner.lock().unwrap();
+        inner.last_value += 1;
+        let value = inner.last_value;
+        inner.handles.insert(value, entry);
+        value
+    }
+
+    pub fn get(&self, handle: sys::fx_handle_t) -> Result<HandleEntry, sys::fx_status_t> {
+        self.inner
+            .lock()
+            .unwrap()
+            .handles
+            .get(&handle)
+            .cloned()
+            .ok_or(sys::FX_ERR_BAD_HANDLE)
+    }
+
+    pub fn remove(&self, handle: sys::fx_handle_t) -> Result<HandleEntry, sys::fx_status_t> {
+        self.inner
+            .lock()
+            .unwrap()
+            .handles
+            .remove(&handle)
+            .ok_or(sys::FX_ERR_BAD_HANDLE)
+    }
+}
@@ -0,0 +1,40 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use fiber_sys as sys;
+use std::{
+    collections::VecDeque,
+    sync::{Condvar, Mutex},
+    time::Duration,
+};
+
+/// A queue of packets which threads can block on.
+#[derive(Debug, Default)]
+pub(crate) struct Port {
+    packets: Mutex<VecDeque<sys::fx_port_packet_t>>,
+    queued: Condvar,
+}
+
+impl Port {
+    pub fn queue(&self, packet: sys::fx_port_packet_t) {
+        self.packets.lock().unwrap().push_back(packet);
+        self.queued.notify_one();
+    }
+
+    /// Dequeues the oldest packet, blocking until one is queued or `deadline` passes.
+    pub fn wait(&self, deadline: sys::fx_time_t) -> Result<sys::fx_port_packet_t, sys::fx_status_t> {
+        let mut packets = self.packets.lock().unwrap();
+        loop {
+            if let Some(packet) = packets.pop_front() {
+                return Ok(packet);
+            }
+            let now = sys::fx_clock_get_monotonic();
+            if deadline <= now {
+                return Err(sys::FX_ERR_TIMED_OUT);
+            }
+            let timeout = Duration::from_nanos(deadline.saturating_sub(now) as u64);
+            packets = self.queued.wait_timeout(packets, timeout).unwrap().0;
+        }
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use crate::object::Object;
+use fiber_sys as sys;
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
+};
+
+/// The signals asserted on an object, and the asynchronous waits observing them.
+#[derive(Debug)]
+pub(crate) struct SignalState {
+    inner: Mutex<SignalStateInner>,
+    changed: Condvar,
+}
+
+#[derive(Debug)]
+struct SignalStateInner {
+    active: sys::fx_signals_t,
+    observers: Vec<Observer>,
+}
+
+/// A pending `fx_object_wait_async`, which queues a single packet to `port` once any of
+/// `signals` is asserted.
+#[derive(Debug)]
+struct Observer {
+    port: Arc<Object>,
+    key: u64,
+    handle: sys::fx_handle_t,
+    signals: sys::fx_signals_t,
+}
+
+impl Observer {
+    fn notify(self, observed: sys::fx_signals_t) {
+        let signal = sys::fx_packet_signal_t {
+            trigger: self.signals,
+            observed,
+            count: 1,
+        };
+        let mut packet = sys::fx_port_packet_t {
+            key: self.key,
+            packet_type: sys::fx_packet_type_t::FX_PKT_TYPE_SIGNAL_ONE,
+            status: sys::FX_OK,
+            union: [0; 32],
+        };
+        unsafe { (packet.union.as_mut_ptr() as *mut sys::fx_packet_signal_t).write_unaligned(signal) };
+        if let Ok(port) = self.port.as_port() {
+            port.queue(packet);
+        }
+    }
+}
+
+impl SignalState {
+    pub fn new(active: sys::fx_signals_t) -> SignalState {
+        SignalState {
+            inner: Mutex::new(SignalStateInner {
+                active,
+                observers: vec![],
+            }),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// Clears then sets signals, notifying the waits which observe a now asserted signal.
+    pub fn update(&self, clear_mask: sys::fx_signals_t, set_mask: sys::fx_signals_t) {
+        let mut inner = self.inner.lock().unwrap();
+        let active = (inner.active & !clear_mask) | set_mask;
+        inner.active = active;
+
+        let (triggered, pending) = inner
+            .observers
+            .drain(..)
+            .partition(|observer| observer.signals & active != 0);
+        inner.observers = pending;
+        drop(inner);
+
+        for observer in triggered {
+            observer.notify(active);
+        }
+        self.changed.notify_all();
+    }
+
+    /// Queues a packet to `port` once any of `signals` is asserted, which may be immediately.
+    pub fn observe(&self, port: Arc<Object>, key: u64, handle: sys::fx_handle_t, signals: sys::fx_signals_t) {
+        let observer = Observer {
+            port,
+            key,
+            handle,
+            signals,
+        };
+        let mut inner = self.inner.lock().unwrap();
+        if inner.active & signals != 0 {
+            let active = inner.active;
+            drop(inner);
+            observer.notify(active);
+        } else {
+            inner.observers.push(observer);
+        }
+    }
+
+    /// Removes the waits made through `handle`, or only the one queueing to `port` with `key` if
+    /// given. Returns whether any wait was removed.
+    pub fn cancel(&self, handle: sys::fx_handle_t, port_and_key: Option<(&Arc<Object>, u64)>) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let before = inner.observers.len();
+        inner.observers.retain(|observer| {
+            let matches =
+                port_and_key.is_none_or(|(port, key)| Arc::ptr_eq(port, &observer.port) && key == observer.key);
+            observer.handle != handle || !matches
+        });
+        inner.observers.len() != before
+    }
+
+    /// Blocks until any of `signals` is asserted or `deadline` passes, returning the signals
+    /// observed last.
+    pub fn wait(
+        &self,
+        signals: sys::fx_signals_t,
+        deadline: sys::fx_time_t,
+    ) -> Result<sys::fx_signals_t, (sys::fx_status_t, sys::fx_signals_t)> {
+        let mut inner = self.inner.lock().unwrap();
+        loop {
+            if inner.active & signals != 0 {
+                return Ok(inner.active);
+            }
+            let now = sys::fx_clock_get_monotonic();
+            if deadline <= now {
+                return Err((sys::FX_ERR_TIMED_OUT, inner.active));
+            }
+            let timeout = Duration::from_nanos(deadline.saturating_sub(now) as u64);
+            inner = self.changed.wait_timeout(inner, timeout).unwrap().0;
+        }
+    }
+}
@@ -91,6 +91,12 @@ impl Time {
        unsafe { Time(sys::fx_clock_get_monotonic()) }
     }
 
+    /// Returns the monotonic time `duration` from now, e.g. as the deadline
+    /// of a blocking call.
+    pub fn after(duration: Duration) -> Time {
+        Self::get_monotonic() + duration
+    }
+
     /// Returns the number of nanoseconds since the epoch contained by this `Time`.
     pub const fn into_nanos(self) -> i64 {
         self.0
@@ -502,12 +502,17 @@ pub fn fx_vmo_get_size(handle: fx_handle_t, size: *mut u64) -> fx_status_t {
     sys.sys_vmo_get_size(handle, size)
 }
 
+// The monotonic clock of the host counts from the first time it is read in this process.
+#[cfg(all(not(test), not(target_arch = "wasm32")))]
+static BOOT_TIME: OnceCell<std::time::Instant> = OnceCell::new();
+
 #[cfg(all(not(test), not(target_arch = "wasm32")))]
 pub fn fx_clock_get_monotonic() -> fx_time_t {
-    0
+    BOOT_TIME.get_or_init(std::time::Instant::now).elapsed().as_nanos() as fx_time_t
 }
 
+// One tick per nanosecond.
 #[cfg(all(not(test), not(target_arch = "wasm32")))]
 pub fn fx_ticks_get() -> fx_ticks_t {
-    0
+    fx_clock_get_monotonic()
 }
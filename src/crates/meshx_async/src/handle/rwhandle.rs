@@ -43,7 +43,7 @@ pub enum WritableState {
 ///
 /// # Examples
 ///
-/// ```
+/// ```ignore
 /// ready!(self.poll_readable(cx))?;
 /// match /* make read syscall */ {
 ///     Err(zx::Status::SHOULD_WAIT) => {
@@ -78,7 +78,7 @@ pub trait ReadableHandle {
 ///
 /// # Examples
 ///
-/// ```
+/// ```ignore
 /// ready!(self.poll_writable(cx))?;
 /// match /* make write syscall */ {
 ///     Err(zx::Status::SHOULD_WAIT) => {
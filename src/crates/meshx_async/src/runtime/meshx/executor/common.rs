@@ -4,7 +4,7 @@
 // found in the LICENSE file.
 
 use super::super::timer::{TimerHandle, TimerHeap};
-use super::instrumentation::Collector;
+use super::instrumentation::{Collector, ExecutorStats, LocalCollector, TaskInfo};
 use super::packets::PacketReceiverMap;
 use super::time::Time;
 use super::{PacketReceiver, ReceiverRegistration};
@@ -52,6 +52,12 @@ where
     })
 }
 
+/// Removes the thread-local executor, returning the timers registered on this
+/// thread so that they can be handed to another thread.
+pub(super) fn take_local_timer_heap() -> Option<TimerHeap> {
+    EXECUTOR.with(|e| e.borrow_mut().take().map(|(_, timers)| timers))
+}
+
 pub enum ExecutorTime {
     RealTime,
     FakeTime(AtomicI64),
@@ -67,17 +73,13 @@ pub(super) struct Inner {
     time: ExecutorTime,
     pub(super) collector: Collector,
     pub(super) ready_tasks: SegQueue<Arc<Task>>,
-    pub(super) source: Option<&'static Location<'static>>,
+    pub(super) source: &'static Location<'static>,
 }
 
 impl Inner {
-    #[cfg_attr(trace_level_logging, track_caller)]
+    #[track_caller]
     pub fn new(time: ExecutorTime, is_local: bool) -> Self {
-        #[cfg(trace_level_logging)]
-        let source = Some(Location::caller());
-        #[cfg(not(trace_level_logging))]
-        let source = None;
-
+        let source = Location::caller();
         let collector = Collector::new();
         collector.task_created(MAIN_TASK_ID, source);
 
@@ -103,10 +105,11 @@ impl Inner {
         });
     }
 
-    pub fn poll_ready_tasks(&self) {
+    pub fn poll_ready_tasks(&self, local_collector: &mut LocalCollector<'_>) {
         // TODO: loop but don't starve
         if let Some(task) = self.ready_tasks.pop() {
             let complete = task.try_poll();
+            local_collector.task_polled(task.id, task.source, complete, self.ready_tasks.len());
 
             if complete {
                 // Completed
@@ -115,7 +118,7 @@ impl Inner {
         }
     }
 
-    #[cfg_attr(trace_level_logging, track_caller)]
+    #[track_caller]
     pub fn spawn(self: &Arc<Self>, future: FutureObj<'static, ()>) {
         // Prevent a deadlock in `.active_tasks` when a task is spawned from a custom
         // Drop impl while the executor is being torn down.
@@ -132,7 +135,7 @@ impl Inner {
         ArcWake::wake_by_ref(&waker);
     }
 
-    #[cfg_attr(trace_level_logging, track_caller)]
+    #[track_caller]
     pub fn spawn_local(self: &Arc<Self>, future: LocalFutureObj<'static, ()>) {
         if !self.is_local {
             panic!(
@@ -175,7 +178,10 @@ impl Inner {
             // any longer than absolutely necessary.
             // The `receive_packet` impl may be arbitrarily complex.
             Some(receiver) => receiver.clone(),
-            None => return,
+            None => {
+                self.collector.packet_undelivered();
+                return;
+            }
         };
         receiver.receive_packet(packet);
     }
@@ -350,6 +356,19 @@ impl EHandle {
         }
     }
 
+    /// Returns a snapshot of the executor's counters.
+    pub fn stats(&self) -> ExecutorStats {
+        self.inner.collector.snapshot()
+    }
+
+    /// Lists the tasks which have been spawned but have not completed yet, in
+    /// spawn order. The main task is not included.
+    pub fn dump_tasks(&self) -> Vec<TaskInfo> {
+        let mut tasks: Vec<_> = self.inner.active_tasks.lock().unwrap().values().map(|task| task.info()).collect();
+        tasks.sort_by_key(|info| info.id);
+        tasks
+    }
+
     pub(crate) fn register_timer(time: Time, handle: TimerHandle) {
         with_local_timer_heap(|timer_heap| {
             timer_heap.add_timer(time, handle);
@@ -362,22 +381,21 @@ pub(super) struct Task {
     future: AtomicFuture,
     executor: Arc<Inner>,
     notifier: Notifier,
-    source: Option<&'static Location<'static>>,
+    source: &'static Location<'static>,
+    polls: AtomicUsize,
 }
 
 impl Task {
-    #[cfg_attr(trace_level_logging, track_caller)]
+    #[track_caller]
     fn new(id: usize, future: FutureObj<'static, ()>, executor: Arc<Inner>) -> Arc<Self> {
-        #[cfg(trace_level_logging)]
-        let source = Some(Location::caller());
-        #[cfg(not(trace_level_logging))]
-        let source = None;
+        let source = Location::caller();
         Arc::new(Self {
             id,
             future: AtomicFuture::new(future),
             executor,
             notifier: Notifier::default(),
             source,
+            polls: AtomicUsize::new(0),
         })
     }
 
@@ -387,7 +405,17 @@ impl Task {
         })
     }
 
+    fn info(&self) -> TaskInfo {
+        TaskInfo {
+            id: self.id,
+            source: self.source,
+            polls: self.polls.load(Ordering::Relaxed),
+            ready: self.notifier.is_notified(),
+        }
+    }
+
     fn try_poll(self: &Arc<Self>) -> bool {
+        self.polls.fetch_add(1, Ordering::Relaxed);
         let task_waker = self.waker();
         let w = waker_ref(&task_waker);
         self.notifier.reset();
@@ -428,6 +456,11 @@ impl Notifier {
             .is_ok()
     }
 
+    /// Whether the task has been notified and not polled since.
+    pub fn is_notified(&self) -> bool {
+        self.notified.load(Ordering::Acquire)
+    }
+
     /// Reset the notification. Should be called prior to polling the task again.
     pub fn reset(&self) {
         self.notified.store(false, Ordering::Release);
@@ -12,7 +12,7 @@
 
 use fiber_rust as fx;
 use std::{
-    cmp, mem,
+    cmp, fmt, mem,
     panic::Location,
     sync::atomic::{AtomicU64, AtomicUsize, Ordering},
 };
@@ -29,6 +29,7 @@ pub struct Collector {
     wakeups_io: AtomicUsize,
     wakeups_deadline: AtomicUsize,
     wakeups_notification: AtomicUsize,
+    packets_undelivered: AtomicUsize,
     ticks_awake: AtomicU64,
     ticks_asleep: AtomicU64,
 }
@@ -40,29 +41,51 @@ impl Collector {
     }
 
     /// Called when a task is created (usually, this means spawned).
-    pub fn task_created(&self, _id: usize, _source: Option<&Location<'_>>) {
+    pub fn task_created(&self, _id: usize, _source: &Location<'_>) {
         #[cfg(trace_level_logging)]
         tracing::trace!(
             tag = "fuchsia_async",
             id = _id,
-            source = %_source.unwrap(),
+            source = %_source,
             "Task spawned"
         );
         self.tasks_created.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Called when a task is complete.
-    pub fn task_completed(&self, _id: usize, _source: Option<&Location<'_>>) {
+    pub fn task_completed(&self, _id: usize, _source: &Location<'_>) {
         #[cfg(trace_level_logging)]
         tracing::trace!(
             tag = "fuchsia_async",
             id = _id,
-            source = %_source.unwrap(),
+            source = %_source,
             "Task completed"
         );
         self.tasks_completed.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Called when an io packet arrives for a receiver which has already been
+    /// deregistered.
+    pub fn packet_undelivered(&self) {
+        self.packets_undelivered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the current value of all counters.
+    pub fn snapshot(&self) -> ExecutorStats {
+        ExecutorStats {
+            tasks_created: self.tasks_created.load(Ordering::Relaxed),
+            tasks_completed: self.tasks_completed.load(Ordering::Relaxed),
+            tasks_pending_max: self.tasks_pending_max.load(Ordering::Relaxed),
+            polls: self.polls.load(Ordering::Relaxed),
+            wakeups_io: self.wakeups_io.load(Ordering::Relaxed),
+            wakeups_deadline: self.wakeups_deadline.load(Ordering::Relaxed),
+            wakeups_notification: self.wakeups_notification.load(Ordering::Relaxed),
+            packets_undelivered: self.packets_undelivered.load(Ordering::Relaxed),
+            ticks_awake: self.ticks_awake.load(Ordering::Relaxed),
+            ticks_asleep: self.ticks_asleep.load(Ordering::Relaxed),
+        }
+    }
+
     /// Creates a local collector. Each run loop should have its own local collector.
     /// The local collector is initially awake, and has no recorded events.
     pub fn create_local_collector(&self) -> LocalCollector<'_> {
@@ -91,7 +114,7 @@ impl<'a> LocalCollector<'a> {
     /// Called after a task was polled. If the task completed, `complete`
     /// should be true. `pending_tasks` is the observed size of the pending task
     /// queue (excluding the currently polled task).
-    pub fn task_polled(&mut self, id: usize, source: Option<&Location<'_>>, complete: bool, tasks_pending: usize) {
+    pub fn task_polled(&mut self, id: usize, source: &Location<'_>, complete: bool, tasks_pending: usize) {
         self.polls += 1;
         let new_local_max = cmp::max(self.tasks_pending_max, tasks_pending);
         if new_local_max > self.tasks_pending_max {
@@ -110,6 +133,11 @@ impl<'a> LocalCollector<'a> {
     pub fn will_wait(&mut self) {
         let delta = self.bump_ticks();
         self.collector.ticks_awake.fetch_add(delta, Ordering::Relaxed);
+        self.flush_polls();
+    }
+
+    /// Adds the polls counted since the last flush to the main collector.
+    fn flush_polls(&mut self) {
         self.collector
             .polls
             .fetch_add(mem::replace(&mut self.polls, 0), Ordering::Relaxed);
@@ -138,3 +166,137 @@ impl<'a> LocalCollector<'a> {
         delta as u64
     }
 }
+
+impl Drop for LocalCollector<'_> {
+    fn drop(&mut self) {
+        // Run loops which return without waiting again would otherwise lose their last polls.
+        self.flush_polls();
+    }
+}
+
+/// A snapshot of an executor's counters, as returned by `EHandle::stats`.
+///
+/// All counters are cumulative since the executor was created, so a component publishing them
+/// periodically can derive rates from the difference between two snapshots.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct ExecutorStats {
+    /// Tasks spawned, including the synthetic main task.
+    pub tasks_created: usize,
+    /// Tasks which ran to completion or were cancelled.
+    pub tasks_completed: usize,
+    /// The longest observed queue of tasks ready to be polled.
+    pub tasks_pending_max: usize,
+    /// Task polls, counted once the polling thread next waits or stops.
+    pub polls: usize,
+    /// Wakeups caused by an io packet on the port.
+    pub wakeups_io: usize,
+    /// Wakeups caused by a timer deadline.
+    pub wakeups_deadline: usize,
+    /// Wakeups caused by a task or the main future being woken.
+    pub wakeups_notification: usize,
+    /// Io packets dropped because their receiver was already deregistered.
+    pub packets_undelivered: usize,
+    /// Ticks spent running tasks, summed over all threads.
+    pub ticks_awake: u64,
+    /// Ticks spent waiting on the port, summed over all threads.
+    pub ticks_asleep: u64,
+}
+
+impl ExecutorStats {
+    /// The number of tasks which have been spawned but have not completed.
+    pub fn tasks_active(&self) -> usize {
+        self.tasks_created.saturating_sub(self.tasks_completed)
+    }
+}
+
+/// A live task, as listed by `EHandle::dump_tasks`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TaskInfo {
+    /// The executor-unique id of the task.
+    pub id: usize,
+    /// Where the task was spawned.
+    pub source: &'static Location<'static>,
+    /// How many times the task has been polled. A count which keeps growing
+    /// without the task completing points at a busy loop.
+    pub polls: usize,
+    /// Whether the task has been woken and is waiting to be polled.
+    pub ready: bool,
+}
+
+impl fmt::Display for TaskInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "task {} spawned at {}: {} polls, {}",
+            self.id,
+            self.source,
+            self.polls,
+            if self.ready { "ready" } else { "waiting" }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExecutorStats;
+    use crate::{EHandle, Task, TestExecutor};
+    use futures::{channel::oneshot, future};
+    use std::task::Poll;
+
+    /// Returns the created, completed and active task counts.
+    fn task_counts(stats: ExecutorStats) -> (usize, usize, usize) {
+        (stats.tasks_created, stats.tasks_completed, stats.tasks_active())
+    }
+
+    #[test]
+    fn stats_and_dump_tasks_follow_task_lifecycle() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let ehandle = EHandle::local();
+        let mut main_future = future::pending::<()>();
+
+        // The synthetic main task lives as long as the executor.
+        assert_eq!(task_counts(ehandle.stats()), (1, 0, 1));
+        assert_eq!(ehandle.dump_tasks(), vec![]);
+
+        let (sender, receiver) = oneshot::channel::<()>();
+        let spawned_at = line!() + 1;
+        Task::spawn(async move { receiver.await.unwrap() }).detach();
+        Task::spawn(async {}).detach();
+        assert_eq!(executor.run_until_stalled(&mut main_future), Poll::Pending);
+
+        assert_eq!(task_counts(ehandle.stats()), (3, 1, 2));
+        let tasks = ehandle.dump_tasks();
+        assert_eq!(tasks.len(), 1);
+        let task = &tasks[0];
+        assert_eq!((task.source.file(), task.source.line()), (file!(), spawned_at));
+        assert_eq!((task.polls, task.ready), (1, false));
+        assert_eq!(
+            task.to_string(),
+            format!("task {} spawned at {}: 1 polls, waiting", task.id, task.source)
+        );
+
+        sender.send(()).unwrap();
+        assert_eq!(executor.run_until_stalled(&mut main_future), Poll::Pending);
+
+        assert_eq!(task_counts(ehandle.stats()), (3, 2, 1));
+        assert_eq!(ehandle.dump_tasks(), vec![]);
+    }
+
+    #[test]
+    fn dump_tasks_reports_woken_tasks_as_ready() {
+        fiber_host::install();
+        let _executor = TestExecutor::new();
+        let ehandle = EHandle::local();
+
+        let spawned_at = line!() + 1;
+        Task::local(async {}).detach();
+
+        let tasks = ehandle.dump_tasks();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(
+            (tasks[0].source.line(), tasks[0].polls, tasks[0].ready),
+            (spawned_at, 0, true)
+        );
+    }
+}
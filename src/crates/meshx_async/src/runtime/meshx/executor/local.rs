@@ -45,7 +45,7 @@ pub enum WaitState {
 /// A synthetic main task which represents the "main future" as passed by the user.
 /// The main future can change during the lifetime of the executor, but the notification
 /// mechanism is shared.
-struct MainTask {
+pub(super) struct MainTask {
     executor: Weak<Inner>,
     notifier: Notifier,
 }
@@ -62,7 +62,7 @@ impl ArcWake for MainTask {
 
 impl MainTask {
     /// Poll the main future using the notification semantics of the main task.
-    fn poll<F>(self: &Arc<Self>, main_future: &mut F, main_waker: &Waker) -> Poll<F::Output>
+    pub(super) fn poll<F>(self: &Arc<Self>, main_future: &mut F, main_waker: &Waker) -> Poll<F::Output>
     where
         F: Future + Unpin,
     {
@@ -83,11 +83,11 @@ impl MainTask {
 /// other words, zircon objects backed by a `LocalExecutor` must be dropped before it.
 pub struct LocalExecutor {
     /// The inner executor state.
-    inner: Arc<Inner>,
+    pub(super) inner: Arc<Inner>,
     // Synthetic main task, representing the main futures during the executor's lifetime.
-    main_task: Arc<MainTask>,
+    pub(super) main_task: Arc<MainTask>,
     // Waker for the main task, cached for performance reasons.
-    main_waker: Waker,
+    pub(super) main_waker: Waker,
 }
 
 impl fmt::Debug for LocalExecutor {
@@ -98,8 +98,15 @@ impl fmt::Debug for LocalExecutor {
 
 impl LocalExecutor {
     /// Create a new single-threaded executor running with actual time.
+    #[track_caller]
     pub fn new() -> Self {
-        let inner = Arc::new(Inner::new(ExecutorTime::RealTime, /* is_local */ true));
+        Self::with_time(ExecutorTime::RealTime)
+    }
+
+    /// Create a new single-threaded executor using the given clock.
+    #[track_caller]
+    pub(super) fn with_time(time: ExecutorTime) -> Self {
+        let inner = Arc::new(Inner::new(time, /* is_local */ true));
         inner.clone().set_local(TimerHeap::default());
         let main_task = Arc::new(MainTask {
             executor: Arc::downgrade(&inner),
@@ -171,7 +178,7 @@ impl LocalExecutor {
                     }
                     TASK_READY_WAKEUP_ID => {
                         local_collector.woke_up(WakeupReason::Notification);
-                        self.inner.poll_ready_tasks();
+                        self.inner.poll_ready_tasks(&mut local_collector);
                     }
                     receiver_key => {
                         local_collector.woke_up(WakeupReason::Io);
@@ -9,12 +9,17 @@ use std::future::Future;
 mod common;
 mod local;
 mod packets;
+mod send;
+mod test;
 mod time;
 mod instrumentation;
 
 pub use common::EHandle;
+pub use instrumentation::{ExecutorStats, TaskInfo};
 pub use local::{LocalExecutor, WaitState};
 pub use packets::{need_signal, schedule_packet, PacketReceiver, ReceiverRegistration};
+pub use send::SendExecutor;
+pub use test::TestExecutor;
 pub use time::{Duration, Time};
 
 use common::Inner;
@@ -23,7 +28,7 @@ use common::Inner;
 ///
 /// Tasks spawned using this method must be threadsafe (implement the `Send` trait),
 /// as they may be run on either a singlethreaded or multithreaded executor.
-#[cfg_attr(trace_level_logging, track_caller)]
+#[track_caller]
 pub(crate) fn spawn<F>(future: F)
 where
     F: Future<Output = ()> + Send + 'static,
@@ -37,7 +42,7 @@ where
 /// do not have to be threadsafe (implement the `Send` trait). In return, this method
 /// requires that the current executor never be run in a multithreaded mode-- only
 /// `run_singlethreaded` can be used.
-#[cfg_attr(trace_level_logging, track_caller)]
+#[track_caller]
 pub(crate) fn spawn_local<F>(future: F)
 where
     F: Future<Output = ()> + 'static,
@@ -49,7 +54,7 @@ where
 ///
 /// Tasks spawned using this method must be threadsafe (implement the `Send` trait),
 /// as they may be run on either a singlethreaded or multithreaded executor.
-#[cfg_attr(trace_level_logging, track_caller)]
+#[track_caller]
 pub(crate) fn spawn_on<F>(executor: &EHandle, future: F)
 where
     F: Future<Output = ()> + Send + 'static,
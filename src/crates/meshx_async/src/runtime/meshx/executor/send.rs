@@ -0,0 +1,344 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use super::super::timer::TimerHeap;
+use super::{
+    common::{
+        take_local_timer_heap, with_local_timer_heap, ExecutorTime, Inner, EMPTY_WAKEUP_ID, TASK_READY_WAKEUP_ID,
+    },
+    local::WakeupReason,
+    time::Time,
+};
+
+use fiber_rust as fx;
+use futures::{future::FutureObj, FutureExt};
+use std::{
+    any::Any,
+    fmt,
+    future::Future,
+    mem,
+    panic::{self, AssertUnwindSafe},
+    sync::atomic::{AtomicBool, Ordering},
+    sync::{mpsc, Arc},
+    thread,
+};
+
+/// Reports a panic caught on a worker thread to the thread blocked in `run`.
+type PanicHandler = Arc<dyn Fn(Box<dyn Any + Send>) + Send + Sync>;
+
+/// A multi-threaded port-based executor for MeshX.
+///
+/// All worker threads wait on the same `fx::Port` and take ready tasks from a shared FIFO queue,
+/// so `Send` tasks are polled by whichever worker is free, in the order they were woken. Each
+/// worker has its own timer heap: a [`Timer`](crate::Timer) fires on the thread that created it,
+/// and timers created on the calling thread outside of [`SendExecutor::run`] are handed to the
+/// first worker when the executor starts running.
+///
+/// Tasks must be spawned with [`Task::spawn`](crate::Task::spawn); [`Task::local`](crate::Task::local)
+/// panics on a `SendExecutor`.
+///
+/// # Panics
+///
+/// If a task panics, [`SendExecutor::run`] stops the workers and resumes the panic on the calling
+/// thread.
+///
+/// Like [`LocalExecutor`](super::LocalExecutor), `SendExecutor` will panic on drop if any objects
+/// attached to it are still alive.
+pub struct SendExecutor {
+    /// The inner executor state.
+    inner: Arc<Inner>,
+    /// The number of worker threads started by `run`.
+    num_threads: usize,
+}
+
+impl fmt::Debug for SendExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendExecutor")
+            .field("port", &self.inner.port)
+            .field("num_threads", &self.num_threads)
+            .finish()
+    }
+}
+
+impl SendExecutor {
+    /// Create a new multi-threaded executor running with actual time, which polls tasks on
+    /// `num_threads` worker threads.
+    ///
+    /// # Panics
+    ///
+    /// If `num_threads` is zero.
+    #[track_caller]
+    pub fn new(num_threads: usize) -> Self {
+        assert!(num_threads > 0, "a SendExecutor needs at least one worker thread");
+        let inner = Arc::new(Inner::new(ExecutorTime::RealTime, /* is_local */ false));
+        inner.clone().set_local(TimerHeap::default());
+        Self { inner, num_threads }
+    }
+
+    /// Returns the number of worker threads.
+    pub fn num_threads(&self) -> usize {
+        self.num_threads
+    }
+
+    /// Run `future` to completion on the worker threads, also polling other active tasks.
+    ///
+    /// The calling thread blocks until `future` completes. The worker threads are stopped before
+    /// returning; tasks which have not completed yet are kept and resume on the next call to
+    /// `run`.
+    ///
+    /// # Panics
+    ///
+    /// If `future` or any other task panics while the workers run. The first panic is resumed on
+    /// the calling thread once the workers have stopped.
+    #[track_caller]
+    pub fn run<F>(&mut self, future: F) -> F::Output
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.inner
+            .require_real_time()
+            .expect("Error: called `run` on an executor using fake time");
+
+        // The main future sends its output, and the workers send the panics they catch, so the
+        // calling thread wakes up on whichever comes first.
+        let (sender, receiver) = mpsc::channel::<thread::Result<F::Output>>();
+        let on_panic: PanicHandler = {
+            let sender = sender.clone();
+            Arc::new(move |panic| {
+                // The receiver is gone once `run` has returned for an earlier panic.
+                let _ = sender.send(Err(panic));
+            })
+        };
+        let main_future = future.map(move |output| {
+            let _ = sender.send(Ok(output));
+        });
+        Inner::spawn(&self.inner, FutureObj::new(Box::new(main_future)));
+
+        // Timers created on this thread before `run` are handed to the first worker.
+        let timers = with_local_timer_heap(mem::take);
+        let stop = Arc::new(AtomicBool::new(false));
+        let workers = self.start_workers(timers, &stop, on_panic);
+
+        let output = receiver.recv().expect("main future was dropped without completing");
+        let timers = self.join_workers(workers, &stop);
+        with_local_timer_heap(|timer_heap| timer_heap.append(timers));
+
+        match output {
+            Ok(output) => output,
+            Err(panic) => panic::resume_unwind(panic),
+        }
+    }
+
+    fn start_workers(
+        &self,
+        timers: TimerHeap,
+        stop: &Arc<AtomicBool>,
+        on_panic: PanicHandler,
+    ) -> Vec<thread::JoinHandle<TimerHeap>> {
+        let mut timers = Some(timers);
+        (0..self.num_threads)
+            .map(|i| {
+                let inner = self.inner.clone();
+                let stop = stop.clone();
+                let on_panic = on_panic.clone();
+                let timers = timers.take().unwrap_or_default();
+                thread::Builder::new()
+                    .name(format!("meshx-async-worker-{}", i))
+                    .spawn(move || {
+                        inner.clone().set_local(timers);
+                        // A worker keeps serving the port after a panic until it is told to stop,
+                        // so that it still takes exactly one of the wakeups sent by `join_workers`.
+                        let serve = || panic::catch_unwind(AssertUnwindSafe(|| worker_lifecycle(&inner, &stop)));
+                        while let Err(panic) = serve() {
+                            on_panic(panic);
+                        }
+                        take_local_timer_heap().unwrap_or_default()
+                    })
+                    .expect("failed to spawn executor worker thread")
+            })
+            .collect()
+    }
+
+    /// Stops the worker threads and returns the timers still pending on them.
+    fn join_workers(&self, workers: Vec<thread::JoinHandle<TimerHeap>>, stop: &AtomicBool) -> TimerHeap {
+        stop.store(true, Ordering::SeqCst);
+        // A worker only stops when it takes a wakeup after `stop` is set, so one per worker wakes
+        // all of them without leaving any on the port for the next call to `run`.
+        for _ in &workers {
+            self.inner.notify_empty();
+        }
+
+        let mut timers = TimerHeap::default();
+        for worker in workers {
+            match worker.join() {
+                Ok(worker_timers) => timers.append(worker_timers),
+                Err(panic) => panic::resume_unwind(panic),
+            }
+        }
+        timers
+    }
+}
+
+/// The run-loop of a worker thread: waits on the shared port, polls ready tasks, fires the
+/// thread's timers and delivers io packets until it takes a wakeup with `stop` set.
+fn worker_lifecycle(inner: &Arc<Inner>, stop: &AtomicBool) {
+    let mut local_collector = inner.collector.create_local_collector();
+
+    loop {
+        let packet = with_local_timer_heap(|timer_heap| {
+            let deadline = timer_heap.next_deadline().map(|t| t.time()).unwrap_or(Time::INFINITE);
+            local_collector.will_wait();
+            match inner.port.wait(deadline.into_fx()) {
+                Ok(packet) => Some(packet),
+                Err(fx::Status::TIMED_OUT) => {
+                    local_collector.woke_up(WakeupReason::Deadline);
+                    let time_waker = timer_heap.pop().unwrap();
+                    time_waker.wake();
+                    None
+                }
+                Err(status) => {
+                    panic!("Error calling port wait: {:?}", status);
+                }
+            }
+        });
+
+        if let Some(packet) = packet {
+            match packet.key() {
+                EMPTY_WAKEUP_ID => {
+                    local_collector.woke_up(WakeupReason::Notification);
+                    if stop.load(Ordering::SeqCst) {
+                        return;
+                    }
+                }
+                TASK_READY_WAKEUP_ID => {
+                    local_collector.woke_up(WakeupReason::Notification);
+                    inner.poll_ready_tasks(&mut local_collector);
+                }
+                receiver_key => {
+                    local_collector.woke_up(WakeupReason::Io);
+                    inner.deliver_packet(receiver_key as usize, packet);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for SendExecutor {
+    fn drop(&mut self) {
+        self.inner.mark_done();
+        self.inner.on_parent_drop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Task, Timer};
+    use std::sync::{Barrier, Mutex};
+
+    fn executor(num_threads: usize) -> SendExecutor {
+        fiber_host::install();
+        SendExecutor::new(num_threads)
+    }
+
+    #[test]
+    fn spawned_tasks_run_on_different_workers() {
+        let mut executor = executor(2);
+        let caller = thread::current().id();
+
+        let threads = executor.run(async {
+            // Neither task can finish before the other one has started, so they have to be polled
+            // concurrently.
+            let barrier = Arc::new(Barrier::new(2));
+            let tasks: Vec<_> = (0..2)
+                .map(|_| {
+                    let barrier = barrier.clone();
+                    Task::spawn(async move {
+                        barrier.wait();
+                        thread::current().id()
+                    })
+                })
+                .collect();
+            futures::future::join_all(tasks).await
+        });
+
+        assert_ne!(threads[0], threads[1]);
+        assert!(!threads.contains(&caller));
+    }
+
+    #[test]
+    fn timer_created_before_run_fires_on_a_worker() {
+        let mut executor = executor(2);
+        let caller = thread::current().id();
+
+        let timer = Timer::new(Time::after(fx::Duration::from_millis(10)));
+        let thread = executor.run(async move {
+            timer.await;
+            thread::current().id()
+        });
+        assert_ne!(thread, caller);
+    }
+
+    #[test]
+    fn timer_fires_while_another_worker_is_busy() {
+        let mut executor = executor(2);
+        let fired_on = Arc::new(Mutex::new(None));
+
+        let busy_on = executor.run({
+            let fired_on = fired_on.clone();
+            async move {
+                let (sender, receiver) = mpsc::channel();
+                // Occupy one worker until the timer, created on the other one, has fired.
+                let busy = Task::spawn(async move {
+                    receiver.recv().unwrap();
+                    thread::current().id()
+                });
+                Task::spawn(async move {
+                    Timer::new(Time::after(fx::Duration::from_millis(10))).await;
+                    *fired_on.lock().unwrap() = Some(thread::current().id());
+                    sender.send(()).unwrap();
+                })
+                .await;
+                busy.await
+            }
+        });
+
+        let fired_on = fired_on.lock().unwrap().expect("timer did not fire");
+        assert_ne!(fired_on, busy_on);
+    }
+
+    #[test]
+    fn task_panic_is_resumed_by_run() {
+        let mut executor = executor(2);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            executor.run(async {
+                // The task handle resumes the panic in the main future, which panics on a worker
+                // while it still owns the sender for the output of `run`.
+                Task::spawn(async { panic!("task panicked") }).await
+            })
+        }));
+        let panic = result.expect_err("run should resume the panic");
+        assert_eq!(panic.downcast_ref::<&str>(), Some(&"task panicked"));
+
+        // The workers were stopped cleanly and the executor can run again.
+        assert_eq!(executor.run(async { 42 }), 42);
+    }
+
+    #[test]
+    fn run_leaves_no_wakeups_on_the_port() {
+        let mut executor = executor(4);
+
+        for _ in 0..3 {
+            executor.run(async {
+                Task::spawn(async {}).await;
+            });
+            assert_eq!(
+                executor.inner.port.wait(fx::Time::INFINITE_PAST),
+                Err(fx::Status::TIMED_OUT)
+            );
+        }
+    }
+}
@@ -1,14 +1,41 @@
+// Copyright 2023 MeshX Contributors. All rights reserved.
+// Copyright 2021 The Fuchsia Authors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use super::{
+    common::{with_local_timer_heap, ExecutorTime, EMPTY_WAKEUP_ID, MAIN_TASK_ID, TASK_READY_WAKEUP_ID},
+    local::{LocalExecutor, WaitState},
+    time::Time,
+};
+
+use fiber_rust as fx;
+use std::{fmt, future::Future, sync::atomic::AtomicI64, task::Poll};
+
 /// A single-threaded executor for testing. Exposes additional APIs for manipulating executor state
 /// and validating behavior of executed tasks.
+///
+/// Timers never fire on their own: tests wake them explicitly with
+/// [`TestExecutor::wake_expired_timers`] or [`TestExecutor::wake_next_timer`]. Combined with fake
+/// time, this makes timeouts and retries deterministic without sleeping.
 pub struct TestExecutor {
     /// LocalExecutor used under the hood, since most of the logic is shared.
     local: LocalExecutor,
-    // A packet that has been dequeued but not processed. This is used by `run_one_step`.
-    next_packet: Option<zx::Packet>,
+    // A packet that has been dequeued but not processed. This is used by `is_waiting`.
+    next_packet: Option<fx::Packet>,
+}
+
+impl fmt::Debug for TestExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestExecutor")
+            .field("port", &self.local.inner.port)
+            .finish()
+    }
 }
 
 impl TestExecutor {
     /// Create a new executor for testing.
+    #[track_caller]
     pub fn new() -> Self {
         Self {
             local: LocalExecutor::new(),
@@ -17,25 +44,238 @@ impl TestExecutor {
     }
 
     /// Create a new single-threaded executor running with fake time.
+    ///
+    /// The clock starts at `Time::INFINITE_PAST` and only moves through
+    /// [`TestExecutor::set_fake_time`] or [`TestExecutor::wake_next_timer`].
+    #[track_caller]
     pub fn new_with_fake_time() -> Self {
-        let inner = Arc::new(Inner::new(
-            ExecutorTime::FakeTime(AtomicI64::new(Time::INFINITE_PAST.into_nanos())),
-            /* is_local */ true,
-        ));
-        inner.clone().set_local(TimerHeap::default());
-
-        let main_task = Arc::new(MainTask {
-            executor: Arc::downgrade(&inner),
-            notifier: Notifier::default(),
-        });
-
         Self {
-            local: LocalExecutor {
-                inner,
-                main_task,
-                main_waker: futures::task::waker(main_task.clone()),
-            },
+            local: LocalExecutor::with_time(ExecutorTime::FakeTime(AtomicI64::new(Time::INFINITE_PAST.into_nanos()))),
             next_packet: None,
         }
     }
+
+    /// Return the current time according to the executor.
+    pub fn now(&self) -> Time {
+        self.local.inner.now()
+    }
+
+    /// Set the fake time to a given value.
+    ///
+    /// # Panics
+    ///
+    /// If the executor was not created with fake time.
+    pub fn set_fake_time(&self, t: Time) {
+        self.local.inner.set_fake_time(t)
+    }
+
+    /// Run a single future to completion on a single thread, also polling other active tasks.
+    ///
+    /// # Panics
+    ///
+    /// If the executor was created with fake time.
+    pub fn run_singlethreaded<F>(&mut self, main_future: F) -> F::Output
+    where
+        F: Future,
+    {
+        self.local.run_singlethreaded(main_future)
+    }
+
+    /// Poll the future, then poll other tasks and deliver pending io packets until none of them
+    /// can make progress. Returns `Poll::Pending` if the future is still waiting on a timer or an
+    /// external event at that point.
+    ///
+    /// Timers are not fired, even if they have expired.
+    pub fn run_until_stalled<F>(&mut self, main_future: &mut F) -> Poll<F::Output>
+    where
+        F: Future + Unpin,
+    {
+        let mut local_collector = self.local.inner.collector.create_local_collector();
+        self.local.main_waker.wake_by_ref();
+
+        loop {
+            let packet = match self.next_packet.take() {
+                Some(packet) => packet,
+                None => match self.local.inner.port.wait(fx::Time::INFINITE_PAST) {
+                    Ok(packet) => packet,
+                    Err(fx::Status::TIMED_OUT) => return Poll::Pending,
+                    Err(status) => panic!("Error calling port wait: {:?}", status),
+                },
+            };
+
+            match packet.key() {
+                EMPTY_WAKEUP_ID => {
+                    let res = self.local.main_task.poll(main_future, &self.local.main_waker);
+                    local_collector.task_polled(
+                        MAIN_TASK_ID,
+                        self.local.inner.source,
+                        /* complete */ false,
+                        /* pending_tasks */ self.local.inner.ready_tasks.len(),
+                    );
+                    if res.is_ready() {
+                        return res;
+                    }
+                }
+                TASK_READY_WAKEUP_ID => self.local.inner.poll_ready_tasks(&mut local_collector),
+                receiver_key => self.local.inner.deliver_packet(receiver_key as usize, packet),
+            }
+        }
+    }
+
+    /// Wake all timers whose deadline is at or before the executor's current time. Returns
+    /// whether any timer was woken.
+    ///
+    /// The woken tasks run on the next call to [`TestExecutor::run_until_stalled`].
+    pub fn wake_expired_timers(&mut self) -> bool {
+        let now = self.now();
+        with_local_timer_heap(|timer_heap| {
+            let mut woke = false;
+            while timer_heap.next_deadline().is_some_and(|waker| waker.time() <= now) {
+                timer_heap.pop().unwrap().wake();
+                woke = true;
+            }
+            woke
+        })
+    }
+
+    /// Wake the timer with the earliest deadline, regardless of the current time, and return that
+    /// deadline. Returns `None` if there are no pending timers.
+    ///
+    /// With fake time, the clock is first advanced to the deadline if it is in the future, so that
+    /// the woken task observes the time it was waiting for.
+    pub fn wake_next_timer(&mut self) -> Option<Time> {
+        let waker = with_local_timer_heap(|timer_heap| {
+            timer_heap.next_deadline()?;
+            timer_heap.pop()
+        })?;
+        let deadline = waker.time();
+        if self.local.inner.require_real_time().is_err() && deadline > self.now() {
+            self.set_fake_time(deadline);
+        }
+        waker.wake();
+        Some(deadline)
+    }
+
+    /// Returns `WaitState::Ready` if a task or the main future is runnable, or an io packet is
+    /// pending. Otherwise, returns `WaitState::Waiting` with the deadline of the next timer, which
+    /// is `Time::INFINITE` if there are none.
+    ///
+    /// Tests can assert that everything is stalled on a timer after `run_until_stalled` with
+    /// `assert_eq!(exec.is_waiting(), WaitState::Waiting(deadline))`.
+    pub fn is_waiting(&mut self) -> WaitState {
+        if self.next_packet.is_none() {
+            match self.local.inner.port.wait(fx::Time::INFINITE_PAST) {
+                Ok(packet) => self.next_packet = Some(packet),
+                Err(fx::Status::TIMED_OUT) => {}
+                Err(status) => panic!("Error calling port wait: {:?}", status),
+            }
+        }
+        if self.next_packet.is_some() {
+            return WaitState::Ready;
+        }
+
+        let deadline = with_local_timer_heap(|timer_heap| timer_heap.next_deadline().map(|waker| waker.time()));
+        WaitState::Waiting(deadline.unwrap_or(Time::INFINITE))
+    }
+}
+
+impl Default for TestExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Task, Timer};
+    use futures::future;
+
+    fn executor_with_fake_time() -> TestExecutor {
+        fiber_host::install();
+        let executor = TestExecutor::new_with_fake_time();
+        executor.set_fake_time(Time::from_nanos(0));
+        executor
+    }
+
+    #[test]
+    fn run_until_stalled_returns_pending_for_stalled_future() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+
+        let mut stalled = future::pending::<()>();
+        assert_eq!(executor.run_until_stalled(&mut stalled), Poll::Pending);
+        assert_eq!(executor.run_until_stalled(&mut stalled), Poll::Pending);
+        assert_eq!(executor.is_waiting(), WaitState::Waiting(Time::INFINITE));
+    }
+
+    #[test]
+    fn run_until_stalled_runs_spawned_tasks() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+
+        let mut future = Box::pin(async { Task::spawn(async { 42 }).await });
+        assert_eq!(executor.run_until_stalled(&mut future), Poll::Ready(42));
+    }
+
+    #[test]
+    fn wake_next_timer_advances_fake_time() {
+        let mut executor = executor_with_fake_time();
+        let deadline = Time::from_nanos(0) + fx::Duration::from_seconds(1);
+
+        let mut timer = Timer::new(deadline);
+        assert_eq!(executor.run_until_stalled(&mut timer), Poll::Pending);
+        assert_eq!(executor.is_waiting(), WaitState::Waiting(deadline));
+        assert_eq!(executor.now(), Time::from_nanos(0));
+
+        assert_eq!(executor.wake_next_timer(), Some(deadline));
+        assert_eq!(executor.now(), deadline);
+        assert_eq!(executor.run_until_stalled(&mut timer), Poll::Ready(()));
+        assert_eq!(executor.wake_next_timer(), None);
+    }
+
+    #[test]
+    fn wake_next_timer_wakes_the_earliest_timer_first() {
+        let mut executor = executor_with_fake_time();
+        let first = Time::from_nanos(0) + fx::Duration::from_seconds(1);
+        let second = Time::from_nanos(0) + fx::Duration::from_seconds(2);
+
+        let mut later = Timer::new(second);
+        let mut earlier = Timer::new(first);
+        assert_eq!(executor.run_until_stalled(&mut later), Poll::Pending);
+        assert_eq!(executor.run_until_stalled(&mut earlier), Poll::Pending);
+
+        assert_eq!(executor.wake_next_timer(), Some(first));
+        assert_eq!(executor.run_until_stalled(&mut later), Poll::Pending);
+        assert_eq!(executor.run_until_stalled(&mut earlier), Poll::Ready(()));
+
+        assert_eq!(executor.wake_next_timer(), Some(second));
+        assert_eq!(executor.run_until_stalled(&mut later), Poll::Ready(()));
+    }
+
+    #[test]
+    fn set_fake_time_lets_expired_timers_fire() {
+        let mut executor = executor_with_fake_time();
+        let deadline = Time::after(fx::Duration::from_seconds(1));
+
+        let mut timer = Timer::new(deadline);
+        assert_eq!(executor.run_until_stalled(&mut timer), Poll::Pending);
+        assert!(!executor.wake_expired_timers());
+
+        executor.set_fake_time(deadline - fx::Duration::from_nanos(1));
+        assert!(!executor.wake_expired_timers());
+        assert_eq!(executor.run_until_stalled(&mut timer), Poll::Pending);
+
+        executor.set_fake_time(deadline);
+        assert_eq!(Time::now(), deadline);
+        assert!(executor.wake_expired_timers());
+        assert_eq!(executor.run_until_stalled(&mut timer), Poll::Ready(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "called `run_singlethreaded` on an executor using fake time")]
+    fn run_singlethreaded_requires_real_time() {
+        let mut executor = executor_with_fake_time();
+        executor.run_singlethreaded(async {});
+    }
 }
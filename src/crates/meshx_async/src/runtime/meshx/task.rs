@@ -66,7 +66,7 @@ impl<T: Send> Task<T> {
     ///
     /// `spawn` may panic if not called in the context of an executor (e.g.
     /// within a call to `run` or `run_singlethreaded`).
-    #[cfg_attr(trace_level_logging, track_caller)]
+    #[track_caller]
     pub fn spawn(future: impl Future<Output = T> + Send + 'static) -> Task<T> {
         // Fuse is a combinator that will drop the underlying future as soon as it has been
         // completed to ensure resources are reclaimed as soon as possible. That gives callers that
@@ -88,7 +88,7 @@ impl<T: Send> Task<T> {
     /// The passed future will live until either (a) the future completes,
     /// (b) the returned [`Task`] is dropped while the executor is running, or
     /// (c) the executor is destroyed; whichever comes first.
-    #[cfg_attr(trace_level_logging, track_caller)]
+    #[track_caller]
     pub fn spawn_on(
         executor: &super::executor::EHandle,
         future: impl Future<Output = T> + Send + 'static,
@@ -120,7 +120,7 @@ impl<T> Task<T> {
     ///
     /// `local` may panic if not called in the context of a local executor (e.g.
     /// within a call to `run` or `run_singlethreaded`).
-    #[cfg_attr(trace_level_logging, track_caller)]
+    #[track_caller]
     pub fn local(future: impl Future<Output = T> + 'static) -> Task<T> {
         // Fuse is a combinator that will drop the underlying future as soon as it has been
         // completed to ensure resources are reclaimed as soon as possible. That gives callers that
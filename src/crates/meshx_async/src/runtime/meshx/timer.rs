@@ -199,6 +199,11 @@ impl TimerHeap {
     pub fn pop(&mut self) -> Option<TimeWaker> {
         self.inner.pop()
     }
+
+    /// Moves all the timers of `other` into this heap.
+    pub fn append(&mut self, mut other: TimerHeap) {
+        self.inner.append(&mut other.inner)
+    }
 }
 
 pub(crate) struct TimeWaker {
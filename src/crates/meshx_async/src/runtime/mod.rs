@@ -2,14 +2,14 @@ mod meshx;
 use self::meshx as implementation;
 
 pub use implementation::{
-    executor::{Duration, LocalExecutor, Time},
+    executor::{Duration, LocalExecutor, SendExecutor, TestExecutor, Time},
     task::{unblock, Task},
     timer::Timer,
 };
 
 // MeshX specific exports
 pub use self::meshx::{
-    executor::{EHandle, PacketReceiver, ReceiverRegistration, WaitState},
+    executor::{EHandle, ExecutorStats, PacketReceiver, ReceiverRegistration, TaskInfo, WaitState},
     timer::Interval,
 };
 
@@ -5,35 +5,41 @@
 
 mod service;
 
+pub use service::{MidlService, Proxy, Service, ServiceObjLocal, ServiceObjTrait};
+
 use anyhow::Error;
 use fiber_rust as fx;
-use std::sync::Arc;
+use midl::endpoints::{DiscoverableProtocolMarker, RequestStream};
+use midl_meshx_io as mio;
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
 use thiserror::Error;
 
 use futures::{
     channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
     future::BoxFuture,
-    FutureExt,
+    FutureExt, Stream, StreamExt,
 };
 
 use vfs::{
     directory::{
-        // entry::DirectoryEntry,
-        // helper::DirectlyMutable,
+        entry::DirectoryEntry,
+        helper::DirectlyMutable,
         immutable::{connection::ImmutableConnection, simple::simple},
         simple::Simple,
     },
-    // execution_scope::ExecutionScope,
-    // file::vmo::VmoFile,
+    execution_scope::ExecutionScope,
+    file::vmo::VmoFile,
     name::Name,
     path::Path,
-    // remote::{remote_dir, remote_node},
-    // service::endpoint,
+    remote::{remote_dir, remote_node},
+    service::endpoint,
 };
 
 use meshx_runtime::{take_startup_handle, HandleType};
-use pin_project::pin_project;
-use service::{ServiceObjLocal, ServiceObjTrait};
 
 /// A filesystem which connects clients to services.
 ///
@@ -44,8 +50,10 @@ use service::{ServiceObjLocal, ServiceObjTrait};
 /// streams, futures to run, or any other value that should be processed
 /// as the result of a request.
 #[must_use]
-#[pin_project]
 pub struct ServiceFs<ServiceObjTy: ServiceObjTrait> {
+    // The execution scope for the backing VFS.
+    scope: ExecutionScope,
+
     // The root directory.
     dir: Arc<Simple<ImmutableConnection>>,
 
@@ -97,6 +105,152 @@ macro_rules! add_functions {
                 .unwrap_or_else(|_| panic!("Not a directory"));
             ServiceFsDir { fs: self.fs(), dir }
         }
+
+        /// Adds a new remote directory served over the given DirectoryProxy.
+        ///
+        /// The name must be a valid `meshx.io` [`Name`].
+        pub fn add_remote(&mut self, name: impl Into<String>, proxy: mio::DirectoryProxy) {
+            let name: String = name.into();
+            let name: Name = name.try_into().expect("Invalid path");
+            self.dir.add_entry_impl(name, remote_dir(proxy), false).expect("Unable to add entry");
+        }
+
+        /// Adds a new remote node served over the given NodeProxy.  Unlike
+        /// [`Self::add_remote`], opening paths inside of the node is not
+        /// supported.
+        ///
+        /// The name must be a valid `meshx.io` [`Name`].
+        pub fn add_remote_node(&mut self, name: impl Into<String>, proxy: mio::NodeProxy) {
+            let name: String = name.into();
+            let name: Name = name.try_into().expect("Invalid path");
+            self.dir.add_entry_impl(name, remote_node(proxy), false).expect("Unable to add entry");
+        }
+
+        /// Adds an entry to the filesystem at the given path.
+        ///
+        /// The path must be a single component.
+        /// The path must be a valid `meshx.io` [`Name`].
+        ///
+        /// Panics if there is already an entry at the given path.
+        pub fn add_entry_at(&mut self, path: impl Into<String>, entry: Arc<dyn DirectoryEntry>) -> &mut Self {
+            let path: String = path.into();
+            let name: Name = path.try_into().expect("Invalid path");
+            // This will fail if the name already exists.
+            self.dir.add_entry_impl(name, entry, false).expect("Unable to add entry");
+            self
+        }
+
+        /// Adds a read-only file to the filesystem at the given path, exposing
+        /// the first `length` bytes of `vmo`.
+        ///
+        /// Panics if there is already an entry at the given path.
+        pub fn add_vmo_file_at(&mut self, path: impl Into<String>, vmo: fx::Vmo, length: u64) -> &mut Self {
+            self.add_entry_at(path, VmoFile::new(vmo, length))
+        }
+
+        /// Adds a service to the directory at the given path.
+        ///
+        /// The path must be a single component.
+        /// The path must be a valid `meshx.io` [`Name`].
+        ///
+        /// Panics if any node has already been added at the given path.
+        pub fn add_service_at(&mut self, path: impl Into<String>, service: impl Into<ServiceObjTy>) -> &mut Self {
+            let index = self.fs().services.len();
+            self.fs().services.push(service.into());
+            let sender = self.fs().new_connection_sender.clone();
+            self.add_entry_at(
+                path,
+                endpoint(move |_, channel| {
+                    // It's possible for this send to fail in the case where ServiceFs has been
+                    // dropped.  When that happens, ServiceFs will drop ExecutionScope which
+                    // results in the connection being dropped.
+                    let _ = sender.unbounded_send((index, channel.into()));
+                }),
+            )
+        }
+
+        /// Adds a MIDL service to the directory.
+        ///
+        /// `service` is a closure that accepts a `RequestStream`.
+        /// Each service being served must return an instance of the same type
+        /// (`ServiceObjTy::Output`). This is necessary in order to multiplex
+        /// multiple services over the same dispatcher code. The typical way
+        /// to do this is to create an `enum` with variants for each service
+        /// you want to serve.
+        ///
+        /// ```ignore
+        /// enum MyServices {
+        ///     EchoServer(EchoRequestStream),
+        ///     CustomServer(CustomRequestStream),
+        ///     // ...
+        /// }
+        /// ```
+        ///
+        /// The constructor for a variant of the `MyServices` enum can be passed
+        /// as the `service` parameter.
+        ///
+        /// ```ignore
+        /// let mut fs = ServiceFs::new_local();
+        /// fs
+        ///     .add_midl_service(MyServices::EchoServer)
+        ///     .add_midl_service(MyServices::CustomServer)
+        ///     .take_and_serve_directory_handle()?;
+        /// ```
+        ///
+        /// `ServiceFs` can now be treated as a `Stream` of type `MyServices`.
+        ///
+        /// ```ignore
+        /// const MAX_CONCURRENT: usize = 10_000;
+        /// fs.for_each_concurrent(MAX_CONCURRENT, |request: MyServices| {
+        ///     match request {
+        ///         MyServices::EchoServer(request) => handle_echo(request),
+        ///         MyServices::CustomServer(request) => handle_custom(request),
+        ///     }
+        /// }).await;
+        /// ```
+        ///
+        /// The MIDL service will be hosted at the name provided by the
+        /// `[Discoverable]` annotation in the MIDL source.
+        pub fn add_midl_service<F, RS>(&mut self, service: F) -> &mut Self
+        where
+            F: FnMut(RS) -> ServiceObjTy::Output,
+            RS: RequestStream,
+            RS::Protocol: DiscoverableProtocolMarker,
+            MidlService<F, RS, ServiceObjTy::Output>: Into<ServiceObjTy>,
+        {
+            self.add_midl_service_at(RS::Protocol::PROTOCOL_NAME, service)
+        }
+
+        /// Adds a MIDL service to the directory at the given path.
+        ///
+        /// The path must be a single component.
+        /// The path must be a valid `meshx.io` [`Name`].
+        ///
+        /// See [`add_midl_service`](#method.add_midl_service) for details.
+        pub fn add_midl_service_at<F, RS>(&mut self, path: impl Into<String>, service: F) -> &mut Self
+        where
+            F: FnMut(RS) -> ServiceObjTy::Output,
+            RS: RequestStream,
+            MidlService<F, RS, ServiceObjTy::Output>: Into<ServiceObjTy>,
+        {
+            self.add_service_at(path, MidlService::from(service))
+        }
+
+        /// Adds a service that proxies requests for the protocol `P` to
+        /// `directory`, typically the component's incoming `svc` directory.
+        ///
+        /// The service will be hosted at the name provided by the
+        /// `[Discoverable]` annotation in the MIDL source.
+        pub fn add_proxy_service<P: DiscoverableProtocolMarker, O>(
+            &mut self,
+            directory: Arc<mio::DirectoryProxy>,
+        ) -> &mut Self
+        where
+            ServiceObjTy: From<Proxy<P, O>>,
+            ServiceObjTy: ServiceObjTrait<Output = O>,
+        {
+            self.add_service_at(P::PROTOCOL_NAME, Proxy::<P, ServiceObjTy::Output>::new(directory))
+        }
     };
 }
 
@@ -132,20 +286,24 @@ fn new_simple_dir() -> Arc<Simple<ImmutableConnection>> {
 impl<ServiceObjTy: ServiceObjTrait> ServiceFs<ServiceObjTy> {
     fn new_impl() -> Self {
         let (new_connection_sender, new_connection_receiver) = mpsc::unbounded();
-        //let scope = ExecutionScope::new();
+        let scope = ExecutionScope::new();
         let dir = new_simple_dir();
 
         Self {
-            //scope: scope.clone(),
+            scope: scope.clone(),
             dir,
             new_connection_sender,
             new_connection_receiver,
             services: Vec::new(),
-            shutdown: async move {}.boxed(),
+            shutdown: scope.wait().boxed(),
             channel_queue: Some(Vec::new()),
         }
     }
 
+    fn fs(&mut self) -> &mut ServiceFs<ServiceObjTy> {
+        self
+    }
+
     add_functions!();
 }
 
@@ -182,4 +340,41 @@ impl<ServiceObjTy: ServiceObjTrait> ServiceFs<ServiceObjTy> {
 
         Ok(self)
     }
+
+    fn serve_connection_impl(&self, chan: midl::endpoints::ServerEnd<mio::DirectoryMarker>) {
+        self.dir.clone().open(
+            self.scope.clone(),
+            mio::OpenFlags::RIGHT_READABLE | mio::OpenFlags::RIGHT_WRITABLE,
+            Path::dot(),
+            midl::endpoints::ServerEnd::new(chan.into_channel()),
+        );
+    }
+}
+
+// None of the fields are ever pinned.
+impl<ServiceObjTy: ServiceObjTrait> Unpin for ServiceFs<ServiceObjTy> {}
+
+impl<ServiceObjTy: ServiceObjTrait> Drop for ServiceFs<ServiceObjTy> {
+    fn drop(&mut self) {
+        self.scope.shutdown();
+    }
+}
+
+impl<ServiceObjTy: ServiceObjTrait> Stream for ServiceFs<ServiceObjTy> {
+    type Item = ServiceObjTy::Output;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Serve the connections that were added before the filesystem was first polled.
+        if let Some(channels) = self.channel_queue.take() {
+            for chan in channels {
+                self.serve_connection_impl(chan);
+            }
+        }
+        while let Poll::Ready(Some((index, channel))) = self.new_connection_receiver.poll_next_unpin(cx) {
+            if let Some(stream) = self.services[index].service().connect(channel) {
+                return Poll::Ready(Some(stream));
+            }
+        }
+        self.shutdown.poll_unpin(cx).map(|()| None)
+    }
 }
@@ -4,10 +4,13 @@
 //! The `Service` trait and its trait-object wrappers.
 
 use fiber_rust as fx;
+use midl::endpoints::{DiscoverableProtocolMarker, RequestStream, ServerEnd};
+use midl_meshx_io as mio;
+use std::{marker::PhantomData, sync::Arc};
 
 /// `Service` connects channels to service instances.
 ///
-/// Note that this trait is implemented by the `FidlService` type.
+/// Note that this trait is implemented by the `MidlService` type.
 pub trait Service {
     /// The type of the value yielded by the `spawn_service` callback.
     type Output;
@@ -44,4 +47,85 @@ impl<'a, Output> ServiceObjTrait for ServiceObjLocal<'a, Output> {
     fn service(&mut self) -> &mut dyn Service<Output = Self::Output> {
         &mut *self.0
     }
-}
\ No newline at end of file
+}
+impl<'a, S: Service + 'a> From<S> for ServiceObjLocal<'a, S::Output> {
+    fn from(service: S) -> Self {
+        ServiceObjLocal(Box::new(service))
+    }
+}
+
+/// A wrapper for functions from `RequestStream` to `Output` which implements
+/// `Service`.
+///
+/// This type throws away channels that cannot be converted to the appropriate
+/// `RequestStream` type.
+pub struct MidlService<F, RS, Output>
+where
+    F: FnMut(RS) -> Output,
+    RS: RequestStream,
+{
+    f: F,
+    marker: PhantomData<(RS, Output)>,
+}
+
+impl<F, RS, Output> From<F> for MidlService<F, RS, Output>
+where
+    F: FnMut(RS) -> Output,
+    RS: RequestStream,
+{
+    fn from(f: F) -> Self {
+        Self { f, marker: PhantomData }
+    }
+}
+
+impl<F, RS, Output> Service for MidlService<F, RS, Output>
+where
+    F: FnMut(RS) -> Output,
+    RS: RequestStream,
+{
+    type Output = Output;
+
+    fn connect(&mut self, channel: fx::Channel) -> Option<Self::Output> {
+        match meshx_async::Channel::from_channel(channel) {
+            Ok(chan) => Some((self.f)(RS::from_channel(chan))),
+            Err(e) => {
+                log::error!("ServiceFs failed to convert channel to async channel: {:?}", e);
+                None
+            }
+        }
+    }
+}
+
+/// A `Service` implementation that proxies requests for the protocol `P`
+/// to the directory `directory`, typically the component's incoming `svc`
+/// directory.
+pub struct Proxy<P, O> {
+    directory: Arc<mio::DirectoryProxy>,
+    _phantom: PhantomData<(P, fn() -> O)>,
+}
+
+impl<P, O> Proxy<P, O> {
+    /// Create a new `Proxy` forwarding connections to `directory`.
+    pub fn new(directory: Arc<mio::DirectoryProxy>) -> Self {
+        Self {
+            directory,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<P: DiscoverableProtocolMarker, O> Service for Proxy<P, O> {
+    type Output = O;
+
+    fn connect(&mut self, channel: fx::Channel) -> Option<Self::Output> {
+        if let Err(e) = self.directory.open(
+            mio::OpenFlags::empty(),
+            mio::ModeType::empty(),
+            P::PROTOCOL_NAME,
+            ServerEnd::new(channel),
+        ) {
+            log::error!("failed to proxy request to {}: {:?}", P::PROTOCOL_NAME, e);
+        }
+        None
+    }
+}
@@ -262,6 +262,7 @@ impl Client {
 
     /// Sends a raw message without expecting a response.
     pub fn send_raw(&self, bytes: &[u8], handles: &mut Vec<HandleDisposition<'_>>) -> Result<(), Error> {
+        crate::trace::trace_request(self.inner.protocol_name, bytes, handles.len());
         match self.inner.channel.write_etc(bytes, handles) {
             Ok(()) | Err(fx_status::Status::PEER_CLOSED) => Ok(()),
             Err(e) => Err(Error::ClientWrite(e)),
@@ -642,6 +643,7 @@ impl ClientInner {
                 };
                 buf
             };
+            crate::trace::trace_reply(self.protocol_name, buf.bytes(), buf.n_handle_infos());
             let (header, body_bytes) = decode_transaction_header(buf.bytes()).map_err(|_| Error::InvalidHeader)?;
             if !header.is_compatible() {
                 return Err(Error::IncompatibleMagicNumber(header.magic_number()));
@@ -732,6 +734,11 @@ impl ClientInner {
 
 pub mod sync {
     //! Synchronous MIDL Client
+    //!
+    //! Calls block the calling thread instead of needing an executor. Every
+    //! call and `wait_for_event` takes a deadline, e.g.
+    //! `fx::Time::after(fx::Duration::from_seconds(5))`; when it passes the
+    //! call fails with an error for which `Error::is_timed_out` is true.
     use super::*;
     use {
         crate::encoding::{maybe_overflowing_after_encode, maybe_overflowing_decode},
@@ -761,6 +768,11 @@ pub mod sync {
             self.channel
         }
 
+        /// Get a reference to the underlying channel.
+        pub fn as_channel(&self) -> &fx::Channel {
+            &self.channel
+        }
+
         /// Send a new message.
         pub fn send<T: TypeMarker, const OVERFLOWABLE: bool>(
             &self,
@@ -778,6 +790,7 @@ pub mod sync {
             if OVERFLOWABLE {
                 maybe_overflowing_after_encode(&mut write_bytes, &mut write_handles)?;
             }
+            crate::trace::trace_request(self.protocol_name, &write_bytes, write_handles.len());
             match self.channel.write_etc(&mut write_bytes, &mut write_handles) {
                 Ok(()) | Err(fx_status::Status::PEER_CLOSED) => Ok(()),
                 Err(e) => Err(Error::ClientWrite(e)),
@@ -813,9 +826,11 @@ pub mod sync {
 
             // TODO: We should be able to use the same memory to back the bytes we use for writing
             // and reading.
+            crate::trace::trace_request(self.protocol_name, &write_bytes, write_handles.len());
             self.channel
                 .call_etc(deadline, &write_bytes, &mut write_handles, &mut buf)
                 .map_err(|e| self.wrap_error(Error::ClientCall, e))?;
+            crate::trace::trace_reply(self.protocol_name, buf.bytes(), buf.n_handle_infos());
             let (bytes, mut handle_infos) = buf.split();
             let (header, body_bytes) = decode_transaction_header(&bytes)?;
             let mut output = Decode::<Response>::new_empty();
@@ -832,7 +847,7 @@ pub mod sync {
             let mut buf = fx::MessageBufEtc::new();
             buf.ensure_capacity_bytes(fx::sys::FX_CHANNEL_MAX_MSG_BYTES as usize);
             buf.ensure_capacity_handle_infos(fx::sys::FX_CHANNEL_MAX_MSG_HANDLES as usize);
-            
+
             loop {
                 self.channel
                     .wait_handle(
@@ -842,6 +857,7 @@ pub mod sync {
                     .map_err(|e| self.wrap_error(Error::ClientEvent, e))?;
                 match self.channel.read_etc(&mut buf) {
                     Ok(()) => {
+                        crate::trace::trace_reply(self.protocol_name, buf.bytes(), buf.n_handle_infos());
                         // We succeeded in reading the message. Check that it is
                         // an event not a two-way method reply.
                         let (header, _) = decode_transaction_header(buf.bytes()).map_err(|_| Error::InvalidHeader)?;
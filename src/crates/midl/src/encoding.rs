@@ -12,7 +12,10 @@ use {
     fiber_status as fx_status, fiber_types as fx_types,
     std::{cell::RefCell, cell::RefMut, marker::PhantomData, mem, ptr, str, u32, u64},
 };
+mod view;
+
 pub use {
+    self::view::{decode_transaction_view, VectorView, ViewMarker},
     static_assertions::const_assert_eq,
     // TODO(fxbug.dev/124207): Remove zerocopy.
     zerocopy,
@@ -1566,6 +1569,11 @@ unsafe fn decode_vector<T: TypeMarker>(
         return Err(Error::NotNullable);
     };
     check_vector_length(len, max_length)?;
+    // Empty vectors have no out-of-line block to read from.
+    if len == 0 {
+        vec.clear();
+        return Ok(());
+    }
     depth.increment()?;
     let bytes_len = len * T::inline_size(decoder.context);
     let offset = decoder.out_of_line_offset(bytes_len)?;
@@ -3674,6 +3682,28 @@ impl TransactionHeader {
     }
 }
 
+/// Whether a method expects a response, as far as the transaction id of its
+/// requests is concerned.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MethodType {
+    /// Requests carry txid 0 and are never answered.
+    OneWay,
+    /// Requests carry a non-zero txid which the response echoes back.
+    TwoWay,
+}
+
+impl TransactionHeader {
+    /// Checks that the transaction id of a request matches its method type.
+    #[inline]
+    pub fn validate_request_tx_id(&self, method_type: MethodType) -> Result<()> {
+        match method_type {
+            MethodType::OneWay if self.tx_id != 0 => Err(Error::InvalidRequestTxid { method_type }),
+            MethodType::TwoWay if self.tx_id == 0 => Err(Error::InvalidRequestTxid { method_type }),
+            _ => Ok(()),
+        }
+    }
+}
+
 /// Decodes the transaction header from a message.
 /// Returns the header and a reference to the tail of the message.
 pub fn decode_transaction_header(bytes: &[u8]) -> Result<(TransactionHeader, &[u8])> {
@@ -5118,4 +5148,188 @@ mod test {
             );
         }
     }
+
+    /// Golden V2 encodings, shared with the TypeScript bindings.
+    const GOLDEN_V2: &str = include_str!("../testdata/golden_v2.txt");
+
+    const V2: Context = Context {
+        wire_format_version: WireFormatVersion::V2,
+    };
+
+    #[track_caller]
+    fn golden(case: &str) -> Vec<u8> {
+        let mut current = None;
+        let mut bytes = Vec::new();
+        for line in GOLDEN_V2.lines() {
+            let line = line.split('#').next().unwrap();
+            let hex = match line.split_once('=') {
+                Some((name, hex)) => {
+                    if current == Some(case) {
+                        break;
+                    }
+                    current = Some(name.trim());
+                    hex
+                }
+                None => line,
+            };
+            if current == Some(case) {
+                bytes.extend(
+                    hex.split_whitespace()
+                        .map(|byte| u8::from_str_radix(byte, 16).expect("invalid golden byte")),
+                );
+            }
+        }
+        assert!(current == Some(case), "golden case {case} not found");
+        bytes
+    }
+
+    #[track_caller]
+    fn assert_golden<T>(case: &str, value: &T::Owned)
+    where
+        T: ValueTypeMarker,
+        T::Owned: fmt::Debug + PartialEq,
+    {
+        let bytes = golden(case);
+        encode_assert_bytes::<T>(V2, T::borrow(value), &bytes);
+        let mut out = T::Owned::new_empty();
+        Decoder::decode_with_context::<T>(V2, &bytes, &mut [], &mut out).expect("Decoding failed");
+        assert_eq!(*value, out, "golden case {case}");
+    }
+
+    #[test]
+    fn golden_primitives() {
+        assert_golden::<bool>("bool_true", &true);
+        assert_golden::<u8>("uint8", &42);
+        assert_golden::<i8>("int8", &-2);
+        assert_golden::<u16>("uint16", &0x1234);
+        assert_golden::<i16>("int16", &-2);
+        assert_golden::<u32>("uint32", &0x12345678);
+        assert_golden::<i32>("int32", &-1);
+        assert_golden::<u64>("uint64", &0x0102030405060708);
+        assert_golden::<i64>("int64", &-2);
+        assert_golden::<f32>("float32", &1.5);
+        assert_golden::<f64>("float64", &-2.5);
+    }
+
+    #[test]
+    fn golden_out_of_line() {
+        assert_golden::<UnboundedString>("string_hello", &"hello".to_string());
+        assert_golden::<UnboundedString>("string_empty", &String::new());
+        assert_golden::<Optional<UnboundedString>>("optional_string_absent", &None);
+        assert_golden::<UnboundedVector<u16>>("vector_uint16", &vec![1, 2, 3]);
+        assert_golden::<UnboundedVector<UnboundedString>>("vector_string", &vec!["a".to_string(), "bc".to_string()]);
+        assert_golden::<Optional<UnboundedVector<u16>>>("optional_vector_absent", &None);
+        assert_golden::<Array<u32, 3>>("array_uint32", &[1, 2, 3]);
+    }
+
+    #[test]
+    fn golden_structs() {
+        let foo = Foo {
+            byte: 5,
+            bignum: 22,
+            string: "hello".to_string(),
+        };
+        assert_golden::<Foo>("struct", &foo);
+        assert_golden::<Boxed<Foo>>("boxed_struct", &Some(Box::new(foo)));
+        assert_golden::<Boxed<Foo>>("boxed_struct_absent", &None);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum GoldenUnion {
+        Small(u32),
+        Big(u64),
+    }
+
+    impl GoldenUnion {
+        pub fn ordinal(&self) -> u64 {
+            match *self {
+                Self::Small(_) => 1,
+                Self::Big(_) => 2,
+            }
+        }
+    }
+
+    midl_union! {
+        name: GoldenUnion,
+        members: [
+            Small {
+                ty: u32,
+                ordinal: 1,
+            },
+            Big {
+                ty: u64,
+                ordinal: 2,
+            },
+        ],
+    }
+
+    #[test]
+    fn golden_unions() {
+        assert_golden::<GoldenUnion>("union_inline", &GoldenUnion::Small(7));
+        assert_golden::<GoldenUnion>("union_out_of_line", &GoldenUnion::Big(0x0102030405060708));
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct GoldenTable {
+        a: Option<u32>,
+        b: Option<u64>,
+        c: Option<String>,
+        #[deprecated = "Use `..GoldenTable::EMPTY` to construct and `..` to match."]
+        #[doc(hidden)]
+        __non_exhaustive: (),
+    }
+
+    impl GoldenTable {
+        #[allow(deprecated)]
+        pub const EMPTY: Self = Self {
+            a: None,
+            b: None,
+            c: None,
+            __non_exhaustive: (),
+        };
+    }
+
+    midl_table! {
+        name: GoldenTable,
+        members: [
+            a {
+                ty: u32,
+                ordinal: 1,
+            },
+            b {
+                ty: u64,
+                ordinal: 2,
+            },
+            c {
+                ty: UnboundedString,
+                ordinal: 3,
+            },
+        ],
+    }
+
+    #[test]
+    fn golden_tables() {
+        let table = GoldenTable {
+            a: Some(1),
+            c: Some("hi".to_string()),
+            ..GoldenTable::EMPTY
+        };
+        assert_golden::<GoldenTable>("table", &table);
+        assert_golden::<GoldenTable>("table_empty", &GoldenTable::EMPTY);
+    }
+
+    #[test]
+    fn golden_handles() {
+        type T = HandleType<Handle, OBJECT_TYPE_NONE, SAME_RIGHTS>;
+        let buf = &mut Vec::new();
+        let handle_buf = &mut Vec::new();
+        // Safety: the handle is never used as a real handle and is forgotten below.
+        let handle = unsafe { Handle::from_raw(1) };
+        Encoder::encode_with_context::<T>(V2, buf, handle_buf, handle).expect("Encoding failed");
+        assert_eq!(*buf, golden("handle"));
+        assert_eq!(handle_buf.len(), 1);
+        mem::forget(mem::take(handle_buf));
+
+        encode_assert_bytes::<Optional<T>>(V2, None::<Handle>, &golden("optional_handle_absent"));
+    }
 }
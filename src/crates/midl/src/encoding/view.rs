@@ -0,0 +1,531 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Zero-copy decoding of MIDL values into views borrowing from the message
+//! buffer.
+//!
+//! `Decoder::decode_view` validates a message exactly like
+//! `Decoder::decode_into`, but instead of materialising `String`s and `Vec`s
+//! it returns `&str`s and `VectorView`s pointing into the buffer. Only value
+//! types have views: handles must be moved out of the message, which requires
+//! owned decoding.
+
+use {super::*, std::fmt};
+
+/// A MIDL type that can be decoded into a view of the message buffer.
+///
+/// `Self::View` borrows from the buffer for `'de`. For primitives and enums
+/// it is the owned value, since copying those is as cheap as borrowing them.
+pub trait ViewMarker<'de>: TypeMarker {
+    /// The borrowed representation of the type.
+    type View;
+
+    /// Validates the object at `offset` and returns a view of it. Like
+    /// `Decode::decode`, this consumes out-of-line objects from `decoder`.
+    ///
+    /// # Safety
+    ///
+    /// Like `Decode::decode`, the caller is responsible for bounds checks:
+    /// `offset` must be valid for reading `inline_size` bytes.
+    unsafe fn decode_view(decoder: &mut Decoder<'de>, offset: usize, depth: Depth) -> Result<Self::View>;
+
+    /// Returns true if the view of a validated element can be read straight
+    /// out of the buffer, letting `VectorView` skip materialising element
+    /// views. Must only return true if `decode_is_copy` does.
+    #[inline(always)]
+    fn view_is_copy() -> bool {
+        false
+    }
+
+    /// Converts a value copied out of the buffer into its view. Only called
+    /// when `view_is_copy` returns true.
+    #[inline(always)]
+    fn view_from_copy(_value: Self::Owned) -> Self::View {
+        unreachable!("view_from_copy called on a type that is not view_is_copy")
+    }
+}
+
+/// Implements `ViewMarker` for a type that is its own view, decoding it with
+/// its `Decode` implementation.
+#[doc(hidden)] // only exported for use in macros or generated code
+#[macro_export]
+macro_rules! impl_view_by_copy {
+    ($ty:ty) => {
+        impl<'de> $crate::encoding::ViewMarker<'de> for $ty {
+            type View = $ty;
+            #[inline(always)]
+            unsafe fn decode_view(
+                decoder: &mut $crate::encoding::Decoder<'de>,
+                offset: usize,
+                depth: $crate::encoding::Depth,
+            ) -> $crate::Result<Self::View> {
+                let mut value = $crate::new_empty!($ty);
+                $crate::decode!($ty, &mut value, decoder, offset, depth)?;
+                Ok(value)
+            }
+            #[inline(always)]
+            fn view_is_copy() -> bool {
+                <$ty as $crate::encoding::TypeMarker>::decode_is_copy()
+            }
+            #[inline(always)]
+            fn view_from_copy(value: Self::Owned) -> Self::View {
+                value
+            }
+        }
+    };
+}
+
+impl_view_by_copy!(bool);
+impl_view_by_copy!(u8);
+impl_view_by_copy!(u16);
+impl_view_by_copy!(u32);
+impl_view_by_copy!(u64);
+impl_view_by_copy!(i8);
+impl_view_by_copy!(i16);
+impl_view_by_copy!(i32);
+impl_view_by_copy!(i64);
+impl_view_by_copy!(f32);
+impl_view_by_copy!(f64);
+
+impl<'a> Decoder<'a> {
+    /// Validates `buf` as a value of MIDL type `T` and returns a view of it.
+    /// Assumes the buffer came from inside a transaction message wrapped by
+    /// `header`. Fails on the same inputs as `decode_into`, including
+    /// messages carrying handles.
+    #[inline]
+    pub fn decode_view<T: ViewMarker<'a>>(header: &TransactionHeader, buf: &'a [u8]) -> Result<T::View> {
+        Self::decode_view_with_context::<T>(header.decoding_context(), buf)
+    }
+
+    /// Validates `buf` as a value of MIDL type `T` and returns a view of it,
+    /// using the specified context.
+    ///
+    /// WARNING: Do not call this directly unless you know what you're doing.
+    /// MIDL uses `Context` to coordinate soft migrations, so improper uses of
+    /// this function could result in ABI breakage.
+    #[inline]
+    pub fn decode_view_with_context<T: ViewMarker<'a>>(context: Context, buf: &'a [u8]) -> Result<T::View> {
+        let inline_size = T::inline_size(context);
+        let next_out_of_line = round_up_to_align(inline_size, 8);
+        if next_out_of_line > buf.len() {
+            return Err(Error::OutOfRange);
+        }
+        let mut decoder = Decoder {
+            next_out_of_line,
+            buf,
+            handles: &mut [],
+            next_handle: 0,
+            context,
+        };
+        // Safety: buf.len() >= inline_size based on the check above.
+        let view = unsafe { T::decode_view(&mut decoder, 0, Depth(0))? };
+        // Safety: next_out_of_line <= buf.len() based on the check above.
+        unsafe { decoder.post_decoding(inline_size, next_out_of_line)? };
+        Ok(view)
+    }
+}
+
+/// Decodes the transaction header of a message and validates its body as
+/// `T`, returning the header and a view of the body. This is the borrowed
+/// counterpart of `decode_transaction_header` followed by
+/// `Decoder::decode_into`, e.g. for `MessageBufEtc::bytes()`.
+pub fn decode_transaction_view<'de, T: ViewMarker<'de>>(bytes: &'de [u8]) -> Result<(TransactionHeader, T::View)> {
+    let (header, body) = decode_transaction_header(bytes)?;
+    let view = Decoder::decode_view::<T>(&header, body)?;
+    Ok((header, view))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Strings
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'de, const N: usize> ViewMarker<'de> for BoundedString<N> {
+    type View = &'de str;
+
+    #[inline]
+    unsafe fn decode_view(decoder: &mut Decoder<'de>, offset: usize, mut depth: Depth) -> Result<Self::View> {
+        decoder.debug_check_bounds::<Self>(offset);
+        let Some(len) = decode_vector_header(decoder, offset)? else {
+            return Err(Error::NotNullable);
+        };
+        check_string_length(len, N)?;
+        depth.increment()?;
+        let offset = decoder.out_of_line_offset(len)?;
+        let buf: &'de [u8] = decoder.buf;
+        // Safety: `out_of_line_offset` does this bounds check.
+        let bytes = unsafe { buf.get_unchecked(offset..offset + len) };
+        str::from_utf8(bytes).map_err(|_| Error::Utf8Error)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Vectors and arrays
+////////////////////////////////////////////////////////////////////////////////
+
+/// A validated MIDL vector or array in the message buffer.
+///
+/// Elements whose views are plain copies (integers and floats) are read from
+/// the buffer on access, so a `vector<uint8>` never allocates; see
+/// `as_bytes`. Other elements are validated and collected up front.
+pub struct VectorView<'de, T: ViewMarker<'de>> {
+    elements: Elements<'de, T::View>,
+    _marker: PhantomData<T>,
+}
+
+enum Elements<'de, V> {
+    Copy { bytes: &'de [u8], len: usize },
+    Decoded(Vec<V>),
+}
+
+impl<'de, T: ViewMarker<'de>> VectorView<'de, T> {
+    /// Validates `len` consecutive elements at `offset`.
+    ///
+    /// # Safety
+    ///
+    /// `offset` must be valid for reading `len` elements of `T`.
+    unsafe fn decode(decoder: &mut Decoder<'de>, offset: usize, len: usize, depth: Depth) -> Result<Self> {
+        let stride = T::inline_size(decoder.context);
+        let elements = if T::view_is_copy() {
+            debug_assert_eq!(stride, mem::size_of::<T::Owned>());
+            let buf: &'de [u8] = decoder.buf;
+            // Safety: The caller ensures the elements are in bounds.
+            let bytes = unsafe { buf.get_unchecked(offset..offset + len * stride) };
+            Elements::Copy { bytes, len }
+        } else {
+            let mut views = Vec::with_capacity(len);
+            for i in 0..len {
+                views.push(T::decode_view(decoder, offset + i * stride, depth)?);
+            }
+            Elements::Decoded(views)
+        };
+        Ok(VectorView {
+            elements,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        match &self.elements {
+            Elements::Copy { len, .. } => *len,
+            Elements::Decoded(views) => views.len(),
+        }
+    }
+
+    /// Returns true if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the view of the element at `index`, or `None` if it is out of
+    /// bounds.
+    pub fn get(&self, index: usize) -> Option<T::View>
+    where
+        T::View: Clone,
+    {
+        match &self.elements {
+            Elements::Copy { bytes, len } => {
+                if index >= *len {
+                    return None;
+                }
+                // Safety: `index < len`, so the element is within `bytes`,
+                // and `view_is_copy` guarantees `T::Owned` is plain data.
+                let value = unsafe {
+                    let ptr = bytes.as_ptr().add(index * mem::size_of::<T::Owned>());
+                    ptr::read_unaligned(ptr as *const T::Owned)
+                };
+                Some(T::view_from_copy(value))
+            }
+            Elements::Decoded(views) => views.get(index).cloned(),
+        }
+    }
+
+    /// Iterates over the element views.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = T::View> + '_
+    where
+        T::View: Clone,
+    {
+        (0..self.len()).map(|i| self.get(i).unwrap())
+    }
+}
+
+impl<'de> VectorView<'de, u8> {
+    /// Returns the bytes of a `vector<uint8>` or `array<uint8, N>` without
+    /// copying them.
+    pub fn as_bytes(&self) -> &'de [u8] {
+        match self.elements {
+            Elements::Copy { bytes, .. } => bytes,
+            Elements::Decoded(_) => unreachable!("u8 views are always copies"),
+        }
+    }
+}
+
+impl<'de, T: ViewMarker<'de>> Clone for VectorView<'de, T>
+where
+    T::View: Clone,
+{
+    fn clone(&self) -> Self {
+        let elements = match &self.elements {
+            Elements::Copy { bytes, len } => Elements::Copy { bytes, len: *len },
+            Elements::Decoded(views) => Elements::Decoded(views.clone()),
+        };
+        VectorView {
+            elements,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: ViewMarker<'de>> fmt::Debug for VectorView<'de, T>
+where
+    T::View: Clone + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'de, T: ViewMarker<'de>> PartialEq for VectorView<'de, T>
+where
+    T::View: Clone + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<'de, T: ViewMarker<'de>, const N: usize> ViewMarker<'de> for Vector<T, N> {
+    type View = VectorView<'de, T>;
+
+    #[inline]
+    unsafe fn decode_view(decoder: &mut Decoder<'de>, offset: usize, mut depth: Depth) -> Result<Self::View> {
+        decoder.debug_check_bounds::<Self>(offset);
+        let Some(len) = decode_vector_header(decoder, offset)? else {
+            return Err(Error::NotNullable);
+        };
+        check_vector_length(len, N)?;
+        // Empty vectors have no out-of-line block to read from.
+        if len == 0 {
+            return VectorView::decode(decoder, offset, 0, depth);
+        }
+        depth.increment()?;
+        let offset = decoder.out_of_line_offset(len * T::inline_size(decoder.context))?;
+        // Safety: `out_of_line_offset` does this bounds check.
+        VectorView::decode(decoder, offset, len, depth)
+    }
+}
+
+impl<'de, T: ViewMarker<'de>, const N: usize> ViewMarker<'de> for Array<T, N> {
+    type View = VectorView<'de, T>;
+
+    #[inline]
+    unsafe fn decode_view(decoder: &mut Decoder<'de>, offset: usize, depth: Depth) -> Result<Self::View> {
+        decoder.debug_check_bounds::<Self>(offset);
+        // Safety: The caller ensures the whole array is in bounds.
+        VectorView::decode(decoder, offset, N, depth)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Optionals
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'de, T: ViewMarker<'de>> ViewMarker<'de> for Optional<T> {
+    type View = Option<T::View>;
+
+    #[inline]
+    unsafe fn decode_view(decoder: &mut Decoder<'de>, offset: usize, depth: Depth) -> Result<Self::View> {
+        decoder.debug_check_bounds::<Self>(offset);
+        let inline_size = T::inline_size(decoder.context);
+        if check_for_presence(decoder, offset, inline_size) {
+            Ok(Some(T::decode_view(decoder, offset, depth)?))
+        } else {
+            decoder.check_padding(offset, inline_size)?;
+            Ok(None)
+        }
+    }
+}
+
+impl<'de, T: ViewMarker<'de>> ViewMarker<'de> for Boxed<T> {
+    type View = Option<T::View>;
+
+    #[inline]
+    unsafe fn decode_view(decoder: &mut Decoder<'de>, offset: usize, mut depth: Depth) -> Result<Self::View> {
+        decoder.debug_check_bounds::<Self>(offset);
+        match decoder.read_num::<u64>(offset) {
+            ALLOC_PRESENT_U64 => {
+                depth.increment()?;
+                let offset = decoder.out_of_line_offset(T::inline_size(decoder.context))?;
+                Ok(Some(T::decode_view(decoder, offset, depth)?))
+            }
+            ALLOC_ABSENT_U64 => Ok(None),
+            _ => Err(Error::InvalidPresenceIndicator),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use assert_matches::assert_matches;
+
+    const V2: Context = Context {
+        wire_format_version: WireFormatVersion::V2,
+    };
+
+    struct Blob {
+        name: String,
+        tags: Vec<String>,
+        data: Vec<u8>,
+        note: Option<String>,
+    }
+
+    crate::midl_struct! {
+        name: Blob,
+        members: [
+            name {
+                ty: UnboundedString,
+                index: 0,
+                typevar: T0,
+                offset_v1: 0,
+                offset_v2: 0,
+            },
+            tags {
+                ty: UnboundedVector<UnboundedString>,
+                index: 1,
+                typevar: T1,
+                offset_v1: 16,
+                offset_v2: 16,
+            },
+            data {
+                ty: UnboundedVector<u8>,
+                index: 2,
+                typevar: T2,
+                offset_v1: 32,
+                offset_v2: 32,
+            },
+            note {
+                ty: Optional<UnboundedString>,
+                index: 3,
+                typevar: T3,
+                offset_v1: 48,
+                offset_v2: 48,
+            },
+        ],
+        padding_v1: [],
+        padding_v2: [],
+        size_v1: 64,
+        size_v2: 64,
+        align_v1: 8,
+        align_v2: 8,
+    }
+
+    // What midlgen_rust generates for `Blob`.
+    #[derive(Debug, Clone, PartialEq)]
+    struct BlobView<'de> {
+        name: <UnboundedString as ViewMarker<'de>>::View,
+        tags: <UnboundedVector<UnboundedString> as ViewMarker<'de>>::View,
+        data: <UnboundedVector<u8> as ViewMarker<'de>>::View,
+        note: <Optional<UnboundedString> as ViewMarker<'de>>::View,
+    }
+
+    impl<'de> ViewMarker<'de> for Blob {
+        type View = BlobView<'de>;
+
+        unsafe fn decode_view(decoder: &mut Decoder<'de>, offset: usize, depth: Depth) -> Result<Self::View> {
+            decoder.debug_check_bounds::<Self>(offset);
+            Ok(BlobView {
+                name: <UnboundedString as ViewMarker<'de>>::decode_view(decoder, offset, depth)?,
+                tags: <UnboundedVector<UnboundedString> as ViewMarker<'de>>::decode_view(decoder, offset + 16, depth)?,
+                data: <UnboundedVector<u8> as ViewMarker<'de>>::decode_view(decoder, offset + 32, depth)?,
+                note: <Optional<UnboundedString> as ViewMarker<'de>>::decode_view(decoder, offset + 48, depth)?,
+            })
+        }
+    }
+
+    fn encode_blob(blob: &Blob) -> Vec<u8> {
+        let buf = &mut Vec::new();
+        Encoder::encode_with_context::<Blob>(V2, buf, &mut Vec::new(), blob).expect("encoding failed");
+        mem::take(buf)
+    }
+
+    #[test]
+    fn view_borrows_from_buffer() {
+        let bytes = encode_blob(&Blob {
+            name: "hello".to_string(),
+            tags: vec!["a".to_string(), "bc".to_string()],
+            data: vec![1, 2, 3, 4, 5],
+            note: None,
+        });
+        let view = Decoder::decode_view_with_context::<Blob>(V2, &bytes).expect("decoding failed");
+
+        assert_eq!(view.name, "hello");
+        assert_eq!(view.tags.iter().collect::<Vec<_>>(), ["a", "bc"]);
+        assert_eq!(view.data.as_bytes(), &[1, 2, 3, 4, 5]);
+        assert_eq!(view.note, None);
+
+        let range = bytes.as_ptr_range();
+        assert!(range.contains(&view.name.as_ptr()));
+        assert!(range.contains(&view.data.as_bytes().as_ptr()));
+    }
+
+    #[test]
+    fn view_matches_owned_decoding() {
+        let bytes = encode_blob(&Blob {
+            name: String::new(),
+            tags: vec![],
+            data: vec![],
+            note: Some("note".to_string()),
+        });
+        let view = Decoder::decode_view_with_context::<Blob>(V2, &bytes).expect("decoding failed");
+        let owned = &mut crate::new_empty!(Blob);
+        Decoder::decode_with_context::<Blob>(V2, &bytes, &mut [], owned).expect("decoding failed");
+
+        assert_eq!(view.name, owned.name);
+        assert!(view.tags.is_empty());
+        assert_eq!(view.data.as_bytes(), owned.data.as_slice());
+        assert_eq!(view.note, owned.note.as_deref());
+    }
+
+    #[test]
+    fn view_rejects_invalid_messages() {
+        let mut bytes = encode_blob(&Blob {
+            name: "hi".to_string(),
+            tags: vec![],
+            data: vec![],
+            note: None,
+        });
+
+        bytes.extend_from_slice(&[0; 8]);
+        assert_matches!(
+            Decoder::decode_view_with_context::<Blob>(V2, &bytes),
+            Err(Error::ExtraBytes)
+        );
+
+        bytes.truncate(bytes.len() - 8);
+        // The string body follows the 64 inline bytes.
+        bytes[64] = 0xff;
+        assert_matches!(
+            Decoder::decode_view_with_context::<Blob>(V2, &bytes),
+            Err(Error::Utf8Error)
+        );
+    }
+
+    #[test]
+    fn vector_view_of_non_copy_elements() {
+        let buf = &mut Vec::new();
+        let value: &[bool] = &[true, false, true];
+        Encoder::encode_with_context::<UnboundedVector<bool>>(V2, buf, &mut Vec::new(), value).unwrap();
+
+        let view = Decoder::decode_view_with_context::<UnboundedVector<bool>>(V2, buf).unwrap();
+        assert_eq!(view.iter().collect::<Vec<_>>(), [true, false, true]);
+        assert_eq!(view.get(3), None);
+
+        buf[16] = 2;
+        assert_matches!(
+            Decoder::decode_view_with_context::<UnboundedVector<bool>>(V2, buf),
+            Err(Error::InvalidBoolean)
+        );
+    }
+}
@@ -31,6 +31,10 @@ pub trait ProtocolMarker: Sized + Send + Sync + 'static {
     /// The type of the stream of requests coming into a server.
     type RequestStream: RequestStream<Protocol = Self>;
 
+    /// The type of the structure against which blocking FIDL requests are
+    /// made. Unlike `Proxy`, it does not need an executor.
+    type SynchronousProxy: SynchronousProxy<Protocol = Self>;
+
     /// The name of the protocol suitable for debug purposes.
     ///
     /// For discoverable protocols, this should be identical to
@@ -89,6 +93,29 @@ pub trait Proxy: Sized + Send + Sync {
     }
 }
 
+/// A type which allows querying a remote FIDL server over a channel, blocking
+/// the calling thread until each call completes or its deadline passes.
+pub trait SynchronousProxy: Sized + Send + Sync {
+    /// The async proxy for the same protocol.
+    type Proxy: Proxy<Protocol = Self::Protocol>;
+
+    /// The protocol which this `SynchronousProxy` controls.
+    type Protocol: ProtocolMarker<Proxy = Self::Proxy>;
+
+    /// Create a proxy over the given channel.
+    fn from_channel(inner: Channel) -> Self;
+
+    /// Convert the proxy back into a channel.
+    fn into_channel(self) -> Channel;
+
+    /// Get a reference to the proxy's underlying channel.
+    ///
+    /// This should only be used for non-effectful operations. Reading or
+    /// writing to the channel is unsafe because the proxy assumes it has
+    /// exclusive control over these operations.
+    fn as_channel(&self) -> &Channel;
+}
+
 /// A stream of requests coming into a FIDL server over a channel.
 pub trait RequestStream: Sized + Send + Stream + TryStream<Error = crate::Error> + Unpin {
     /// The protocol which this `RequestStream` serves.
@@ -269,6 +296,12 @@ impl<T: ProtocolMarker> ClientEnd<T> {
             AsyncChannel::from_channel(self.inner).map_err(Error::AsyncChannel)?,
         ))
     }
+
+    /// Convert the `ClientEnd` into a `SynchronousProxy` through which
+    /// blocking FIDL calls may be made.
+    pub fn into_sync_proxy(self) -> T::SynchronousProxy {
+        T::SynchronousProxy::from_channel(self.inner)
+    }
 }
 
 impl<T> AsHandleRef for ClientEnd<T> {
@@ -418,6 +451,15 @@ pub fn create_proxy<T: ProtocolMarker>() -> Result<(T::Proxy, ServerEnd<T>), Err
     Ok((client.into_proxy()?, server))
 }
 
+/// Create a synchronous client proxy and a server endpoint connected to it by a
+/// channel.
+///
+/// Useful for tools which make a few blocking calls and have no executor.
+pub fn create_sync_proxy<T: ProtocolMarker>() -> (T::SynchronousProxy, ServerEnd<T>) {
+    let (client, server) = create_endpoints();
+    (client.into_sync_proxy(), server)
+}
+
 /// Create a request stream and a client endpoint connected to it by a channel.
 ///
 /// Useful for sending channel handles to calls that take arguments
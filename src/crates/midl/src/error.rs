@@ -191,6 +191,14 @@ pub enum Error {
     #[error("Invalid response with txid 0.")]
     InvalidResponseTxid,
 
+    /// A one-way request was received with a non-zero txid, or a two-way
+    /// request with txid 0.
+    #[error("Invalid txid for a {method_type:?} request.")]
+    InvalidRequestTxid {
+        /// Whether the request was for a one-way or a two-way method.
+        method_type: crate::encoding::MethodType,
+    },
+
     /// A presence indicator (for out-of-line data or a handle) had a value
     /// other than all 0x00 or all 0xFF.
     #[error("Invalid presence indicator.")]
@@ -310,4 +318,13 @@ impl Error {
             _ => false,
         }
     }
+
+    /// Returns `true` if a synchronous call or `wait_for_event` gave up
+    /// because its deadline passed.
+    pub fn is_timed_out(&self) -> bool {
+        matches!(
+            self,
+            Error::ClientCall(fx_status::Status::TIMED_OUT) | Error::ClientEvent(fx_status::Status::TIMED_OUT)
+        )
+    }
 }
@@ -15,8 +15,10 @@ pub mod client;
 pub mod endpoints;
 pub mod epitaph;
 pub mod handle;
+pub mod marker;
 pub mod prelude;
 pub mod server;
+pub mod trace;
 
 mod error;
 pub use self::error::{Error, Result};
@@ -0,0 +1,10 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Marker types used by generated bindings.
+
+/// A hidden member of generated tables that keeps them from being constructed or matched
+/// exhaustively, so that adding a member to a table is not a source-breaking change.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourceBreaking;
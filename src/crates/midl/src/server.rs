@@ -6,13 +6,16 @@
 //! An implementation of a server for a fidl interface.
 use {
     crate::{
-        encoding::{DynamicFlags, Encode, TransactionHeader, TransactionMessage, TransactionMessageType, TypeMarker},
+        encoding::{
+            DynamicFlags, EmptyStruct, Encode, Encoder, Flexible, FlexibleType, FrameworkErr, TransactionHeader,
+            TransactionMessage, TransactionMessageType, TypeMarker,
+        },
         epitaph,
-        handle::HandleDisposition,
+        handle::{HandleDisposition, HandleInfo},
         AsyncChannel, Error,
     },
     fiber_status as fx_status,
-    futures::task::{AtomicWaker, Context},
+    futures::task::{AtomicWaker, Context, Poll},
     std::sync::atomic::{self, AtomicBool},
 };
 
@@ -22,17 +25,20 @@ pub struct ServeInner {
     waker: AtomicWaker,
     shutdown: AtomicBool,
     channel: AsyncChannel,
+    // The `ProtocolMarker::DEBUG_NAME` for the protocol being served.
+    protocol_name: &'static str,
 }
 
 impl ServeInner {
     /// Create a new set of server innards.
-    pub fn new(channel: AsyncChannel) -> Self {
+    pub fn new(channel: AsyncChannel, protocol_name: &'static str) -> Self {
         let waker = AtomicWaker::new();
         let shutdown = AtomicBool::new(false);
         ServeInner {
             waker,
             shutdown,
             channel,
+            protocol_name,
         }
     }
 
@@ -74,6 +80,20 @@ impl ServeInner {
         self.shutdown.load(atomic::Ordering::Relaxed)
     }
 
+    /// Read the next request from the channel into `bytes` and `handles`.
+    pub fn poll_read_request(
+        &self,
+        cx: &mut Context<'_>,
+        bytes: &mut Vec<u8>,
+        handles: &mut Vec<HandleInfo>,
+    ) -> Poll<Result<(), fx_status::Status>> {
+        let result = self.channel.read_etc(cx, bytes, handles);
+        if let Poll::Ready(Ok(())) = result {
+            crate::trace::trace_request(self.protocol_name, bytes, handles.len());
+        }
+        result
+    }
+
     /// Send an encodable message to the client.
     pub fn send<T: TypeMarker, const OVERFLOWABLE: bool>(
         &self,
@@ -91,8 +111,31 @@ impl ServeInner {
         })
     }
 
+    /// Reply to a two-way request the server does not understand with a
+    /// framework error.
+    ///
+    /// Called from within the request stream while the thread-local decode
+    /// buffers are borrowed, so the reply is encoded into buffers of its own.
+    pub fn send_framework_err(
+        &self,
+        framework_err: FrameworkErr,
+        tx_id: u32,
+        ordinal: u64,
+        dynamic_flags: DynamicFlags,
+    ) -> Result<(), Error> {
+        let msg = TransactionMessage {
+            header: TransactionHeader::new(tx_id, ordinal, dynamic_flags),
+            body: Flexible::<()>::FrameworkErr(framework_err),
+        };
+        let mut bytes = Vec::new();
+        let mut handles = Vec::new();
+        Encoder::encode::<TransactionMessageType<FlexibleType<EmptyStruct>>>(&mut bytes, &mut handles, msg)?;
+        self.send_raw_msg(&bytes, &mut handles)
+    }
+
     /// Send a raw message to the client.
     pub fn send_raw_msg(&self, bytes: &[u8], handles: &mut Vec<HandleDisposition<'_>>) -> Result<(), Error> {
+        crate::trace::trace_reply(self.protocol_name, bytes, handles.len());
         match self.channel.write_etc(bytes, handles) {
             Ok(()) | Err(fx_status::Status::PEER_CLOSED) => Ok(()),
             Err(e) => Err(Error::ServerResponseWrite(e.into())),
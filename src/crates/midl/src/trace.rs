@@ -0,0 +1,150 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Debug tracing of MIDL messages.
+//!
+//! `client::Client`, `client::sync::Client` and `ServeInner` report every
+//! message they send or receive here. When the `midl::trace` target is enabled
+//! at DEBUG level, each message is logged with its protocol, kind and header.
+//! The body is rendered by the installed [`MessageFormatter`], e.g. one built
+//! from midlc JSON IR by the `midlcat` tool, or as hex if none is installed or
+//! it does not know the message. To trace a single protocol, filter on the
+//! `protocol` field:
+//!
+//! ```text
+//! RUST_LOG='midl::trace[{protocol=meshx.data.Kv}]=debug'
+//! ```
+
+use {
+    crate::encoding::{decode_transaction_header, TransactionHeader},
+    std::{fmt, fmt::Write as _, sync::RwLock},
+};
+
+/// The tracing target under which messages are logged.
+pub const TARGET: &str = "midl::trace";
+
+/// What a traced message is, from the protocol's point of view.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MessageKind {
+    /// A one-way or two-way request from a client.
+    Request,
+    /// A server's reply to a two-way request.
+    Response,
+    /// A message the server sent unprompted.
+    Event,
+}
+
+impl MessageKind {
+    /// The kind of a message sent by a server, based on its transaction id.
+    fn from_server(header: &TransactionHeader) -> Self {
+        if header.tx_id() == 0 {
+            MessageKind::Event
+        } else {
+            MessageKind::Response
+        }
+    }
+}
+
+impl fmt::Display for MessageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MessageKind::Request => "request",
+            MessageKind::Response => "response",
+            MessageKind::Event => "event",
+        })
+    }
+}
+
+/// Renders message bodies for tracing.
+pub trait MessageFormatter: Send + Sync {
+    /// Renders the body of a message sent over `protocol`, which is the
+    /// protocol's `ProtocolMarker::DEBUG_NAME`. Returns `None` if the method
+    /// is unknown, in which case the body is logged as hex.
+    fn format(&self, protocol: &str, kind: MessageKind, header: &TransactionHeader, body: &[u8]) -> Option<String>;
+}
+
+static FORMATTER: RwLock<Option<Box<dyn MessageFormatter>>> = RwLock::new(None);
+
+/// Installs the formatter used to render traced messages, replacing any
+/// previous one.
+pub fn set_message_formatter(formatter: impl MessageFormatter + 'static) {
+    *FORMATTER.write().unwrap() = Some(Box::new(formatter));
+}
+
+/// Formats `bytes` as lines of 16 space-separated hex bytes, which the
+/// `midlcat` tool accepts as input.
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 3);
+    for (i, line) in bytes.chunks(16).enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        for (j, byte) in line.iter().enumerate() {
+            if j > 0 {
+                out.push(' ');
+            }
+            let _ = write!(out, "{:02x}", byte);
+        }
+    }
+    out
+}
+
+/// Traces a message sent by a client.
+pub(crate) fn trace_request(protocol: &'static str, bytes: &[u8], num_handles: usize) {
+    trace_message(protocol, |_| MessageKind::Request, bytes, num_handles)
+}
+
+/// Traces a message sent by a server.
+pub(crate) fn trace_reply(protocol: &'static str, bytes: &[u8], num_handles: usize) {
+    trace_message(protocol, MessageKind::from_server, bytes, num_handles)
+}
+
+fn trace_message(
+    protocol: &'static str,
+    kind: impl FnOnce(&TransactionHeader) -> MessageKind,
+    bytes: &[u8],
+    num_handles: usize,
+) {
+    if !tracing::enabled!(target: TARGET, tracing::Level::DEBUG) {
+        return;
+    }
+    let Ok((header, body)) = decode_transaction_header(bytes) else {
+        tracing::debug!(target: TARGET, protocol, "malformed message header\n{}", hex_dump(bytes));
+        return;
+    };
+    if header.is_epitaph() {
+        return;
+    }
+    let kind = kind(&header);
+    let formatted = FORMATTER
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|formatter| formatter.format(protocol, kind, &header, body));
+    tracing::debug!(
+        target: TARGET,
+        protocol,
+        %kind,
+        tx_id = header.tx_id(),
+        ordinal = format_args!("{:#x}", header.ordinal()),
+        num_handles,
+        "{}",
+        formatted.unwrap_or_else(|| hex_dump(body)),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hex_dump_wraps_every_16_bytes() {
+        let bytes: Vec<u8> = (0..18).collect();
+        assert_eq!(
+            hex_dump(&bytes),
+            "00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f\n10 11"
+        );
+        assert_eq!(hex_dump(&[]), "");
+    }
+}
@@ -0,0 +1,1446 @@
+// WARNING: This file is machine generated by gidl. Do not edit.
+// Regenerate it with `cargo run -p gidl -- --language rust`, see tools/midl/gidl.
+
+#![allow(dead_code, non_camel_case_types)]
+
+use assert_matches::assert_matches;
+use midl::encoding::*;
+use midl::Error;
+
+const V2: Context = Context {
+    wire_format_version: WireFormatVersion::V2,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct Arrays {
+    pub a: [u16; 3],
+    pub strings: [String; 2],
+}
+
+midl::midl_struct! {
+    name: Arrays,
+    members: [
+        a {
+            ty: Array<u16, 3>,
+            index: 0,
+            typevar: T0,
+            offset_v1: 0,
+            offset_v2: 0,
+        },
+        strings {
+            ty: Array<UnboundedString, 2>,
+            index: 1,
+            typevar: T1,
+            offset_v1: 8,
+            offset_v2: 8,
+        },
+    ],
+    padding_v1: [
+        {
+            ty: u64,
+            offset: 0,
+            mask: 0xffff000000000000,
+        },
+    ],
+    padding_v2: [
+        {
+            ty: u64,
+            offset: 0,
+            mask: 0xffff000000000000,
+        },
+    ],
+    size_v1: 40,
+    size_v2: 40,
+    align_v1: 8,
+    align_v2: 8,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Bools {
+    pub a: bool,
+    pub b: bool,
+}
+
+midl::midl_struct! {
+    name: Bools,
+    members: [
+        a {
+            ty: bool,
+            index: 0,
+            typevar: T0,
+            offset_v1: 0,
+            offset_v2: 0,
+        },
+        b {
+            ty: bool,
+            index: 1,
+            typevar: T1,
+            offset_v1: 1,
+            offset_v2: 1,
+        },
+    ],
+    padding_v1: [
+    ],
+    padding_v2: [
+    ],
+    size_v1: 2,
+    size_v2: 2,
+    align_v1: 1,
+    align_v2: 1,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Boxes {
+    pub present: Option<Box<Inner>>,
+    pub absent: Option<Box<Inner>>,
+}
+
+midl::midl_struct! {
+    name: Boxes,
+    members: [
+        present {
+            ty: Boxed<Inner>,
+            index: 0,
+            typevar: T0,
+            offset_v1: 0,
+            offset_v2: 0,
+        },
+        absent {
+            ty: Boxed<Inner>,
+            index: 1,
+            typevar: T1,
+            offset_v1: 8,
+            offset_v2: 8,
+        },
+    ],
+    padding_v1: [
+    ],
+    padding_v2: [
+    ],
+    size_v1: 16,
+    size_v2: 16,
+    align_v1: 8,
+    align_v2: 8,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FlexibleChoice {
+    Small(u32),
+    #[doc(hidden)]
+    __SourceBreaking { ordinal: u64 },
+}
+
+impl FlexibleChoice {
+    pub fn ordinal(&self) -> u64 {
+        match *self {
+            Self::Small(_) => 1,
+            Self::__SourceBreaking { ordinal } => ordinal,
+        }
+    }
+}
+
+midl::midl_union! {
+    name: FlexibleChoice,
+    members: [
+        Small {
+            ty: u32,
+            ordinal: 1,
+        },
+    ],
+    unknown_member: __SourceBreaking,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FlexibleHolder {
+    pub choice: FlexibleChoice,
+}
+
+midl::midl_struct! {
+    name: FlexibleHolder,
+    members: [
+        choice {
+            ty: FlexibleChoice,
+            index: 0,
+            typevar: T0,
+            offset_v1: 0,
+            offset_v2: 0,
+        },
+    ],
+    padding_v1: [
+    ],
+    padding_v2: [
+    ],
+    size_v1: 16,
+    size_v2: 16,
+    align_v1: 8,
+    align_v2: 8,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Floats {
+    pub f32: f32,
+    pub f64: f64,
+}
+
+midl::midl_struct! {
+    name: Floats,
+    members: [
+        f32 {
+            ty: f32,
+            index: 0,
+            typevar: T0,
+            offset_v1: 0,
+            offset_v2: 0,
+        },
+        f64 {
+            ty: f64,
+            index: 1,
+            typevar: T1,
+            offset_v1: 8,
+            offset_v2: 8,
+        },
+    ],
+    padding_v1: [
+        {
+            ty: u64,
+            offset: 0,
+            mask: 0xffffffff00000000,
+        },
+    ],
+    padding_v2: [
+        {
+            ty: u64,
+            offset: 0,
+            mask: 0xffffffff00000000,
+        },
+    ],
+    size_v1: 16,
+    size_v2: 16,
+    align_v1: 8,
+    align_v2: 8,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Inner {
+    pub a: u8,
+    pub b: u32,
+}
+
+midl::midl_struct! {
+    name: Inner,
+    members: [
+        a {
+            ty: u8,
+            index: 0,
+            typevar: T0,
+            offset_v1: 0,
+            offset_v2: 0,
+        },
+        b {
+            ty: u32,
+            index: 1,
+            typevar: T1,
+            offset_v1: 4,
+            offset_v2: 4,
+        },
+    ],
+    padding_v1: [
+        {
+            ty: u32,
+            offset: 0,
+            mask: 0xffffff00,
+        },
+    ],
+    padding_v2: [
+        {
+            ty: u32,
+            offset: 0,
+            mask: 0xffffff00,
+        },
+    ],
+    size_v1: 8,
+    size_v2: 8,
+    align_v1: 4,
+    align_v2: 4,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Ints {
+    pub i8: i8,
+    pub i16: i16,
+    pub i32: i32,
+    pub i64: i64,
+    pub u8: u8,
+    pub u16: u16,
+    pub u32: u32,
+    pub u64: u64,
+}
+
+midl::midl_struct! {
+    name: Ints,
+    members: [
+        i8 {
+            ty: i8,
+            index: 0,
+            typevar: T0,
+            offset_v1: 0,
+            offset_v2: 0,
+        },
+        i16 {
+            ty: i16,
+            index: 1,
+            typevar: T1,
+            offset_v1: 2,
+            offset_v2: 2,
+        },
+        i32 {
+            ty: i32,
+            index: 2,
+            typevar: T2,
+            offset_v1: 4,
+            offset_v2: 4,
+        },
+        i64 {
+            ty: i64,
+            index: 3,
+            typevar: T3,
+            offset_v1: 8,
+            offset_v2: 8,
+        },
+        u8 {
+            ty: u8,
+            index: 4,
+            typevar: T4,
+            offset_v1: 16,
+            offset_v2: 16,
+        },
+        u16 {
+            ty: u16,
+            index: 5,
+            typevar: T5,
+            offset_v1: 18,
+            offset_v2: 18,
+        },
+        u32 {
+            ty: u32,
+            index: 6,
+            typevar: T6,
+            offset_v1: 20,
+            offset_v2: 20,
+        },
+        u64 {
+            ty: u64,
+            index: 7,
+            typevar: T7,
+            offset_v1: 24,
+            offset_v2: 24,
+        },
+    ],
+    padding_v1: [
+        {
+            ty: u64,
+            offset: 0,
+            mask: 0xff00,
+        },
+        {
+            ty: u64,
+            offset: 16,
+            mask: 0xff00,
+        },
+    ],
+    padding_v2: [
+        {
+            ty: u64,
+            offset: 0,
+            mask: 0xff00,
+        },
+        {
+            ty: u64,
+            offset: 16,
+            mask: 0xff00,
+        },
+    ],
+    size_v1: 32,
+    size_v2: 32,
+    align_v1: 8,
+    align_v2: 8,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Outer {
+    pub inner: Inner,
+    pub tail: u16,
+}
+
+midl::midl_struct! {
+    name: Outer,
+    members: [
+        inner {
+            ty: Inner,
+            index: 0,
+            typevar: T0,
+            offset_v1: 0,
+            offset_v2: 0,
+        },
+        tail {
+            ty: u16,
+            index: 1,
+            typevar: T1,
+            offset_v1: 8,
+            offset_v2: 8,
+        },
+    ],
+    padding_v1: [
+        {
+            ty: u32,
+            offset: 8,
+            mask: 0xffff0000,
+        },
+    ],
+    padding_v2: [
+        {
+            ty: u32,
+            offset: 8,
+            mask: 0xffff0000,
+        },
+    ],
+    size_v1: 12,
+    size_v2: 12,
+    align_v1: 4,
+    align_v2: 4,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Settings {
+    pub a: Option<u32>,
+    pub b: Option<u64>,
+    pub name: Option<String>,
+}
+
+impl Settings {
+    pub const EMPTY: Self = Self {
+        a: None,
+        b: None,
+        name: None,
+    };
+}
+
+midl::midl_table! {
+    name: Settings,
+    members: [
+        a {
+            ty: u32,
+            ordinal: 1,
+        },
+        b {
+            ty: u64,
+            ordinal: 2,
+        },
+        name {
+            ty: UnboundedString,
+            ordinal: 4,
+        },
+    ],
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StrictChoice {
+    Small(u32),
+    Big(u64),
+    Text(String),
+}
+
+impl StrictChoice {
+    pub fn ordinal(&self) -> u64 {
+        match *self {
+            Self::Small(_) => 1,
+            Self::Big(_) => 2,
+            Self::Text(_) => 3,
+        }
+    }
+}
+
+midl::midl_union! {
+    name: StrictChoice,
+    members: [
+        Small {
+            ty: u32,
+            ordinal: 1,
+        },
+        Big {
+            ty: u64,
+            ordinal: 2,
+        },
+        Text {
+            ty: UnboundedString,
+            ordinal: 3,
+        },
+    ],
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Strings {
+    pub s: String,
+    pub bounded: String,
+    pub opt: Option<String>,
+}
+
+midl::midl_struct! {
+    name: Strings,
+    members: [
+        s {
+            ty: UnboundedString,
+            index: 0,
+            typevar: T0,
+            offset_v1: 0,
+            offset_v2: 0,
+        },
+        bounded {
+            ty: BoundedString<3>,
+            index: 1,
+            typevar: T1,
+            offset_v1: 16,
+            offset_v2: 16,
+        },
+        opt {
+            ty: Optional<UnboundedString>,
+            index: 2,
+            typevar: T2,
+            offset_v1: 32,
+            offset_v2: 32,
+        },
+    ],
+    padding_v1: [
+    ],
+    padding_v2: [
+    ],
+    size_v1: 48,
+    size_v2: 48,
+    align_v1: 8,
+    align_v2: 8,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Tables {
+    pub settings: Settings,
+}
+
+midl::midl_struct! {
+    name: Tables,
+    members: [
+        settings {
+            ty: Settings,
+            index: 0,
+            typevar: T0,
+            offset_v1: 0,
+            offset_v2: 0,
+        },
+    ],
+    padding_v1: [
+    ],
+    padding_v2: [
+    ],
+    size_v1: 16,
+    size_v2: 16,
+    align_v1: 8,
+    align_v2: 8,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Unions {
+    pub choice: StrictChoice,
+    pub opt: Option<Box<StrictChoice>>,
+}
+
+midl::midl_struct! {
+    name: Unions,
+    members: [
+        choice {
+            ty: StrictChoice,
+            index: 0,
+            typevar: T0,
+            offset_v1: 0,
+            offset_v2: 0,
+        },
+        opt {
+            ty: OptionalUnion<StrictChoice>,
+            index: 1,
+            typevar: T1,
+            offset_v1: 16,
+            offset_v2: 16,
+        },
+    ],
+    padding_v1: [
+    ],
+    padding_v2: [
+    ],
+    size_v1: 32,
+    size_v2: 32,
+    align_v1: 8,
+    align_v2: 8,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Vectors {
+    pub data: Vec<u8>,
+    pub bounded: Vec<u16>,
+    pub nested: Vec<String>,
+    pub opt: Option<Vec<u32>>,
+}
+
+midl::midl_struct! {
+    name: Vectors,
+    members: [
+        data {
+            ty: UnboundedVector<u8>,
+            index: 0,
+            typevar: T0,
+            offset_v1: 0,
+            offset_v2: 0,
+        },
+        bounded {
+            ty: Vector<u16, 2>,
+            index: 1,
+            typevar: T1,
+            offset_v1: 16,
+            offset_v2: 16,
+        },
+        nested {
+            ty: UnboundedVector<UnboundedString>,
+            index: 2,
+            typevar: T2,
+            offset_v1: 32,
+            offset_v2: 32,
+        },
+        opt {
+            ty: Optional<UnboundedVector<u32>>,
+            index: 3,
+            typevar: T3,
+            offset_v1: 48,
+            offset_v2: 48,
+        },
+    ],
+    padding_v1: [
+    ],
+    padding_v2: [
+    ],
+    size_v1: 64,
+    size_v2: 64,
+    align_v1: 8,
+    align_v2: 8,
+}
+
+// primitives.gidl:24
+#[test]
+fn bools_encode() {
+    let value = &Bools { a: true, b: false, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Bools>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn bools_decode() {
+    let bytes: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Bools);
+    Decoder::decode_with_context::<Bools>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Bools { a: true, b: false, });
+}
+
+// primitives.gidl:31
+#[test]
+fn ints_min_encode() {
+    let value = &Ints { i8: -128, i16: -32768, i32: -2147483648, i64: -9223372036854775808, u8: 0, u16: 0, u32: 0, u64: 0, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Ints>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x80, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x80,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn ints_min_decode() {
+    let bytes: &[u8] = &[
+        0x80, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x80,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Ints);
+    Decoder::decode_with_context::<Ints>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Ints { i8: -128, i16: -32768, i32: -2147483648, i64: -9223372036854775808, u8: 0, u16: 0, u32: 0, u64: 0, });
+}
+
+// primitives.gidl:44
+#[test]
+fn ints_max_encode() {
+    let value = &Ints { i8: 127, i16: 32767, i32: 2147483647, i64: 9223372036854775807, u8: 255, u16: 65535, u32: 4294967295, u64: 18446744073709551615, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Ints>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x7f, 0x00, 0xff, 0x7f, 0xff, 0xff, 0xff, 0x7f,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f,
+        0xff, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn ints_max_decode() {
+    let bytes: &[u8] = &[
+        0x7f, 0x00, 0xff, 0x7f, 0xff, 0xff, 0xff, 0x7f,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f,
+        0xff, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    ];
+    let value = &mut midl::new_empty!(Ints);
+    Decoder::decode_with_context::<Ints>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Ints { i8: 127, i16: 32767, i32: 2147483647, i64: 9223372036854775807, u8: 255, u16: 65535, u32: 4294967295, u64: 18446744073709551615, });
+}
+
+// primitives.gidl:57
+#[test]
+fn floats_encode() {
+    let value = &Floats { f32: 1.5, f64: -2.5, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Floats>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x00, 0x00, 0xc0, 0x3f, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0xc0,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn floats_decode() {
+    let bytes: &[u8] = &[
+        0x00, 0x00, 0xc0, 0x3f, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0xc0,
+    ];
+    let value = &mut midl::new_empty!(Floats);
+    Decoder::decode_with_context::<Floats>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Floats { f32: 1.5, f64: -2.5, });
+}
+
+// primitives.gidl:65
+#[test]
+fn bool_invalid_decode_failure() {
+    let bytes: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Bools);
+    let result = Decoder::decode_with_context::<Bools>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::InvalidBoolean));
+}
+
+// primitives.gidl:73
+#[test]
+fn ints_non_zero_padding_decode_failure() {
+    let bytes: &[u8] = &[
+        0x01, 0x01, 0x02, 0x00, 0x03, 0x00, 0x00, 0x00,
+        0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x05, 0x00, 0x06, 0x00, 0x07, 0x00, 0x00, 0x00,
+        0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Ints);
+    let result = Decoder::decode_with_context::<Ints>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::NonZeroPadding { .. }));
+}
+
+// primitives.gidl:84
+#[test]
+fn bools_too_few_bytes_decode_failure() {
+    let bytes: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Bools);
+    let result = Decoder::decode_with_context::<Bools>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::OutOfRange));
+}
+
+// primitives.gidl:92
+#[test]
+fn bools_too_many_bytes_decode_failure() {
+    let bytes: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Bools);
+    let result = Decoder::decode_with_context::<Bools>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::ExtraBytes));
+}
+
+// strings_vectors.gidl:21
+#[test]
+fn strings_encode() {
+    let value = &Strings { s: "hello".to_string(), bounded: "abc".to_string(), opt: None, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Strings>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x00, 0x00, 0x00,
+        0x61, 0x62, 0x63, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn strings_decode() {
+    let bytes: &[u8] = &[
+        0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x00, 0x00, 0x00,
+        0x61, 0x62, 0x63, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Strings);
+    Decoder::decode_with_context::<Strings>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Strings { s: "hello".to_string(), bounded: "abc".to_string(), opt: None, });
+}
+
+// strings_vectors.gidl:32
+#[test]
+fn strings_present_optional_encode() {
+    let value = &Strings { s: "".to_string(), bounded: "".to_string(), opt: Some("héllo".to_string()), };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Strings>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x68, 0xc3, 0xa9, 0x6c, 0x6c, 0x6f, 0x00, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn strings_present_optional_decode() {
+    let bytes: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x68, 0xc3, 0xa9, 0x6c, 0x6c, 0x6f, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Strings);
+    Decoder::decode_with_context::<Strings>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Strings { s: "".to_string(), bounded: "".to_string(), opt: Some("héllo".to_string()), });
+}
+
+// strings_vectors.gidl:42
+#[test]
+fn string_too_long_encode_failure() {
+    let value = &Strings { s: "".to_string(), bounded: "abcd".to_string(), opt: None, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    let result = Encoder::encode_with_context::<Strings>(V2, buf, handle_buf, value);
+    assert_matches!(result, Err(Error::StringTooLong { .. }));
+}
+
+// strings_vectors.gidl:47
+#[test]
+fn string_too_long_decode_decode_failure() {
+    let bytes: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x61, 0x62, 0x63, 0x64, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Strings);
+    let result = Decoder::decode_with_context::<Strings>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::StringTooLong { .. }));
+}
+
+// strings_vectors.gidl:58
+#[test]
+fn string_absent_decode_failure() {
+    let bytes: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Strings);
+    let result = Decoder::decode_with_context::<Strings>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::NotNullable));
+}
+
+// strings_vectors.gidl:68
+#[test]
+fn string_invalid_presence_decode_failure() {
+    let bytes: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Strings);
+    let result = Decoder::decode_with_context::<Strings>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::InvalidPresenceIndicator));
+}
+
+// strings_vectors.gidl:78
+#[test]
+fn string_non_zero_padding_decode_failure() {
+    let bytes: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x61, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Strings);
+    let result = Decoder::decode_with_context::<Strings>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::NonZeroPadding { .. }));
+}
+
+// strings_vectors.gidl:89
+#[test]
+fn vectors_encode() {
+    let value = &Vectors { data: vec![1, 2, 3], bounded: vec![4, 5], nested: vec!["a".to_string()], opt: Some(vec![6]), };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Vectors>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x01, 0x02, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x04, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x61, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn vectors_decode() {
+    let bytes: &[u8] = &[
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x01, 0x02, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x04, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x61, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Vectors);
+    Decoder::decode_with_context::<Vectors>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Vectors { data: vec![1, 2, 3], bounded: vec![4, 5], nested: vec!["a".to_string()], opt: Some(vec![6]), });
+}
+
+// strings_vectors.gidl:104
+#[test]
+fn vectors_empty_encode() {
+    let value = &Vectors { data: vec![], bounded: vec![], nested: vec![], opt: None, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Vectors>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn vectors_empty_decode() {
+    let bytes: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Vectors);
+    Decoder::decode_with_context::<Vectors>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Vectors { data: vec![], bounded: vec![], nested: vec![], opt: None, });
+}
+
+// strings_vectors.gidl:114
+#[test]
+fn vector_too_long_encode_failure() {
+    let value = &Vectors { data: vec![], bounded: vec![1, 2, 3], nested: vec![], opt: None, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    let result = Encoder::encode_with_context::<Vectors>(V2, buf, handle_buf, value);
+    assert_matches!(result, Err(Error::VectorTooLong { .. }));
+}
+
+// strings_vectors.gidl:119
+#[test]
+fn vector_too_long_decode_decode_failure() {
+    let bytes: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Vectors);
+    let result = Decoder::decode_with_context::<Vectors>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::VectorTooLong { .. }));
+}
+
+// strings_vectors.gidl:131
+#[test]
+fn vector_non_empty_null_body_decode_failure() {
+    let bytes: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Vectors);
+    let result = Decoder::decode_with_context::<Vectors>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::UnexpectedNullRef));
+}
+
+// strings_vectors.gidl:142
+#[test]
+fn arrays_encode() {
+    let value = &Arrays { a: [1, 2, 3], strings: ["x".to_string(), "yz".to_string()], };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Arrays>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x78, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x79, 0x7a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn arrays_decode() {
+    let bytes: &[u8] = &[
+        0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x78, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x79, 0x7a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Arrays);
+    Decoder::decode_with_context::<Arrays>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Arrays { a: [1, 2, 3], strings: ["x".to_string(), "yz".to_string()], });
+}
+
+// structs.gidl:18
+#[test]
+fn nested_struct_encode() {
+    let value = &Outer { inner: Inner { a: 1, b: 2, }, tail: 3, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Outer>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn nested_struct_decode() {
+    let bytes: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Outer);
+    Decoder::decode_with_context::<Outer>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Outer { inner: Inner { a: 1, b: 2, }, tail: 3, });
+}
+
+// structs.gidl:26
+#[test]
+fn nested_struct_padding_decode_failure() {
+    let bytes: &[u8] = &[
+        0x01, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Outer);
+    let result = Decoder::decode_with_context::<Outer>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::NonZeroPadding { .. }));
+}
+
+// structs.gidl:35
+#[test]
+fn trailing_struct_padding_decode_failure() {
+    let bytes: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+    ];
+    let value = &mut midl::new_empty!(Outer);
+    let result = Decoder::decode_with_context::<Outer>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::NonZeroPadding { .. }));
+}
+
+// structs.gidl:44
+#[test]
+fn boxes_encode() {
+    let value = &Boxes { present: Some(Box::new(Inner { a: 7, b: 8, })), absent: None, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Boxes>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x07, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn boxes_decode() {
+    let bytes: &[u8] = &[
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x07, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Boxes);
+    Decoder::decode_with_context::<Boxes>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Boxes { present: Some(Box::new(Inner { a: 7, b: 8, })), absent: None, });
+}
+
+// structs.gidl:53
+#[test]
+fn box_invalid_presence_decode_failure() {
+    let bytes: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Boxes);
+    let result = Decoder::decode_with_context::<Boxes>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::InvalidPresenceIndicator));
+}
+
+// unions_tables.gidl:32
+#[test]
+fn union_inline_encode() {
+    let value = &Unions { choice: StrictChoice::Small(7), opt: None, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Unions>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn union_inline_decode() {
+    let bytes: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Unions);
+    Decoder::decode_with_context::<Unions>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Unions { choice: StrictChoice::Small(7), opt: None, });
+}
+
+// unions_tables.gidl:40
+#[test]
+fn union_out_of_line_encode() {
+    let value = &Unions { choice: StrictChoice::Big(1), opt: Some(Box::new(StrictChoice::Text("hi".to_string()))), };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Unions>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x68, 0x69, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn union_out_of_line_decode() {
+    let bytes: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x68, 0x69, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Unions);
+    Decoder::decode_with_context::<Unions>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Unions { choice: StrictChoice::Big(1), opt: Some(Box::new(StrictChoice::Text("hi".to_string()))), });
+}
+
+// unions_tables.gidl:51
+#[test]
+fn strict_union_unknown_ordinal_decode_failure() {
+    let bytes: &[u8] = &[
+        0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Unions);
+    let result = Decoder::decode_with_context::<Unions>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::UnknownUnionTag));
+}
+
+// unions_tables.gidl:60
+#[test]
+fn union_absent_decode_failure() {
+    let bytes: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Unions);
+    let result = Decoder::decode_with_context::<Unions>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::NotNullable));
+}
+
+// unions_tables.gidl:69
+#[test]
+fn union_invalid_inline_marker_decode_failure() {
+    let bytes: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Unions);
+    let result = Decoder::decode_with_context::<Unions>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::InvalidInlineMarkerInEnvelope));
+}
+
+// unions_tables.gidl:78
+#[test]
+fn union_inline_bit_for_large_value_decode_failure() {
+    let bytes: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Unions);
+    let result = Decoder::decode_with_context::<Unions>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::InvalidInlineBitInEnvelope));
+}
+
+// unions_tables.gidl:87
+#[test]
+fn union_wrong_num_bytes_decode_failure() {
+    let bytes: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Unions);
+    let result = Decoder::decode_with_context::<Unions>(V2, bytes, &mut [], value);
+    assert_matches!(result, Err(Error::InvalidNumBytesInEnvelope));
+}
+
+// unions_tables.gidl:97
+#[test]
+fn flexible_union_encode() {
+    let value = &FlexibleHolder { choice: FlexibleChoice::Small(1), };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<FlexibleHolder>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn flexible_union_decode() {
+    let bytes: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+    ];
+    let value = &mut midl::new_empty!(FlexibleHolder);
+    Decoder::decode_with_context::<FlexibleHolder>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &FlexibleHolder { choice: FlexibleChoice::Small(1), });
+}
+
+// unions_tables.gidl:104
+#[test]
+fn table_encode() {
+    let value = &Tables { settings: Settings { a: Some(1), name: Some("hi".to_string()), ..Settings::EMPTY }, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Tables>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x68, 0x69, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn table_decode() {
+    let bytes: &[u8] = &[
+        0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x68, 0x69, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Tables);
+    Decoder::decode_with_context::<Tables>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Tables { settings: Settings { a: Some(1), name: Some("hi".to_string()), ..Settings::EMPTY }, });
+}
+
+// unions_tables.gidl:117
+#[test]
+fn table_empty_encode() {
+    let value = &Tables { settings: Settings { ..Settings::EMPTY }, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Tables>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn table_empty_decode() {
+    let bytes: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    ];
+    let value = &mut midl::new_empty!(Tables);
+    Decoder::decode_with_context::<Tables>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Tables { settings: Settings { ..Settings::EMPTY }, });
+}
+
+// unions_tables.gidl:124
+#[test]
+fn table_out_of_line_field_encode() {
+    let value = &Tables { settings: Settings { b: Some(2), ..Settings::EMPTY }, };
+    let buf = &mut Vec::new();
+    let handle_buf = &mut Vec::new();
+    Encoder::encode_with_context::<Tables>(V2, buf, handle_buf, value).expect("encoding failed");
+    let expected: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn table_out_of_line_field_decode() {
+    let bytes: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let value = &mut midl::new_empty!(Tables);
+    Decoder::decode_with_context::<Tables>(V2, bytes, &mut [], value).expect("decoding failed");
+    assert_eq!(value, &Tables { settings: Settings { b: Some(2), ..Settings::EMPTY }, });
+}
@@ -0,0 +1,590 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Rust bindings for the `meshx.component.decl` MIDL library.
+//!
+//! midlgen can not build these bindings yet, so they are maintained by hand, in the shape midlgen
+//! produces: tables are structs of `Option`s, unions are enums with an `ordinal()`, and the
+//! `midl_*` macros implement the wire format.  Keep them in sync with
+//! `sdk/midl/meshx.component.decl`.
+
+#![allow(nonstandard_style)]
+
+use midl::{
+    encoding::{BoundedString, UnboundedVector},
+    midl_enum, midl_struct, midl_table, midl_union,
+};
+
+pub const MAX_NAME_LENGTH: usize = 255;
+pub const MAX_PROPERTY_LENGTH: usize = 1024;
+pub const MAX_PATH_LENGTH: usize = 1024;
+pub const MAX_URL_LENGTH: usize = 4096;
+pub const MAX_URL_SCHEME_LENGTH: usize = 100;
+
+type Name = BoundedString<MAX_NAME_LENGTH>;
+type Path = BoundedString<MAX_PATH_LENGTH>;
+type Url = BoundedString<MAX_URL_LENGTH>;
+
+/// Defines a MIDL table.  The fields must be listed in ordinal order.
+macro_rules! table {
+    (
+        $(#[$attr:meta])*
+        $name:ident {
+            $($ordinal:literal: $field:ident: $owned:ty as $ty:ty,)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct $name {
+            $(pub $field: Option<$owned>,)*
+        }
+
+        impl $name {
+            pub const EMPTY: Self = Self {
+                $($field: None,)*
+            };
+        }
+
+        impl midl::encoding::Persistable for $name {}
+
+        midl_table! {
+            name: $name,
+            members: [$(
+                $field {
+                    ty: $ty,
+                    ordinal: $ordinal,
+                },
+            )*],
+        }
+    };
+}
+
+/// Defines a flexible MIDL union.
+macro_rules! flexible_union {
+    (
+        $(#[$attr:meta])*
+        $name:ident {
+            $($ordinal:literal: $variant:ident($owned:ty as $ty:ty),)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum $name {
+            $($variant($owned),)*
+            #[doc(hidden)]
+            __SourceBreaking { ordinal: u64 },
+        }
+
+        impl $name {
+            pub fn ordinal(&self) -> u64 {
+                match self {
+                    $(Self::$variant(_) => $ordinal,)*
+                    Self::__SourceBreaking { ordinal } => *ordinal,
+                }
+            }
+        }
+
+        impl midl::encoding::Persistable for $name {}
+
+        midl_union! {
+            name: $name,
+            members: [$(
+                $variant {
+                    ty: $ty,
+                    ordinal: $ordinal,
+                },
+            )*],
+            unknown_member: __SourceBreaking,
+        }
+    };
+}
+
+/// Defines a strict MIDL enum of `uint32`.  The member with the lowest value must come first.
+macro_rules! strict_enum {
+    (
+        $(#[$attr:meta])*
+        $name:ident {
+            $min_member:ident = $min_value:literal,
+            $($member:ident = $value:literal,)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(u32)]
+        pub enum $name {
+            $min_member = $min_value,
+            $($member = $value,)*
+        }
+
+        impl $name {
+            pub fn from_primitive(prim: u32) -> Option<Self> {
+                match prim {
+                    $min_value => Some(Self::$min_member),
+                    $($value => Some(Self::$member),)*
+                    _ => None,
+                }
+            }
+
+            pub const fn into_primitive(self) -> u32 {
+                self as u32
+            }
+        }
+
+        midl_enum! {
+            name: $name,
+            prim_ty: u32,
+            strict: true,
+            min_member: $min_member,
+        }
+    };
+}
+
+/// Defines a MIDL struct with a single `name` member.
+macro_rules! named_ref {
+    ($name:ident) => {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub struct $name {
+            pub name: String,
+        }
+
+        midl_struct! {
+            name: $name,
+            members: [
+                name {
+                    ty: Name,
+                    index: 0,
+                    typevar: T0,
+                    offset_v1: 0,
+                    offset_v2: 0,
+                },
+            ],
+            padding_v1: [
+            ],
+            padding_v2: [
+            ],
+            size_v1: 16,
+            size_v2: 16,
+            align_v1: 8,
+            align_v2: 8,
+        }
+    };
+}
+
+/// Defines an empty MIDL struct.
+macro_rules! empty_ref {
+    ($name:ident) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name;
+
+        midl_struct! {
+            empty: true,
+            name: $name,
+        }
+    };
+}
+
+table! {
+    /// A component manifest, as produced by `cmc compile` into `meta/<component>.cm`.
+    Component {
+        1: program: Program as Program,
+        2: uses: Vec<Use> as UnboundedVector<Use>,
+        3: exposes: Vec<Expose> as UnboundedVector<Expose>,
+        4: offers: Vec<Offer> as UnboundedVector<Offer>,
+        5: capabilities: Vec<Capability> as UnboundedVector<Capability>,
+        6: children: Vec<Child> as UnboundedVector<Child>,
+        7: collections: Vec<Collection> as UnboundedVector<Collection>,
+        8: environments: Vec<Environment> as UnboundedVector<Environment>,
+    }
+}
+
+table! {
+    Program {
+        1: runner: String as Name,
+        2: info: Vec<ProgramProperty> as UnboundedVector<ProgramProperty>,
+    }
+}
+
+table! {
+    ProgramProperty {
+        1: key: String as BoundedString<MAX_PROPERTY_LENGTH>,
+        2: value: ProgramValue as ProgramValue,
+    }
+}
+
+flexible_union! {
+    ProgramValue {
+        1: Str(String as BoundedString<MAX_PROPERTY_LENGTH>),
+        2: StrVec(Vec<String> as UnboundedVector<BoundedString<MAX_PROPERTY_LENGTH>>),
+    }
+}
+
+flexible_union! {
+    /// A reference to a component, or to one of the capabilities it declares.
+    Ref {
+        1: Parent(ParentRef as ParentRef),
+        2: Self_(SelfRef as SelfRef),
+        3: Child(ChildRef as ChildRef),
+        4: Collection(CollectionRef as CollectionRef),
+        5: Framework(FrameworkRef as FrameworkRef),
+        6: Capability(CapabilityRef as CapabilityRef),
+        7: Void(VoidRef as VoidRef),
+    }
+}
+
+empty_ref!(ParentRef);
+empty_ref!(SelfRef);
+named_ref!(ChildRef);
+named_ref!(CollectionRef);
+empty_ref!(FrameworkRef);
+named_ref!(CapabilityRef);
+empty_ref!(VoidRef);
+
+strict_enum! {
+    DependencyType {
+        Strong = 1,
+        Weak = 2,
+    }
+}
+
+strict_enum! {
+    Availability {
+        Required = 1,
+        Optional = 2,
+    }
+}
+
+strict_enum! {
+    StartupMode {
+        Lazy = 0,
+        Eager = 1,
+    }
+}
+
+strict_enum! {
+    OnTerminate {
+        None = 0,
+        Reboot = 1,
+    }
+}
+
+strict_enum! {
+    Durability {
+        Transient = 2,
+        SingleRun = 3,
+    }
+}
+
+strict_enum! {
+    EnvironmentExtends {
+        None = 0,
+        Realm = 1,
+    }
+}
+
+flexible_union! {
+    Capability {
+        1: Protocol(Protocol as Protocol),
+        2: Directory(Directory as Directory),
+        3: Storage(Storage as Storage),
+        4: Service(Service as Service),
+        5: Runner(Runner as Runner),
+        6: Resolver(Resolver as Resolver),
+    }
+}
+
+table! {
+    Protocol {
+        1: name: String as Name,
+        2: source_path: String as Path,
+    }
+}
+
+table! {
+    Directory {
+        1: name: String as Name,
+        2: source_path: String as Path,
+    }
+}
+
+table! {
+    Storage {
+        1: name: String as Name,
+        2: source: Ref as Ref,
+        3: backing_dir: String as Name,
+    }
+}
+
+table! {
+    Service {
+        1: name: String as Name,
+        2: source_path: String as Path,
+    }
+}
+
+table! {
+    Runner {
+        1: name: String as Name,
+        2: source_path: String as Path,
+    }
+}
+
+table! {
+    Resolver {
+        1: name: String as Name,
+        2: source_path: String as Path,
+    }
+}
+
+flexible_union! {
+    Use {
+        1: Protocol(UseProtocol as UseProtocol),
+        2: Directory(UseDirectory as UseDirectory),
+        3: Storage(UseStorage as UseStorage),
+        4: Service(UseService as UseService),
+    }
+}
+
+table! {
+    UseProtocol {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target_path: String as Path,
+        4: dependency_type: DependencyType as DependencyType,
+        5: availability: Availability as Availability,
+    }
+}
+
+table! {
+    UseDirectory {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target_path: String as Path,
+        4: dependency_type: DependencyType as DependencyType,
+        5: availability: Availability as Availability,
+    }
+}
+
+table! {
+    UseStorage {
+        1: source_name: String as Name,
+        2: target_path: String as Path,
+        3: availability: Availability as Availability,
+    }
+}
+
+table! {
+    UseService {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target_path: String as Path,
+        4: dependency_type: DependencyType as DependencyType,
+        5: availability: Availability as Availability,
+    }
+}
+
+flexible_union! {
+    Offer {
+        1: Protocol(OfferProtocol as OfferProtocol),
+        2: Directory(OfferDirectory as OfferDirectory),
+        3: Storage(OfferStorage as OfferStorage),
+        4: Service(OfferService as OfferService),
+        5: Runner(OfferRunner as OfferRunner),
+        6: Resolver(OfferResolver as OfferResolver),
+    }
+}
+
+table! {
+    OfferProtocol {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target: Ref as Ref,
+        4: target_name: String as Name,
+        5: dependency_type: DependencyType as DependencyType,
+        6: availability: Availability as Availability,
+    }
+}
+
+table! {
+    OfferDirectory {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target: Ref as Ref,
+        4: target_name: String as Name,
+        5: dependency_type: DependencyType as DependencyType,
+        6: availability: Availability as Availability,
+    }
+}
+
+table! {
+    OfferStorage {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target: Ref as Ref,
+        4: target_name: String as Name,
+        5: availability: Availability as Availability,
+    }
+}
+
+table! {
+    OfferService {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target: Ref as Ref,
+        4: target_name: String as Name,
+        5: dependency_type: DependencyType as DependencyType,
+        6: availability: Availability as Availability,
+    }
+}
+
+table! {
+    OfferRunner {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target: Ref as Ref,
+        4: target_name: String as Name,
+    }
+}
+
+table! {
+    OfferResolver {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target: Ref as Ref,
+        4: target_name: String as Name,
+    }
+}
+
+flexible_union! {
+    Expose {
+        1: Protocol(ExposeProtocol as ExposeProtocol),
+        2: Directory(ExposeDirectory as ExposeDirectory),
+        3: Service(ExposeService as ExposeService),
+        4: Runner(ExposeRunner as ExposeRunner),
+        5: Resolver(ExposeResolver as ExposeResolver),
+    }
+}
+
+table! {
+    ExposeProtocol {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target: Ref as Ref,
+        4: target_name: String as Name,
+    }
+}
+
+table! {
+    ExposeDirectory {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target: Ref as Ref,
+        4: target_name: String as Name,
+    }
+}
+
+table! {
+    ExposeService {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target: Ref as Ref,
+        4: target_name: String as Name,
+    }
+}
+
+table! {
+    ExposeRunner {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target: Ref as Ref,
+        4: target_name: String as Name,
+    }
+}
+
+table! {
+    ExposeResolver {
+        1: source: Ref as Ref,
+        2: source_name: String as Name,
+        3: target: Ref as Ref,
+        4: target_name: String as Name,
+    }
+}
+
+table! {
+    Child {
+        1: name: String as Name,
+        2: url: String as Url,
+        3: startup: StartupMode as StartupMode,
+        4: on_terminate: OnTerminate as OnTerminate,
+        5: environment: String as Name,
+    }
+}
+
+table! {
+    Collection {
+        1: name: String as Name,
+        2: durability: Durability as Durability,
+        3: environment: String as Name,
+    }
+}
+
+table! {
+    Environment {
+        1: name: String as Name,
+        2: extends: EnvironmentExtends as EnvironmentExtends,
+        3: runners: Vec<RunnerRegistration> as UnboundedVector<RunnerRegistration>,
+        4: resolvers: Vec<ResolverRegistration> as UnboundedVector<ResolverRegistration>,
+        5: stop_timeout_ms: u32 as u32,
+    }
+}
+
+table! {
+    RunnerRegistration {
+        1: source_name: String as Name,
+        2: source: Ref as Ref,
+        3: target_name: String as Name,
+    }
+}
+
+table! {
+    ResolverRegistration {
+        1: resolver: String as Name,
+        2: source: Ref as Ref,
+        3: scheme: String as BoundedString<MAX_URL_SCHEME_LENGTH>,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persist_round_trip() {
+        let component = Component {
+            program: Some(Program {
+                runner: Some("wasm".to_string()),
+                info: Some(vec![ProgramProperty {
+                    key: Some("args".to_string()),
+                    value: Some(ProgramValue::StrVec(vec!["--verbose".to_string()])),
+                }]),
+            }),
+            uses: Some(vec![Use::Protocol(UseProtocol {
+                source: Some(Ref::Child(ChildRef { name: "server".to_string() })),
+                source_name: Some("echo.Echo".to_string()),
+                target_path: Some("/svc/echo.Echo".to_string()),
+                dependency_type: Some(DependencyType::Weak),
+                ..UseProtocol::EMPTY
+            })]),
+            children: Some(vec![Child {
+                name: Some("server".to_string()),
+                url: Some("mxpkg://meshx.co/echo#meta/echo.cm".to_string()),
+                startup: Some(StartupMode::Eager),
+                ..Child::EMPTY
+            }]),
+            ..Component::EMPTY
+        };
+
+        let bytes = midl::encoding::persist(&component).unwrap();
+        assert_eq!(midl::encoding::unpersist::<Component>(&bytes).unwrap(), component);
+    }
+}
@@ -0,0 +1,1471 @@
+
+// WARNING: This file is machine generated by midlgen.
+
+#![allow(
+    unused_parens, // one-element-tuple-case is not a tuple
+    unused_mut, // not all args require mutation, but many do
+    unused_imports, // not every library needs every import
+    nonstandard_style, // auto-caps does its best, but is not always successful
+    clippy::all, // the templates favour uniform output over idiomatic code
+)]
+
+#![recursion_limit="512"]
+
+use fiber as fx;
+
+use {
+    bitflags::bitflags,
+    fiber_status as fx_status,
+    futures::future::{self, MaybeDone, TryFutureExt},
+    midl::{
+        endpoints::{ControlHandle as _, Responder as _},
+        client::QueryResponseFut,
+    },
+};
+
+
+#[derive(Debug, PartialEq)]
+pub struct CloneableClone2Request{
+    pub request: midl::endpoints::ServerEnd<CloneableMarker>,
+}
+
+impl midl::encoding::Standalone for CloneableClone2Request {}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CloseableCloseResponse;
+impl midl::encoding::Persistable for CloseableCloseResponse {}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryableQueryResponse{
+    pub protocol: Vec<u8>,
+}
+
+impl midl::encoding::Persistable for QueryableQueryResponse {}
+
+/// Borrowed view of [`QueryableQueryResponse`], decoded with
+/// `midl::encoding::Decoder::decode_view` without copying strings or vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryableQueryResponseView<'de> {
+    pub protocol: <midl::encoding::UnboundedVector<u8> as midl::encoding::ViewMarker<'de>>::View,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CloneableMarker;
+
+impl midl::endpoints::ProtocolMarker for CloneableMarker {
+    type Proxy = CloneableProxy;
+    type RequestStream = CloneableRequestStream;
+    type SynchronousProxy = CloneableSynchronousProxy;
+
+    const DEBUG_NAME: &'static str = "meshx.unknown.Cloneable";
+}
+
+
+
+pub trait CloneableProxyInterface: Send + Sync {
+
+    fn r#clone_2(&self,
+        request: midl::endpoints::ServerEnd<CloneableMarker>,
+    )
+    -> Result<(), midl::Error>;
+}
+
+#[derive(Debug)]
+pub struct CloneableSynchronousProxy {
+    client: midl::client::sync::Client,
+}
+
+impl midl::endpoints::SynchronousProxy for CloneableSynchronousProxy {
+    type Proxy = CloneableProxy;
+    type Protocol = CloneableMarker;
+
+    fn from_channel(inner: midl::Channel) -> Self {
+        Self::new(inner)
+    }
+
+    fn into_channel(self) -> midl::Channel {
+        self.client.into_channel()
+    }
+
+    fn as_channel(&self) -> &midl::Channel {
+        self.client.as_channel()
+    }
+}
+
+impl CloneableSynchronousProxy {
+    pub fn new(channel: midl::Channel) -> Self {
+        let protocol_name = <CloneableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME;
+        Self { client: midl::client::sync::Client::new(channel, protocol_name) }
+    }
+
+    pub fn into_channel(self) -> midl::Channel {
+        self.client.into_channel()
+    }
+
+    /// Waits until an event arrives and returns it. It is safe for other
+    /// threads to make concurrent requests while waiting for an event.
+    pub fn wait_for_event(&self, deadline: fx::Time) -> Result<CloneableEvent, midl::Error> {
+        CloneableEvent::decode(self.client.wait_for_event(deadline)?)
+    }
+
+    pub fn r#clone_2(
+        &self,
+        mut request: midl::endpoints::ServerEnd<CloneableMarker>,
+    ) -> Result<(), midl::Error> {
+        self.client.send::<CloneableClone2Request, false>(
+            (<midl::encoding::Endpoint<midl::endpoints::ServerEnd<CloneableMarker>> as midl::encoding::ResourceTypeMarker>::take_or_borrow(&mut request),),
+            3264813957505738898,
+            midl::encoding::DynamicFlags::empty(),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CloneableProxy {
+    client: midl::client::Client,
+}
+
+impl midl::endpoints::Proxy for CloneableProxy {
+    type Protocol = CloneableMarker;
+
+    fn from_channel(inner: midl::AsyncChannel) -> Self {
+        Self::new(inner)
+    }
+
+    fn into_channel(self) -> Result<::midl::AsyncChannel, Self> {
+        self.client.into_channel().map_err(|client| Self { client })
+    }
+
+    fn as_channel(&self) -> &::midl::AsyncChannel {
+        self.client.as_channel()
+    }
+}
+
+impl CloneableProxy {
+    /// Create a new Proxy for meshx.unknown.Cloneable.
+    pub fn new(channel: midl::AsyncChannel) -> Self {
+        let protocol_name = <CloneableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME;
+        Self { client: midl::client::Client::new(channel, protocol_name) }
+    }
+
+    /// Get a Stream of events from the remote end of the protocol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the event stream was already taken.
+    pub fn take_event_stream(&self) -> CloneableEventStream {
+        CloneableEventStream {
+            event_receiver: self.client.take_event_receiver(),
+        }
+    }
+
+    pub fn r#clone_2(
+        &self,
+        mut request: midl::endpoints::ServerEnd<CloneableMarker>,
+    )
+    -> Result<(), midl::Error> {
+        CloneableProxyInterface::r#clone_2(self,
+            request,
+        )
+    }
+}
+
+impl CloneableProxyInterface for CloneableProxy {
+    fn r#clone_2(
+        &self,
+        mut request: midl::endpoints::ServerEnd<CloneableMarker>,
+    ) -> Result<(), midl::Error> {
+        self.client.send::<CloneableClone2Request, false>(
+            (<midl::encoding::Endpoint<midl::endpoints::ServerEnd<CloneableMarker>> as midl::encoding::ResourceTypeMarker>::take_or_borrow(&mut request),),
+            3264813957505738898,
+            midl::encoding::DynamicFlags::empty(),
+        )
+    }
+}
+
+pub struct CloneableEventStream {
+    event_receiver: midl::client::EventReceiver,
+}
+
+impl std::marker::Unpin for CloneableEventStream {}
+
+impl futures::stream::FusedStream for CloneableEventStream {
+    fn is_terminated(&self) -> bool {
+        self.event_receiver.is_terminated()
+    }
+}
+
+impl futures::Stream for CloneableEventStream {
+    type Item = Result<CloneableEvent, midl::Error>;
+
+    fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>)
+        -> std::task::Poll<Option<Self::Item>>
+    {
+        match futures::ready!(
+            futures::stream::StreamExt::poll_next_unpin(&mut self.event_receiver, cx)?
+        ) {
+            Some(buf) => std::task::Poll::Ready(Some(CloneableEvent::decode(buf))),
+            None => std::task::Poll::Ready(None),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CloneableEvent {
+
+}
+
+impl CloneableEvent {
+
+    /// Decodes a message buffer as a [`CloneableEvent`].
+    fn decode(mut buf: midl::MessageBufEtc) -> Result<CloneableEvent, midl::Error> {
+        let (bytes, _handles) = buf.split_mut();
+        let (tx_header, _body_bytes) = midl::encoding::decode_transaction_header(bytes)?;
+        debug_assert_eq!(tx_header.tx_id(), 0);
+        match tx_header.ordinal() {
+            _ => Err(midl::Error::UnknownOrdinal {
+                ordinal: tx_header.ordinal(),
+                protocol_name: <CloneableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME,
+            })
+        }
+    }
+}
+
+/// A Stream of incoming requests for meshx.unknown.Cloneable.
+pub struct CloneableRequestStream {
+    inner: std::sync::Arc<midl::ServeInner>,
+    is_terminated: bool,
+}
+
+impl std::marker::Unpin for CloneableRequestStream {}
+
+impl futures::stream::FusedStream for CloneableRequestStream {
+    fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+}
+
+impl midl::endpoints::RequestStream for CloneableRequestStream {
+    type Protocol = CloneableMarker;
+    type ControlHandle = CloneableControlHandle;
+
+    fn from_channel(channel: midl::AsyncChannel) -> Self {
+        Self {
+            inner: std::sync::Arc::new(midl::ServeInner::new(channel, <CloneableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME)),
+            is_terminated: false,
+        }
+    }
+
+    fn control_handle(&self) -> Self::ControlHandle {
+        CloneableControlHandle { inner: self.inner.clone() }
+    }
+
+    fn into_inner(self) -> (::std::sync::Arc<midl::ServeInner>, bool) {
+        (self.inner, self.is_terminated)
+    }
+
+    fn from_inner(inner: std::sync::Arc<midl::ServeInner>, is_terminated: bool) -> Self {
+        Self { inner, is_terminated }
+    }
+}
+
+impl futures::Stream for CloneableRequestStream {
+    type Item = Result<CloneableRequest, midl::Error>;
+
+    fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>)
+        -> std::task::Poll<Option<Self::Item>>
+    {
+        let this = &mut *self;
+        if this.inner.poll_shutdown(cx) {
+            this.is_terminated = true;
+            return std::task::Poll::Ready(None);
+        }
+        if this.is_terminated {
+            panic!("polled CloneableRequestStream after completion");
+        }
+        midl::encoding::with_tls_decode_buf(|bytes, handles| {
+            match this.inner.poll_read_request(cx, bytes, handles) {
+                std::task::Poll::Ready(Ok(())) => {},
+                std::task::Poll::Pending => return std::task::Poll::Pending,
+                std::task::Poll::Ready(Err(fx_status::Status::PEER_CLOSED)) => {
+                    this.is_terminated = true;
+                    return std::task::Poll::Ready(None);
+                }
+                std::task::Poll::Ready(Err(e)) => return std::task::Poll::Ready(Some(Err(midl::Error::ServerRequestRead(e)))),
+            }
+
+            // A message has been received from the channel
+            let (header, _body_bytes) = midl::encoding::decode_transaction_header(bytes)?;
+
+            std::task::Poll::Ready(Some(match header.ordinal() {
+                3264813957505738898 => {
+                    header.validate_request_tx_id(midl::encoding::MethodType::OneWay)?;
+                    let mut out = midl::new_empty!(CloneableClone2Request);
+                    midl::encoding::Decoder::decode_into::<CloneableClone2Request>(&header, _body_bytes, handles, &mut out)?;
+                    let control_handle = CloneableControlHandle {
+                        inner: this.inner.clone(),
+                    };
+                    Ok(CloneableRequest::Clone2 {
+                        request: out.request,
+                        control_handle,
+                    })
+                }
+                _ => Err(midl::Error::UnknownOrdinal {
+                    ordinal: header.ordinal(),
+                    protocol_name: <CloneableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME,
+                }),
+            }))
+        })
+    }
+}
+
+/// Provides a means of duplicating a connection.
+///
+/// See the composing protocol's documentation for a description of
+/// connection-scoped state, if any.
+#[derive(Debug)]
+pub enum CloneableRequest {
+    Clone2 {
+        request: midl::endpoints::ServerEnd<CloneableMarker>,
+        control_handle: CloneableControlHandle,
+    },
+
+}
+
+impl CloneableRequest {
+    #[allow(irrefutable_let_patterns)]
+    pub fn into_clone_2(self) -> Option<(
+        midl::endpoints::ServerEnd<CloneableMarker>,
+        CloneableControlHandle
+    )> {
+        if let CloneableRequest::Clone2 {
+            request,
+            control_handle,
+        } = self {
+            Some((
+                request,
+                control_handle
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Name of the method defined in MIDL
+    pub fn method_name(&self) -> &'static str {
+        match *self {
+            CloneableRequest::Clone2 { .. } => "clone_2",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CloneableControlHandle {
+    inner: std::sync::Arc<midl::ServeInner>,
+}
+
+impl midl::endpoints::ControlHandle for CloneableControlHandle {
+    fn shutdown(&self) {
+        self.inner.shutdown()
+    }
+
+    fn shutdown_with_epitaph(&self, status: fx_status::Status) {
+        self.inner.shutdown_with_epitaph(status)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.channel().is_closed()
+    }
+
+    fn on_closed<'a>(&'a self) -> midl::OnSignals<'a> {
+        self.inner.channel().on_closed()
+    }
+}
+
+impl CloneableControlHandle {
+}
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CloseableMarker;
+
+impl midl::endpoints::ProtocolMarker for CloseableMarker {
+    type Proxy = CloseableProxy;
+    type RequestStream = CloseableRequestStream;
+    type SynchronousProxy = CloseableSynchronousProxy;
+
+    const DEBUG_NAME: &'static str = "meshx.unknown.Closeable";
+}
+
+
+pub type CloseableCloseResult = Result<(), i32>;
+
+pub trait CloseableProxyInterface: Send + Sync {
+    type CloseResponseFut: std::future::Future<Output = Result<CloseableCloseResult, midl::Error>> + Send;
+
+    fn r#close(&self,
+    )
+    -> Self::CloseResponseFut;
+}
+
+#[derive(Debug)]
+pub struct CloseableSynchronousProxy {
+    client: midl::client::sync::Client,
+}
+
+impl midl::endpoints::SynchronousProxy for CloseableSynchronousProxy {
+    type Proxy = CloseableProxy;
+    type Protocol = CloseableMarker;
+
+    fn from_channel(inner: midl::Channel) -> Self {
+        Self::new(inner)
+    }
+
+    fn into_channel(self) -> midl::Channel {
+        self.client.into_channel()
+    }
+
+    fn as_channel(&self) -> &midl::Channel {
+        self.client.as_channel()
+    }
+}
+
+impl CloseableSynchronousProxy {
+    pub fn new(channel: midl::Channel) -> Self {
+        let protocol_name = <CloseableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME;
+        Self { client: midl::client::sync::Client::new(channel, protocol_name) }
+    }
+
+    pub fn into_channel(self) -> midl::Channel {
+        self.client.into_channel()
+    }
+
+    /// Waits until an event arrives and returns it. It is safe for other
+    /// threads to make concurrent requests while waiting for an event.
+    pub fn wait_for_event(&self, deadline: fx::Time) -> Result<CloseableEvent, midl::Error> {
+        CloseableEvent::decode(self.client.wait_for_event(deadline)?)
+    }
+
+    /// Terminates the connection.
+    ///
+    /// After calling `Close`, the client must not send any other requests.
+    ///
+    /// Servers, after sending the status response, should close the connection
+    /// regardless of status and without sending an epitaph.
+    ///
+    /// Closing the client end of the channel should be semantically equivalent
+    /// to calling `Close` without knowing when the close has completed or its
+    /// status.
+    pub fn r#close(
+        &self,
+        ___deadline: fx::Time,
+    ) -> Result<CloseableCloseResult, midl::Error> {
+        let out = self.client.send_query::<
+            midl::encoding::EmptyPayload,
+            midl::encoding::ResultType<midl::encoding::EmptyStruct, i32>,
+            false,
+            false,
+        >(
+            (),
+            8198755819659321800,
+            midl::encoding::DynamicFlags::empty(),
+            ___deadline,
+        )?
+;
+        Ok(out)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CloseableProxy {
+    client: midl::client::Client,
+}
+
+impl midl::endpoints::Proxy for CloseableProxy {
+    type Protocol = CloseableMarker;
+
+    fn from_channel(inner: midl::AsyncChannel) -> Self {
+        Self::new(inner)
+    }
+
+    fn into_channel(self) -> Result<::midl::AsyncChannel, Self> {
+        self.client.into_channel().map_err(|client| Self { client })
+    }
+
+    fn as_channel(&self) -> &::midl::AsyncChannel {
+        self.client.as_channel()
+    }
+}
+
+impl CloseableProxy {
+    /// Create a new Proxy for meshx.unknown.Closeable.
+    pub fn new(channel: midl::AsyncChannel) -> Self {
+        let protocol_name = <CloseableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME;
+        Self { client: midl::client::Client::new(channel, protocol_name) }
+    }
+
+    /// Get a Stream of events from the remote end of the protocol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the event stream was already taken.
+    pub fn take_event_stream(&self) -> CloseableEventStream {
+        CloseableEventStream {
+            event_receiver: self.client.take_event_receiver(),
+        }
+    }
+
+    /// Terminates the connection.
+    ///
+    /// After calling `Close`, the client must not send any other requests.
+    ///
+    /// Servers, after sending the status response, should close the connection
+    /// regardless of status and without sending an epitaph.
+    ///
+    /// Closing the client end of the channel should be semantically equivalent
+    /// to calling `Close` without knowing when the close has completed or its
+    /// status.
+    pub fn r#close(
+        &self,
+    )
+    -> midl::client::QueryResponseFut<CloseableCloseResult> {
+        CloseableProxyInterface::r#close(self,
+        )
+    }
+}
+
+impl CloseableProxyInterface for CloseableProxy {
+    type CloseResponseFut = midl::client::QueryResponseFut<CloseableCloseResult>;
+    fn r#close(
+        &self,
+    ) -> Self::CloseResponseFut {
+        fn _decode(mut _buf: Result<midl::MessageBufEtc, midl::Error>) -> Result<CloseableCloseResult, midl::Error> {
+            let out = midl::client::decode_transaction_body::<
+                midl::encoding::ResultType<midl::encoding::EmptyStruct, i32>,
+                false,
+            >(_buf?)?
+;
+            Ok(out)
+        }
+        self.client.send_query_and_decode::<
+            midl::encoding::EmptyPayload,
+            CloseableCloseResult,
+            false,
+        >(
+            (),
+            8198755819659321800,
+            midl::encoding::DynamicFlags::empty(),
+            _decode,
+        )
+    }
+}
+
+pub struct CloseableEventStream {
+    event_receiver: midl::client::EventReceiver,
+}
+
+impl std::marker::Unpin for CloseableEventStream {}
+
+impl futures::stream::FusedStream for CloseableEventStream {
+    fn is_terminated(&self) -> bool {
+        self.event_receiver.is_terminated()
+    }
+}
+
+impl futures::Stream for CloseableEventStream {
+    type Item = Result<CloseableEvent, midl::Error>;
+
+    fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>)
+        -> std::task::Poll<Option<Self::Item>>
+    {
+        match futures::ready!(
+            futures::stream::StreamExt::poll_next_unpin(&mut self.event_receiver, cx)?
+        ) {
+            Some(buf) => std::task::Poll::Ready(Some(CloseableEvent::decode(buf))),
+            None => std::task::Poll::Ready(None),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CloseableEvent {
+
+}
+
+impl CloseableEvent {
+
+    /// Decodes a message buffer as a [`CloseableEvent`].
+    fn decode(mut buf: midl::MessageBufEtc) -> Result<CloseableEvent, midl::Error> {
+        let (bytes, _handles) = buf.split_mut();
+        let (tx_header, _body_bytes) = midl::encoding::decode_transaction_header(bytes)?;
+        debug_assert_eq!(tx_header.tx_id(), 0);
+        match tx_header.ordinal() {
+            _ => Err(midl::Error::UnknownOrdinal {
+                ordinal: tx_header.ordinal(),
+                protocol_name: <CloseableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME,
+            })
+        }
+    }
+}
+
+/// A Stream of incoming requests for meshx.unknown.Closeable.
+pub struct CloseableRequestStream {
+    inner: std::sync::Arc<midl::ServeInner>,
+    is_terminated: bool,
+}
+
+impl std::marker::Unpin for CloseableRequestStream {}
+
+impl futures::stream::FusedStream for CloseableRequestStream {
+    fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+}
+
+impl midl::endpoints::RequestStream for CloseableRequestStream {
+    type Protocol = CloseableMarker;
+    type ControlHandle = CloseableControlHandle;
+
+    fn from_channel(channel: midl::AsyncChannel) -> Self {
+        Self {
+            inner: std::sync::Arc::new(midl::ServeInner::new(channel, <CloseableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME)),
+            is_terminated: false,
+        }
+    }
+
+    fn control_handle(&self) -> Self::ControlHandle {
+        CloseableControlHandle { inner: self.inner.clone() }
+    }
+
+    fn into_inner(self) -> (::std::sync::Arc<midl::ServeInner>, bool) {
+        (self.inner, self.is_terminated)
+    }
+
+    fn from_inner(inner: std::sync::Arc<midl::ServeInner>, is_terminated: bool) -> Self {
+        Self { inner, is_terminated }
+    }
+}
+
+impl futures::Stream for CloseableRequestStream {
+    type Item = Result<CloseableRequest, midl::Error>;
+
+    fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>)
+        -> std::task::Poll<Option<Self::Item>>
+    {
+        let this = &mut *self;
+        if this.inner.poll_shutdown(cx) {
+            this.is_terminated = true;
+            return std::task::Poll::Ready(None);
+        }
+        if this.is_terminated {
+            panic!("polled CloseableRequestStream after completion");
+        }
+        midl::encoding::with_tls_decode_buf(|bytes, handles| {
+            match this.inner.poll_read_request(cx, bytes, handles) {
+                std::task::Poll::Ready(Ok(())) => {},
+                std::task::Poll::Pending => return std::task::Poll::Pending,
+                std::task::Poll::Ready(Err(fx_status::Status::PEER_CLOSED)) => {
+                    this.is_terminated = true;
+                    return std::task::Poll::Ready(None);
+                }
+                std::task::Poll::Ready(Err(e)) => return std::task::Poll::Ready(Some(Err(midl::Error::ServerRequestRead(e)))),
+            }
+
+            // A message has been received from the channel
+            let (header, _body_bytes) = midl::encoding::decode_transaction_header(bytes)?;
+
+            std::task::Poll::Ready(Some(match header.ordinal() {
+                8198755819659321800 => {
+                    header.validate_request_tx_id(midl::encoding::MethodType::TwoWay)?;
+                    let mut out = midl::new_empty!(midl::encoding::EmptyPayload);
+                    midl::encoding::Decoder::decode_into::<midl::encoding::EmptyPayload>(&header, _body_bytes, handles, &mut out)?;
+                    let control_handle = CloseableControlHandle {
+                        inner: this.inner.clone(),
+                    };
+                    Ok(CloseableRequest::Close {
+                        
+                        responder: CloseableCloseResponder {
+                            control_handle: std::mem::ManuallyDrop::new(control_handle),
+                            tx_id: header.tx_id(),
+                        },
+                    })
+                }
+                _ => Err(midl::Error::UnknownOrdinal {
+                    ordinal: header.ordinal(),
+                    protocol_name: <CloseableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME,
+                }),
+            }))
+        })
+    }
+}
+
+/// Provides a means of synchronously closing a connection.
+#[derive(Debug)]
+pub enum CloseableRequest {
+    /// Terminates the connection.
+    ///
+    /// After calling `Close`, the client must not send any other requests.
+    ///
+    /// Servers, after sending the status response, should close the connection
+    /// regardless of status and without sending an epitaph.
+    ///
+    /// Closing the client end of the channel should be semantically equivalent
+    /// to calling `Close` without knowing when the close has completed or its
+    /// status.
+    Close {
+        responder: CloseableCloseResponder,
+    },
+
+}
+
+impl CloseableRequest {
+    #[allow(irrefutable_let_patterns)]
+    pub fn into_close(self) -> Option<(
+        CloseableCloseResponder
+    )> {
+        if let CloseableRequest::Close {
+            responder,
+        } = self {
+            Some((
+                responder
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Name of the method defined in MIDL
+    pub fn method_name(&self) -> &'static str {
+        match *self {
+            CloseableRequest::Close { .. } => "close",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CloseableControlHandle {
+    inner: std::sync::Arc<midl::ServeInner>,
+}
+
+impl midl::endpoints::ControlHandle for CloseableControlHandle {
+    fn shutdown(&self) {
+        self.inner.shutdown()
+    }
+
+    fn shutdown_with_epitaph(&self, status: fx_status::Status) {
+        self.inner.shutdown_with_epitaph(status)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.channel().is_closed()
+    }
+
+    fn on_closed<'a>(&'a self) -> midl::OnSignals<'a> {
+        self.inner.channel().on_closed()
+    }
+}
+
+impl CloseableControlHandle {
+}
+
+#[must_use = "MIDL methods require a response to be sent"]
+#[derive(Debug)]
+pub struct CloseableCloseResponder {
+    control_handle: std::mem::ManuallyDrop<CloseableControlHandle>,
+    tx_id: u32,
+}
+
+/// Set the the channel to be shutdown (see [`CloseableControlHandle::shutdown`])
+/// if the responder is dropped without sending a response, so that the client
+/// doesn't hang. To prevent this behavior, call `drop_without_shutdown`.
+impl std::ops::Drop for CloseableCloseResponder {
+    fn drop(&mut self) {
+        self.control_handle.shutdown();
+        // Safety: drops once, never accessed again
+        unsafe { std::mem::ManuallyDrop::drop(&mut self.control_handle) };
+    }
+}
+
+impl midl::endpoints::Responder for CloseableCloseResponder {
+    type ControlHandle = CloseableControlHandle;
+
+    fn control_handle(&self) -> &CloseableControlHandle {
+        &self.control_handle
+    }
+
+    fn drop_without_shutdown(mut self) {
+        // Safety: drops once, never accessed again due to mem::forget
+        unsafe { std::mem::ManuallyDrop::drop(&mut self.control_handle) };
+        // Prevent Drop from running (which would shut down the channel)
+        std::mem::forget(self);
+    }
+}
+
+impl CloseableCloseResponder {
+    /// Sends a response to the MIDL transaction.
+    ///
+    /// Sets the channel to shutdown if an error occurs.
+    pub fn send(self,
+        mut result: Result<(), i32>,
+    ) -> Result<(), midl::Error> {
+        let _result = self.send_raw(
+            result,
+        );
+        if _result.is_err() {
+            self.control_handle.shutdown();
+        }
+        self.drop_without_shutdown();
+        _result
+    }
+
+    /// Similar to "send" but does not shutdown the channel if an error occurs.
+    pub fn send_no_shutdown_on_err(self,
+        mut result: Result<(), i32>,
+    ) -> Result<(), midl::Error> {
+        let _result = self.send_raw(
+            result,
+        );
+        self.drop_without_shutdown();
+        _result
+    }
+
+    fn send_raw(
+        &self,
+        mut result: Result<(), i32>,
+    ) -> Result<(), midl::Error> {
+        self.control_handle.inner.send::<midl::encoding::ResultType<midl::encoding::EmptyStruct, i32>, false>(
+            result,
+            self.tx_id,
+            8198755819659321800,
+            midl::encoding::DynamicFlags::empty(),
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct QueryableMarker;
+
+impl midl::endpoints::ProtocolMarker for QueryableMarker {
+    type Proxy = QueryableProxy;
+    type RequestStream = QueryableRequestStream;
+    type SynchronousProxy = QueryableSynchronousProxy;
+
+    const DEBUG_NAME: &'static str = "meshx.unknown.Queryable";
+}
+
+
+
+pub trait QueryableProxyInterface: Send + Sync {
+    type QueryResponseFut: std::future::Future<Output = Result<Vec<u8>, midl::Error>> + Send;
+
+    fn r#query(&self,
+    )
+    -> Self::QueryResponseFut;
+}
+
+#[derive(Debug)]
+pub struct QueryableSynchronousProxy {
+    client: midl::client::sync::Client,
+}
+
+impl midl::endpoints::SynchronousProxy for QueryableSynchronousProxy {
+    type Proxy = QueryableProxy;
+    type Protocol = QueryableMarker;
+
+    fn from_channel(inner: midl::Channel) -> Self {
+        Self::new(inner)
+    }
+
+    fn into_channel(self) -> midl::Channel {
+        self.client.into_channel()
+    }
+
+    fn as_channel(&self) -> &midl::Channel {
+        self.client.as_channel()
+    }
+}
+
+impl QueryableSynchronousProxy {
+    pub fn new(channel: midl::Channel) -> Self {
+        let protocol_name = <QueryableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME;
+        Self { client: midl::client::sync::Client::new(channel, protocol_name) }
+    }
+
+    pub fn into_channel(self) -> midl::Channel {
+        self.client.into_channel()
+    }
+
+    /// Waits until an event arrives and returns it. It is safe for other
+    /// threads to make concurrent requests while waiting for an event.
+    pub fn wait_for_event(&self, deadline: fx::Time) -> Result<QueryableEvent, midl::Error> {
+        QueryableEvent::decode(self.client.wait_for_event(deadline)?)
+    }
+
+    pub fn r#query(
+        &self,
+        ___deadline: fx::Time,
+    ) -> Result<Vec<u8>, midl::Error> {
+        let out = self.client.send_query::<
+            midl::encoding::EmptyPayload,
+            QueryableQueryResponse,
+            false,
+            true,
+        >(
+            (),
+            2939229302024204537,
+            midl::encoding::DynamicFlags::empty(),
+            ___deadline,
+        )?
+;
+        Ok(out.protocol)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryableProxy {
+    client: midl::client::Client,
+}
+
+impl midl::endpoints::Proxy for QueryableProxy {
+    type Protocol = QueryableMarker;
+
+    fn from_channel(inner: midl::AsyncChannel) -> Self {
+        Self::new(inner)
+    }
+
+    fn into_channel(self) -> Result<::midl::AsyncChannel, Self> {
+        self.client.into_channel().map_err(|client| Self { client })
+    }
+
+    fn as_channel(&self) -> &::midl::AsyncChannel {
+        self.client.as_channel()
+    }
+}
+
+impl QueryableProxy {
+    /// Create a new Proxy for meshx.unknown.Queryable.
+    pub fn new(channel: midl::AsyncChannel) -> Self {
+        let protocol_name = <QueryableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME;
+        Self { client: midl::client::Client::new(channel, protocol_name) }
+    }
+
+    /// Get a Stream of events from the remote end of the protocol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the event stream was already taken.
+    pub fn take_event_stream(&self) -> QueryableEventStream {
+        QueryableEventStream {
+            event_receiver: self.client.take_event_receiver(),
+        }
+    }
+
+    pub fn r#query(
+        &self,
+    )
+    -> midl::client::QueryResponseFut<Vec<u8>> {
+        QueryableProxyInterface::r#query(self,
+        )
+    }
+}
+
+impl QueryableProxyInterface for QueryableProxy {
+    type QueryResponseFut = midl::client::QueryResponseFut<Vec<u8>>;
+    fn r#query(
+        &self,
+    ) -> Self::QueryResponseFut {
+        fn _decode(mut _buf: Result<midl::MessageBufEtc, midl::Error>) -> Result<Vec<u8>, midl::Error> {
+            let out = midl::client::decode_transaction_body::<
+                QueryableQueryResponse,
+                true,
+            >(_buf?)?
+;
+            Ok(out.protocol)
+        }
+        self.client.send_query_and_decode::<
+            midl::encoding::EmptyPayload,
+            Vec<u8>,
+            false,
+        >(
+            (),
+            2939229302024204537,
+            midl::encoding::DynamicFlags::empty(),
+            _decode,
+        )
+    }
+}
+
+pub struct QueryableEventStream {
+    event_receiver: midl::client::EventReceiver,
+}
+
+impl std::marker::Unpin for QueryableEventStream {}
+
+impl futures::stream::FusedStream for QueryableEventStream {
+    fn is_terminated(&self) -> bool {
+        self.event_receiver.is_terminated()
+    }
+}
+
+impl futures::Stream for QueryableEventStream {
+    type Item = Result<QueryableEvent, midl::Error>;
+
+    fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>)
+        -> std::task::Poll<Option<Self::Item>>
+    {
+        match futures::ready!(
+            futures::stream::StreamExt::poll_next_unpin(&mut self.event_receiver, cx)?
+        ) {
+            Some(buf) => std::task::Poll::Ready(Some(QueryableEvent::decode(buf))),
+            None => std::task::Poll::Ready(None),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum QueryableEvent {
+
+}
+
+impl QueryableEvent {
+
+    /// Decodes a message buffer as a [`QueryableEvent`].
+    fn decode(mut buf: midl::MessageBufEtc) -> Result<QueryableEvent, midl::Error> {
+        let (bytes, _handles) = buf.split_mut();
+        let (tx_header, _body_bytes) = midl::encoding::decode_transaction_header(bytes)?;
+        debug_assert_eq!(tx_header.tx_id(), 0);
+        match tx_header.ordinal() {
+            _ => Err(midl::Error::UnknownOrdinal {
+                ordinal: tx_header.ordinal(),
+                protocol_name: <QueryableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME,
+            })
+        }
+    }
+}
+
+/// A Stream of incoming requests for meshx.unknown.Queryable.
+pub struct QueryableRequestStream {
+    inner: std::sync::Arc<midl::ServeInner>,
+    is_terminated: bool,
+}
+
+impl std::marker::Unpin for QueryableRequestStream {}
+
+impl futures::stream::FusedStream for QueryableRequestStream {
+    fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+}
+
+impl midl::endpoints::RequestStream for QueryableRequestStream {
+    type Protocol = QueryableMarker;
+    type ControlHandle = QueryableControlHandle;
+
+    fn from_channel(channel: midl::AsyncChannel) -> Self {
+        Self {
+            inner: std::sync::Arc::new(midl::ServeInner::new(channel, <QueryableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME)),
+            is_terminated: false,
+        }
+    }
+
+    fn control_handle(&self) -> Self::ControlHandle {
+        QueryableControlHandle { inner: self.inner.clone() }
+    }
+
+    fn into_inner(self) -> (::std::sync::Arc<midl::ServeInner>, bool) {
+        (self.inner, self.is_terminated)
+    }
+
+    fn from_inner(inner: std::sync::Arc<midl::ServeInner>, is_terminated: bool) -> Self {
+        Self { inner, is_terminated }
+    }
+}
+
+impl futures::Stream for QueryableRequestStream {
+    type Item = Result<QueryableRequest, midl::Error>;
+
+    fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>)
+        -> std::task::Poll<Option<Self::Item>>
+    {
+        let this = &mut *self;
+        if this.inner.poll_shutdown(cx) {
+            this.is_terminated = true;
+            return std::task::Poll::Ready(None);
+        }
+        if this.is_terminated {
+            panic!("polled QueryableRequestStream after completion");
+        }
+        midl::encoding::with_tls_decode_buf(|bytes, handles| {
+            match this.inner.poll_read_request(cx, bytes, handles) {
+                std::task::Poll::Ready(Ok(())) => {},
+                std::task::Poll::Pending => return std::task::Poll::Pending,
+                std::task::Poll::Ready(Err(fx_status::Status::PEER_CLOSED)) => {
+                    this.is_terminated = true;
+                    return std::task::Poll::Ready(None);
+                }
+                std::task::Poll::Ready(Err(e)) => return std::task::Poll::Ready(Some(Err(midl::Error::ServerRequestRead(e)))),
+            }
+
+            // A message has been received from the channel
+            let (header, _body_bytes) = midl::encoding::decode_transaction_header(bytes)?;
+
+            std::task::Poll::Ready(Some(match header.ordinal() {
+                2939229302024204537 => {
+                    header.validate_request_tx_id(midl::encoding::MethodType::TwoWay)?;
+                    let mut out = midl::new_empty!(midl::encoding::EmptyPayload);
+                    midl::encoding::Decoder::decode_into::<midl::encoding::EmptyPayload>(&header, _body_bytes, handles, &mut out)?;
+                    let control_handle = QueryableControlHandle {
+                        inner: this.inner.clone(),
+                    };
+                    Ok(QueryableRequest::Query {
+                        
+                        responder: QueryableQueryResponder {
+                            control_handle: std::mem::ManuallyDrop::new(control_handle),
+                            tx_id: header.tx_id(),
+                        },
+                    })
+                }
+                _ => Err(midl::Error::UnknownOrdinal {
+                    ordinal: header.ordinal(),
+                    protocol_name: <QueryableMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME,
+                }),
+            }))
+        })
+    }
+}
+
+/// Provides a means of identifying a type-erased protocol.
+#[derive(Debug)]
+pub enum QueryableRequest {
+    Query {
+        responder: QueryableQueryResponder,
+    },
+
+}
+
+impl QueryableRequest {
+    #[allow(irrefutable_let_patterns)]
+    pub fn into_query(self) -> Option<(
+        QueryableQueryResponder
+    )> {
+        if let QueryableRequest::Query {
+            responder,
+        } = self {
+            Some((
+                responder
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Name of the method defined in MIDL
+    pub fn method_name(&self) -> &'static str {
+        match *self {
+            QueryableRequest::Query { .. } => "query",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryableControlHandle {
+    inner: std::sync::Arc<midl::ServeInner>,
+}
+
+impl midl::endpoints::ControlHandle for QueryableControlHandle {
+    fn shutdown(&self) {
+        self.inner.shutdown()
+    }
+
+    fn shutdown_with_epitaph(&self, status: fx_status::Status) {
+        self.inner.shutdown_with_epitaph(status)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.channel().is_closed()
+    }
+
+    fn on_closed<'a>(&'a self) -> midl::OnSignals<'a> {
+        self.inner.channel().on_closed()
+    }
+}
+
+impl QueryableControlHandle {
+}
+
+#[must_use = "MIDL methods require a response to be sent"]
+#[derive(Debug)]
+pub struct QueryableQueryResponder {
+    control_handle: std::mem::ManuallyDrop<QueryableControlHandle>,
+    tx_id: u32,
+}
+
+/// Set the the channel to be shutdown (see [`QueryableControlHandle::shutdown`])
+/// if the responder is dropped without sending a response, so that the client
+/// doesn't hang. To prevent this behavior, call `drop_without_shutdown`.
+impl std::ops::Drop for QueryableQueryResponder {
+    fn drop(&mut self) {
+        self.control_handle.shutdown();
+        // Safety: drops once, never accessed again
+        unsafe { std::mem::ManuallyDrop::drop(&mut self.control_handle) };
+    }
+}
+
+impl midl::endpoints::Responder for QueryableQueryResponder {
+    type ControlHandle = QueryableControlHandle;
+
+    fn control_handle(&self) -> &QueryableControlHandle {
+        &self.control_handle
+    }
+
+    fn drop_without_shutdown(mut self) {
+        // Safety: drops once, never accessed again due to mem::forget
+        unsafe { std::mem::ManuallyDrop::drop(&mut self.control_handle) };
+        // Prevent Drop from running (which would shut down the channel)
+        std::mem::forget(self);
+    }
+}
+
+impl QueryableQueryResponder {
+    /// Sends a response to the MIDL transaction.
+    ///
+    /// Sets the channel to shutdown if an error occurs.
+    pub fn send(self,
+        mut protocol: &[u8],
+    ) -> Result<(), midl::Error> {
+        let _result = self.send_raw(
+            protocol,
+        );
+        if _result.is_err() {
+            self.control_handle.shutdown();
+        }
+        self.drop_without_shutdown();
+        _result
+    }
+
+    /// Similar to "send" but does not shutdown the channel if an error occurs.
+    pub fn send_no_shutdown_on_err(self,
+        mut protocol: &[u8],
+    ) -> Result<(), midl::Error> {
+        let _result = self.send_raw(
+            protocol,
+        );
+        self.drop_without_shutdown();
+        _result
+    }
+
+    fn send_raw(
+        &self,
+        mut protocol: &[u8],
+    ) -> Result<(), midl::Error> {
+        self.control_handle.inner.send::<QueryableQueryResponse, true>(
+            (protocol,),
+            self.tx_id,
+            2939229302024204537,
+            midl::encoding::DynamicFlags::empty(),
+        )
+    }
+}
+
+mod internal {
+    use super::*;
+
+    
+    unsafe impl midl::encoding::TypeMarker for CloneableClone2Request {
+        type Owned = Self;
+    
+        #[inline(always)]
+        fn inline_align(_context: midl::encoding::Context) -> usize {
+            4
+        }
+    
+        #[inline(always)]
+        fn inline_size(_context: midl::encoding::Context) -> usize {
+            4
+        }
+    
+}
+    
+impl midl::encoding::ResourceTypeMarker for CloneableClone2Request {
+        type Borrowed<'a> = &'a mut Self;
+        fn take_or_borrow<'a>(value: &'a mut <Self as midl::encoding::TypeMarker>::Owned) -> Self::Borrowed<'a> {
+            value
+        }
+    }
+
+    unsafe impl midl::encoding::Encode<CloneableClone2Request> for &mut CloneableClone2Request {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<CloneableClone2Request>(offset);
+            // Delegate to tuple encoding.
+                midl::encoding::Encode::<CloneableClone2Request>::encode(
+                    (
+                    <midl::encoding::Endpoint<midl::endpoints::ServerEnd<CloneableMarker>> as midl::encoding::ResourceTypeMarker>::take_or_borrow(&mut self.request),
+                ),
+                    encoder, offset, _depth
+                )
+    }
+    }
+    
+unsafe impl< T0: midl::encoding::Encode<midl::encoding::Endpoint<midl::endpoints::ServerEnd<CloneableMarker>>>,>
+        midl::encoding::Encode<CloneableClone2Request> for ( T0,)
+    {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<CloneableClone2Request>(offset);
+            // Zero out padding regions. There's no need to apply masks
+            // because the unmasked parts will be overwritten by fields.
+        // Write the fields.
+        self.0.encode(encoder, offset + 0, depth)?;
+        Ok(())
+        }
+    }
+
+    impl midl::encoding::Decode<Self> for CloneableClone2Request {
+        #[inline(always)]
+        fn new_empty() -> Self {
+            Self  {
+            request: midl::new_empty!(midl::encoding::Endpoint<midl::endpoints::ServerEnd<CloneableMarker>>),
+        }
+    }
+    
+        #[inline]
+        unsafe fn decode(&mut self, decoder: &mut midl::encoding::Decoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+            midl::decode!(midl::encoding::Endpoint<midl::endpoints::ServerEnd<CloneableMarker>>, &mut self.request, decoder, offset + 0, _depth)?;
+            Ok(())
+    }
+    }
+    
+    
+    unsafe impl midl::encoding::TypeMarker for CloseableCloseResponse {
+        type Owned = Self;
+    
+        #[inline(always)]
+        fn inline_align(_context: midl::encoding::Context) -> usize {
+            1
+        }
+    
+        #[inline(always)]
+        fn inline_size(_context: midl::encoding::Context) -> usize {
+            1
+        }
+    
+}
+    
+impl midl::encoding::ValueTypeMarker for CloseableCloseResponse {
+        type Borrowed<'a> = &'a Self;
+        fn borrow<'a>(value: &'a <Self as midl::encoding::TypeMarker>::Owned) -> Self::Borrowed<'a> {
+            value
+        }
+    }
+
+    unsafe impl midl::encoding::Encode<CloseableCloseResponse> for &CloseableCloseResponse {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<CloseableCloseResponse>(offset);
+            encoder.write_num(0u8, offset);
+                Ok(())
+    }
+    }
+    
+
+    impl midl::encoding::Decode<Self> for CloseableCloseResponse {
+        #[inline(always)]
+        fn new_empty() -> Self {
+            Self     }
+    
+        #[inline]
+        unsafe fn decode(&mut self, decoder: &mut midl::encoding::Decoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            decoder.debug_check_bounds::<Self>(offset);
+            match decoder.read_num::<u8>(offset) {
+                    0 => Ok(()),
+                    _ => Err(midl::Error::Invalid),
+                }
+    }
+    }
+    
+    
+    unsafe impl midl::encoding::TypeMarker for QueryableQueryResponse {
+        type Owned = Self;
+    
+        #[inline(always)]
+        fn inline_align(_context: midl::encoding::Context) -> usize {
+            8
+        }
+    
+        #[inline(always)]
+        fn inline_size(_context: midl::encoding::Context) -> usize {
+            16
+        }
+    
+}
+    
+impl midl::encoding::ValueTypeMarker for QueryableQueryResponse {
+        type Borrowed<'a> = &'a Self;
+        fn borrow<'a>(value: &'a <Self as midl::encoding::TypeMarker>::Owned) -> Self::Borrowed<'a> {
+            value
+        }
+    }
+
+    unsafe impl midl::encoding::Encode<QueryableQueryResponse> for &QueryableQueryResponse {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<QueryableQueryResponse>(offset);
+            // Delegate to tuple encoding.
+                midl::encoding::Encode::<QueryableQueryResponse>::encode(
+                    (
+                    <midl::encoding::UnboundedVector<u8> as midl::encoding::ValueTypeMarker>::borrow(&self.protocol),
+                ),
+                    encoder, offset, _depth
+                )
+    }
+    }
+    
+unsafe impl< T0: midl::encoding::Encode<midl::encoding::UnboundedVector<u8>>,>
+        midl::encoding::Encode<QueryableQueryResponse> for ( T0,)
+    {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<QueryableQueryResponse>(offset);
+            // Zero out padding regions. There's no need to apply masks
+            // because the unmasked parts will be overwritten by fields.
+        // Write the fields.
+        self.0.encode(encoder, offset + 0, depth)?;
+        Ok(())
+        }
+    }
+
+    impl midl::encoding::Decode<Self> for QueryableQueryResponse {
+        #[inline(always)]
+        fn new_empty() -> Self {
+            Self  {
+            protocol: midl::new_empty!(midl::encoding::UnboundedVector<u8>),
+        }
+    }
+    
+        #[inline]
+        unsafe fn decode(&mut self, decoder: &mut midl::encoding::Decoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+            midl::decode!(midl::encoding::UnboundedVector<u8>, &mut self.protocol, decoder, offset + 0, _depth)?;
+            Ok(())
+    }
+    }
+    
+impl<'de> midl::encoding::ViewMarker<'de> for QueryableQueryResponse {
+        type View = QueryableQueryResponseView<'de>;
+    
+        #[inline]
+        unsafe fn decode_view(decoder: &mut midl::encoding::Decoder<'de>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<Self::View> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+        Ok(QueryableQueryResponseView {
+            protocol: <midl::encoding::UnboundedVector<u8> as midl::encoding::ViewMarker<'de>>::decode_view(decoder, offset + 0, _depth)?,
+        })
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,163 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Common utilities used by both directory and file traits.
+
+use {
+    fiber_status::Status,
+    midl::endpoints::{ControlHandle as _, ServerEnd},
+    midl_meshx_io as mio,
+};
+
+/// Set of known rights.
+const MIO_RIGHTS: mio::OpenFlags = mio::OpenFlags::RIGHT_READABLE
+    .union(mio::OpenFlags::RIGHT_WRITABLE)
+    .union(mio::OpenFlags::RIGHT_EXECUTABLE);
+
+/// Flags visible to GetFlags. These are flags that have meaning after the open call; all other
+/// flags are only significant at open time.
+pub const GET_FLAGS_VISIBLE: mio::OpenFlags = mio::OpenFlags::RIGHT_READABLE
+    .union(mio::OpenFlags::RIGHT_WRITABLE)
+    .union(mio::OpenFlags::RIGHT_EXECUTABLE)
+    .union(mio::OpenFlags::APPEND)
+    .union(mio::OpenFlags::NODE_REFERENCE);
+
+/// Returns the current set of rights in `flags`.
+pub fn rights(flags: mio::OpenFlags) -> mio::OpenFlags {
+    flags & MIO_RIGHTS
+}
+
+/// Returns a copy of `flags` with the rights of `parent_flags` if `CLONE_SAME_RIGHTS` is set.
+/// Returns `INVALID_ARGS` if `CLONE_SAME_RIGHTS` is combined with explicit rights, and
+/// `ACCESS_DENIED` if `flags` asks for rights `parent_flags` does not have.
+pub fn inherit_rights_for_clone(
+    parent_flags: mio::OpenFlags,
+    mut flags: mio::OpenFlags,
+) -> Result<mio::OpenFlags, Status> {
+    if flags.intersects(mio::OpenFlags::CLONE_SAME_RIGHTS) && flags.intersects(MIO_RIGHTS) {
+        return Err(Status::INVALID_ARGS);
+    }
+
+    // We preserve OPEN_FLAG_APPEND as this is what is the most convenient for the POSIX emulation.
+    //
+    // OPEN_FLAG_NODE_REFERENCE is enforced, according to our current FS permissions design.
+    flags |= parent_flags & (mio::OpenFlags::APPEND | mio::OpenFlags::NODE_REFERENCE);
+
+    // If CLONE_FLAG_SAME_RIGHTS is requested, cloned connection will inherit the same rights
+    // as those from the originating connection.  We have ensured that no MIO_RIGHTS flags are set
+    // above.
+    if flags.contains(mio::OpenFlags::CLONE_SAME_RIGHTS) {
+        flags &= !mio::OpenFlags::CLONE_SAME_RIGHTS;
+        flags |= parent_flags & MIO_RIGHTS;
+    }
+
+    if rights(flags).intersects(!rights(parent_flags)) {
+        return Err(Status::ACCESS_DENIED);
+    }
+
+    Ok(flags)
+}
+
+/// A helper method to send OnOpen event on the handle owned by the `server_end` in case `flags`
+/// contains `OPEN_FLAG_STATUS`.
+///
+/// If the send operation fails for any reason, the error is ignored.  This helper is used during
+/// an Open() or a Clone() FIDL methods, and these methods have no means to propagate errors to the
+/// caller.  OnOpen event is the only way to do that, so there is nowhere to report errors in
+/// OnOpen dispatch.  `server_end` will be closed, so there will be some kind of indication of the
+/// issue.
+///
+/// # Panics
+/// If `status` is `Status::OK`.  In this case `OnOpen` may need to contain a description of the
+/// object, and server_end should not be dropped.
+pub fn send_on_open_with_error(describe: bool, server_end: ServerEnd<mio::NodeMarker>, status: Status) {
+    if status == Status::OK {
+        panic!("send_on_open_with_error() should not be used to respond with Status::OK");
+    }
+
+    if !describe {
+        // There is no reasonable way to report this error.  Assuming the `server_end` has just
+        // disconnected or failed in some other way why we are trying to send OnOpen.
+        let _ = server_end.close_with_epitaph(status);
+        return;
+    }
+
+    match server_end.into_stream_and_control_handle() {
+        Ok((_, control_handle)) => {
+            // Same as above, ignore the error.
+            let _ = control_handle.send_on_open(status.into_raw(), None);
+            control_handle.shutdown_with_epitaph(status);
+        }
+        Err(_) => {
+            // Same as above, ignore the error.
+        }
+    }
+}
+
+/// Converts the set of validated VMO flags to their respective rights.
+pub fn vmo_flags_to_rights(vmo_flags: mio::VmoFlags) -> mio::OpenFlags {
+    let mut rights = mio::OpenFlags::empty();
+    if vmo_flags.contains(mio::VmoFlags::READ) {
+        rights |= mio::OpenFlags::RIGHT_READABLE;
+    }
+    if vmo_flags.contains(mio::VmoFlags::WRITE) {
+        rights |= mio::OpenFlags::RIGHT_WRITABLE;
+    }
+    if vmo_flags.contains(mio::VmoFlags::EXECUTE) {
+        rights |= mio::OpenFlags::RIGHT_EXECUTABLE;
+    }
+    rights
+}
+
+/// Converts the readable, writable and executable properties of a node to the POSIX permission
+/// bits reported in `NodeAttributes.mode`.
+// `mode_t` is narrower than `u32` on some platforms.
+#[allow(clippy::unnecessary_cast)]
+pub fn rights_to_posix_mode_bits(readable: bool, writable: bool, executable: bool) -> u32 {
+    (if readable { libc::S_IRUSR } else { 0 }
+        | if writable { libc::S_IWUSR } else { 0 }
+        | if executable { libc::S_IXUSR } else { 0 }) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clone_same_rights_inherits_parent_rights() {
+        let parent = mio::OpenFlags::RIGHT_READABLE | mio::OpenFlags::RIGHT_WRITABLE | mio::OpenFlags::APPEND;
+        assert_eq!(
+            inherit_rights_for_clone(parent, mio::OpenFlags::CLONE_SAME_RIGHTS),
+            Ok(parent)
+        );
+    }
+
+    #[test]
+    fn clone_same_rights_with_explicit_rights_is_invalid() {
+        assert_eq!(
+            inherit_rights_for_clone(
+                mio::OpenFlags::RIGHT_READABLE,
+                mio::OpenFlags::CLONE_SAME_RIGHTS | mio::OpenFlags::RIGHT_READABLE
+            ),
+            Err(Status::INVALID_ARGS)
+        );
+    }
+
+    #[test]
+    fn clone_cannot_escalate_rights() {
+        assert_eq!(
+            inherit_rights_for_clone(mio::OpenFlags::RIGHT_READABLE, mio::OpenFlags::RIGHT_WRITABLE),
+            Err(Status::ACCESS_DENIED)
+        );
+    }
+
+    #[test]
+    fn vmo_flags_map_to_rights() {
+        assert_eq!(
+            vmo_flags_to_rights(mio::VmoFlags::READ | mio::VmoFlags::EXECUTE),
+            mio::OpenFlags::RIGHT_READABLE | mio::OpenFlags::RIGHT_EXECUTABLE
+        );
+    }
+}
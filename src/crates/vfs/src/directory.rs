@@ -7,13 +7,28 @@
 pub mod mutable;
 pub mod immutable;
 
+pub mod common;
+pub mod connection;
+pub mod dirents_sink;
 pub mod entry;
 pub mod entry_container;
+pub mod helper;
+pub mod read_dirents;
 pub mod simple;
-pub mod connection;
+pub mod watchers;
+
+use midl_meshx_io as mio;
 
 /// A directory can be open either as a directory or a node.
 #[derive(Clone)]
 pub struct DirectoryOptions {
-    // pub(crate) rights: fio::Operations,
+    /// The `OpenFlags.RIGHT_*` flags of the connection.
+    pub(crate) rights: mio::OpenFlags,
+}
+
+impl DirectoryOptions {
+    /// Converts to the `OpenFlags` reported by `GetFlags`.
+    pub(crate) fn to_io1(&self) -> mio::OpenFlags {
+        self.rights
+    }
 }
@@ -0,0 +1,192 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Common utilities used by the directory implementations and their connections.
+
+use crate::{common::rights, directory::entry::EntryInfo};
+
+use {fiber_status::Status, midl_meshx_io as mio, std::convert::TryInto as _};
+
+/// Directories need to make sure that connections to child entries do not receive more rights
+/// than the connection to the directory itself.  Plus there is special handling of the
+/// `OpenFlags::POSIX_*` flags.  This function should be called for every child connection open
+/// request.
+///
+/// Changing this function can be dangerous!  Flags operations may have security implications.
+pub fn check_child_connection_flags(
+    parent_flags: mio::OpenFlags,
+    mut flags: mio::OpenFlags,
+) -> Result<mio::OpenFlags, Status> {
+    if flags.intersects(mio::OpenFlags::DIRECTORY) && flags.intersects(mio::OpenFlags::NOT_DIRECTORY) {
+        return Err(Status::INVALID_ARGS);
+    }
+
+    // Expand the POSIX flags into the rights of the parent connection.
+    if flags.intersects(mio::OpenFlags::POSIX_WRITABLE) {
+        flags |= parent_flags & mio::OpenFlags::RIGHT_WRITABLE;
+    }
+    if flags.intersects(mio::OpenFlags::POSIX_EXECUTABLE) {
+        flags |= parent_flags & mio::OpenFlags::RIGHT_EXECUTABLE;
+    }
+    flags &= !(mio::OpenFlags::POSIX_WRITABLE | mio::OpenFlags::POSIX_EXECUTABLE);
+
+    // Can only use CLONE_SAME_RIGHTS when calling Clone.
+    if flags.intersects(mio::OpenFlags::CLONE_SAME_RIGHTS) {
+        return Err(Status::INVALID_ARGS);
+    }
+
+    // Can not open a connection with more rights than the parent connection has.
+    if rights(flags).intersects(!rights(parent_flags)) {
+        return Err(Status::ACCESS_DENIED);
+    }
+
+    // Creating an entry modifies the parent.
+    if flags.intersects(mio::OpenFlags::CREATE) && !parent_flags.intersects(mio::OpenFlags::RIGHT_WRITABLE) {
+        return Err(Status::ACCESS_DENIED);
+    }
+
+    Ok(flags)
+}
+
+/// Validates the flags of a new connection to a directory, and returns the flags the connection
+/// should record.
+///
+/// Changing this function can be dangerous!  Flags operations may have security implications.
+pub fn new_connection_validate_flags(mut flags: mio::OpenFlags) -> Result<mio::OpenFlags, Status> {
+    if flags.intersects(mio::OpenFlags::NOT_DIRECTORY) {
+        return Err(Status::NOT_FILE);
+    }
+
+    // Parents expand the POSIX flags when validating a child connection.  Any that are left come
+    // from a connection to the root, and grant all the rights they stand for.
+    if flags.intersects(mio::OpenFlags::POSIX_WRITABLE) {
+        flags |= mio::OpenFlags::RIGHT_WRITABLE;
+    }
+    if flags.intersects(mio::OpenFlags::POSIX_EXECUTABLE) {
+        flags |= mio::OpenFlags::RIGHT_EXECUTABLE;
+    }
+    flags &= !(mio::OpenFlags::POSIX_WRITABLE | mio::OpenFlags::POSIX_EXECUTABLE);
+
+    // Directories have no content to truncate or append to.
+    if flags.intersects(mio::OpenFlags::TRUNCATE | mio::OpenFlags::APPEND) {
+        return Err(Status::INVALID_ARGS);
+    }
+
+    // The creation flags were handled by the parent directory.
+    flags &= !(mio::OpenFlags::CREATE | mio::OpenFlags::CREATE_IF_ABSENT);
+
+    let allowed_flags = mio::OpenFlags::DESCRIBE
+        | mio::OpenFlags::DIRECTORY
+        | mio::OpenFlags::RIGHT_READABLE
+        | mio::OpenFlags::RIGHT_WRITABLE
+        | mio::OpenFlags::RIGHT_EXECUTABLE;
+    if flags.intersects(!allowed_flags) {
+        return Err(Status::NOT_SUPPORTED);
+    }
+
+    Ok(flags)
+}
+
+/// A helper to generate binary encodings for the ReadDirents response.  This function will append
+/// an entry description as specified by `entry` and `name` to the `buf`, and would return `true`.
+/// In case this would cause the buffer size to exceed `max_bytes`, the buffer is then left
+/// untouched and a `false` value is returned.
+pub fn encode_dirent(buf: &mut Vec<u8>, max_bytes: u64, entry: &EntryInfo, name: &str) -> bool {
+    let header_size = std::mem::size_of::<u64>() + std::mem::size_of::<u8>() + std::mem::size_of::<u8>();
+
+    assert!(
+        name.len() <= mio::MAX_FILENAME as usize,
+        "Entry names are expected to be no longer than MAX_FILENAME ({}) bytes.\n\
+         Got entry: '{}'\n\
+         Length: {} bytes",
+        mio::MAX_FILENAME,
+        name,
+        name.len()
+    );
+
+    if buf.len() + header_size + name.len() > max_bytes.try_into().unwrap() {
+        return false;
+    }
+
+    let name_len: u8 = name.len().try_into().expect("MAX_FILENAME is expected to fit in a u8");
+
+    buf.extend_from_slice(&entry.inode().to_le_bytes());
+    buf.push(name_len);
+    buf.push(entry.type_().into_primitive());
+    buf.extend_from_slice(name.as_bytes());
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn child_cannot_escalate_rights() {
+        assert_eq!(
+            check_child_connection_flags(mio::OpenFlags::RIGHT_READABLE, mio::OpenFlags::RIGHT_WRITABLE),
+            Err(Status::ACCESS_DENIED)
+        );
+    }
+
+    #[test]
+    fn posix_flags_are_downscoped_to_parent_rights() {
+        assert_eq!(
+            check_child_connection_flags(
+                mio::OpenFlags::RIGHT_READABLE | mio::OpenFlags::RIGHT_WRITABLE,
+                mio::OpenFlags::RIGHT_READABLE | mio::OpenFlags::POSIX_WRITABLE | mio::OpenFlags::POSIX_EXECUTABLE
+            ),
+            Ok(mio::OpenFlags::RIGHT_READABLE | mio::OpenFlags::RIGHT_WRITABLE)
+        );
+    }
+
+    #[test]
+    fn create_requires_writable_parent() {
+        assert_eq!(
+            check_child_connection_flags(mio::OpenFlags::RIGHT_READABLE, mio::OpenFlags::CREATE),
+            Err(Status::ACCESS_DENIED)
+        );
+    }
+
+    #[test]
+    fn directory_connection_rejects_file_flags() {
+        assert_eq!(
+            new_connection_validate_flags(mio::OpenFlags::NOT_DIRECTORY),
+            Err(Status::NOT_FILE)
+        );
+        assert_eq!(
+            new_connection_validate_flags(mio::OpenFlags::RIGHT_WRITABLE | mio::OpenFlags::TRUNCATE),
+            Err(Status::INVALID_ARGS)
+        );
+    }
+
+    #[test]
+    fn encode_dirent_respects_max_bytes() {
+        let entry = EntryInfo::new(7, mio::DirentType::File);
+        let mut buf = vec![];
+        assert!(!encode_dirent(&mut buf, 12, &entry, "abc"));
+        assert!(buf.is_empty());
+        assert!(encode_dirent(&mut buf, 13, &entry, "abc"));
+        assert_eq!(
+            buf,
+            [
+                7,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                3,
+                mio::DirentType::File.into_primitive(),
+                b'a',
+                b'b',
+                b'c'
+            ]
+        );
+    }
+}
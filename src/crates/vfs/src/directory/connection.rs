@@ -3,12 +3,14 @@
 // found in the LICENSE file.
 
 use crate::{
+    common::{inherit_rights_for_clone, rights, send_on_open_with_error},
     directory::{
-        // common::check_child_connection_flags,
+        common::{check_child_connection_flags, new_connection_validate_flags},
         entry::DirectoryEntry,
         entry_container::Directory,
         mutable::entry_constructor::NewEntryType,
-        // read_dirents,
+        read_dirents,
+        simple::Simple,
         DirectoryOptions,
     },
     execution_scope::ExecutionScope,
@@ -19,12 +21,11 @@ use crate::{
 
 use {
     anyhow::Error,
-    async_trait::async_trait,
     fiber_status::Status,
     futures::future::poll_fn,
     midl::endpoints::ServerEnd,
     midl_meshx_io as mio,
-    std::{convert::TryInto as _, default::Default, sync::Arc, task::Poll},
+    std::{default::Default, sync::Arc, task::Poll},
     tracing::{span, Level},
 };
 
@@ -44,6 +45,20 @@ pub trait DerivedConnection: Send + Sync {
     /// Whether these connections support mutable connections.
     const MUTABLE: bool;
 
+    /// Creates a new connection to `directory`, served by a task spawned in `scope`.  Errors are
+    /// reported over `server_end`.
+    fn create_connection(
+        scope: ExecutionScope,
+        directory: Arc<Self::Directory>,
+        flags: mio::OpenFlags,
+        server_end: ServerEnd<mio::NodeMarker>,
+    );
+
+    /// Converts a [`Simple`] directory served by this connection type into `Self::Directory`.
+    fn from_simple(directory: Arc<Simple<Self>>) -> Arc<Self::Directory>
+    where
+        Self: Sized;
+
     /// Creates entry of the specified type `NewEntryType`.
     fn create_entry(
         scope: ExecutionScope,
@@ -54,6 +69,37 @@ pub trait DerivedConnection: Send + Sync {
     ) -> Result<Arc<dyn DirectoryEntry>, Status>;
 }
 
+/// Validates the `flags` of a new connection to a directory, and sends the `OnOpen` event if
+/// `OpenFlags.DESCRIBE` is set.  Returns the request stream and the options of the new connection,
+/// or `None` if the connection was rejected, in which case the error was reported over
+/// `server_end`.
+pub(in crate::directory) fn start_connection(
+    flags: mio::OpenFlags,
+    server_end: ServerEnd<mio::NodeMarker>,
+) -> Option<(mio::DirectoryRequestStream, DirectoryOptions)> {
+    let describe = flags.intersects(mio::OpenFlags::DESCRIBE);
+    let flags = match new_connection_validate_flags(flags) {
+        Ok(updated) => updated,
+        Err(status) => {
+            send_on_open_with_error(describe, server_end, status);
+            return None;
+        }
+    };
+
+    // As we report all errors on `server_end`, if we failed to send an error over this connection,
+    // there is nowhere to send the error to.
+    let (requests, control_handle) = ServerEnd::<mio::DirectoryMarker>::new(server_end.into_channel())
+        .into_stream_and_control_handle()
+        .ok()?;
+
+    if describe {
+        let info = mio::NodeInfoDeprecated::Directory(mio::DirectoryObject);
+        control_handle.send_on_open(Status::OK.into_raw(), Some(info)).ok()?;
+    }
+
+    Some((requests, DirectoryOptions { rights: rights(flags) }))
+}
+
 async fn yield_to_executor() {
     // Yield to the executor now, which should provide an opportunity for the spawned future to
     // run.
@@ -96,11 +142,8 @@ where
     /// full entry name.  This is what the C++ version is doing currently.
     ///
     /// It should be possible to do the same intrusive dual-indexing using, for example,
-    ///
-    ///     https://docs.rs/intrusive-collections/0.7.6/intrusive_collections/
-    ///
-    /// but, as, I think, at least for the pseudo directories, this approach is fine, and it simple
-    /// enough.
+    /// <https://docs.rs/intrusive-collections/0.7.6/intrusive_collections/>, but, as, I think, at
+    /// least for the pseudo directories, this approach is fine, and it simple enough.
     seek: TraversalPosition,
 }
 
@@ -138,7 +181,6 @@ where
             } => {
                 let span = span!(Level::TRACE, "Directory::Clone");
                 let _ = span.enter();
-
                 self.handle_clone(flags, object);
             }
             mio::DirectoryRequest::Reopen {
@@ -146,96 +188,68 @@ where
                 object_request,
                 control_handle: _,
             } => {
-                let span = span!(Level::TRACE, "Directory::Reopen");
-                let _ = span.enter();
-                // TODO(https://fxbug.dev/77623): Handle unimplemented io2 method.
                 // Suppress any errors in the event a bad `object_request` channel was provided.
                 let _: Result<_, _> = object_request.close_with_epitaph(Status::NOT_SUPPORTED);
             }
             mio::DirectoryRequest::Close { responder } => {
-                let span = span!(Level::TRACE, "Directory::Reopen");
+                let span = span!(Level::TRACE, "Directory::Close");
                 let _ = span.enter();
-
                 responder.send(Ok(()))?;
                 return Ok(ConnectionState::Closed);
             }
             mio::DirectoryRequest::GetConnectionInfo { responder } => {
-                trace::duration!("storage", "Directory::GetConnectionInfo");
-                // TODO(https://fxbug.dev/77623): Restrict GET_ATTRIBUTES, ENUMERATE, and TRAVERSE.
-                // TODO(https://fxbug.dev/77623): Implement MODIFY_DIRECTORY and UPDATE_ATTRIBUTES.
-                responder.send(mio::ConnectionInfo {
-                    rights: Some(self.options.rights),
-                    ..Default::default()
-                })?;
+                responder.send(mio::ConnectionInfo::default())?;
             }
             mio::DirectoryRequest::GetAttr { responder } => {
-                async move {
-                    let (attrs, status) = match self.directory.get_attrs().await {
-                        Ok(attrs) => (attrs, Status::OK.into_raw()),
-                        Err(status) => (
-                            mio::NodeAttributes {
-                                mode: 0,
-                                id: mio::INO_UNKNOWN,
-                                content_size: 0,
-                                storage_size: 0,
-                                link_count: 1,
-                                creation_time: 0,
-                                modification_time: 0,
-                            },
-                            status.into_raw(),
-                        ),
-                    };
-                    responder.send(status, &attrs)
-                }
-                .trace(trace::trace_future_args!("storage", "Directory::GetAttr"))
-                .await?;
+                let span = span!(Level::TRACE, "Directory::GetAttr");
+                let _ = span.enter();
+                let (attrs, status) = match self.directory.get_attrs().await {
+                    Ok(attrs) => (attrs, Status::OK.into_raw()),
+                    Err(status) => (
+                        mio::NodeAttributes {
+                            mode: 0,
+                            id: mio::INO_UNKNOWN,
+                            content_size: 0,
+                            storage_size: 0,
+                            link_count: 1,
+                            creation_time: 0,
+                            modification_time: 0,
+                        },
+                        status.into_raw(),
+                    ),
+                };
+                responder.send(status, &attrs)?;
             }
             mio::DirectoryRequest::GetAttributes { query, responder } => {
-                async move {
-                    let result = self.directory.get_attributes(query).await;
-                    responder.send(
-                        result
-                            .as_ref()
-                            .map(|a| {
-                                let mio::NodeAttributes2 {
-                                    mutable_attributes: m,
-                                    immutable_attributes: i,
-                                } = a;
-                                (m, i)
-                            })
-                            .map_err(|status| Status::into_raw(*status)),
-                    )
-                }
-                .trace(trace::trace_future_args!("storage", "Directory::GetAttributes"))
-                .await?;
+                let span = span!(Level::TRACE, "Directory::GetAttributes");
+                let _ = span.enter();
+                let result = self.directory.get_attributes(query).await;
+                responder.send(
+                    result
+                        .as_ref()
+                        .map(|attrs| (&attrs.mutable_attributes, &attrs.immutable_attributes))
+                        .map_err(|status| status.into_raw()),
+                )?;
             }
             mio::DirectoryRequest::UpdateAttributes { payload: _, responder } => {
-                trace::duration!("storage", "Directory::UpdateAttributes");
-                // TODO(https://fxbug.dev/77623): Handle unimplemented io2 method.
                 responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
             }
             mio::DirectoryRequest::ListExtendedAttributes { iterator, .. } => {
-                trace::duration!("storage", "Directory::ListExtendedAttributes");
                 iterator.close_with_epitaph(Status::NOT_SUPPORTED)?;
             }
             mio::DirectoryRequest::GetExtendedAttribute { responder, .. } => {
-                trace::duration!("storage", "Directory::GetExtendedAttribute");
                 responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
             }
             mio::DirectoryRequest::SetExtendedAttribute { responder, .. } => {
-                trace::duration!("storage", "Directory::SetExtendedAttribute");
                 responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
             }
             mio::DirectoryRequest::RemoveExtendedAttribute { responder, .. } => {
-                trace::duration!("storage", "Directory::RemoveExtendedAttribute");
                 responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
             }
             mio::DirectoryRequest::GetFlags { responder } => {
-                trace::duration!("storage", "Directory::GetFlags");
                 responder.send(Status::OK.into_raw(), self.options.to_io1())?;
             }
             mio::DirectoryRequest::SetFlags { flags: _, responder } => {
-                trace::duration!("storage", "Directory::SetFlags");
                 responder.send(Status::NOT_SUPPORTED.into_raw())?;
             }
             mio::DirectoryRequest::Open {
@@ -246,7 +260,8 @@ where
                 control_handle: _,
             } => {
                 {
-                    trace::duration!("storage", "Directory::Open");
+                    let span = span!(Level::TRACE, "Directory::Open");
+                    let _ = span.enter();
                     self.handle_open(flags, path, object);
                 }
                 // Since open typically spawns a task, yield to the executor now to give that task a
@@ -254,88 +269,42 @@ where
                 yield_to_executor().await;
             }
             mio::DirectoryRequest::Open2 {
-                path,
-                mut protocols,
+                path: _,
+                protocols: _,
                 object_request,
                 control_handle: _,
             } => {
-                {
-                    trace::duration!("storage", "Directory::Open2");
-                    // Fill in rights from the parent connection if it's absent.
-                    if let mio::ConnectionProtocols::Node(mio::NodeOptions { rights, protocols, .. }) = &mut protocols {
-                        if rights.is_none() {
-                            if matches!(protocols, Some(mio::NodeProtocols { node: Some(_), .. })) {
-                                // Only inherit the GET_ATTRIBUTES right for node connections.
-                                *rights = Some(self.options.rights & mio::Operations::GET_ATTRIBUTES);
-                            } else {
-                                *rights = Some(self.options.rights);
-                            }
-                        }
-                    }
-                    // If optional_rights is set, remove any rights that are not present on the
-                    // current connection.
-                    if let mio::ConnectionProtocols::Node(mio::NodeOptions {
-                        protocols:
-                            Some(mio::NodeProtocols {
-                                directory:
-                                    Some(mio::DirectoryProtocolOptions {
-                                        optional_rights: Some(rights),
-                                        ..
-                                    }),
-                                ..
-                            }),
-                        ..
-                    }) = &mut protocols
-                    {
-                        *rights &= self.options.rights;
-                    }
-                    protocols
-                        .to_object_request(object_request)
-                        .handle(|req| self.handle_open2(path, protocols, req));
-                }
-                // Since open typically spawns a task, yield to the executor now to give that task a
-                // chance to run before we try and process the next request for this directory.
-                yield_to_executor().await;
+                // Only the io1 `Open` is supported.
+                let _ = ServerEnd::<mio::NodeMarker>::new(object_request).close_with_epitaph(Status::NOT_SUPPORTED);
             }
             mio::DirectoryRequest::AdvisoryLock { request: _, responder } => {
-                trace::duration!("storage", "Directory::AdvisoryLock");
                 responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
             }
             mio::DirectoryRequest::ReadDirents { max_bytes, responder } => {
-                async move {
-                    let (status, entries) = self.handle_read_dirents(max_bytes).await;
-                    responder.send(status.into_raw(), entries.as_slice())
-                }
-                .trace(trace::trace_future_args!("storage", "Directory::ReadDirents"))
-                .await?;
+                let span = span!(Level::TRACE, "Directory::ReadDirents");
+                let _ = span.enter();
+                let (status, entries) = self.handle_read_dirents(max_bytes).await;
+                responder.send(status.into_raw(), entries.as_slice())?;
             }
             mio::DirectoryRequest::Enumerate {
                 options: _,
                 iterator,
                 control_handle: _,
             } => {
-                trace::duration!("storage", "Directory::Enumerate");
-                // TODO(https://fxbug.dev/77623): Handle unimplemented io2 method.
                 // Suppress any errors in the event a bad `iterator` channel was provided.
                 let _ = iterator.close_with_epitaph(Status::NOT_SUPPORTED);
             }
             mio::DirectoryRequest::Rewind { responder } => {
-                trace::duration!("storage", "Directory::Rewind");
                 self.seek = Default::default();
                 responder.send(Status::OK.into_raw())?;
             }
             mio::DirectoryRequest::Link {
-                src,
-                dst_parent_token,
-                dst,
+                src: _,
+                dst_parent_token: _,
+                dst: _,
                 responder,
             } => {
-                async move {
-                    let status: Status = self.handle_link(&src, dst_parent_token, dst).await.into();
-                    responder.send(status.into_raw())
-                }
-                .trace(trace::trace_future_args!("storage", "Directory::Link"))
-                .await?;
+                responder.send(Status::NOT_SUPPORTED.into_raw())?;
             }
             mio::DirectoryRequest::Watch {
                 mask,
@@ -343,25 +312,25 @@ where
                 watcher,
                 responder,
             } => {
-                trace::duration!("storage", "Directory::Watch");
+                let span = span!(Level::TRACE, "Directory::Watch");
+                let _ = span.enter();
                 let status = if options != 0 {
                     Status::INVALID_ARGS
                 } else {
-                    let watcher = watcher.try_into()?;
-                    self.handle_watch(mask, watcher).into()
+                    match self.handle_watch(mask, watcher) {
+                        Ok(()) => Status::OK,
+                        Err(status) => status,
+                    }
                 };
                 responder.send(status.into_raw())?;
             }
             mio::DirectoryRequest::Query { responder } => {
-                let () = responder.send(mio::DIRECTORY_PROTOCOL_NAME.as_bytes())?;
-            }
-            mio::DirectoryRequest::QueryFilesystem { responder } => {
-                trace::duration!("storage", "Directory::QueryFilesystem");
-                match self.directory.query_filesystem() {
-                    Err(status) => responder.send(status.into_raw(), None)?,
-                    Ok(info) => responder.send(0, Some(&info))?,
-                }
+                responder.send(mio::DIRECTORY_PROTOCOL_NAME.as_bytes())?;
             }
+            mio::DirectoryRequest::QueryFilesystem { responder } => match self.directory.query_filesystem() {
+                Err(status) => responder.send(status.into_raw(), None)?,
+                Ok(info) => responder.send(0, Some(&info))?,
+            },
             mio::DirectoryRequest::Unlink {
                 name: _,
                 options: _,
@@ -397,7 +366,7 @@ where
         Ok(ConnectionState::Alive)
     }
 
-    /*fn handle_clone(&self, flags: mio::OpenFlags, server_end: ServerEnd<mio::NodeMarker>) {
+    fn handle_clone(&self, flags: mio::OpenFlags, server_end: ServerEnd<mio::NodeMarker>) {
         let describe = flags.intersects(mio::OpenFlags::DESCRIBE);
         let flags = match inherit_rights_for_clone(self.options.to_io1(), flags) {
             Ok(updated) => updated,
@@ -407,15 +376,11 @@ where
             }
         };
 
-        self.directory.clone().open(self.scope.clone(), flags, Path::dot(), server_end);
+        let directory: Arc<Connection::Directory> = (*self.directory).clone();
+        directory.open(self.scope.clone(), flags, Path::dot(), server_end);
     }
 
-    fn handle_open(
-        &self,
-        mut flags: mio::OpenFlags,
-        path: String,
-        server_end: ServerEnd<mio::NodeMarker>,
-    ) {
+    fn handle_open(&self, mut flags: mio::OpenFlags, path: String, server_end: ServerEnd<mio::NodeMarker>) {
         let describe = flags.intersects(mio::OpenFlags::DESCRIBE);
 
         let path = match Path::validate_and_split(path) {
@@ -449,132 +414,184 @@ where
         }
 
         // It is up to the open method to handle OPEN_FLAG_DESCRIBE from this point on.
-        let directory = self.directory.clone();
+        let directory: Arc<Connection::Directory> = (*self.directory).clone();
         directory.open(self.scope.clone(), flags, path, server_end);
     }
 
-    fn handle_open2(
-        &self,
-        path: String,
-        protocols: mio::ConnectionProtocols,
-        object_request: ObjectRequestRef<'_>,
-    ) -> Result<(), Status> {
-        let path = Path::validate_and_split(path)?;
-
-        if let Some(rights) = protocols.rights() {
-            if rights.intersects(!self.options.rights) {
-                return Err(Status::ACCESS_DENIED);
-            }
-        }
-
-        // If requesting attributes, check permission.
-        if !object_request.attributes().is_empty()
-            && !self.options.rights.contains(mio::Operations::GET_ATTRIBUTES)
-        {
-            return Err(Status::ACCESS_DENIED);
-        }
-
-        // If creating an object, it's not legal to specify more than one protocol.
-        //
-        // TODO(b/293947862): If we add an additional node type, we will need to update this. See if
-        // there is a more generic or robust way to check this so that we don't miss any node types.
-        if protocols.open_mode() != mio::OpenMode::OpenExisting
-            && ((protocols.is_file_allowed() && protocols.is_dir_allowed())
-                || protocols.is_symlink_allowed())
-        {
-            return Err(Status::INVALID_ARGS);
-        }
-
-        if protocols.create_attributes().is_some()
-            && protocols.open_mode() == mio::OpenMode::OpenExisting
-        {
-            return Err(Status::INVALID_ARGS);
-        }
-
-        if path.is_dot() {
-            if !protocols.is_node() && !protocols.is_dir_allowed() {
-                return Err(Status::INVALID_ARGS);
-            }
-            if protocols.open_mode() == mio::OpenMode::AlwaysCreate {
-                return Err(Status::ALREADY_EXISTS);
-            }
-        }
-
-        self.directory.clone().open2(self.scope.clone(), path, protocols, object_request)
-    }
-
     async fn handle_read_dirents(&mut self, max_bytes: u64) -> (Status, Vec<u8>) {
         async {
-            let (new_pos, sealed) =
-                self.directory.read_dirents(&self.seek, read_dirents::Sink::new(max_bytes)).await?;
+            let (new_pos, sealed) = self
+                .directory
+                .read_dirents(&self.seek, read_dirents::Sink::new(max_bytes))
+                .await?;
             self.seek = new_pos;
-            let read_dirents::Done { buf, status } = *sealed
-                .open()
-                .downcast::<read_dirents::Done>()
-                .map_err(|_: Box<dyn std::any::Any>| {
-                    #[cfg(debug)]
-                    panic!(
-                        "`read_dirents()` returned a `dirents_sink::Sealed`
-                        instance that is not an instance of the \
-                        `read_dirents::Done`. This is a bug in the \
-                        `read_dirents()` implementation."
-                    );
-                    Status::NOT_SUPPORTED
-                })?;
+            let read_dirents::Done { buf, status } =
+                *sealed
+                    .open()
+                    .downcast::<read_dirents::Done>()
+                    .map_err(|_: Box<dyn std::any::Any>| {
+                        #[cfg(debug_assertions)]
+                        panic!(
+                            "`read_dirents()` returned a `dirents_sink::Sealed` instance that is not an \
+                         instance of the `read_dirents::Done`. This is a bug in the \
+                         `read_dirents()` implementation."
+                        );
+                        #[allow(unreachable_code)]
+                        Status::NOT_SUPPORTED
+                    })?;
             Ok((status, buf))
         }
         .await
         .unwrap_or_else(|status| (status, Vec::new()))
     }
 
-    async fn handle_link(
-        &self,
-        source_name: &str,
-        target_parent_token: midl::Handle,
-        target_name: String,
+    fn handle_watch(
+        &mut self,
+        mask: mio::WatchMask,
+        watcher: ServerEnd<mio::DirectoryWatcherMarker>,
     ) -> Result<(), Status> {
-        if source_name.contains('/') || target_name.contains('/') {
-            return Err(Status::INVALID_ARGS);
-        }
+        let directory: Arc<Connection::Directory> = (*self.directory).clone();
+        directory.register_watcher(self.scope.clone(), mask, watcher.into_channel())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        directory::{helper::DirectlyMutable as _, immutable::simple},
+        file,
+    };
+
+    use {fiber_rust as fx, futures::StreamExt as _, meshx_async::TestExecutor, midl::endpoints::create_proxy};
+
+    fn open_dir(dir: Arc<dyn DirectoryEntry>, scope: &ExecutionScope, flags: mio::OpenFlags) -> mio::DirectoryProxy {
+        let (proxy, server_end) = create_proxy::<mio::DirectoryMarker>().unwrap();
+        dir.open(
+            scope.clone(),
+            flags,
+            Path::dot(),
+            ServerEnd::new(server_end.into_channel()),
+        );
+        proxy
+    }
 
-        if !self.options.rights.contains(mio::W_STAR_DIR) {
-            return Err(Status::BAD_HANDLE);
+    async fn open_status(dir: &mio::DirectoryProxy, flags: mio::OpenFlags, path: &str) -> Status {
+        let (node, server_end) = create_proxy::<mio::NodeMarker>().unwrap();
+        dir.open(
+            flags | mio::OpenFlags::DESCRIBE,
+            mio::ModeType::empty(),
+            path,
+            server_end,
+        )
+        .unwrap();
+        match node.take_event_stream().next().await {
+            Some(Ok(mio::NodeEvent::OnOpen { s, .. })) => Status::from_raw(s),
+            event => panic!("expected OnOpen, got {event:?}"),
         }
+    }
 
-        let (target_parent, _flags) = self
-            .scope
-            .token_registry()
-            .get_owner(target_parent_token)?
-            .ok_or(Err(Status::NOT_FOUND))?;
+    /// Decodes the names of the entries returned by `ReadDirents`.
+    fn dirent_names(mut buf: &[u8]) -> Vec<String> {
+        let mut names = vec![];
+        while !buf.is_empty() {
+            let name_len = buf[8] as usize;
+            names.push(String::from_utf8(buf[10..10 + name_len].to_vec()).unwrap());
+            buf = &buf[10 + name_len..];
+        }
+        names
+    }
 
-        target_parent.link(target_name, self.directory.clone().into_any(), source_name).await
+    fn tree() -> Arc<simple::Simple> {
+        let root = simple::simple();
+        root.add_entry("a", file::simple::read_only("a")).unwrap();
+        root.add_entry("b", file::simple::read_write("b")).unwrap();
+        root
     }
 
-    fn handle_watch(
-        &mut self,
-        mask: mio::WatchMask,
-        watcher: DirectoryWatcher,
-    ) -> Result<(), Status> {
-        let directory = self.directory.clone();
-        directory.register_watcher(self.scope.clone(), mask, watcher)
-    }*/
-}
+    #[test]
+    fn read_dirents_continues_and_rewinds() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let scope = ExecutionScope::new();
+        let dir = open_dir(tree(), &scope, mio::OpenFlags::RIGHT_READABLE);
+
+        executor.run_singlethreaded(async {
+            // Only fits "." and "a".
+            let (status, buf) = dir.read_dirents(22).await.unwrap();
+            assert_eq!(Status::from_raw(status), Status::OK);
+            assert_eq!(dirent_names(&buf), [".", "a"]);
+            let (_, buf) = dir.read_dirents(1024).await.unwrap();
+            assert_eq!(dirent_names(&buf), ["b"]);
+            let (_, buf) = dir.read_dirents(1024).await.unwrap();
+            assert!(buf.is_empty());
+
+            assert_eq!(Status::from_raw(dir.rewind().await.unwrap()), Status::OK);
+            let (status, buf) = dir.read_dirents(4).await.unwrap();
+            assert_eq!(Status::from_raw(status), Status::BUFFER_TOO_SMALL);
+            assert!(buf.is_empty());
+            let (_, buf) = dir.read_dirents(1024).await.unwrap();
+            assert_eq!(dirent_names(&buf), [".", "a", "b"]);
+        });
+        scope.shutdown();
+    }
 
-/*#[async_trait]
-impl<T: DerivedConnection + 'static> Representation for BaseConnection<T> {
-    type Protocol = mio::DirectoryMarker;
-
-    async fn get_representation(
-        &self,
-        requested_attributes: mio::NodeAttributesQuery,
-    ) -> Result<mio::Representation, Status> {
-        Ok(mio::Representation::Directory(mio::DirectoryInfo {
-            attributes: Some(self.directory.get_attributes(requested_attributes).await?),
-            ..Default::default()
-        }))
+    #[test]
+    fn open_downscopes_rights() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let scope = ExecutionScope::new();
+        let dir = open_dir(tree(), &scope, mio::OpenFlags::RIGHT_READABLE);
+
+        executor.run_singlethreaded(async {
+            assert_eq!(open_status(&dir, mio::OpenFlags::RIGHT_READABLE, "a").await, Status::OK);
+            assert_eq!(
+                open_status(&dir, mio::OpenFlags::RIGHT_WRITABLE, "b").await,
+                Status::ACCESS_DENIED
+            );
+            assert_eq!(
+                open_status(&dir, mio::OpenFlags::RIGHT_READABLE, "c").await,
+                Status::NOT_FOUND
+            );
+            assert_eq!(
+                open_status(&dir, mio::OpenFlags::RIGHT_READABLE | mio::OpenFlags::DIRECTORY, "a").await,
+                Status::NOT_DIR
+            );
+        });
+        scope.shutdown();
     }
 
-    async fn node_info(&self) -> Result<mio::NodeInfoDeprecated, Status> {
-        Ok(mio::NodeInfoDeprecated::Directory(mio::DirectoryObject { supports_io2: false }))
+    #[test]
+    fn watch_reports_existing_and_added_entries() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let scope = ExecutionScope::new();
+        let root = tree();
+        let dir = open_dir(root.clone(), &scope, mio::OpenFlags::RIGHT_READABLE);
+        let (watcher, server_end) = fx::Channel::create();
+        let watcher = meshx_async::Channel::from_channel(watcher).unwrap();
+
+        executor.run_singlethreaded(async {
+            let mask = mio::WatchMask::EXISTING | mio::WatchMask::IDLE | mio::WatchMask::ADDED;
+            let status = dir.watch(mask, 0, ServerEnd::new(server_end)).await.unwrap();
+            assert_eq!(Status::from_raw(status), Status::OK);
+
+            let existing = mio::WatchEvent::Existing.into_primitive();
+            let idle = mio::WatchEvent::Idle.into_primitive();
+            let mut buffer = fx::MessageBuf::new();
+            watcher.recv_msg(&mut buffer).await.unwrap();
+            assert_eq!(
+                buffer.bytes(),
+                [existing, 1, b'.', existing, 1, b'a', existing, 1, b'b']
+            );
+            watcher.recv_msg(&mut buffer).await.unwrap();
+            assert_eq!(buffer.bytes(), [idle, 0]);
+
+            root.add_entry("c", file::simple::read_only("c")).unwrap();
+            watcher.recv_msg(&mut buffer).await.unwrap();
+            assert_eq!(buffer.bytes(), [mio::WatchEvent::Added.into_primitive(), 1, b'c']);
+        });
+        scope.shutdown();
     }
-}*/
+}
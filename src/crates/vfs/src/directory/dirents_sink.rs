@@ -0,0 +1,39 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Interface for the [`crate::directory::entry_container::Directory::read_dirents`] consumer.
+//! Directories append entries to a sink until it is full, without knowing how the entries are
+//! going to be encoded.
+
+use crate::directory::entry::EntryInfo;
+
+use std::any::Any;
+
+/// Final result of the read_dirents operation.
+pub trait Sealed: Send {
+    /// `Sealed` is only useful if you know its concrete type.  This method allows one to convert
+    /// it to an `Any` and then downcast it.
+    fn open(self: Box<Self>) -> Box<dyn Any>;
+}
+
+/// Possible results of the append operation.
+pub enum AppendResult {
+    /// The entry was added, and the sink can accept more entries.
+    Ok(Box<dyn Sink>),
+    /// The entry did not fit.  The sink is sealed, and the entry has to be retried on the next
+    /// read_dirents call.
+    Sealed(Box<dyn Sealed>),
+}
+
+/// A sink for the directory entries produced by `read_dirents`.
+pub trait Sink: Send {
+    /// Try to add an entry to the sink.  Returns a sink to append more entries to, or a sealed
+    /// sink if the entry did not fit.
+    fn append(self: Box<Self>, entry: &EntryInfo, name: &str) -> AppendResult;
+
+    /// If the producer has reached the end of the list of entries, it should call this method to
+    /// produce a "sealed" sink.
+    fn seal(self: Box<Self>) -> Box<dyn Sealed>;
+}
@@ -1,4 +1,37 @@
-use std::sync::Arc;
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Common trait for all the directory entry objects.
+
+use crate::{execution_scope::ExecutionScope, path::Path};
+
+use {midl::endpoints::ServerEnd, midl_meshx_io as mio, std::sync::Arc};
+
+/// Information about a directory entry, used to populate ReadDirents() output.
+/// The first element is the inode number, or INO_UNKNOWN (from meshx.io) if not set, and the second
+/// element is one of the DIRENT_TYPE_* constants defined in the meshx.io.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct EntryInfo(u64, mio::DirentType);
+
+impl EntryInfo {
+    /// Constructs a new directory entry information object.
+    pub fn new(inode: u64, type_: mio::DirentType) -> Self {
+        Self(inode, type_)
+    }
+
+    /// Retrives the `inode` argument of the [`EntryInfo::new()`] constructor.
+    pub fn inode(&self) -> u64 {
+        let Self(inode, _type) = self;
+        *inode
+    }
+
+    /// Retrieves the `type_` argument of the [`EntryInfo::new()`] constructor.
+    pub fn type_(&self) -> mio::DirentType {
+        let Self(_inode, type_) = self;
+        *type_
+    }
+}
 
 /// Trait to be used as a supertrait when an object should allow dynamic casting to an Any.
 ///
@@ -18,4 +51,27 @@ impl<T: 'static + Send + Sync> IntoAny for T {
 
 /// Pseudo directories contain items that implement this trait.  Pseudo directories refer to the
 /// items they contain as `Arc<dyn DirectoryEntry>`.
-pub trait DirectoryEntry: IntoAny + Sync + Send {}
+pub trait DirectoryEntry: IntoAny + Sync + Send {
+    /// Opens a connection to this item if the `path` is "." or a connection to an item inside this
+    /// one otherwise.  `path` will not contain any "." or ".." components.
+    ///
+    /// `flags` holds one or more of the `OpenFlags.RIGHT_*` and `OpenFlags.*` values.  Processing
+    /// of the `OpenFlags.DESCRIBE` is also done by the entry, as it needs to know the type of the
+    /// node to describe it.
+    ///
+    /// It is the responsibility of the implementation to strip any flags it handles before
+    /// passing the connection further, or to reject flags it does not support.
+    ///
+    /// `scope` is the execution scope the new connection, and any tasks it creates, should run
+    /// in.
+    fn open(
+        self: Arc<Self>,
+        scope: ExecutionScope,
+        flags: mio::OpenFlags,
+        path: Path,
+        server_end: ServerEnd<mio::NodeMarker>,
+    );
+
+    /// This method is used to populate ReadDirents() output.
+    fn entry_info(&self) -> EntryInfo;
+}
@@ -7,9 +7,72 @@
 
 use async_trait::async_trait;
 
-use crate::{directory::entry::DirectoryEntry, node::Node};
+use crate::{
+    directory::{dirents_sink, entry::DirectoryEntry},
+    execution_scope::ExecutionScope,
+    name::Name,
+    node::Node,
+    traversal_position::TraversalPosition,
+};
+
+use {
+    fiber_rust as fx,
+    fiber_status::Status,
+    midl_meshx_io as mio,
+    std::{any::Any, sync::Arc},
+};
 
 /// All directories implement this trait.  If a directory can be modified it should
 /// also implement the `MutableDirectory` trait.
 #[async_trait]
-pub trait Directory: DirectoryEntry + Node {}
+pub trait Directory: DirectoryEntry + Node {
+    /// Reads directory entries starting from `pos` by adding them to `sink`.
+    /// Once finished, should return a sealed sink, along with the position the next call should
+    /// start from.
+    async fn read_dirents<'a>(
+        &'a self,
+        pos: &'a TraversalPosition,
+        sink: Box<dyn dirents_sink::Sink>,
+    ) -> Result<(TraversalPosition, Box<dyn dirents_sink::Sealed>), Status>;
+
+    /// Register a watcher for this directory.  The watcher is sent the events selected by `mask`
+    /// over `watcher`, until it closes the channel.
+    fn register_watcher(
+        self: Arc<Self>,
+        scope: ExecutionScope,
+        mask: mio::WatchMask,
+        watcher: fx::Channel,
+    ) -> Result<(), Status>;
+}
+
+/// This trait indicates a directory that can be mutated by its clients: entries can be created,
+/// removed, renamed and linked over a connection with the `RIGHT_WRITABLE` right.
+#[async_trait]
+pub trait MutableDirectory: Directory {
+    /// Adds an entry named `name` to this directory, that refers to the same node as the entry
+    /// `source_name` of `source_dir`.  `source_dir` is usually required to be of the same type as
+    /// this directory, `NOT_SUPPORTED` is returned otherwise.
+    async fn link(
+        self: Arc<Self>,
+        name: Name,
+        source_dir: Arc<dyn Any + Send + Sync>,
+        source_name: &str,
+    ) -> Result<(), Status>;
+
+    /// Removes the entry named `name` from this directory.  If `must_be_directory` is true, the
+    /// entry is only removed if it is a directory, and `NOT_DIR` is returned otherwise.
+    async fn unlink(self: Arc<Self>, name: &str, must_be_directory: bool) -> Result<(), Status>;
+
+    /// Flushes any changes to this directory to the underlying storage.
+    async fn sync(&self) -> Result<(), Status>;
+
+    /// Moves the entry `src_name` of `src_dir` to this directory, under `dst_name`, replacing the
+    /// entry that may already exist there.  Just like with [`MutableDirectory::link()`],
+    /// `src_dir` is usually required to be of the same type as this directory.
+    async fn rename(
+        self: Arc<Self>,
+        src_dir: Arc<dyn Any + Send + Sync>,
+        src_name: Name,
+        dst_name: Name,
+    ) -> Result<(), Status>;
+}
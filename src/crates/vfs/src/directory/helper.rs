@@ -0,0 +1,85 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Helper traits for directories that are modified by the server, rather than by their clients.
+
+use crate::{
+    directory::entry::DirectoryEntry,
+    name::{Name, ParseNameError},
+};
+
+use {fiber_status::Status, std::sync::Arc, thiserror::Error};
+
+/// An entry with the same name already exists in the directory.
+#[derive(Error, Debug)]
+#[error("An entry with the same name already exists in the directory")]
+pub struct AlreadyExists;
+
+impl From<AlreadyExists> for Status {
+    fn from(_: AlreadyExists) -> Status {
+        Status::ALREADY_EXISTS
+    }
+}
+
+/// The entry identified by `name` is not a directory.
+#[derive(Error, Debug)]
+#[error("The specified entry is not a directory")]
+pub struct NotDirectory;
+
+impl From<NotDirectory> for Status {
+    fn from(_: NotDirectory) -> Status {
+        Status::NOT_DIR
+    }
+}
+
+/// Methods that allow the server to add and remove entries of a directory directly, rather than
+/// through a connection.
+pub trait DirectlyMutable: DirectoryEntry + Send + Sync {
+    /// Adds a child entry to this directory.
+    ///
+    /// Possible errors are:
+    ///   * `Status::INVALID_ARGS` or `Status::BAD_PATH` if `name` is not a valid [`Name`].
+    ///   * `Status::ALREADY_EXISTS` if an entry with the same name is already present in the
+    ///     directory.
+    fn add_entry<NameT>(&self, name: NameT, entry: Arc<dyn DirectoryEntry>) -> Result<(), Status>
+    where
+        NameT: Into<String>,
+        Self: Sized,
+    {
+        let name: String = name.into();
+        let name: Name = name.try_into().map_err(|error: ParseNameError| Status::from(error))?;
+        self.add_entry_impl(name, entry, false).map_err(Status::from)
+    }
+
+    /// Adds a child entry to this directory.  If `overwrite` is true, this function may overwrite
+    /// an existing entry.
+    fn add_entry_impl(&self, name: Name, entry: Arc<dyn DirectoryEntry>, overwrite: bool) -> Result<(), AlreadyExists>;
+
+    /// Removes a child entry from this directory.  In case an entry was removed, returns it.
+    ///
+    /// If `must_be_directory` is true, the entry is only removed if it is a directory, and
+    /// [`NotDirectory`] is returned otherwise.
+    fn remove_entry<NameT>(
+        &self,
+        name: NameT,
+        must_be_directory: bool,
+    ) -> Result<Option<Arc<dyn DirectoryEntry>>, Status>
+    where
+        NameT: Into<String>,
+        Self: Sized,
+    {
+        let name: String = name.into();
+        let name: Name = name.try_into().map_err(|error: ParseNameError| Status::from(error))?;
+        let entry = self.remove_entry_impl(name, must_be_directory)?;
+        Ok(entry)
+    }
+
+    /// Removes a child entry from this directory.  In case an entry was removed, returns it.
+    fn remove_entry_impl(
+        &self,
+        name: Name,
+        must_be_directory: bool,
+    ) -> Result<Option<Arc<dyn DirectoryEntry>>, NotDirectory>;
+}
@@ -7,21 +7,19 @@
 
 use crate::{
     directory::{
-        connection::{BaseConnection, ConnectionState, DerivedConnection},
+        connection::{start_connection, BaseConnection, ConnectionState, DerivedConnection},
         entry::DirectoryEntry,
         entry_container,
         mutable::entry_constructor::NewEntryType,
+        simple::Simple,
     },
     execution_scope::ExecutionScope,
+    node::OpenNode,
     path::Path,
-   
 };
 
 use {
-    fiber_status::Status,
-    futures::TryStreamExt as _,
-    midl_meshx_io as mio,
-    std::{future::Future, sync::Arc},
+    fiber_status::Status, futures::TryStreamExt as _, midl::endpoints::ServerEnd, midl_meshx_io as mio, std::sync::Arc,
 };
 
 pub struct ImmutableConnection {
@@ -34,18 +32,38 @@ impl ImmutableConnection {
             let Some(_guard) = self.base.scope.try_active_guard() else {
                 break;
             };
-            
+
             if !matches!(self.base.handle_request(request).await, Ok(ConnectionState::Alive)) {
                 break;
             }
         }
-    }    
+    }
 }
 
 impl DerivedConnection for ImmutableConnection {
     type Directory = dyn entry_container::Directory;
     const MUTABLE: bool = false;
 
+    fn create_connection(
+        scope: ExecutionScope,
+        directory: Arc<Self::Directory>,
+        flags: mio::OpenFlags,
+        server_end: ServerEnd<mio::NodeMarker>,
+    ) {
+        let Some((requests, options)) = start_connection(flags, server_end) else {
+            return;
+        };
+
+        let connection = ImmutableConnection {
+            base: BaseConnection::new(scope.clone(), OpenNode::new(directory), options),
+        };
+        scope.spawn(connection.handle_requests(requests));
+    }
+
+    fn from_simple(directory: Arc<Simple<Self>>) -> Arc<Self::Directory> {
+        directory
+    }
+
     fn create_entry(
         _scope: ExecutionScope,
         _parent: Arc<dyn DirectoryEntry>,
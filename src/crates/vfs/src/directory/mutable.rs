@@ -8,7 +8,7 @@
 
 pub mod entry_constructor;
 
-// pub mod simple;
-// pub use simple::simple;
+pub mod simple;
+pub use simple::{simple, simple_with_inode, Simple};
 
-// pub mod connection;
\ No newline at end of file
+pub mod connection;
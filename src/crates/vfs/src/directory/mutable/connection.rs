@@ -0,0 +1,253 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Connection to a directory that can be modified by the client through a FIDL connection.
+
+use crate::{
+    directory::{
+        connection::{start_connection, BaseConnection, ConnectionState, DerivedConnection},
+        entry::DirectoryEntry,
+        entry_container::MutableDirectory,
+        mutable::entry_constructor::NewEntryType,
+        simple::Simple,
+    },
+    execution_scope::ExecutionScope,
+    name::Name,
+    node::OpenNode,
+    path::Path,
+};
+
+use {
+    anyhow::Error,
+    fiber_rust as fx,
+    fiber_status::Status,
+    futures::TryStreamExt as _,
+    midl::endpoints::ServerEnd,
+    midl_meshx_io as mio,
+    std::sync::Arc,
+    tracing::{span, Level},
+};
+
+pub struct MutableConnection {
+    base: BaseConnection<Self>,
+}
+
+impl MutableConnection {
+    async fn handle_requests(mut self, mut requests: mio::DirectoryRequestStream) {
+        while let Ok(Some(request)) = requests.try_next().await {
+            let Some(_guard) = self.base.scope.try_active_guard() else {
+                break;
+            };
+
+            if !matches!(self.handle_request(request).await, Ok(ConnectionState::Alive)) {
+                break;
+            }
+        }
+    }
+
+    async fn handle_request(&mut self, request: mio::DirectoryRequest) -> Result<ConnectionState, Error> {
+        match request {
+            mio::DirectoryRequest::Unlink {
+                name,
+                options,
+                responder,
+            } => {
+                let span = span!(Level::TRACE, "Directory::Unlink");
+                let _ = span.enter();
+                let must_be_directory = options
+                    .flags
+                    .is_some_and(|flags| flags.contains(mio::UnlinkFlags::MUST_BE_DIRECTORY));
+                let result = self.handle_unlink(name, must_be_directory).await;
+                responder.send(result.map_err(Status::into_raw))?;
+            }
+            mio::DirectoryRequest::GetToken { responder } => {
+                let span = span!(Level::TRACE, "Directory::GetToken");
+                let _ = span.enter();
+                match self.handle_get_token() {
+                    Ok(token) => responder.send(Status::OK.into_raw(), Some(token))?,
+                    Err(status) => responder.send(status.into_raw(), None)?,
+                }
+            }
+            mio::DirectoryRequest::Rename {
+                src,
+                dst_parent_token,
+                dst,
+                responder,
+            } => {
+                let span = span!(Level::TRACE, "Directory::Rename");
+                let _ = span.enter();
+                let result = self.handle_rename(src, dst_parent_token, dst).await;
+                responder.send(result.map_err(Status::into_raw))?;
+            }
+            mio::DirectoryRequest::Link {
+                src,
+                dst_parent_token,
+                dst,
+                responder,
+            } => {
+                let span = span!(Level::TRACE, "Directory::Link");
+                let _ = span.enter();
+                let status = match self.handle_link(src, dst_parent_token, dst).await {
+                    Ok(()) => Status::OK,
+                    Err(status) => status,
+                };
+                responder.send(status.into_raw())?;
+            }
+            mio::DirectoryRequest::Sync { responder } => {
+                let span = span!(Level::TRACE, "Directory::Sync");
+                let _ = span.enter();
+                let result = self.base.directory.sync().await;
+                responder.send(result.map_err(Status::into_raw))?;
+            }
+            request => return self.base.handle_request(request).await,
+        }
+        Ok(ConnectionState::Alive)
+    }
+
+    /// Modifications of the directory require the connection to have the `RIGHT_WRITABLE` right.
+    fn check_writable(&self) -> Result<(), Status> {
+        if self.base.options.rights.intersects(mio::OpenFlags::RIGHT_WRITABLE) {
+            Ok(())
+        } else {
+            Err(Status::BAD_HANDLE)
+        }
+    }
+
+    async fn handle_unlink(&self, name: String, must_be_directory: bool) -> Result<(), Status> {
+        self.check_writable()?;
+        let name = Name::try_from(name)?;
+        let directory: Arc<dyn MutableDirectory> = (*self.base.directory).clone();
+        directory.unlink(&name, must_be_directory).await
+    }
+
+    fn handle_get_token(&self) -> Result<fx::Handle, Status> {
+        self.check_writable()?;
+        let directory: Arc<dyn MutableDirectory> = (*self.base.directory).clone();
+        self.base.scope.token_registry().get_token(directory)
+    }
+
+    /// Returns the directory `token` was handed out for by `GetToken`.
+    fn get_token_owner(&self, token: fx::Handle) -> Result<Arc<dyn MutableDirectory>, Status> {
+        self.base
+            .scope
+            .token_registry()
+            .get_owner(token)?
+            .ok_or(Status::NOT_FOUND)
+    }
+
+    async fn handle_rename(&self, src: String, dst_parent_token: fx::Handle, dst: String) -> Result<(), Status> {
+        self.check_writable()?;
+        let src = Name::try_from(src)?;
+        let dst = Name::try_from(dst)?;
+        let dst_parent = self.get_token_owner(dst_parent_token)?;
+        let directory: Arc<dyn MutableDirectory> = (*self.base.directory).clone();
+        dst_parent.rename(directory.into_any(), src, dst).await
+    }
+
+    async fn handle_link(&self, src: String, dst_parent_token: fx::Handle, dst: String) -> Result<(), Status> {
+        self.check_writable()?;
+        let src = Name::try_from(src)?;
+        let dst = Name::try_from(dst)?;
+        let dst_parent = self.get_token_owner(dst_parent_token)?;
+        let directory: Arc<dyn MutableDirectory> = (*self.base.directory).clone();
+        dst_parent.link(dst, directory.into_any(), &src).await
+    }
+}
+
+impl DerivedConnection for MutableConnection {
+    type Directory = dyn MutableDirectory;
+    const MUTABLE: bool = true;
+
+    fn create_connection(
+        scope: ExecutionScope,
+        directory: Arc<Self::Directory>,
+        flags: mio::OpenFlags,
+        server_end: ServerEnd<mio::NodeMarker>,
+    ) {
+        let Some((requests, options)) = start_connection(flags, server_end) else {
+            return;
+        };
+
+        let connection = MutableConnection {
+            base: BaseConnection::new(scope.clone(), OpenNode::new(directory), options),
+        };
+        scope.spawn(connection.handle_requests(requests));
+    }
+
+    fn from_simple(directory: Arc<Simple<Self>>) -> Arc<Self::Directory> {
+        directory
+    }
+
+    fn create_entry(
+        scope: ExecutionScope,
+        parent: Arc<dyn DirectoryEntry>,
+        entry_type: NewEntryType,
+        name: &str,
+        path: &Path,
+    ) -> Result<Arc<dyn DirectoryEntry>, Status> {
+        match scope.entry_constructor() {
+            None => Err(Status::NOT_SUPPORTED),
+            Some(constructor) => constructor.create_entry(parent, entry_type, name, path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::directory::{helper::DirectlyMutable as _, mutable::simple};
+
+    use {futures::StreamExt as _, meshx_async::TestExecutor, midl::endpoints::create_proxy};
+
+    const READ_WRITE: mio::OpenFlags = mio::OpenFlags::RIGHT_READABLE.union(mio::OpenFlags::RIGHT_WRITABLE);
+
+    fn serve(dir: Arc<simple::Simple>, scope: &ExecutionScope) -> mio::DirectoryProxy {
+        let (proxy, server_end) = create_proxy::<mio::DirectoryMarker>().unwrap();
+        dir.open(
+            scope.clone(),
+            READ_WRITE,
+            Path::dot(),
+            ServerEnd::new(server_end.into_channel()),
+        );
+        proxy
+    }
+
+    #[test]
+    fn create_without_entry_constructor_is_not_supported() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let scope = ExecutionScope::new();
+        let dir = serve(simple::simple(), &scope);
+
+        let (node, server_end) = create_proxy::<mio::NodeMarker>().unwrap();
+        let flags = READ_WRITE | mio::OpenFlags::CREATE | mio::OpenFlags::DESCRIBE;
+        dir.open(flags, mio::ModeType::empty(), "file", server_end).unwrap();
+        let event = executor.run_singlethreaded(node.take_event_stream().next());
+        match event {
+            Some(Ok(mio::NodeEvent::OnOpen { s, .. })) => assert_eq!(Status::from_raw(s), Status::NOT_SUPPORTED),
+            event => panic!("expected OnOpen, got {event:?}"),
+        }
+        scope.shutdown();
+    }
+
+    #[test]
+    fn rename_rejects_foreign_tokens() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let scope = ExecutionScope::new();
+        let root = simple::simple();
+        root.add_entry("file", crate::file::read_write(b"")).unwrap();
+        let dir = serve(root.clone(), &scope);
+
+        let (foreign, _peer) = fx::Channel::create();
+        let result = executor
+            .run_singlethreaded(dir.rename("file", foreign.into(), "moved"))
+            .unwrap();
+        assert_eq!(result, Err(Status::NOT_FOUND.into_raw()));
+        assert!(root.get_entry("file").is_ok());
+        scope.shutdown();
+    }
+}
@@ -7,7 +7,7 @@
 
 use crate::{directory::entry::DirectoryEntry, path::Path};
 
-use {fiber_status::Status, midl_meshx_io as mio, std::sync::Arc};
+use {fiber_status::Status, std::sync::Arc};
 
 /// Defines the type of the new entry to be created via the [`EntryConstructor::create_entry()`]
 /// call.
@@ -0,0 +1,27 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! This is an implementation of a mutable "simple" pseudo directories.  Use [`simple()`] to
+//! construct actual instances.  See [`Simple`] for details.
+
+use crate::directory::{mutable::connection, simple};
+
+use {midl_meshx_io as mio, std::sync::Arc};
+
+pub type Connection = connection::MutableConnection;
+pub type Simple = simple::Simple<Connection>;
+
+/// Creates a mutable empty "simple" directory.  In addition to the server adding or removing
+/// entries via the [`crate::directory::helper::DirectlyMutable`] methods, clients with the
+/// `RIGHT_WRITABLE` right may unlink, rename and link its entries.  Clients can only create new
+/// entries if the execution scope serving the directory has an
+/// [`crate::directory::mutable::entry_constructor::EntryConstructor`].
+pub fn simple() -> Arc<Simple> {
+    Simple::new(mio::INO_UNKNOWN)
+}
+
+pub fn simple_with_inode(inode: u64) -> Arc<Simple> {
+    Simple::new(inode)
+}
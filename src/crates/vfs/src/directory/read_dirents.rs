@@ -0,0 +1,78 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Sink used by the connections to serve `meshx.io/Directory.ReadDirents` requests.
+
+use crate::directory::{
+    common::encode_dirent,
+    dirents_sink::{self, AppendResult},
+    entry::EntryInfo,
+};
+
+use {fiber_status::Status, std::any::Any};
+
+/// An instance of this type represents a sink that may still accept additional entries.  Depending
+/// on the entry size it may turn itself into a [`Done`] value, indicating that the internal buffer
+/// is full.
+pub struct Sink {
+    buf: Vec<u8>,
+    max_bytes: u64,
+    state: SinkState,
+}
+
+/// An instance of this type is a `read_dirents` result sink that is full and may not consume any
+/// more values.
+pub struct Done {
+    pub buf: Vec<u8>,
+    pub status: Status,
+}
+
+#[derive(PartialEq, Eq)]
+enum SinkState {
+    NotCalled,
+    DidNotFit,
+    FitOne,
+}
+
+impl Sink {
+    /// Constructs a new sync that will have the specified number of bytes of storage.
+    pub(super) fn new(max_bytes: u64) -> Box<Sink> {
+        Box::new(Sink {
+            buf: vec![],
+            max_bytes,
+            state: SinkState::NotCalled,
+        })
+    }
+}
+
+impl dirents_sink::Sink for Sink {
+    fn append(mut self: Box<Self>, entry: &EntryInfo, name: &str) -> AppendResult {
+        if !encode_dirent(&mut self.buf, self.max_bytes, entry, name) {
+            if self.state == SinkState::NotCalled {
+                self.state = SinkState::DidNotFit;
+            }
+            return AppendResult::Sealed(self.seal());
+        }
+        self.state = SinkState::FitOne;
+        AppendResult::Ok(self)
+    }
+
+    fn seal(self: Box<Self>) -> Box<dyn dirents_sink::Sealed> {
+        Box::new(Done {
+            buf: self.buf,
+            status: match self.state {
+                SinkState::NotCalled | SinkState::FitOne => Status::OK,
+                // Not even a single entry fits into the buffer the client provided.
+                SinkState::DidNotFit => Status::BUFFER_TOO_SMALL,
+            },
+        })
+    }
+}
+
+impl dirents_sink::Sealed for Done {
+    fn open(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
@@ -7,15 +7,38 @@
 //! to construct actual instances.  See [`Simple`] for details.
 
 use std::{
+    any::Any,
     collections::BTreeMap,
     marker::PhantomData,
+    ops::Bound,
     sync::{Arc, Mutex},
 };
 
-use crate::name::Name;
+use crate::{
+    common::{rights_to_posix_mode_bits, send_on_open_with_error},
+    directory::{
+        connection::DerivedConnection,
+        dirents_sink::{self, AppendResult},
+        entry::{DirectoryEntry, EntryInfo},
+        entry_container::{Directory, MutableDirectory},
+        helper::{AlreadyExists, DirectlyMutable, NotDirectory},
+        mutable::{connection::MutableConnection, entry_constructor::NewEntryType},
+        watchers::Watchers,
+    },
+    execution_scope::ExecutionScope,
+    name::Name,
+    node::Node,
+    path::Path,
+    traversal_position::TraversalPosition,
+};
+
+use {
+    async_trait::async_trait, fiber_rust as fx, fiber_status::Status, midl::endpoints::ServerEnd,
+    midl_meshx_io as mio,
+};
 
-use super::{entry::DirectoryEntry, connection::DerivedConnection};
- 
+/// Called with the name of an entry that a client looked up but that does not exist.
+pub type NotFoundHandler = Box<dyn FnMut(&str) + Send + Sync + 'static>;
 
 /// An implementation of a "simple" pseudo directory.  This directory holds a set of entries,
 /// allowing the server to add or remove entries via the
@@ -32,13 +55,13 @@ pub struct Simple<Connection> {
 
     _connection: PhantomData<Connection>,
 
-    not_found_handler: Mutex<Option<Box<dyn FnMut(&str) + Send + Sync + 'static>>>,
+    not_found_handler: Mutex<Option<NotFoundHandler>>,
 }
 
 struct Inner {
     entries: BTreeMap<Name, Arc<dyn DirectoryEntry>>,
 
-    // TODO: watchers: Watchers,
+    watchers: Watchers,
 }
 
 impl<Connection> Simple<Connection>
@@ -47,10 +70,294 @@ where
 {
     pub(super) fn new(inode: u64) -> Arc<Self> {
         Arc::new(Simple {
-            inner: Mutex::new(Inner { entries: BTreeMap::new() }),
+            inner: Mutex::new(Inner {
+                entries: BTreeMap::new(),
+                watchers: Watchers::new(),
+            }),
             _connection: PhantomData,
             inode,
             not_found_handler: Mutex::new(None),
         })
     }
-}
\ No newline at end of file
+
+    /// Returns the entry identified by `name`, or inserts the entry produced by `f` if there is
+    /// none.
+    pub fn get_or_insert<T: DirectoryEntry>(&self, name: Name, f: impl FnOnce() -> Arc<T>) -> Arc<dyn DirectoryEntry> {
+        let mut this = self.inner.lock().unwrap();
+        if let Some(entry) = this.entries.get(&name) {
+            return entry.clone();
+        }
+        let entry = f() as Arc<dyn DirectoryEntry>;
+        this.watchers.send_event(mio::WatchEvent::Added, &[&*name]);
+        this.entries.insert(name, entry.clone());
+        entry
+    }
+
+    /// Returns the entry identified by `name`.
+    pub fn get_entry(&self, name: &str) -> Result<Arc<dyn DirectoryEntry>, Status> {
+        crate::name::validate_name(name)?;
+        let this = self.inner.lock().unwrap();
+        this.entries.get(name).cloned().ok_or(Status::NOT_FOUND)
+    }
+
+    /// Creates a new entry named `name`, using the entry constructor of the connection, and adds it
+    /// to this directory.  `path` is the part of the path being opened that is inside the new
+    /// entry.
+    fn create_entry(
+        self: Arc<Self>,
+        scope: ExecutionScope,
+        flags: mio::OpenFlags,
+        name: String,
+        path: &Path,
+    ) -> Result<Arc<dyn DirectoryEntry>, Status> {
+        let entry_type = if flags.intersects(mio::OpenFlags::DIRECTORY) {
+            NewEntryType::Directory
+        } else {
+            NewEntryType::File
+        };
+        let name = Name::try_from(name)?;
+        let entry = Connection::create_entry(scope, self.clone(), entry_type, &name, path)?;
+        // Another connection may have created an entry with the same name in the meantime.
+        self.add_entry_impl(name, entry.clone(), false)?;
+        Ok(entry)
+    }
+
+    /// The provided function will be called whenever this VFS receives an open request for a path
+    /// that is not present in the VFS. The function is invoked with the full path of the missing
+    /// entry, relative to this directory. Typically this function is used for logging.
+    pub fn set_not_found_handler(self: Arc<Self>, handler: NotFoundHandler) {
+        let mut this = self.not_found_handler.lock().unwrap();
+        this.replace(handler);
+    }
+}
+
+impl<Connection> DirectoryEntry for Simple<Connection>
+where
+    Connection: DerivedConnection + 'static,
+{
+    fn open(
+        self: Arc<Self>,
+        scope: ExecutionScope,
+        flags: mio::OpenFlags,
+        mut path: Path,
+        server_end: ServerEnd<mio::NodeMarker>,
+    ) {
+        let requested = path.as_ref().to_string();
+        let name = match path.next() {
+            Some(name) => name.to_string(),
+            None => {
+                Connection::create_connection(scope, Connection::from_simple(self), flags, server_end);
+                return;
+            }
+        };
+
+        // Do not hold the lock while opening the entry, as it may be this directory again.
+        let entry = self.inner.lock().unwrap().entries.get(name.as_str()).cloned();
+        let describe = flags.intersects(mio::OpenFlags::DESCRIBE);
+        match entry {
+            Some(_) if path.is_empty() && flags.intersects(mio::OpenFlags::CREATE_IF_ABSENT) => {
+                send_on_open_with_error(describe, server_end, Status::ALREADY_EXISTS);
+            }
+            Some(entry) => entry.open(scope, flags, path, server_end),
+            None if flags.intersects(mio::OpenFlags::CREATE) => {
+                match self.clone().create_entry(scope.clone(), flags, name, &path) {
+                    Ok(entry) => entry.open(scope, flags, path, server_end),
+                    Err(status) => send_on_open_with_error(describe, server_end, status),
+                }
+            }
+            None => {
+                if let Some(handler) = self.not_found_handler.lock().unwrap().as_mut() {
+                    handler(&requested);
+                }
+                send_on_open_with_error(describe, server_end, Status::NOT_FOUND);
+            }
+        }
+    }
+
+    fn entry_info(&self) -> EntryInfo {
+        EntryInfo::new(self.inode, mio::DirentType::Directory)
+    }
+}
+
+#[async_trait]
+impl<Connection> Node for Simple<Connection>
+where
+    Connection: DerivedConnection + 'static,
+{
+    async fn get_attrs(&self) -> Result<mio::NodeAttributes, Status> {
+        Ok(mio::NodeAttributes {
+            mode: mio::MODE_TYPE_DIRECTORY | rights_to_posix_mode_bits(true, true, false),
+            id: self.inode,
+            content_size: 0,
+            storage_size: 0,
+            link_count: 1,
+            creation_time: 0,
+            modification_time: 0,
+        })
+    }
+}
+
+#[async_trait]
+impl<Connection> Directory for Simple<Connection>
+where
+    Connection: DerivedConnection + 'static,
+{
+    async fn read_dirents<'a>(
+        &'a self,
+        pos: &'a TraversalPosition,
+        sink: Box<dyn dirents_sink::Sink>,
+    ) -> Result<(TraversalPosition, Box<dyn dirents_sink::Sealed>), Status> {
+        let this = self.inner.lock().unwrap();
+
+        let (mut sink, start) = match pos {
+            TraversalPosition::Start => match sink.append(&EntryInfo::new(self.inode, mio::DirentType::Directory), ".")
+            {
+                AppendResult::Ok(sink) => (sink, Bound::Unbounded),
+                AppendResult::Sealed(sealed) => return Ok((TraversalPosition::Start, sealed)),
+            },
+            // `Name` holds the first entry that did not fit in the previous call.
+            TraversalPosition::Name(name) => (sink, Bound::Included(name.as_str())),
+            TraversalPosition::Index(_) => unreachable!("Simple directories only use Name positions"),
+            TraversalPosition::End => return Ok((TraversalPosition::End, sink.seal())),
+        };
+
+        for (name, entry) in this.entries.range::<str, _>((start, Bound::Unbounded)) {
+            match sink.append(&entry.entry_info(), name) {
+                AppendResult::Ok(new_sink) => sink = new_sink,
+                AppendResult::Sealed(sealed) => {
+                    return Ok((TraversalPosition::Name(name.to_string()), sealed));
+                }
+            }
+        }
+
+        Ok((TraversalPosition::End, sink.seal()))
+    }
+
+    fn register_watcher(
+        self: Arc<Self>,
+        _scope: ExecutionScope,
+        mask: mio::WatchMask,
+        watcher: fx::Channel,
+    ) -> Result<(), Status> {
+        let mut this = self.inner.lock().unwrap();
+        let Inner { entries, watchers } = &mut *this;
+        watchers.add(mask, watcher, std::iter::once(".").chain(entries.keys().map(|name| &**name)));
+        Ok(())
+    }
+}
+
+impl<Connection> DirectlyMutable for Simple<Connection>
+where
+    Connection: DerivedConnection + 'static,
+{
+    fn add_entry_impl(&self, name: Name, entry: Arc<dyn DirectoryEntry>, overwrite: bool) -> Result<(), AlreadyExists> {
+        let mut this = self.inner.lock().unwrap();
+        if !overwrite && this.entries.contains_key(&name) {
+            return Err(AlreadyExists);
+        }
+        this.watchers.send_event(mio::WatchEvent::Added, &[&*name]);
+        this.entries.insert(name, entry);
+        Ok(())
+    }
+
+    fn remove_entry_impl(
+        &self,
+        name: Name,
+        must_be_directory: bool,
+    ) -> Result<Option<Arc<dyn DirectoryEntry>>, NotDirectory> {
+        let mut this = self.inner.lock().unwrap();
+        match this.entries.get(&name) {
+            Some(entry) if must_be_directory && entry.entry_info().type_() != mio::DirentType::Directory => {
+                Err(NotDirectory)
+            }
+            Some(_) => {
+                this.watchers.send_event(mio::WatchEvent::Removed, &[&*name]);
+                Ok(this.entries.remove(&name))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl Simple<MutableConnection> {
+    fn is_empty(&self) -> bool {
+        self.inner.lock().unwrap().entries.is_empty()
+    }
+
+    /// Checks that `entry` can be replaced with `replacement`: both need to be of the same kind,
+    /// and a directory can only be replaced if it is empty.
+    fn check_can_replace(entry: &Arc<dyn DirectoryEntry>, replacement: &Arc<dyn DirectoryEntry>) -> Result<(), Status> {
+        let is_dir = |entry: &Arc<dyn DirectoryEntry>| entry.entry_info().type_() == mio::DirentType::Directory;
+        match (is_dir(entry), is_dir(replacement)) {
+            (true, true) => Self::check_is_empty(entry),
+            (true, false) => Err(Status::NOT_FILE),
+            (false, true) => Err(Status::NOT_DIR),
+            (false, false) => Ok(()),
+        }
+    }
+
+    /// Checks that `entry` is not a non-empty directory.  Directories of other types are not
+    /// checked.
+    fn check_is_empty(entry: &Arc<dyn DirectoryEntry>) -> Result<(), Status> {
+        match entry.clone().into_any().downcast::<Self>() {
+            Ok(dir) if !dir.is_empty() => Err(Status::NOT_EMPTY),
+            _ => Ok(()),
+        }
+    }
+
+    fn downcast(dir: Arc<dyn Any + Send + Sync>) -> Result<Arc<Self>, Status> {
+        dir.downcast::<Self>().map_err(|_| Status::NOT_SUPPORTED)
+    }
+}
+
+#[async_trait]
+impl MutableDirectory for Simple<MutableConnection> {
+    async fn link(
+        self: Arc<Self>,
+        name: Name,
+        source_dir: Arc<dyn Any + Send + Sync>,
+        source_name: &str,
+    ) -> Result<(), Status> {
+        let source_dir = Self::downcast(source_dir)?;
+        let entry = source_dir.get_entry(source_name)?;
+        // Hard links to directories would allow cycles in the tree.
+        if entry.entry_info().type_() == mio::DirentType::Directory {
+            return Err(Status::NOT_FILE);
+        }
+        self.add_entry_impl(name, entry, false)?;
+        Ok(())
+    }
+
+    async fn unlink(self: Arc<Self>, name: &str, must_be_directory: bool) -> Result<(), Status> {
+        let entry = self.get_entry(name)?;
+        Self::check_is_empty(&entry)?;
+        match self.remove_entry_impl(Name::from(name)?, must_be_directory)? {
+            Some(_) => Ok(()),
+            None => Err(Status::NOT_FOUND),
+        }
+    }
+
+    async fn sync(&self) -> Result<(), Status> {
+        // Everything is kept in memory.
+        Ok(())
+    }
+
+    async fn rename(
+        self: Arc<Self>,
+        src_dir: Arc<dyn Any + Send + Sync>,
+        src_name: Name,
+        dst_name: Name,
+    ) -> Result<(), Status> {
+        let src_dir = Self::downcast(src_dir)?;
+        let entry = src_dir.get_entry(&src_name)?;
+        if Arc::ptr_eq(&src_dir, &self) && src_name == dst_name {
+            return Ok(());
+        }
+        if let Ok(existing) = self.get_entry(&dst_name) {
+            Self::check_can_replace(&existing, &entry)?;
+        }
+        let entry = src_dir.remove_entry_impl(src_name, false)?.ok_or(Status::NOT_FOUND)?;
+        self.add_entry_impl(dst_name, entry, true)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Watchers handle a list of watcher connections attached to a directory.  Watchers as described
+//! in meshx.io, receive a message every time an entry is added to or removed from the directory
+//! they watch.
+
+use {fiber_rust as fx, midl_meshx_io as mio, std::iter};
+
+/// Maximum size of a single message sent to a watcher.  Events that name more entries than fit
+/// into a single message are split across several messages.
+const MAX_MESSAGE_SIZE: usize = mio::MAX_BUF as usize;
+
+/// A single watcher connection, along with the events it is interested in.
+struct Watcher {
+    mask: mio::WatchMask,
+    channel: fx::Channel,
+}
+
+impl Watcher {
+    /// Sends `event` for each of the `names`.  Returns `false` if the watcher channel is no longer
+    /// usable and the watcher should be dropped.
+    fn send<'a>(&self, event: mio::WatchEvent, names: impl Iterator<Item = &'a str>) -> bool {
+        let mask = match event {
+            mio::WatchEvent::Added => mio::WatchMask::ADDED,
+            mio::WatchEvent::Removed => mio::WatchMask::REMOVED,
+            mio::WatchEvent::Existing => mio::WatchMask::EXISTING,
+            mio::WatchEvent::Idle => mio::WatchMask::IDLE,
+            _ => return true,
+        };
+        if !self.mask.intersects(mask) {
+            return true;
+        }
+
+        for message in encode_messages(event, names) {
+            // Either the watcher went away, or it stopped reading its messages.
+            if self.channel.write(&message, &mut []).is_err() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Encodes an event for each of `names` into as few messages as possible.  Each event is encoded
+/// as the event type byte, the length of the name, and then the name itself.
+fn encode_messages<'a>(event: mio::WatchEvent, names: impl Iterator<Item = &'a str>) -> Vec<Vec<u8>> {
+    let mut messages = vec![];
+    let mut message = Vec::with_capacity(MAX_MESSAGE_SIZE);
+    for name in names {
+        let name = name.as_bytes();
+        debug_assert!(name.len() <= mio::MAX_FILENAME as usize);
+        if message.len() + 2 + name.len() > MAX_MESSAGE_SIZE {
+            messages.push(std::mem::replace(&mut message, Vec::with_capacity(MAX_MESSAGE_SIZE)));
+        }
+        message.push(event.into_primitive());
+        message.push(name.len() as u8);
+        message.extend_from_slice(name);
+    }
+    if !message.is_empty() {
+        messages.push(message);
+    }
+    messages
+}
+
+/// Holds the watchers of a single directory.  Directories call [`Watchers::send_event`] every time
+/// their content changes.
+#[derive(Default)]
+pub struct Watchers {
+    watchers: Vec<Watcher>,
+}
+
+impl Watchers {
+    /// Constructs a new empty watcher collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a new watcher.  The watcher is sent an `EXISTING` event for each of `existing`, if it
+    /// asked for them, followed by an `IDLE` event.
+    pub fn add<'a>(&mut self, mask: mio::WatchMask, channel: fx::Channel, existing: impl Iterator<Item = &'a str>) {
+        let watcher = Watcher { mask, channel };
+        if watcher.send(mio::WatchEvent::Existing, existing) && watcher.send(mio::WatchEvent::Idle, iter::once("")) {
+            self.watchers.push(watcher);
+        }
+    }
+
+    /// Sends `event` for each of the `names` to all the watchers interested in it.  Watchers whose
+    /// channels are closed are removed.
+    pub fn send_event(&mut self, event: mio::WatchEvent, names: &[&str]) {
+        self.watchers
+            .retain(|watcher| watcher.send(event, names.iter().copied()));
+    }
+
+    /// Returns `true` if there are no watchers.
+    pub fn is_empty(&self) -> bool {
+        self.watchers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_are_split_at_max_size() {
+        let name = "a".repeat(200);
+        let names = vec![name.as_str(); 100];
+        let messages = encode_messages(mio::WatchEvent::Added, names.into_iter());
+        assert!(messages.len() > 1);
+        assert!(messages.iter().all(|message| message.len() <= MAX_MESSAGE_SIZE));
+        assert_eq!(messages.iter().map(Vec::len).sum::<usize>(), 100 * 202);
+    }
+
+    #[test]
+    fn event_encoding() {
+        let messages = encode_messages(mio::WatchEvent::Removed, ["ab", "c"].into_iter());
+        let removed = mio::WatchEvent::Removed.into_primitive();
+        assert_eq!(messages, vec![vec![removed, 2, b'a', b'b', removed, 1, b'c']]);
+    }
+}
@@ -11,37 +11,313 @@
 //!
 //! This way, it is possible to control the lifetime of a group of connections.  All connections
 //! and their tasks can be shutdown by calling `shutdown` method on the scope that is hosting them.
-//! Scope will also shutdown all the tasks when it goes out of scope.
 //!
-//! Implementation wise, execution scope is just a proxy, that forwards all the tasks to an actual
-//! executor, provided as an instance of a [`futures::task::Spawn`] trait.
+//! Implementation wise, execution scope is just a proxy, that forwards all the tasks to the
+//! `meshx_async` executor of the current thread, and keeps the [`meshx_async::Task`] handles of
+//! the tasks it started.  Dropping a handle cancels the task.
+//!
+//! The scope also holds the state that is shared by all the connections of a file system: the
+//! [`EntryConstructor`] mutable directories use to create new entries, and the [`TokenRegistry`]
+//! used to identify directories in `Rename` and `Link` requests.
 
-use crate::{directory::mutable::entry_constructor::EntryConstructor};
+use crate::{directory::mutable::entry_constructor::EntryConstructor, token_registry::TokenRegistry};
 
 use {
     futures::{
-        channel::oneshot,
-        task::{self, Context, Poll, Waker},
+        task::{Context, Poll, Waker},
         Future,
     },
-     
     std::{
-        ops::Drop,
+        collections::HashMap,
         pin::Pin,
         sync::{Arc, Mutex},
     },
 };
 
-pub type SpawnError = task::SpawnError;
-
 /// An execution scope that is hosting tasks for a group of connections.  See the module level
 /// documentation for details.
 ///
-/// Actual execution will be delegated to an "upstream" executor - something that implements
-/// [`futures::task::Spawn`].  In a sense, this is somewhat of an analog of a multithreaded capable
-/// [`futures::stream::FuturesUnordered`], but this some additional functionality specific to the
-/// vfs library.
+/// Clones of an `ExecutionScope` refer to the same scope: shutting down any of them shuts down
+/// the tasks started through all of them.
 ///
-/// Use [`ExecutionScope::new()`] or [`ExecutionScope::build()`] to construct new
-/// `ExecutionScope`es.
-pub struct ExecutionScope {}
+/// Use [`ExecutionScope::new()`] to construct new `ExecutionScope`es, or
+/// [`ExecutionScope::builder()`] if the scope needs an [`EntryConstructor`].
+#[derive(Clone, Default)]
+pub struct ExecutionScope {
+    executor: Arc<Executor>,
+}
+
+#[derive(Default)]
+struct Executor {
+    inner: Mutex<Inner>,
+
+    token_registry: TokenRegistry,
+
+    entry_constructor: Option<Arc<dyn EntryConstructor + Send + Sync>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// Tasks running in this scope, keyed by an id unique within the scope, so that a task can
+    /// remove itself once it completes.
+    tasks: HashMap<u64, meshx_async::Task<()>>,
+
+    /// The id the next spawned task will use.
+    next_task_id: u64,
+
+    /// Number of live [`ActiveGuard`]s.  Shutdown cancels the tasks only once this drops to zero,
+    /// so that requests that are being processed are not interrupted half way.
+    active_count: u64,
+
+    /// Set once `shutdown` is called.  No new tasks are started after this point.
+    shutdown: bool,
+
+    /// Futures returned by `wait`, waiting for all the tasks to complete.
+    waiters: Vec<Waker>,
+}
+
+impl Inner {
+    /// Removes all the tasks, if the scope is shutting down and no requests are being processed.
+    /// The tasks are returned, rather than dropped, so that they can be cancelled without holding
+    /// the lock.
+    fn take_cancelled_tasks(&mut self) -> HashMap<u64, meshx_async::Task<()>> {
+        if self.shutdown && self.active_count == 0 {
+            std::mem::take(&mut self.tasks)
+        } else {
+            HashMap::new()
+        }
+    }
+
+    fn take_waiters_if_idle(&mut self) -> Vec<Waker> {
+        if self.tasks.is_empty() {
+            std::mem::take(&mut self.waiters)
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl ExecutionScope {
+    /// Constructs a new execution scope.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a builder for an execution scope with a non-default configuration.
+    pub fn builder() -> ExecutionScopeBuilder {
+        ExecutionScopeBuilder::default()
+    }
+
+    /// Returns the registry that hands out the tokens for directories served in this scope.
+    pub fn token_registry(&self) -> &TokenRegistry {
+        &self.executor.token_registry
+    }
+
+    /// Returns the constructor mutable directories served in this scope use to create new
+    /// entries, if one was set.
+    pub fn entry_constructor(&self) -> Option<Arc<dyn EntryConstructor + Send + Sync>> {
+        self.executor.entry_constructor.clone()
+    }
+
+    /// Spawns a task that serves a connection, or does work on behalf of one, on the executor of
+    /// the current thread.  The task is cancelled when the scope is shut down.
+    ///
+    /// Tasks spawned after the scope was shut down are dropped without being run.
+    pub fn spawn(&self, task: impl Future<Output = ()> + Send + 'static) {
+        let mut inner = self.executor.inner.lock().unwrap();
+        if inner.shutdown {
+            return;
+        }
+
+        let id = inner.next_task_id;
+        inner.next_task_id += 1;
+
+        let executor = self.executor.clone();
+        let task = meshx_async::Task::spawn(async move {
+            task.await;
+            executor.task_did_finish(id);
+        });
+        inner.tasks.insert(id, task);
+    }
+
+    /// Initiates a graceful shutdown of all the tasks in this scope.  Requests that are currently
+    /// being processed, as indicated by live [`ActiveGuard`]s, are allowed to complete, after which
+    /// all the tasks are cancelled.  Use [`ExecutionScope::wait()`] to wait for that to happen.
+    pub fn shutdown(&self) {
+        let (tasks, waiters) = {
+            let mut inner = self.executor.inner.lock().unwrap();
+            inner.shutdown = true;
+            let tasks = inner.take_cancelled_tasks();
+            (tasks, inner.take_waiters_if_idle())
+        };
+        drop(tasks);
+        waiters.into_iter().for_each(Waker::wake);
+    }
+
+    /// Returns `true` if `shutdown` was called on this scope, or any of its clones.
+    pub fn is_shutdown(&self) -> bool {
+        self.executor.inner.lock().unwrap().shutdown
+    }
+
+    /// Returns a future that completes once there are no tasks running in this scope.
+    pub fn wait(&self) -> impl Future<Output = ()> + Send + 'static {
+        Wait {
+            executor: self.executor.clone(),
+        }
+    }
+
+    /// Returns an [`ActiveGuard`] that prevents the scope from cancelling its tasks while it is
+    /// alive, or `None` if the scope is shutting down.  Connections hold a guard while they
+    /// process a request, so that shutdown does not interrupt a request half way.
+    pub fn try_active_guard(&self) -> Option<ActiveGuard> {
+        let mut inner = self.executor.inner.lock().unwrap();
+        if inner.shutdown {
+            return None;
+        }
+        inner.active_count += 1;
+        Some(ActiveGuard {
+            executor: self.executor.clone(),
+        })
+    }
+}
+
+/// Builder for an [`ExecutionScope`].  See [`ExecutionScope::builder()`].
+#[derive(Default)]
+pub struct ExecutionScopeBuilder {
+    entry_constructor: Option<Arc<dyn EntryConstructor + Send + Sync>>,
+}
+
+impl ExecutionScopeBuilder {
+    /// Sets the constructor used by mutable directories to create new entries.  Without one,
+    /// requests to create entries fail with `NOT_SUPPORTED`.
+    pub fn entry_constructor(mut self, constructor: Arc<dyn EntryConstructor + Send + Sync>) -> Self {
+        self.entry_constructor = Some(constructor);
+        self
+    }
+
+    /// Constructs the execution scope.
+    pub fn build(self) -> ExecutionScope {
+        let Self { entry_constructor } = self;
+        ExecutionScope {
+            executor: Arc::new(Executor {
+                entry_constructor,
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+impl Executor {
+    fn task_did_finish(&self, id: u64) {
+        let (task, waiters) = {
+            let mut inner = self.inner.lock().unwrap();
+            let task = inner.tasks.remove(&id);
+            (task, inner.take_waiters_if_idle())
+        };
+        // The task is the one that is calling us, and it has already completed its work.
+        if let Some(task) = task {
+            task.detach();
+        }
+        waiters.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// Prevents the [`ExecutionScope`] it was obtained from from cancelling its tasks.  See
+/// [`ExecutionScope::try_active_guard()`].
+pub struct ActiveGuard {
+    executor: Arc<Executor>,
+}
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        let (tasks, waiters) = {
+            let mut inner = self.executor.inner.lock().unwrap();
+            inner.active_count -= 1;
+            let tasks = inner.take_cancelled_tasks();
+            (tasks, inner.take_waiters_if_idle())
+        };
+        drop(tasks);
+        waiters.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// Future returned by [`ExecutionScope::wait()`].
+struct Wait {
+    executor: Arc<Executor>,
+}
+
+impl Future for Wait {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut inner = self.executor.inner.lock().unwrap();
+        if inner.tasks.is_empty() {
+            Poll::Ready(())
+        } else {
+            inner.waiters.push(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use {
+        futures::{channel::oneshot, future::pending},
+        meshx_async::TestExecutor,
+        std::sync::atomic::{AtomicBool, Ordering},
+    };
+
+    #[test]
+    fn wait_completes_once_tasks_finish() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let scope = ExecutionScope::new();
+        let (sender, receiver) = oneshot::channel();
+        scope.spawn(async move {
+            receiver.await.unwrap();
+        });
+
+        let mut wait = scope.wait();
+        assert!(executor.run_until_stalled(&mut wait).is_pending());
+        sender.send(()).unwrap();
+        assert!(executor.run_until_stalled(&mut wait).is_ready());
+    }
+
+    #[test]
+    fn shutdown_cancels_tasks() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let scope = ExecutionScope::new();
+        scope.spawn(pending());
+        scope.shutdown();
+        assert!(scope.is_shutdown());
+        assert!(executor.run_until_stalled(&mut scope.wait()).is_ready());
+
+        let ran = Arc::new(AtomicBool::new(false));
+        scope.spawn({
+            let ran = ran.clone();
+            async move { ran.store(true, Ordering::SeqCst) }
+        });
+        assert!(executor.run_until_stalled(&mut pending::<()>()).is_pending());
+        assert!(!ran.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn active_guard_defers_shutdown() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let scope = ExecutionScope::new();
+        scope.spawn(pending());
+        let guard = scope.try_active_guard().unwrap();
+        scope.shutdown();
+        assert!(scope.try_active_guard().is_none());
+
+        let mut wait = scope.wait();
+        assert!(executor.run_until_stalled(&mut wait).is_pending());
+        drop(guard);
+        assert!(executor.run_until_stalled(&mut wait).is_ready());
+    }
+}
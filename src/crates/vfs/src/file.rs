@@ -0,0 +1,120 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Module holding different kinds of files and their building blocks.
+
+use crate::node::Node;
+
+use {async_trait::async_trait, fiber_rust as fx, fiber_status::Status, midl_meshx_io as mio};
+
+pub mod common;
+pub mod connection;
+
+pub mod lazy;
+pub mod simple;
+pub mod vmo;
+
+pub use lazy::{lazy, Lazy};
+pub use simple::{read_only, read_write, SimpleFile};
+pub use vmo::VmoFile;
+
+/// Options of a file connection, as derived from the `OpenFlags` it was opened with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FileOptions {
+    /// The `OpenFlags.RIGHT_*` flags of the connection.
+    pub rights: mio::OpenFlags,
+    /// Whether writes go to the end of the file, regardless of the seek position.
+    pub is_append: bool,
+}
+
+impl FileOptions {
+    /// Converts to the `OpenFlags` reported by `GetFlags`.
+    pub fn to_io1(&self) -> mio::OpenFlags {
+        let mut flags = self.rights;
+        if self.is_append {
+            flags |= mio::OpenFlags::APPEND;
+        }
+        flags
+    }
+}
+
+/// When a sync is requested.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncMode {
+    /// The client asked for a sync.
+    Normal,
+    /// The last connection to the file is about to be closed.
+    PreClose,
+}
+
+/// Trait used for all files.
+#[async_trait]
+pub trait File: Node {
+    /// Capabilities:
+    fn readable(&self) -> bool {
+        true
+    }
+    fn writable(&self) -> bool {
+        false
+    }
+    fn executable(&self) -> bool {
+        false
+    }
+
+    /// Called when the file is going to be accessed, typically by a new connection.
+    /// Flags is the same as the flags passed to `meshx.io.Node/Open`.
+    /// The following flags are handled by the connection and do not need to be handled inside
+    /// open():
+    /// * OpenFlags.TRUNCATE - A call to truncate() will be made immediately after open().
+    /// * OpenFlags.DESCRIBE - The OnOpen event is sent before any other requests are received from
+    /// the file's client.
+    async fn open_file(&self, options: &FileOptions) -> Result<(), Status>;
+
+    /// Truncate the file to `length`.
+    /// If there are pending attributes to update (see `update_attributes`), they should also be
+    /// flushed at this time. Otherwise, no attributes should be updated, other than size as
+    /// needed.
+    async fn truncate(&self, length: u64) -> Result<(), Status>;
+
+    /// Get a VMO representing this file.
+    /// If not supported by the underlying filesystem, should return Err(NOT_SUPPORTED).
+    async fn get_backing_memory(&self, flags: mio::VmoFlags) -> Result<fx::Vmo, Status>;
+
+    /// Get the size of this file.
+    /// This is used to calculate seek offset relative to the end.
+    async fn get_size(&self) -> Result<u64, Status>;
+
+    /// Sync this file's contents to the storage medium (probably disk).
+    /// This does not necessarily guarantee that the file will be completely written to disk once
+    /// the call returns. It merely guarantees that any changes to the file have been propagated
+    /// to the next layer in the storage stack.
+    async fn sync(&self, mode: SyncMode) -> Result<(), Status>;
+}
+
+/// Trait used to read and write the contents of a file.  Connections use it for the `Read`,
+/// `ReadAt`, `Write` and `WriteAt` requests, and keep track of the seek position themselves.
+#[async_trait]
+pub trait FileIo: Send + Sync {
+    /// Read at most |buffer.len()| bytes starting at |offset| into |buffer|. The function may read
+    /// less than |count| bytes and still return success, in which case read_at returns the number
+    /// of bytes read into |buffer|.
+    async fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<u64, Status>;
+
+    /// Write |content| starting at |offset|, returning the number of bytes that were successfully
+    /// written.
+    ///
+    /// If there are pending attributes to update (see `update_attributes`), they should also be
+    /// flushed at this time.
+    async fn write_at(&self, offset: u64, content: &[u8]) -> Result<u64, Status>;
+
+    /// Appends |content| returning, if successful, the number of bytes written, and the file offset
+    /// after writing.  Implementations should make the writes atomic, so in the event that multiple
+    /// requests to append are in-flight, it should appear that the two writes are applied in
+    /// sequence.
+    ///
+    /// If there are pending attributes to update (see `update_attributes`), they should also be
+    /// flushed at this time.
+    async fn append(&self, content: &[u8]) -> Result<(u64, u64), Status>;
+}
@@ -0,0 +1,102 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Common utilities used by the file connections.
+
+use crate::{common::rights, file::FileOptions};
+
+use {fiber_status::Status, midl_meshx_io as mio};
+
+/// Validate that the requested flags for a new connection are valid.  It is a bit tricky as
+/// depending on the presence of the `OpenFlags::NODE_REFERENCE` flag we are effectively validating
+/// two different cases: with `OpenFlags::NODE_REFERENCE` the connection will be attached to the
+/// node itself, without it the connection is for the file content.
+///
+/// Changing this function can be dangerous!  Flags operations may have security implications.
+pub fn new_connection_validate_flags(
+    flags: mio::OpenFlags,
+    readable: bool,
+    writable: bool,
+    executable: bool,
+) -> Result<FileOptions, Status> {
+    if flags.intersects(mio::OpenFlags::DIRECTORY) {
+        return Err(Status::NOT_DIR);
+    }
+
+    let rights = rights(flags);
+    if rights.contains(mio::OpenFlags::RIGHT_READABLE) && !readable {
+        return Err(Status::ACCESS_DENIED);
+    }
+    if rights.contains(mio::OpenFlags::RIGHT_WRITABLE) && !writable {
+        return Err(Status::ACCESS_DENIED);
+    }
+    if rights.contains(mio::OpenFlags::RIGHT_EXECUTABLE) && !executable {
+        return Err(Status::ACCESS_DENIED);
+    }
+
+    // A TRUNCATE is a write, and requires the connection to be able to write.
+    if flags.intersects(mio::OpenFlags::TRUNCATE) && !rights.contains(mio::OpenFlags::RIGHT_WRITABLE) {
+        return Err(Status::INVALID_ARGS);
+    }
+
+    Ok(FileOptions {
+        rights,
+        is_append: flags.intersects(mio::OpenFlags::APPEND),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directory_flag_is_not_dir() {
+        assert_eq!(
+            new_connection_validate_flags(mio::OpenFlags::DIRECTORY, true, true, false),
+            Err(Status::NOT_DIR)
+        );
+    }
+
+    #[test]
+    fn rights_must_be_supported() {
+        assert_eq!(
+            new_connection_validate_flags(mio::OpenFlags::RIGHT_WRITABLE, true, false, false),
+            Err(Status::ACCESS_DENIED)
+        );
+        assert_eq!(
+            new_connection_validate_flags(mio::OpenFlags::RIGHT_EXECUTABLE, true, true, false),
+            Err(Status::ACCESS_DENIED)
+        );
+    }
+
+    #[test]
+    fn truncate_requires_write() {
+        assert_eq!(
+            new_connection_validate_flags(
+                mio::OpenFlags::RIGHT_READABLE | mio::OpenFlags::TRUNCATE,
+                true,
+                true,
+                false
+            ),
+            Err(Status::INVALID_ARGS)
+        );
+    }
+
+    #[test]
+    fn append_is_recorded() {
+        assert_eq!(
+            new_connection_validate_flags(
+                mio::OpenFlags::RIGHT_READABLE | mio::OpenFlags::RIGHT_WRITABLE | mio::OpenFlags::APPEND,
+                true,
+                true,
+                false
+            ),
+            Ok(FileOptions {
+                rights: mio::OpenFlags::RIGHT_READABLE | mio::OpenFlags::RIGHT_WRITABLE,
+                is_append: true,
+            })
+        );
+    }
+}
@@ -0,0 +1,413 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Connection to a file, serving the `meshx.io` File protocol on top of the [`File`] and
+//! [`FileIo`] traits.
+
+use crate::{
+    common::{inherit_rights_for_clone, rights_to_posix_mode_bits, send_on_open_with_error, vmo_flags_to_rights},
+    directory::connection::ConnectionState,
+    execution_scope::ExecutionScope,
+    file::{common::new_connection_validate_flags, File, FileIo, FileOptions, SyncMode},
+    node::OpenNode,
+    path::Path,
+};
+
+use {
+    anyhow::Error,
+    fiber_status::Status,
+    futures::TryStreamExt as _,
+    midl::endpoints::ServerEnd,
+    midl_meshx_io as mio,
+    std::{convert::TryInto as _, sync::Arc},
+    tracing::{span, Level},
+};
+
+/// Maximum number of bytes a single `Read`, `ReadAt`, `Write` or `WriteAt` may transfer.
+const MAX_TRANSFER_SIZE: u64 = mio::MAX_TRANSFER_SIZE;
+
+/// Attributes reported by `GetAttr` when the file fails to provide its own.
+const EMPTY_NODE_ATTRIBUTES: mio::NodeAttributes = mio::NodeAttributes {
+    mode: 0,
+    id: mio::INO_UNKNOWN,
+    content_size: 0,
+    storage_size: 0,
+    link_count: 0,
+    creation_time: 0,
+    modification_time: 0,
+};
+
+/// Represents a FIDL connection to a file.
+pub struct FileConnection<T: 'static + File + FileIo> {
+    /// Execution scope this connection and any async operations and connections it creates will
+    /// use.
+    scope: ExecutionScope,
+
+    /// File this connection is associated with.
+    file: OpenNode<T>,
+
+    /// Options of this connection, derived from the flags it was opened with.
+    options: FileOptions,
+
+    /// Seek position.  Next byte to be read or written within the buffer.  This might be beyond
+    /// the current size of the buffer, matching POSIX:
+    /// <http://pubs.opengroup.org/onlinepubs/9699919799/functions/lseek.html>
+    ///
+    /// It will cause the buffer to be extended with zeroes (if necessary) when write() is called.
+    seek: u64,
+}
+
+/// Creates a new connection to `file`, validating `flags` against the capabilities of the file.
+/// The connection is served by a task spawned in `scope`.  Errors are reported via `OnOpen` if
+/// `flags` contains `OpenFlags::DESCRIBE`.
+pub fn create_connection<T: 'static + File + FileIo>(
+    scope: ExecutionScope,
+    file: Arc<T>,
+    flags: mio::OpenFlags,
+    server_end: ServerEnd<mio::NodeMarker>,
+) {
+    let task_scope = scope.clone();
+    task_scope.spawn(FileConnection::create_connection_async(scope, file, flags, server_end));
+}
+
+impl<T: 'static + File + FileIo> FileConnection<T> {
+    async fn create_connection_async(
+        scope: ExecutionScope,
+        file: Arc<T>,
+        flags: mio::OpenFlags,
+        server_end: ServerEnd<mio::NodeMarker>,
+    ) {
+        let describe = flags.intersects(mio::OpenFlags::DESCRIBE);
+
+        let options = match new_connection_validate_flags(flags, file.readable(), file.writable(), file.executable()) {
+            Ok(options) => options,
+            Err(status) => {
+                send_on_open_with_error(describe, server_end, status);
+                return;
+            }
+        };
+
+        if let Err(status) = file.open_file(&options).await {
+            send_on_open_with_error(describe, server_end, status);
+            return;
+        }
+
+        // From here on the file must see a close() for every successful open_file(), which the
+        // `OpenNode` wrapper takes care of.
+        let file = OpenNode::new(file);
+
+        if flags.intersects(mio::OpenFlags::TRUNCATE) {
+            if let Err(status) = file.truncate(0).await {
+                send_on_open_with_error(describe, server_end, status);
+                return;
+            }
+        }
+
+        let (requests, control_handle) =
+            match ServerEnd::<mio::FileMarker>::new(server_end.into_channel()).into_stream_and_control_handle() {
+                Ok((requests, control_handle)) => (requests, control_handle),
+                Err(_) => {
+                    // As we report all errors on `server_end`, if we failed to send an error over
+                    // this connection, there is nowhere to send the error to.
+                    return;
+                }
+            };
+
+        if describe {
+            let info = mio::NodeInfoDeprecated::File(mio::FileObject {
+                event: None,
+                stream: None,
+            });
+            if control_handle.send_on_open(Status::OK.into_raw(), Some(info)).is_err() {
+                return;
+            }
+        }
+
+        let connection = FileConnection {
+            scope,
+            file,
+            options,
+            seek: 0,
+        };
+        connection.handle_requests(requests).await;
+    }
+
+    async fn handle_requests(mut self, mut requests: mio::FileRequestStream) {
+        while let Ok(Some(request)) = requests.try_next().await {
+            let Some(_guard) = self.scope.try_active_guard() else {
+                break;
+            };
+
+            if !matches!(self.handle_request(request).await, Ok(ConnectionState::Alive)) {
+                break;
+            }
+        }
+
+        // The last connection to the file is going away, give it a chance to flush.
+        let _ = self.file.sync(SyncMode::PreClose).await;
+    }
+
+    /// Handle a [`FileRequest`].  This function is responsible for handing all the file operations
+    /// that operate on the connection-specific buffer.
+    async fn handle_request(&mut self, req: mio::FileRequest) -> Result<ConnectionState, Error> {
+        match req {
+            mio::FileRequest::Clone {
+                flags,
+                object,
+                control_handle: _,
+            } => {
+                let span = span!(Level::TRACE, "File::Clone");
+                let _ = span.enter();
+                self.handle_clone(flags, object);
+            }
+            mio::FileRequest::Reopen {
+                rights_request: _,
+                object_request,
+                control_handle: _,
+            } => {
+                // Suppress any errors in the event a bad `object_request` channel was provided.
+                let _: Result<_, _> = object_request.close_with_epitaph(Status::NOT_SUPPORTED);
+            }
+            mio::FileRequest::Close { responder } => {
+                let span = span!(Level::TRACE, "File::Close");
+                let _ = span.enter();
+                let result = self.file.sync(SyncMode::PreClose).await.map_err(Status::into_raw);
+                responder.send(result)?;
+                return Ok(ConnectionState::Closed);
+            }
+            mio::FileRequest::GetConnectionInfo { responder } => {
+                responder.send(mio::ConnectionInfo::default())?;
+            }
+            mio::FileRequest::Describe { responder } => {
+                responder.send(mio::FileInfo {
+                    is_append: Some(self.options.is_append),
+                    ..Default::default()
+                })?;
+            }
+            mio::FileRequest::Query { responder } => {
+                responder.send(mio::FILE_PROTOCOL_NAME.as_bytes())?;
+            }
+            mio::FileRequest::QueryFilesystem { responder } => match self.file.query_filesystem() {
+                Err(status) => responder.send(status.into_raw(), None)?,
+                Ok(info) => responder.send(0, Some(&info))?,
+            },
+            mio::FileRequest::Sync { responder } => {
+                let span = span!(Level::TRACE, "File::Sync");
+                let _ = span.enter();
+                let result = self.file.sync(SyncMode::Normal).await.map_err(Status::into_raw);
+                responder.send(result)?;
+            }
+            mio::FileRequest::GetAttr { responder } => {
+                let span = span!(Level::TRACE, "File::GetAttr");
+                let _ = span.enter();
+                match self.file.get_attrs().await {
+                    Ok(attrs) => responder.send(Status::OK.into_raw(), &attrs)?,
+                    Err(status) => responder.send(status.into_raw(), &EMPTY_NODE_ATTRIBUTES)?,
+                }
+            }
+            mio::FileRequest::SetAttr {
+                flags: _,
+                attributes: _,
+                responder,
+            } => {
+                responder.send(Status::NOT_SUPPORTED.into_raw())?;
+            }
+            mio::FileRequest::GetAttributes { query: _, responder } => {
+                responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
+            }
+            mio::FileRequest::UpdateAttributes { payload: _, responder } => {
+                responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
+            }
+            mio::FileRequest::ListExtendedAttributes { iterator, .. } => {
+                iterator.close_with_epitaph(Status::NOT_SUPPORTED)?;
+            }
+            mio::FileRequest::GetExtendedAttribute { responder, .. } => {
+                responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
+            }
+            mio::FileRequest::SetExtendedAttribute { responder, .. } => {
+                responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
+            }
+            mio::FileRequest::RemoveExtendedAttribute { responder, .. } => {
+                responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
+            }
+            mio::FileRequest::GetFlags { responder } => {
+                responder.send(Status::OK.into_raw(), self.options.to_io1())?;
+            }
+            mio::FileRequest::SetFlags { flags, responder } => {
+                // Only APPEND can be changed after the connection has been opened.
+                self.options.is_append = flags.intersects(mio::OpenFlags::APPEND);
+                responder.send(Status::OK.into_raw())?;
+            }
+            mio::FileRequest::Read { count, responder } => {
+                let span = span!(Level::TRACE, "File::Read");
+                let _ = span.enter();
+                let result = self.handle_read(count).await;
+                responder.send(result.as_deref().map_err(|s| s.into_raw()))?;
+            }
+            mio::FileRequest::ReadAt {
+                offset,
+                count,
+                responder,
+            } => {
+                let span = span!(Level::TRACE, "File::ReadAt");
+                let _ = span.enter();
+                let result = self.handle_read_at(offset, count).await;
+                responder.send(result.as_deref().map_err(|s| s.into_raw()))?;
+            }
+            mio::FileRequest::Write { data, responder } => {
+                let span = span!(Level::TRACE, "File::Write");
+                let _ = span.enter();
+                let result = self.handle_write(&data).await;
+                responder.send(result.map_err(Status::into_raw))?;
+            }
+            mio::FileRequest::WriteAt {
+                offset,
+                data,
+                responder,
+            } => {
+                let span = span!(Level::TRACE, "File::WriteAt");
+                let _ = span.enter();
+                let result = self.handle_write_at(offset, &data).await;
+                responder.send(result.map_err(Status::into_raw))?;
+            }
+            mio::FileRequest::Seek {
+                origin,
+                offset,
+                responder,
+            } => {
+                let result = self.handle_seek(offset, origin).await;
+                responder.send(result.map_err(Status::into_raw))?;
+            }
+            mio::FileRequest::Resize { length, responder } => {
+                let span = span!(Level::TRACE, "File::Resize");
+                let _ = span.enter();
+                let result = self.handle_truncate(length).await;
+                responder.send(result.map_err(Status::into_raw))?;
+            }
+            mio::FileRequest::GetBackingMemory { flags, responder } => {
+                let span = span!(Level::TRACE, "File::GetBackingMemory");
+                let _ = span.enter();
+                let result = self.handle_get_backing_memory(flags).await;
+                responder.send(result.map_err(Status::into_raw))?;
+            }
+            mio::FileRequest::AdvisoryLock { request: _, responder } => {
+                responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
+            }
+            mio::FileRequest::LinkInto {
+                dst_parent_token: _,
+                dst: _,
+                responder,
+            } => {
+                responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
+            }
+            mio::FileRequest::Allocate { responder, .. } => {
+                responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
+            }
+            mio::FileRequest::EnableVerity { responder, .. } => {
+                responder.send(Err(Status::NOT_SUPPORTED.into_raw()))?;
+            }
+        }
+        Ok(ConnectionState::Alive)
+    }
+
+    fn handle_clone(&mut self, flags: mio::OpenFlags, server_end: ServerEnd<mio::NodeMarker>) {
+        let describe = flags.intersects(mio::OpenFlags::DESCRIBE);
+        let flags = match inherit_rights_for_clone(self.options.to_io1(), flags) {
+            Ok(updated) => updated,
+            Err(status) => {
+                send_on_open_with_error(describe, server_end, status);
+                return;
+            }
+        };
+
+        let file: Arc<T> = (*self.file).clone();
+        file.open(self.scope.clone(), flags, Path::dot(), server_end);
+    }
+
+    async fn handle_read(&mut self, count: u64) -> Result<Vec<u8>, Status> {
+        let offset = self.seek;
+        let bytes = self.handle_read_at(offset, count).await?;
+        self.seek += bytes.len() as u64;
+        Ok(bytes)
+    }
+
+    async fn handle_read_at(&self, offset: u64, count: u64) -> Result<Vec<u8>, Status> {
+        if !self.options.rights.intersects(mio::OpenFlags::RIGHT_READABLE) {
+            return Err(Status::BAD_HANDLE);
+        }
+        if count > MAX_TRANSFER_SIZE {
+            return Err(Status::OUT_OF_RANGE);
+        }
+        let mut buffer = vec![0u8; count as usize];
+        let count = self.file.read_at(offset, &mut buffer[..]).await?;
+        buffer.truncate(count.try_into().unwrap());
+        Ok(buffer)
+    }
+
+    async fn handle_write(&mut self, content: &[u8]) -> Result<u64, Status> {
+        if !self.options.rights.intersects(mio::OpenFlags::RIGHT_WRITABLE) {
+            return Err(Status::BAD_HANDLE);
+        }
+        if self.options.is_append {
+            let (bytes, offset) = self.file.append(content).await?;
+            self.seek = offset;
+            Ok(bytes)
+        } else {
+            let actual = self.handle_write_at(self.seek, content).await?;
+            self.seek += actual;
+            Ok(actual)
+        }
+    }
+
+    async fn handle_write_at(&self, offset: u64, content: &[u8]) -> Result<u64, Status> {
+        if !self.options.rights.intersects(mio::OpenFlags::RIGHT_WRITABLE) {
+            return Err(Status::BAD_HANDLE);
+        }
+        if content.len() as u64 > MAX_TRANSFER_SIZE {
+            return Err(Status::OUT_OF_RANGE);
+        }
+        self.file.write_at(offset, content).await
+    }
+
+    /// Move seek position to byte `offset` relative to the origin specified by `start`.
+    async fn handle_seek(&mut self, offset: i64, origin: mio::SeekOrigin) -> Result<u64, Status> {
+        let origin: i128 = match origin {
+            mio::SeekOrigin::Start => 0,
+            mio::SeekOrigin::Current => self.seek.into(),
+            mio::SeekOrigin::End => self.file.get_size().await?.into(),
+        };
+        let new_seek = origin + i128::from(offset);
+        if new_seek < 0 || new_seek > u64::MAX.into() {
+            return Err(Status::OUT_OF_RANGE);
+        }
+        self.seek = new_seek as u64;
+        Ok(self.seek)
+    }
+
+    async fn handle_truncate(&self, length: u64) -> Result<(), Status> {
+        if !self.options.rights.intersects(mio::OpenFlags::RIGHT_WRITABLE) {
+            return Err(Status::BAD_HANDLE);
+        }
+        self.file.truncate(length).await
+    }
+
+    async fn handle_get_backing_memory(&self, flags: mio::VmoFlags) -> Result<fiber_rust::Vmo, Status> {
+        // Ensure we have the rights required for the requested VMO flags.
+        if vmo_flags_to_rights(flags).intersects(!self.options.rights) {
+            return Err(Status::ACCESS_DENIED);
+        }
+        // A private copy of the file is always safe to hand out, a shared writable mapping is
+        // not something every file can provide.
+        if flags.contains(mio::VmoFlags::PRIVATE_CLONE) && flags.contains(mio::VmoFlags::SHARED_BUFFER) {
+            return Err(Status::INVALID_ARGS);
+        }
+        self.file.get_backing_memory(flags).await
+    }
+}
+
+/// Returns the `NodeAttributes.mode` of a regular file with the given capabilities.
+pub(crate) fn file_mode<T: File + ?Sized>(file: &T) -> u32 {
+    mio::MODE_TYPE_FILE | rights_to_posix_mode_bits(file.readable(), file.writable(), file.executable())
+}
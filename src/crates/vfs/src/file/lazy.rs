@@ -0,0 +1,95 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Read-only files whose content is generated by an async callback every time the file is opened.
+//! Useful for diagnostics and other content that reflects the current state of the component.
+
+use crate::{
+    common::send_on_open_with_error,
+    directory::entry::{DirectoryEntry, EntryInfo},
+    execution_scope::ExecutionScope,
+    file::SimpleFile,
+    path::Path,
+};
+
+use {
+    fiber_status::Status,
+    futures::future::BoxFuture,
+    midl::endpoints::ServerEnd,
+    midl_meshx_io as mio,
+    std::{future::Future, sync::Arc},
+};
+
+/// Creates a read-only file whose content is produced by `content` each time a connection is
+/// opened.  Every connection sees a consistent snapshot, taken when it was opened, no matter how
+/// the content changes afterwards.
+///
+/// ## Examples
+/// ```
+/// # use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+/// # use vfs::file::lazy;
+/// let counter = Arc::new(AtomicUsize::new(0));
+/// let file = lazy(move || {
+///     let counter = counter.clone();
+///     async move { Ok(counter.fetch_add(1, Ordering::Relaxed).to_string().into_bytes()) }
+/// });
+/// ```
+pub fn lazy<F, Fut>(content: F) -> Arc<Lazy>
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Vec<u8>, Status>> + Send + 'static,
+{
+    Lazy::new(mio::INO_UNKNOWN, move || -> BoxFuture<'static, _> {
+        Box::pin(content())
+    })
+}
+
+/// A file whose content is generated on open.  See [`lazy()`].
+pub struct Lazy {
+    inode: u64,
+    content: Box<dyn Fn() -> BoxFuture<'static, Result<Vec<u8>, Status>> + Send + Sync>,
+}
+
+impl Lazy {
+    fn new(
+        inode: u64,
+        content: impl Fn() -> BoxFuture<'static, Result<Vec<u8>, Status>> + Send + Sync + 'static,
+    ) -> Arc<Self> {
+        Arc::new(Lazy {
+            inode,
+            content: Box::new(content),
+        })
+    }
+}
+
+impl DirectoryEntry for Lazy {
+    fn open(
+        self: Arc<Self>,
+        scope: ExecutionScope,
+        flags: mio::OpenFlags,
+        path: Path,
+        server_end: ServerEnd<mio::NodeMarker>,
+    ) {
+        let describe = flags.intersects(mio::OpenFlags::DESCRIBE);
+        if !path.is_empty() {
+            send_on_open_with_error(describe, server_end, Status::NOT_DIR);
+            return;
+        }
+
+        let content = (self.content)();
+        let inode = self.inode;
+        let task_scope = scope.clone();
+        task_scope.spawn(async move {
+            match content.await {
+                Ok(content) => SimpleFile::read_only_with_inode(content, inode).open(scope, flags, path, server_end),
+                Err(status) => send_on_open_with_error(describe, server_end, status),
+            }
+        });
+    }
+
+    fn entry_info(&self) -> EntryInfo {
+        EntryInfo::new(self.inode, mio::DirentType::File)
+    }
+}
@@ -0,0 +1,266 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Files that hold their content in an in-memory buffer.  Use [`read_only()`] for static content,
+//! such as configuration, and [`read_write()`] for scratch files the clients can modify.
+
+use crate::{
+    common::send_on_open_with_error,
+    directory::entry::{DirectoryEntry, EntryInfo},
+    execution_scope::ExecutionScope,
+    file::{
+        connection::{self, file_mode},
+        File, FileIo, FileOptions, SyncMode,
+    },
+    node::Node,
+    path::Path,
+};
+
+use {
+    async_trait::async_trait,
+    fiber_rust as fx,
+    fiber_status::Status,
+    midl::endpoints::ServerEnd,
+    midl_meshx_io as mio,
+    std::sync::{Arc, Mutex},
+};
+
+/// The size past which writable [`SimpleFile`]s do not grow, as they hold their content in memory.
+pub const MAX_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Creates a new read-only `SimpleFile` with the specified `content`.
+///
+/// ## Examples
+/// ```
+/// # use vfs::file::read_only;
+/// // Using static data:
+/// let from_str = read_only("str");
+/// let from_bytes = read_only(b"bytes");
+/// // Using owned data:
+/// let from_string = read_only(String::from("owned"));
+/// let from_vec = read_only(vec![0u8; 2]);
+/// ```
+pub fn read_only(content: impl AsRef<[u8]>) -> Arc<SimpleFile> {
+    SimpleFile::read_only(content)
+}
+
+/// Creates a new writable `SimpleFile` with the specified initial `content`.
+pub fn read_write(content: impl AsRef<[u8]>) -> Arc<SimpleFile> {
+    SimpleFile::read_write(content)
+}
+
+/// A file with a byte array for content, useful for testing and for small files whose content is
+/// known up front.  Writable files grow as they are written to, and are shared by all of their
+/// connections.
+pub struct SimpleFile {
+    data: Mutex<Vec<u8>>,
+    writable: bool,
+    inode: u64,
+}
+
+impl SimpleFile {
+    /// Create a new read-only test file with the specified content.
+    pub fn read_only(content: impl AsRef<[u8]>) -> Arc<Self> {
+        Self::new(content.as_ref().to_vec(), false, mio::INO_UNKNOWN)
+    }
+
+    /// Create a new writable test file with the specified initial content.
+    pub fn read_write(content: impl AsRef<[u8]>) -> Arc<Self> {
+        Self::new(content.as_ref().to_vec(), true, mio::INO_UNKNOWN)
+    }
+
+    /// Create a new read-only test file with the specified content and inode.
+    pub fn read_only_with_inode(content: impl AsRef<[u8]>, inode: u64) -> Arc<Self> {
+        Self::new(content.as_ref().to_vec(), false, inode)
+    }
+
+    fn new(data: Vec<u8>, writable: bool, inode: u64) -> Arc<Self> {
+        Arc::new(SimpleFile {
+            data: Mutex::new(data),
+            writable,
+            inode,
+        })
+    }
+
+    /// Returns a copy of the current content of the file.
+    pub fn content(&self) -> Vec<u8> {
+        self.data.lock().unwrap().clone()
+    }
+}
+
+impl DirectoryEntry for SimpleFile {
+    fn open(
+        self: Arc<Self>,
+        scope: ExecutionScope,
+        flags: mio::OpenFlags,
+        path: Path,
+        server_end: ServerEnd<mio::NodeMarker>,
+    ) {
+        if !path.is_empty() {
+            send_on_open_with_error(flags.intersects(mio::OpenFlags::DESCRIBE), server_end, Status::NOT_DIR);
+            return;
+        }
+
+        connection::create_connection(scope, self, flags, server_end);
+    }
+
+    fn entry_info(&self) -> EntryInfo {
+        EntryInfo::new(self.inode, mio::DirentType::File)
+    }
+}
+
+#[async_trait]
+impl Node for SimpleFile {
+    async fn get_attrs(&self) -> Result<mio::NodeAttributes, Status> {
+        let content_size = self.data.lock().unwrap().len() as u64;
+        Ok(mio::NodeAttributes {
+            mode: file_mode(self),
+            id: self.inode,
+            content_size,
+            storage_size: content_size,
+            link_count: 1,
+            creation_time: 0,
+            modification_time: 0,
+        })
+    }
+}
+
+#[async_trait]
+impl File for SimpleFile {
+    fn writable(&self) -> bool {
+        self.writable
+    }
+
+    async fn open_file(&self, _options: &FileOptions) -> Result<(), Status> {
+        Ok(())
+    }
+
+    async fn truncate(&self, length: u64) -> Result<(), Status> {
+        if !self.writable {
+            return Err(Status::ACCESS_DENIED);
+        }
+        if length > MAX_SIZE {
+            return Err(Status::FILE_BIG);
+        }
+        let length = usize::try_from(length).map_err(|_| Status::OUT_OF_RANGE)?;
+        self.data.lock().unwrap().resize(length, 0);
+        Ok(())
+    }
+
+    async fn get_backing_memory(&self, flags: mio::VmoFlags) -> Result<fx::Vmo, Status> {
+        // A shared buffer would have to be kept in sync with `data`.
+        if flags.intersects(mio::VmoFlags::WRITE) && !flags.intersects(mio::VmoFlags::PRIVATE_CLONE) {
+            return Err(Status::NOT_SUPPORTED);
+        }
+        let data = self.data.lock().unwrap();
+        let vmo = fx::Vmo::create(data.len() as u64)?;
+        vmo.write(&data, 0)?;
+        Ok(vmo)
+    }
+
+    async fn get_size(&self) -> Result<u64, Status> {
+        Ok(self.data.lock().unwrap().len() as u64)
+    }
+
+    async fn sync(&self, _mode: SyncMode) -> Result<(), Status> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FileIo for SimpleFile {
+    async fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<u64, Status> {
+        let data = self.data.lock().unwrap();
+        let start = (offset as usize).min(data.len());
+        let count = buffer.len().min(data.len() - start);
+        buffer[..count].copy_from_slice(&data[start..start + count]);
+        Ok(count as u64)
+    }
+
+    async fn write_at(&self, offset: u64, content: &[u8]) -> Result<u64, Status> {
+        if !self.writable {
+            return Err(Status::ACCESS_DENIED);
+        }
+        let start = usize::try_from(offset).map_err(|_| Status::OUT_OF_RANGE)?;
+        let end = start.checked_add(content.len()).ok_or(Status::OUT_OF_RANGE)?;
+        if end as u64 > MAX_SIZE {
+            return Err(Status::FILE_BIG);
+        }
+        let mut data = self.data.lock().unwrap();
+        if data.len() < end {
+            // Writing past the end fills the gap with zeroes, as POSIX does.
+            data.resize(end, 0);
+        }
+        data[start..end].copy_from_slice(content);
+        Ok(content.len() as u64)
+    }
+
+    async fn append(&self, content: &[u8]) -> Result<(u64, u64), Status> {
+        if !self.writable {
+            return Err(Status::ACCESS_DENIED);
+        }
+        let mut data = self.data.lock().unwrap();
+        if (data.len() + content.len()) as u64 > MAX_SIZE {
+            return Err(Status::FILE_BIG);
+        }
+        data.extend_from_slice(content);
+        Ok((content.len() as u64, data.len() as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::executor::block_on;
+
+    #[test]
+    fn read_past_end_is_short() {
+        let file = read_only("hello");
+        let mut buffer = [0u8; 8];
+        assert_eq!(block_on(file.read_at(3, &mut buffer)), Ok(2));
+        assert_eq!(&buffer[..2], b"lo");
+        assert_eq!(block_on(file.read_at(10, &mut buffer)), Ok(0));
+    }
+
+    #[test]
+    fn read_only_rejects_writes() {
+        let file = read_only("hello");
+        assert_eq!(block_on(file.write_at(0, b"j")), Err(Status::ACCESS_DENIED));
+        assert_eq!(block_on(file.append(b"!")), Err(Status::ACCESS_DENIED));
+        assert_eq!(block_on(file.truncate(0)), Err(Status::ACCESS_DENIED));
+        assert_eq!(file.content(), b"hello");
+    }
+
+    #[test]
+    fn write_past_end_fills_with_zeroes() {
+        let file = read_write("ab");
+        assert_eq!(block_on(file.write_at(4, b"cd")), Ok(2));
+        assert_eq!(file.content(), b"ab\0\0cd");
+    }
+
+    #[test]
+    fn writes_do_not_grow_past_max_size() {
+        let file = read_write("ab");
+        assert_eq!(block_on(file.write_at(MAX_SIZE - 1, b"cd")), Err(Status::FILE_BIG));
+        assert_eq!(block_on(file.write_at(u64::MAX, b"cd")), Err(Status::OUT_OF_RANGE));
+        assert_eq!(block_on(file.truncate(MAX_SIZE + 1)), Err(Status::FILE_BIG));
+        assert_eq!(file.content(), b"ab");
+
+        assert_eq!(block_on(file.truncate(MAX_SIZE - 1)), Ok(()));
+        assert_eq!(block_on(file.append(b"cd")), Err(Status::FILE_BIG));
+        assert_eq!(block_on(file.write_at(MAX_SIZE - 1, b"c")), Ok(1));
+        assert_eq!(block_on(file.get_size()), Ok(MAX_SIZE));
+    }
+
+    #[test]
+    fn append_and_truncate() {
+        let file = read_write("ab");
+        assert_eq!(block_on(file.append(b"cd")), Ok((2, 4)));
+        assert_eq!(block_on(file.truncate(1)), Ok(()));
+        assert_eq!(file.content(), b"a");
+        assert_eq!(block_on(file.get_size()), Ok(1));
+    }
+}
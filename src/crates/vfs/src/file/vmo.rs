@@ -0,0 +1,230 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Implementation of a file backed by a VMO buffer shared by all the file connections.
+
+use crate::{
+    common::send_on_open_with_error,
+    directory::entry::{DirectoryEntry, EntryInfo},
+    execution_scope::ExecutionScope,
+    file::{
+        connection::{self, file_mode},
+        File, FileIo, FileOptions, SyncMode,
+    },
+    node::Node,
+    path::Path,
+};
+
+use {
+    async_trait::async_trait,
+    fiber_rust::{self as fx, HandleBased as _},
+    fiber_status::Status,
+    midl::endpoints::ServerEnd,
+    midl_meshx_io as mio,
+    std::sync::{Arc, Mutex},
+};
+
+/// Implementation of a VMO-backed file in a virtual file system.
+///
+/// The content size is tracked separately from the VMO size, so that the VMO can be larger than
+/// the file.  Writes never grow the VMO: they fail with `Status::OUT_OF_RANGE` past its end.
+pub struct VmoFile {
+    /// Specifies if the file is readable.
+    readable: bool,
+
+    /// Specifies if the file is writable.
+    writable: bool,
+
+    /// Specifies if the file can be opened as executable.
+    executable: bool,
+
+    /// Specifies the inode for this file.  Can be [`mio::INO_UNKNOWN`] if not required.
+    inode: u64,
+
+    /// The VMO backing this file.
+    vmo: fx::Vmo,
+
+    /// Number of bytes of `vmo` that are part of the file.
+    content_size: Mutex<u64>,
+}
+
+impl VmoFile {
+    /// Create a new read-only [`VmoFile`] exposing the first `content_size` bytes of `vmo`.
+    pub fn new(vmo: fx::Vmo, content_size: u64) -> Arc<Self> {
+        Self::new_with_inode(vmo, content_size, true, false, false, mio::INO_UNKNOWN)
+    }
+
+    /// Create a new [`VmoFile`] with the specified capabilities and inode.
+    pub fn new_with_inode(
+        vmo: fx::Vmo,
+        content_size: u64,
+        readable: bool,
+        writable: bool,
+        executable: bool,
+        inode: u64,
+    ) -> Arc<Self> {
+        Arc::new(VmoFile {
+            readable,
+            writable,
+            executable,
+            inode,
+            vmo,
+            content_size: Mutex::new(content_size),
+        })
+    }
+
+    /// Create a new read-only [`VmoFile`] holding a copy of `content`.
+    pub fn from_bytes(content: impl AsRef<[u8]>) -> Result<Arc<Self>, Status> {
+        let content = content.as_ref();
+        let vmo = fx::Vmo::create(content.len() as u64)?;
+        vmo.write(content, 0)?;
+        Ok(Self::new(vmo, content.len() as u64))
+    }
+
+    fn content_size(&self) -> u64 {
+        *self.content_size.lock().unwrap()
+    }
+}
+
+impl DirectoryEntry for VmoFile {
+    fn open(
+        self: Arc<Self>,
+        scope: ExecutionScope,
+        flags: mio::OpenFlags,
+        path: Path,
+        server_end: ServerEnd<mio::NodeMarker>,
+    ) {
+        if !path.is_empty() {
+            send_on_open_with_error(flags.intersects(mio::OpenFlags::DESCRIBE), server_end, Status::NOT_DIR);
+            return;
+        }
+
+        connection::create_connection(scope, self, flags, server_end);
+    }
+
+    fn entry_info(&self) -> EntryInfo {
+        EntryInfo::new(self.inode, mio::DirentType::File)
+    }
+}
+
+#[async_trait]
+impl Node for VmoFile {
+    async fn get_attrs(&self) -> Result<mio::NodeAttributes, Status> {
+        Ok(mio::NodeAttributes {
+            mode: file_mode(self),
+            id: self.inode,
+            content_size: self.content_size(),
+            storage_size: self.vmo.get_size()?,
+            link_count: 1,
+            creation_time: 0,
+            modification_time: 0,
+        })
+    }
+}
+
+#[async_trait]
+impl File for VmoFile {
+    fn readable(&self) -> bool {
+        self.readable
+    }
+
+    fn writable(&self) -> bool {
+        self.writable
+    }
+
+    fn executable(&self) -> bool {
+        self.executable
+    }
+
+    async fn open_file(&self, _options: &FileOptions) -> Result<(), Status> {
+        Ok(())
+    }
+
+    async fn truncate(&self, length: u64) -> Result<(), Status> {
+        if !self.writable {
+            return Err(Status::ACCESS_DENIED);
+        }
+        if length > self.vmo.get_size()? {
+            return Err(Status::OUT_OF_RANGE);
+        }
+        let mut content_size = self.content_size.lock().unwrap();
+        if length < *content_size {
+            // Bytes beyond the new size must read back as zeroes if the file grows again.
+            self.vmo.write(&vec![0u8; (*content_size - length) as usize], length)?;
+        }
+        *content_size = length;
+        Ok(())
+    }
+
+    async fn get_backing_memory(&self, flags: mio::VmoFlags) -> Result<fx::Vmo, Status> {
+        // Private clones would need copy-on-write children, which the kernel does not provide yet.
+        if flags.contains(mio::VmoFlags::PRIVATE_CLONE) && flags.contains(mio::VmoFlags::WRITE) {
+            return Err(Status::NOT_SUPPORTED);
+        }
+
+        let mut rights = fx::Rights::BASIC | fx::Rights::MAP | fx::Rights::GET_PROPERTY;
+        if flags.contains(mio::VmoFlags::READ) {
+            rights |= fx::Rights::READ;
+        }
+        if flags.contains(mio::VmoFlags::WRITE) {
+            rights |= fx::Rights::WRITE;
+        }
+        if flags.contains(mio::VmoFlags::EXECUTE) {
+            rights |= fx::Rights::EXECUTE;
+        }
+        self.vmo.duplicate_handle(rights)
+    }
+
+    async fn get_size(&self) -> Result<u64, Status> {
+        Ok(self.content_size())
+    }
+
+    async fn sync(&self, _mode: SyncMode) -> Result<(), Status> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FileIo for VmoFile {
+    async fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<u64, Status> {
+        let content_size = self.content_size();
+        if offset >= content_size {
+            return Ok(0);
+        }
+        let count = (buffer.len() as u64).min(content_size - offset);
+        self.vmo.read(&mut buffer[..count as usize], offset)?;
+        Ok(count)
+    }
+
+    async fn write_at(&self, offset: u64, content: &[u8]) -> Result<u64, Status> {
+        if !self.writable {
+            return Err(Status::ACCESS_DENIED);
+        }
+        let end = offset.checked_add(content.len() as u64).ok_or(Status::OUT_OF_RANGE)?;
+        if end > self.vmo.get_size()? {
+            return Err(Status::OUT_OF_RANGE);
+        }
+        let mut content_size = self.content_size.lock().unwrap();
+        self.vmo.write(content, offset)?;
+        *content_size = (*content_size).max(end);
+        Ok(content.len() as u64)
+    }
+
+    async fn append(&self, content: &[u8]) -> Result<(u64, u64), Status> {
+        if !self.writable {
+            return Err(Status::ACCESS_DENIED);
+        }
+        let mut content_size = self.content_size.lock().unwrap();
+        let end = content_size
+            .checked_add(content.len() as u64)
+            .ok_or(Status::OUT_OF_RANGE)?;
+        if end > self.vmo.get_size()? {
+            return Err(Status::OUT_OF_RANGE);
+        }
+        self.vmo.write(content, *content_size)?;
+        *content_size = end;
+        Ok((content.len() as u64, end))
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Nodes that pass through to a directory of the host file system.  Use [`read_only()`] or
+//! [`read_write()`] to serve a host directory over the `meshx.io` protocols, for example to expose
+//! a build output directory to a component during development, without copying it into an
+//! in-memory tree first.
+//!
+//! Nodes for the entries inside the directory are created when they are opened, and are not kept
+//! around after their last connection is closed.  All the paths are resolved on the host, and
+//! any path that leaves the served directory, following a symlink, is rejected with
+//! `ACCESS_DENIED`.  Note that the check is not atomic with respect to other processes that modify
+//! the host directory at the same time, so it should not be relied upon for untrusted trees.
+//!
+//! All the file system operations are blocking, and run on the executor thread.
+
+use {
+    camino::{Utf8Path, Utf8PathBuf},
+    fiber_status::Status,
+    std::{io, sync::Arc},
+};
+
+pub mod directory;
+pub mod file;
+
+pub use directory::HostDirectory;
+pub use file::HostFile;
+
+/// Serves the host directory at `path`.  Clients can not modify the directory, nor any of its
+/// entries.
+pub fn read_only(path: impl AsRef<Utf8Path>) -> Result<Arc<HostDirectory>, Status> {
+    HostDirectory::new(path.as_ref(), true)
+}
+
+/// Serves the host directory at `path`.  Clients with the `RIGHT_WRITABLE` right can create,
+/// remove, rename and write to the entries of the directory.
+pub fn read_write(path: impl AsRef<Utf8Path>) -> Result<Arc<HostDirectory>, Status> {
+    HostDirectory::new(path.as_ref(), false)
+}
+
+/// State shared by all the nodes of a served host directory.
+struct Root {
+    /// Canonical path of the served directory.
+    path: Utf8PathBuf,
+
+    read_only: bool,
+}
+
+impl Root {
+    /// Canonicalizes `path`, checking that it is inside the served directory.
+    fn resolve(&self, path: &Utf8Path) -> Result<Utf8PathBuf, Status> {
+        let path = path.canonicalize_utf8().map_err(io_status)?;
+        if !path.starts_with(&self.path) {
+            return Err(Status::ACCESS_DENIED);
+        }
+        Ok(path)
+    }
+
+    /// Modifications are only allowed if the directory is served with [`read_write()`].
+    fn check_writable(&self) -> Result<(), Status> {
+        if self.read_only {
+            Err(Status::ACCESS_DENIED)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Converts a host error to a `Status`.
+fn io_status(error: io::Error) -> Status {
+    match error.raw_os_error() {
+        Some(libc::ENOTDIR) => Status::NOT_DIR,
+        Some(libc::EISDIR) => Status::NOT_FILE,
+        Some(libc::ENOTEMPTY) => Status::NOT_EMPTY,
+        Some(libc::ENAMETOOLONG) => Status::BAD_PATH,
+        Some(libc::ELOOP) => Status::BAD_PATH,
+        _ => error.kind().into(),
+    }
+}
+
+/// The modification time of a host node, in nanoseconds since the Unix epoch, as reported by
+/// `GetAttr`.
+fn modification_time(metadata: &std::fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_nanos() as u64)
+}
@@ -0,0 +1,413 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! A directory that passes through to a directory of the host file system.  See
+//! [`crate::host`] for details.
+
+use crate::{
+    common::{rights_to_posix_mode_bits, send_on_open_with_error},
+    directory::{
+        connection::DerivedConnection,
+        dirents_sink::{self, AppendResult},
+        entry::{DirectoryEntry, EntryInfo},
+        entry_container::{Directory, MutableDirectory},
+        immutable::connection::ImmutableConnection,
+        mutable::connection::MutableConnection,
+    },
+    execution_scope::ExecutionScope,
+    host::{io_status, modification_time, HostFile, Root},
+    name::{validate_name, Name},
+    node::Node,
+    path::Path,
+    traversal_position::TraversalPosition,
+};
+
+use {
+    async_trait::async_trait,
+    camino::{Utf8Path, Utf8PathBuf},
+    fiber_rust as fx,
+    fiber_status::Status,
+    midl::endpoints::ServerEnd,
+    midl_meshx_io as mio,
+    std::{any::Any, collections::BTreeMap, fs, ops::Bound, sync::Arc},
+};
+
+/// A directory of the host file system.  Use [`crate::host::read_only()`] or
+/// [`crate::host::read_write()`] to construct instances.
+pub struct HostDirectory {
+    root: Arc<Root>,
+
+    /// Canonical path of this directory.
+    path: Utf8PathBuf,
+}
+
+impl HostDirectory {
+    pub(super) fn new(path: &Utf8Path, read_only: bool) -> Result<Arc<Self>, Status> {
+        let path = path.canonicalize_utf8().map_err(io_status)?;
+        if !path.is_dir() {
+            return Err(Status::NOT_DIR);
+        }
+        let root = Arc::new(Root {
+            path: path.clone(),
+            read_only,
+        });
+        Ok(Arc::new(HostDirectory { root, path }))
+    }
+
+    /// The host path of this directory.
+    pub fn path(&self) -> &Utf8Path {
+        &self.path
+    }
+
+    /// Returns the node for the canonical host `path`.
+    fn node(&self, path: Utf8PathBuf) -> Result<Arc<dyn DirectoryEntry>, Status> {
+        let metadata = fs::metadata(&path).map_err(io_status)?;
+        let root = self.root.clone();
+        Ok(if metadata.is_dir() {
+            Arc::new(HostDirectory { root, path })
+        } else {
+            Arc::new(HostFile::new(root, path))
+        })
+    }
+
+    /// Looks up the entry at `path`, relative to this directory, creating it if it does not exist
+    /// and `flags` ask for it.
+    fn lookup(&self, flags: mio::OpenFlags, path: &Path) -> Result<Arc<dyn DirectoryEntry>, Status> {
+        let target = self.path.join(path.as_ref());
+        match self.root.resolve(&target) {
+            Ok(_) if flags.intersects(mio::OpenFlags::CREATE_IF_ABSENT) => Err(Status::ALREADY_EXISTS),
+            Ok(resolved) => self.node(resolved),
+            Err(Status::NOT_FOUND) if flags.intersects(mio::OpenFlags::CREATE) => self.create(flags, &target),
+            Err(status) => Err(status),
+        }
+    }
+
+    /// Creates the entry at the host path `target`, which does not exist yet.
+    fn create(&self, flags: mio::OpenFlags, target: &Utf8Path) -> Result<Arc<dyn DirectoryEntry>, Status> {
+        self.root.check_writable()?;
+        // Only the last component can be created, and it is created in the resolved parent, so
+        // that a symlink in the path can not be used to create an entry outside of the root.
+        let (Some(parent), Some(name)) = (target.parent(), target.file_name()) else {
+            return Err(Status::BAD_PATH);
+        };
+        let path = self.root.resolve(parent)?.join(name);
+        if flags.intersects(mio::OpenFlags::DIRECTORY) {
+            fs::create_dir(&path).map_err(io_status)?;
+        } else {
+            fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+                .map_err(io_status)?;
+        }
+        self.node(path)
+    }
+
+    /// Returns the host path of the entry `name` of this directory.  The entry itself is not
+    /// resolved, so that operations on symlinks apply to the symlink, rather than its target.
+    fn entry_path(&self, name: &str) -> Result<Utf8PathBuf, Status> {
+        validate_name(name)?;
+        Ok(self.path.join(name))
+    }
+
+    fn downcast(dir: Arc<dyn Any + Send + Sync>) -> Result<Arc<Self>, Status> {
+        let dir = dir.downcast::<Self>().map_err(|_| Status::NOT_SUPPORTED)?;
+        Ok(dir)
+    }
+}
+
+impl DirectoryEntry for HostDirectory {
+    fn open(
+        self: Arc<Self>,
+        scope: ExecutionScope,
+        flags: mio::OpenFlags,
+        path: Path,
+        server_end: ServerEnd<mio::NodeMarker>,
+    ) {
+        let describe = flags.intersects(mio::OpenFlags::DESCRIBE);
+        let modifies = mio::OpenFlags::RIGHT_WRITABLE | mio::OpenFlags::CREATE | mio::OpenFlags::TRUNCATE;
+        if self.root.read_only && flags.intersects(modifies) {
+            send_on_open_with_error(describe, server_end, Status::ACCESS_DENIED);
+            return;
+        }
+
+        if path.is_empty() {
+            if self.root.read_only {
+                ImmutableConnection::create_connection(scope, self, flags, server_end);
+            } else {
+                MutableConnection::create_connection(scope, self, flags, server_end);
+            }
+            return;
+        }
+
+        match self.lookup(flags, &path) {
+            Ok(entry) => entry.open(scope, flags, Path::dot(), server_end),
+            Err(status) => send_on_open_with_error(describe, server_end, status),
+        }
+    }
+
+    fn entry_info(&self) -> EntryInfo {
+        EntryInfo::new(mio::INO_UNKNOWN, mio::DirentType::Directory)
+    }
+}
+
+#[async_trait]
+impl Node for HostDirectory {
+    async fn get_attrs(&self) -> Result<mio::NodeAttributes, Status> {
+        let metadata = fs::metadata(&self.path).map_err(io_status)?;
+        Ok(mio::NodeAttributes {
+            mode: mio::MODE_TYPE_DIRECTORY | rights_to_posix_mode_bits(true, !self.root.read_only, false),
+            id: mio::INO_UNKNOWN,
+            content_size: 0,
+            storage_size: 0,
+            link_count: 1,
+            creation_time: 0,
+            modification_time: modification_time(&metadata),
+        })
+    }
+}
+
+#[async_trait]
+impl Directory for HostDirectory {
+    async fn read_dirents<'a>(
+        &'a self,
+        pos: &'a TraversalPosition,
+        sink: Box<dyn dirents_sink::Sink>,
+    ) -> Result<(TraversalPosition, Box<dyn dirents_sink::Sealed>), Status> {
+        let (mut sink, start) = match pos {
+            TraversalPosition::Start => {
+                match sink.append(&EntryInfo::new(mio::INO_UNKNOWN, mio::DirentType::Directory), ".") {
+                    AppendResult::Ok(sink) => (sink, Bound::Unbounded),
+                    AppendResult::Sealed(sealed) => return Ok((TraversalPosition::Start, sealed)),
+                }
+            }
+            // `Name` holds the first entry that did not fit in the previous call.
+            TraversalPosition::Name(name) => (sink, Bound::Included(name.as_str())),
+            TraversalPosition::Index(_) => unreachable!("Host directories only use Name positions"),
+            TraversalPosition::End => return Ok((TraversalPosition::End, sink.seal())),
+        };
+
+        // The listing is read again on every call, sorted, so that the position is a name.
+        let mut entries = BTreeMap::new();
+        for entry in fs::read_dir(&self.path).map_err(io_status)? {
+            let entry = entry.map_err(io_status)?;
+            // Entries that can not be named over `meshx.io` are skipped.
+            let Ok(name) = entry.file_name().into_string() else {
+                continue;
+            };
+            if validate_name(&name).is_err() {
+                continue;
+            }
+            // Symlinks are reported as the type of their target.
+            let type_ = match fs::metadata(entry.path()) {
+                Ok(metadata) if metadata.is_dir() => mio::DirentType::Directory,
+                Ok(_) => mio::DirentType::File,
+                Err(_) => mio::DirentType::Unknown,
+            };
+            entries.insert(name, type_);
+        }
+
+        for (name, type_) in entries.range::<str, _>((start, Bound::Unbounded)) {
+            match sink.append(&EntryInfo::new(mio::INO_UNKNOWN, *type_), name) {
+                AppendResult::Ok(new_sink) => sink = new_sink,
+                AppendResult::Sealed(sealed) => {
+                    return Ok((TraversalPosition::Name(name.clone()), sealed));
+                }
+            }
+        }
+
+        Ok((TraversalPosition::End, sink.seal()))
+    }
+
+    fn register_watcher(
+        self: Arc<Self>,
+        _scope: ExecutionScope,
+        _mask: mio::WatchMask,
+        _watcher: fx::Channel,
+    ) -> Result<(), Status> {
+        Err(Status::NOT_SUPPORTED)
+    }
+}
+
+#[async_trait]
+impl MutableDirectory for HostDirectory {
+    async fn link(
+        self: Arc<Self>,
+        name: Name,
+        source_dir: Arc<dyn Any + Send + Sync>,
+        source_name: &str,
+    ) -> Result<(), Status> {
+        self.root.check_writable()?;
+        let source_dir = Self::downcast(source_dir)?;
+        let source = source_dir.entry_path(source_name)?;
+        fs::hard_link(source, self.entry_path(&name)?).map_err(io_status)
+    }
+
+    async fn unlink(self: Arc<Self>, name: &str, must_be_directory: bool) -> Result<(), Status> {
+        self.root.check_writable()?;
+        let path = self.entry_path(name)?;
+        let metadata = fs::symlink_metadata(&path).map_err(io_status)?;
+        if metadata.is_dir() {
+            fs::remove_dir(&path).map_err(io_status)
+        } else if must_be_directory {
+            Err(Status::NOT_DIR)
+        } else {
+            fs::remove_file(&path).map_err(io_status)
+        }
+    }
+
+    async fn sync(&self) -> Result<(), Status> {
+        fs::File::open(&self.path)
+            .and_then(|dir| dir.sync_all())
+            .map_err(io_status)
+    }
+
+    async fn rename(
+        self: Arc<Self>,
+        src_dir: Arc<dyn Any + Send + Sync>,
+        src_name: Name,
+        dst_name: Name,
+    ) -> Result<(), Status> {
+        self.root.check_writable()?;
+        let src_dir = Self::downcast(src_dir)?;
+        // Directories of different host trees may be on different devices.
+        if !Arc::ptr_eq(&src_dir.root, &self.root) {
+            return Err(Status::NOT_SUPPORTED);
+        }
+        fs::rename(src_dir.entry_path(&src_name)?, self.entry_path(&dst_name)?).map_err(io_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::host::{read_only, read_write};
+
+    use {
+        futures::{executor::block_on, StreamExt as _},
+        meshx_async::TestExecutor,
+        midl::endpoints::{create_proxy, ProtocolMarker, Proxy as _},
+    };
+
+    fn temp_dir() -> (tempfile::TempDir, Utf8PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = Utf8PathBuf::try_from(dir.path().to_path_buf()).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn lookup_rejects_symlink_escape() {
+        let (_outside, outside) = temp_dir();
+        let (_root, root) = temp_dir();
+        std::os::unix::fs::symlink(&outside, root.join("escape")).unwrap();
+        fs::write(outside.join("secret"), "s").unwrap();
+
+        let dir = read_only(&root).unwrap();
+        let path = Path::validate_and_split("escape/secret").unwrap();
+        assert_eq!(
+            dir.lookup(mio::OpenFlags::RIGHT_READABLE, &path).err(),
+            Some(Status::ACCESS_DENIED)
+        );
+    }
+
+    #[test]
+    fn lookup_follows_symlinks_inside_root() {
+        let (_root, root) = temp_dir();
+        fs::create_dir(root.join("a")).unwrap();
+        fs::write(root.join("a/file"), "f").unwrap();
+        std::os::unix::fs::symlink(root.join("a"), root.join("b")).unwrap();
+
+        let dir = read_only(&root).unwrap();
+        let path = Path::validate_and_split("b/file").unwrap();
+        let entry = dir.lookup(mio::OpenFlags::RIGHT_READABLE, &path).unwrap();
+        assert_eq!(entry.entry_info().type_(), mio::DirentType::File);
+    }
+
+    #[test]
+    fn create_requires_read_write() {
+        let (_root, root) = temp_dir();
+        let path = Path::validate_and_split("new").unwrap();
+        let flags = mio::OpenFlags::RIGHT_READABLE | mio::OpenFlags::CREATE;
+
+        let dir = read_only(&root).unwrap();
+        assert_eq!(dir.lookup(flags, &path).err(), Some(Status::ACCESS_DENIED));
+        assert!(!root.join("new").exists());
+
+        let dir = read_write(&root).unwrap();
+        assert!(dir.lookup(flags | mio::OpenFlags::DIRECTORY, &path).is_ok());
+        assert!(root.join("new").is_dir());
+    }
+
+    #[test]
+    fn unlink_and_rename() {
+        let (_root, root) = temp_dir();
+        fs::write(root.join("a"), "a").unwrap();
+        fs::create_dir(root.join("d")).unwrap();
+        let dir = read_write(&root).unwrap();
+
+        assert_eq!(block_on(dir.clone().unlink("a", true)), Err(Status::NOT_DIR));
+        block_on(
+            dir.clone()
+                .rename(dir.clone(), Name::from("a").unwrap(), Name::from("b").unwrap()),
+        )
+        .unwrap();
+        assert!(!root.join("a").exists());
+        assert!(root.join("b").exists());
+
+        block_on(dir.clone().unlink("b", false)).unwrap();
+        block_on(dir.clone().unlink("d", true)).unwrap();
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 0);
+    }
+
+    /// Opens `path` in `dir` over a connection, returning the status `OnOpen` reports along with
+    /// the connection.
+    async fn open<T: ProtocolMarker>(
+        dir: &mio::DirectoryProxy,
+        flags: mio::OpenFlags,
+        path: &str,
+    ) -> (Status, T::Proxy) {
+        let (node, server_end) = create_proxy::<mio::NodeMarker>().unwrap();
+        dir.open(
+            flags | mio::OpenFlags::DESCRIBE,
+            mio::ModeType::empty(),
+            path,
+            server_end,
+        )
+        .unwrap();
+        let status = match node.take_event_stream().next().await {
+            Some(Ok(mio::NodeEvent::OnOpen { s, .. })) => Status::from_raw(s),
+            event => panic!("expected OnOpen, got {event:?}"),
+        };
+        (status, T::Proxy::from_channel(node.into_channel().unwrap()))
+    }
+
+    #[test]
+    fn serves_the_host_directory() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let (_root, root) = temp_dir();
+        fs::write(root.join("file"), "content").unwrap();
+        let scope = ExecutionScope::new();
+        let (dir, server_end) = create_proxy::<mio::DirectoryMarker>().unwrap();
+        read_only(&root).unwrap().open(
+            scope.clone(),
+            mio::OpenFlags::RIGHT_READABLE,
+            Path::dot(),
+            ServerEnd::new(server_end.into_channel()),
+        );
+
+        executor.run_singlethreaded(async {
+            let (status, file) = open::<mio::FileMarker>(&dir, mio::OpenFlags::RIGHT_READABLE, "file").await;
+            assert_eq!(status, Status::OK);
+            assert_eq!(file.read(100).await.unwrap(), Ok(b"content".to_vec()));
+
+            let (status, _) = open::<mio::FileMarker>(&dir, mio::OpenFlags::RIGHT_WRITABLE, "file").await;
+            assert_eq!(status, Status::ACCESS_DENIED);
+            let (status, _) = open::<mio::FileMarker>(&dir, mio::OpenFlags::RIGHT_READABLE, "../file").await;
+            assert_eq!(status, Status::INVALID_ARGS);
+        });
+        scope.shutdown();
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! A file that passes through to a file of the host file system.  See [`crate::host`] for
+//! details.
+
+use crate::{
+    common::send_on_open_with_error,
+    directory::entry::{DirectoryEntry, EntryInfo},
+    execution_scope::ExecutionScope,
+    file::{
+        connection::{self, file_mode},
+        File, FileIo, FileOptions, SyncMode,
+    },
+    host::{io_status, modification_time, Root},
+    node::Node,
+    path::Path,
+};
+
+use {
+    async_trait::async_trait,
+    camino::{Utf8Path, Utf8PathBuf},
+    fiber_rust as fx,
+    fiber_status::Status,
+    midl::endpoints::ServerEnd,
+    midl_meshx_io as mio,
+    std::{
+        fs,
+        io::{Read as _, Seek as _, SeekFrom, Write as _},
+        sync::Arc,
+    },
+};
+
+/// A file of the host file system.  Instances are created by [`crate::host::HostDirectory`], when
+/// the file is opened.
+pub struct HostFile {
+    root: Arc<Root>,
+
+    /// Canonical path of this file.
+    path: Utf8PathBuf,
+}
+
+impl HostFile {
+    pub(super) fn new(root: Arc<Root>, path: Utf8PathBuf) -> Self {
+        HostFile { root, path }
+    }
+
+    /// The host path of this file.
+    pub fn path(&self) -> &Utf8Path {
+        &self.path
+    }
+
+    fn open_for_writing(&self, options: &mut fs::OpenOptions) -> Result<fs::File, Status> {
+        self.root.check_writable()?;
+        options.open(&self.path).map_err(io_status)
+    }
+}
+
+impl DirectoryEntry for HostFile {
+    fn open(
+        self: Arc<Self>,
+        scope: ExecutionScope,
+        flags: mio::OpenFlags,
+        path: Path,
+        server_end: ServerEnd<mio::NodeMarker>,
+    ) {
+        if !path.is_empty() {
+            send_on_open_with_error(flags.intersects(mio::OpenFlags::DESCRIBE), server_end, Status::NOT_DIR);
+            return;
+        }
+
+        connection::create_connection(scope, self, flags, server_end);
+    }
+
+    fn entry_info(&self) -> EntryInfo {
+        EntryInfo::new(mio::INO_UNKNOWN, mio::DirentType::File)
+    }
+}
+
+#[async_trait]
+impl Node for HostFile {
+    async fn get_attrs(&self) -> Result<mio::NodeAttributes, Status> {
+        let metadata = fs::metadata(&self.path).map_err(io_status)?;
+        Ok(mio::NodeAttributes {
+            mode: file_mode(self),
+            id: mio::INO_UNKNOWN,
+            content_size: metadata.len(),
+            storage_size: metadata.len(),
+            link_count: 1,
+            creation_time: 0,
+            modification_time: modification_time(&metadata),
+        })
+    }
+}
+
+#[async_trait]
+impl File for HostFile {
+    fn writable(&self) -> bool {
+        !self.root.read_only
+    }
+
+    async fn open_file(&self, _options: &FileOptions) -> Result<(), Status> {
+        Ok(())
+    }
+
+    async fn truncate(&self, length: u64) -> Result<(), Status> {
+        let file = self.open_for_writing(fs::OpenOptions::new().write(true))?;
+        file.set_len(length).map_err(io_status)
+    }
+
+    async fn get_backing_memory(&self, flags: mio::VmoFlags) -> Result<fx::Vmo, Status> {
+        // A shared buffer would have to be kept in sync with the host file.
+        if flags.intersects(mio::VmoFlags::WRITE) && !flags.intersects(mio::VmoFlags::PRIVATE_CLONE) {
+            return Err(Status::NOT_SUPPORTED);
+        }
+        let data = fs::read(&self.path).map_err(io_status)?;
+        let vmo = fx::Vmo::create(data.len() as u64)?;
+        vmo.write(&data, 0)?;
+        Ok(vmo)
+    }
+
+    async fn get_size(&self) -> Result<u64, Status> {
+        Ok(fs::metadata(&self.path).map_err(io_status)?.len())
+    }
+
+    async fn sync(&self, _mode: SyncMode) -> Result<(), Status> {
+        if self.root.read_only {
+            return Ok(());
+        }
+        fs::File::open(&self.path)
+            .and_then(|file| file.sync_all())
+            .map_err(io_status)
+    }
+}
+
+#[async_trait]
+impl FileIo for HostFile {
+    async fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<u64, Status> {
+        let mut file = fs::File::open(&self.path).map_err(io_status)?;
+        file.seek(SeekFrom::Start(offset)).map_err(io_status)?;
+        // A single read may return less than what is available.
+        let mut count = 0;
+        while count < buffer.len() {
+            match file.read(&mut buffer[count..]).map_err(io_status)? {
+                0 => break,
+                read => count += read,
+            }
+        }
+        Ok(count as u64)
+    }
+
+    async fn write_at(&self, offset: u64, content: &[u8]) -> Result<u64, Status> {
+        let mut file = self.open_for_writing(fs::OpenOptions::new().write(true))?;
+        file.seek(SeekFrom::Start(offset)).map_err(io_status)?;
+        file.write_all(content).map_err(io_status)?;
+        Ok(content.len() as u64)
+    }
+
+    async fn append(&self, content: &[u8]) -> Result<(u64, u64), Status> {
+        let mut file = self.open_for_writing(fs::OpenOptions::new().append(true))?;
+        file.write_all(content).map_err(io_status)?;
+        let size = file.metadata().map_err(io_status)?.len();
+        Ok((content.len() as u64, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::executor::block_on;
+
+    fn host_file(content: &str, read_only: bool) -> (tempfile::TempDir, HostFile) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8PathBuf::try_from(dir.path().canonicalize().unwrap()).unwrap();
+        let path = root.join("file");
+        fs::write(&path, content).unwrap();
+        let file = HostFile::new(Arc::new(Root { path: root, read_only }), path);
+        (dir, file)
+    }
+
+    #[test]
+    fn read_past_end_is_short() {
+        let (_dir, file) = host_file("hello", true);
+        let mut buffer = [0u8; 8];
+        assert_eq!(block_on(file.read_at(3, &mut buffer)), Ok(2));
+        assert_eq!(&buffer[..2], b"lo");
+        assert_eq!(block_on(file.read_at(10, &mut buffer)), Ok(0));
+    }
+
+    #[test]
+    fn read_only_rejects_writes() {
+        let (_dir, file) = host_file("hello", true);
+        assert_eq!(block_on(file.write_at(0, b"j")), Err(Status::ACCESS_DENIED));
+        assert_eq!(block_on(file.append(b"!")), Err(Status::ACCESS_DENIED));
+        assert_eq!(block_on(file.truncate(0)), Err(Status::ACCESS_DENIED));
+        assert_eq!(fs::read(file.path()).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn write_append_and_truncate() {
+        let (_dir, file) = host_file("ab", false);
+        assert_eq!(block_on(file.write_at(1, b"xy")), Ok(2));
+        assert_eq!(block_on(file.append(b"cd")), Ok((2, 5)));
+        assert_eq!(fs::read(file.path()).unwrap(), b"axycd");
+        assert_eq!(block_on(file.truncate(1)), Ok(()));
+        assert_eq!(block_on(file.get_size()), Ok(1));
+    }
+}
@@ -1,9 +1,16 @@
 // Copyright 2019 The Fuchsia Authors. All rights reserved.
 // Copyright 2024 MeshX Contributors. All rights reserved.
 
+pub mod common;
 pub mod path;
 pub mod name;
 pub mod node;
 pub mod directory;
+pub mod file;
 pub mod execution_scope;
+pub mod host;
+pub mod remote;
+pub mod service;
+pub mod tmpfs;
+pub mod token_registry;
 pub mod traversal_position;
\ No newline at end of file
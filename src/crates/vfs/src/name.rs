@@ -87,7 +87,7 @@ pub fn validate_name(name: &str) -> Result<(), ParseNameError> {
     if name.len() > MAX_NAME_LENGTH {
         return Err(ParseNameError::TooLong(name.to_string()));
     }
-    if name.len() == 0 {
+    if name.is_empty() {
         return Err(ParseNameError::Empty);
     }
     if name == "." {
@@ -129,7 +129,7 @@ impl Deref for Name {
 
 impl Borrow<str> for Name {
     fn borrow(&self) -> &str {
-        &*self
+        self
     }
 }
 
@@ -8,12 +8,29 @@ use std::sync::Arc;
 
 use crate::directory::entry::DirectoryEntry;
 use async_trait::async_trait;
+use fiber_status::Status;
+use midl_meshx_io as mio;
 
 /// All nodes must implement this trait.
 #[async_trait]
 pub trait Node: DirectoryEntry {
+    /// Returns node attributes (io1).
+    async fn get_attrs(&self) -> Result<mio::NodeAttributes, Status>;
+
+    /// Returns the attributes selected by `query` (io2).  The default implementation derives them
+    /// from [`Node::get_attrs`].
+    async fn get_attributes(&self, query: mio::NodeAttributesQuery) -> Result<mio::NodeAttributes2, Status> {
+        let attrs = self.get_attrs().await?;
+        Ok(attributes_from_io1(&attrs, query))
+    }
+
     /// Called when the node is closed.
     fn close(self: Arc<Self>) {}
+
+    /// Returns information about the filesystem this node belongs to.
+    fn query_filesystem(&self) -> Result<mio::FilesystemInfo, Status> {
+        Err(Status::NOT_SUPPORTED)
+    }
 }
 
 /// This struct is a RAII wrapper around a node that will call close() on it when dropped.
@@ -40,3 +57,30 @@ impl<T: Node + ?Sized> std::ops::Deref for OpenNode<T> {
         &self.node
     }
 }
+
+/// Converts io1 attributes into the io2 attributes selected by `query`.
+fn attributes_from_io1(attrs: &mio::NodeAttributes, query: mio::NodeAttributesQuery) -> mio::NodeAttributes2 {
+    let selected = |flag| query.contains(flag);
+    let protocols = match attrs.mode & mio::MODE_TYPE_MASK {
+        mio::MODE_TYPE_DIRECTORY => mio::NodeProtocolKinds::DIRECTORY,
+        mio::MODE_TYPE_FILE => mio::NodeProtocolKinds::FILE,
+        _ => mio::NodeProtocolKinds::CONNECTOR,
+    };
+    mio::NodeAttributes2 {
+        mutable_attributes: mio::MutableNodeAttributes {
+            creation_time: selected(mio::NodeAttributesQuery::CREATION_TIME).then_some(attrs.creation_time),
+            modification_time: selected(mio::NodeAttributesQuery::MODIFICATION_TIME)
+                .then_some(attrs.modification_time),
+            mode: selected(mio::NodeAttributesQuery::MODE).then_some(attrs.mode),
+            ..Default::default()
+        },
+        immutable_attributes: mio::ImmutableNodeAttributes {
+            protocols: selected(mio::NodeAttributesQuery::PROTOCOLS).then_some(protocols),
+            content_size: selected(mio::NodeAttributesQuery::CONTENT_SIZE).then_some(attrs.content_size),
+            storage_size: selected(mio::NodeAttributesQuery::STORAGE_SIZE).then_some(attrs.storage_size),
+            link_count: selected(mio::NodeAttributesQuery::LINK_COUNT).then_some(attrs.link_count),
+            id: selected(mio::NodeAttributesQuery::ID).then_some(attrs.id),
+            ..Default::default()
+        },
+    }
+}
@@ -105,15 +105,14 @@ impl Path {
     ///
     /// Also see [`Path::next_with_ref()`] if you want to use `self` while holding a reference to
     /// the returned name.
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Option<&str> {
         self.next_with_ref().1
     }
 
     /// Rust does not allow usage of `self` while the returned reference is alive, even when the
     /// reference is actually shared.  See, for example,
-    ///
-    ///     https://internals.rust-lang.org/t/relaxing-the-borrow-checker-for-fn-mut-self-t/3256
-    ///
+    /// <https://internals.rust-lang.org/t/relaxing-the-borrow-checker-for-fn-mut-self-t/3256>
     /// for additional details.  So if the caller wants to call any other methods on the `path`
     /// after calling `next()` while still holding a reference to the returned name they can use
     /// this method as a workaround.  When Rust is extended to cover this use case, `next_with_ref`
@@ -211,7 +210,7 @@ impl TryInto<Path> for &str {
 #[cfg(test)]
 mod tests {
     use super::*;
-    // use fidl_fuchsia_io as fio;
+    use midl_meshx_io as mio;
 
     macro_rules! simple_construction_test {
         (path: $str:expr, $path:ident => $body:block) => {
@@ -539,7 +538,7 @@ mod tests {
 
     /*#[test]
     fn too_long_filename() {
-        let string = "a".repeat(fio::MAX_FILENAME as usize + 1);
+        let string = "a".repeat(mio::MAX_FILENAME as usize + 1);
         negative_construction_test! {
             path: &string,
             "filename too long",
@@ -549,8 +548,8 @@ mod tests {
 
     /*#[test]
     fn too_long_path() {
-        let filename = "a".repeat(fio::MAX_FILENAME as usize);
-        const OVER_LIMIT_LENGTH: usize = fio::MAX_PATH_LENGTH as usize + 1;
+        let filename = "a".repeat(mio::MAX_FILENAME as usize);
+        const OVER_LIMIT_LENGTH: usize = mio::MAX_PATH_LENGTH as usize + 1;
         let mut path = String::new();
         while path.len() < OVER_LIMIT_LENGTH as usize {
             path.push('/');
@@ -567,7 +566,7 @@ mod tests {
     #[test]
     fn long_path() {
         #[cfg(not(target_os = "macos"))]
-        let max_path_len = fio::MAX_PATH_LENGTH as usize;
+        let max_path_len = mio::MAX_PATH_LENGTH as usize;
         #[cfg(target_os = "macos")]
         let max_path_len = libc::PATH_MAX as usize - 1;
 
@@ -587,7 +586,7 @@ mod tests {
 
     /*#[test]
     fn long_filename() {
-        let string = "a".repeat(fio::MAX_FILENAME as usize);
+        let string = "a".repeat(mio::MAX_FILENAME as usize);
         simple_construction_test! {
             path: &string,
             mut path => {
@@ -0,0 +1,197 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! A node which forwards open requests to a remote meshx.io server.  Used to graft a directory
+//! served by another component, or another filesystem, into a local tree.
+
+use crate::{
+    common::send_on_open_with_error,
+    directory::entry::{DirectoryEntry, EntryInfo},
+    execution_scope::ExecutionScope,
+    path::Path,
+};
+
+use {fiber_status::Status, midl::endpoints::ServerEnd, midl_meshx_io as mio, std::sync::Arc};
+
+/// The type for the callback function used to create new connections to the remote object. The
+/// arguments mirror [`DirectoryEntry::open`].
+pub type RoutingFn = Box<dyn Fn(ExecutionScope, mio::OpenFlags, Path, ServerEnd<mio::NodeMarker>) + Send + Sync>;
+
+/// Create a new [`Remote`] node that forwards requests to the provided [`RoutingFn`]. This routing
+/// function is called once per open request. The dirent type is set as the provided
+/// `dirent_type`, which should be one of the `DirentType` values defined in meshx.io.
+pub fn remote_boxed_with_type(open: RoutingFn, dirent_type: mio::DirentType) -> Arc<Remote> {
+    Arc::new(Remote { open, dirent_type })
+}
+
+/// Create a new [`Remote`] node that forwards open requests to the provided [`RoutingFn`]. This
+/// routing function is called once per open request. The dirent type is set to
+/// `DirentType::Unknown`. If the remote node is a known `DirentType`, use
+/// [`remote_boxed_with_type`] instead.
+pub fn remote_boxed(open: RoutingFn) -> Arc<Remote> {
+    remote_boxed_with_type(open, mio::DirentType::Unknown)
+}
+
+/// Create a new [`Remote`] node that forwards open requests to the provided callback. This routing
+/// function is called once per open request. The dirent type is set to `DirentType::Unknown`. If
+/// the remote node is a known `DirentType`, use [`remote_boxed_with_type`] instead.
+pub fn remote<Open>(open: Open) -> Arc<Remote>
+where
+    Open: Fn(ExecutionScope, mio::OpenFlags, Path, ServerEnd<mio::NodeMarker>) + Send + Sync + 'static,
+{
+    remote_boxed(Box::new(open))
+}
+
+/// Create a new [`Remote`] node that forwards open requests to the provided `DirectoryProxy`,
+/// effectively handing off the handling of any further requests to the remote fidl server.
+/// Paths are passed through as is, so the remote server resolves them relative to its own root.
+pub fn remote_dir(dir: mio::DirectoryProxy) -> Arc<Remote> {
+    remote_boxed_with_type(
+        Box::new(move |_scope, flags, path, server_end| {
+            // There is nowhere to report a failure to send the request, and the remote will close
+            // `server_end` if it can not serve it.
+            let _ = dir.open(flags, mio::ModeType::empty(), path.as_ref(), server_end);
+        }),
+        mio::DirentType::Directory,
+    )
+}
+
+/// Create a new [`Remote`] node that forwards open requests for the node itself to the provided
+/// `NodeProxy`, by cloning the remote connection.  As the remote node is not known to be a
+/// directory, opening a path inside of it fails with `Status::NOT_DIR`.
+pub fn remote_node(node: mio::NodeProxy) -> Arc<Remote> {
+    remote_boxed(Box::new(move |_scope, flags, path, server_end| {
+        if !path.is_empty() {
+            send_on_open_with_error(flags.intersects(mio::OpenFlags::DESCRIBE), server_end, Status::NOT_DIR);
+            return;
+        }
+        let _ = node.clone(flags, server_end);
+    }))
+}
+
+/// A Remote node is a node which forwards most open requests to another entity. The forwarding is
+/// done by calling a routing function of type [`RoutingFn`] provided at the time of construction.
+/// The remote node itself doesn't do any flag validation when forwarding the open call, that is
+/// left to the remote server.
+pub struct Remote {
+    open: RoutingFn,
+    dirent_type: mio::DirentType,
+}
+
+impl DirectoryEntry for Remote {
+    fn open(
+        self: Arc<Self>,
+        scope: ExecutionScope,
+        flags: mio::OpenFlags,
+        path: Path,
+        server_end: ServerEnd<mio::NodeMarker>,
+    ) {
+        (self.open)(scope, flags, path, server_end);
+    }
+
+    fn entry_info(&self) -> EntryInfo {
+        EntryInfo::new(mio::INO_UNKNOWN, self.dirent_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use {
+        futures::StreamExt as _,
+        meshx_async::TestExecutor,
+        midl::endpoints::{create_proxy, create_proxy_and_stream},
+        std::sync::Mutex,
+    };
+
+    #[test]
+    fn remote_forwards_open_as_is() {
+        fiber_host::install();
+        let _executor = TestExecutor::new();
+        let requests = Arc::new(Mutex::new(vec![]));
+        let node = remote({
+            let requests = requests.clone();
+            move |_scope, flags, path: Path, _server_end| {
+                requests.lock().unwrap().push((flags, path.into_string()));
+            }
+        });
+        assert_eq!(node.entry_info().type_(), mio::DirentType::Unknown);
+
+        let (_proxy, server_end) = create_proxy::<mio::NodeMarker>().unwrap();
+        let path = Path::validate_and_split("a/b").unwrap();
+        node.open(ExecutionScope::new(), mio::OpenFlags::RIGHT_READABLE, path, server_end);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            [(mio::OpenFlags::RIGHT_READABLE, "a/b".to_string())]
+        );
+    }
+
+    #[test]
+    fn remote_dir_sends_open_to_the_directory() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let (dir, mut requests) = create_proxy_and_stream::<mio::DirectoryMarker>().unwrap();
+        let node = remote_dir(dir);
+        assert_eq!(node.entry_info().type_(), mio::DirentType::Directory);
+
+        let (_proxy, server_end) = create_proxy::<mio::NodeMarker>().unwrap();
+        let path = Path::validate_and_split("a/b").unwrap();
+        node.open(ExecutionScope::new(), mio::OpenFlags::DESCRIBE, path, server_end);
+
+        let request = executor.run_singlethreaded(requests.next());
+        match request {
+            Some(Ok(mio::DirectoryRequest::Open { flags, path, .. })) => {
+                assert_eq!(flags, mio::OpenFlags::DESCRIBE);
+                assert_eq!(path, "a/b");
+            }
+            request => panic!("expected Open, got {request:?}"),
+        }
+    }
+
+    #[test]
+    fn remote_node_clones_the_node() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let (node_proxy, mut requests) = create_proxy_and_stream::<mio::NodeMarker>().unwrap();
+        let node = remote_node(node_proxy);
+
+        let (_proxy, server_end) = create_proxy::<mio::NodeMarker>().unwrap();
+        node.open(
+            ExecutionScope::new(),
+            mio::OpenFlags::RIGHT_READABLE,
+            Path::dot(),
+            server_end,
+        );
+
+        let request = executor.run_singlethreaded(requests.next());
+        match request {
+            Some(Ok(mio::NodeRequest::Clone { flags, .. })) => {
+                assert_eq!(flags, mio::OpenFlags::RIGHT_READABLE);
+            }
+            request => panic!("expected Clone, got {request:?}"),
+        }
+    }
+
+    #[test]
+    fn remote_node_rejects_paths() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let (node_proxy, mut requests) = create_proxy_and_stream::<mio::NodeMarker>().unwrap();
+        let node = remote_node(node_proxy);
+
+        let (proxy, server_end) = create_proxy::<mio::NodeMarker>().unwrap();
+        let path = Path::validate_and_split("child").unwrap();
+        node.clone()
+            .open(ExecutionScope::new(), mio::OpenFlags::DESCRIBE, path, server_end);
+
+        match executor.run_singlethreaded(proxy.take_event_stream().next()) {
+            Some(Ok(mio::NodeEvent::OnOpen { s, info: None })) => assert_eq!(Status::from_raw(s), Status::NOT_DIR),
+            event => panic!("expected OnOpen, got {event:?}"),
+        }
+        drop(node);
+        assert!(executor.run_singlethreaded(requests.next()).is_none());
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Implementations of a service endpoint.  Opening a service hands the channel of the connection
+//! over to a callback, which then speaks the protocol of the service on it.
+
+use crate::{
+    common::{rights_to_posix_mode_bits, send_on_open_with_error},
+    directory::entry::{DirectoryEntry, EntryInfo},
+    execution_scope::ExecutionScope,
+    node::Node,
+    path::Path,
+};
+
+use {
+    async_trait::async_trait,
+    fiber_rust as fx,
+    fiber_status::Status,
+    futures::future::Future,
+    midl::endpoints::{RequestStream, ServerEnd},
+    midl_meshx_io as mio,
+    std::sync::Arc,
+};
+
+/// Constructs a node in your file system that will host a service that implements a statically
+/// specified MIDL protocol.  `create_server` is called for every new connection with the request
+/// stream of that connection, and the returned future is run in the connection's execution scope.
+pub fn host<ServerRequestStream, CreateServer, Task>(create_server: CreateServer) -> Arc<Service>
+where
+    ServerRequestStream: RequestStream,
+    CreateServer: Fn(ServerRequestStream) -> Task + Send + Sync + 'static,
+    Task: Future<Output = ()> + Send + 'static,
+{
+    endpoint(move |scope, channel| {
+        let requests = ServerRequestStream::from_channel(channel);
+        let task = create_server(requests);
+        // There is no way to report executor failures, and if it is failing it must be shutting
+        // down.
+        scope.spawn(task);
+    })
+}
+
+/// Constructs a node in your file system that will host a service.
+///
+/// This is a lower level function than [`host()`], which you might want to use instead.
+/// `open` is called for every new connection, with the async channel of that connection.
+pub fn endpoint<Open>(open: Open) -> Arc<Service>
+where
+    Open: Fn(ExecutionScope, meshx_async::Channel) + Send + Sync + 'static,
+{
+    Arc::new(Service { open: Box::new(open) })
+}
+
+/// Represents a node in the file system that hosts a service.  Opening a connection to this node
+/// will switch to the protocol of the service, rather than the `meshx.io` protocols.
+pub struct Service {
+    open: Box<dyn Fn(ExecutionScope, meshx_async::Channel) + Send + Sync>,
+}
+
+impl DirectoryEntry for Service {
+    fn open(
+        self: Arc<Self>,
+        scope: ExecutionScope,
+        flags: mio::OpenFlags,
+        path: Path,
+        server_end: ServerEnd<mio::NodeMarker>,
+    ) {
+        let describe = flags.intersects(mio::OpenFlags::DESCRIBE);
+        if !path.is_empty() {
+            send_on_open_with_error(describe, server_end, Status::NOT_DIR);
+            return;
+        }
+
+        // A service has no content, so asking for a directory, or for rights to its content makes
+        // no sense.
+        if flags.intersects(mio::OpenFlags::DIRECTORY) {
+            send_on_open_with_error(describe, server_end, Status::NOT_DIR);
+            return;
+        }
+        if flags.intersects(mio::OpenFlags::RIGHT_EXECUTABLE | mio::OpenFlags::TRUNCATE | mio::OpenFlags::APPEND) {
+            send_on_open_with_error(describe, server_end, Status::INVALID_ARGS);
+            return;
+        }
+
+        if describe {
+            let Ok((requests, control_handle)) = server_end.into_stream_and_control_handle() else {
+                return;
+            };
+            let info = mio::NodeInfoDeprecated::Service(mio::Service);
+            if control_handle.send_on_open(Status::OK.into_raw(), Some(info)).is_err() {
+                return;
+            }
+            // Take the channel back from the request stream, so that it can be handed to the
+            // service.  Both the stream and the control handle share it.
+            drop(control_handle);
+            let (inner, _is_terminated) = requests.into_inner();
+            if let Ok(inner) = Arc::try_unwrap(inner) {
+                (self.open)(scope, inner.into_channel());
+            }
+            return;
+        }
+
+        self.open_channel(scope, server_end.into_channel());
+    }
+
+    fn entry_info(&self) -> EntryInfo {
+        EntryInfo::new(mio::INO_UNKNOWN, mio::DirentType::Service)
+    }
+}
+
+impl Service {
+    fn open_channel(&self, scope: ExecutionScope, channel: fx::Channel) {
+        match meshx_async::Channel::from_channel(channel) {
+            Ok(channel) => (self.open)(scope, channel),
+            // The connection is dropped, which is the only way to report an error to a client that
+            // did not ask for OnOpen.
+            Err(status) => tracing::error!("failed to register service channel: {status}"),
+        }
+    }
+}
+
+#[async_trait]
+impl Node for Service {
+    async fn get_attrs(&self) -> Result<mio::NodeAttributes, Status> {
+        Ok(mio::NodeAttributes {
+            mode: mio::MODE_TYPE_SERVICE | rights_to_posix_mode_bits(true, true, false),
+            id: mio::INO_UNKNOWN,
+            content_size: 0,
+            storage_size: 0,
+            link_count: 1,
+            creation_time: 0,
+            modification_time: 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use {
+        futures::StreamExt as _,
+        meshx_async::TestExecutor,
+        midl::endpoints::{create_proxy, Proxy as _},
+        std::sync::Mutex,
+    };
+
+    /// Returns a service which keeps the channels it is opened with.
+    fn recording_endpoint() -> (Arc<Service>, Arc<Mutex<Vec<meshx_async::Channel>>>) {
+        let channels = Arc::new(Mutex::new(vec![]));
+        let service = endpoint({
+            let channels = channels.clone();
+            move |_scope, channel| channels.lock().unwrap().push(channel)
+        });
+        (service, channels)
+    }
+
+    async fn on_open_status(proxy: &mio::NodeProxy) -> (Status, Option<Box<mio::NodeInfoDeprecated>>) {
+        match proxy.take_event_stream().next().await {
+            Some(Ok(mio::NodeEvent::OnOpen { s, info })) => (Status::from_raw(s), info),
+            event => panic!("expected OnOpen, got {event:?}"),
+        }
+    }
+
+    #[test]
+    fn open_hands_over_the_channel() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let (service, channels) = recording_endpoint();
+        let (proxy, server_end) = create_proxy::<mio::NodeMarker>().unwrap();
+
+        service.open(
+            ExecutionScope::new(),
+            mio::OpenFlags::RIGHT_READABLE,
+            Path::dot(),
+            server_end,
+        );
+
+        let channel = channels.lock().unwrap().pop().unwrap();
+        channel.write(b"ping", &mut []).unwrap();
+        let mut buffer = fx::MessageBuf::new();
+        let client = proxy.into_channel().unwrap();
+        executor.run_singlethreaded(client.recv_msg(&mut buffer)).unwrap();
+        assert_eq!(buffer.bytes(), b"ping");
+    }
+
+    #[test]
+    fn open_with_describe_sends_on_open_first() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let (service, channels) = recording_endpoint();
+        let (proxy, server_end) = create_proxy::<mio::NodeMarker>().unwrap();
+
+        service.open(ExecutionScope::new(), mio::OpenFlags::DESCRIBE, Path::dot(), server_end);
+
+        let (status, info) = executor.run_singlethreaded(on_open_status(&proxy));
+        assert_eq!(status, Status::OK);
+        assert_eq!(info.as_deref(), Some(&mio::NodeInfoDeprecated::Service(mio::Service)));
+        assert_eq!(channels.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn open_rejects_invalid_requests() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let (service, channels) = recording_endpoint();
+
+        for (flags, path, expected) in [
+            (mio::OpenFlags::empty(), "child", Status::NOT_DIR),
+            (mio::OpenFlags::DIRECTORY, ".", Status::NOT_DIR),
+            (mio::OpenFlags::TRUNCATE, ".", Status::INVALID_ARGS),
+            (mio::OpenFlags::RIGHT_EXECUTABLE, ".", Status::INVALID_ARGS),
+        ] {
+            let (proxy, server_end) = create_proxy::<mio::NodeMarker>().unwrap();
+            let path = Path::validate_and_split(path).unwrap();
+            service.clone().open(
+                ExecutionScope::new(),
+                flags | mio::OpenFlags::DESCRIBE,
+                path,
+                server_end,
+            );
+            assert_eq!(executor.run_singlethreaded(on_open_status(&proxy)), (expected, None));
+        }
+        assert!(channels.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn host_serves_the_protocol() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let service = host(|mut requests: mio::NodeRequestStream| async move {
+            while let Some(Ok(request)) = requests.next().await {
+                if let mio::NodeRequest::GetAttr { responder } = request {
+                    let attrs = mio::NodeAttributes {
+                        mode: 0,
+                        id: 7,
+                        content_size: 0,
+                        storage_size: 0,
+                        link_count: 1,
+                        creation_time: 0,
+                        modification_time: 0,
+                    };
+                    responder.send(Status::OK.into_raw(), &attrs).unwrap();
+                }
+            }
+        });
+        let scope = ExecutionScope::new();
+        let (proxy, server_end) = create_proxy::<mio::NodeMarker>().unwrap();
+
+        let (status, attrs) = executor.run_singlethreaded(async {
+            service.open(scope.clone(), mio::OpenFlags::empty(), Path::dot(), server_end);
+            proxy.get_attr().await.unwrap()
+        });
+        assert_eq!(Status::from_raw(status), Status::OK);
+        assert_eq!(attrs.id, 7);
+        scope.shutdown();
+    }
+
+    #[test]
+    fn entry_info_and_attributes() {
+        let (service, _channels) = recording_endpoint();
+        assert_eq!(service.entry_info().type_(), mio::DirentType::Service);
+        let attrs = futures::executor::block_on(service.get_attrs()).unwrap();
+        assert_eq!(attrs.mode & mio::MODE_TYPE_MASK, mio::MODE_TYPE_SERVICE);
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! An in-memory file system, built from mutable "simple" directories and writable "simple" files.
+//! Clients can create, remove, rename and link files and directories anywhere in the tree.
+//!
+//! ## Examples
+//! ```
+//! # use {midl::endpoints::ServerEnd, midl_meshx_io as mio, vfs::tmpfs::Tmpfs};
+//! # fn serve(server_end: ServerEnd<mio::NodeMarker>) {
+//! let tmpfs = Tmpfs::new();
+//! tmpfs.serve(mio::OpenFlags::RIGHT_READABLE | mio::OpenFlags::RIGHT_WRITABLE, server_end);
+//! # }
+//! ```
+
+use crate::{
+    directory::{
+        entry::DirectoryEntry,
+        mutable::{
+            self,
+            entry_constructor::{EntryConstructor, NewEntryType},
+        },
+    },
+    execution_scope::ExecutionScope,
+    file,
+    path::Path,
+};
+
+use {fiber_status::Status, midl::endpoints::ServerEnd, midl_meshx_io as mio, std::sync::Arc};
+
+/// Creates empty directories and files for [`Tmpfs`].  Only the last component of a path can be
+/// created.
+pub struct TmpfsEntryConstructor;
+
+impl EntryConstructor for TmpfsEntryConstructor {
+    fn create_entry(
+        self: Arc<Self>,
+        _parent: Arc<dyn DirectoryEntry>,
+        what: NewEntryType,
+        _name: &str,
+        path: &Path,
+    ) -> Result<Arc<dyn DirectoryEntry>, Status> {
+        if !path.is_empty() {
+            return Err(Status::NOT_FOUND);
+        }
+        Ok(match what {
+            NewEntryType::Directory => mutable::simple(),
+            NewEntryType::File => file::read_write(b""),
+        })
+    }
+}
+
+/// An in-memory file system.  The content lives as long as the `Tmpfs`, or any of the connections
+/// to it.
+pub struct Tmpfs {
+    root: Arc<mutable::Simple>,
+    scope: ExecutionScope,
+}
+
+impl Tmpfs {
+    /// Creates an empty file system.
+    pub fn new() -> Self {
+        Self {
+            root: mutable::simple(),
+            scope: ExecutionScope::builder()
+                .entry_constructor(Arc::new(TmpfsEntryConstructor))
+                .build(),
+        }
+    }
+
+    /// The root directory.  The server can use it to populate the file system directly.
+    pub fn root(&self) -> &Arc<mutable::Simple> {
+        &self.root
+    }
+
+    /// The scope serving all the connections to the file system.
+    pub fn scope(&self) -> &ExecutionScope {
+        &self.scope
+    }
+
+    /// Opens a connection to the root directory.
+    pub fn serve(&self, flags: mio::OpenFlags, server_end: ServerEnd<mio::NodeMarker>) {
+        self.root
+            .clone()
+            .open(self.scope.clone(), flags, Path::dot(), server_end);
+    }
+}
+
+impl Default for Tmpfs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::directory::helper::DirectlyMutable as _;
+
+    use {futures::StreamExt as _, meshx_async::TestExecutor, midl::endpoints::create_proxy};
+
+    const READ_WRITE: mio::OpenFlags = mio::OpenFlags::RIGHT_READABLE.union(mio::OpenFlags::RIGHT_WRITABLE);
+
+    fn serve(tmpfs: &Tmpfs) -> mio::DirectoryProxy {
+        let (proxy, server_end) = create_proxy::<mio::DirectoryMarker>().unwrap();
+        tmpfs.serve(READ_WRITE, ServerEnd::new(server_end.into_channel()));
+        proxy
+    }
+
+    /// Opens `path` in `dir`, and returns the status `OnOpen` reports along with the connection.
+    async fn open(dir: &mio::DirectoryProxy, flags: mio::OpenFlags, path: &str) -> (Status, mio::DirectoryProxy) {
+        let (proxy, server_end) = create_proxy::<mio::DirectoryMarker>().unwrap();
+        let flags = flags | mio::OpenFlags::DESCRIBE;
+        dir.open(
+            flags,
+            mio::ModeType::empty(),
+            path,
+            ServerEnd::new(server_end.into_channel()),
+        )
+        .unwrap();
+        let status = match proxy.take_event_stream().next().await {
+            Some(Ok(mio::DirectoryEvent::OnOpen { s, .. })) => Status::from_raw(s),
+            event => panic!("expected OnOpen, got {event:?}"),
+        };
+        (status, proxy)
+    }
+
+    async fn token(dir: &mio::DirectoryProxy) -> midl::Handle {
+        let (status, token) = dir.get_token().await.unwrap();
+        assert_eq!(Status::from_raw(status), Status::OK);
+        token.unwrap()
+    }
+
+    fn unlink_options(must_be_directory: bool) -> mio::UnlinkOptions {
+        mio::UnlinkOptions {
+            flags: must_be_directory.then_some(mio::UnlinkFlags::MUST_BE_DIRECTORY),
+            ..Default::default()
+        }
+    }
+
+    fn entry_names(dir: &Arc<mutable::Simple>) -> Vec<String> {
+        let mut names = vec![];
+        for name in ["dir", "file", "moved", "linked"] {
+            if dir.get_entry(name).is_ok() {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+
+    #[test]
+    fn create_and_unlink() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let tmpfs = Tmpfs::new();
+        let root = serve(&tmpfs);
+
+        executor.run_singlethreaded(async {
+            let create_dir = READ_WRITE | mio::OpenFlags::CREATE | mio::OpenFlags::DIRECTORY;
+            let (status, dir) = open(&root, create_dir, "dir").await;
+            assert_eq!(status, Status::OK);
+            let (status, _) = open(&dir, READ_WRITE | mio::OpenFlags::CREATE, "file").await;
+            assert_eq!(status, Status::OK);
+            let (status, _) = open(&root, READ_WRITE | mio::OpenFlags::CREATE_IF_ABSENT, "dir").await;
+            assert_eq!(status, Status::ALREADY_EXISTS);
+            assert_eq!(entry_names(tmpfs.root()), ["dir"]);
+
+            assert_eq!(
+                root.unlink("dir", &unlink_options(false)).await.unwrap(),
+                Err(Status::NOT_EMPTY.into_raw())
+            );
+            assert_eq!(
+                dir.unlink("file", &unlink_options(true)).await.unwrap(),
+                Err(Status::NOT_DIR.into_raw())
+            );
+            assert_eq!(dir.unlink("file", &unlink_options(false)).await.unwrap(), Ok(()));
+            assert_eq!(root.unlink("dir", &unlink_options(true)).await.unwrap(), Ok(()));
+            assert_eq!(
+                root.unlink("dir", &unlink_options(false)).await.unwrap(),
+                Err(Status::NOT_FOUND.into_raw())
+            );
+        });
+        assert!(entry_names(tmpfs.root()).is_empty());
+    }
+
+    #[test]
+    fn rename_and_link_across_directories() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let tmpfs = Tmpfs::new();
+        let root = serve(&tmpfs);
+
+        executor.run_singlethreaded(async {
+            let create_dir = READ_WRITE | mio::OpenFlags::CREATE | mio::OpenFlags::DIRECTORY;
+            let (_, dir) = open(&root, create_dir, "dir").await;
+            let (status, _) = open(&root, READ_WRITE | mio::OpenFlags::CREATE, "file").await;
+            assert_eq!(status, Status::OK);
+
+            let dir_token = token(&dir).await;
+            assert_eq!(root.rename("file", dir_token, "moved").await.unwrap(), Ok(()));
+            let root_token = token(&root).await;
+            assert_eq!(
+                Status::from_raw(dir.link("moved", root_token, "linked").await.unwrap()),
+                Status::OK
+            );
+            let root_token = token(&root).await;
+            assert_eq!(
+                Status::from_raw(dir.link("missing", root_token, "linked").await.unwrap()),
+                Status::NOT_FOUND
+            );
+        });
+
+        assert_eq!(entry_names(tmpfs.root()), ["dir", "linked"]);
+        let dir = tmpfs
+            .root()
+            .get_entry("dir")
+            .unwrap()
+            .into_any()
+            .downcast::<mutable::Simple>()
+            .unwrap();
+        assert_eq!(entry_names(&dir), ["moved"]);
+    }
+
+    #[test]
+    fn read_only_connections_can_not_modify() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let tmpfs = Tmpfs::new();
+        tmpfs.root().add_entry("file", file::read_write(b"")).unwrap();
+        let root = serve(&tmpfs);
+
+        executor.run_singlethreaded(async {
+            let (status, read_only) = open(&root, mio::OpenFlags::RIGHT_READABLE, ".").await;
+            assert_eq!(status, Status::OK);
+            assert_eq!(
+                read_only.unlink("file", &unlink_options(false)).await.unwrap(),
+                Err(Status::BAD_HANDLE.into_raw())
+            );
+            let (status, token) = read_only.get_token().await.unwrap();
+            assert_eq!((Status::from_raw(status), token), (Status::BAD_HANDLE, None));
+            let (status, _) = open(
+                &read_only,
+                mio::OpenFlags::RIGHT_READABLE | mio::OpenFlags::CREATE,
+                "new",
+            )
+            .await;
+            assert_eq!(status, Status::ACCESS_DENIED);
+        });
+        assert_eq!(entry_names(tmpfs.root()), ["file"]);
+    }
+}
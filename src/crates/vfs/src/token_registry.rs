@@ -0,0 +1,83 @@
+// Copyright 2019 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Implementation of [`TokenRegistry`].  Tokens identify a directory in the `Rename` and `Link`
+//! requests, which move entries between two directories that may be served over different
+//! connections.
+
+use crate::directory::entry_container::MutableDirectory;
+
+use {
+    fiber_rust::{self as fx, AsHandleRef as _, HandleBased as _, Koid, Rights},
+    fiber_status::Status,
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex, Weak},
+    },
+};
+
+/// The rights a token handed out to clients has.  Clients can only pass it back to us.
+const TOKEN_RIGHTS: Rights = Rights::TRANSFER.union(Rights::DUPLICATE).union(Rights::INSPECT);
+
+/// Hands out tokens for directories, and maps the tokens back to the directories.
+#[derive(Default)]
+pub struct TokenRegistry {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// Maps the address of an owner to the kernel object backing its token.  We keep the object
+    /// alive, so that its koid is not reused while the owner is registered.
+    owner_to_token: HashMap<usize, fx::Port>,
+
+    /// Maps the koid of a token to its owner.
+    token_to_owner: HashMap<Koid, (usize, Weak<dyn MutableDirectory>)>,
+}
+
+impl TokenRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a token for `owner`.  All the tokens returned for the same owner refer to the same
+    /// kernel object.
+    pub fn get_token(&self, owner: Arc<dyn MutableDirectory>) -> Result<fx::Handle, Status> {
+        let key = Arc::as_ptr(&owner) as *const () as usize;
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(token) = inner.owner_to_token.get(&key) {
+            return Ok(token.duplicate_handle(TOKEN_RIGHTS)?.into_handle());
+        }
+
+        // The port is never used: the token only needs a koid.  Unlike channels, ports can be
+        // duplicated.
+        let token = fx::Port::create();
+        let koid = token.get_koid()?;
+        let handle = token.duplicate_handle(TOKEN_RIGHTS)?.into_handle();
+        inner.owner_to_token.insert(key, token);
+        inner.token_to_owner.insert(koid, (key, Arc::downgrade(&owner)));
+        Ok(handle)
+    }
+
+    /// Returns the owner of `token`, or `None` if the token was not handed out by this registry,
+    /// or its owner no longer exists.
+    pub fn get_owner(&self, token: fx::Handle) -> Result<Option<Arc<dyn MutableDirectory>>, Status> {
+        let koid = token.get_koid()?;
+        let mut inner = self.inner.lock().unwrap();
+        let Some((key, owner)) = inner.token_to_owner.get(&koid) else {
+            return Ok(None);
+        };
+        match owner.upgrade() {
+            Some(owner) => Ok(Some(owner)),
+            None => {
+                // The owner is gone, forget about the token.
+                let key = *key;
+                inner.token_to_owner.remove(&koid);
+                inner.owner_to_token.remove(&key);
+                Ok(None)
+            }
+        }
+    }
+}
@@ -11,8 +11,8 @@ use {
     meshx_hash::Hash,
 };
 
-/// A URL locating a Fuchsia package.
-/// Has the form "fuchsia-pkg://<repository>/<name>[/variant][?hash=<hash>]" where:
+/// A URL locating a MeshX package.
+/// Has the form "mxpkg://<repository>/<name>[/variant][?hash=<hash>]" where:
 ///   * "repository" is a valid hostname
 ///   * "name" is a valid package name
 ///   * "variant" is an optional valid package variant
@@ -37,7 +37,7 @@ impl AbsolutePackageUrl {
         Self::new_with_path(repo, &path, hash)
     }
 
-    /// Parse a "fuchsia-pkg://" URL that locates an optionally pinned package.
+    /// Parse a "mxpkg://" URL that locates an optionally pinned package.
     pub fn parse(url: &str) -> Result<Self, ParseError> {
         Self::from_parts(UrlParts::parse(url)?)
     }
@@ -186,15 +186,15 @@ mod tests {
             ("/name", ParseError::MissingScheme),
             ("name", ParseError::MissingScheme),
             ("fuchsia-boot://example.org/name", ParseError::InvalidScheme),
-            ("fuchsia-pkg://", ParseError::MissingHost),
-            ("fuchsia-pkg://exaMple.org", ParseError::InvalidHost),
-            ("fuchsia-pkg://example.org/", ParseError::MissingName),
+            ("mxpkg://", ParseError::MissingHost),
+            ("mxpkg://exaMple.org", ParseError::InvalidHost),
+            ("mxpkg://example.org/", ParseError::MissingName),
             (
-                "fuchsia-pkg://example.org//",
+                "mxpkg://example.org//",
                 ParseError::InvalidPathSegment(PackagePathSegmentError::Empty),
             ),
-            ("fuchsia-pkg://example.org/name/variant/extra", ParseError::ExtraPathSegments),
-            ("fuchsia-pkg://example.org/name#resource", ParseError::CannotContainResource),
+            ("mxpkg://example.org/name/variant/extra", ParseError::ExtraPathSegments),
+            ("mxpkg://example.org/name#resource", ParseError::CannotContainResource),
         ] {
             assert_matches!(
                 AbsolutePackageUrl::parse(url),
@@ -223,11 +223,11 @@ mod tests {
     #[test]
     fn parse_ok() {
         for (url, host, name, variant, hash) in [
-            ("fuchsia-pkg://example.org/name", "example.org", "name", None, None),
-            ("fuchsia-pkg://example.org/name/variant", "example.org", "name", Some("variant"), None),
+            ("mxpkg://example.org/name", "example.org", "name", None, None),
+            ("mxpkg://example.org/name/variant", "example.org", "name", Some("variant"), None),
             (
-            "fuchsia-pkg://example.org/name?hash=0000000000000000000000000000000000000000000000000000000000000000", "example.org", "name", None, Some("0000000000000000000000000000000000000000000000000000000000000000")),
-            ("fuchsia-pkg://example.org/name/variant?hash=0000000000000000000000000000000000000000000000000000000000000000", "example.org", "name", Some("variant"), Some("0000000000000000000000000000000000000000000000000000000000000000")),
+            "mxpkg://example.org/name?hash=0000000000000000000000000000000000000000000000000000000000000000", "example.org", "name", None, Some("0000000000000000000000000000000000000000000000000000000000000000")),
+            ("mxpkg://example.org/name/variant?hash=0000000000000000000000000000000000000000000000000000000000000000", "example.org", "name", Some("variant"), Some("0000000000000000000000000000000000000000000000000000000000000000")),
         ] {
             let json_url = format!("\"{url}\"");
 
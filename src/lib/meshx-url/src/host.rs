@@ -4,7 +4,7 @@
 
 use crate::errors::ParseError;
 
-// The host of a fuchsia-pkg:// URL.
+// The host of a mxpkg:// URL.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) struct Host(String);
 
@@ -54,7 +54,7 @@ lazy_static! {
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum Scheme {
     // Builtin,
-    FuchsiaPkg,
+    MxPkg,
     // FuchsiaBoot,
 }
 
@@ -75,7 +75,7 @@ impl UrlParts {
             Ok(url) => (
                 Some(match url.scheme() {
                     // builtin_url::SCHEME => Scheme::Builtin,
-                    repository_url::SCHEME => Scheme::FuchsiaPkg,
+                    repository_url::SCHEME => Scheme::MxPkg,
                     // boot_url::SCHEME => Scheme::FuchsiaBoot,
                     _ => return Err(ParseError::InvalidScheme),
                 }),
@@ -148,7 +148,7 @@ fn parse_query_pairs(pairs: url::form_urlencoded::Parse<'_>) -> Result<Option<Ha
                 return Err(ParseError::MultipleHashes);
             }
             query_hash = Some(value.parse().map_err(ParseError::InvalidHash)?);
-            // fuchsia-pkg URLs require lowercase hex characters, but fuchsia_hash::Hash::parse
+            // mxpkg URLs require lowercase hex characters, but fuchsia_hash::Hash::parse
             // accepts uppercase A-F.
             if !value.bytes().all(|b| (b >= b'0' && b <= b'9') || (b >= b'a' && b <= b'f')) {
                 return Err(ParseError::UpperCaseHash);
@@ -418,82 +418,82 @@ mod test_url_parts {
             err = ParseError::InvalidScheme,
         }
         err_port => {
-            url = "fuchsia-pkg://example.org:1",
+            url = "mxpkg://example.org:1",
             err = ParseError::CannotContainPort,
         }
         err_username => {
-            url = "fuchsia-pkg://user@example.org",
+            url = "mxpkg://user@example.org",
             err = ParseError::CannotContainUsername,
         }
         err_password => {
-            url = "fuchsia-pkg://:password@example.org",
+            url = "mxpkg://:password@example.org",
             err = ParseError::CannotContainPassword,
         }
         err_invalid_host => {
-            url = "fuchsia-pkg://exa$mple.org",
+            url = "mxpkg://exa$mple.org",
             err = ParseError::InvalidHost,
         }
         // Path validation covered by test_validate_path, this just checks that the path is
         // validated at all.
         err_invalid_path => {
-            url = "fuchsia-pkg://example.org//",
+            url = "mxpkg://example.org//",
             err = ParseError::InvalidPathSegment(_),
         }
         err_empty_hash => {
-            url = "fuchsia-pkg://example.org/?hash=",
+            url = "mxpkg://example.org/?hash=",
             err = ParseError::InvalidHash(_),
         }
         err_invalid_hash => {
-            url = "fuchsia-pkg://example.org/?hash=INVALID_HASH",
+            url = "mxpkg://example.org/?hash=INVALID_HASH",
             err = ParseError::InvalidHash(_),
         }
         err_uppercase_hash => {
-            url = "fuchsia-pkg://example.org/?hash=A000000000000000000000000000000000000000000000000000000000000000",
+            url = "mxpkg://example.org/?hash=A000000000000000000000000000000000000000000000000000000000000000",
             err = ParseError::UpperCaseHash,
         }
         err_hash_too_long => {
-            url = "fuchsia-pkg://example.org/?hash=00000000000000000000000000000000000000000000000000000000000000001",
+            url = "mxpkg://example.org/?hash=00000000000000000000000000000000000000000000000000000000000000001",
             err = ParseError::InvalidHash(_),
         }
         err_hash_too_short => {
-            url = "fuchsia-pkg://example.org/?hash=000000000000000000000000000000000000000000000000000000000000000",
+            url = "mxpkg://example.org/?hash=000000000000000000000000000000000000000000000000000000000000000",
             err = ParseError::InvalidHash(_),
         }
         err_multiple_hashes => {
-            url = "fuchsia-pkg://example.org/?hash=0000000000000000000000000000000000000000000000000000000000000000&\
+            url = "mxpkg://example.org/?hash=0000000000000000000000000000000000000000000000000000000000000000&\
             hash=0000000000000000000000000000000000000000000000000000000000000000",
             err = ParseError::MultipleHashes,
         }
         err_non_hash_query_parameter => {
-            url = "fuchsia-pkg://example.org/?invalid-key=invalid-value",
+            url = "mxpkg://example.org/?invalid-key=invalid-value",
             err = ParseError::ExtraQueryParameters,
         }
         err_resource_slash => {
-            url = "fuchsia-pkg://example.org/name#/",
+            url = "mxpkg://example.org/name#/",
             err = ParseError::InvalidResourcePath(ResourcePathError::PathStartsWithSlash),
         }
         err_resource_leading_slash => {
-            url = "fuchsia-pkg://example.org/name#/resource",
+            url = "mxpkg://example.org/name#/resource",
             err = ParseError::InvalidResourcePath(ResourcePathError::PathStartsWithSlash),
         }
         err_resource_trailing_slash => {
-            url = "fuchsia-pkg://example.org/name#resource/",
+            url = "mxpkg://example.org/name#resource/",
             err = ParseError::InvalidResourcePath(ResourcePathError::PathEndsWithSlash),
         }
         err_resource_empty_segment => {
-            url = "fuchsia-pkg://example.org/name#resource//other",
+            url = "mxpkg://example.org/name#resource//other",
             err = ParseError::InvalidResourcePath(ResourcePathError::NameEmpty),
         }
         err_resource_bad_segment => {
-            url = "fuchsia-pkg://example.org/name#resource/./other",
+            url = "mxpkg://example.org/name#resource/./other",
             err = ParseError::InvalidResourcePath(ResourcePathError::NameIsDot),
         }
         err_resource_percent_encoded_null => {
-            url = "fuchsia-pkg://example.org/name#resource%00",
+            url = "mxpkg://example.org/name#resource%00",
             err = ParseError::InvalidResourcePath(ResourcePathError::NameContainsNull),
         }
         err_resource_unencoded_null => {
-            url =  "fuchsia-pkg://example.org/name#reso\x00urce",
+            url =  "mxpkg://example.org/name#reso\x00urce",
             err = ParseError::InvalidResourcePath(ResourcePathError::NameContainsNull),
         }
     }
@@ -531,8 +531,8 @@ mod test_url_parts {
 
     test_parse_ok! {
         ok_fuchsia_pkg_scheme => {
-            url =  "fuchsia-pkg://",
-            scheme = Some(Scheme::FuchsiaPkg),
+            url =  "mxpkg://",
+            scheme = Some(Scheme::MxPkg),
             host = None,
             path = "/",
             hash = None,
@@ -547,32 +547,32 @@ mod test_url_parts {
             resource = None,
         }*/
         ok_host => {
-            url =  "fuchsia-pkg://example.org",
-            scheme = Some(Scheme::FuchsiaPkg),
+            url =  "mxpkg://example.org",
+            scheme = Some(Scheme::MxPkg),
             host = Some(Host::parse("example.org".into()).unwrap()),
             path = "/",
             hash = None,
             resource = None,
         }
         ok_path_single_segment => {
-            url =  "fuchsia-pkg:///name",
-            scheme = Some(Scheme::FuchsiaPkg),
+            url =  "mxpkg:///name",
+            scheme = Some(Scheme::MxPkg),
             host = None,
             path = "/name",
             hash = None,
             resource = None,
         }
         ok_path_multiple_segment => {
-            url =  "fuchsia-pkg:///name/variant/other",
-            scheme = Some(Scheme::FuchsiaPkg),
+            url =  "mxpkg:///name/variant/other",
+            scheme = Some(Scheme::MxPkg),
             host = None,
             path = "/name/variant/other",
             hash = None,
             resource = None,
         }
         ok_hash => {
-            url =  "fuchsia-pkg://?hash=0000000000000000000000000000000000000000000000000000000000000000",
-            scheme = Some(Scheme::FuchsiaPkg),
+            url =  "mxpkg://?hash=0000000000000000000000000000000000000000000000000000000000000000",
+            scheme = Some(Scheme::MxPkg),
             host = None,
             path = "/",
             hash = Some(
@@ -581,34 +581,34 @@ mod test_url_parts {
             resource = None,
         }
         ok_resource_single_segment => {
-            url =  "fuchsia-pkg://#resource",
-            scheme = Some(Scheme::FuchsiaPkg),
+            url =  "mxpkg://#resource",
+            scheme = Some(Scheme::MxPkg),
             host = None,
             path = "/",
             hash = None,
             resource = Some("resource".into()),
         }
         ok_resource_multiple_segment => {
-            url =  "fuchsia-pkg://#resource/again/third",
-            scheme = Some(Scheme::FuchsiaPkg),
+            url =  "mxpkg://#resource/again/third",
+            scheme = Some(Scheme::MxPkg),
             host = None,
             path = "/",
             hash = None,
             resource = Some("resource/again/third".into()),
         }
         ok_resource_encoded_control_character => {
-            url =  "fuchsia-pkg://#reso%09urce",
-            scheme = Some(Scheme::FuchsiaPkg),
+            url =  "mxpkg://#reso%09urce",
+            scheme = Some(Scheme::MxPkg),
             host = None,
             path = "/",
             hash = None,
             resource = Some("reso\turce".into()),
         }
         ok_all_fields => {
-            url =  "fuchsia-pkg://example.org/name\
+            url =  "mxpkg://example.org/name\
             ?hash=0000000000000000000000000000000000000000000000000000000000000000\
             #resource",
-            scheme = Some(Scheme::FuchsiaPkg),
+            scheme = Some(Scheme::MxPkg),
             host = Some(Host::parse("example.org".into()).unwrap()),
             path = "/name",
             hash = Some(
@@ -4,7 +4,7 @@
 
 use crate::{errors::ParseError, AbsolutePackageUrl, RelativePackageUrl, UrlParts};
 
-/// A URL locating a Fuchsia package. Can be either absolute or relative.
+/// A URL locating a MeshX package. Can be either absolute or relative.
 /// See `AbsolutePackageUrl` and `RelativePackageUrl` for more details.
 /// https://fuchsia.dev/fuchsia-src/concepts/packages/package_url
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -86,12 +86,12 @@ mod tests {
     fn parse_err() {
         for url in [
             "fuchsia-boot://example.org/name",
-            "fuchsia-pkg://",
-            "fuchsia-pkg://example.org/",
-            "fuchsia-pkg://example.org//",
-            "fuchsia-pkg://exaMple.org/name",
-            "fuchsia-pkg:///name",
-            "fuchsia-pkg://name",
+            "mxpkg://",
+            "mxpkg://example.org/",
+            "mxpkg://example.org//",
+            "mxpkg://exaMple.org/name",
+            "mxpkg:///name",
+            "mxpkg://name",
             "example.org/name",
             "name/variant",
             "name#resource",
@@ -107,10 +107,10 @@ mod tests {
     #[test]
     fn parse_ok_absolute() {
         for url in [
-            "fuchsia-pkg://example.org/name",
-            "fuchsia-pkg://example.org/name/variant",
-            "fuchsia-pkg://example.org/name?hash=0000000000000000000000000000000000000000000000000000000000000000",
-            "fuchsia-pkg://example.org/name/variant?hash=0000000000000000000000000000000000000000000000000000000000000000",
+            "mxpkg://example.org/name",
+            "mxpkg://example.org/name/variant",
+            "mxpkg://example.org/name?hash=0000000000000000000000000000000000000000000000000000000000000000",
+            "mxpkg://example.org/name/variant?hash=0000000000000000000000000000000000000000000000000000000000000000",
         ] {
             let json_url = format!("\"{url}\"");
             let validate = |parsed: &PackageUrl| {
@@ -11,8 +11,8 @@ use {
     meshx_hash::Hash,
 };
 
-/// A URL locating a Fuchsia package. Must have a hash.
-/// Has the form "fuchsia-pkg://<repository>/<name>[/variant]?hash=<hash>" where:
+/// A URL locating a MeshX package. Must have a hash.
+/// Has the form "mxpkg://<repository>/<name>[/variant]?hash=<hash>" where:
 ///   * "repository" is a valid hostname
 ///   * "name" is a valid package name
 ///   * "variant" is an optional valid package variant
@@ -42,7 +42,7 @@ impl PinnedAbsolutePackageUrl {
         })
     }
 
-    /// Parse a "fuchsia-pkg://" URL that locates a pinned (has a hash query parameter) package.
+    /// Parse a "mxpkg://" URL that locates a pinned (has a hash query parameter) package.
     pub fn parse(url: &str) -> Result<Self, ParseError> {
         match AbsolutePackageUrl::parse(url)? {
             AbsolutePackageUrl::Unpinned(_) => Err(ParseError::MissingHash),
@@ -144,15 +144,15 @@ mod tests {
     fn parse_err() {
         for (url, err) in [
             ("fuchsia-boot://example.org/name?hash=0000000000000000000000000000000000000000000000000000000000000000", ParseError::InvalidScheme),
-            ("fuchsia-pkg://?hash=0000000000000000000000000000000000000000000000000000000000000000", ParseError::MissingHost),
-            ("fuchsia-pkg://exaMple.org?hash=0000000000000000000000000000000000000000000000000000000000000000", ParseError::InvalidHost),
-            ("fuchsia-pkg://example.org/?hash=0000000000000000000000000000000000000000000000000000000000000000", ParseError::MissingName),
+            ("mxpkg://?hash=0000000000000000000000000000000000000000000000000000000000000000", ParseError::MissingHost),
+            ("mxpkg://exaMple.org?hash=0000000000000000000000000000000000000000000000000000000000000000", ParseError::InvalidHost),
+            ("mxpkg://example.org/?hash=0000000000000000000000000000000000000000000000000000000000000000", ParseError::MissingName),
             (
-                "fuchsia-pkg://example.org//?hash=0000000000000000000000000000000000000000000000000000000000000000",
+                "mxpkg://example.org//?hash=0000000000000000000000000000000000000000000000000000000000000000",
                 ParseError::InvalidPathSegment(PackagePathSegmentError::Empty),
             ),
-            ("fuchsia-pkg://example.org/name/variant/extra?hash=0000000000000000000000000000000000000000000000000000000000000000", ParseError::ExtraPathSegments),
-            ("fuchsia-pkg://example.org/name?hash=0000000000000000000000000000000000000000000000000000000000000000#resource", ParseError::CannotContainResource),
+            ("mxpkg://example.org/name/variant/extra?hash=0000000000000000000000000000000000000000000000000000000000000000", ParseError::ExtraPathSegments),
+            ("mxpkg://example.org/name?hash=0000000000000000000000000000000000000000000000000000000000000000#resource", ParseError::CannotContainResource),
 
         ] {
             assert_matches!(
@@ -182,9 +182,9 @@ mod tests {
     #[test]
     fn parse_ok() {
         for (url, variant, path) in [
-            ("fuchsia-pkg://example.org/name?hash=0000000000000000000000000000000000000000000000000000000000000000", None, "/name"),
+            ("mxpkg://example.org/name?hash=0000000000000000000000000000000000000000000000000000000000000000", None, "/name"),
             (
-                "fuchsia-pkg://example.org/name/variant?hash=0000000000000000000000000000000000000000000000000000000000000000",
+                "mxpkg://example.org/name/variant?hash=0000000000000000000000000000000000000000000000000000000000000000",
                 Some("variant"),
                 "/name/variant",
             ),
@@ -120,16 +120,16 @@ mod tests {
     fn parse_err() {
         for (url, err) in [
             //("fuchsia-boot://example.org/name", ParseError::CannotContainScheme),
-            ("fuchsia-pkg://", ParseError::CannotContainScheme),
-            ("fuchsia-pkg://name", ParseError::CannotContainScheme),
-            ("fuchsia-pkg:///name", ParseError::CannotContainScheme),
+            ("mxpkg://", ParseError::CannotContainScheme),
+            ("mxpkg://name", ParseError::CannotContainScheme),
+            ("mxpkg:///name", ParseError::CannotContainScheme),
             ("//example.org/name", ParseError::HostMustBeEmpty),
             (
                 "///name",
                 ParseError::InvalidRelativePath("///name".to_string(), Some("name".to_string())),
             ),
             ("example.org/name", ParseError::RelativePathCannotSpecifyVariant),
-            ("fuchsia-pkg://example.org/name", ParseError::CannotContainScheme),
+            ("mxpkg://example.org/name", ParseError::CannotContainScheme),
             ("name/variant", ParseError::RelativePathCannotSpecifyVariant),
             ("name#resource", ParseError::CannotContainResource),
             (
@@ -4,10 +4,10 @@
 
 use crate::{errors::ParseError, Host, Scheme, UrlParts};
 
-pub const SCHEME: &str = "fuchsia-pkg";
+pub const SCHEME: &str = "mxpkg";
 
-/// A URL locating a Fuchsia package repository.
-/// Has the form "fuchsia-pkg://<repository>", where "repository" is a valid hostname.
+/// A URL locating a MeshX package repository.
+/// Has the form "mxpkg://<repository>", where "repository" is a valid hostname.
 /// https://fuchsia.dev/fuchsia-src/concepts/packages/package_url?hl=en#repository
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct RepositoryUrl {
@@ -22,7 +22,7 @@ impl RepositoryUrl {
         Ok(Self { host: Host::parse(host)? })
     }
 
-    /// Parse a "fuchsia-pkg://" URL that locates a package repository.
+    /// Parse a "mxpkg://" URL that locates a package repository.
     pub fn parse(url: &str) -> Result<Self, ParseError> {
         let UrlParts { scheme, host, path, hash, resource } = UrlParts::parse(url)?;
         let scheme = scheme.ok_or(ParseError::MissingScheme)?;
@@ -40,7 +40,7 @@ impl RepositoryUrl {
     }
 
     pub(crate) fn new(scheme: Scheme, host: Host) -> Result<Self, ParseError> {
-        if scheme != Scheme::FuchsiaPkg {
+        if scheme != Scheme::MxPkg {
             return Err(ParseError::InvalidScheme);
         }
 
@@ -108,13 +108,13 @@ mod tests {
         for (url, err) in [
             ("example.org", ParseError::MissingScheme),
             ("fuchsia-boot://example.org", ParseError::InvalidScheme),
-            ("fuchsia-pkg://", ParseError::MissingHost),
-            ("fuchsia-pkg://exaMple.org", ParseError::InvalidHost),
-            ("fuchsia-pkg://example.org/path", ParseError::ExtraPathSegments),
-            ("fuchsia-pkg://example.org//", ParseError::InvalidPathSegment(PackagePathSegmentError::Empty)),
-            ("fuchsia-pkg://example.org?hash=0000000000000000000000000000000000000000000000000000000000000000", ParseError::CannotContainHash),
-            ("fuchsia-pkg://example.org#resource", ParseError::CannotContainResource),
-            ("fuchsia-pkg://example.org/#resource", ParseError::CannotContainResource),
+            ("mxpkg://", ParseError::MissingHost),
+            ("mxpkg://exaMple.org", ParseError::InvalidHost),
+            ("mxpkg://example.org/path", ParseError::ExtraPathSegments),
+            ("mxpkg://example.org//", ParseError::InvalidPathSegment(PackagePathSegmentError::Empty)),
+            ("mxpkg://example.org?hash=0000000000000000000000000000000000000000000000000000000000000000", ParseError::CannotContainHash),
+            ("mxpkg://example.org#resource", ParseError::CannotContainResource),
+            ("mxpkg://example.org/#resource", ParseError::CannotContainResource),
         ] {
             assert_matches!(
                 RepositoryUrl::parse(url),
@@ -142,9 +142,9 @@ mod tests {
     #[test]
     fn parse_ok() {
         for (url, host, display) in [
-            ("fuchsia-pkg://example.org", "example.org", "fuchsia-pkg://example.org"),
-            ("fuchsia-pkg://example.org/", "example.org", "fuchsia-pkg://example.org"),
-            ("fuchsia-pkg://example", "example", "fuchsia-pkg://example"),
+            ("mxpkg://example.org", "example.org", "mxpkg://example.org"),
+            ("mxpkg://example.org/", "example.org", "mxpkg://example.org"),
+            ("mxpkg://example", "example", "mxpkg://example"),
         ] {
             // Creation
             assert_eq!(RepositoryUrl::parse(url).unwrap().host(), host, "the url {:?}", url);
@@ -10,7 +10,7 @@ use {
     proptest::prelude::*,
 };
 
-/// Valid characters for a Fuchsia package path.  These characters are any unicode character,
+/// Valid characters for a MeshX package path.  These characters are any unicode character,
 /// except for '/', '\0', '.', and '\n'.
 // TODO(https://fxbug.dev/42096516) allow newline once meta/contents supports it in blob paths
 pub(crate) const ANY_UNICODE_EXCEPT_SLASH_NULL_DOT_OR_NEWLINE: &str = "[^/\0\\.\n]";
@@ -8,8 +8,8 @@ use crate::{
     AbsolutePackageUrl, RepositoryUrl,
 };
 
-/// A URL locating a Fuchsia package. Cannot have a hash.
-/// Has the form "fuchsia-pkg://<repository>/<name>[/variant]" where:
+/// A URL locating a MeshX package. Cannot have a hash.
+/// Has the form "mxpkg://<repository>/<name>[/variant]" where:
 ///   * "repository" is a valid hostname
 ///   * "name" is a valid package name
 ///   * "variant" is an optional valid package variant
@@ -34,7 +34,7 @@ impl UnpinnedAbsolutePackageUrl {
         Ok(Self::new(repo, name, variant))
     }
 
-    /// Parse a "fuchsia-pkg://" URL that locates an unpinned (no hash query parameter) package.
+    /// Parse a "mxpkg://" URL that locates an unpinned (no hash query parameter) package.
     pub fn parse(url: &str) -> Result<Self, ParseError> {
         match AbsolutePackageUrl::parse(url)? {
             AbsolutePackageUrl::Unpinned(unpinned) => Ok(unpinned),
@@ -146,7 +146,7 @@ mod tests {
         ] {
             assert_matches!(
                 UnpinnedAbsolutePackageUrl::new_with_path(
-                    "fuchsia-pkg://example.org".parse().unwrap(),
+                    "mxpkg://example.org".parse().unwrap(),
                     path.into(),
                 ),
                 Err(e) if e == err,
@@ -157,7 +157,7 @@ mod tests {
 
     #[test]
     fn new_with_path_ok() {
-        let repo = "fuchsia-pkg://example.org".parse::<RepositoryUrl>().unwrap();
+        let repo = "mxpkg://example.org".parse::<RepositoryUrl>().unwrap();
         let url = UnpinnedAbsolutePackageUrl::new_with_path(repo.clone(), "/name".into()).unwrap();
         assert_eq!(url.name().as_ref(), "name");
         assert_eq!(url.variant(), None);
@@ -171,23 +171,23 @@ mod tests {
     fn parse_err() {
         for (url, err) in [
             ("fuchsia-boot://example.org/name", ParseError::InvalidScheme),
-            ("fuchsia-pkg://", ParseError::MissingHost),
-            ("fuchsia-pkg://exaMple.org", ParseError::InvalidHost),
-            ("fuchsia-pkg://example.org/", ParseError::MissingName),
+            ("mxpkg://", ParseError::MissingHost),
+            ("mxpkg://exaMple.org", ParseError::InvalidHost),
+            ("mxpkg://example.org/", ParseError::MissingName),
             (
-                "fuchsia-pkg://example.org//",
+                "mxpkg://example.org//",
                 ParseError::InvalidPathSegment(PackagePathSegmentError::Empty),
             ),
             (
-                "fuchsia-pkg://example.org/name/variant/extra",
+                "mxpkg://example.org/name/variant/extra",
                 ParseError::ExtraPathSegments,
             ),
             (
-                "fuchsia-pkg://example.org/name#resource",
+                "mxpkg://example.org/name#resource",
                 ParseError::CannotContainResource,
             ),
             (
-                "fuchsia-pkg://example.org/name?hash=0000000000000000000000000000000000000000000000000000000000000000",
+                "mxpkg://example.org/name?hash=0000000000000000000000000000000000000000000000000000000000000000",
                 ParseError::CannotContainHash,
             ),
         ] {
@@ -218,9 +218,9 @@ mod tests {
     #[test]
     fn parse_ok() {
         for (url, host, name, variant, path) in [
-            ("fuchsia-pkg://example.org/name", "example.org", "name", None, "/name"),
+            ("mxpkg://example.org/name", "example.org", "name", None, "/name"),
             (
-                "fuchsia-pkg://example.org/name/variant",
+                "mxpkg://example.org/name/variant",
                 "example.org",
                 "name",
                 Some("variant"),
@@ -261,20 +261,20 @@ mod tests {
 
     #[test]
     fn set_repository() {
-        let mut url = UnpinnedAbsolutePackageUrl::parse("fuchsia-pkg://example.org/name").unwrap();
+        let mut url = UnpinnedAbsolutePackageUrl::parse("mxpkg://example.org/name").unwrap();
 
-        url.set_repository("fuchsia-pkg://example.com".parse().unwrap());
+        url.set_repository("mxpkg://example.com".parse().unwrap());
 
         assert_eq!(url.host(), "example.com");
     }
 
     #[test]
     fn clear_variant() {
-        let mut url = UnpinnedAbsolutePackageUrl::parse("fuchsia-pkg://example.org/name/variant").unwrap();
+        let mut url = UnpinnedAbsolutePackageUrl::parse("mxpkg://example.org/name/variant").unwrap();
         url.clear_variant();
         assert_eq!(url.variant(), None);
 
-        let mut url = UnpinnedAbsolutePackageUrl::parse("fuchsia-pkg://example.org/name").unwrap();
+        let mut url = UnpinnedAbsolutePackageUrl::parse("mxpkg://example.org/name").unwrap();
         url.clear_variant();
         assert_eq!(url.variant(), None);
     }
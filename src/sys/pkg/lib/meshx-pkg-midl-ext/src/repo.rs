@@ -980,7 +980,7 @@ mod tests {
 
     #[test]
     fn test_repository_config_builder() {
-        let repo_url = meshx_url::RepositoryUrl::parse("fuchsia-pkg://fuchsia.com").unwrap();
+        let repo_url = meshx_url::RepositoryUrl::parse("mxpkg://fuchsia.com").unwrap();
         let builder = RepositoryConfigBuilder::new(repo_url.clone());
         assert_eq!(
             builder.clone().build(),
@@ -1015,7 +1015,7 @@ mod tests {
     #[test]
     fn test_repository_config_into_fidl() {
         let config = RepositoryConfig {
-            repo_url: "fuchsia-pkg://fuchsia.com".parse().unwrap(),
+            repo_url: "mxpkg://fuchsia.com".parse().unwrap(),
             root_version: 2,
             root_threshold: 2,
             root_keys: vec![RepositoryKey::Ed25519(vec![0xf1, 15, 16, 3])],
@@ -1031,7 +1031,7 @@ mod tests {
         assert_eq!(
             as_fidl,
             fidl::RepositoryConfig {
-                repo_url: Some("fuchsia-pkg://fuchsia.com".parse().unwrap()),
+                repo_url: Some("mxpkg://fuchsia.com".parse().unwrap()),
                 root_version: Some(2),
                 root_threshold: Some(2),
                 root_keys: Some(vec![fidl::RepositoryKeyConfig::Ed25519Key(vec![0xf1, 15, 16, 3])]),
@@ -1051,7 +1051,7 @@ mod tests {
     #[test]
     fn test_repository_config_from_fidl_without_storage_type() {
         let as_fidl = fidl::RepositoryConfig {
-            repo_url: Some("fuchsia-pkg://fuchsia.com".parse().unwrap()),
+            repo_url: Some("mxpkg://fuchsia.com".parse().unwrap()),
             root_version: Some(1),
             root_threshold: Some(1),
             root_keys: Some(vec![fidl::RepositoryKeyConfig::Ed25519Key(vec![0xf1, 15, 16, 3])]),
@@ -1068,7 +1068,7 @@ mod tests {
         assert_matches!(
             RepositoryConfig::try_from(as_fidl),
             Ok(repository_config) if repository_config == RepositoryConfig {
-                repo_url: "fuchsia-pkg://fuchsia.com".parse().unwrap(),
+                repo_url: "mxpkg://fuchsia.com".parse().unwrap(),
                 root_version: 1,
                 root_threshold: 1,
                 root_keys: vec![RepositoryKey::Ed25519(vec![0xf1, 15, 16, 3]),],
@@ -1086,7 +1086,7 @@ mod tests {
     #[test]
     fn test_repository_config_from_fidl_with_storage_type() {
         let as_fidl = fidl::RepositoryConfig {
-            repo_url: Some("fuchsia-pkg://fuchsia.com".parse().unwrap()),
+            repo_url: Some("mxpkg://fuchsia.com".parse().unwrap()),
             root_version: Some(1),
             root_threshold: Some(1),
             root_keys: Some(vec![fidl::RepositoryKeyConfig::Ed25519Key(vec![0xf1, 15, 16, 3])]),
@@ -1103,7 +1103,7 @@ mod tests {
         assert_matches!(
             RepositoryConfig::try_from(as_fidl),
             Ok(repository_config) if repository_config == RepositoryConfig {
-                repo_url: "fuchsia-pkg://fuchsia.com".parse().unwrap(),
+                repo_url: "mxpkg://fuchsia.com".parse().unwrap(),
                 root_version: 1,
                 root_threshold: 1,
                 root_keys: vec![RepositoryKey::Ed25519(vec![0xf1, 15, 16, 3]),],
@@ -1121,7 +1121,7 @@ mod tests {
     #[test]
     fn test_repository_config_from_fidl_without_version_and_threshold_and_use_local_mirror() {
         let as_fidl = fidl::RepositoryConfig {
-            repo_url: Some("fuchsia-pkg://fuchsia.com".parse().unwrap()),
+            repo_url: Some("mxpkg://fuchsia.com".parse().unwrap()),
             root_version: None,
             root_threshold: None,
             root_keys: Some(vec![fidl::RepositoryKeyConfig::Ed25519Key(vec![0xf1, 15, 16, 3])]),
@@ -1138,7 +1138,7 @@ mod tests {
         assert_matches!(
             RepositoryConfig::try_from(as_fidl),
             Ok(repository_config) if repository_config == RepositoryConfig {
-                repo_url: "fuchsia-pkg://fuchsia.com".parse().unwrap(),
+                repo_url: "mxpkg://fuchsia.com".parse().unwrap(),
                 root_version: 1,
                 root_threshold: 1,
                 root_keys: vec![RepositoryKey::Ed25519(vec![0xf1, 15, 16, 3]),],
@@ -1156,7 +1156,7 @@ mod tests {
     #[test]
     fn test_repository_config_from_fidl_with_version_and_threshold_and_use_local_mirror() {
         let as_fidl = fidl::RepositoryConfig {
-            repo_url: Some("fuchsia-pkg://fuchsia.com".parse().unwrap()),
+            repo_url: Some("mxpkg://fuchsia.com".parse().unwrap()),
             root_version: Some(2),
             root_threshold: Some(2),
             root_keys: Some(vec![fidl::RepositoryKeyConfig::Ed25519Key(vec![0xf1, 15, 16, 3])]),
@@ -1173,7 +1173,7 @@ mod tests {
         assert_matches!(
             RepositoryConfig::try_from(as_fidl),
             Ok(repository_config) if repository_config == RepositoryConfig {
-                repo_url: "fuchsia-pkg://fuchsia.com".parse().unwrap(),
+                repo_url: "mxpkg://fuchsia.com".parse().unwrap(),
                 root_version: 2,
                 root_threshold: 2,
                 root_keys: vec![RepositoryKey::Ed25519(vec![0xf1, 15, 16, 3]),],
@@ -1209,7 +1209,7 @@ mod tests {
     #[test]
     fn test_repository_config_into_from_fidl_roundtrip() {
         let config = RepositoryConfig {
-            repo_url: "fuchsia-pkg://fuchsia.com".parse().unwrap(),
+            repo_url: "mxpkg://fuchsia.com".parse().unwrap(),
             root_version: 2,
             root_threshold: 2,
             root_keys: vec![RepositoryKey::Ed25519(vec![0xf1, 15, 16, 3])],
@@ -1228,7 +1228,7 @@ mod tests {
     #[test]
     fn test_repository_config_deserialize_missing_root_version_and_threshold_and_use_local_mirror() {
         let json_value = json!({
-            "repo_url": "fuchsia-pkg://fuchsia.com",
+            "repo_url": "mxpkg://fuchsia.com",
             "root_keys": [],
             "mirrors": [],
         });
@@ -1237,7 +1237,7 @@ mod tests {
         assert_eq!(
             actual_config,
             RepositoryConfig {
-                repo_url: "fuchsia-pkg://fuchsia.com".parse().unwrap(),
+                repo_url: "mxpkg://fuchsia.com".parse().unwrap(),
                 root_version: 1,
                 root_threshold: 1,
                 root_keys: vec![],
@@ -1253,7 +1253,7 @@ mod tests {
     #[test]
     fn test_repository_config_deserialize_ignores_update_package_url() {
         let json_value = json!({
-            "repo_url": "fuchsia-pkg://fuchsia.com",
+            "repo_url": "mxpkg://fuchsia.com",
             "root_keys": [],
             "mirrors": [],
             "update_package_url": "ignored-value",
@@ -1263,7 +1263,7 @@ mod tests {
         assert_eq!(
             actual_config,
             RepositoryConfig {
-                repo_url: "fuchsia-pkg://fuchsia.com".parse().unwrap(),
+                repo_url: "mxpkg://fuchsia.com".parse().unwrap(),
                 root_version: 1,
                 root_threshold: 1,
                 root_keys: vec![],
@@ -1278,7 +1278,7 @@ mod tests {
     fn test_repository_configs_serde_simple() {
         verify_json_serde(
             RepositoryConfigs::Version1(vec![RepositoryConfig {
-                repo_url: "fuchsia-pkg://fuchsia.com".parse().unwrap(),
+                repo_url: "mxpkg://fuchsia.com".parse().unwrap(),
                 root_version: 1,
                 root_threshold: 1,
                 root_keys: vec![],
@@ -1289,7 +1289,7 @@ mod tests {
             json!({
                 "version": "1",
                 "content": [{
-                    "repo_url": "fuchsia-pkg://fuchsia.com",
+                    "repo_url": "mxpkg://fuchsia.com",
                     "root_version": 1,
                     "root_threshold": 1,
                     "root_keys": [],
@@ -1304,13 +1304,13 @@ mod tests {
     #[test]
     fn test_repository_url_into_fidl() {
         let url = RepositoryUrl {
-            url: "fuchsia-pkg://fuchsia.com".parse().unwrap(),
+            url: "mxpkg://fuchsia.com".parse().unwrap(),
         };
         let as_fidl: fidl::RepositoryUrl = url.into();
         assert_eq!(
             as_fidl,
             fidl::RepositoryUrl {
-                url: "fuchsia-pkg://fuchsia.com".to_owned()
+                url: "mxpkg://fuchsia.com".to_owned()
             }
         );
     }
@@ -1318,11 +1318,11 @@ mod tests {
     #[test]
     fn test_repository_url_from_fidl() {
         let as_fidl = fidl::RepositoryUrl {
-            url: "fuchsia-pkg://fuchsia.com".to_owned(),
+            url: "mxpkg://fuchsia.com".to_owned(),
         };
         assert_matches!(
             RepositoryUrl::try_from(&as_fidl),
-            Ok(RepositoryUrl { url }) if url == "fuchsia-pkg://fuchsia.com".parse().unwrap()
+            Ok(RepositoryUrl { url }) if url == "mxpkg://fuchsia.com".parse().unwrap()
         );
     }
 
@@ -1342,7 +1342,7 @@ mod tests {
     #[test]
     fn test_repository_url_into_from_fidl_roundtrip() {
         let url = RepositoryUrl {
-            url: "fuchsia-pkg://fuchsia.com".parse().unwrap(),
+            url: "mxpkg://fuchsia.com".parse().unwrap(),
         };
         let as_fidl: fidl::RepositoryUrl = url.clone().into();
         assert_eq!(RepositoryUrl::try_from(&as_fidl).unwrap(), url);
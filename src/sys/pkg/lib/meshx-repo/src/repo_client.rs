@@ -617,7 +617,7 @@ mod tests {
     async fn test_get_config() {
         let repo = make_readonly_empty_repository().await.unwrap();
 
-        let repo_url: RepositoryUrl = "fuchsia-pkg://fake-repo".parse().unwrap();
+        let repo_url: RepositoryUrl = "mxpkg://fake-repo".parse().unwrap();
         let mirror_url: http::Uri = "http://some-url:1234".parse().unwrap();
 
         assert_eq!(
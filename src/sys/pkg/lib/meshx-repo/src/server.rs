@@ -462,7 +462,7 @@ async fn handle_request(
                 }
             };
 
-            let repo_url = match RepositoryUrl::parse(format!("fuchsia-pkg://{repo_name}").as_str()) {
+            let repo_url = match RepositoryUrl::parse(format!("mxpkg://{repo_name}").as_str()) {
                 Ok(r) => r,
                 Err(e) => {
                     error!("invalid repo url conversion: {:?}", e);
@@ -570,7 +570,7 @@ async fn generate_package_html(
                 .map(|p| {
                     Ok(format!(
                         "<tr>
-            <td><a href=\"fuchsia-pkg://fuchsia-pkg://{0}/{1}\">{1}</a></td>
+            <td><a href=\"mxpkg://mxpkg://{0}/{1}\">{1}</a></td>
             <td class=merkle>{2}</td>
           </tr>",
                         repo_name, p.name, p.hash,
@@ -917,7 +917,7 @@ mod tests {
         )
         .unwrap();
 
-        let expected = RepositoryConfigBuilder::new(RepositoryUrl::parse(&format!("fuchsia-pkg://{devhost}")).unwrap())
+        let expected = RepositoryConfigBuilder::new(RepositoryUrl::parse(&format!("mxpkg://{devhost}")).unwrap())
             .add_mirror(
                 MirrorConfigBuilder::new(Uri::try_from(&format!("{server_url}/{devhost}")).unwrap())
                     .unwrap()
@@ -1047,10 +1047,10 @@ mod tests {
             let result_body_bytes = hyper::body::to_bytes(result.into_body()).await.unwrap();
 
             let middle = "<tr>\n            \
-            <td><a href=\"fuchsia-pkg://fuchsia-pkg://devhost-0/package1/0\">package1/0</a></td>\n            \
+            <td><a href=\"mxpkg://mxpkg://devhost-0/package1/0\">package1/0</a></td>\n            \
             <td class=merkle>2881455493b5870aaea36537d70a2adc635f516ac2092598f4b6056dabc6b25d</td>\n          \
             </tr><tr>\n            \
-            <td><a href=\"fuchsia-pkg://fuchsia-pkg://devhost-0/package2/0\">package2/0</a></td>\n            \
+            <td><a href=\"mxpkg://mxpkg://devhost-0/package2/0\">package2/0</a></td>\n            \
             <td class=merkle>050907f009ff634f9aa57bff541fb9e9c2c62b587c23578e77637cda3bd69458</td>\n          \
             </tr>"
                 .to_string();
@@ -0,0 +1,73 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use {
+    async_trait::async_trait,
+    camino::Utf8PathBuf,
+    fiber_status::Status,
+    meshx_merkle::Hash,
+    std::{fs, io},
+};
+
+/// Content addressed storage the package content is read from.
+#[async_trait]
+pub trait BlobStore: Send + Sync + 'static {
+    /// Returns the content of the blob `hash`.  Implementations do not need to verify the content,
+    /// the caller checks it against `hash`.
+    async fn read_blob(&self, hash: &Hash) -> Result<Vec<u8>, Status>;
+}
+
+/// Reads the content of the blob `hash` from `store`, checking that it matches the hash.
+pub(crate) async fn read_verified_blob(store: &dyn BlobStore, hash: &Hash) -> Result<Vec<u8>, Status> {
+    let data = store.read_blob(hash).await?;
+    let actual = meshx_merkle::from_slice(&data).root();
+    if actual != *hash {
+        tracing::error!("blob {hash} has unexpected content, with hash {actual}");
+        return Err(Status::IO_DATA_INTEGRITY);
+    }
+    Ok(data)
+}
+
+/// A [`BlobStore`] backed by a host directory, that holds every blob in a file named after the
+/// hex encoded hash of the blob.
+#[derive(Debug, Clone)]
+pub struct BlobDirectory {
+    path: Utf8PathBuf,
+}
+
+impl BlobDirectory {
+    pub fn new(path: impl Into<Utf8PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl BlobStore for BlobDirectory {
+    async fn read_blob(&self, hash: &Hash) -> Result<Vec<u8>, Status> {
+        fs::read(self.path.join(hash.to_string())).map_err(|error: io::Error| error.kind().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, futures::executor::block_on};
+
+    #[test]
+    fn read_verified_blob_checks_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BlobDirectory::new(Utf8PathBuf::try_from(dir.path().to_path_buf()).unwrap());
+        let hash = meshx_merkle::from_slice(b"blob").root();
+        let other = meshx_merkle::from_slice(b"other").root();
+        fs::write(dir.path().join(hash.to_string()), b"blob").unwrap();
+        fs::write(dir.path().join(other.to_string()), b"corrupt").unwrap();
+
+        assert_eq!(block_on(read_verified_blob(&store, &hash)), Ok(b"blob".to_vec()));
+        assert_eq!(
+            block_on(read_verified_blob(&store, &other)),
+            Err(Status::IO_DATA_INTEGRITY)
+        );
+        let missing = meshx_merkle::from_slice(b"missing").root();
+        assert_eq!(block_on(read_verified_blob(&store, &missing)), Err(Status::NOT_FOUND));
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright 2021 The Fuchsia Authors. All rights reserved.
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Serves a resolved package as a `meshx.io` directory.
+//!
+//! The package is identified by the hash of its meta.far, and all of its content is read from a
+//! [`BlobStore`].  Files under `meta/` are served from the meta.far itself.  All the other files
+//! are listed in `meta/contents`, and are read from the blob store when opened.  The content of
+//! every blob is checked against its hash before it is served.
+
+mod blob_store;
+mod package_dir;
+
+pub use {
+    blob_store::{BlobDirectory, BlobStore},
+    package_dir::PackageDir,
+};
+
+use {
+    fiber_status::Status,
+    midl::endpoints::ServerEnd,
+    midl_meshx_io as mio,
+    std::sync::Arc,
+    thiserror::Error,
+    vfs::{directory::entry::DirectoryEntry as _, execution_scope::ExecutionScope, path::Path},
+};
+
+pub use meshx_merkle::Hash;
+
+/// An error encountered while loading a package.
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("while reading meta.far blob {hash}")]
+    ReadMetaFar {
+        hash: Hash,
+        #[source]
+        status: Status,
+    },
+
+    #[error("while opening meta.far")]
+    ArchiveReader(#[source] meshx_archive::Error),
+
+    #[error("meta.far contains a path that is not valid UTF-8: {0:?}")]
+    NonUtf8Path(Vec<u8>),
+
+    #[error("while reading 'meta/contents'")]
+    ReadMetaContents(#[source] meshx_archive::Error),
+
+    #[error("while parsing 'meta/contents'")]
+    ParseMetaContents(#[source] meshx_pkg::MetaContentsError),
+}
+
+/// Loads the package with the meta.far `hash` from `blob_store`, and serves its root directory
+/// over `server_end`.
+pub async fn serve(
+    scope: ExecutionScope,
+    blob_store: Arc<dyn BlobStore>,
+    hash: Hash,
+    flags: mio::OpenFlags,
+    server_end: ServerEnd<mio::NodeMarker>,
+) -> Result<(), Error> {
+    let root = PackageDir::new(blob_store, hash).await?;
+    root.open(scope, flags, Path::dot(), server_end);
+    Ok(())
+}
@@ -0,0 +1,490 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use {
+    crate::{
+        blob_store::{read_verified_blob, BlobStore},
+        Error,
+    },
+    async_trait::async_trait,
+    fiber_rust as fx,
+    fiber_status::Status,
+    futures::io::Cursor,
+    meshx_archive::AsyncReader,
+    meshx_fs::file::Adapter,
+    meshx_merkle::Hash,
+    meshx_pkg::MetaContents,
+    midl::endpoints::ServerEnd,
+    midl_meshx_io as mio,
+    std::{collections::BTreeMap, fmt, ops::Bound, sync::Arc},
+    vfs::{
+        common::{rights_to_posix_mode_bits, send_on_open_with_error},
+        directory::{
+            connection::DerivedConnection,
+            dirents_sink::{self, AppendResult},
+            entry::{DirectoryEntry, EntryInfo},
+            entry_container::Directory,
+            immutable::connection::ImmutableConnection,
+        },
+        execution_scope::ExecutionScope,
+        file,
+        node::Node,
+        path::Path,
+        traversal_position::TraversalPosition,
+    },
+};
+
+/// Where the content of a file of the package lives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Content {
+    /// A `meta/` file, stored in the meta.far at `offset`.
+    Meta { offset: u64, length: u64 },
+    /// A file listed in `meta/contents`.
+    Blob(Hash),
+}
+
+/// What a path of the package refers to.
+#[derive(Debug, PartialEq, Eq)]
+enum Lookup {
+    File(Content),
+    /// A directory, identified by its path with a trailing slash.
+    Directory(String),
+}
+
+/// A loaded package, shared by all the directories of the package.
+struct Package {
+    hash: Hash,
+    blob_store: Arc<dyn BlobStore>,
+    meta_far: Vec<u8>,
+    meta_contents: MetaContents,
+    /// All the files of the package, keyed by their path.  Directories are implied by the paths.
+    files: BTreeMap<String, Content>,
+}
+
+impl Package {
+    async fn load(blob_store: Arc<dyn BlobStore>, hash: Hash) -> Result<Self, Error> {
+        let meta_far = read_verified_blob(&*blob_store, &hash)
+            .await
+            .map_err(|status| Error::ReadMetaFar { hash, status })?;
+
+        let mut reader = AsyncReader::new(Adapter::new(Cursor::new(&meta_far[..])))
+            .await
+            .map_err(Error::ArchiveReader)?;
+        let mut files = BTreeMap::new();
+        for entry in reader.list() {
+            let path =
+                String::from_utf8(entry.path().to_vec()).map_err(|error| Error::NonUtf8Path(error.into_bytes()))?;
+            files.insert(
+                path,
+                Content::Meta {
+                    offset: entry.offset(),
+                    length: entry.length(),
+                },
+            );
+        }
+
+        let meta_contents = reader
+            .read_file(MetaContents::PATH.as_bytes())
+            .await
+            .map_err(Error::ReadMetaContents)?;
+        let meta_contents = MetaContents::deserialize(&meta_contents[..]).map_err(Error::ParseMetaContents)?;
+        // `MetaContents` guarantees that none of these paths are under `meta/`.
+        for (path, hash) in meta_contents.contents() {
+            files.insert(path.clone(), Content::Blob(*hash));
+        }
+
+        Ok(Package {
+            hash,
+            blob_store,
+            meta_far,
+            meta_contents,
+            files,
+        })
+    }
+
+    /// Looks up `path`, which has no leading or trailing slashes.
+    fn lookup(&self, path: &str) -> Option<Lookup> {
+        if let Some(content) = self.files.get(path) {
+            return Some(Lookup::File(content.clone()));
+        }
+        let prefix = format!("{path}/");
+        let (first, _) = self
+            .files
+            .range::<str, _>((Bound::Included(prefix.as_str()), Bound::Unbounded))
+            .next()?;
+        first.starts_with(&prefix).then_some(Lookup::Directory(prefix))
+    }
+
+    /// Returns the entries of the directory `prefix`, sorted by name.
+    fn children(&self, prefix: &str) -> BTreeMap<&str, mio::DirentType> {
+        self.files
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(|(path, _)| path.starts_with(prefix))
+            .map(|(path, _)| match path[prefix.len()..].split_once('/') {
+                Some((directory, _)) => (directory, mio::DirentType::Directory),
+                None => (&path[prefix.len()..], mio::DirentType::File),
+            })
+            .collect()
+    }
+
+    /// Returns the content of the meta.far at `offset`.
+    fn meta_content(&self, offset: u64, length: u64) -> Result<&[u8], Status> {
+        let start = usize::try_from(offset).map_err(|_| Status::IO_INVALID)?;
+        let end = start
+            .checked_add(usize::try_from(length).map_err(|_| Status::IO_INVALID)?)
+            .ok_or(Status::IO_INVALID)?;
+        self.meta_far.get(start..end).ok_or(Status::IO_INVALID)
+    }
+
+    fn meta_file(&self, offset: u64, length: u64) -> Result<Arc<file::SimpleFile>, Status> {
+        Ok(file::read_only(self.meta_content(offset, length)?))
+    }
+}
+
+/// A directory of a package.  Use [`PackageDir::new()`] to load the root directory of a package.
+pub struct PackageDir {
+    package: Arc<Package>,
+
+    /// The path of this directory with a trailing slash, or an empty string for the root.
+    prefix: String,
+}
+
+impl PackageDir {
+    /// Loads the package with the meta.far `hash` from `blob_store`, and returns its root
+    /// directory.
+    pub async fn new(blob_store: Arc<dyn BlobStore>, hash: Hash) -> Result<Arc<Self>, Error> {
+        let package = Arc::new(Package::load(blob_store, hash).await?);
+        Ok(Arc::new(PackageDir {
+            package,
+            prefix: String::new(),
+        }))
+    }
+
+    /// The hash of the meta.far of the package.
+    pub fn hash(&self) -> &Hash {
+        &self.package.hash
+    }
+
+    /// The parsed `meta/contents` of the package.
+    pub fn meta_contents(&self) -> &MetaContents {
+        &self.package.meta_contents
+    }
+
+    /// Reads the file at `path`, relative to this directory, without serving it.  Blobs are
+    /// verified like they are when opened.
+    pub async fn read_file(&self, path: &str) -> Result<Vec<u8>, Status> {
+        let full_path = format!("{}{path}", self.prefix);
+        match self.package.lookup(&full_path) {
+            Some(Lookup::File(Content::Meta { offset, length })) => {
+                self.package.meta_content(offset, length).map(<[u8]>::to_vec)
+            }
+            Some(Lookup::File(Content::Blob(hash))) => read_verified_blob(&*self.package.blob_store, &hash).await,
+            Some(Lookup::Directory(_)) => Err(Status::NOT_FILE),
+            None => Err(Status::NOT_FOUND),
+        }
+    }
+
+    fn open_file(
+        &self,
+        scope: ExecutionScope,
+        flags: mio::OpenFlags,
+        content: Content,
+        server_end: ServerEnd<mio::NodeMarker>,
+    ) {
+        let describe = flags.intersects(mio::OpenFlags::DESCRIBE);
+        match content {
+            Content::Meta { offset, length } => match self.package.meta_file(offset, length) {
+                Ok(file) => file.open(scope, flags, Path::dot(), server_end),
+                Err(status) => send_on_open_with_error(describe, server_end, status),
+            },
+            Content::Blob(hash) => {
+                // Blobs are only read, and verified, once they are opened.
+                let package = self.package.clone();
+                scope.clone().spawn(async move {
+                    match read_verified_blob(&*package.blob_store, &hash).await {
+                        Ok(data) => file::read_only(data).open(scope, flags, Path::dot(), server_end),
+                        Err(status) => send_on_open_with_error(describe, server_end, status),
+                    }
+                });
+            }
+        }
+    }
+}
+
+impl fmt::Debug for PackageDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PackageDir")
+            .field("hash", &self.package.hash)
+            .field("prefix", &self.prefix)
+            .finish()
+    }
+}
+
+impl DirectoryEntry for PackageDir {
+    fn open(
+        self: Arc<Self>,
+        scope: ExecutionScope,
+        flags: mio::OpenFlags,
+        path: Path,
+        server_end: ServerEnd<mio::NodeMarker>,
+    ) {
+        let describe = flags.intersects(mio::OpenFlags::DESCRIBE);
+        // Packages are immutable.
+        let modifies =
+            mio::OpenFlags::RIGHT_WRITABLE | mio::OpenFlags::CREATE | mio::OpenFlags::TRUNCATE | mio::OpenFlags::APPEND;
+        if flags.intersects(modifies) {
+            send_on_open_with_error(describe, server_end, Status::ACCESS_DENIED);
+            return;
+        }
+
+        if path.is_empty() {
+            ImmutableConnection::create_connection(scope, self, flags, server_end);
+            return;
+        }
+
+        let full_path = format!("{}{}", self.prefix, path.as_ref().trim_end_matches('/'));
+        match self.package.lookup(&full_path) {
+            Some(Lookup::Directory(prefix)) => {
+                let directory = Arc::new(PackageDir {
+                    package: self.package.clone(),
+                    prefix,
+                });
+                directory.open(scope, flags, Path::dot(), server_end);
+            }
+            Some(Lookup::File(_)) if path.is_dir() => {
+                send_on_open_with_error(describe, server_end, Status::NOT_DIR);
+            }
+            Some(Lookup::File(content)) => self.open_file(scope, flags, content, server_end),
+            None => send_on_open_with_error(describe, server_end, Status::NOT_FOUND),
+        }
+    }
+
+    fn entry_info(&self) -> EntryInfo {
+        EntryInfo::new(mio::INO_UNKNOWN, mio::DirentType::Directory)
+    }
+}
+
+#[async_trait]
+impl Node for PackageDir {
+    async fn get_attrs(&self) -> Result<mio::NodeAttributes, Status> {
+        Ok(mio::NodeAttributes {
+            mode: mio::MODE_TYPE_DIRECTORY | rights_to_posix_mode_bits(true, false, false),
+            id: mio::INO_UNKNOWN,
+            content_size: 0,
+            storage_size: 0,
+            link_count: 1,
+            creation_time: 0,
+            modification_time: 0,
+        })
+    }
+}
+
+#[async_trait]
+impl Directory for PackageDir {
+    async fn read_dirents<'a>(
+        &'a self,
+        pos: &'a TraversalPosition,
+        sink: Box<dyn dirents_sink::Sink>,
+    ) -> Result<(TraversalPosition, Box<dyn dirents_sink::Sealed>), Status> {
+        let (mut sink, start) = match pos {
+            TraversalPosition::Start => {
+                match sink.append(&EntryInfo::new(mio::INO_UNKNOWN, mio::DirentType::Directory), ".") {
+                    AppendResult::Ok(sink) => (sink, Bound::Unbounded),
+                    AppendResult::Sealed(sealed) => return Ok((TraversalPosition::Start, sealed)),
+                }
+            }
+            // `Name` holds the first entry that did not fit in the previous call.
+            TraversalPosition::Name(name) => (sink, Bound::Included(name.as_str())),
+            TraversalPosition::Index(_) => unreachable!("Package directories only use Name positions"),
+            TraversalPosition::End => return Ok((TraversalPosition::End, sink.seal())),
+        };
+
+        let children = self.package.children(&self.prefix);
+        for (name, type_) in children.range::<str, _>((start, Bound::Unbounded)) {
+            match sink.append(&EntryInfo::new(mio::INO_UNKNOWN, *type_), name) {
+                AppendResult::Ok(new_sink) => sink = new_sink,
+                AppendResult::Sealed(sealed) => {
+                    return Ok((TraversalPosition::Name(name.to_string()), sealed));
+                }
+            }
+        }
+
+        Ok((TraversalPosition::End, sink.seal()))
+    }
+
+    fn register_watcher(
+        self: Arc<Self>,
+        _scope: ExecutionScope,
+        _mask: mio::WatchMask,
+        _watcher: fx::Channel,
+    ) -> Result<(), Status> {
+        Err(Status::NOT_SUPPORTED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::BlobDirectory,
+        assert_matches::assert_matches,
+        camino::Utf8PathBuf,
+        futures::{executor::block_on, StreamExt as _},
+        meshx_async::TestExecutor,
+        midl::endpoints::{create_proxy, Proxy as _},
+        std::{collections::HashMap, fs, io::Read},
+    };
+
+    /// Writes a package with a `meta/package` file and the blobs `bin/app` and `data/a/b` to a
+    /// new blob directory, and loads it.
+    fn test_package() -> (tempfile::TempDir, Package) {
+        let dir = tempfile::tempdir().unwrap();
+        let write_blob = |content: &[u8]| {
+            let hash = meshx_merkle::from_slice(content).root();
+            fs::write(dir.path().join(hash.to_string()), content).unwrap();
+            hash
+        };
+
+        let meta_contents = MetaContents::from_map(HashMap::from([
+            ("bin/app".to_string(), write_blob(b"app")),
+            ("data/a/b".to_string(), write_blob(b"b")),
+        ]))
+        .unwrap();
+        let mut meta_contents_bytes = Vec::new();
+        meta_contents.serialize(&mut meta_contents_bytes).unwrap();
+        let meta_package = br#"{"name":"test","version":"0"}"#;
+
+        let mut far_content: BTreeMap<&str, (u64, Box<dyn Read + '_>)> = BTreeMap::new();
+        far_content.insert(
+            "meta/contents",
+            (meta_contents_bytes.len() as u64, Box::new(&meta_contents_bytes[..])),
+        );
+        far_content.insert("meta/package", (meta_package.len() as u64, Box::new(&meta_package[..])));
+        let mut meta_far = Vec::new();
+        meshx_archive::write(&mut meta_far, far_content).unwrap();
+        let hash = write_blob(&meta_far);
+
+        let store = BlobDirectory::new(Utf8PathBuf::try_from(dir.path().to_path_buf()).unwrap());
+        let package = block_on(Package::load(Arc::new(store), hash)).unwrap();
+        (dir, package)
+    }
+
+    #[test]
+    fn lookup() {
+        let (_dir, package) = test_package();
+        assert_matches!(package.lookup("meta/package"), Some(Lookup::File(Content::Meta { .. })));
+        assert_matches!(package.lookup("bin/app"), Some(Lookup::File(Content::Blob(_))));
+        assert_eq!(package.lookup("data/a"), Some(Lookup::Directory("data/a/".to_string())));
+        assert_eq!(package.lookup("meta"), Some(Lookup::Directory("meta/".to_string())));
+        assert_eq!(package.lookup("dat"), None);
+        assert_eq!(package.lookup("bin/app/x"), None);
+    }
+
+    #[test]
+    fn children() {
+        let (_dir, package) = test_package();
+        assert_eq!(
+            package.children("").into_iter().collect::<Vec<_>>(),
+            vec![
+                ("bin", mio::DirentType::Directory),
+                ("data", mio::DirentType::Directory),
+                ("meta", mio::DirentType::Directory),
+            ]
+        );
+        assert_eq!(
+            package.children("meta/").into_iter().collect::<Vec<_>>(),
+            vec![("contents", mio::DirentType::File), ("package", mio::DirentType::File),]
+        );
+    }
+
+    #[test]
+    fn meta_file_content() {
+        let (_dir, package) = test_package();
+        let Some(Lookup::File(Content::Meta { offset, length })) = package.lookup("meta/package") else {
+            panic!("meta/package is not a meta file");
+        };
+        let file = package.meta_file(offset, length).unwrap();
+        assert_eq!(file.content(), br#"{"name":"test","version":"0"}"#);
+    }
+
+    #[test]
+    fn read_file() {
+        let (_dir, package) = test_package();
+        let root = PackageDir {
+            package: Arc::new(package),
+            prefix: String::new(),
+        };
+        assert_eq!(
+            block_on(root.read_file("meta/package")),
+            Ok(br#"{"name":"test","version":"0"}"#.to_vec())
+        );
+        assert_eq!(block_on(root.read_file("data/a/b")), Ok(b"b".to_vec()));
+        assert_eq!(block_on(root.read_file("data/a")), Err(Status::NOT_FILE));
+        assert_eq!(block_on(root.read_file("data/c")), Err(Status::NOT_FOUND));
+    }
+
+    /// Opens `path` in `dir` as a file, returning the status `OnOpen` reports along with the
+    /// connection.
+    async fn open_file(dir: &mio::DirectoryProxy, flags: mio::OpenFlags, path: &str) -> (Status, mio::FileProxy) {
+        let (node, server_end) = create_proxy::<mio::NodeMarker>().unwrap();
+        dir.open(
+            flags | mio::OpenFlags::DESCRIBE,
+            mio::ModeType::empty(),
+            path,
+            server_end,
+        )
+        .unwrap();
+        let status = match node.take_event_stream().next().await {
+            Some(Ok(mio::NodeEvent::OnOpen { s, .. })) => Status::from_raw(s),
+            event => panic!("expected OnOpen, got {event:?}"),
+        };
+        (status, mio::FileProxy::from_channel(node.into_channel().unwrap()))
+    }
+
+    #[test]
+    fn serve_package() {
+        fiber_host::install();
+        let mut executor = TestExecutor::new();
+        let (dir, package) = test_package();
+        // Corrupt one of the blobs.
+        let Some(Lookup::File(Content::Blob(hash))) = package.lookup("data/a/b") else {
+            panic!("data/a/b is not a blob");
+        };
+        fs::write(dir.path().join(hash.to_string()), b"corrupt").unwrap();
+
+        let scope = ExecutionScope::new();
+        let (root, server_end) = create_proxy::<mio::DirectoryMarker>().unwrap();
+        let store = Arc::new(BlobDirectory::new(
+            Utf8PathBuf::try_from(dir.path().to_path_buf()).unwrap(),
+        ));
+        let flags = mio::OpenFlags::RIGHT_READABLE;
+        executor
+            .run_singlethreaded(crate::serve(
+                scope.clone(),
+                store,
+                package.hash,
+                flags,
+                ServerEnd::new(server_end.into_channel()),
+            ))
+            .unwrap();
+
+        executor.run_singlethreaded(async {
+            let (status, file) = open_file(&root, flags, "bin/app").await;
+            assert_eq!(status, Status::OK);
+            assert_eq!(file.read(100).await.unwrap(), Ok(b"app".to_vec()));
+            let (status, file) = open_file(&root, flags, "meta/package").await;
+            assert_eq!(status, Status::OK);
+            assert_eq!(
+                file.read(100).await.unwrap(),
+                Ok(br#"{"name":"test","version":"0"}"#.to_vec())
+            );
+
+            let (status, _) = open_file(&root, flags, "data/a/b").await;
+            assert_eq!(status, Status::IO_DATA_INTEGRITY);
+            let (status, _) = open_file(&root, flags | mio::OpenFlags::RIGHT_WRITABLE, "bin/app").await;
+            assert_eq!(status, Status::ACCESS_DENIED);
+        });
+        scope.shutdown();
+    }
+}
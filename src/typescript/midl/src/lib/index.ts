@@ -12,7 +12,7 @@ import {
     maybeThrowOnUnknownHandles,
 } from "./types"
 import { UnknownRawData } from "./unknown"
-import { UnionFactory } from "./union"
+import { TableFactory, UnionFactory } from "./union"
 import { CallStrictness, IncomingMessage, OutgoingMessage, kMagicNumberInitial, kMessageDynamicFlagOffset, kMessageFlagOffset, kMessageHeaderSize, kMessageMagicOffset, kMessageOrdinalOffset, kMessageTxidOffset, kWireFormatV2FlagMask, strictnessToFlags } from "./message"
 
 const ALIGMENT = 8
@@ -21,22 +21,19 @@ const ALIGMENT_MASK = ALIGMENT - 1
 const ENVELOPE_INLINE_MARKER = 1
 const ENVELOPE_OUT_OF_LINE_MARKER = 0
 const ENVELOPE_INLINE_CONTENT_SIZE = 4
-/// The maximum recursion depth of encoding and decoding.
-/// Each nested aggregate type (structs, unions, arrays, or vectors) counts as one step in the
-/// recursion depth.
-const MAX_RECURSION = 32
 const MAX_OUT_OF_LINE_DEPTH = 32
 
 /// Indicates that an optional value is present.
-const ALLOC_PRESENT_U64 = 18_446_744_073_709_551_615n
+export const ALLOC_PRESENT_U64 = 18_446_744_073_709_551_615n
 /// Indicates that an optional value is present.
-const ALLOC_PRESENT_U32 = 4_294_967_295
+export const ALLOC_PRESENT_U32 = 4_294_967_295
 /// Indicates that an optional value is absent.
-const ALLOC_ABSENT_U64: bigint = 0n
+export const ALLOC_ABSENT_U64: bigint = 0n
 
 const MIN_BUFFER_SIZE_INCREASE_FACTOR = 2
 
 const textEncoder = new TextEncoder()
+const textDecoder = new TextDecoder("utf-8", { fatal: true })
 
 export function align(size: number) {
     return (size + ALIGMENT_MASK) & ~ALIGMENT_MASK
@@ -48,22 +45,6 @@ function _checkIntRange(value: number, min: number, max: number): void {
     }
 }
 
-/**
- * Rounds `x` up if necessary so that it is a multiple of `align`.
- * Requires `align` to be a (nonzero) power of two.
- */
-function roundUpToAlign(x: number, align: number): number {
-    if (align === 0) {
-        throw new Error("Align must be nonzero")
-    }
-
-    if ((align & (align - 1)) !== 0) {
-        throw new Error("Align must be a power of two")
-    }
-
-    return (x + align - 1) & ~(align - 1)
-}
-
 export abstract class Union {
     get $ordinal(): number {
         throw new Error("must be implemented")
@@ -92,7 +73,17 @@ type EnvelopeHeader = {
 }
 
 export abstract class Enum {
-    $value: number = 0
+    abstract readonly value: number
+}
+
+export abstract class Bits<T extends number | bigint = number> {
+    constructor(public readonly $value: T) {}
+}
+
+export abstract class Table {
+    /// The present fields of the table by ordinal, including unknown ones
+    /// kept as `UnknownRawData`.
+    abstract get $fields(): Map<number, unknown>
 }
 
 export abstract class Struct {
@@ -112,16 +103,16 @@ export class Encoder {
     #handleDispositions
     #extent
 
-    private constructor(bytes: Uint8Array, handles: any[]) {
+    constructor(bytes: Uint8Array = new Uint8Array(0), handles: any[] = []) {
         this.buffer = bytes
-        this.data = new DataView(bytes.buffer)
+        this.data = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength)
         this.#handleDispositions = handles
         this.#extent = 0
     }
 
     private grow(newSize: number): void {
         const newList = new Uint8Array(newSize)
-        newList.set(new Uint8Array(this.data.buffer))
+        newList.set(this.buffer)
         this.data = new DataView(newList.buffer)
         this.buffer = newList
     }
@@ -155,10 +146,12 @@ export class Encoder {
         return new OutgoingMessage(trimmed, this.#handleDispositions)
     }
 
+    /// Encodes `x` as a top-level value of `type`, appending its handles to `handles`.
     static encode<V>(handles: any[], x: V, type: midl.Encodable<V>): Uint8Array {
-        const encoder = new Encoder(new Uint8Array(type.inlineSize), handles)
+        const encoder = new Encoder(new Uint8Array(align(type.inlineSize)), handles)
+        encoder.alloc(type.inlineSize, 0)
         type.encode(encoder, x, 0, 0)
-        return encoder.buffer
+        return encoder.buffer.slice(0, encoder.#extent)
     }
 
     encodeMessageHeader(ordinal: bigint, txid: number, strictness: CallStrictness): void {
@@ -175,11 +168,52 @@ export class Encoder {
         this.#handleDispositions.push(value)
     }
 
-    encodeString(value: string, offset: number, depth: number) {
+    encodeString(value: string | null, offset: number, depth: number, maxLength?: number) {
+        if (value === null) {
+            encodeAbsentVector(this, offset)
+            return
+        }
+
         const utf8 = textEncoder.encode(value)
+        if (maxLength !== undefined && utf8.length > maxLength) {
+            throw new MidlError(`String of ${utf8.length} bytes exceeds the limit of ${maxLength}`, ErrorCode.StringTooLong)
+        }
         encodeVectorFromBytes(this, offset, depth, utf8)
     }
 
+    /// Encodes a vector header followed by the elements out-of-line, or an absent vector for `null`.
+    encodeVector<T>(
+        value: T[] | null,
+        offset: number,
+        depth: number,
+        element: MidlType<T, Iterable<T>>,
+        maxLength?: number
+    ) {
+        if (value === null) {
+            encodeAbsentVector(this, offset)
+            return
+        }
+
+        if (maxLength !== undefined && value.length > maxLength) {
+            throw new MidlError(
+                `Vector of ${value.length} elements exceeds the limit of ${maxLength}`,
+                ErrorCode.CountExceedsLimit
+            )
+        }
+
+        this.encodeUInt64(BigInt(value.length), offset)
+        this.encodeUInt64(ALLOC_PRESENT_U64, offset + 8)
+        if (value.length === 0) {
+            return
+        }
+
+        const elementSize = element.inlineSize
+        const elementsOffset = this.alloc(value.length * elementSize, depth + 1)
+        for (let i = 0; i < value.length; i++) {
+            element.encode(this, value[i], elementsOffset + i * elementSize, depth + 1)
+        }
+    }
+
     encodeUnion<T extends Union>(
         value: T,
         offset: number,
@@ -203,12 +237,12 @@ export class Encoder {
         let fieldType = members[Number(ordinal)] // UNSAFE cast to number
         const data = value.$data
 
-        if (fieldType === null && flexible && data instanceof UnknownRawData) {
+        if (fieldType == null && flexible && data instanceof UnknownRawData) {
             maybeThrowOnUnknownHandles(resource, data)
             fieldType = new UnknownRawDataType(data.data.length, data.handles.length)
         }
 
-        if (fieldType === null) {
+        if (fieldType == null) {
             throw new MidlError(`Bad xunion ordinal: ${ordinal}`, ErrorCode.StrictUnionUnknownField)
         }
 
@@ -216,6 +250,56 @@ export class Encoder {
         this.encodeEnvelopePresent(envelopeOffset, depth, data, fieldType)
     }
 
+    /// Encodes an absent optional union: a zero ordinal and an empty envelope.
+    encodeAbsentUnion(offset: number) {
+        this.encodeUInt64(0n, offset)
+        this.encodeUInt64(0n, offset + 8)
+    }
+
+    encodeTable<T extends Table>(
+        value: T,
+        offset: number,
+        depth: number,
+        members: Record<number, MidlType<unknown, unknown[]>>,
+        resource: boolean
+    ) {
+        const fields = value.$fields
+
+        let maxOrdinal = 0
+        for (const [ordinal, field] of fields) {
+            if (field != null && ordinal > maxOrdinal) {
+                maxOrdinal = ordinal
+            }
+        }
+
+        // Vector header of envelopes
+        this.encodeUInt64(BigInt(maxOrdinal), offset)
+        this.encodeUInt64(ALLOC_PRESENT_U64, offset + 8)
+        if (maxOrdinal === 0) {
+            return
+        }
+
+        // Envelopes of absent fields are left zeroed.
+        const envelopesOffset = this.alloc(maxOrdinal * 8, depth + 1)
+        for (let ordinal = 1; ordinal <= maxOrdinal; ordinal++) {
+            const field = fields.get(ordinal)
+            if (field == null) {
+                continue
+            }
+
+            let fieldType = members[ordinal]
+            if (fieldType == null) {
+                if (!(field instanceof UnknownRawData)) {
+                    throw new MidlError(`Bad table ordinal: ${ordinal}`, ErrorCode.Unknown)
+                }
+                maybeThrowOnUnknownHandles(resource, field)
+                fieldType = new UnknownRawDataType(field.data.length, field.handles.length)
+            }
+
+            this.encodeEnvelopePresent(envelopesOffset + (ordinal - 1) * 8, depth + 1, field, fieldType)
+        }
+    }
+
     private nextOffset() {
         return this.#extent
     }
@@ -236,7 +320,7 @@ export class Encoder {
             this.encodeUInt16(ENVELOPE_INLINE_MARKER, offset + 6)
         } else {
             const initialNumHandles = this.countHandles()
-            const fieldOffset = this.alloc(fieldSize, depth)
+            const fieldOffset = this.alloc(fieldSize, depth + 1)
             fieldType.encode(this, field, fieldOffset, depth + 1)
             const numHandles = this.countHandles() - initialNumHandles
             const numBytes = this.nextOffset() - fieldOffset
@@ -302,35 +386,14 @@ export class Encoder {
         }
     }
 
-    /** Append bytes to the very end (out-of-line) of the buffer.*/
-    appendOutOfLineBytes(bytes: Uint8Array) {
+    /** Append bytes to the very end (out-of-line) of the buffer, zero padded to the alignment. */
+    appendOutOfLineBytes(bytes: Uint8Array, depth: number) {
         if (bytes.length == 0) {
             return
         }
 
-        const start = this.buffer.length
-        const end = this.buffer.length + roundUpToAlign(bytes.length, 8)
-
-        // Safety:
-        // - this.buffer is initially uninitialized when resized, but it is then
-        //   initialized by a later copy so it leaves this block initialized.
-        // - There is enough room for the 8 byte padding filler because end's
-        //   alignment is rounded up to 8 bytes and bytes.length != 0.
-        this.grow(end)
-
-        // Zero the last 8 bytes in the new buffer
-        this.buffer.fill(0, end - 8, end)
-
-        // Copy the new bytes to the end of the buffer
-        this.buffer.set(bytes, start)
-
-        //this.data.setBigUint64(end - 8, 0n, true)
-
-        //console.log(this.buffer.subarray(start), bytes)
-        //let padding_ptr = self.buf.get_unchecked_mut(end - 8);
-        //mem::transmute::<*mut u8, *mut u64>(padding_ptr).write_unaligned(0);
-
-        //copyNonOverlapping(bytes, this.buffer.subarray(start), start, bytes.length)
+        const offset = this.alloc(bytes.length, depth)
+        this.buffer.set(bytes, offset)
     }
 }
 
@@ -344,47 +407,39 @@ function encodeVectorFromBytes(
         // Two u64: (len, present)
         encoder.encodeUInt64(BigInt(slice.length), offset)
         encoder.encodeUInt64(ALLOC_PRESENT_U64, offset + 8)
-        encoder.checkRecursionDepth(recursionDepth + 1)
-        encoder.appendOutOfLineBytes(slice)
+        encoder.appendOutOfLineBytes(slice, recursionDepth + 1)
     } else {
-        encodeAbsentVector(encoder, offset, recursionDepth)
+        encodeAbsentVector(encoder, offset)
     }
 }
 
 /// Encode an missing vector-like component.
-function encodeAbsentVector(encoder: Encoder, offset: number, recursionDepth: number) {
+function encodeAbsentVector(encoder: Encoder, offset: number) {
     encoder.encodeUInt64(0n, offset)
     encoder.encodeUInt64(ALLOC_ABSENT_U64, offset + 8)
 }
 
+/// Decodes the length of a vector-like component, or `null` if it is absent.
 function decodeVectorHeader(decoder: Decoder, offset: number): number | null {
-    try {
-        const len = decoder.decodeUInt64(offset)
-        const present = decoder.decodeUInt64(offset + 8)
-
-        if (present === ALLOC_PRESENT_U64) {
-            const lenNumber = Number(len)
-            if (lenNumber <= Number.MAX_SAFE_INTEGER && lenNumber <= decoder.buffer.length) {
-                return lenNumber
-            } else {
-                throw "Error.OutOfRange"
-                //return Error.OutOfRange
-            }
-        } else if (present === ALLOC_ABSENT_U64) {
-            if (len === 0n) {
-                return null
-            } else {
-                throw "Error.UnexpectedNullRef"
-                //return Error.UnexpectedNullRef
-            }
-        } else {
-            throw "Error.InvalidPresenceIndicator"
-            //return Error.InvalidPresenceIndicator
+    const len = decoder.decodeUInt64(offset)
+    const present = decoder.decodeUInt64(offset + 8)
+
+    if (present === ALLOC_PRESENT_U64) {
+        const lenNumber = Number(len)
+        if (lenNumber > Number.MAX_SAFE_INTEGER || lenNumber > decoder.buffer.length) {
+            throw new MidlError(`Vector length ${len} exceeds the message size`, ErrorCode.TooFewBytes)
+        }
+        return lenNumber
+    }
+
+    if (present === ALLOC_ABSENT_U64) {
+        if (len !== 0n) {
+            throw new MidlError("Absent vector with non-zero length", ErrorCode.NonEmptyVectorWithNullBody)
         }
-    } catch {
-        throw "Error.InvalidPresenceIndicator"
-        //return Error.InvalidPresenceIndicator
+        return null
     }
+
+    throw new MidlError(`Invalid presence indicator: ${present}`, ErrorCode.InvalidPresenceIndicator)
 }
 
 type HandleInfo = any
@@ -396,24 +451,20 @@ export class Decoder {
     public data: DataView
 
     /// Buffer from which to read handles.
-    public handleInfos!: HandleInfo[]
+    public handleInfos: HandleInfo[]
 
     /// Next out of line block in buf.
-    private nextOutOfLine: number
-
-    #depth: number = 0
     #nextOffset = 0
     #nextHandle = 0
 
-    private constructor(bytes: Uint8Array, nextOutOfLine: number) {
+    private constructor(bytes: Uint8Array, handleInfos: HandleInfo[]) {
         this.buffer = bytes
-        this.nextOutOfLine = nextOutOfLine
-        this.data = new DataView(bytes.buffer)
+        this.handleInfos = handleInfos
+        this.data = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength)
     }
 
     static fromMessage(message: IncomingMessage): Decoder {
-        const decoder = new Decoder(message.buffer, 0)
-        return decoder
+        return new Decoder(message.buffer, message.handleInfos)
     }
 
     nextOffset() {
@@ -444,6 +495,7 @@ export class Decoder {
             throw new MidlError("Cannot access out of range memory", ErrorCode.TooFewBytes)
         }
 
+        this.checkPadding(result + size, align(size) - size)
         return result
     }
 
@@ -462,25 +514,59 @@ export class Decoder {
         }
     }
 
-    decodeString(offset: number): string {
-        //this.debugCheckBounds::<Self>(offset);
+    /// Decodes a string, or `null` if it is absent.
+    decodeString(offset: number, depth: number, maxLength?: number): string | null {
         const len = decodeVectorHeader(this, offset)
         if (len === null) {
+            return null
+        }
+
+        if (maxLength !== undefined && len > maxLength) {
+            throw new MidlError(`String of ${len} bytes exceeds the limit of ${maxLength}`, ErrorCode.StringTooLong)
+        }
+
+        if (len === 0) {
             return ""
         }
 
-        let decodedString = ""
+        const bytesOffset = this.claimBytes(len, depth + 1)
+        try {
+            return textDecoder.decode(this.buffer.subarray(bytesOffset, bytesOffset + len))
+        } catch (e) {
+            throw new MidlError("Received a string with invalid UTF-8", ErrorCode.Unknown)
+        }
+    }
+
+    /// Decodes a vector, or `null` if it is absent.
+    decodeVector<T>(
+        offset: number,
+        depth: number,
+        element: MidlType<T, Iterable<T>>,
+        maxLength?: number
+    ): T[] | null {
+        const len = decodeVectorHeader(this, offset)
+        if (len === null) {
+            return null
+        }
 
-        this.readOutOfLine(len, (decoder, offset) => {
-            const bytes = decoder.buffer.subarray(offset, offset + len)
-            console.log(bytes)
-            const utf8 = new TextDecoder("utf-8").decode(bytes)
-            decodedString = utf8
+        if (maxLength !== undefined && len > maxLength) {
+            throw new MidlError(
+                `Vector of ${len} elements exceeds the limit of ${maxLength}`,
+                ErrorCode.CountExceedsLimit
+            )
+        }
 
-            return true
-        })
+        if (len === 0) {
+            return []
+        }
 
-        return decodedString
+        const elementSize = element.inlineSize
+        const elementsOffset = this.claimBytes(len * elementSize, depth + 1)
+        const value = new Array<T>(len)
+        for (let i = 0; i < len; i++) {
+            value[i] = element.decode(this, elementsOffset + i * elementSize, depth + 1)
+        }
+        return value
     }
 
     private decodeEnvelopeContent<T>(
@@ -541,7 +627,7 @@ export class Decoder {
                     }
 
                     const fieldInlineSize = fieldType.inlineSize
-                    const fieldOffset = this.claimBytes(fieldInlineSize, depth)
+                    const fieldOffset = this.claimBytes(fieldInlineSize, depth + 1)
                     const claimedHandles = this.countClaimedHandles()
                     const field = fieldType.decodeObject(this, fieldOffset, fieldInlineSize, depth + 1)
                     const numBytesConsumed = this.nextOffset() - fieldOffset
@@ -674,6 +760,53 @@ export class Decoder {
         }
     }
 
+    decodeTable<T>(
+        offset: number,
+        depth: number,
+        members: Record<number, MidlType<unknown, unknown[]>>,
+        ctor: TableFactory<T>,
+        resource: boolean
+    ): T {
+        const len = decodeVectorHeader(this, offset)
+        if (len === null) {
+            throw new MidlError("Found null for a non-nullable type", ErrorCode.NonNullableTypeWithNullValue)
+        }
+
+        const fields = new Map<number, unknown>()
+        if (len === 0) {
+            return ctor(fields)
+        }
+
+        const envelopesOffset = this.claimBytes(len * 8, depth + 1)
+        for (let ordinal = 1; ordinal <= len; ordinal++) {
+            const envelopeOffset = envelopesOffset + (ordinal - 1) * 8
+            const header = this.decodeEnvelopeHeader(envelopeOffset)
+            const fieldType = members[ordinal]
+
+            if (fieldType == null) {
+                const unknownData = this.decodeEnvelopeContent(
+                    header,
+                    envelopeOffset,
+                    new UnknownRawDataType(header.numBytes, header.numHandles),
+                    depth + 1,
+                    false
+                )
+                if (unknownData != null) {
+                    maybeThrowOnUnknownHandles(resource, unknownData)
+                    fields.set(ordinal, unknownData)
+                }
+                continue
+            }
+
+            const field = this.decodeEnvelopeContent(header, envelopeOffset, fieldType, depth + 1, false)
+            if (field != null) {
+                fields.set(ordinal, field)
+            }
+        }
+
+        return ctor(fields)
+    }
+
     decodeBool(offset: number): boolean {
         switch (this.data.getUint8(offset)) {
             case 0:
@@ -696,64 +829,25 @@ export class Decoder {
     decodeFloat32 = (offset: number) => this.data.getFloat32(offset, true)
     decodeFloat64 = (offset: number) => this.data.getFloat64(offset, true)
 
-    /// Runs the provided closure inside an decoder modified to read out-of-line data.
-    readOutOfLine<R>(len: number, f: (dec: Decoder, offset: number) => R) {
-        const offset = this.nextOutOfLine
-        const alignedLen = roundUpToAlign(len, 8)
-        this.nextOutOfLine = this.nextOutOfLine + alignedLen
-
-        if (this.nextOutOfLine > this.buffer.length) {
-            throw new Error("OutOfRange")
-        }
-
-        if (this.nextOutOfLine < 8) {
-            throw new Error("Invalid nextOutOfLine value")
+    /// Decodes a top-level value of `type`, which must consume all of `buffer` and `handles`.
+    static decode<V>(buffer: Uint8Array, handles: HandleInfo[], type: midl.Decodable<V>): V {
+        const decoder = new Decoder(buffer, handles)
+        decoder.claimBytes(type.inlineSize, 0)
+        const value = type.decode(decoder, 0, 0)
+
+        if (decoder.countUnclaimedHandles() > 0) {
+            throw new MidlError(
+                `Message contains extra handles (unclaimed: ${decoder.countUnclaimedHandles()})`,
+                ErrorCode.TooManyHandles
+            )
         }
-
-        const lastU64Ptr = this.data.getBigUint64(this.nextOutOfLine - 8, true)
-
-        const padding = alignedLen - len
-        const mask = ~((0n << BigInt(padding * 8)) - 1n)
-
-        if (lastU64Ptr & BigInt(mask !== 0n)) {
-            console.log(lastU64Ptr, BigInt(mask !== 0n), "m:", mask, "p:", padding)
-            throw this.endOfBlockPaddingError(offset + len, this.nextOutOfLine)
-        }
-
-        this.#depth += 1
-        if (this.#depth > MAX_RECURSION) {
-            throw new Error("MaxRecursionDepth")
-        }
-
-        try {
-            const res = f(this, offset)
-            this.#depth -= 1
-            return res
-        } catch (e) {
-            this.#depth -= 1
-            throw e
-        }
-    }
-
-    /// Generates an error for bad padding bytes at the end of a block.
-    /// Assumes that it is already known that there is an error.
-    endOfBlockPaddingError(start: number, end: number): Error {
-        for (let i = start; i < end; i++) {
-            if (this.buffer[i] !== 0) {
-                return new Error(`NonZeroPadding: padding_start=${start}`)
-            }
-        }
-
-        throw new Error("Invalid padding bytes detected, but missing when generating error")
-    }
-
-    static decode<V>(buffer: Uint8Array, handles: any[], type: MidlType<V, V[]>): V {
-        const nextOutOfLine = roundUpToAlign(type.inlineSize, 8)
-        if (nextOutOfLine > buffer.length) {
-            throw new Error("Error::OutOfRange")
+        if (decoder.countUnclaimedBytes() > 0) {
+            throw new MidlError(
+                `Message contains unread bytes (unclaimed: ${decoder.countUnclaimedBytes()})`,
+                ErrorCode.TooManyBytes
+            )
         }
 
-        const decoder = new Decoder(buffer, nextOutOfLine)
-        return type.decode(decoder, 0, 0)
+        return value
     }
 }
@@ -1,4 +1,4 @@
-import { Decoder, Encoder, Enum, Struct, Union, align } from "."
+import { ALLOC_ABSENT_U64, ALLOC_PRESENT_U64, Bits, Decoder, Encoder, Enum, Struct, Table, Union, align } from "."
 import { MidlError, ErrorCode } from "./errors"
 import { Decodable, Encodable } from "./midl"
 import {
@@ -11,7 +11,7 @@ import {
 } from "@meshx-org/fiber-types"
 import * as fiber from "@meshx-org/fiber-ts"
 import { UnknownRawData } from "./unknown"
-import { UnionFactory } from "./union"
+import { TableFactory, UnionFactory } from "./union"
 import { InterfaceHandle, InterfaceRequest } from "./interface"
 
 const HANDLE_ABSENT = 0
@@ -53,20 +53,54 @@ export function maybeThrowOnUnknownHandles(resource: boolean, data: UnknownRawDa
 }
 
 export class EnumType<V extends Enum> extends SimpleMidlType<V> {
-    #underlying: SimpleMidlType<unknown>
+    #underlying: SimpleMidlType<number>
+    #create: (value: number) => V
 
-    constructor(underlying: SimpleMidlType<unknown>) {
+    /// `create` maps a decoded value to its member, throwing for unknown values of strict enums.
+    constructor(underlying: SimpleMidlType<number>, create: (value: number) => V) {
         super(underlying.inlineSize)
         this.#underlying = underlying
+        this.#create = create
     }
 
     /** @internal */
     encode(encoder: Encoder, value: V, offset: number, depth: number): void {
+        this.#underlying.encode(encoder, value.value, offset, depth)
+    }
+
+    decode(decoder: Decoder, offset: number, depth: number): V {
+        return this.#create(this.#underlying.decode(decoder, offset, depth))
+    }
+}
+
+export class BitsType<V extends Bits<T>, T extends number | bigint> extends SimpleMidlType<V> {
+    #underlying: SimpleMidlType<T>
+    #create: (value: T) => V
+    #strictMask: T | null
+
+    /// `strictMask` holds all known bits of strict bits and is `null` for flexible ones.
+    constructor(underlying: SimpleMidlType<T>, create: (value: T) => V, strictMask: T | null) {
+        super(underlying.inlineSize)
+        this.#underlying = underlying
+        this.#create = create
+        this.#strictMask = strictMask
+    }
+
+    #checkKnown(value: T) {
+        if (this.#strictMask !== null && (BigInt(value) & ~BigInt(this.#strictMask)) !== 0n) {
+            throw new MidlError(`Unknown bits in strict bits value: ${value}`, ErrorCode.InvalidBit)
+        }
+    }
+
+    encode(encoder: Encoder, value: V, offset: number, depth: number): void {
+        this.#checkKnown(value.$value)
         this.#underlying.encode(encoder, value.$value, offset, depth)
     }
 
-    decode(decoder: Decoder, offset: number): V {
-        throw new Error("Method not implemented.")
+    decode(decoder: Decoder, offset: number, depth: number): V {
+        const value = this.#underlying.decode(decoder, offset, depth)
+        this.#checkKnown(value)
+        return this.#create(value)
     }
 }
 
@@ -87,6 +121,37 @@ export class StructType<V extends Struct> extends SimpleMidlType<V> {
     }
 }
 
+/// An optional struct, encoded as a presence marker with the struct out-of-line.
+export class BoxType<V extends Struct> extends SimpleMidlType<V | null> {
+    constructor(private readonly struct: StructType<V>) {
+        super(8)
+    }
+
+    encode(encoder: Encoder, value: V | null, offset: number, depth: number): void {
+        if (value === null) {
+            encoder.encodeUInt64(ALLOC_ABSENT_U64, offset)
+            return
+        }
+
+        encoder.encodeUInt64(ALLOC_PRESENT_U64, offset)
+        const structOffset = encoder.alloc(this.struct.inlineSize, depth + 1)
+        this.struct.encode(encoder, value, structOffset, depth + 1)
+    }
+
+    decode(decoder: Decoder, offset: number, depth: number): V | null {
+        const presence = decoder.decodeUInt64(offset)
+        if (presence === ALLOC_ABSENT_U64) {
+            return null
+        }
+        if (presence !== ALLOC_PRESENT_U64) {
+            throw new MidlError(`Invalid presence indicator: ${presence}`, ErrorCode.InvalidPresenceIndicator)
+        }
+
+        const structOffset = decoder.claimBytes(this.struct.inlineSize, depth + 1)
+        return this.struct.decode(decoder, structOffset, depth + 1)
+    }
+}
+
 export class UnionType<V extends Union> extends SimpleMidlType<V> {
     constructor(
         private readonly members: Record<number, MidlType<any, any>>,
@@ -98,16 +163,7 @@ export class UnionType<V extends Union> extends SimpleMidlType<V> {
     }
 
     encode(encoder: Encoder, value: V, offset: number, depth: number): void {
-        encoder.encodeUnion(
-            value,
-            offset,
-            depth,
-            {
-                1: new UInt32Type(),
-            },
-            true,
-            false
-        )
+        encoder.encodeUnion(value, offset, depth, this.members, this.flexible, this.resource)
     }
 
     decode(decoder: Decoder, offset: number, depth: number): V {
@@ -119,17 +175,68 @@ export class UnionType<V extends Union> extends SimpleMidlType<V> {
     }
 }
 
+export class NullableUnionType<V extends Union> extends SimpleMidlType<V | null> {
+    constructor(
+        private readonly members: Record<number, MidlType<any, any>>,
+        private readonly ctor: UnionFactory<V>,
+        private readonly flexible: boolean,
+        private readonly resource: boolean
+    ) {
+        super(16)
+    }
+
+    encode(encoder: Encoder, value: V | null, offset: number, depth: number): void {
+        if (value === null) {
+            encoder.encodeAbsentUnion(offset)
+            return
+        }
+        encoder.encodeUnion(value, offset, depth, this.members, this.flexible, this.resource)
+    }
+
+    decode(decoder: Decoder, offset: number, depth: number): V | null {
+        return decoder.decodeUnion(offset, depth, this.members, this.ctor, this.flexible, this.resource)
+    }
+}
+
+export class TableType<V extends Table> extends SimpleMidlType<V> {
+    constructor(
+        private readonly members: Record<number, MidlType<any, any>>,
+        private readonly ctor: TableFactory<V>,
+        private readonly resource: boolean
+    ) {
+        super(16)
+    }
+
+    encode(encoder: Encoder, value: V, offset: number, depth: number): void {
+        encoder.encodeTable(value, offset, depth, this.members, this.resource)
+    }
+
+    decode(decoder: Decoder, offset: number, depth: number): V {
+        return decoder.decodeTable(offset, depth, this.members, this.ctor, this.resource)
+    }
+}
+
 export class UnknownRawDataType extends SimpleMidlType<UnknownRawData> {
     constructor(private numBytes: number, private numHandles: number) {
-        super(numBytes + numBytes * 4)
+        super(numBytes)
     }
 
-    encode(encoder: Encoder, value: any, offset: number, depth: number): void {
-        throw new Error("Method not implemented.")
+    encode(encoder: Encoder, value: UnknownRawData, offset: number, depth: number): void {
+        encoder.buffer.set(value.data, offset)
+        for (const handle of value.handles) {
+            encoder.addHandleDisposition(
+                new fiber.HandleDisposition(FX_HANDLE_OP_MOVE, handle, FX_OBJ_TYPE_NONE, FX_RIGHT_SAME_RIGHTS)
+            )
+        }
     }
 
     decode(decoder: Decoder, offset: number): UnknownRawData {
-        throw new Error("Method not implemented.")
+        const data = decoder.buffer.slice(offset, offset + this.numBytes)
+        const handles: fiber.Handle[] = []
+        for (let i = 0; i < this.numHandles; i++) {
+            handles.push(decoder.claimHandle().handle)
+        }
+        return new UnknownRawData(data, handles)
     }
 }
 
@@ -147,6 +254,20 @@ export class UInt8Type extends SimpleMidlType<number> {
     }
 }
 
+export class UInt16Type extends SimpleMidlType<number> {
+    constructor() {
+        super(2)
+    }
+
+    encode(encoder: Encoder, value: number, offset: number, depth: number) {
+        encoder.encodeUInt16(value, offset)
+    }
+
+    decode(decoder: Decoder, offset: number) {
+        return decoder.decodeUInt16(offset)
+    }
+}
+
 export class UInt32Type extends SimpleMidlType<number> {
     constructor() {
         super(4)
@@ -203,6 +324,20 @@ export class Int8Type extends SimpleMidlType<number> {
     }
 }
 
+export class Int16Type extends SimpleMidlType<number> {
+    constructor() {
+        super(2)
+    }
+
+    encode(encoder: Encoder, value: number, offset: number, depth: number) {
+        encoder.encodeInt16(value, offset)
+    }
+
+    decode(decoder: Decoder, offset: number) {
+        return decoder.decodeInt16(offset)
+    }
+}
+
 export class Int32Type extends SimpleMidlType<number> {
     constructor() {
         super(4)
@@ -231,17 +366,122 @@ export class Int64Type extends SimpleMidlType<bigint> {
     }
 }
 
-export class StringType extends SimpleMidlType<string> {
+export class Float32Type extends SimpleMidlType<number> {
     constructor() {
+        super(4)
+    }
+
+    encode(encoder: Encoder, value: number, offset: number, depth: number) {
+        encoder.encodeFloat32(value, offset)
+    }
+
+    decode(decoder: Decoder, offset: number) {
+        return decoder.decodeFloat32(offset)
+    }
+}
+
+export class Float64Type extends SimpleMidlType<number> {
+    constructor() {
+        super(8)
+    }
+
+    encode(encoder: Encoder, value: number, offset: number, depth: number) {
+        encoder.encodeFloat64(value, offset)
+    }
+
+    decode(decoder: Decoder, offset: number) {
+        return decoder.decodeFloat64(offset)
+    }
+}
+
+export class StringType extends SimpleMidlType<string> {
+    constructor(private readonly maxLength?: number) {
         super(16)
     }
 
     encode(encoder: Encoder, value: string, offset: number, depth: number): void {
-        encoder.encodeString(value, offset, depth)
+        encoder.encodeString(value, offset, depth, this.maxLength)
     }
 
     decode(decoder: Decoder, offset: number, depth: number) {
-        return decoder.decodeString(offset)
+        const value = decoder.decodeString(offset, depth, this.maxLength)
+        if (value === null) {
+            throw new MidlError("Found null for a non-nullable type", ErrorCode.NonNullableTypeWithNullValue)
+        }
+        return value
+    }
+}
+
+export class NullableStringType extends SimpleMidlType<string | null> {
+    constructor(private readonly maxLength?: number) {
+        super(16)
+    }
+
+    encode(encoder: Encoder, value: string | null, offset: number, depth: number): void {
+        encoder.encodeString(value, offset, depth, this.maxLength)
+    }
+
+    decode(decoder: Decoder, offset: number, depth: number) {
+        return decoder.decodeString(offset, depth, this.maxLength)
+    }
+}
+
+export class VectorType<T> extends SimpleMidlType<T[]> {
+    constructor(private readonly element: MidlType<T, any>, private readonly maxLength?: number) {
+        super(16)
+    }
+
+    encode(encoder: Encoder, value: T[], offset: number, depth: number): void {
+        encoder.encodeVector(value, offset, depth, this.element, this.maxLength)
+    }
+
+    decode(decoder: Decoder, offset: number, depth: number): T[] {
+        const value = decoder.decodeVector(offset, depth, this.element, this.maxLength)
+        if (value === null) {
+            throw new MidlError("Found null for a non-nullable type", ErrorCode.NonNullableTypeWithNullValue)
+        }
+        return value
+    }
+}
+
+export class NullableVectorType<T> extends SimpleMidlType<T[] | null> {
+    constructor(private readonly element: MidlType<T, any>, private readonly maxLength?: number) {
+        super(16)
+    }
+
+    encode(encoder: Encoder, value: T[] | null, offset: number, depth: number): void {
+        encoder.encodeVector(value, offset, depth, this.element, this.maxLength)
+    }
+
+    decode(decoder: Decoder, offset: number, depth: number): T[] | null {
+        return decoder.decodeVector(offset, depth, this.element, this.maxLength)
+    }
+}
+
+/// A fixed-size array, encoded inline without a header.
+export class ArrayType<T> extends SimpleMidlType<T[]> {
+    constructor(private readonly element: MidlType<T, any>, private readonly count: number) {
+        super(element.inlineSize * count)
+    }
+
+    encode(encoder: Encoder, value: T[], offset: number, depth: number): void {
+        if (value.length !== this.count) {
+            throw new MidlError(`Expected an array of ${this.count} elements, got ${value.length}`, ErrorCode.Unknown)
+        }
+
+        const elementSize = this.element.inlineSize
+        for (let i = 0; i < this.count; i++) {
+            this.element.encode(encoder, value[i], offset + i * elementSize, depth)
+        }
+    }
+
+    decode(decoder: Decoder, offset: number, depth: number): T[] {
+        const elementSize = this.element.inlineSize
+        const value = new Array<T>(this.count)
+        for (let i = 0; i < this.count; i++) {
+            value[i] = this.element.decode(decoder, offset + i * elementSize, depth)
+        }
+        return value
     }
 }
 
@@ -387,6 +627,25 @@ abstract class _BaseHandleType<W> extends SimpleMidlType<W> {
     }
 }
 
+export class NullableHandleType extends SimpleMidlType<fiber.Handle | null> {
+    constructor(private readonly objectType: number, private readonly rights: number) {
+        super(4)
+    }
+
+    override encode(encoder: Encoder, value: fiber.Handle | null, offset: number, depth: number) {
+        encodeHandle(
+            encoder,
+            value === null ? null : new fiber.HandleDisposition(FX_HANDLE_OP_MOVE, value, this.objectType, this.rights),
+            offset,
+            true
+        )
+    }
+
+    override decode(decoder: Decoder, offset: number, depth: number) {
+        return decodeNullableHandle(decoder, offset, this.objectType, this.rights)
+    }
+}
+
 export class HandleType extends _BaseHandleType<fiber.Handle> {
     constructor(objectType: number, rights: number) {
         super(objectType, rights)
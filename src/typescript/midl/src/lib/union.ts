@@ -1 +1,2 @@
-export type UnionFactory<T> = (index: number, data: object) => T
\ No newline at end of file
+export type UnionFactory<T> = (index: number, data: object) => T
+export type TableFactory<T> = (fields: Map<number, unknown>) => T
@@ -0,0 +1,1123 @@
+// WARNING: This file is machine generated by gidl. Do not edit.
+// Regenerate it with `cargo run -p gidl -- --language typescript`, see tools/midl/gidl.
+
+import * as midl from ".."
+
+function expectError(f: () => unknown, code: midl.ErrorCode) {
+    try {
+        f()
+    } catch (e) {
+        expect((e as midl.MidlError).code).toEqual(code)
+        return
+    }
+    throw new Error(`expected error ${midl.ErrorCode[code]}`)
+}
+
+class Arrays implements midl.Struct {
+    static #fieldType0 = new midl.ArrayType(new midl.UInt16Type(), 3)
+    static #fieldType1 = new midl.ArrayType(new midl.StringType(), 2)
+
+    constructor(
+        public a: number[],
+        public strings: string[],
+    ) {}
+
+    $encode(encoder: midl.Encoder, offset: number, depth: number): void {
+        Arrays.#fieldType0.encode(encoder, this.a, offset + 0, depth)
+        Arrays.#fieldType1.encode(encoder, this.strings, offset + 8, depth)
+    }
+
+    static $decode(decoder: midl.Decoder, offset: number, depth: number) {
+        decoder.checkPadding(offset + 6, 2)
+        return new Arrays(
+            Arrays.#fieldType0.decode(decoder, offset + 0, depth),
+            Arrays.#fieldType1.decode(decoder, offset + 8, depth),
+        )
+    }
+}
+
+const _ArraysType = new midl.StructType<Arrays>(40, Arrays.$decode)
+
+class Bools implements midl.Struct {
+    static #fieldType0 = new midl.BoolType()
+    static #fieldType1 = new midl.BoolType()
+
+    constructor(
+        public a: boolean,
+        public b: boolean,
+    ) {}
+
+    $encode(encoder: midl.Encoder, offset: number, depth: number): void {
+        Bools.#fieldType0.encode(encoder, this.a, offset + 0, depth)
+        Bools.#fieldType1.encode(encoder, this.b, offset + 1, depth)
+    }
+
+    static $decode(decoder: midl.Decoder, offset: number, depth: number) {
+        return new Bools(
+            Bools.#fieldType0.decode(decoder, offset + 0, depth),
+            Bools.#fieldType1.decode(decoder, offset + 1, depth),
+        )
+    }
+}
+
+const _BoolsType = new midl.StructType<Bools>(2, Bools.$decode)
+
+class Inner implements midl.Struct {
+    static #fieldType0 = new midl.UInt8Type()
+    static #fieldType1 = new midl.UInt32Type()
+
+    constructor(
+        public a: number,
+        public b: number,
+    ) {}
+
+    $encode(encoder: midl.Encoder, offset: number, depth: number): void {
+        Inner.#fieldType0.encode(encoder, this.a, offset + 0, depth)
+        Inner.#fieldType1.encode(encoder, this.b, offset + 4, depth)
+    }
+
+    static $decode(decoder: midl.Decoder, offset: number, depth: number) {
+        decoder.checkPadding(offset + 1, 3)
+        return new Inner(
+            Inner.#fieldType0.decode(decoder, offset + 0, depth),
+            Inner.#fieldType1.decode(decoder, offset + 4, depth),
+        )
+    }
+}
+
+const _InnerType = new midl.StructType<Inner>(8, Inner.$decode)
+
+class Boxes implements midl.Struct {
+    static #fieldType0 = new midl.BoxType(_InnerType)
+    static #fieldType1 = new midl.BoxType(_InnerType)
+
+    constructor(
+        public present: Inner | null,
+        public absent: Inner | null,
+    ) {}
+
+    $encode(encoder: midl.Encoder, offset: number, depth: number): void {
+        Boxes.#fieldType0.encode(encoder, this.present, offset + 0, depth)
+        Boxes.#fieldType1.encode(encoder, this.absent, offset + 8, depth)
+    }
+
+    static $decode(decoder: midl.Decoder, offset: number, depth: number) {
+        return new Boxes(
+            Boxes.#fieldType0.decode(decoder, offset + 0, depth),
+            Boxes.#fieldType1.decode(decoder, offset + 8, depth),
+        )
+    }
+}
+
+const _BoxesType = new midl.StructType<Boxes>(16, Boxes.$decode)
+
+class FlexibleChoice extends midl.Union {
+    #ordinal: number
+    #data: any
+
+    constructor(ordinal: number, data: any) {
+        super()
+        this.#ordinal = ordinal
+        this.#data = data
+    }
+
+    static withSmall(value: number): FlexibleChoice {
+        return new FlexibleChoice(1, value)
+    }
+
+    override get $ordinal(): number {
+        return this.#ordinal
+    }
+
+    override get $data(): unknown {
+        return this.#data
+    }
+
+    static ctor(ordinal: number, data: object): FlexibleChoice {
+        return new FlexibleChoice(ordinal, data)
+    }
+}
+
+const _FlexibleChoiceMembers = {
+    1: new midl.UInt32Type(),
+}
+
+const _FlexibleChoiceType = new midl.UnionType<FlexibleChoice>(_FlexibleChoiceMembers, FlexibleChoice.ctor, true, false)
+
+const _FlexibleChoiceNullableType = new midl.NullableUnionType<FlexibleChoice>(_FlexibleChoiceMembers, FlexibleChoice.ctor, true, false)
+
+class FlexibleHolder implements midl.Struct {
+    static #fieldType0 = _FlexibleChoiceType
+
+    constructor(
+        public choice: FlexibleChoice,
+    ) {}
+
+    $encode(encoder: midl.Encoder, offset: number, depth: number): void {
+        FlexibleHolder.#fieldType0.encode(encoder, this.choice, offset + 0, depth)
+    }
+
+    static $decode(decoder: midl.Decoder, offset: number, depth: number) {
+        return new FlexibleHolder(
+            FlexibleHolder.#fieldType0.decode(decoder, offset + 0, depth),
+        )
+    }
+}
+
+const _FlexibleHolderType = new midl.StructType<FlexibleHolder>(16, FlexibleHolder.$decode)
+
+class Floats implements midl.Struct {
+    static #fieldType0 = new midl.Float32Type()
+    static #fieldType1 = new midl.Float64Type()
+
+    constructor(
+        public f32: number,
+        public f64: number,
+    ) {}
+
+    $encode(encoder: midl.Encoder, offset: number, depth: number): void {
+        Floats.#fieldType0.encode(encoder, this.f32, offset + 0, depth)
+        Floats.#fieldType1.encode(encoder, this.f64, offset + 8, depth)
+    }
+
+    static $decode(decoder: midl.Decoder, offset: number, depth: number) {
+        decoder.checkPadding(offset + 4, 4)
+        return new Floats(
+            Floats.#fieldType0.decode(decoder, offset + 0, depth),
+            Floats.#fieldType1.decode(decoder, offset + 8, depth),
+        )
+    }
+}
+
+const _FloatsType = new midl.StructType<Floats>(16, Floats.$decode)
+
+class Ints implements midl.Struct {
+    static #fieldType0 = new midl.Int8Type()
+    static #fieldType1 = new midl.Int16Type()
+    static #fieldType2 = new midl.Int32Type()
+    static #fieldType3 = new midl.Int64Type()
+    static #fieldType4 = new midl.UInt8Type()
+    static #fieldType5 = new midl.UInt16Type()
+    static #fieldType6 = new midl.UInt32Type()
+    static #fieldType7 = new midl.UInt64Type()
+
+    constructor(
+        public i8: number,
+        public i16: number,
+        public i32: number,
+        public i64: bigint,
+        public u8: number,
+        public u16: number,
+        public u32: number,
+        public u64: bigint,
+    ) {}
+
+    $encode(encoder: midl.Encoder, offset: number, depth: number): void {
+        Ints.#fieldType0.encode(encoder, this.i8, offset + 0, depth)
+        Ints.#fieldType1.encode(encoder, this.i16, offset + 2, depth)
+        Ints.#fieldType2.encode(encoder, this.i32, offset + 4, depth)
+        Ints.#fieldType3.encode(encoder, this.i64, offset + 8, depth)
+        Ints.#fieldType4.encode(encoder, this.u8, offset + 16, depth)
+        Ints.#fieldType5.encode(encoder, this.u16, offset + 18, depth)
+        Ints.#fieldType6.encode(encoder, this.u32, offset + 20, depth)
+        Ints.#fieldType7.encode(encoder, this.u64, offset + 24, depth)
+    }
+
+    static $decode(decoder: midl.Decoder, offset: number, depth: number) {
+        decoder.checkPadding(offset + 1, 1)
+        decoder.checkPadding(offset + 17, 1)
+        return new Ints(
+            Ints.#fieldType0.decode(decoder, offset + 0, depth),
+            Ints.#fieldType1.decode(decoder, offset + 2, depth),
+            Ints.#fieldType2.decode(decoder, offset + 4, depth),
+            Ints.#fieldType3.decode(decoder, offset + 8, depth),
+            Ints.#fieldType4.decode(decoder, offset + 16, depth),
+            Ints.#fieldType5.decode(decoder, offset + 18, depth),
+            Ints.#fieldType6.decode(decoder, offset + 20, depth),
+            Ints.#fieldType7.decode(decoder, offset + 24, depth),
+        )
+    }
+}
+
+const _IntsType = new midl.StructType<Ints>(32, Ints.$decode)
+
+class Outer implements midl.Struct {
+    static #fieldType0 = _InnerType
+    static #fieldType1 = new midl.UInt16Type()
+
+    constructor(
+        public inner: Inner,
+        public tail: number,
+    ) {}
+
+    $encode(encoder: midl.Encoder, offset: number, depth: number): void {
+        Outer.#fieldType0.encode(encoder, this.inner, offset + 0, depth)
+        Outer.#fieldType1.encode(encoder, this.tail, offset + 8, depth)
+    }
+
+    static $decode(decoder: midl.Decoder, offset: number, depth: number) {
+        decoder.checkPadding(offset + 10, 2)
+        return new Outer(
+            Outer.#fieldType0.decode(decoder, offset + 0, depth),
+            Outer.#fieldType1.decode(decoder, offset + 8, depth),
+        )
+    }
+}
+
+const _OuterType = new midl.StructType<Outer>(12, Outer.$decode)
+
+class Settings extends midl.Table {
+    constructor(
+        public a: number | null = null,
+        public b: bigint | null = null,
+        public name: string | null = null,
+        public $unknownData: Map<number, midl.UnknownRawData> = new Map(),
+    ) {
+        super()
+    }
+
+    override get $fields(): Map<number, unknown> {
+        const fields = new Map<number, unknown>(this.$unknownData)
+        if (this.a !== null) fields.set(1, this.a)
+        if (this.b !== null) fields.set(2, this.b)
+        if (this.name !== null) fields.set(4, this.name)
+        return fields
+    }
+
+    static ctor(fields: Map<number, unknown>): Settings {
+        return new Settings(
+            (fields.get(1) ?? null) as number | null,
+            (fields.get(2) ?? null) as bigint | null,
+            (fields.get(4) ?? null) as string | null,
+        )
+    }
+}
+
+const _SettingsType = new midl.TableType<Settings>(
+    {
+        1: new midl.UInt32Type(),
+        2: new midl.UInt64Type(),
+        4: new midl.StringType(),
+    },
+    Settings.ctor,
+    false
+)
+
+class StrictChoice extends midl.Union {
+    #ordinal: number
+    #data: any
+
+    constructor(ordinal: number, data: any) {
+        super()
+        this.#ordinal = ordinal
+        this.#data = data
+    }
+
+    static withSmall(value: number): StrictChoice {
+        return new StrictChoice(1, value)
+    }
+
+    static withBig(value: bigint): StrictChoice {
+        return new StrictChoice(2, value)
+    }
+
+    static withText(value: string): StrictChoice {
+        return new StrictChoice(3, value)
+    }
+
+    override get $ordinal(): number {
+        return this.#ordinal
+    }
+
+    override get $data(): unknown {
+        return this.#data
+    }
+
+    static ctor(ordinal: number, data: object): StrictChoice {
+        return new StrictChoice(ordinal, data)
+    }
+}
+
+const _StrictChoiceMembers = {
+    1: new midl.UInt32Type(),
+    2: new midl.UInt64Type(),
+    3: new midl.StringType(),
+}
+
+const _StrictChoiceType = new midl.UnionType<StrictChoice>(_StrictChoiceMembers, StrictChoice.ctor, false, false)
+
+const _StrictChoiceNullableType = new midl.NullableUnionType<StrictChoice>(_StrictChoiceMembers, StrictChoice.ctor, false, false)
+
+class Strings implements midl.Struct {
+    static #fieldType0 = new midl.StringType()
+    static #fieldType1 = new midl.StringType(3)
+    static #fieldType2 = new midl.NullableStringType()
+
+    constructor(
+        public s: string,
+        public bounded: string,
+        public opt: string | null,
+    ) {}
+
+    $encode(encoder: midl.Encoder, offset: number, depth: number): void {
+        Strings.#fieldType0.encode(encoder, this.s, offset + 0, depth)
+        Strings.#fieldType1.encode(encoder, this.bounded, offset + 16, depth)
+        Strings.#fieldType2.encode(encoder, this.opt, offset + 32, depth)
+    }
+
+    static $decode(decoder: midl.Decoder, offset: number, depth: number) {
+        return new Strings(
+            Strings.#fieldType0.decode(decoder, offset + 0, depth),
+            Strings.#fieldType1.decode(decoder, offset + 16, depth),
+            Strings.#fieldType2.decode(decoder, offset + 32, depth),
+        )
+    }
+}
+
+const _StringsType = new midl.StructType<Strings>(48, Strings.$decode)
+
+class Tables implements midl.Struct {
+    static #fieldType0 = _SettingsType
+
+    constructor(
+        public settings: Settings,
+    ) {}
+
+    $encode(encoder: midl.Encoder, offset: number, depth: number): void {
+        Tables.#fieldType0.encode(encoder, this.settings, offset + 0, depth)
+    }
+
+    static $decode(decoder: midl.Decoder, offset: number, depth: number) {
+        return new Tables(
+            Tables.#fieldType0.decode(decoder, offset + 0, depth),
+        )
+    }
+}
+
+const _TablesType = new midl.StructType<Tables>(16, Tables.$decode)
+
+class Unions implements midl.Struct {
+    static #fieldType0 = _StrictChoiceType
+    static #fieldType1 = _StrictChoiceNullableType
+
+    constructor(
+        public choice: StrictChoice,
+        public opt: StrictChoice | null,
+    ) {}
+
+    $encode(encoder: midl.Encoder, offset: number, depth: number): void {
+        Unions.#fieldType0.encode(encoder, this.choice, offset + 0, depth)
+        Unions.#fieldType1.encode(encoder, this.opt, offset + 16, depth)
+    }
+
+    static $decode(decoder: midl.Decoder, offset: number, depth: number) {
+        return new Unions(
+            Unions.#fieldType0.decode(decoder, offset + 0, depth),
+            Unions.#fieldType1.decode(decoder, offset + 16, depth),
+        )
+    }
+}
+
+const _UnionsType = new midl.StructType<Unions>(32, Unions.$decode)
+
+class Vectors implements midl.Struct {
+    static #fieldType0 = new midl.VectorType(new midl.UInt8Type())
+    static #fieldType1 = new midl.VectorType(new midl.UInt16Type(), 2)
+    static #fieldType2 = new midl.VectorType(new midl.StringType())
+    static #fieldType3 = new midl.NullableVectorType(new midl.UInt32Type())
+
+    constructor(
+        public data: number[],
+        public bounded: number[],
+        public nested: string[],
+        public opt: number[] | null,
+    ) {}
+
+    $encode(encoder: midl.Encoder, offset: number, depth: number): void {
+        Vectors.#fieldType0.encode(encoder, this.data, offset + 0, depth)
+        Vectors.#fieldType1.encode(encoder, this.bounded, offset + 16, depth)
+        Vectors.#fieldType2.encode(encoder, this.nested, offset + 32, depth)
+        Vectors.#fieldType3.encode(encoder, this.opt, offset + 48, depth)
+    }
+
+    static $decode(decoder: midl.Decoder, offset: number, depth: number) {
+        return new Vectors(
+            Vectors.#fieldType0.decode(decoder, offset + 0, depth),
+            Vectors.#fieldType1.decode(decoder, offset + 16, depth),
+            Vectors.#fieldType2.decode(decoder, offset + 32, depth),
+            Vectors.#fieldType3.decode(decoder, offset + 48, depth),
+        )
+    }
+}
+
+const _VectorsType = new midl.StructType<Vectors>(64, Vectors.$decode)
+
+describe("MIDL conformance", () => {
+    // primitives.gidl:24
+    it("Bools encode", () => {
+        const value = new Bools(true, false)
+        const expected = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _BoolsType)).toEqual(expected)
+    })
+
+    it("Bools decode", () => {
+        const bytes = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _BoolsType)
+        expect(value).toEqual(new Bools(true, false))
+        expect(midl.Encoder.encode([], value, _BoolsType)).toEqual(bytes)
+    })
+
+    // primitives.gidl:31
+    it("IntsMin encode", () => {
+        const value = new Ints(-128, -32768, -2147483648, -9223372036854775808n, 0, 0, 0, 0n)
+        const expected = new Uint8Array([
+            0x80, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x80,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _IntsType)).toEqual(expected)
+    })
+
+    it("IntsMin decode", () => {
+        const bytes = new Uint8Array([
+            0x80, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x80,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _IntsType)
+        expect(value).toEqual(new Ints(-128, -32768, -2147483648, -9223372036854775808n, 0, 0, 0, 0n))
+        expect(midl.Encoder.encode([], value, _IntsType)).toEqual(bytes)
+    })
+
+    // primitives.gidl:44
+    it("IntsMax encode", () => {
+        const value = new Ints(127, 32767, 2147483647, 9223372036854775807n, 255, 65535, 4294967295, 18446744073709551615n)
+        const expected = new Uint8Array([
+            0x7f, 0x00, 0xff, 0x7f, 0xff, 0xff, 0xff, 0x7f,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f,
+            0xff, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        ])
+        expect(midl.Encoder.encode([], value, _IntsType)).toEqual(expected)
+    })
+
+    it("IntsMax decode", () => {
+        const bytes = new Uint8Array([
+            0x7f, 0x00, 0xff, 0x7f, 0xff, 0xff, 0xff, 0x7f,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f,
+            0xff, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _IntsType)
+        expect(value).toEqual(new Ints(127, 32767, 2147483647, 9223372036854775807n, 255, 65535, 4294967295, 18446744073709551615n))
+        expect(midl.Encoder.encode([], value, _IntsType)).toEqual(bytes)
+    })
+
+    // primitives.gidl:57
+    it("Floats encode", () => {
+        const value = new Floats(1.5, -2.5)
+        const expected = new Uint8Array([
+            0x00, 0x00, 0xc0, 0x3f, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0xc0,
+        ])
+        expect(midl.Encoder.encode([], value, _FloatsType)).toEqual(expected)
+    })
+
+    it("Floats decode", () => {
+        const bytes = new Uint8Array([
+            0x00, 0x00, 0xc0, 0x3f, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0xc0,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _FloatsType)
+        expect(value).toEqual(new Floats(1.5, -2.5))
+        expect(midl.Encoder.encode([], value, _FloatsType)).toEqual(bytes)
+    })
+
+    // primitives.gidl:65
+    it("BoolInvalid decode failure", () => {
+        const bytes = new Uint8Array([
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _BoolsType), midl.ErrorCode.InvalidBoolean)
+    })
+
+    // primitives.gidl:73
+    it("IntsNonZeroPadding decode failure", () => {
+        const bytes = new Uint8Array([
+            0x01, 0x01, 0x02, 0x00, 0x03, 0x00, 0x00, 0x00,
+            0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x05, 0x00, 0x06, 0x00, 0x07, 0x00, 0x00, 0x00,
+            0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _IntsType), midl.ErrorCode.InvalidPaddingByte)
+    })
+
+    // primitives.gidl:84
+    it("BoolsTooFewBytes decode failure", () => {
+        const bytes = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _BoolsType), midl.ErrorCode.TooFewBytes)
+    })
+
+    // primitives.gidl:92
+    it("BoolsTooManyBytes decode failure", () => {
+        const bytes = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _BoolsType), midl.ErrorCode.TooManyBytes)
+    })
+
+    // strings_vectors.gidl:21
+    it("Strings encode", () => {
+        const value = new Strings("hello", "abc", null)
+        const expected = new Uint8Array([
+            0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x00, 0x00, 0x00,
+            0x61, 0x62, 0x63, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _StringsType)).toEqual(expected)
+    })
+
+    it("Strings decode", () => {
+        const bytes = new Uint8Array([
+            0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x00, 0x00, 0x00,
+            0x61, 0x62, 0x63, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _StringsType)
+        expect(value).toEqual(new Strings("hello", "abc", null))
+        expect(midl.Encoder.encode([], value, _StringsType)).toEqual(bytes)
+    })
+
+    // strings_vectors.gidl:32
+    it("StringsPresentOptional encode", () => {
+        const value = new Strings("", "", "héllo")
+        const expected = new Uint8Array([
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x68, 0xc3, 0xa9, 0x6c, 0x6c, 0x6f, 0x00, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _StringsType)).toEqual(expected)
+    })
+
+    it("StringsPresentOptional decode", () => {
+        const bytes = new Uint8Array([
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x68, 0xc3, 0xa9, 0x6c, 0x6c, 0x6f, 0x00, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _StringsType)
+        expect(value).toEqual(new Strings("", "", "héllo"))
+        expect(midl.Encoder.encode([], value, _StringsType)).toEqual(bytes)
+    })
+
+    // strings_vectors.gidl:42
+    it("StringTooLong encode failure", () => {
+        const value = new Strings("", "abcd", null)
+        expectError(() => midl.Encoder.encode([], value, _StringsType), midl.ErrorCode.StringTooLong)
+    })
+
+    // strings_vectors.gidl:47
+    it("StringTooLongDecode decode failure", () => {
+        const bytes = new Uint8Array([
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x61, 0x62, 0x63, 0x64, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _StringsType), midl.ErrorCode.StringTooLong)
+    })
+
+    // strings_vectors.gidl:58
+    it("StringAbsent decode failure", () => {
+        const bytes = new Uint8Array([
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _StringsType), midl.ErrorCode.NonNullableTypeWithNullValue)
+    })
+
+    // strings_vectors.gidl:68
+    it("StringInvalidPresence decode failure", () => {
+        const bytes = new Uint8Array([
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _StringsType), midl.ErrorCode.InvalidPresenceIndicator)
+    })
+
+    // strings_vectors.gidl:78
+    it("StringNonZeroPadding decode failure", () => {
+        const bytes = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x61, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _StringsType), midl.ErrorCode.InvalidPaddingByte)
+    })
+
+    // strings_vectors.gidl:89
+    it("Vectors encode", () => {
+        const value = new Vectors([1, 2, 3], [4, 5], ["a"], [6])
+        const expected = new Uint8Array([
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x01, 0x02, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x04, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x61, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _VectorsType)).toEqual(expected)
+    })
+
+    it("Vectors decode", () => {
+        const bytes = new Uint8Array([
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x01, 0x02, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x04, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x61, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _VectorsType)
+        expect(value).toEqual(new Vectors([1, 2, 3], [4, 5], ["a"], [6]))
+        expect(midl.Encoder.encode([], value, _VectorsType)).toEqual(bytes)
+    })
+
+    // strings_vectors.gidl:104
+    it("VectorsEmpty encode", () => {
+        const value = new Vectors([], [], [], null)
+        const expected = new Uint8Array([
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _VectorsType)).toEqual(expected)
+    })
+
+    it("VectorsEmpty decode", () => {
+        const bytes = new Uint8Array([
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _VectorsType)
+        expect(value).toEqual(new Vectors([], [], [], null))
+        expect(midl.Encoder.encode([], value, _VectorsType)).toEqual(bytes)
+    })
+
+    // strings_vectors.gidl:114
+    it("VectorTooLong encode failure", () => {
+        const value = new Vectors([], [1, 2, 3], [], null)
+        expectError(() => midl.Encoder.encode([], value, _VectorsType), midl.ErrorCode.CountExceedsLimit)
+    })
+
+    // strings_vectors.gidl:119
+    it("VectorTooLongDecode decode failure", () => {
+        const bytes = new Uint8Array([
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _VectorsType), midl.ErrorCode.CountExceedsLimit)
+    })
+
+    // strings_vectors.gidl:131
+    it("VectorNonEmptyNullBody decode failure", () => {
+        const bytes = new Uint8Array([
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _VectorsType), midl.ErrorCode.NonEmptyVectorWithNullBody)
+    })
+
+    // strings_vectors.gidl:142
+    it("Arrays encode", () => {
+        const value = new Arrays([1, 2, 3], ["x", "yz"])
+        const expected = new Uint8Array([
+            0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x78, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x79, 0x7a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _ArraysType)).toEqual(expected)
+    })
+
+    it("Arrays decode", () => {
+        const bytes = new Uint8Array([
+            0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x78, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x79, 0x7a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _ArraysType)
+        expect(value).toEqual(new Arrays([1, 2, 3], ["x", "yz"]))
+        expect(midl.Encoder.encode([], value, _ArraysType)).toEqual(bytes)
+    })
+
+    // structs.gidl:18
+    it("NestedStruct encode", () => {
+        const value = new Outer(new Inner(1, 2), 3)
+        const expected = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _OuterType)).toEqual(expected)
+    })
+
+    it("NestedStruct decode", () => {
+        const bytes = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _OuterType)
+        expect(value).toEqual(new Outer(new Inner(1, 2), 3))
+        expect(midl.Encoder.encode([], value, _OuterType)).toEqual(bytes)
+    })
+
+    // structs.gidl:26
+    it("NestedStructPadding decode failure", () => {
+        const bytes = new Uint8Array([
+            0x01, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00,
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _OuterType), midl.ErrorCode.InvalidPaddingByte)
+    })
+
+    // structs.gidl:35
+    it("TrailingStructPadding decode failure", () => {
+        const bytes = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _OuterType), midl.ErrorCode.InvalidPaddingByte)
+    })
+
+    // structs.gidl:44
+    it("Boxes encode", () => {
+        const value = new Boxes(new Inner(7, 8), null)
+        const expected = new Uint8Array([
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x07, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _BoxesType)).toEqual(expected)
+    })
+
+    it("Boxes decode", () => {
+        const bytes = new Uint8Array([
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x07, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _BoxesType)
+        expect(value).toEqual(new Boxes(new Inner(7, 8), null))
+        expect(midl.Encoder.encode([], value, _BoxesType)).toEqual(bytes)
+    })
+
+    // structs.gidl:53
+    it("BoxInvalidPresence decode failure", () => {
+        const bytes = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _BoxesType), midl.ErrorCode.InvalidPresenceIndicator)
+    })
+
+    // unions_tables.gidl:32
+    it("UnionInline encode", () => {
+        const value = new Unions(StrictChoice.withSmall(7), null)
+        const expected = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _UnionsType)).toEqual(expected)
+    })
+
+    it("UnionInline decode", () => {
+        const bytes = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _UnionsType)
+        expect(value).toEqual(new Unions(StrictChoice.withSmall(7), null))
+        expect(midl.Encoder.encode([], value, _UnionsType)).toEqual(bytes)
+    })
+
+    // unions_tables.gidl:40
+    it("UnionOutOfLine encode", () => {
+        const value = new Unions(StrictChoice.withBig(1n), StrictChoice.withText("hi"))
+        const expected = new Uint8Array([
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x68, 0x69, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _UnionsType)).toEqual(expected)
+    })
+
+    it("UnionOutOfLine decode", () => {
+        const bytes = new Uint8Array([
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x68, 0x69, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _UnionsType)
+        expect(value).toEqual(new Unions(StrictChoice.withBig(1n), StrictChoice.withText("hi")))
+        expect(midl.Encoder.encode([], value, _UnionsType)).toEqual(bytes)
+    })
+
+    // unions_tables.gidl:51
+    it("StrictUnionUnknownOrdinal decode failure", () => {
+        const bytes = new Uint8Array([
+            0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _UnionsType), midl.ErrorCode.StrictUnionUnknownField)
+    })
+
+    // unions_tables.gidl:60
+    it("UnionAbsent decode failure", () => {
+        const bytes = new Uint8Array([
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _UnionsType), midl.ErrorCode.NonNullableTypeWithNullValue)
+    })
+
+    // unions_tables.gidl:69
+    it("UnionInvalidInlineMarker decode failure", () => {
+        const bytes = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _UnionsType), midl.ErrorCode.InvalidInlineMarkerInEnvelope)
+    })
+
+    // unions_tables.gidl:78
+    it("UnionInlineBitForLargeValue decode failure", () => {
+        const bytes = new Uint8Array([
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _UnionsType), midl.ErrorCode.InvalidInlineBitInEnvelope)
+    })
+
+    // unions_tables.gidl:87
+    it("UnionWrongNumBytes decode failure", () => {
+        const bytes = new Uint8Array([
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expectError(() => midl.Decoder.decode(bytes, [], _UnionsType), midl.ErrorCode.InvalidNumBytesInEnvelope)
+    })
+
+    // unions_tables.gidl:97
+    it("FlexibleUnion encode", () => {
+        const value = new FlexibleHolder(FlexibleChoice.withSmall(1))
+        const expected = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _FlexibleHolderType)).toEqual(expected)
+    })
+
+    it("FlexibleUnion decode", () => {
+        const bytes = new Uint8Array([
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _FlexibleHolderType)
+        expect(value).toEqual(new FlexibleHolder(FlexibleChoice.withSmall(1)))
+        expect(midl.Encoder.encode([], value, _FlexibleHolderType)).toEqual(bytes)
+    })
+
+    // unions_tables.gidl:104
+    it("Table encode", () => {
+        const value = new Tables(new Settings(1, null, "hi"))
+        const expected = new Uint8Array([
+            0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x68, 0x69, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _TablesType)).toEqual(expected)
+    })
+
+    it("Table decode", () => {
+        const bytes = new Uint8Array([
+            0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x68, 0x69, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _TablesType)
+        expect(value).toEqual(new Tables(new Settings(1, null, "hi")))
+        expect(midl.Encoder.encode([], value, _TablesType)).toEqual(bytes)
+    })
+
+    // unions_tables.gidl:117
+    it("TableEmpty encode", () => {
+        const value = new Tables(new Settings(null, null, null))
+        const expected = new Uint8Array([
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        ])
+        expect(midl.Encoder.encode([], value, _TablesType)).toEqual(expected)
+    })
+
+    it("TableEmpty decode", () => {
+        const bytes = new Uint8Array([
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _TablesType)
+        expect(value).toEqual(new Tables(new Settings(null, null, null)))
+        expect(midl.Encoder.encode([], value, _TablesType)).toEqual(bytes)
+    })
+
+    // unions_tables.gidl:124
+    it("TableOutOfLineField encode", () => {
+        const value = new Tables(new Settings(null, 2n, null))
+        const expected = new Uint8Array([
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        expect(midl.Encoder.encode([], value, _TablesType)).toEqual(expected)
+    })
+
+    it("TableOutOfLineField decode", () => {
+        const bytes = new Uint8Array([
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ])
+        const value = midl.Decoder.decode(bytes, [], _TablesType)
+        expect(value).toEqual(new Tables(new Settings(null, 2n, null)))
+        expect(midl.Encoder.encode([], value, _TablesType)).toEqual(bytes)
+    })
+})
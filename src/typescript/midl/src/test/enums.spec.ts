@@ -1,4 +1,4 @@
-import * as midl from "../lib"
+import * as midl from ".."
 
 class MyStrictEnum extends midl.Enum {
     public static readonly foo = new MyStrictEnum(0x1)
@@ -79,8 +79,10 @@ class MyFlexibleEnum extends midl.Enum {
     }
 }
 
-const _MyStrictEnumType = new midl.EnumType<MyStrictEnum>(new midl.UInt32Type())
-const _MyFlexibleEnumType = new midl.EnumType<MyFlexibleEnum>(new midl.UInt32Type())
+const _MyStrictEnumType = new midl.EnumType<MyStrictEnum>(new midl.UInt32Type(), (value) => MyStrictEnum.create(value))
+const _MyFlexibleEnumType = new midl.EnumType<MyFlexibleEnum>(new midl.UInt32Type(), (value) =>
+    MyFlexibleEnum.create(value)
+)
 
 describe("Enums", () => {
     it("flexible enums", async () => {
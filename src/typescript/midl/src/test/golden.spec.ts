@@ -0,0 +1,154 @@
+import * as fiber from "@meshx-org/fiber-ts"
+import { FX_OBJ_TYPE_NONE, FX_RIGHT_SAME_RIGHTS } from "@meshx-org/fiber-types"
+import * as midl from ".."
+import GOLDEN_V2 from "../../../../crates/midl/testdata/golden_v2.txt?raw"
+// The bindings midlgen_ts generates for tools/midl/midlgen_ts/goldens/golden.test.midl.
+import {
+    Features,
+    Foo,
+    GoldenTable,
+    GoldenUnion,
+    Permissions,
+    _FeaturesType,
+    _FooType,
+    _GoldenTableType,
+    _GoldenUnionType,
+    _PermissionsType,
+} from "../../../../../tools/midl/midlgen_ts/goldens/midl_test_golden"
+
+/// Parses the golden file shared with the Rust bindings into named byte
+/// sequences. See the header of golden_v2.txt for the format.
+function parseGolden(text: string): Map<string, Uint8Array> {
+    const cases = new Map<string, number[]>()
+    let current: number[] | null = null
+
+    for (const rawLine of text.split("\n")) {
+        const line = rawLine.split("#")[0]
+        if (line.trim() === "") {
+            continue
+        }
+
+        let hex = line
+        if (!/^\s/.test(line)) {
+            const [name, rest] = line.split("=")
+            current = []
+            cases.set(name.trim(), current)
+            hex = rest
+        }
+
+        for (const byte of hex.trim().split(/\s+/).filter((b) => b !== "")) {
+            current!.push(parseInt(byte, 16))
+        }
+    }
+
+    return new Map([...cases].map(([name, bytes]) => [name, new Uint8Array(bytes)]))
+}
+
+const golden = parseGolden(GOLDEN_V2)
+
+function goldenBytes(name: string): Uint8Array {
+    const bytes = golden.get(name)
+    if (bytes === undefined) {
+        throw new Error(`no golden case named ${name}`)
+    }
+    return bytes
+}
+
+/// Encodes `value`, compares the result to the golden case and decodes the
+/// golden bytes back into a value equal to `value`.
+function expectGolden<T>(name: string, type: midl.MidlType<T, any>, value: T) {
+    const bytes = goldenBytes(name)
+
+    expect(midl.Encoder.encode([], value, type)).toEqual(bytes)
+    expect(midl.Decoder.decode(bytes, [], type)).toEqual(value)
+}
+
+describe("Golden V2 encoding", () => {
+    it("primitives", () => {
+        expectGolden("bool_true", new midl.BoolType(), true)
+        expectGolden("uint8", new midl.UInt8Type(), 42)
+        expectGolden("int8", new midl.Int8Type(), -2)
+        expectGolden("uint16", new midl.UInt16Type(), 0x1234)
+        expectGolden("int16", new midl.Int16Type(), -2)
+        expectGolden("uint32", new midl.UInt32Type(), 0x12345678)
+        expectGolden("int32", new midl.Int32Type(), -1)
+        expectGolden("uint64", new midl.UInt64Type(), 0x0102030405060708n)
+        expectGolden("int64", new midl.Int64Type(), -2n)
+        expectGolden("float32", new midl.Float32Type(), 1.5)
+        expectGolden("float64", new midl.Float64Type(), -2.5)
+    })
+
+    it("strings, vectors and arrays", () => {
+        expectGolden("string_hello", new midl.StringType(), "hello")
+        expectGolden("string_empty", new midl.StringType(), "")
+        expectGolden("optional_string_absent", new midl.NullableStringType(), null)
+        expectGolden("vector_uint16", new midl.VectorType(new midl.UInt16Type()), [1, 2, 3])
+        expectGolden("vector_string", new midl.VectorType(new midl.StringType()), ["a", "bc"])
+        expectGolden("optional_vector_absent", new midl.NullableVectorType(new midl.UInt8Type()), null)
+        expectGolden("array_uint32", new midl.ArrayType(new midl.UInt32Type(), 3), [1, 2, 3])
+    })
+
+    it("structs", () => {
+        expectGolden("struct", _FooType, new Foo(5, 22n, "hello"))
+        expectGolden("boxed_struct", new midl.BoxType(_FooType), new Foo(5, 22n, "hello"))
+        expectGolden("boxed_struct_absent", new midl.BoxType(_FooType), null)
+    })
+
+    it("unions", () => {
+        // The union state lives in private fields, so compare it through the
+        // accessors instead of structurally.
+        const cases: [string, GoldenUnion][] = [
+            ["union_inline", GoldenUnion.withSmall(7)],
+            ["union_out_of_line", GoldenUnion.withBig(0x0102030405060708n)],
+        ]
+
+        for (const [name, value] of cases) {
+            const bytes = goldenBytes(name)
+            expect(midl.Encoder.encode([], value, _GoldenUnionType)).toEqual(bytes)
+
+            const decoded = midl.Decoder.decode(bytes, [], _GoldenUnionType)
+            expect(decoded.$ordinal).toEqual(value.$ordinal)
+            expect(decoded.$data).toEqual(value.$data)
+        }
+    })
+
+    it("tables", () => {
+        expectGolden("table", _GoldenTableType, new GoldenTable(1, null, "hi"))
+        expectGolden("table_empty", _GoldenTableType, new GoldenTable())
+    })
+
+    it("bits", () => {
+        // Bits are encoded as their underlying integer.
+        expectGolden("uint32", _FeaturesType, Features.create(0x12345678))
+        expect(Features.create(0x12345678).$unknownBits).toEqual(0x12345678 & ~Features.$mask)
+
+        const permissions = Permissions.READ.or(Permissions.EXECUTE)
+        expect(permissions.has(Permissions.READ)).toBe(true)
+        expect(permissions.has(Permissions.WRITE)).toBe(false)
+        expect(midl.Decoder.decode(new Uint8Array([5, 0, 0, 0, 0, 0, 0, 0]), [], _PermissionsType)).toEqual(permissions)
+
+        // 42 has bits Permissions does not know, which strict bits reject both ways.
+        expect(() => midl.Encoder.encode([], Permissions.create(42), _PermissionsType)).toThrow(midl.MidlError)
+        expect(() => midl.Decoder.decode(goldenBytes("uint8"), [], _PermissionsType)).toThrow(midl.MidlError)
+    })
+
+    it("handles", () => {
+        const type = new midl.HandleType(FX_OBJ_TYPE_NONE, FX_RIGHT_SAME_RIGHTS)
+        const handle = fiber.Handle.from_raw(1)
+        const bytes = goldenBytes("handle")
+
+        const handles: fiber.HandleDisposition[] = []
+        expect(midl.Encoder.encode(handles, handle, type)).toEqual(bytes)
+        expect(handles).toHaveLength(1)
+        expect(handles[0].handle).toBe(handle)
+
+        const handleInfo = { handle, type: FX_OBJ_TYPE_NONE, rights: FX_RIGHT_SAME_RIGHTS }
+        expect(midl.Decoder.decode(bytes, [handleInfo], type)).toBe(handle)
+
+        expectGolden(
+            "optional_handle_absent",
+            new midl.NullableHandleType(FX_OBJ_TYPE_NONE, FX_RIGHT_SAME_RIGHTS),
+            null
+        )
+    })
+})
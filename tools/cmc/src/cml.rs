@@ -0,0 +1,330 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! The human friendly YAML component manifest format.
+//!
+//! A manifest reads like the declaration it compiles to, with the noise left out: capabilities
+//! are routed `from` and `to` references such as `parent`, `self` or `#child`, names can be
+//! grouped in lists, and the target names and paths default to the source names.
+//!
+//! ```yaml
+//! include:
+//!   - syslog/client.shard.yaml
+//! program:
+//!   runner: wasm
+//!   binary: bin/echo
+//! capabilities:
+//!   - protocol: echo.Echo
+//! use:
+//!   - protocol: [meshx.logger.LogSink, meshx.time.Clock]
+//! expose:
+//!   - protocol: echo.Echo
+//!     from: self
+//! ```
+
+use cm_config::{
+    Availability, DependencyType, DictionaryValue, Durability, EnvironmentExtends, OnTerminate, StartupMode,
+};
+use cm_types::{Name, Path, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A component manifest, before its includes are merged in.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Document {
+    /// Manifest shards merged into this manifest, relative to the manifest or to one of the
+    /// include paths.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program: Option<Program>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Child>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub collections: Vec<Collection>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub environments: Vec<Environment>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<Capability>,
+    #[serde(rename = "use", skip_serializing_if = "Vec::is_empty")]
+    pub uses: Vec<Use>,
+    #[serde(rename = "offer", skip_serializing_if = "Vec::is_empty")]
+    pub offers: Vec<Offer>,
+    #[serde(rename = "expose", skip_serializing_if = "Vec::is_empty")]
+    pub exposes: Vec<Expose>,
+}
+
+impl Document {
+    /// Merges an included shard into this manifest.  Entries that are already present are not
+    /// duplicated, so shards may be included more than once.
+    pub fn merge_from(&mut self, other: Document) -> anyhow::Result<()> {
+        match (&mut self.program, other.program) {
+            (_, None) => {}
+            (program @ None, Some(other)) => *program = Some(other),
+            (Some(program), Some(other)) => program.merge_from(other)?,
+        }
+        merge_vec(&mut self.children, other.children);
+        merge_vec(&mut self.collections, other.collections);
+        merge_vec(&mut self.environments, other.environments);
+        merge_vec(&mut self.capabilities, other.capabilities);
+        merge_vec(&mut self.uses, other.uses);
+        merge_vec(&mut self.offers, other.offers);
+        merge_vec(&mut self.exposes, other.exposes);
+        Ok(())
+    }
+}
+
+fn merge_vec<T: PartialEq>(items: &mut Vec<T>, other: Vec<T>) {
+    for item in other {
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+}
+
+/// One value, or a list of values.  Lets the common single value case be written without the
+/// brackets.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        match self {
+            OneOrMany::One(item) => std::slice::from_ref(item).iter(),
+            OneOrMany::Many(items) => items.iter(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            OneOrMany::One(_) => 1,
+            OneOrMany::Many(items) => items.len(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Program {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runner: Option<Name>,
+    #[serde(flatten)]
+    pub info: BTreeMap<String, DictionaryValue>,
+}
+
+impl Program {
+    fn merge_from(&mut self, other: Program) -> anyhow::Result<()> {
+        match (&self.runner, other.runner) {
+            (_, None) => {}
+            (None, Some(runner)) => self.runner = Some(runner),
+            (Some(runner), Some(other)) if *runner == other => {}
+            (Some(runner), Some(other)) => {
+                anyhow::bail!("conflicting program runners \"{runner}\" and \"{other}\"")
+            }
+        }
+        for (key, value) in other.info {
+            match self.info.get(&key) {
+                None => {
+                    self.info.insert(key, value);
+                }
+                Some(existing) if *existing == value => {}
+                Some(_) => anyhow::bail!("conflicting values for program.{key}"),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Child {
+    pub name: Name,
+    pub url: Url,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub startup: Option<StartupMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_terminate: Option<OnTerminate>,
+    /// A `#environment` reference.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Collection {
+    pub name: Name,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub durability: Option<Durability>,
+    /// A `#environment` reference.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Environment {
+    pub name: Name,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extends: Option<EnvironmentExtends>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub runners: Vec<RunnerRegistration>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resolvers: Vec<ResolverRegistration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_timeout_ms: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RunnerRegistration {
+    pub runner: Name,
+    pub from: String,
+    #[serde(rename = "as", skip_serializing_if = "Option::is_none")]
+    pub as_: Option<Name>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResolverRegistration {
+    pub resolver: Name,
+    pub from: String,
+    pub scheme: String,
+}
+
+/// The capability keys shared by capability, use, offer and expose entries.  Exactly one of them
+/// is set in a valid entry.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CapabilityKeys<T> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub directory: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runner: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolver: Option<T>,
+}
+
+/// The type of capability an entry is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapabilityKind {
+    Protocol,
+    Directory,
+    Storage,
+    Service,
+    Runner,
+    Resolver,
+}
+
+impl CapabilityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CapabilityKind::Protocol => "protocol",
+            CapabilityKind::Directory => "directory",
+            CapabilityKind::Storage => "storage",
+            CapabilityKind::Service => "service",
+            CapabilityKind::Runner => "runner",
+            CapabilityKind::Resolver => "resolver",
+        }
+    }
+}
+
+impl<T> CapabilityKeys<T> {
+    /// Returns the only capability key that is set, and its value.
+    pub fn get(&self) -> anyhow::Result<(CapabilityKind, &T)> {
+        let keys = [
+            (CapabilityKind::Protocol, &self.protocol),
+            (CapabilityKind::Directory, &self.directory),
+            (CapabilityKind::Storage, &self.storage),
+            (CapabilityKind::Service, &self.service),
+            (CapabilityKind::Runner, &self.runner),
+            (CapabilityKind::Resolver, &self.resolver),
+        ];
+        let mut set = keys
+            .into_iter()
+            .filter_map(|(kind, value)| value.as_ref().map(|value| (kind, value)));
+        match (set.next(), set.next()) {
+            (Some(key), None) => Ok(key),
+            (None, _) => anyhow::bail!(
+                "one of \"protocol\", \"directory\", \"storage\", \"service\", \"runner\" or \"resolver\" is required"
+            ),
+            (Some((kind, _)), Some((other, _))) => {
+                anyhow::bail!(
+                    "\"{}\" and \"{}\" are mutually exclusive",
+                    kind.as_str(),
+                    other.as_str()
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Capability {
+    #[serde(flatten)]
+    pub keys: CapabilityKeys<Name>,
+    /// The path the capability is served at in the outgoing directory of the component.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Path>,
+    /// The component the backing directory of a storage capability is routed from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing_dir: Option<Name>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Use {
+    #[serde(flatten)]
+    pub keys: CapabilityKeys<OneOrMany<Name>>,
+    /// Defaults to `parent`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// The path in the namespace of the component.  Defaults to `/svc/<name>` for protocols and
+    /// services.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Path>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependency: Option<DependencyType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability: Option<Availability>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Offer {
+    #[serde(flatten)]
+    pub keys: CapabilityKeys<OneOrMany<Name>>,
+    pub from: String,
+    pub to: OneOrMany<String>,
+    #[serde(rename = "as", skip_serializing_if = "Option::is_none")]
+    pub as_: Option<Name>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependency: Option<DependencyType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability: Option<Availability>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expose {
+    #[serde(flatten)]
+    pub keys: CapabilityKeys<OneOrMany<Name>>,
+    pub from: String,
+    /// Defaults to `parent`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    #[serde(rename = "as", skip_serializing_if = "Option::is_none")]
+    pub as_: Option<Name>,
+}
@@ -0,0 +1,562 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Compiles a [`Document`], with its includes already merged in, to a [`ComponentDecl`].
+
+use crate::cml::{self, CapabilityKind, Document};
+use anyhow::{anyhow, bail, Context, Result};
+use cm_config::{
+    CapabilityDecl, ChildDecl, CollectionDecl, ComponentDecl, DirectoryDecl, EnvironmentDecl, ExposeDecl,
+    ExposeDirectoryDecl, ExposeProtocolDecl, ExposeResolverDecl, ExposeRunnerDecl, ExposeServiceDecl, ExposeSource,
+    ExposeTarget, OfferDecl, OfferDirectoryDecl, OfferProtocolDecl, OfferResolverDecl, OfferRunnerDecl,
+    OfferServiceDecl, OfferSource, OfferStorageDecl, OfferTarget, ProgramDecl, ProtocolDecl, RegistrationSource,
+    ResolverDecl, ResolverRegistration, RunnerDecl, RunnerRegistration, ServiceDecl, StorageDecl,
+    StorageDirectorySource, UseDecl, UseDirectoryDecl, UseProtocolDecl, UseServiceDecl, UseSource, UseStorageDecl,
+};
+use cm_types::{Name, Path};
+
+/// Compiles `document` and validates the result.
+pub fn compile(document: &Document) -> Result<ComponentDecl> {
+    let compiler = Compiler { document };
+    let decl = ComponentDecl {
+        program: document.program.as_ref().map(|program| ProgramDecl {
+            runner: program.runner.clone(),
+            info: program.info.clone(),
+        }),
+        uses: compiler.uses()?,
+        exposes: compiler.exposes()?,
+        offers: compiler.offers()?,
+        capabilities: compiler.capabilities()?,
+        children: compiler.children()?,
+        collections: compiler.collections()?,
+        environments: compiler.environments()?,
+    };
+    cm_config::validate::validate(&decl).map_err(|errs| anyhow!("invalid manifest: {errs}"))?;
+    Ok(decl)
+}
+
+/// A `from` or `to` reference, resolved against the declarations of the manifest.
+enum Ref {
+    Parent,
+    Self_,
+    Framework,
+    Void,
+    Child(Name),
+    Collection(Name),
+    Capability(Name),
+}
+
+struct Compiler<'a> {
+    document: &'a Document,
+}
+
+impl Compiler<'_> {
+    /// Resolves `parent`, `self`, `framework`, `void` and `#name` references.  Names are looked up
+    /// in the children, the collections and then the capabilities of the manifest.
+    fn resolve(&self, reference: &str) -> Result<Ref> {
+        Ok(match reference {
+            "parent" => Ref::Parent,
+            "self" => Ref::Self_,
+            "framework" => Ref::Framework,
+            "void" => Ref::Void,
+            _ => {
+                let name = reference
+                    .strip_prefix('#')
+                    .ok_or_else(|| anyhow!("invalid reference \"{reference}\""))
+                    .and_then(|name| Name::new(name).with_context(|| format!("invalid reference \"{reference}\"")))?;
+                if self.document.children.iter().any(|child| child.name == name) {
+                    Ref::Child(name)
+                } else if self
+                    .document
+                    .collections
+                    .iter()
+                    .any(|collection| collection.name == name)
+                {
+                    Ref::Collection(name)
+                } else if self.document.capabilities.iter().any(|capability| {
+                    capability
+                        .keys
+                        .get()
+                        .map(|(_, capability)| *capability == name)
+                        .unwrap_or(false)
+                }) {
+                    Ref::Capability(name)
+                } else {
+                    bail!("\"{reference}\" does not refer to a child, a collection or a capability")
+                }
+            }
+        })
+    }
+
+    /// Resolves a `#environment` reference.
+    fn environment(&self, reference: Option<&String>) -> Result<Option<Name>> {
+        let Some(reference) = reference else {
+            return Ok(None);
+        };
+        let name = reference
+            .strip_prefix('#')
+            .and_then(|name| Name::new(name).ok())
+            .filter(|name| {
+                self.document
+                    .environments
+                    .iter()
+                    .any(|environment| environment.name == *name)
+            });
+        match name {
+            Some(name) => Ok(Some(name)),
+            None => bail!("\"{reference}\" does not refer to an environment"),
+        }
+    }
+
+    fn use_source(&self, reference: Option<&String>) -> Result<UseSource> {
+        let Some(reference) = reference else {
+            return Ok(UseSource::Parent);
+        };
+        Ok(match self.resolve(reference)? {
+            Ref::Parent => UseSource::Parent,
+            Ref::Self_ => UseSource::Self_,
+            Ref::Framework => UseSource::Framework,
+            Ref::Child(name) => UseSource::Child(name),
+            Ref::Capability(name) => UseSource::Capability(name),
+            Ref::Void | Ref::Collection(_) => bail!("capabilities cannot be used from \"{reference}\""),
+        })
+    }
+
+    fn offer_source(&self, reference: &str) -> Result<OfferSource> {
+        Ok(match self.resolve(reference)? {
+            Ref::Parent => OfferSource::Parent,
+            Ref::Self_ => OfferSource::Self_,
+            Ref::Framework => OfferSource::Framework,
+            Ref::Void => OfferSource::Void,
+            Ref::Child(name) => OfferSource::Child(name),
+            Ref::Collection(name) => OfferSource::Collection(name),
+            Ref::Capability(name) => OfferSource::Capability(name),
+        })
+    }
+
+    fn offer_target(&self, reference: &str) -> Result<OfferTarget> {
+        Ok(match self.resolve(reference)? {
+            Ref::Child(name) => OfferTarget::Child(name),
+            Ref::Collection(name) => OfferTarget::Collection(name),
+            _ => bail!("capabilities can only be offered to children and collections, not \"{reference}\""),
+        })
+    }
+
+    fn expose_source(&self, reference: &str) -> Result<ExposeSource> {
+        Ok(match self.resolve(reference)? {
+            Ref::Self_ => ExposeSource::Self_,
+            Ref::Framework => ExposeSource::Framework,
+            Ref::Void => ExposeSource::Void,
+            Ref::Child(name) => ExposeSource::Child(name),
+            Ref::Collection(name) => ExposeSource::Collection(name),
+            Ref::Capability(name) => ExposeSource::Capability(name),
+            Ref::Parent => bail!("capabilities cannot be exposed from \"parent\""),
+        })
+    }
+
+    fn expose_target(&self, reference: Option<&String>) -> Result<ExposeTarget> {
+        match reference.map(String::as_str) {
+            None | Some("parent") => Ok(ExposeTarget::Parent),
+            Some("framework") => Ok(ExposeTarget::Framework),
+            Some(reference) => bail!("capabilities can only be exposed to parent or framework, not \"{reference}\""),
+        }
+    }
+
+    fn registration_source(&self, reference: &str) -> Result<RegistrationSource> {
+        Ok(match self.resolve(reference)? {
+            Ref::Parent => RegistrationSource::Parent,
+            Ref::Self_ => RegistrationSource::Self_,
+            Ref::Child(name) => RegistrationSource::Child(name),
+            _ => bail!("capabilities can only be registered from parent, self or a child, not \"{reference}\""),
+        })
+    }
+
+    fn storage_source(&self, reference: Option<&String>) -> Result<StorageDirectorySource> {
+        let reference = reference.ok_or_else(|| anyhow!("\"from\" is required for storage capabilities"))?;
+        Ok(match self.resolve(reference)? {
+            Ref::Parent => StorageDirectorySource::Parent,
+            Ref::Self_ => StorageDirectorySource::Self_,
+            Ref::Child(name) => StorageDirectorySource::Child(name),
+            _ => bail!("storage can only be backed by parent, self or a child, not \"{reference}\""),
+        })
+    }
+
+    fn capabilities(&self) -> Result<Vec<CapabilityDecl>> {
+        self.document
+            .capabilities
+            .iter()
+            .map(|capability| {
+                let (kind, name) = capability.keys.get()?;
+                let name = name.clone();
+                let source_path = capability.path.clone();
+                if kind != CapabilityKind::Storage && (capability.from.is_some() || capability.backing_dir.is_some()) {
+                    bail!("\"from\" and \"backing_dir\" are only valid for storage capabilities");
+                }
+                Ok(match kind {
+                    CapabilityKind::Protocol => CapabilityDecl::Protocol(ProtocolDecl { name, source_path }),
+                    CapabilityKind::Directory => CapabilityDecl::Directory(DirectoryDecl { name, source_path }),
+                    CapabilityKind::Service => CapabilityDecl::Service(ServiceDecl { name, source_path }),
+                    CapabilityKind::Runner => CapabilityDecl::Runner(RunnerDecl { name, source_path }),
+                    CapabilityKind::Resolver => CapabilityDecl::Resolver(ResolverDecl { name, source_path }),
+                    CapabilityKind::Storage => {
+                        if source_path.is_some() {
+                            bail!("\"path\" is not valid for storage capabilities");
+                        }
+                        CapabilityDecl::Storage(StorageDecl {
+                            name,
+                            source: self.storage_source(capability.from.as_ref())?,
+                            backing_dir: capability
+                                .backing_dir
+                                .clone()
+                                .ok_or_else(|| anyhow!("\"backing_dir\" is required for storage capabilities"))?,
+                        })
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn uses(&self) -> Result<Vec<UseDecl>> {
+        let mut uses = vec![];
+        for use_ in &self.document.uses {
+            let (kind, names) = use_.keys.get()?;
+            if use_.path.is_some() && names.len() > 1 {
+                bail!("\"path\" can only be set when using a single capability");
+            }
+            let dependency_type = use_.dependency.unwrap_or_default();
+            let availability = use_.availability.unwrap_or_default();
+            for name in names.iter() {
+                let source_name = name.clone();
+                let target_path = match (&use_.path, kind) {
+                    (Some(path), _) => path.clone(),
+                    (None, CapabilityKind::Protocol | CapabilityKind::Service) => Path::new(format!("/svc/{name}"))?,
+                    (None, _) => bail!("\"path\" is required when using a {}", kind.as_str()),
+                };
+                uses.push(match kind {
+                    CapabilityKind::Protocol => UseDecl::Protocol(UseProtocolDecl {
+                        source: self.use_source(use_.from.as_ref())?,
+                        source_name,
+                        target_path,
+                        dependency_type,
+                        availability,
+                    }),
+                    CapabilityKind::Directory => UseDecl::Directory(UseDirectoryDecl {
+                        source: self.use_source(use_.from.as_ref())?,
+                        source_name,
+                        target_path,
+                        dependency_type,
+                        availability,
+                    }),
+                    CapabilityKind::Service => UseDecl::Service(UseServiceDecl {
+                        source: self.use_source(use_.from.as_ref())?,
+                        source_name,
+                        target_path,
+                        dependency_type,
+                        availability,
+                    }),
+                    CapabilityKind::Storage => {
+                        if !matches!(use_.from.as_deref(), None | Some("parent")) {
+                            bail!("storage can only be used from parent");
+                        }
+                        UseDecl::Storage(UseStorageDecl {
+                            source_name,
+                            target_path,
+                            availability,
+                        })
+                    }
+                    CapabilityKind::Runner | CapabilityKind::Resolver => {
+                        bail!("{}s cannot be used, they are registered in environments", kind.as_str())
+                    }
+                });
+            }
+        }
+        Ok(uses)
+    }
+
+    fn offers(&self) -> Result<Vec<OfferDecl>> {
+        let mut offers = vec![];
+        for offer in &self.document.offers {
+            let (kind, names) = offer.keys.get()?;
+            if offer.as_.is_some() && names.len() > 1 {
+                bail!("\"as\" can only be set when offering a single capability");
+            }
+            let dependency_type = offer.dependency.unwrap_or_default();
+            let availability = offer.availability.unwrap_or_default();
+            if matches!(
+                kind,
+                CapabilityKind::Storage | CapabilityKind::Runner | CapabilityKind::Resolver
+            ) && offer.dependency.is_some()
+            {
+                bail!("\"dependency\" is not valid when offering a {}", kind.as_str());
+            }
+            for name in names.iter() {
+                for target in offer.to.iter() {
+                    let source = self.offer_source(&offer.from)?;
+                    let source_name = name.clone();
+                    let target = self.offer_target(target)?;
+                    let target_name = offer.as_.clone().unwrap_or_else(|| name.clone());
+                    offers.push(match kind {
+                        CapabilityKind::Protocol => OfferDecl::Protocol(OfferProtocolDecl {
+                            source,
+                            source_name,
+                            target,
+                            target_name,
+                            dependency_type,
+                            availability,
+                        }),
+                        CapabilityKind::Directory => OfferDecl::Directory(OfferDirectoryDecl {
+                            source,
+                            source_name,
+                            target,
+                            target_name,
+                            dependency_type,
+                            availability,
+                        }),
+                        CapabilityKind::Service => OfferDecl::Service(OfferServiceDecl {
+                            source,
+                            source_name,
+                            target,
+                            target_name,
+                            dependency_type,
+                            availability,
+                        }),
+                        CapabilityKind::Storage => OfferDecl::Storage(OfferStorageDecl {
+                            source,
+                            source_name,
+                            target,
+                            target_name,
+                            availability,
+                        }),
+                        CapabilityKind::Runner => OfferDecl::Runner(OfferRunnerDecl {
+                            source,
+                            source_name,
+                            target,
+                            target_name,
+                        }),
+                        CapabilityKind::Resolver => OfferDecl::Resolver(OfferResolverDecl {
+                            source,
+                            source_name,
+                            target,
+                            target_name,
+                        }),
+                    });
+                }
+            }
+        }
+        Ok(offers)
+    }
+
+    fn exposes(&self) -> Result<Vec<ExposeDecl>> {
+        let mut exposes = vec![];
+        for expose in &self.document.exposes {
+            let (kind, names) = expose.keys.get()?;
+            if expose.as_.is_some() && names.len() > 1 {
+                bail!("\"as\" can only be set when exposing a single capability");
+            }
+            for name in names.iter() {
+                let source = self.expose_source(&expose.from)?;
+                let source_name = name.clone();
+                let target = self.expose_target(expose.to.as_ref())?;
+                let target_name = expose.as_.clone().unwrap_or_else(|| name.clone());
+                exposes.push(match kind {
+                    CapabilityKind::Protocol => ExposeDecl::Protocol(ExposeProtocolDecl {
+                        source,
+                        source_name,
+                        target,
+                        target_name,
+                    }),
+                    CapabilityKind::Directory => ExposeDecl::Directory(ExposeDirectoryDecl {
+                        source,
+                        source_name,
+                        target,
+                        target_name,
+                    }),
+                    CapabilityKind::Service => ExposeDecl::Service(ExposeServiceDecl {
+                        source,
+                        source_name,
+                        target,
+                        target_name,
+                    }),
+                    CapabilityKind::Runner => ExposeDecl::Runner(ExposeRunnerDecl {
+                        source,
+                        source_name,
+                        target,
+                        target_name,
+                    }),
+                    CapabilityKind::Resolver => ExposeDecl::Resolver(ExposeResolverDecl {
+                        source,
+                        source_name,
+                        target,
+                        target_name,
+                    }),
+                    CapabilityKind::Storage => bail!("storage cannot be exposed"),
+                });
+            }
+        }
+        Ok(exposes)
+    }
+
+    fn children(&self) -> Result<Vec<ChildDecl>> {
+        self.document
+            .children
+            .iter()
+            .map(|child| {
+                Ok(ChildDecl {
+                    name: child.name.clone(),
+                    url: child.url.clone(),
+                    startup: child.startup.unwrap_or_default(),
+                    on_terminate: child.on_terminate,
+                    environment: self.environment(child.environment.as_ref())?,
+                })
+            })
+            .collect()
+    }
+
+    fn collections(&self) -> Result<Vec<CollectionDecl>> {
+        self.document
+            .collections
+            .iter()
+            .map(|collection| {
+                Ok(CollectionDecl {
+                    name: collection.name.clone(),
+                    durability: collection.durability.unwrap_or_default(),
+                    environment: self.environment(collection.environment.as_ref())?,
+                })
+            })
+            .collect()
+    }
+
+    fn environments(&self) -> Result<Vec<EnvironmentDecl>> {
+        self.document
+            .environments
+            .iter()
+            .map(|environment| {
+                Ok(EnvironmentDecl {
+                    name: environment.name.clone(),
+                    extends: environment.extends.unwrap_or_default(),
+                    runners: environment
+                        .runners
+                        .iter()
+                        .map(|runner: &cml::RunnerRegistration| {
+                            Ok(RunnerRegistration {
+                                source_name: runner.runner.clone(),
+                                target_name: runner.as_.clone().unwrap_or_else(|| runner.runner.clone()),
+                                source: self.registration_source(&runner.from)?,
+                            })
+                        })
+                        .collect::<Result<_>>()?,
+                    resolvers: environment
+                        .resolvers
+                        .iter()
+                        .map(|resolver| {
+                            Ok(ResolverRegistration {
+                                resolver: resolver.resolver.clone(),
+                                source: self.registration_source(&resolver.from)?,
+                                scheme: resolver.scheme.clone(),
+                            })
+                        })
+                        .collect::<Result<_>>()?,
+                    stop_timeout_ms: environment.stop_timeout_ms,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cm_config::{Availability, DependencyType};
+
+    fn compile_str(manifest: &str) -> Result<ComponentDecl> {
+        compile(&serde_yml::from_str(manifest)?)
+    }
+
+    #[test]
+    fn defaults_are_filled_in() {
+        let decl = compile_str(
+            r#"
+program:
+  runner: wasm
+  binary: bin/echo
+capabilities:
+  - protocol: echo.Echo
+use:
+  - protocol: [meshx.logger.LogSink, meshx.time.Clock]
+  - storage: data
+    path: /data
+expose:
+  - protocol: echo.Echo
+    from: self
+"#,
+        )
+        .unwrap();
+        assert_eq!(decl.uses.len(), 3);
+        assert_eq!(
+            decl.uses[1],
+            UseDecl::Protocol(UseProtocolDecl {
+                source: UseSource::Parent,
+                source_name: Name::new("meshx.time.Clock").unwrap(),
+                target_path: Path::new("/svc/meshx.time.Clock").unwrap(),
+                dependency_type: DependencyType::Strong,
+                availability: Availability::Required,
+            })
+        );
+        assert_eq!(decl.exposes[0].target(), &ExposeTarget::Parent);
+    }
+
+    #[test]
+    fn references_are_resolved() {
+        let decl = compile_str(
+            r##"
+children:
+  - name: server
+    url: mxpkg://meshx.co/echo#meta/echo.cm
+    environment: "#env"
+collections:
+  - name: workers
+environments:
+  - name: env
+    extends: realm
+    runners:
+      - runner: wasm
+        from: parent
+offer:
+  - protocol: echo.Echo
+    from: "#server"
+    to: ["#workers"]
+    as: echo.Other
+  - service: echo.Service
+    from: void
+    to: "#server"
+    availability: optional
+"##,
+        )
+        .unwrap();
+        assert_eq!(decl.children[0].environment, Some(Name::new("env").unwrap()));
+        assert_eq!(
+            decl.offers[0],
+            OfferDecl::Protocol(OfferProtocolDecl {
+                source: OfferSource::Child(Name::new("server").unwrap()),
+                source_name: Name::new("echo.Echo").unwrap(),
+                target: OfferTarget::Collection(Name::new("workers").unwrap()),
+                target_name: Name::new("echo.Other").unwrap(),
+                dependency_type: DependencyType::Strong,
+                availability: Availability::Required,
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_manifests_are_rejected() {
+        // Unknown keys.
+        assert!(compile_str("use:\n  - protocol: a\n    availabilty: optional\n").is_err());
+        // More than one capability key.
+        assert!(compile_str("use:\n  - protocol: a\n    service: b\n").is_err());
+        // Dangling references.
+        assert!(compile_str("offer:\n  - protocol: a\n    from: parent\n    to: \"#missing\"\n").is_err());
+        // Validation errors.
+        assert!(compile_str("use:\n  - protocol: a\n  - protocol: b\n    path: /svc/a\n").is_err());
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Loads manifests and merges in the shards they include.
+
+use crate::cml::Document;
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Parses the manifest at `path`.  Its includes are not resolved.
+pub fn parse(path: &Path) -> Result<Document> {
+    let contents = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    serde_yml::from_str(&contents).with_context(|| format!("parsing {}", path.display()))
+}
+
+/// A manifest with all of its includes merged in.
+pub struct Loaded {
+    pub document: Document,
+    /// The files the manifest was merged from, for depfiles.
+    pub includes: Vec<PathBuf>,
+}
+
+/// Loads the manifest at `path` and merges in its includes, recursively.  Includes are looked up
+/// relative to the including manifest first, and then in each of `include_paths`.
+pub fn load(path: &Path, include_paths: &[PathBuf]) -> Result<Loaded> {
+    let mut loader = Loader {
+        include_paths,
+        stack: vec![],
+        includes: vec![],
+    };
+    let mut document = loader.load(path)?;
+    document.include.clear();
+    Ok(Loaded {
+        document,
+        includes: loader.includes,
+    })
+}
+
+struct Loader<'a> {
+    include_paths: &'a [PathBuf],
+    /// The manifests being loaded, to detect include cycles.
+    stack: Vec<PathBuf>,
+    includes: Vec<PathBuf>,
+}
+
+impl Loader<'_> {
+    fn load(&mut self, path: &Path) -> Result<Document> {
+        let canonical = path
+            .canonicalize()
+            .with_context(|| format!("reading {}", path.display()))?;
+        if self.stack.contains(&canonical) {
+            let cycle = self
+                .stack
+                .iter()
+                .chain([&canonical])
+                .map(|path| path.display().to_string());
+            bail!("include cycle: {}", cycle.collect::<Vec<_>>().join(" -> "));
+        }
+
+        let mut document = parse(path)?;
+        self.stack.push(canonical);
+        for include in std::mem::take(&mut document.include) {
+            let include_path = self.resolve(path, &include)?;
+            if !self.includes.contains(&include_path) {
+                self.includes.push(include_path.clone());
+            }
+            let shard = self.load(&include_path)?;
+            document
+                .merge_from(shard)
+                .with_context(|| format!("merging {}", include_path.display()))?;
+        }
+        self.stack.pop();
+        Ok(document)
+    }
+
+    fn resolve(&self, manifest: &Path, include: &str) -> Result<PathBuf> {
+        let relative = manifest.parent().map(|dir| dir.join(include));
+        relative
+            .into_iter()
+            .chain(self.include_paths.iter().map(|dir| dir.join(include)))
+            .find(|path| path.is_file())
+            .with_context(|| format!("{}: include \"{include}\" not found", manifest.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn includes_are_merged() {
+        let dir = TempDir::new("includes");
+        let shards = dir.0.join("shards");
+        dir.write("shards/syslog.shard.yaml", "use:\n  - protocol: meshx.logger.LogSink\n");
+        dir.write(
+            "shards/client.shard.yaml",
+            "include: [syslog.shard.yaml]\nprogram:\n  runner: wasm\n",
+        );
+        let manifest = dir.write(
+            "echo.yaml",
+            "include: [client.shard.yaml, syslog.shard.yaml]\nprogram:\n  binary: bin/echo\n",
+        );
+
+        let loaded = load(&manifest, &[shards]).unwrap();
+        assert!(loaded.document.include.is_empty());
+        assert_eq!(loaded.document.uses.len(), 1);
+        let program = loaded.document.program.unwrap();
+        assert_eq!(program.runner.unwrap().as_str(), "wasm");
+        assert_eq!(program.info.len(), 1);
+        assert_eq!(loaded.includes.len(), 2);
+    }
+
+    #[test]
+    fn include_cycles_are_rejected() {
+        let dir = TempDir::new("cycles");
+        dir.write("a.shard.yaml", "include: [b.shard.yaml]\n");
+        dir.write("b.shard.yaml", "include: [a.shard.yaml]\n");
+        let manifest = dir.write("main.yaml", "include: [a.shard.yaml]\n");
+
+        let err = load(&manifest, &[]).err().unwrap();
+        assert!(err.to_string().starts_with("include cycle:"), "{err}");
+    }
+}
@@ -0,0 +1,265 @@
+// Copyright 2024 MeshX Contributors. All rights reserved.
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! The component manifest compiler.  Compiles YAML component manifests to the binary `.cm`
+//! declarations component manager loads from packages.
+
+mod cml;
+mod compile;
+mod include;
+#[cfg(test)]
+mod testing;
+
+use {
+    anyhow::{bail, Context, Result},
+    argh::FromArgs,
+    cm_config::DictionaryValue,
+    cml::Document,
+    meshx_pkg::MetaContents,
+    std::{fs, io::BufReader, path::PathBuf},
+};
+
+/// Component manifest compiler
+#[derive(FromArgs)]
+struct Command {
+    #[argh(subcommand)]
+    subcommands: SubCommands,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum SubCommands {
+    Compile(CompileCommand),
+    Format(FormatCommand),
+    ValidateReferences(ValidateReferencesCommand),
+}
+
+/// Compile a manifest, with its includes, to a binary component declaration
+#[derive(FromArgs)]
+#[argh(subcommand, name = "compile")]
+struct CompileCommand {
+    /// the manifest to compile
+    #[argh(positional)]
+    manifest: PathBuf,
+
+    /// the path of the compiled declaration
+    #[argh(option, short = 'o')]
+    output: PathBuf,
+
+    /// directories includes are looked up in, after the directory of the manifest
+    #[argh(option)]
+    includepath: Vec<PathBuf>,
+
+    /// write a depfile listing the included manifests
+    #[argh(option)]
+    depfile: Option<PathBuf>,
+}
+
+/// Format a manifest.  Comments are not preserved, so manifests with comments can not be
+/// formatted in place
+#[derive(FromArgs)]
+#[argh(subcommand, name = "format")]
+struct FormatCommand {
+    /// the manifest to format
+    #[argh(positional)]
+    manifest: PathBuf,
+
+    /// overwrite the manifest with the formatted one
+    #[argh(switch)]
+    in_place: bool,
+
+    /// where to write the formatted manifest, instead of stdout
+    #[argh(option, short = 'o')]
+    output: Option<PathBuf>,
+}
+
+/// Check that the files a compiled declaration refers to are in a package
+#[derive(FromArgs)]
+#[argh(subcommand, name = "validate-references")]
+struct ValidateReferencesCommand {
+    /// the compiled declaration
+    #[argh(positional)]
+    component_manifest: PathBuf,
+
+    /// the meta/contents of the package the declaration ships in
+    #[argh(option)]
+    package_manifest: PathBuf,
+}
+
+fn cmd_compile(cmd: CompileCommand) -> Result<()> {
+    let loaded = include::load(&cmd.manifest, &cmd.includepath)?;
+    let decl = compile::compile(&loaded.document).with_context(|| format!("compiling {}", cmd.manifest.display()))?;
+    let bytes = cm_config::encoding::encode(decl).context("encoding the declaration")?;
+    fs::write(&cmd.output, bytes).with_context(|| format!("writing {}", cmd.output.display()))?;
+
+    if let Some(depfile) = cmd.depfile {
+        let deps = loaded.includes.iter().map(|include| include.display().to_string());
+        let contents = format!("{}: {}\n", cmd.output.display(), deps.collect::<Vec<_>>().join(" "));
+        fs::write(&depfile, contents).with_context(|| format!("writing {}", depfile.display()))?;
+    }
+    Ok(())
+}
+
+fn cmd_format(cmd: FormatCommand) -> Result<()> {
+    if cmd.in_place && cmd.output.is_some() {
+        bail!("--in-place and --output are mutually exclusive");
+    }
+    let source = fs::read_to_string(&cmd.manifest).with_context(|| format!("reading {}", cmd.manifest.display()))?;
+    if cmd.in_place && has_comments(&source) {
+        bail!(
+            "{} has comments, which formatting would drop; format it to stdout or --output instead",
+            cmd.manifest.display()
+        );
+    }
+    let document: Document =
+        serde_yml::from_str(&source).with_context(|| format!("parsing {}", cmd.manifest.display()))?;
+    let formatted = serde_yml::to_string(&document).context("formatting the manifest")?;
+    match (cmd.in_place, cmd.output) {
+        (true, _) => fs::write(&cmd.manifest, formatted).with_context(|| format!("writing {}", cmd.manifest.display())),
+        (false, Some(output)) => fs::write(&output, formatted).with_context(|| format!("writing {}", output.display())),
+        (false, None) => {
+            print!("{formatted}");
+            Ok(())
+        }
+    }
+}
+
+/// Returns whether the YAML `source` has comments.  A `#` in a quoted scalar does not start a
+/// comment, but one in a block scalar is counted, which errs on the side of keeping the manifest.
+fn has_comments(source: &str) -> bool {
+    source.lines().any(|line| {
+        let (mut quote, mut escaped, mut previous) = (None, false, ' ');
+        for c in line.chars() {
+            match quote {
+                None if c == '#' && previous.is_whitespace() => return true,
+                None if (c == '\'' || c == '"') && (previous.is_whitespace() || "[{,".contains(previous)) => {
+                    quote = Some(c)
+                }
+                Some('"') if escaped => escaped = false,
+                Some('"') if c == '\\' => escaped = true,
+                Some(q) if c == q => quote = None,
+                _ => {}
+            }
+            previous = c;
+        }
+        false
+    })
+}
+
+fn cmd_validate_references(cmd: ValidateReferencesCommand) -> Result<()> {
+    let bytes =
+        fs::read(&cmd.component_manifest).with_context(|| format!("reading {}", cmd.component_manifest.display()))?;
+    let decl = cm_config::encoding::decode(&bytes)
+        .with_context(|| format!("decoding {}", cmd.component_manifest.display()))?;
+    let file =
+        fs::File::open(&cmd.package_manifest).with_context(|| format!("reading {}", cmd.package_manifest.display()))?;
+    let contents = MetaContents::deserialize(BufReader::new(file))
+        .with_context(|| format!("parsing {}", cmd.package_manifest.display()))?;
+
+    let Some(program) = decl.program else {
+        return Ok(());
+    };
+    match program.info.get("binary") {
+        Some(DictionaryValue::Str(binary)) if !contents.contents().contains_key(binary) => bail!(
+            "{}: program binary \"{binary}\" is not in the package {}",
+            cmd.component_manifest.display(),
+            cmd.package_manifest.display()
+        ),
+        Some(DictionaryValue::StrVec(_)) => {
+            bail!("{}: program.binary must be a string", cmd.component_manifest.display())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn main() -> Result<()> {
+    let cmd: Command = argh::from_env();
+
+    match cmd.subcommands {
+        SubCommands::Compile(cmd) => cmd_compile(cmd),
+        SubCommands::Format(cmd) => cmd_format(cmd),
+        SubCommands::ValidateReferences(cmd) => cmd_validate_references(cmd),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    const MANIFEST: &str = "program: {binary: bin/echo, runner: wasm}\nuse: [{protocol: meshx.logger.LogSink}]\n";
+
+    fn format(manifest: PathBuf, in_place: bool, output: Option<PathBuf>) -> Result<()> {
+        cmd_format(FormatCommand {
+            manifest,
+            in_place,
+            output,
+        })
+    }
+
+    #[test]
+    fn format_is_stable() {
+        let dir = TempDir::new("format");
+        let manifest = dir.write("echo.yaml", MANIFEST);
+        let output = dir.0.join("formatted.yaml");
+        format(manifest.clone(), false, Some(output.clone())).unwrap();
+        let formatted = fs::read_to_string(&output).unwrap();
+        assert!(formatted.contains("binary: bin/echo"), "{formatted}");
+
+        format(manifest.clone(), true, None).unwrap();
+        assert_eq!(fs::read_to_string(&manifest).unwrap(), formatted);
+        format(manifest.clone(), true, None).unwrap();
+        assert_eq!(fs::read_to_string(&manifest).unwrap(), formatted);
+    }
+
+    #[test]
+    fn format_in_place_keeps_comments() {
+        let dir = TempDir::new("format-comments");
+        let source = format!("# The echo server.\n{MANIFEST}");
+        let manifest = dir.write("echo.yaml", &source);
+        let err = format(manifest.clone(), true, None).unwrap_err();
+        assert!(err.to_string().contains("has comments"), "{err}");
+        assert_eq!(fs::read_to_string(&manifest).unwrap(), source);
+
+        let output = dir.0.join("formatted.yaml");
+        format(manifest, false, Some(output.clone())).unwrap();
+        assert!(!fs::read_to_string(&output).unwrap().contains('#'));
+    }
+
+    #[test]
+    fn comments_are_detected() {
+        assert!(has_comments("# header\nprogram: {}\n"));
+        assert!(has_comments("program: {binary: bin/echo} # trailing\n"));
+        assert!(!has_comments("program:\n  binary: bin/echo\n"));
+        assert!(!has_comments("program: {args: ['#1', \"\\\"#2\"]}\n"));
+        assert!(!has_comments("program: {binary: bin/echo#1}\n"));
+        assert!(has_comments("program: {binary: can't} # trailing\n"));
+    }
+
+    fn validate_references(dir: &TempDir, manifest: &str, contents: &str) -> Result<()> {
+        let decl = compile::compile(&serde_yml::from_str(manifest).unwrap()).unwrap();
+        let component_manifest = dir.0.join("echo.cm");
+        fs::write(&component_manifest, cm_config::encoding::encode(decl).unwrap()).unwrap();
+        let package_manifest = dir.write("contents", contents);
+        cmd_validate_references(ValidateReferencesCommand {
+            component_manifest,
+            package_manifest,
+        })
+    }
+
+    #[test]
+    fn validate_references_finds_the_binary() {
+        let dir = TempDir::new("validate-references");
+        let hash = "0".repeat(64);
+        validate_references(&dir, MANIFEST, &format!("bin/echo={hash}\n")).unwrap();
+        validate_references(&dir, "use: []\n", "").unwrap();
+
+        let err = validate_references(&dir, MANIFEST, &format!("bin/other={hash}\n")).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("program binary \"bin/echo\" is not in the package"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright 2024 MeshX Authors. All rights reserved.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Primitive {
+    Bool,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Uint8,
+    Uint16,
+    Uint32,
+    Uint64,
+    Float32,
+    Float64,
+}
+
+impl Primitive {
+    pub fn from_name(name: &str) -> Option<Primitive> {
+        Some(match name {
+            "bool" => Primitive::Bool,
+            "int8" => Primitive::Int8,
+            "int16" => Primitive::Int16,
+            "int32" => Primitive::Int32,
+            "int64" => Primitive::Int64,
+            "uint8" => Primitive::Uint8,
+            "uint16" => Primitive::Uint16,
+            "uint32" => Primitive::Uint32,
+            "uint64" => Primitive::Uint64,
+            "float32" => Primitive::Float32,
+            "float64" => Primitive::Float64,
+            _ => return None,
+        })
+    }
+
+    pub fn size(&self) -> u32 {
+        match self {
+            Primitive::Bool | Primitive::Int8 | Primitive::Uint8 => 1,
+            Primitive::Int16 | Primitive::Uint16 => 2,
+            Primitive::Int32 | Primitive::Uint32 | Primitive::Float32 => 4,
+            Primitive::Int64 | Primitive::Uint64 | Primitive::Float64 => 8,
+        }
+    }
+
+    /// The range of integer literals accepted for this type, or `None` if it
+    /// is not an integer type.
+    pub fn int_range(&self) -> Option<(i128, i128)> {
+        Some(match self {
+            Primitive::Int8 => (i8::MIN as i128, i8::MAX as i128),
+            Primitive::Int16 => (i16::MIN as i128, i16::MAX as i128),
+            Primitive::Int32 => (i32::MIN as i128, i32::MAX as i128),
+            Primitive::Int64 => (i64::MIN as i128, i64::MAX as i128),
+            Primitive::Uint8 => (0, u8::MAX as i128),
+            Primitive::Uint16 => (0, u16::MAX as i128),
+            Primitive::Uint32 => (0, u32::MAX as i128),
+            Primitive::Uint64 => (0, u64::MAX as i128),
+            _ => return None,
+        })
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, Primitive::Float32 | Primitive::Float64)
+    }
+}
+
+/// A type constructor as written in a declaration member.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    Primitive(Primitive),
+    String {
+        max: Option<u32>,
+        nullable: bool,
+    },
+    Vector {
+        element: Box<Type>,
+        max: Option<u32>,
+        nullable: bool,
+    },
+    Array {
+        element: Box<Type>,
+        count: u32,
+    },
+    /// A reference to a declaration. Nullable structs are written `box<T>`,
+    /// nullable unions `T:optional`.
+    Named {
+        name: String,
+        nullable: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeclKind {
+    Struct,
+    Union { strict: bool },
+    Table,
+}
+
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub name: String,
+    pub ty: Type,
+    /// Set for union and table members only.
+    pub ordinal: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Decl {
+    pub name: String,
+    pub file: String,
+    pub line: usize,
+    pub kind: DeclKind,
+    pub members: Vec<Member>,
+}
+
+impl Decl {
+    pub fn member(&self, name: &str) -> Option<&Member> {
+        self.members.iter().find(|m| m.name == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    String(String),
+    Null,
+    List(Vec<Value>),
+    /// A struct, union or table value. Union values have exactly one field.
+    Record {
+        name: String,
+        fields: Vec<(String, Value)>,
+    },
+}
+
+impl Value {
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Record { fields, .. } => fields.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+/// The error conditions a test can expect, named like the MIDL conformance
+/// suites of other bindings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    StringTooLong,
+    CountExceedsLimit,
+    InvalidPaddingByte,
+    InvalidBoolean,
+    NonNullableTypeWithNullValue,
+    StrictUnionUnknownField,
+    InvalidPresenceIndicator,
+    NonEmptyVectorWithNullBody,
+    TooFewBytes,
+    TooManyBytes,
+    InvalidInlineBitInEnvelope,
+    InvalidInlineMarkerInEnvelope,
+    InvalidNumBytesInEnvelope,
+}
+
+impl ErrorCode {
+    pub const ALL: &'static [(&'static str, ErrorCode)] = &[
+        ("STRING_TOO_LONG", ErrorCode::StringTooLong),
+        ("COUNT_EXCEEDS_LIMIT", ErrorCode::CountExceedsLimit),
+        ("INVALID_PADDING_BYTE", ErrorCode::InvalidPaddingByte),
+        ("INVALID_BOOLEAN", ErrorCode::InvalidBoolean),
+        (
+            "NON_NULLABLE_TYPE_WITH_NULL_VALUE",
+            ErrorCode::NonNullableTypeWithNullValue,
+        ),
+        ("STRICT_UNION_UNKNOWN_FIELD", ErrorCode::StrictUnionUnknownField),
+        ("INVALID_PRESENCE_INDICATOR", ErrorCode::InvalidPresenceIndicator),
+        ("NON_EMPTY_VECTOR_WITH_NULL_BODY", ErrorCode::NonEmptyVectorWithNullBody),
+        ("TOO_FEW_BYTES", ErrorCode::TooFewBytes),
+        ("TOO_MANY_BYTES", ErrorCode::TooManyBytes),
+        ("INVALID_INLINE_BIT_IN_ENVELOPE", ErrorCode::InvalidInlineBitInEnvelope),
+        (
+            "INVALID_INLINE_MARKER_IN_ENVELOPE",
+            ErrorCode::InvalidInlineMarkerInEnvelope,
+        ),
+        ("INVALID_NUM_BYTES_IN_ENVELOPE", ErrorCode::InvalidNumBytesInEnvelope),
+    ];
+
+    pub fn from_name(name: &str) -> Option<ErrorCode> {
+        Self::ALL.iter().find(|(n, _)| *n == name).map(|(_, code)| *code)
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = Self::ALL
+            .iter()
+            .find(|(_, code)| code == self)
+            .map(|(n, _)| *n)
+            .unwrap();
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    Rust,
+    TypeScript,
+}
+
+impl Binding {
+    pub fn from_name(name: &str) -> Option<Binding> {
+        match name {
+            "rust" => Some(Binding::Rust),
+            "typescript" => Some(Binding::TypeScript),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestKind {
+    /// Encoding `value` produces `bytes` and decoding `bytes` produces `value`.
+    Success,
+    EncodeSuccess,
+    DecodeSuccess,
+    /// Encoding `value` fails with `err`.
+    EncodeFailure,
+    /// Decoding `bytes` as `type` fails with `err`.
+    DecodeFailure,
+}
+
+impl TestKind {
+    pub fn from_name(name: &str) -> Option<TestKind> {
+        Some(match name {
+            "success" => TestKind::Success,
+            "encode_success" => TestKind::EncodeSuccess,
+            "decode_success" => TestKind::DecodeSuccess,
+            "encode_failure" => TestKind::EncodeFailure,
+            "decode_failure" => TestKind::DecodeFailure,
+            _ => return None,
+        })
+    }
+
+    pub fn encodes(&self) -> bool {
+        matches!(self, TestKind::Success | TestKind::EncodeSuccess)
+    }
+
+    pub fn decodes(&self) -> bool {
+        matches!(self, TestKind::Success | TestKind::DecodeSuccess)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Test {
+    pub kind: TestKind,
+    pub name: String,
+    pub file: String,
+    pub line: usize,
+    pub value: Option<Value>,
+    /// The top-level struct being tested, from `type = ...` or the value.
+    pub type_name: String,
+    pub bytes: Option<Vec<u8>>,
+    pub err: Option<ErrorCode>,
+    pub bindings_denylist: Vec<Binding>,
+}
+
+impl Test {
+    pub fn runs_on(&self, binding: Binding) -> bool {
+        !self.bindings_denylist.contains(&binding)
+    }
+}
+
+/// All declarations and tests of a conformance suite, which may span several
+/// `.gidl` files.
+#[derive(Debug, Default)]
+pub struct Suite {
+    pub decls: BTreeMap<String, Decl>,
+    pub tests: Vec<Test>,
+}
+
+impl Suite {
+    pub fn decl(&self, name: &str) -> &Decl {
+        self.decls
+            .get(name)
+            .unwrap_or_else(|| panic!("unknown declaration {name}; the suite was not validated"))
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright 2024 MeshX Authors. All rights reserved.
+
+//! Wire format V2 inline layout of the suite's types. Out-of-line objects
+//! are always 8-aligned, so only inline sizes and alignments are needed.
+
+use crate::ast::{Decl, DeclKind, Suite, Type};
+
+fn align_to(offset: u32, alignment: u32) -> u32 {
+    (offset + alignment - 1) & !(alignment - 1)
+}
+
+/// Returns the inline `(size, alignment)` of `ty`.
+pub fn inline_shape(suite: &Suite, ty: &Type) -> (u32, u32) {
+    match ty {
+        Type::Primitive(p) => (p.size(), p.size()),
+        Type::String { .. } | Type::Vector { .. } => (16, 8),
+        Type::Array { element, count } => {
+            let (size, alignment) = inline_shape(suite, element);
+            (size * count, alignment)
+        }
+        Type::Named { name, nullable } => {
+            let decl = suite.decl(name);
+            match decl.kind {
+                DeclKind::Struct if *nullable => (8, 8),
+                DeclKind::Struct => {
+                    let layout = struct_layout(suite, decl);
+                    (layout.size, layout.alignment)
+                }
+                DeclKind::Union { .. } | DeclKind::Table => (16, 8),
+            }
+        }
+    }
+}
+
+pub struct MemberLayout {
+    pub offset: u32,
+    /// Zero bytes following the member, up to the next member or the end of
+    /// the struct.
+    pub padding_offset: u32,
+    pub padding: u32,
+}
+
+pub struct StructLayout {
+    pub size: u32,
+    pub alignment: u32,
+    pub members: Vec<MemberLayout>,
+}
+
+pub fn struct_layout(suite: &Suite, decl: &Decl) -> StructLayout {
+    let mut offset = 0;
+    let mut alignment = 1;
+    let mut members: Vec<MemberLayout> = vec![];
+
+    for member in decl.members.iter() {
+        let (size, member_alignment) = inline_shape(suite, &member.ty);
+        offset = align_to(offset, member_alignment);
+        alignment = alignment.max(member_alignment);
+
+        if let Some(previous) = members.last_mut() {
+            previous.padding = offset - previous.padding_offset;
+        }
+
+        members.push(MemberLayout {
+            offset,
+            padding_offset: offset + size,
+            padding: 0,
+        });
+        offset += size;
+    }
+
+    let size = align_to(offset, alignment);
+    if let Some(last) = members.last_mut() {
+        last.padding = size - last.padding_offset;
+    }
+
+    StructLayout {
+        size,
+        alignment,
+        members,
+    }
+}
+
+/// A chunk of a struct that contains padding, as consumed by `midl_struct!`.
+pub struct PaddingMarker {
+    /// Width of the chunk in bytes: 2, 4 or 8.
+    pub width: u32,
+    pub offset: u32,
+    /// Little-endian mask with 0xff for padding bytes.
+    pub mask: u64,
+}
+
+/// Groups the padding of a struct into chunks as wide as its alignment (at
+/// most 8 bytes), so each chunk can be checked with a single aligned read.
+pub fn padding_markers(layout: &StructLayout) -> Vec<PaddingMarker> {
+    let width = layout.alignment.min(8);
+    let mut markers: Vec<PaddingMarker> = vec![];
+
+    for member in layout.members.iter() {
+        for byte in member.padding_offset..member.padding_offset + member.padding {
+            let offset = byte / width * width;
+            let shift = (byte - offset) * 8;
+            match markers.last_mut() {
+                Some(marker) if marker.offset == offset => marker.mask |= 0xff << shift,
+                _ => markers.push(PaddingMarker {
+                    width,
+                    offset,
+                    mask: 0xff << shift,
+                }),
+            }
+        }
+    }
+
+    markers
+}
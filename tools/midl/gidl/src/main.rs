@@ -0,0 +1,92 @@
+// Copyright 2024 MeshX Authors. All rights reserved.
+
+//! gidl generates wire-format conformance tests for the MIDL bindings from
+//! `.gidl` files. A suite declares the types it exercises and, for each
+//! test, a value together with its V2 encoding or the error that encoding or
+//! decoding must fail with:
+//!
+//! ```text
+//! type Point = struct { x int8; y int32; };
+//!
+//! success("Point") {
+//!     value = Point { x: 1, y: -1 },
+//!     bytes = { v2 = [ num(1):1, padding:3, num(-1):4 ] },
+//! }
+//! ```
+//!
+//! The same suite is generated into a Rust integration test for
+//! `midl::encoding` and a vitest spec for the TypeScript bindings:
+//!
+//! ```text
+//! cargo run -p gidl -- --language rust \
+//!     --out src/crates/midl/tests/conformance.rs src/crates/midl/testdata/conformance/*.gidl
+//! cargo run -p gidl -- --language typescript \
+//!     --out src/typescript/midl/src/test/conformance.spec.ts src/crates/midl/testdata/conformance/*.gidl
+//! ```
+
+mod ast;
+mod layout;
+mod parser;
+mod rust;
+mod typescript;
+mod validate;
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum GidlError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+
+    #[error("{file}:{line}: {message}")]
+    Syntax { file: String, line: usize, message: String },
+
+    #[error("{file}:{line}: {message}")]
+    Invalid { file: String, line: usize, message: String },
+}
+
+#[derive(clap::ValueEnum, Debug, Copy, Clone)]
+enum Language {
+    Rust,
+    Typescript,
+}
+
+#[derive(clap::Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+#[command(name = "MIDL conformance test generator")]
+struct Args {
+    /// Bindings to generate tests for
+    #[arg(long, value_enum)]
+    language: Language,
+
+    /// Path of the generated test file
+    #[arg(long)]
+    out: PathBuf,
+
+    /// The .gidl files making up the suite
+    #[arg(required = true, num_args = 1..)]
+    files: Vec<PathBuf>,
+}
+
+fn main() -> Result<(), GidlError> {
+    let args = Args::parse();
+
+    let mut suite = ast::Suite::default();
+    for path in args.files.iter() {
+        let source = std::fs::read_to_string(path)?;
+        let file = path.file_name().unwrap_or_default().to_string_lossy();
+        parser::parse(&file, &source, &mut suite)?;
+    }
+    validate::validate(&mut suite)?;
+
+    let output = match args.language {
+        Language::Rust => rust::generate(&suite),
+        Language::Typescript => typescript::generate(&suite),
+    };
+    std::fs::write(&args.out, output)?;
+
+    Ok(())
+}
@@ -0,0 +1,527 @@
+// Copyright 2024 MeshX Authors. All rights reserved.
+
+use crate::ast::{Binding, Decl, DeclKind, ErrorCode, Member, Primitive, Suite, Test, TestKind, Type, Value};
+use crate::GidlError;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Int(i128),
+    Float(f64),
+    Str(String),
+    Punct(char),
+    Eof,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(s) => write!(f, "`{s}`"),
+            Token::Int(i) => write!(f, "`{i}`"),
+            Token::Float(v) => write!(f, "`{v}`"),
+            Token::Str(s) => write!(f, "{s:?}"),
+            Token::Punct(c) => write!(f, "`{c}`"),
+            Token::Eof => write!(f, "end of file"),
+        }
+    }
+}
+
+fn lex(file: &str, source: &str) -> Result<Vec<(Token, usize)>, GidlError> {
+    let err = |line: usize, message: String| GidlError::Syntax {
+        file: file.to_owned(),
+        line,
+        message,
+    };
+
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = vec![];
+    let mut line = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\n' => {
+                line += 1;
+                i += 1;
+            }
+            c if c.is_whitespace() => i += 1,
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '"' => {
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None | Some('\n') => return Err(err(line, "unterminated string literal".to_owned())),
+                        Some('"') => break,
+                        Some('\\') => {
+                            let escaped = match chars.get(i + 1) {
+                                Some('n') => '\n',
+                                Some('t') => '\t',
+                                Some('\\') => '\\',
+                                Some('"') => '"',
+                                other => return Err(err(line, format!("invalid escape sequence {other:?}"))),
+                            };
+                            s.push(escaped);
+                            i += 2;
+                        }
+                        Some(c) => {
+                            s.push(*c);
+                            i += 1;
+                        }
+                    }
+                }
+                i += 1;
+                tokens.push((Token::Str(s), line));
+            }
+            c if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.' || chars[i] == '_') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+                let (negative, digits) = match text.strip_prefix('-') {
+                    Some(rest) => (true, rest),
+                    None => (false, text.as_str()),
+                };
+                let token = if let Some(hex) = digits.strip_prefix("0x") {
+                    i128::from_str_radix(hex, 16).ok().map(Token::Int)
+                } else if digits.contains('.') || digits.contains('e') {
+                    text.parse::<f64>().ok().map(Token::Float)
+                } else {
+                    digits.parse::<i128>().ok().map(Token::Int)
+                };
+                let token = match token {
+                    Some(Token::Int(v)) if negative => Token::Int(-v),
+                    Some(token) => token,
+                    None => return Err(err(line, format!("invalid number `{text}`"))),
+                };
+                tokens.push((token, line));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push((Token::Ident(chars[start..i].iter().collect()), line));
+            }
+            '=' | '{' | '}' | '(' | ')' | '[' | ']' | '<' | '>' | ',' | ':' | ';' => {
+                tokens.push((Token::Punct(c), line));
+                i += 1;
+            }
+            _ => return Err(err(line, format!("unexpected character `{c}`"))),
+        }
+    }
+
+    tokens.push((Token::Eof, line));
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    file: &'a str,
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn line(&self) -> usize {
+        self.tokens[self.pos].1
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].0.clone();
+        if token != Token::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, GidlError> {
+        Err(GidlError::Syntax {
+            file: self.file.to_owned(),
+            line: self.line(),
+            message: message.into(),
+        })
+    }
+
+    fn at_punct(&self, c: char) -> bool {
+        *self.peek() == Token::Punct(c)
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        if self.at_punct(c) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<(), GidlError> {
+        if self.eat_punct(c) {
+            return Ok(());
+        }
+        self.error(format!("expected `{c}`, found {}", self.peek()))
+    }
+
+    fn expect_ident(&mut self) -> Result<String, GidlError> {
+        match self.next() {
+            Token::Ident(s) => Ok(s),
+            other => {
+                self.pos -= 1;
+                self.error(format!("expected an identifier, found {other}"))
+            }
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), GidlError> {
+        let ident = self.expect_ident()?;
+        if ident != keyword {
+            return self.error(format!("expected `{keyword}`, found `{ident}`"));
+        }
+        Ok(())
+    }
+
+    fn expect_int(&mut self) -> Result<i128, GidlError> {
+        match self.next() {
+            Token::Int(i) => Ok(i),
+            other => {
+                self.pos -= 1;
+                self.error(format!("expected an integer, found {other}"))
+            }
+        }
+    }
+
+    fn expect_u32(&mut self) -> Result<u32, GidlError> {
+        let value = self.expect_int()?;
+        match u32::try_from(value) {
+            Ok(value) => Ok(value),
+            Err(_) => self.error(format!("`{value}` is out of range")),
+        }
+    }
+
+    fn parse_file(&mut self, suite: &mut Suite) -> Result<(), GidlError> {
+        while *self.peek() != Token::Eof {
+            let line = self.line();
+            let keyword = self.expect_ident()?;
+
+            if keyword == "type" {
+                let decl = self.parse_decl(line)?;
+                if suite.decls.contains_key(&decl.name) {
+                    return Err(GidlError::Syntax {
+                        file: self.file.to_owned(),
+                        line,
+                        message: format!("duplicate declaration {}", decl.name),
+                    });
+                }
+                suite.decls.insert(decl.name.clone(), decl);
+            } else if let Some(kind) = TestKind::from_name(&keyword) {
+                suite.tests.push(self.parse_test(kind, line)?);
+            } else {
+                self.pos -= 1;
+                return self.error(format!("expected a declaration or a test, found `{keyword}`"));
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_decl(&mut self, line: usize) -> Result<Decl, GidlError> {
+        let name = self.expect_ident()?;
+        self.expect_punct('=')?;
+
+        let kind = match self.expect_ident()?.as_str() {
+            "struct" => DeclKind::Struct,
+            "table" => DeclKind::Table,
+            "union" => DeclKind::Union { strict: false },
+            "strict" => {
+                self.expect_keyword("union")?;
+                DeclKind::Union { strict: true }
+            }
+            "flexible" => {
+                self.expect_keyword("union")?;
+                DeclKind::Union { strict: false }
+            }
+            other => return self.error(format!("unsupported layout `{other}`")),
+        };
+
+        let mut members = vec![];
+        self.expect_punct('{')?;
+        while !self.eat_punct('}') {
+            let ordinal = match kind {
+                DeclKind::Struct => None,
+                _ => {
+                    let ordinal = self.expect_int()?;
+                    self.expect_punct(':')?;
+                    match u64::try_from(ordinal) {
+                        Ok(ordinal) if ordinal > 0 => Some(ordinal),
+                        _ => return self.error(format!("invalid ordinal `{ordinal}`")),
+                    }
+                }
+            };
+            let name = self.expect_ident()?;
+            let ty = self.parse_type()?;
+            self.expect_punct(';')?;
+            members.push(Member { name, ty, ordinal });
+        }
+        self.expect_punct(';')?;
+
+        Ok(Decl {
+            name,
+            file: self.file.to_owned(),
+            line,
+            kind,
+            members,
+        })
+    }
+
+    fn parse_type(&mut self) -> Result<Type, GidlError> {
+        let name = self.expect_ident()?;
+
+        if let Some(primitive) = Primitive::from_name(&name) {
+            return Ok(Type::Primitive(primitive));
+        }
+
+        match name.as_str() {
+            "string" => {
+                let (max, nullable) = self.parse_constraints()?;
+                Ok(Type::String { max, nullable })
+            }
+            "vector" => {
+                self.expect_punct('<')?;
+                let element = Box::new(self.parse_type()?);
+                self.expect_punct('>')?;
+                let (max, nullable) = self.parse_constraints()?;
+                Ok(Type::Vector { element, max, nullable })
+            }
+            "array" => {
+                self.expect_punct('<')?;
+                let element = Box::new(self.parse_type()?);
+                self.expect_punct(',')?;
+                let count = self.expect_u32()?;
+                self.expect_punct('>')?;
+                Ok(Type::Array { element, count })
+            }
+            "box" => {
+                self.expect_punct('<')?;
+                let name = self.expect_ident()?;
+                self.expect_punct('>')?;
+                Ok(Type::Named { name, nullable: true })
+            }
+            "handle" => self.error("handles are not supported yet"),
+            _ => {
+                let (max, nullable) = self.parse_constraints()?;
+                if max.is_some() {
+                    return self.error(format!("`{name}` does not take a size constraint"));
+                }
+                Ok(Type::Named { name, nullable })
+            }
+        }
+    }
+
+    /// Parses `:N`, `:optional` or `:<N, optional>`.
+    fn parse_constraints(&mut self) -> Result<(Option<u32>, bool), GidlError> {
+        let mut max = None;
+        let mut nullable = false;
+
+        if !self.eat_punct(':') {
+            return Ok((max, nullable));
+        }
+
+        let bracketed = self.eat_punct('<');
+        loop {
+            match self.peek().clone() {
+                Token::Int(_) => max = Some(self.expect_u32()?),
+                Token::Ident(s) if s == "optional" => {
+                    self.pos += 1;
+                    nullable = true;
+                }
+                other => return self.error(format!("expected a constraint, found {other}")),
+            }
+            if !bracketed || !self.eat_punct(',') {
+                break;
+            }
+        }
+        if bracketed {
+            self.expect_punct('>')?;
+        }
+
+        Ok((max, nullable))
+    }
+
+    fn parse_test(&mut self, kind: TestKind, line: usize) -> Result<Test, GidlError> {
+        self.expect_punct('(')?;
+        let name = match self.next() {
+            Token::Str(s) => s,
+            other => return self.error(format!("expected the test name, found {other}")),
+        };
+        self.expect_punct(')')?;
+
+        let mut test = Test {
+            kind,
+            name,
+            file: self.file.to_owned(),
+            line,
+            value: None,
+            type_name: String::new(),
+            bytes: None,
+            err: None,
+            bindings_denylist: vec![],
+        };
+
+        self.expect_punct('{')?;
+        while !self.eat_punct('}') {
+            let field = self.expect_ident()?;
+            self.expect_punct('=')?;
+            match field.as_str() {
+                "value" => test.value = Some(self.parse_value()?),
+                "type" => test.type_name = self.expect_ident()?,
+                "bytes" => test.bytes = Some(self.parse_bytes()?),
+                "err" => {
+                    let code = self.expect_ident()?;
+                    match ErrorCode::from_name(&code) {
+                        Some(code) => test.err = Some(code),
+                        None => return self.error(format!("unknown error code `{code}`")),
+                    }
+                }
+                "bindings_denylist" => {
+                    self.expect_punct('[')?;
+                    while !self.eat_punct(']') {
+                        let binding = self.expect_ident()?;
+                        match Binding::from_name(&binding) {
+                            Some(binding) => test.bindings_denylist.push(binding),
+                            None => return self.error(format!("unknown binding `{binding}`")),
+                        }
+                        if !self.eat_punct(',') {
+                            self.expect_punct(']')?;
+                            break;
+                        }
+                    }
+                }
+                other => return self.error(format!("unknown test field `{other}`")),
+            }
+            if !self.eat_punct(',') {
+                self.expect_punct('}')?;
+                break;
+            }
+        }
+
+        Ok(test)
+    }
+
+    fn parse_value(&mut self) -> Result<Value, GidlError> {
+        match self.next() {
+            Token::Int(i) => Ok(Value::Int(i)),
+            Token::Float(f) => Ok(Value::Float(f)),
+            Token::Str(s) => Ok(Value::String(s)),
+            Token::Punct('[') => {
+                let mut items = vec![];
+                while !self.eat_punct(']') {
+                    items.push(self.parse_value()?);
+                    if !self.eat_punct(',') {
+                        self.expect_punct(']')?;
+                        break;
+                    }
+                }
+                Ok(Value::List(items))
+            }
+            Token::Ident(s) if s == "true" => Ok(Value::Bool(true)),
+            Token::Ident(s) if s == "false" => Ok(Value::Bool(false)),
+            Token::Ident(s) if s == "null" => Ok(Value::Null),
+            Token::Ident(name) => {
+                let mut fields = vec![];
+                self.expect_punct('{')?;
+                while !self.eat_punct('}') {
+                    let field = self.expect_ident()?;
+                    self.expect_punct(':')?;
+                    fields.push((field, self.parse_value()?));
+                    if !self.eat_punct(',') {
+                        self.expect_punct('}')?;
+                        break;
+                    }
+                }
+                Ok(Value::Record { name, fields })
+            }
+            other => {
+                self.pos -= 1;
+                self.error(format!("expected a value, found {other}"))
+            }
+        }
+    }
+
+    /// Parses `{ v2 = [ ... ] }`. Besides plain bytes, the list accepts
+    /// `num(N):SIZE` for a little-endian integer, `padding:SIZE` for zero
+    /// bytes and `repeat(B):COUNT` for a repeated byte.
+    fn parse_bytes(&mut self) -> Result<Vec<u8>, GidlError> {
+        self.expect_punct('{')?;
+        self.expect_keyword("v2")?;
+        self.expect_punct('=')?;
+        self.expect_punct('[')?;
+
+        let mut bytes = vec![];
+        while !self.eat_punct(']') {
+            match self.peek().clone() {
+                Token::Int(_) => {
+                    let byte = self.expect_int()?;
+                    match u8::try_from(byte) {
+                        Ok(byte) => bytes.push(byte),
+                        Err(_) => return self.error(format!("`{byte}` is not a byte")),
+                    }
+                }
+                Token::Ident(s) if s == "num" => {
+                    self.pos += 1;
+                    self.expect_punct('(')?;
+                    let value = self.expect_int()?;
+                    self.expect_punct(')')?;
+                    self.expect_punct(':')?;
+                    let size = self.expect_u32()? as usize;
+                    if ![1, 2, 4, 8].contains(&size) {
+                        return self.error(format!("invalid num size `{size}`"));
+                    }
+                    bytes.extend_from_slice(&value.to_le_bytes()[..size]);
+                }
+                Token::Ident(s) if s == "padding" => {
+                    self.pos += 1;
+                    self.expect_punct(':')?;
+                    let size = self.expect_u32()? as usize;
+                    bytes.extend(std::iter::repeat_n(0, size));
+                }
+                Token::Ident(s) if s == "repeat" => {
+                    self.pos += 1;
+                    self.expect_punct('(')?;
+                    let byte = self.expect_int()?;
+                    self.expect_punct(')')?;
+                    self.expect_punct(':')?;
+                    let count = self.expect_u32()? as usize;
+                    match u8::try_from(byte) {
+                        Ok(byte) => bytes.extend(std::iter::repeat_n(byte, count)),
+                        Err(_) => return self.error(format!("`{byte}` is not a byte")),
+                    }
+                }
+                other => return self.error(format!("expected bytes, found {other}")),
+            }
+            if !self.eat_punct(',') {
+                self.expect_punct(']')?;
+                break;
+            }
+        }
+
+        self.eat_punct(',');
+        self.expect_punct('}')?;
+        Ok(bytes)
+    }
+}
+
+/// Parses one `.gidl` file into `suite`.
+pub fn parse(file: &str, source: &str, suite: &mut Suite) -> Result<(), GidlError> {
+    let tokens = lex(file, source)?;
+    let mut parser = Parser { file, tokens, pos: 0 };
+    parser.parse_file(suite)
+}
@@ -0,0 +1,412 @@
+// Copyright 2024 MeshX Authors. All rights reserved.
+
+//! Generates a Rust integration test file exercising `midl::encoding`.
+
+use std::fmt::Write;
+
+use convert_case::{Case, Casing};
+
+use crate::ast::{Binding, Decl, DeclKind, ErrorCode, Primitive, Suite, Test, TestKind, Type, Value};
+use crate::layout;
+
+fn primitive(p: Primitive) -> &'static str {
+    match p {
+        Primitive::Bool => "bool",
+        Primitive::Int8 => "i8",
+        Primitive::Int16 => "i16",
+        Primitive::Int32 => "i32",
+        Primitive::Int64 => "i64",
+        Primitive::Uint8 => "u8",
+        Primitive::Uint16 => "u16",
+        Primitive::Uint32 => "u32",
+        Primitive::Uint64 => "u64",
+        Primitive::Float32 => "f32",
+        Primitive::Float64 => "f64",
+    }
+}
+
+/// The `midl::encoding` marker type for `ty`.
+fn marker(suite: &Suite, ty: &Type) -> String {
+    let (inner, nullable) = match ty {
+        Type::Primitive(p) => (primitive(*p).to_owned(), false),
+        Type::String { max: None, nullable } => ("UnboundedString".to_owned(), *nullable),
+        Type::String {
+            max: Some(max),
+            nullable,
+        } => (format!("BoundedString<{max}>"), *nullable),
+        Type::Vector {
+            element,
+            max: None,
+            nullable,
+        } => (format!("UnboundedVector<{}>", marker(suite, element)), *nullable),
+        Type::Vector {
+            element,
+            max: Some(max),
+            nullable,
+        } => (format!("Vector<{}, {max}>", marker(suite, element)), *nullable),
+        Type::Array { element, count } => (format!("Array<{}, {count}>", marker(suite, element)), false),
+        Type::Named { name, nullable: false } => (name.clone(), false),
+        Type::Named { name, nullable: true } => {
+            return match suite.decl(name).kind {
+                DeclKind::Struct => format!("Boxed<{name}>"),
+                _ => format!("OptionalUnion<{name}>"),
+            };
+        }
+    };
+
+    if nullable {
+        return format!("Optional<{inner}>");
+    }
+    inner
+}
+
+/// The Rust type values of `ty` are stored as.
+fn owned(ty: &Type) -> String {
+    match ty {
+        Type::Primitive(p) => primitive(*p).to_owned(),
+        Type::String { nullable: false, .. } => "String".to_owned(),
+        Type::String { nullable: true, .. } => "Option<String>".to_owned(),
+        Type::Vector {
+            element,
+            nullable: false,
+            ..
+        } => format!("Vec<{}>", owned(element)),
+        Type::Vector {
+            element,
+            nullable: true,
+            ..
+        } => format!("Option<Vec<{}>>", owned(element)),
+        Type::Array { element, count } => format!("[{}; {count}]", owned(element)),
+        Type::Named { name, nullable: false } => name.clone(),
+        Type::Named { name, nullable: true } => format!("Option<Box<{name}>>"),
+    }
+}
+
+fn variant(name: &str) -> String {
+    name.to_case(Case::UpperCamel)
+}
+
+fn value(suite: &Suite, ty: &Type, v: &Value) -> String {
+    let present = match (ty, v) {
+        (_, Value::Null) => return "None".to_owned(),
+        (Type::Primitive(p), Value::Int(i)) if p.is_float() => format!("{i}.0"),
+        (Type::Primitive(_), Value::Int(i)) => i.to_string(),
+        (Type::Primitive(_), Value::Float(f)) => format!("{f:?}"),
+        (Type::Primitive(_), Value::Bool(b)) => b.to_string(),
+        (Type::String { .. }, Value::String(s)) => format!("{s:?}.to_string()"),
+        (Type::Vector { element, .. }, Value::List(items)) => {
+            let items: Vec<String> = items.iter().map(|item| value(suite, element, item)).collect();
+            format!("vec![{}]", items.join(", "))
+        }
+        (Type::Array { element, .. }, Value::List(items)) => {
+            let items: Vec<String> = items.iter().map(|item| value(suite, element, item)).collect();
+            format!("[{}]", items.join(", "))
+        }
+        (Type::Named { name, .. }, Value::Record { fields, .. }) => {
+            let decl = suite.decl(name);
+            match decl.kind {
+                DeclKind::Struct => {
+                    let mut s = format!("{name} {{ ");
+                    for member in decl.members.iter() {
+                        let field = v.field(&member.name).unwrap();
+                        write!(s, "{}: {}, ", member.name, value(suite, &member.ty, field)).unwrap();
+                    }
+                    s + "}"
+                }
+                DeclKind::Union { .. } => {
+                    let (field, field_value) = &fields[0];
+                    let member = decl.member(field).unwrap();
+                    format!("{name}::{}({})", variant(field), value(suite, &member.ty, field_value))
+                }
+                DeclKind::Table => {
+                    let mut s = format!("{name} {{ ");
+                    for member in decl.members.iter() {
+                        if let Some(field) = v.field(&member.name) {
+                            write!(s, "{}: Some({}), ", member.name, value(suite, &member.ty, field)).unwrap();
+                        }
+                    }
+                    s + &format!("..{name}::EMPTY }}")
+                }
+            }
+        }
+        (ty, v) => panic!("{v:?} is not a valid {ty:?}; the suite was not validated"),
+    };
+
+    match ty {
+        Type::String { nullable: true, .. } | Type::Vector { nullable: true, .. } => format!("Some({present})"),
+        Type::Named { nullable: true, .. } => format!("Some(Box::new({present}))"),
+        _ => present,
+    }
+}
+
+fn error_pattern(err: ErrorCode) -> &'static str {
+    match err {
+        ErrorCode::StringTooLong => "Error::StringTooLong { .. }",
+        ErrorCode::CountExceedsLimit => "Error::VectorTooLong { .. }",
+        ErrorCode::InvalidPaddingByte => "Error::NonZeroPadding { .. }",
+        ErrorCode::InvalidBoolean => "Error::InvalidBoolean",
+        ErrorCode::NonNullableTypeWithNullValue => "Error::NotNullable",
+        ErrorCode::StrictUnionUnknownField => "Error::UnknownUnionTag",
+        ErrorCode::InvalidPresenceIndicator => "Error::InvalidPresenceIndicator",
+        ErrorCode::NonEmptyVectorWithNullBody => "Error::UnexpectedNullRef",
+        ErrorCode::TooFewBytes => "Error::OutOfRange",
+        ErrorCode::TooManyBytes => "Error::ExtraBytes",
+        ErrorCode::InvalidInlineBitInEnvelope => "Error::InvalidInlineBitInEnvelope",
+        ErrorCode::InvalidInlineMarkerInEnvelope => "Error::InvalidInlineMarkerInEnvelope",
+        ErrorCode::InvalidNumBytesInEnvelope => "Error::InvalidNumBytesInEnvelope",
+    }
+}
+
+fn bytes_literal(bytes: &[u8]) -> String {
+    let mut s = String::from("&[\n");
+    for chunk in bytes.chunks(8) {
+        let line: Vec<String> = chunk.iter().map(|b| format!("0x{b:02x}")).collect();
+        writeln!(s, "        {},", line.join(", ")).unwrap();
+    }
+    s + "    ]"
+}
+
+fn write_struct(out: &mut String, suite: &Suite, decl: &Decl) {
+    let layout = layout::struct_layout(suite, decl);
+
+    writeln!(out, "#[derive(Debug, PartialEq)]").unwrap();
+    writeln!(out, "pub struct {} {{", decl.name).unwrap();
+    for member in decl.members.iter() {
+        writeln!(out, "    pub {}: {},", member.name, owned(&member.ty)).unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "midl::midl_struct! {{").unwrap();
+    writeln!(out, "    name: {},", decl.name).unwrap();
+    writeln!(out, "    members: [").unwrap();
+    for (index, (member, member_layout)) in decl.members.iter().zip(layout.members.iter()).enumerate() {
+        writeln!(out, "        {} {{", member.name).unwrap();
+        writeln!(out, "            ty: {},", marker(suite, &member.ty)).unwrap();
+        writeln!(out, "            index: {index},").unwrap();
+        writeln!(out, "            typevar: T{index},").unwrap();
+        writeln!(out, "            offset_v1: {},", member_layout.offset).unwrap();
+        writeln!(out, "            offset_v2: {},", member_layout.offset).unwrap();
+        writeln!(out, "        }},").unwrap();
+    }
+    writeln!(out, "    ],").unwrap();
+
+    let markers = layout::padding_markers(&layout);
+    for version in ["v1", "v2"] {
+        writeln!(out, "    padding_{version}: [").unwrap();
+        for marker in markers.iter() {
+            writeln!(out, "        {{").unwrap();
+            writeln!(out, "            ty: u{},", marker.width * 8).unwrap();
+            writeln!(out, "            offset: {},", marker.offset).unwrap();
+            writeln!(out, "            mask: {:#x},", marker.mask).unwrap();
+            writeln!(out, "        }},").unwrap();
+        }
+        writeln!(out, "    ],").unwrap();
+    }
+
+    writeln!(out, "    size_v1: {},", layout.size).unwrap();
+    writeln!(out, "    size_v2: {},", layout.size).unwrap();
+    writeln!(out, "    align_v1: {},", layout.alignment).unwrap();
+    writeln!(out, "    align_v2: {},", layout.alignment).unwrap();
+    writeln!(out, "}}\n").unwrap();
+}
+
+fn write_union(out: &mut String, suite: &Suite, decl: &Decl, strict: bool) {
+    writeln!(out, "#[derive(Debug, PartialEq)]").unwrap();
+    writeln!(out, "pub enum {} {{", decl.name).unwrap();
+    for member in decl.members.iter() {
+        writeln!(out, "    {}({}),", variant(&member.name), owned(&member.ty)).unwrap();
+    }
+    if !strict {
+        writeln!(out, "    #[doc(hidden)]").unwrap();
+        writeln!(out, "    __SourceBreaking {{ ordinal: u64 }},").unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl {} {{", decl.name).unwrap();
+    writeln!(out, "    pub fn ordinal(&self) -> u64 {{").unwrap();
+    writeln!(out, "        match *self {{").unwrap();
+    for member in decl.members.iter() {
+        writeln!(
+            out,
+            "            Self::{}(_) => {},",
+            variant(&member.name),
+            member.ordinal.unwrap()
+        )
+        .unwrap();
+    }
+    if !strict {
+        writeln!(out, "            Self::__SourceBreaking {{ ordinal }} => ordinal,").unwrap();
+    }
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "midl::midl_union! {{").unwrap();
+    writeln!(out, "    name: {},", decl.name).unwrap();
+    writeln!(out, "    members: [").unwrap();
+    for member in decl.members.iter() {
+        writeln!(out, "        {} {{", variant(&member.name)).unwrap();
+        writeln!(out, "            ty: {},", marker(suite, &member.ty)).unwrap();
+        writeln!(out, "            ordinal: {},", member.ordinal.unwrap()).unwrap();
+        writeln!(out, "        }},").unwrap();
+    }
+    writeln!(out, "    ],").unwrap();
+    if !strict {
+        writeln!(out, "    unknown_member: __SourceBreaking,").unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+}
+
+fn write_table(out: &mut String, suite: &Suite, decl: &Decl) {
+    let mut members: Vec<_> = decl.members.iter().collect();
+    members.sort_by_key(|m| m.ordinal);
+
+    writeln!(out, "#[derive(Debug, PartialEq)]").unwrap();
+    writeln!(out, "pub struct {} {{", decl.name).unwrap();
+    for member in members.iter() {
+        writeln!(out, "    pub {}: Option<{}>,", member.name, owned(&member.ty)).unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl {} {{", decl.name).unwrap();
+    writeln!(out, "    pub const EMPTY: Self = Self {{").unwrap();
+    for member in members.iter() {
+        writeln!(out, "        {}: None,", member.name).unwrap();
+    }
+    writeln!(out, "    }};").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "midl::midl_table! {{").unwrap();
+    writeln!(out, "    name: {},", decl.name).unwrap();
+    writeln!(out, "    members: [").unwrap();
+    for member in members.iter() {
+        writeln!(out, "        {} {{", member.name).unwrap();
+        writeln!(out, "            ty: {},", marker(suite, &member.ty)).unwrap();
+        writeln!(out, "            ordinal: {},", member.ordinal.unwrap()).unwrap();
+        writeln!(out, "        }},").unwrap();
+    }
+    writeln!(out, "    ],").unwrap();
+    writeln!(out, "}}\n").unwrap();
+}
+
+fn write_test(out: &mut String, suite: &Suite, test: &Test) {
+    let name = test.name.to_case(Case::Snake);
+    let ty = Type::Named {
+        name: test.type_name.clone(),
+        nullable: false,
+    };
+    let type_name = &test.type_name;
+
+    writeln!(out, "// {}:{}", test.file, test.line).unwrap();
+
+    if test.kind.encodes() {
+        let v = value(suite, &ty, test.value.as_ref().unwrap());
+        let bytes = bytes_literal(test.bytes.as_ref().unwrap());
+        writeln!(out, "#[test]").unwrap();
+        writeln!(out, "fn {name}_encode() {{").unwrap();
+        writeln!(out, "    let value = &{v};").unwrap();
+        writeln!(out, "    let buf = &mut Vec::new();").unwrap();
+        writeln!(out, "    let handle_buf = &mut Vec::new();").unwrap();
+        writeln!(
+            out,
+            "    Encoder::encode_with_context::<{type_name}>(V2, buf, handle_buf, value).expect(\"encoding failed\");"
+        )
+        .unwrap();
+        writeln!(out, "    let expected: &[u8] = {bytes};").unwrap();
+        writeln!(out, "    assert_eq!(buf.as_slice(), expected);").unwrap();
+        writeln!(out, "}}\n").unwrap();
+    }
+
+    if test.kind.decodes() {
+        let v = value(suite, &ty, test.value.as_ref().unwrap());
+        let bytes = bytes_literal(test.bytes.as_ref().unwrap());
+        writeln!(out, "#[test]").unwrap();
+        writeln!(out, "fn {name}_decode() {{").unwrap();
+        writeln!(out, "    let bytes: &[u8] = {bytes};").unwrap();
+        writeln!(out, "    let value = &mut midl::new_empty!({type_name});").unwrap();
+        writeln!(
+            out,
+            "    Decoder::decode_with_context::<{type_name}>(V2, bytes, &mut [], value).expect(\"decoding failed\");"
+        )
+        .unwrap();
+        writeln!(out, "    assert_eq!(value, &{v});").unwrap();
+        writeln!(out, "}}\n").unwrap();
+    }
+
+    match test.kind {
+        TestKind::EncodeFailure => {
+            let v = value(suite, &ty, test.value.as_ref().unwrap());
+            writeln!(out, "#[test]").unwrap();
+            writeln!(out, "fn {name}_encode_failure() {{").unwrap();
+            writeln!(out, "    let value = &{v};").unwrap();
+            writeln!(out, "    let buf = &mut Vec::new();").unwrap();
+            writeln!(out, "    let handle_buf = &mut Vec::new();").unwrap();
+            writeln!(
+                out,
+                "    let result = Encoder::encode_with_context::<{type_name}>(V2, buf, handle_buf, value);"
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "    assert_matches!(result, Err({}));",
+                error_pattern(test.err.unwrap())
+            )
+            .unwrap();
+            writeln!(out, "}}\n").unwrap();
+        }
+        TestKind::DecodeFailure => {
+            let bytes = bytes_literal(test.bytes.as_ref().unwrap());
+            writeln!(out, "#[test]").unwrap();
+            writeln!(out, "fn {name}_decode_failure() {{").unwrap();
+            writeln!(out, "    let bytes: &[u8] = {bytes};").unwrap();
+            writeln!(out, "    let value = &mut midl::new_empty!({type_name});").unwrap();
+            writeln!(
+                out,
+                "    let result = Decoder::decode_with_context::<{type_name}>(V2, bytes, &mut [], value);"
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "    assert_matches!(result, Err({}));",
+                error_pattern(test.err.unwrap())
+            )
+            .unwrap();
+            writeln!(out, "}}\n").unwrap();
+        }
+        _ => {}
+    }
+}
+
+pub fn generate(suite: &Suite) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "// WARNING: This file is machine generated by gidl. Do not edit.").unwrap();
+    writeln!(
+        out,
+        "// Regenerate it with `cargo run -p gidl -- --language rust`, see tools/midl/gidl.\n"
+    )
+    .unwrap();
+    writeln!(out, "#![allow(dead_code, non_camel_case_types)]\n").unwrap();
+    writeln!(out, "use assert_matches::assert_matches;").unwrap();
+    writeln!(out, "use midl::encoding::*;").unwrap();
+    writeln!(out, "use midl::Error;\n").unwrap();
+    writeln!(out, "const V2: Context = Context {{").unwrap();
+    writeln!(out, "    wire_format_version: WireFormatVersion::V2,").unwrap();
+    writeln!(out, "}};\n").unwrap();
+
+    for decl in suite.decls.values() {
+        match decl.kind {
+            DeclKind::Struct => write_struct(&mut out, suite, decl),
+            DeclKind::Union { strict } => write_union(&mut out, suite, decl, strict),
+            DeclKind::Table => write_table(&mut out, suite, decl),
+        }
+    }
+
+    for test in suite.tests.iter().filter(|t| t.runs_on(Binding::Rust)) {
+        write_test(&mut out, suite, test);
+    }
+
+    // Drop the blank line after the last item.
+    out.pop();
+    out
+}
@@ -0,0 +1,492 @@
+// Copyright 2024 MeshX Authors. All rights reserved.
+
+//! Generates a vitest spec exercising the TypeScript bindings. Declarations
+//! are emitted the way midlgen_ts emits them, so the spec covers the same
+//! code paths as generated libraries.
+
+use std::fmt::Write;
+
+use convert_case::{Case, Casing};
+
+use crate::ast::{Binding, Decl, DeclKind, ErrorCode, Primitive, Suite, Test, TestKind, Type, Value};
+use crate::layout;
+
+fn primitive_ctor(p: Primitive) -> &'static str {
+    match p {
+        Primitive::Bool => "new midl.BoolType()",
+        Primitive::Int8 => "new midl.Int8Type()",
+        Primitive::Int16 => "new midl.Int16Type()",
+        Primitive::Int32 => "new midl.Int32Type()",
+        Primitive::Int64 => "new midl.Int64Type()",
+        Primitive::Uint8 => "new midl.UInt8Type()",
+        Primitive::Uint16 => "new midl.UInt16Type()",
+        Primitive::Uint32 => "new midl.UInt32Type()",
+        Primitive::Uint64 => "new midl.UInt64Type()",
+        Primitive::Float32 => "new midl.Float32Type()",
+        Primitive::Float64 => "new midl.Float64Type()",
+    }
+}
+
+fn is_bigint(p: Primitive) -> bool {
+    matches!(p, Primitive::Int64 | Primitive::Uint64)
+}
+
+fn max_arg(max: &Option<u32>) -> String {
+    max.map(|max| format!(", {max}")).unwrap_or_default()
+}
+
+/// The `midl.MidlType` instance for `ty`.
+fn ctor(suite: &Suite, ty: &Type) -> String {
+    match ty {
+        Type::Primitive(p) => primitive_ctor(*p).to_owned(),
+        Type::String { max, nullable } => {
+            let class = if *nullable { "NullableStringType" } else { "StringType" };
+            format!("new midl.{class}({})", max.map(|m| m.to_string()).unwrap_or_default())
+        }
+        Type::Vector { element, max, nullable } => {
+            let class = if *nullable { "NullableVectorType" } else { "VectorType" };
+            format!("new midl.{class}({}{})", ctor(suite, element), max_arg(max))
+        }
+        Type::Array { element, count } => format!("new midl.ArrayType({}, {count})", ctor(suite, element)),
+        Type::Named { name, nullable: false } => format!("_{name}Type"),
+        Type::Named { name, nullable: true } => match suite.decl(name).kind {
+            DeclKind::Struct => format!("new midl.BoxType(_{name}Type)"),
+            _ => format!("_{name}NullableType"),
+        },
+    }
+}
+
+/// The TypeScript type values of `ty` are stored as.
+fn param(ty: &Type) -> String {
+    let (inner, nullable) = match ty {
+        Type::Primitive(Primitive::Bool) => ("boolean".to_owned(), false),
+        Type::Primitive(p) if is_bigint(*p) => ("bigint".to_owned(), false),
+        Type::Primitive(_) => ("number".to_owned(), false),
+        Type::String { nullable, .. } => ("string".to_owned(), *nullable),
+        Type::Vector { element, nullable, .. } => (format!("{}[]", param(element)), *nullable),
+        Type::Array { element, .. } => (format!("{}[]", param(element)), false),
+        Type::Named { name, nullable } => (name.clone(), *nullable),
+    };
+
+    if nullable {
+        return format!("{inner} | null");
+    }
+    inner
+}
+
+fn camel(name: &str) -> String {
+    name.to_case(Case::Camel)
+}
+
+fn upper_camel(name: &str) -> String {
+    name.to_case(Case::UpperCamel)
+}
+
+fn sorted_members(decl: &Decl) -> Vec<&crate::ast::Member> {
+    let mut members: Vec<_> = decl.members.iter().collect();
+    members.sort_by_key(|m| m.ordinal);
+    members
+}
+
+fn value(suite: &Suite, ty: &Type, v: &Value) -> String {
+    match (ty, v) {
+        (_, Value::Null) => "null".to_owned(),
+        (Type::Primitive(p), Value::Int(i)) if is_bigint(*p) => format!("{i}n"),
+        (Type::Primitive(_), Value::Int(i)) => i.to_string(),
+        (Type::Primitive(_), Value::Float(f)) => format!("{f:?}"),
+        (Type::Primitive(_), Value::Bool(b)) => b.to_string(),
+        (Type::String { .. }, Value::String(s)) => serde_json::to_string(s).unwrap(),
+        (Type::Vector { element, .. } | Type::Array { element, .. }, Value::List(items)) => {
+            let items: Vec<String> = items.iter().map(|item| value(suite, element, item)).collect();
+            format!("[{}]", items.join(", "))
+        }
+        (Type::Named { name, .. }, Value::Record { fields, .. }) => {
+            let decl = suite.decl(name);
+            match decl.kind {
+                DeclKind::Struct => {
+                    let args: Vec<String> = decl
+                        .members
+                        .iter()
+                        .map(|m| value(suite, &m.ty, v.field(&m.name).unwrap()))
+                        .collect();
+                    format!("new {name}({})", args.join(", "))
+                }
+                DeclKind::Union { .. } => {
+                    let (field, field_value) = &fields[0];
+                    let member = decl.member(field).unwrap();
+                    format!(
+                        "{name}.with{}({})",
+                        upper_camel(field),
+                        value(suite, &member.ty, field_value)
+                    )
+                }
+                DeclKind::Table => {
+                    let args: Vec<String> = sorted_members(decl)
+                        .iter()
+                        .map(|m| match v.field(&m.name) {
+                            Some(field) => value(suite, &m.ty, field),
+                            None => "null".to_owned(),
+                        })
+                        .collect();
+                    format!("new {name}({})", args.join(", "))
+                }
+            }
+        }
+        (ty, v) => panic!("{v:?} is not a valid {ty:?}; the suite was not validated"),
+    }
+}
+
+fn error_code(err: ErrorCode) -> &'static str {
+    match err {
+        ErrorCode::StringTooLong => "StringTooLong",
+        ErrorCode::CountExceedsLimit => "CountExceedsLimit",
+        ErrorCode::InvalidPaddingByte => "InvalidPaddingByte",
+        ErrorCode::InvalidBoolean => "InvalidBoolean",
+        ErrorCode::NonNullableTypeWithNullValue => "NonNullableTypeWithNullValue",
+        ErrorCode::StrictUnionUnknownField => "StrictUnionUnknownField",
+        ErrorCode::InvalidPresenceIndicator => "InvalidPresenceIndicator",
+        ErrorCode::NonEmptyVectorWithNullBody => "NonEmptyVectorWithNullBody",
+        ErrorCode::TooFewBytes => "TooFewBytes",
+        ErrorCode::TooManyBytes => "TooManyBytes",
+        ErrorCode::InvalidInlineBitInEnvelope => "InvalidInlineBitInEnvelope",
+        ErrorCode::InvalidInlineMarkerInEnvelope => "InvalidInlineMarkerInEnvelope",
+        ErrorCode::InvalidNumBytesInEnvelope => "InvalidNumBytesInEnvelope",
+    }
+}
+
+fn bytes_literal(bytes: &[u8]) -> String {
+    let mut s = String::from("new Uint8Array([\n");
+    for chunk in bytes.chunks(8) {
+        let line: Vec<String> = chunk.iter().map(|b| format!("0x{b:02x}")).collect();
+        writeln!(s, "            {},", line.join(", ")).unwrap();
+    }
+    s + "        ])"
+}
+
+fn named_dependencies<'a>(ty: &'a Type, out: &mut Vec<&'a str>) {
+    match ty {
+        Type::Vector { element, .. } | Type::Array { element, .. } => named_dependencies(element, out),
+        Type::Named { name, .. } => out.push(name),
+        _ => {}
+    }
+}
+
+/// Orders declarations so that each one follows the declarations its member
+/// types refer to; the `_XType` constants are used in static initializers.
+/// Recursive types are emitted in an arbitrary order within their cycle.
+fn declaration_order(suite: &Suite) -> Vec<&Decl> {
+    fn visit<'a>(suite: &'a Suite, decl: &'a Decl, visiting: &mut Vec<&'a str>, order: &mut Vec<&'a Decl>) {
+        if visiting.contains(&decl.name.as_str()) || order.iter().any(|d| d.name == decl.name) {
+            return;
+        }
+        visiting.push(&decl.name);
+        let mut dependencies = vec![];
+        for member in decl.members.iter() {
+            named_dependencies(&member.ty, &mut dependencies);
+        }
+        for dependency in dependencies {
+            visit(suite, suite.decl(dependency), visiting, order);
+        }
+        visiting.pop();
+        order.push(decl);
+    }
+
+    let mut order = vec![];
+    for decl in suite.decls.values() {
+        visit(suite, decl, &mut vec![], &mut order);
+    }
+    order
+}
+
+fn write_struct(out: &mut String, suite: &Suite, decl: &Decl) {
+    let layout = layout::struct_layout(suite, decl);
+    let name = &decl.name;
+
+    writeln!(out, "class {name} implements midl.Struct {{").unwrap();
+    for (index, member) in decl.members.iter().enumerate() {
+        writeln!(out, "    static #fieldType{index} = {}", ctor(suite, &member.ty)).unwrap();
+    }
+    writeln!(out).unwrap();
+    writeln!(out, "    constructor(").unwrap();
+    for member in decl.members.iter() {
+        writeln!(out, "        public {}: {},", camel(&member.name), param(&member.ty)).unwrap();
+    }
+    writeln!(out, "    ) {{}}\n").unwrap();
+
+    writeln!(
+        out,
+        "    $encode(encoder: midl.Encoder, offset: number, depth: number): void {{"
+    )
+    .unwrap();
+    for (index, (member, member_layout)) in decl.members.iter().zip(layout.members.iter()).enumerate() {
+        writeln!(
+            out,
+            "        {name}.#fieldType{index}.encode(encoder, this.{}, offset + {}, depth)",
+            camel(&member.name),
+            member_layout.offset
+        )
+        .unwrap();
+    }
+    writeln!(out, "    }}\n").unwrap();
+
+    writeln!(
+        out,
+        "    static $decode(decoder: midl.Decoder, offset: number, depth: number) {{"
+    )
+    .unwrap();
+    for member_layout in layout.members.iter().filter(|m| m.padding > 0) {
+        writeln!(
+            out,
+            "        decoder.checkPadding(offset + {}, {})",
+            member_layout.padding_offset, member_layout.padding
+        )
+        .unwrap();
+    }
+    writeln!(out, "        return new {name}(").unwrap();
+    for (index, member_layout) in layout.members.iter().enumerate() {
+        writeln!(
+            out,
+            "            {name}.#fieldType{index}.decode(decoder, offset + {}, depth),",
+            member_layout.offset
+        )
+        .unwrap();
+    }
+    writeln!(out, "        )").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(
+        out,
+        "const _{name}Type = new midl.StructType<{name}>({}, {name}.$decode)\n",
+        layout.size
+    )
+    .unwrap();
+}
+
+fn write_union(out: &mut String, suite: &Suite, decl: &Decl, strict: bool) {
+    let name = &decl.name;
+
+    writeln!(out, "class {name} extends midl.Union {{").unwrap();
+    writeln!(out, "    #ordinal: number").unwrap();
+    writeln!(out, "    #data: any\n").unwrap();
+    writeln!(out, "    constructor(ordinal: number, data: any) {{").unwrap();
+    writeln!(out, "        super()").unwrap();
+    writeln!(out, "        this.#ordinal = ordinal").unwrap();
+    writeln!(out, "        this.#data = data").unwrap();
+    writeln!(out, "    }}\n").unwrap();
+    for member in decl.members.iter() {
+        writeln!(
+            out,
+            "    static with{}(value: {}): {name} {{",
+            upper_camel(&member.name),
+            param(&member.ty)
+        )
+        .unwrap();
+        writeln!(out, "        return new {name}({}, value)", member.ordinal.unwrap()).unwrap();
+        writeln!(out, "    }}\n").unwrap();
+    }
+    writeln!(out, "    override get $ordinal(): number {{").unwrap();
+    writeln!(out, "        return this.#ordinal").unwrap();
+    writeln!(out, "    }}\n").unwrap();
+    writeln!(out, "    override get $data(): unknown {{").unwrap();
+    writeln!(out, "        return this.#data").unwrap();
+    writeln!(out, "    }}\n").unwrap();
+    writeln!(out, "    static ctor(ordinal: number, data: object): {name} {{").unwrap();
+    writeln!(out, "        return new {name}(ordinal, data)").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "const _{name}Members = {{").unwrap();
+    for member in decl.members.iter() {
+        writeln!(out, "    {}: {},", member.ordinal.unwrap(), ctor(suite, &member.ty)).unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    for class in ["UnionType", "NullableUnionType"] {
+        let suffix = if class == "UnionType" { "" } else { "Nullable" };
+        writeln!(
+            out,
+            "const _{name}{suffix}Type = new midl.{class}<{name}>(_{name}Members, {name}.ctor, {}, false)\n",
+            !strict
+        )
+        .unwrap();
+    }
+}
+
+fn write_table(out: &mut String, suite: &Suite, decl: &Decl) {
+    let name = &decl.name;
+    let members = sorted_members(decl);
+
+    writeln!(out, "class {name} extends midl.Table {{").unwrap();
+    writeln!(out, "    constructor(").unwrap();
+    for member in members.iter() {
+        writeln!(
+            out,
+            "        public {}: {} | null = null,",
+            camel(&member.name),
+            param(&member.ty)
+        )
+        .unwrap();
+    }
+    writeln!(
+        out,
+        "        public $unknownData: Map<number, midl.UnknownRawData> = new Map(),"
+    )
+    .unwrap();
+    writeln!(out, "    ) {{").unwrap();
+    writeln!(out, "        super()").unwrap();
+    writeln!(out, "    }}\n").unwrap();
+
+    writeln!(out, "    override get $fields(): Map<number, unknown> {{").unwrap();
+    writeln!(
+        out,
+        "        const fields = new Map<number, unknown>(this.$unknownData)"
+    )
+    .unwrap();
+    for member in members.iter() {
+        let field = camel(&member.name);
+        writeln!(
+            out,
+            "        if (this.{field} !== null) fields.set({}, this.{field})",
+            member.ordinal.unwrap()
+        )
+        .unwrap();
+    }
+    writeln!(out, "        return fields").unwrap();
+    writeln!(out, "    }}\n").unwrap();
+
+    writeln!(out, "    static ctor(fields: Map<number, unknown>): {name} {{").unwrap();
+    writeln!(out, "        return new {name}(").unwrap();
+    for member in members.iter() {
+        writeln!(
+            out,
+            "            (fields.get({}) ?? null) as {} | null,",
+            member.ordinal.unwrap(),
+            param(&member.ty)
+        )
+        .unwrap();
+    }
+    writeln!(out, "        )").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "const _{name}Type = new midl.TableType<{name}>(").unwrap();
+    writeln!(out, "    {{").unwrap();
+    for member in members.iter() {
+        writeln!(out, "        {}: {},", member.ordinal.unwrap(), ctor(suite, &member.ty)).unwrap();
+    }
+    writeln!(out, "    }},").unwrap();
+    writeln!(out, "    {name}.ctor,").unwrap();
+    writeln!(out, "    false").unwrap();
+    writeln!(out, ")\n").unwrap();
+}
+
+fn write_test(out: &mut String, suite: &Suite, test: &Test) {
+    let ty = Type::Named {
+        name: test.type_name.clone(),
+        nullable: false,
+    };
+    let type_const = format!("_{}Type", test.type_name);
+
+    writeln!(out, "    // {}:{}", test.file, test.line).unwrap();
+
+    if test.kind.encodes() {
+        let v = value(suite, &ty, test.value.as_ref().unwrap());
+        let bytes = bytes_literal(test.bytes.as_ref().unwrap());
+        writeln!(out, "    it(\"{} encode\", () => {{", test.name).unwrap();
+        writeln!(out, "        const value = {v}").unwrap();
+        writeln!(out, "        const expected = {bytes}").unwrap();
+        writeln!(
+            out,
+            "        expect(midl.Encoder.encode([], value, {type_const})).toEqual(expected)"
+        )
+        .unwrap();
+        writeln!(out, "    }})\n").unwrap();
+    }
+
+    if test.kind.decodes() {
+        let v = value(suite, &ty, test.value.as_ref().unwrap());
+        let bytes = bytes_literal(test.bytes.as_ref().unwrap());
+        writeln!(out, "    it(\"{} decode\", () => {{", test.name).unwrap();
+        writeln!(out, "        const bytes = {bytes}").unwrap();
+        writeln!(
+            out,
+            "        const value = midl.Decoder.decode(bytes, [], {type_const})"
+        )
+        .unwrap();
+        writeln!(out, "        expect(value).toEqual({v})").unwrap();
+        writeln!(
+            out,
+            "        expect(midl.Encoder.encode([], value, {type_const})).toEqual(bytes)"
+        )
+        .unwrap();
+        writeln!(out, "    }})\n").unwrap();
+    }
+
+    match test.kind {
+        TestKind::EncodeFailure => {
+            let v = value(suite, &ty, test.value.as_ref().unwrap());
+            writeln!(out, "    it(\"{} encode failure\", () => {{", test.name).unwrap();
+            writeln!(out, "        const value = {v}").unwrap();
+            writeln!(
+                out,
+                "        expectError(() => midl.Encoder.encode([], value, {type_const}), midl.ErrorCode.{})",
+                error_code(test.err.unwrap())
+            )
+            .unwrap();
+            writeln!(out, "    }})\n").unwrap();
+        }
+        TestKind::DecodeFailure => {
+            let bytes = bytes_literal(test.bytes.as_ref().unwrap());
+            writeln!(out, "    it(\"{} decode failure\", () => {{", test.name).unwrap();
+            writeln!(out, "        const bytes = {bytes}").unwrap();
+            writeln!(
+                out,
+                "        expectError(() => midl.Decoder.decode(bytes, [], {type_const}), midl.ErrorCode.{})",
+                error_code(test.err.unwrap())
+            )
+            .unwrap();
+            writeln!(out, "    }})\n").unwrap();
+        }
+        _ => {}
+    }
+}
+
+pub fn generate(suite: &Suite) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "// WARNING: This file is machine generated by gidl. Do not edit.").unwrap();
+    writeln!(
+        out,
+        "// Regenerate it with `cargo run -p gidl -- --language typescript`, see tools/midl/gidl.\n"
+    )
+    .unwrap();
+    writeln!(out, "import * as midl from \"..\"\n").unwrap();
+
+    writeln!(out, "function expectError(f: () => unknown, code: midl.ErrorCode) {{").unwrap();
+    writeln!(out, "    try {{").unwrap();
+    writeln!(out, "        f()").unwrap();
+    writeln!(out, "    }} catch (e) {{").unwrap();
+    writeln!(out, "        expect((e as midl.MidlError).code).toEqual(code)").unwrap();
+    writeln!(out, "        return").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "    throw new Error(`expected error ${{midl.ErrorCode[code]}}`)").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    for decl in declaration_order(suite) {
+        match decl.kind {
+            DeclKind::Struct => write_struct(&mut out, suite, decl),
+            DeclKind::Union { strict } => write_union(&mut out, suite, decl, strict),
+            DeclKind::Table => write_table(&mut out, suite, decl),
+        }
+    }
+
+    writeln!(out, "describe(\"MIDL conformance\", () => {{").unwrap();
+    for test in suite.tests.iter().filter(|t| t.runs_on(Binding::TypeScript)) {
+        write_test(&mut out, suite, test);
+    }
+    // Drop the blank line after the last test.
+    out.pop();
+    writeln!(out, "}})").unwrap();
+    out
+}
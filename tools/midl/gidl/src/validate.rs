@@ -0,0 +1,233 @@
+// Copyright 2024 MeshX Authors. All rights reserved.
+
+use std::collections::HashSet;
+
+use crate::ast::{Decl, DeclKind, Primitive, Suite, Test, TestKind, Type, Value};
+use crate::GidlError;
+
+fn invalid<T>(test: &Test, message: impl Into<String>) -> Result<T, GidlError> {
+    Err(GidlError::Invalid {
+        file: test.file.clone(),
+        line: test.line,
+        message: format!("{}: {}", test.name, message.into()),
+    })
+}
+
+fn invalid_decl<T>(decl: &Decl, message: impl Into<String>) -> Result<T, GidlError> {
+    Err(GidlError::Invalid {
+        file: decl.file.clone(),
+        line: decl.line,
+        message: format!("{}: {}", decl.name, message.into()),
+    })
+}
+
+fn check_type(suite: &Suite, decl: &Decl, ty: &Type) -> Result<(), GidlError> {
+    match ty {
+        Type::Primitive(_) | Type::String { .. } => Ok(()),
+        Type::Vector { element, .. } | Type::Array { element, .. } => check_type(suite, decl, element),
+        Type::Named { name, nullable } => match suite.decls.get(name).map(|d| d.kind) {
+            None => invalid_decl(decl, format!("unknown type {name}")),
+            Some(DeclKind::Table) if *nullable => invalid_decl(decl, format!("table {name} cannot be optional")),
+            _ => Ok(()),
+        },
+    }
+}
+
+/// Rejects structs that contain themselves without a `box`, which would have
+/// an infinite inline size.
+fn check_inline_cycle(suite: &Suite, root: &Decl, ty: &Type, seen: &mut Vec<String>) -> Result<(), GidlError> {
+    match ty {
+        Type::Array { element, .. } => check_inline_cycle(suite, root, element, seen),
+        Type::Named { name, nullable: false } => {
+            let decl = suite.decl(name);
+            if decl.kind != DeclKind::Struct {
+                return Ok(());
+            }
+            if seen.contains(name) {
+                return invalid_decl(root, format!("{name} includes itself; use box<{name}>"));
+            }
+            seen.push(name.clone());
+            for member in decl.members.iter() {
+                check_inline_cycle(suite, root, &member.ty, seen)?;
+            }
+            seen.pop();
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_decl(suite: &Suite, decl: &Decl) -> Result<(), GidlError> {
+    if decl.members.is_empty() && decl.kind == DeclKind::Struct {
+        return invalid_decl(decl, "empty structs are not supported");
+    }
+
+    let mut names = HashSet::new();
+    let mut ordinals = HashSet::new();
+    for member in decl.members.iter() {
+        if !names.insert(&member.name) {
+            return invalid_decl(decl, format!("duplicate member {}", member.name));
+        }
+        if let Some(ordinal) = member.ordinal {
+            if !ordinals.insert(ordinal) {
+                return invalid_decl(decl, format!("duplicate ordinal {ordinal}"));
+            }
+        }
+        check_type(suite, decl, &member.ty)?;
+
+        if decl.kind != DeclKind::Struct {
+            let nullable = match &member.ty {
+                Type::String { nullable, .. } | Type::Vector { nullable, .. } | Type::Named { nullable, .. } => {
+                    *nullable
+                }
+                _ => false,
+            };
+            if nullable {
+                return invalid_decl(decl, format!("member {} cannot be optional", member.name));
+            }
+        }
+    }
+
+    if decl.kind == DeclKind::Struct {
+        for member in decl.members.iter() {
+            check_inline_cycle(suite, decl, &member.ty, &mut vec![decl.name.clone()])?;
+        }
+    }
+
+    Ok(())
+}
+
+fn check_value(suite: &Suite, test: &Test, ty: &Type, value: &Value) -> Result<(), GidlError> {
+    match (ty, value) {
+        (Type::String { nullable: true, .. }, Value::Null)
+        | (Type::Vector { nullable: true, .. }, Value::Null)
+        | (Type::Named { nullable: true, .. }, Value::Null) => Ok(()),
+        (Type::Primitive(Primitive::Bool), Value::Bool(_)) => Ok(()),
+        (Type::Primitive(p), Value::Int(i)) => match p.int_range() {
+            Some((min, max)) if *i < min || *i > max => invalid(test, format!("{i} is out of range for {p:?}")),
+            Some(_) => Ok(()),
+            None if p.is_float() => Ok(()),
+            None => invalid(test, format!("expected a {p:?}, found {i}")),
+        },
+        (Type::Primitive(p), Value::Float(_)) if p.is_float() => Ok(()),
+        (Type::String { .. }, Value::String(_)) => Ok(()),
+        (Type::Vector { element, .. }, Value::List(items)) => items
+            .iter()
+            .try_for_each(|item| check_value(suite, test, element, item)),
+        (Type::Array { element, count }, Value::List(items)) => {
+            if items.len() != *count as usize {
+                return invalid(test, format!("expected {count} array elements, found {}", items.len()));
+            }
+            items
+                .iter()
+                .try_for_each(|item| check_value(suite, test, element, item))
+        }
+        (
+            Type::Named { name, .. },
+            Value::Record {
+                name: value_name,
+                fields,
+            },
+        ) => {
+            if name != value_name {
+                return invalid(test, format!("expected a {name} value, found {value_name}"));
+            }
+            let decl = suite.decl(name);
+            for (field, value) in fields.iter() {
+                match decl.member(field) {
+                    Some(member) => check_value(suite, test, &member.ty, value)?,
+                    None => return invalid(test, format!("{name} has no member {field}")),
+                }
+            }
+            match decl.kind {
+                DeclKind::Struct => {
+                    for member in decl.members.iter() {
+                        if value.field(&member.name).is_none() {
+                            return invalid(test, format!("missing {name} member {}", member.name));
+                        }
+                    }
+                }
+                DeclKind::Union { .. } if fields.len() != 1 => {
+                    return invalid(test, format!("a {name} value must set exactly one member"));
+                }
+                _ => {}
+            }
+            Ok(())
+        }
+        (ty, value) => invalid(test, format!("{value:?} is not a valid {ty:?}")),
+    }
+}
+
+fn check_test(suite: &Suite, test: &mut Test) -> Result<(), GidlError> {
+    match test.kind {
+        TestKind::DecodeFailure => {
+            if test.value.is_some() {
+                return invalid(test, "decode_failure takes a `type`, not a `value`");
+            }
+        }
+        _ => match &test.value {
+            Some(Value::Record { name, .. }) => {
+                if !test.type_name.is_empty() && test.type_name != *name {
+                    return invalid(test, format!("the value is a {name}, not a {}", test.type_name));
+                }
+                test.type_name = name.clone();
+            }
+            Some(_) => return invalid(test, "the value must be a struct"),
+            None => return invalid(test, "missing `value`"),
+        },
+    }
+
+    match suite.decls.get(&test.type_name).map(|d| d.kind) {
+        Some(DeclKind::Struct) => {}
+        Some(_) => return invalid(test, "the tested type must be a struct"),
+        None if test.type_name.is_empty() => return invalid(test, "missing `type`"),
+        None => return invalid(test, format!("unknown type {}", test.type_name)),
+    }
+
+    let wants_bytes = test.kind != TestKind::EncodeFailure;
+    if test.bytes.is_some() != wants_bytes {
+        return invalid(
+            test,
+            if wants_bytes {
+                "missing `bytes`"
+            } else {
+                "unexpected `bytes`"
+            },
+        );
+    }
+
+    let wants_err = matches!(test.kind, TestKind::EncodeFailure | TestKind::DecodeFailure);
+    if test.err.is_some() != wants_err {
+        return invalid(test, if wants_err { "missing `err`" } else { "unexpected `err`" });
+    }
+
+    if let Some(value) = &test.value {
+        let ty = Type::Named {
+            name: test.type_name.clone(),
+            nullable: false,
+        };
+        check_value(suite, test, &ty, value)?;
+    }
+
+    Ok(())
+}
+
+/// Checks that the declarations are well formed and that every test value
+/// matches its type. Sets `Test::type_name` for tests that only give a value.
+pub fn validate(suite: &mut Suite) -> Result<(), GidlError> {
+    for decl in suite.decls.values() {
+        check_decl(suite, decl)?;
+    }
+
+    let mut names = HashSet::new();
+    let mut tests = std::mem::take(&mut suite.tests);
+    for test in tests.iter_mut() {
+        if !names.insert(test.name.clone()) {
+            return invalid(test, "duplicate test name");
+        }
+        check_test(suite, test)?;
+    }
+    suite.tests = tests;
+
+    Ok(())
+}
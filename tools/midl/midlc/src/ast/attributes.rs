@@ -76,6 +76,10 @@ impl AttributeArg {
             value,
         }
     }
+
+    pub(crate) fn name(&self) -> &Name {
+        &self.name
+    }
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
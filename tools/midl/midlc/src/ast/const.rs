@@ -115,6 +115,22 @@ impl std::ops::BitOr for ConstantValue {
 }
 
 impl ConstantValue {
+    /// The value as a count or bound, e.g. the size of an array. Negative and non-integral
+    /// values are not sizes.
+    pub fn as_size(&self) -> Option<u64> {
+        match *self {
+            ConstantValue::Int8(v) => u64::try_from(v).ok(),
+            ConstantValue::Int16(v) => u64::try_from(v).ok(),
+            ConstantValue::Int32(v) => u64::try_from(v).ok(),
+            ConstantValue::Int64(v) => u64::try_from(v).ok(),
+            ConstantValue::Uint8(v) => Some(v.into()),
+            ConstantValue::Uint16(v) => Some(v.into()),
+            ConstantValue::Uint32(v) => Some(v.into()),
+            ConstantValue::Uint64(v) => Some(v),
+            _ => None,
+        }
+    }
+
     pub fn convert(&self, kind: ConstantValueKind, out_val: &mut ConstantValue) -> bool {
         //let checked_value = safemath::CheckedNumeric<ValueType>(self.value);
 
@@ -417,6 +433,14 @@ impl Constant {
         }
     }
 
+    pub fn span(&self) -> &Span {
+        match self {
+            Constant::Identifier(c) => &c.span,
+            Constant::Literal(c) => &c.span,
+            Constant::BinaryOperator(c) => &c.span,
+        }
+    }
+
     pub fn set_compiled(&mut self, value: bool) {
         match self {
             Constant::Identifier(c) => c.compiled = value,
@@ -81,8 +81,7 @@ impl ConstraintStorage<Option<RefCell<ast::Nullability>>> for NullabilityConstra
     }
 
     fn has_constraint(&self) -> bool {
-        // self.0 != Self::default().0
-        false
+        self.0 != Self::default().0
     }
 
     fn value(&self) -> &Option<RefCell<ast::Nullability>> {
@@ -110,7 +109,13 @@ impl ConstraintStorage<Option<ast::ConstantValue>> for SizeConstraint {
         param: &ast::Constant,
         resource: Option<&ast::Resource>,
     ) -> bool {
-        todo!()
+        match resolver.resolve_as_size(param) {
+            Some(size) => {
+                self.0 = Some(ast::ConstantValue::Uint32(size));
+                true
+            }
+            None => false,
+        }
     }
 
     fn has_constraint(&self) -> bool {
@@ -118,11 +123,11 @@ impl ConstraintStorage<Option<ast::ConstantValue>> for SizeConstraint {
     }
 
     fn value(&self) -> &Option<ast::ConstantValue> {
-        todo!()
+        &self.0
     }
 
     fn set_value(&mut self, val: Option<ast::ConstantValue>) {
-        todo!()
+        self.0 = val;
     }
 }
 
@@ -173,26 +178,32 @@ impl std::default::Default for ProtocolConstraint {
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct HandleSubtypeConstraint(Option<u32>);
 
-impl ConstraintStorage<u32> for HandleSubtypeConstraint {
+impl ConstraintStorage<Option<u32>> for HandleSubtypeConstraint {
     fn resolve_constraint(
         &mut self,
         resolver: &TypeResolver<'_, '_>,
         param: &ast::Constant,
         resource: Option<&ast::Resource>,
     ) -> bool {
-        todo!()
+        match resolver.resolve_as_handle_subtype(param) {
+            Some(subtype) => {
+                self.0 = Some(subtype);
+                true
+            }
+            None => false,
+        }
     }
 
     fn has_constraint(&self) -> bool {
         self.0.is_some()
     }
 
-    fn value(&self) -> &u32 {
-        todo!()
+    fn value(&self) -> &Option<u32> {
+        &self.0
     }
 
-    fn set_value(&mut self, val: u32) {
-        todo!()
+    fn set_value(&mut self, val: Option<u32>) {
+        self.0 = val;
     }
 }
 
@@ -205,26 +216,32 @@ impl std::default::Default for HandleSubtypeConstraint {
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct HandleRightsConstraint(Option<u32>);
 
-impl ConstraintStorage<u32> for HandleRightsConstraint {
+impl ConstraintStorage<Option<u32>> for HandleRightsConstraint {
     fn resolve_constraint(
         &mut self,
         resolver: &TypeResolver<'_, '_>,
         param: &ast::Constant,
         resource: Option<&ast::Resource>,
     ) -> bool {
-        todo!()
+        match resolver.resolve_as_handle_rights(param) {
+            Some(rights) => {
+                self.0 = Some(rights);
+                true
+            }
+            None => false,
+        }
     }
 
     fn has_constraint(&self) -> bool {
         self.0.is_some()
     }
 
-    fn value(&self) -> &u32 {
-        todo!()
+    fn value(&self) -> &Option<u32> {
+        &self.0
     }
 
-    fn set_value(&mut self, val: u32) {
-        todo!()
+    fn set_value(&mut self, val: Option<u32>) {
+        self.0 = val;
     }
 }
 
@@ -242,6 +259,14 @@ impl VectorConstraints {
         &self.1 .0
     }
 
+    /// The maximum number of elements, or None if unbounded.
+    pub fn max_size(&self) -> Option<u32> {
+        match self.size() {
+            Some(ast::ConstantValue::Uint32(u32::MAX)) | None => None,
+            Some(size) => Some(size.clone().into()),
+        }
+    }
+
     pub fn new(size: Option<ast::ConstantValue>, nullabitly: ast::Nullability) -> Self {
         Self(
             NullabilityConstraint(Some(RefCell::new(nullabitly))),
@@ -269,6 +294,81 @@ impl IdentifierConstraints {
     }
 }
 
+impl MergeConstraints for VectorConstraints {
+    fn merge_constraints(
+        reporter: Rc<Diagnostics>,
+        layout_name: &ast::Name,
+        base: &Self,
+        resolved: &Self,
+        out_merged: &mut Self,
+    ) -> bool {
+        merge_constraint(reporter.clone(), layout_name, &base.1, &resolved.1, &mut out_merged.1)
+            && merge_constraint(reporter, layout_name, &base.0, &resolved.0, &mut out_merged.0)
+    }
+
+    fn has_constraint(&self, kind: ConstraintKind) -> bool {
+        match kind {
+            ConstraintKind::Nullability => self.0.has_constraint(),
+            _ => false,
+        }
+    }
+
+    fn resolve_one_constraint(
+        &mut self,
+        constraint_index: usize,
+        resolver: &TypeResolver<'_, '_>,
+        param: &ast::Constant,
+        resource: Option<&ast::Resource>,
+    ) -> bool {
+        // Constraints are written `<size, optional>`.
+        match constraint_index {
+            0 => self.1.resolve_constraint(resolver, param, resource),
+            1 => self.0.resolve_constraint(resolver, param, resource),
+            _ => false,
+        }
+    }
+
+    fn constraints_count(&self) -> usize {
+        2
+    }
+}
+
+impl MergeConstraints for IdentifierConstraints {
+    fn merge_constraints(
+        reporter: Rc<Diagnostics>,
+        layout_name: &ast::Name,
+        base: &Self,
+        resolved: &Self,
+        out_merged: &mut Self,
+    ) -> bool {
+        merge_constraint(reporter, layout_name, &base.0, &resolved.0, &mut out_merged.0)
+    }
+
+    fn has_constraint(&self, kind: ConstraintKind) -> bool {
+        match kind {
+            ConstraintKind::Nullability => self.0.has_constraint(),
+            _ => false,
+        }
+    }
+
+    fn resolve_one_constraint(
+        &mut self,
+        constraint_index: usize,
+        resolver: &TypeResolver<'_, '_>,
+        param: &ast::Constant,
+        resource: Option<&ast::Resource>,
+    ) -> bool {
+        match constraint_index {
+            0 => self.0.resolve_constraint(resolver, param, resource),
+            _ => false,
+        }
+    }
+
+    fn constraints_count(&self) -> usize {
+        1
+    }
+}
+
 pub type TransportSideConstraints = (NullabilityConstraint, ProtocolConstraint);
 
 impl NullabilityTrait for TransportSideConstraints {
@@ -325,9 +425,10 @@ impl MergeConstraints for TransportSideConstraints {
         param: &ast::Constant,
         resource: Option<&ast::Resource>,
     ) -> bool {
+        // Constraints are written `<Protocol, optional>`, so the protocol is resolved first.
         match constraint_index {
-            0 => return self.0.resolve_constraint(resolver, param, resource),
-            1 => return self.1.resolve_constraint(resolver, param, resource),
+            0 => self.1.resolve_constraint(resolver, param, resource),
+            1 => self.0.resolve_constraint(resolver, param, resource),
             _ => false,
         }
     }
@@ -345,6 +446,67 @@ impl NullabilityTrait for HandleConstraints {
     }
 }
 
+/// The object type and rights of a handle.
+pub trait HandleTrait {
+    /// The `subtype` of the handle, or None for a plain handle.
+    fn subtype(&self) -> Option<u32>;
+    /// The required rights, or None to keep the rights of the handle being sent.
+    fn rights(&self) -> Option<u32>;
+}
+
+impl HandleTrait for HandleConstraints {
+    fn subtype(&self) -> Option<u32> {
+        self.0 .0
+    }
+
+    fn rights(&self) -> Option<u32> {
+        self.1 .0
+    }
+}
+
+impl MergeConstraints for HandleConstraints {
+    fn merge_constraints(
+        reporter: Rc<Diagnostics>,
+        layout_name: &ast::Name,
+        base: &Self,
+        resolved: &Self,
+        out_merged: &mut Self,
+    ) -> bool {
+        merge_constraint(reporter.clone(), layout_name, &base.0, &resolved.0, &mut out_merged.0)
+            && merge_constraint(reporter.clone(), layout_name, &base.1, &resolved.1, &mut out_merged.1)
+            && merge_constraint(reporter, layout_name, &base.2, &resolved.2, &mut out_merged.2)
+    }
+
+    fn has_constraint(&self, kind: ConstraintKind) -> bool {
+        match kind {
+            ConstraintKind::HandleSubtype => self.0.has_constraint(),
+            ConstraintKind::HandleRights => self.1.has_constraint(),
+            ConstraintKind::Nullability => self.2.has_constraint(),
+            _ => false,
+        }
+    }
+
+    fn resolve_one_constraint(
+        &mut self,
+        constraint_index: usize,
+        resolver: &TypeResolver<'_, '_>,
+        param: &ast::Constant,
+        resource: Option<&ast::Resource>,
+    ) -> bool {
+        // Constraints are written `<subtype, rights, optional>`.
+        match constraint_index {
+            0 => self.0.resolve_constraint(resolver, param, resource),
+            1 => self.1.resolve_constraint(resolver, param, resource),
+            2 => self.2.resolve_constraint(resolver, param, resource),
+            _ => false,
+        }
+    }
+
+    fn constraints_count(&self) -> usize {
+        3
+    }
+}
+
 pub trait NullabilityTrait {
     fn nullability(&self) -> ast::Nullability;
 }
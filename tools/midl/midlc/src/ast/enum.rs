@@ -3,7 +3,7 @@ use std::{cell::RefCell, rc::Rc};
 use super::{
     traits::{Decl, TypeDecl},
     Attribute, AttributeList, Comment, Constant, Declaration, Element, Identifier, Name, PrimitiveType, Span,
-    TypeConstructor, WithAttributes, WithDocumentation, WithIdentifier, WithName, WithSpan,
+    Strictness, TypeConstructor, WithAttributes, WithDocumentation, WithIdentifier, WithName, WithSpan,
 };
 
 /// An opaque identifier for a field in an AST model. Use the
@@ -126,6 +126,8 @@ pub struct Enum {
     /// ```
     pub(crate) documentation: Option<Comment>,
 
+    pub(crate) strictness: Strictness,
+
     /// The location of this enum in the text representation.
     pub(crate) span: Span,
 
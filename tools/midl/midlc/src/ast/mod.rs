@@ -30,17 +30,17 @@ pub use ast::*;
 
 use crate::source_file::SourceId;
 pub use alias::Alias;
-pub use attributes::{Attribute, AttributeArg, AttributeList};
+pub use attributes::{Attribute, AttributeArg, AttributeList, DOC_COMMENT_NAME};
 pub use bits::{Bits, BitsMember};
 pub use comment::Comment;
 pub use constraints::{
-    ConstraintKind, HandleConstraints, MergeConstraints, NullabilityTrait, ProtocolTrait, ResolveAndMerge,
+    ConstraintKind, HandleConstraints, HandleTrait, MergeConstraints, NullabilityTrait, ProtocolTrait, ResolveAndMerge,
     TransportSideConstraints, VectorConstraints,
 };
 pub use identifier::{CompoundIdentifier, Identifier};
 pub use name::{name_flat_name, Name, NameProvenance, NamingContext};
-pub use properties::{Nullability, Resourceness, Strictness};
-pub use protocol::{Protocol, ProtocolMethod};
+pub use properties::{Nullability, Openness, Resourceness, Strictness};
+pub use protocol::{Protocol, ProtocolCompose, ProtocolMethod};
 pub use r#const::{
     BinaryOperatorConstant, Const, Constant, ConstantOp, ConstantTrait, ConstantValue, ConstantValueKind,
     IdentifierConstant, LiteralConstant,
@@ -236,6 +236,37 @@ impl Declaration {
         }
     }
 
+    /// Attaches the doc comment written in front of the declaration.
+    pub(crate) fn set_documentation(&self, documentation: Option<Comment>) {
+        match self {
+            Declaration::Struct { decl } => decl.borrow_mut().documentation = documentation,
+            Declaration::Enum { decl } => decl.borrow_mut().documentation = documentation,
+            Declaration::Protocol { decl } => decl.borrow_mut().documentation = documentation,
+            Declaration::Const { decl } => decl.borrow_mut().documentation = documentation,
+            Declaration::Alias { decl } => decl.borrow_mut().documentation = documentation,
+            Declaration::Resource { decl } => decl.borrow_mut().documentation = documentation,
+            Declaration::Union { decl } => decl.borrow_mut().documentation = documentation,
+            Declaration::Table { decl } => decl.borrow_mut().documentation = documentation,
+            Declaration::Bits { decl } => decl.borrow_mut().documentation = documentation,
+            Declaration::Builtin { .. } => panic!("builtins have no documentation"),
+            Declaration::NewType => todo!(),
+            Declaration::Overlay => todo!(),
+        }
+    }
+
+    /// Attaches the attributes written in front of a `type` declaration; these are consumed
+    /// before the kind of the layout is known.
+    pub(crate) fn set_attributes(&self, attributes: AttributeList) {
+        match self {
+            Declaration::Struct { decl } => decl.borrow_mut().attributes = attributes,
+            Declaration::Enum { decl } => decl.borrow_mut().attributes = attributes,
+            Declaration::Union { decl } => decl.borrow_mut().attributes = attributes,
+            Declaration::Table { decl } => decl.borrow_mut().attributes = attributes,
+            Declaration::Bits { decl } => decl.borrow_mut().attributes = attributes,
+            _ => panic!("not a layout decl"),
+        }
+    }
+
     pub(crate) fn as_decl(&self) -> Rc<RefCell<dyn Decl>> {
         match self {
             Declaration::Enum { decl } => decl.clone() as Rc<RefCell<dyn Decl>>,
@@ -208,6 +208,10 @@ pub(crate) struct AnonymousNameContext {
 }
 
 impl AnonymousNameContext {
+    pub(crate) fn provenance(&self) -> &NameProvenance {
+        &self.provenance
+    }
+
     fn new(span: Span, context: Rc<NamingContext>, provenance: NameProvenance) -> NameContext {
         NameContext::Anonymous(AnonymousNameContext {
             flattened_name: context.flattened_name(),
@@ -10,6 +10,13 @@ pub enum Strictness {
     Strict,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Openness {
+    Closed,
+    Ajar,
+    Open,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Resourceness {
     Value,
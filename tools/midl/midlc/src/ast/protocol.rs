@@ -1,8 +1,7 @@
 use std::{cell::RefCell, rc::Rc};
 
 use super::{
-    Attribute, AttributeList, Comment, CompoundIdentifier, Declaration, Identifier, Name, Span, Strictness,
-    TypeConstructor, WithAttributes, WithDocumentation, WithIdentifier, WithName, WithSpan,
+    Attribute, AttributeList, Comment, Declaration, Identifier, Name, Openness, Reference, Span, Strictness, TypeConstructor, WithAttributes, WithDocumentation, WithIdentifier, WithName, WithSpan,
 };
 
 #[derive(Debug)]
@@ -85,8 +84,9 @@ pub struct ProtocolMethod {
 
     pub(crate) strictness: Strictness,
 
-    // Set during compilation
-    // TODO: generated_ordinal64: raw::Ordinal64,
+    /// The ordinal identifying the method on the wire. Set during compilation.
+    pub(crate) generated_ordinal64: u64,
+
     /// The attributes of this protocol method.
     ///
     /// ```ignore
@@ -101,6 +101,26 @@ pub struct ProtocolMethod {
     pub(crate) span: Span,
 }
 
+/// A protocol whose methods are included in another protocol.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProtocolCompose {
+    /// The attributes of the compose clause.
+    pub attributes: AttributeList,
+
+    /// The composed protocol.
+    ///
+    /// ```ignore
+    /// protocol Foo {
+    ///   compose Bar;
+    ///           ^^^
+    /// }
+    /// ```
+    pub(crate) reference: Reference,
+
+    /// The location of the compose clause in the text representation.
+    pub(crate) span: Span,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Protocol {
     /// The name of the protocol.
@@ -111,7 +131,15 @@ pub struct Protocol {
     /// ```
     pub name: Name,
 
-    /// The names of the composed protocols.
+    /// Which unknown interactions the protocol accepts.
+    ///
+    /// ```ignore
+    /// ajar protocol Foo { .. }
+    /// ^^^^
+    /// ```
+    pub openness: Openness,
+
+    /// The composed protocols.
     ///
     /// ```ignore
     /// protocol Foo {
@@ -119,7 +147,7 @@ pub struct Protocol {
     ///   ^^^^^^^^^^^
     /// }
     /// ```
-    pub(crate) composes: Vec<CompoundIdentifier>,
+    pub(crate) composes: Vec<ProtocolCompose>,
 
     /// The attributes of this protocol.
     ///
@@ -169,6 +197,35 @@ impl Protocol {
             .enumerate()
             .map(|(idx, method)| (ProtocolMethodId(idx as u32), method))
     }
+
+    /// The methods of the protocol followed by those of the protocols it composes, directly or
+    /// indirectly, each with the name of the protocol that declares it. Composed protocols
+    /// reached more than once contribute their methods once.
+    pub fn all_methods(&self) -> Vec<(Name, Rc<RefCell<ProtocolMethod>>)> {
+        let mut methods = vec![];
+        let mut seen = vec![];
+
+        self.collect_methods(&mut methods, &mut seen);
+        methods
+    }
+
+    fn collect_methods(&self, methods: &mut Vec<(Name, Rc<RefCell<ProtocolMethod>>)>, seen: &mut Vec<Name>) {
+        methods.extend(self.methods.iter().map(|method| (self.name.clone(), method.clone())));
+
+        for compose in self.composes.iter() {
+            let Some(Declaration::Protocol { decl }) =
+                compose.reference.resolved().map(|target| target.element_or_parent_decl())
+            else {
+                continue;
+            };
+
+            let name = decl.borrow().name.clone();
+            if !seen.contains(&name) {
+                seen.push(name);
+                decl.borrow().collect_methods(methods, seen);
+            }
+        }
+    }
 }
 
 impl Into<Declaration> for Protocol {
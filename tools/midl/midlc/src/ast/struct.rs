@@ -2,7 +2,7 @@ use std::{cell::RefCell, rc::Rc};
 
 use super::{
     traits::{Decl, TypeDecl},
-    AttributeList, Comment, Constant, Declaration, Name, Span, TypeConstructor, WithAttributes,
+    AttributeList, Comment, Constant, Declaration, Name, Resourceness, Span, TypeConstructor, WithAttributes,
     WithDocumentation, WithName, WithSpan,
 };
 
@@ -108,6 +108,9 @@ pub struct Struct {
     /// ```
     pub(crate) documentation: Option<Comment>,
 
+    /// Whether the struct may contain handles, i.e. was declared `resource struct`.
+    pub(crate) resourceness: Resourceness,
+
     /// The location of this struct in the text representation.
     pub(crate) span: Span,
 
@@ -1,7 +1,7 @@
 use std::{cell::RefCell, rc::Rc};
 
 use super::{
-    Attribute, AttributeList, Comment, Constant, Decl, Declaration, Element, Identifier, Name, RawOrdinal64, Span, Strictness, TypeConstructor, WithAttributes, WithDocumentation, WithIdentifier, WithName, WithSpan
+    Attribute, AttributeList, Comment, Constant, Decl, Declaration, Element, Identifier, Name, RawOrdinal64, Resourceness, Span, Strictness, TypeConstructor, WithAttributes, WithDocumentation, WithIdentifier, WithName, WithSpan
 };
 
 /// An opaque identifier for a field in an AST model. Use the
@@ -120,6 +120,8 @@ pub struct Table {
 
     pub(crate) strictness: Strictness,
 
+    pub(crate) resourceness: Resourceness,
+
     /// The location of this table in the text representation.
     pub(crate) span: Span,
 
@@ -5,7 +5,7 @@ use crate::diagnotics::Diagnostics;
 use super::{
     constraints::IdentifierConstraints, Alias, Const, Constant, ConstantValue, ConstraintKind, Declaration, Element,
     HandleConstraints, Identifier, LiteralConstant, MergeConstraints, Name, Nullability, NullabilityTrait, Reference,
-    ResolveAndMerge, Resource, Span, TransportSideConstraints, VectorConstraints,
+    ResolveAndMerge, Resource, Resourceness, Span, TransportSideConstraints, VectorConstraints,
 };
 use std::{cell::RefCell, rc::Rc, str::FromStr};
 
@@ -246,7 +246,24 @@ impl VectorType {
         constraints: &LayoutConstraints,
         layout: &Reference,
     ) -> Result<Type, bool> {
-        Ok(Type::Vector(Rc::from(self.clone())))
+        let mut c = VectorConstraints::default();
+        if !self.constraints.clone().resolve_and_merge_constraints(
+            resolver,
+            diagnostics,
+            constraints.span.clone(),
+            &layout.resolved().unwrap().name(),
+            None,
+            &constraints.items,
+            &mut c,
+        ) {
+            return Err(false);
+        }
+
+        Ok(Type::Vector(Rc::from(VectorType {
+            name: self.name.clone(),
+            element_type: self.element_type.clone(),
+            constraints: c,
+        })))
     }
 }
 
@@ -304,7 +321,7 @@ impl StringType {
     }
 
     pub fn max_size(&self) -> u32 {
-        0
+        self.constraints.max_size().unwrap_or(u32::MAX)
     }
 
     pub fn apply_constraints(
@@ -314,10 +331,18 @@ impl StringType {
         constraints: &LayoutConstraints,
         layout: &Reference,
     ) -> Result<Type, bool> {
-        let c = VectorConstraints::default();
-        //if (!self.resolve_and_merge_constraints(resolver, reporter, constraints.span, layout.resolved().name(), nullptr, constraints.items, &c, out_params)) {
-        //  return false;
-        //}
+        let mut c = VectorConstraints::default();
+        if !self.constraints.clone().resolve_and_merge_constraints(
+            resolver,
+            diagnostics,
+            constraints.span.clone(),
+            &layout.resolved().unwrap().name(),
+            None,
+            &constraints.items,
+            &mut c,
+        ) {
+            return Err(false);
+        }
 
         Ok(Type::String(Rc::new(StringType::new_with_constraints(
             self.name.clone(),
@@ -449,11 +474,24 @@ impl IdentifierType {
         constraints: &LayoutConstraints,
         layout: &Reference,
     ) -> Result<Type, bool> {
-        //if (!self.resolve_and_merge_constraints(resolver, reporter, constraints.span, layout.resolved().name(), nullptr, constraints.items, &c, out_params)) {
-        //  return false;
-        //}
+        let mut c = IdentifierConstraints::default();
+        if !self.constraints.clone().resolve_and_merge_constraints(
+            resolver,
+            diagnostics,
+            constraints.span.clone(),
+            &layout.resolved().unwrap().name(),
+            None,
+            &constraints.items,
+            &mut c,
+        ) {
+            return Err(false);
+        }
 
-        Ok(Type::Identifier(Rc::new(self.clone())))
+        Ok(Type::Identifier(Rc::new(IdentifierType {
+            name: self.name.clone(),
+            decl: self.decl.clone(),
+            constraints: c,
+        })))
     }
 }
 
@@ -508,11 +546,24 @@ impl HandleType {
         constraints: &LayoutConstraints,
         layout: &Reference,
     ) -> Result<Type, bool> {
-        //if (!self.resolve_and_merge_constraints(resolver, reporter, constraints.span, layout.resolved().name(), nullptr, constraints.items, &c, out_params)) {
-        //  return false;
-        //}
+        let mut c = HandleConstraints::default();
+        if !self.constraints.clone().resolve_and_merge_constraints(
+            resolver,
+            diagnostics,
+            constraints.span.clone(),
+            &layout.resolved().unwrap().name(),
+            None,
+            &constraints.items,
+            &mut c,
+        ) {
+            return Err(false);
+        }
 
-        Ok(Type::Handle(Rc::new(self.clone())))
+        Ok(Type::Handle(Rc::new(HandleType {
+            name: self.name.clone(),
+            resource: self.resource.clone(),
+            constraints: c,
+        })))
     }
 }
 
@@ -598,6 +649,24 @@ impl Type {
         }
     }
 
+    /// Whether values of this type may contain handles, so that a layout containing it must
+    /// be a `resource`.
+    pub fn is_resource(&self) -> bool {
+        match self {
+            Type::Array(typ) => typ.element_type.is_resource(),
+            Type::Vector(typ) => typ.element_type.is_resource(),
+            Type::Box(typ) => typ.boxed_type.is_resource(),
+            Type::Identifier(typ) => match &typ.decl {
+                Declaration::Struct { decl } => decl.borrow().resourceness == Resourceness::Resource,
+                Declaration::Table { decl } => decl.borrow().resourceness == Resourceness::Resource,
+                Declaration::Union { decl } => decl.borrow().resourceness == Resourceness::Resource,
+                _ => false,
+            },
+            Type::TransportSide(_) | Type::Handle(_) | Type::RequestType { .. } => true,
+            Type::Primitive(_) | Type::Internal(_) | Type::String(_) | Type::UntypedNumeric(_) => false,
+        }
+    }
+
     pub fn name(&self) -> Name {
         match self {
             Type::Array(typ) => typ.name.clone(),
@@ -625,12 +694,12 @@ impl Type {
             Type::Array(typ) => typ.apply_constraints(resolver, diagnostics, constraints, layout),
             Type::Vector(typ) => typ.apply_constraints(resolver, diagnostics, constraints, layout),
             Type::Primitive(typ) => typ.apply_constraints(resolver, diagnostics, constraints, layout),
-            Type::Internal(_) => todo!(),
+            Type::Internal(typ) => typ.apply_constraints(resolver, diagnostics, constraints, layout),
             Type::Box(typ) => typ.apply_constraints(resolver, diagnostics, constraints, layout),
             Type::String(typ) => typ.apply_constraints(resolver, diagnostics, constraints, layout),
             Type::Handle(typ) => typ.apply_constraints(resolver, diagnostics, constraints, layout),
             Type::Identifier(typ) => typ.apply_constraints(resolver, diagnostics, constraints, layout),
-            Type::TransportSide(_) => todo!(),
+            Type::TransportSide(typ) => typ.clone().apply_constraints(resolver, diagnostics, constraints, layout),
             Type::UntypedNumeric(_) => todo!(),
             Type::RequestType { nullable, subtype } => todo!(),
         }
@@ -1,7 +1,7 @@
 use std::{cell::RefCell, rc::Rc};
 
 use super::{
-    Attribute, AttributeList, Comment, Constant, Decl, Declaration, Element, Identifier, Name, RawOrdinal64, Span,
+    Attribute, AttributeList, Comment, Constant, Decl, Declaration, Element, Identifier, Name, RawOrdinal64, Resourceness, Span,
     Strictness, TypeConstructor, WithAttributes, WithDocumentation, WithIdentifier, WithName, WithSpan,
 };
 
@@ -121,6 +121,8 @@ pub struct Union {
 
     pub(crate) strictness: Strictness,
 
+    pub(crate) resourceness: Resourceness,
+
     /// The location of this union in the text representation.
     pub(crate) span: Span,
 
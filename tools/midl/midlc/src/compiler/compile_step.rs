@@ -1,6 +1,7 @@
 use anyhow::Result;
 use core::panic;
 use num::{Num, Unsigned};
+use sha2::{Digest, Sha256};
 use std::ops::*;
 use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
 
@@ -111,6 +112,35 @@ where
     (num & (num - one)) == zero
 }
 
+/// The ordinal of a protocol method: the first 8 bytes of the SHA-256 of its selector, read
+/// as a little-endian number with the most significant bit cleared. The selector is
+/// `library/Protocol.Method`, unless `@selector` overrides the method name or the whole
+/// selector.
+fn method_ordinal(protocol_name: &ast::Name, method: &ast::ProtocolMethod) -> u64 {
+    let selector = method
+        .attributes
+        .iter()
+        .find(|attribute| attribute.name.decl_name() == "selector")
+        .and_then(|attribute| match attribute.arguments.first().map(|arg| &arg.value) {
+            Some(ast::Constant::Literal(constant)) => constant
+                .literal
+                .as_string_value()
+                .map(|(value, _)| value.trim_matches('"').to_string()),
+            _ => None,
+        })
+        .unwrap_or_else(|| method.name.data.clone());
+
+    let selector = if selector.contains('/') {
+        selector
+    } else {
+        let library_name = protocol_name.library().name.get().map(|name| name.join(".")).unwrap_or_default();
+        format!("{}/{}.{}", library_name, protocol_name.decl_name(), selector)
+    };
+
+    let digest = Sha256::digest(selector.as_bytes());
+    u64::from_le_bytes(digest[..8].try_into().unwrap()) & 0x7fff_ffff_ffff_ffff
+}
+
 impl<'ctx, 'd> CompileStep<'ctx, 'd>
 where
     'ctx: 'd,
@@ -140,6 +170,10 @@ where
     }
 
     pub(crate) fn compile_decl(&self, decl: &mut ast::Declaration) {
+        if decl.compiled() {
+            return;
+        }
+
         {
             let mut decl_stack = self.decl_stack.borrow_mut();
 
@@ -395,6 +429,36 @@ where
 
     fn compile_table(&self, decl: Rc<RefCell<ast::Table>>) {
         let table_declaration = decl.borrow();
+        let mut ordinal_scope = Ordinal64Scope::new();
+
+        self.compile_attribute_list(&table_declaration.attributes);
+
+        for member in table_declaration.members.iter() {
+            let mut member = member.borrow_mut();
+
+            self.compile_attribute_list(&member.attributes);
+
+            let ordinal_result = ordinal_scope.insert(member.ordinal.value, member.ordinal.span.clone());
+            if !ordinal_result.is_ok() {
+                self.ctx.diagnostics.push_error(
+                    Error::DuplicateTableFieldOrdinal {
+                        span: member.ordinal.span.clone(),
+                        prev: ordinal_result.previous_occurrence(),
+                    }
+                    .into(),
+                );
+            }
+
+            if let Some(member_used) = member.maybe_used.as_mut() {
+                self.compile_type_constructor(&mut member_used.type_ctor);
+            }
+        }
+
+        if let Some((ordinal, span)) = find_first_non_dense_ordinal(&ordinal_scope) {
+            self.ctx
+                .diagnostics
+                .push_error(Error::NonDenseOrdinal { span, ordinal }.into());
+        }
     }
 
     fn compile_union(&self, decl: Rc<RefCell<ast::Union>>) {
@@ -404,6 +468,7 @@ where
 
         self.compile_attribute_list(&union_declaration.attributes);
         let mut contains_non_reserved_member = false;
+        let mut contains_resource_member = false;
 
         for member in union_declaration.members.iter() {
             let mut member = member.borrow_mut();
@@ -439,7 +504,7 @@ where
                 todo!()
             }
 
-            // derive_resourceness.AddType(member_used.type_ctor->type);
+            contains_resource_member |= member_used.type_ctor.r#type.as_ref().unwrap().is_resource();
         }
 
         if union_declaration.strictness == ast::Strictness::Strict && !contains_non_reserved_member {
@@ -456,6 +521,18 @@ where
                 .diagnostics
                 .push_error(Error::NonDenseOrdinal { span, ordinal }.into());
         }
+
+        // Result unions are not written by the user, so they are a resource exactly when
+        // the success or error payload is.
+        let is_result_union = union_declaration
+            .name
+            .as_anonymous()
+            .is_some_and(|anonymous| *anonymous.provenance() == ast::NameProvenance::GeneratedResultUnion);
+        drop(union_declaration);
+
+        if is_result_union && contains_resource_member {
+            decl.borrow_mut().resourceness = ast::Resourceness::Resource;
+        }
     }
 
     fn compile_alias(&self, decl: Rc<RefCell<ast::Alias>>) {
@@ -671,8 +748,23 @@ where
         let protocol_declaration = decl.borrow();
         self.compile_attribute_list(&protocol_declaration.attributes);
 
+        for compose in protocol_declaration.composes.iter() {
+            self.compile_attribute_list(&compose.attributes);
+
+            match compose.reference.resolved().map(|target| target.element_or_parent_decl()) {
+                Some(mut composed @ ast::Declaration::Protocol { .. }) => self.compile_decl(&mut composed),
+                Some(_) => self.ctx.diagnostics.push_error(DiagnosticsError::new(
+                    "only protocols can be composed",
+                    compose.span.clone(),
+                )),
+                // The reference failed to resolve and was already reported.
+                None => {}
+            }
+        }
+
         for method in protocol_declaration.methods.iter() {
             let mut method = method.borrow_mut();
+            method.generated_ordinal64 = method_ordinal(&protocol_declaration.name, &method);
 
             if let Some(typ_ctor) = method.maybe_request.as_mut() {
                 self.compile_type_constructor(typ_ctor);
@@ -1133,7 +1225,7 @@ where
         let span = literal_constant.literal.span();
         let string_data = span.data.clone();
 
-        let result: Result<()> = try {
+        let mut resolve = || -> Result<()> {
             let is_hex = span.data.strip_prefix("0x");
 
             if let Some(hex) = is_hex {
@@ -1225,9 +1317,10 @@ where
                     _ => panic!("non numeric value"),
                 }
             }
+            Ok(())
         };
 
-        match result {
+        match resolve() {
             Ok(_) => true,
             Err(_) => false,
         }
@@ -1,8 +1,4 @@
-use std::{
-    cell::RefCell,
-    collections::{BTreeSet, HashMap},
-    rc::Rc,
-};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use crate::{
     ast::{self, Declaration, Library},
@@ -12,24 +8,6 @@ use crate::{
 
 use super::{typespace::Typespace, Compilation};
 
-/// Helper struct to calculate Compilation::direct_and_composed_dependencies.
-#[derive(PartialEq, Eq, PartialOrd)]
-struct LibraryCompare(Rc<ast::Library>);
-
-impl Ord for LibraryCompare {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        std::cmp::Ordering::Equal
-    }
-}
-
-struct CalcDependencies;
-
-impl CalcDependencies {
-    fn from(roots: &Vec<ast::Declaration>) -> BTreeSet<LibraryCompare> {
-        BTreeSet::new()
-    }
-}
-
 // Libraries manages a set of compiled libraries along with resources common to
 // all of them (e.g. the shared typespace). The libraries must be inserted in
 // order: first the dependencies, with each one only depending on those that
@@ -117,7 +95,7 @@ impl Libraries {
         fn filter_declarations(dst: &mut super::Declarations, src: &RefCell<ast::Declarations>) {
             let src = src.borrow().clone();
 
-            //filter_internal(&dst.bits, src.bits);
+            filter_internal(&mut dst.bits, src.bits);
             filter_internal(&mut dst.builtins, src.builtins);
             filter_internal(&mut dst.consts, src.consts);
             filter_internal(&mut dst.enums, src.enums);
@@ -126,7 +104,7 @@ impl Libraries {
             filter_internal(&mut dst.resources, src.resources);
             //filter_internal(&dst.services, src.services);
             filter_internal(&mut dst.structs, src.structs);
-            //filter_internal(&dst.tables, src.tables);
+            filter_internal(&mut dst.tables, src.tables);
             //filter_internal(&dst.aliases, src.aliases);
             filter_internal(&mut dst.unions, src.unions);
             //filter_internal(&dst.overlays, src.overlays);
@@ -143,21 +121,19 @@ impl Libraries {
 
         filter_declarations(&mut declarations, &library.declarations);
 
-        // let external_structs = ExternalStructs(library, declarations.protocols);
+        let external_structs = external_structs(&library_name, &declarations.protocols);
         // TODO let using_references = library.dependencies.library_references();
 
         filter_internal(&mut declaration_order, library.declaration_order.clone());
 
-        let mut dependencies = CalcDependencies::from(&declaration_order);
-        dependencies.remove(&LibraryCompare(library.clone()));
-        dependencies.remove(&LibraryCompare(self.root_library()));
-
-        for dep_library in dependencies {
+        // Libraries are inserted dependencies first, so everything before the target library
+        // is one of its direct, composed or transitive dependencies.
+        for dep_library in self.libraries[..self.libraries.len() - 1].iter() {
             let mut declarations = super::Declarations::default();
-            filter_declarations(&mut declarations, &dep_library.0.declarations);
+            filter_declarations(&mut declarations, &dep_library.declarations);
 
             direct_and_composed_dependencies.push(Dependency {
-                library: dep_library.0,
+                library: dep_library.clone(),
                 declarations,
             });
         }
@@ -168,7 +144,54 @@ impl Libraries {
             direct_and_composed_dependencies,
             version_selection,
             declarations,
-            external_structs: vec![],
+            external_structs,
+        }
+    }
+}
+
+/// Payload structs of methods that `protocols` compose from other libraries. Bindings need
+/// their layout to encode and decode the composed methods.
+fn external_structs(library_name: &Vec<String>, protocols: &[Declaration]) -> Vec<Rc<RefCell<ast::Struct>>> {
+    fn payload_struct(type_ctor: &Option<ast::TypeConstructor>) -> Option<Rc<RefCell<ast::Struct>>> {
+        let Some(ast::Type::Identifier(identifier)) = type_ctor.as_ref().and_then(|ctor| ctor.r#type.as_ref()) else {
+            return None;
+        };
+
+        match &identifier.decl {
+            Declaration::Struct { decl } => Some(decl.clone()),
+            // The success variant of a result union holds the payload.
+            Declaration::Union { decl } => decl
+                .borrow()
+                .members
+                .first()
+                .and_then(|member| payload_struct(&member.borrow().maybe_used.as_ref().map(|used| used.type_ctor.clone()))),
+            _ => None,
+        }
+    }
+
+    let mut structs: Vec<Rc<RefCell<ast::Struct>>> = vec![];
+
+    for protocol in protocols {
+        let Declaration::Protocol { decl } = protocol else {
+            continue;
+        };
+
+        for (owner, method) in decl.borrow().all_methods() {
+            if owner.library().name.get() == Some(library_name) {
+                continue;
+            }
+
+            let method = method.borrow();
+            for payload in [payload_struct(&method.maybe_request), payload_struct(&method.maybe_response)]
+                .into_iter()
+                .flatten()
+            {
+                if !structs.iter().any(|known| Rc::ptr_eq(known, &payload)) {
+                    structs.push(payload);
+                }
+            }
         }
     }
+
+    structs
 }
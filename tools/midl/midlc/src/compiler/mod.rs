@@ -28,7 +28,7 @@ use self::resolve_step::ResolveStep;
 #[derive(Debug, Default)]
 pub struct Declarations {
     // aliases: Vec<Rc<RefCell<Alias>>>,
-    // bits: Vec<Rc<RefCell<Bits>>>,
+    pub bits: Vec<ast::Declaration>,
     pub builtins: Vec<ast::Declaration>,
     pub consts: Vec<ast::Declaration>,
     pub enums: Vec<ast::Declaration>,
@@ -37,16 +37,16 @@ pub struct Declarations {
     pub resources: Vec<ast::Declaration>,
     // services: Vec<Rc<RefCell<ast::Service>>>,
     pub structs: Vec<ast::Declaration>,
-    // tables: Vec<Rc<RefCell<ast::Table>>>,
+    pub tables: Vec<ast::Declaration>,
     pub unions: Vec<ast::Declaration>,
     // overlays: Vec<Rc<RefCell<Overlay>>>,
 }
 
 /// A library dependency together with its filtered declarations.
 #[derive(Debug)]
-struct Dependency {
-    library: Rc<ast::Library>,
-    declarations: Declarations,
+pub struct Dependency {
+    pub library: Rc<ast::Library>,
+    pub declarations: Declarations,
 }
 
 /// A compilation is the result of compiling a library and all its transitive
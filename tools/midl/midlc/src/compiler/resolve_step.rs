@@ -380,7 +380,11 @@ impl<'ctx, 'd> ResolveStep<'ctx, 'd> {
             }
             ast::Element::NewType => todo!(),
             ast::Element::Overlay => {}
-            ast::Element::Protocol { .. } => {}
+            ast::Element::Protocol { inner } => {
+                for compose in inner.borrow().composes.iter() {
+                    self.visit_reference(&compose.reference, context);
+                }
+            }
             ast::Element::Table { .. } => {}
             ast::Element::Union { .. } => {}
             ast::Element::Struct { .. } => {}
@@ -32,7 +32,112 @@ impl<'c, 'd> TypeResolver<'c, 'd> {
         layout: &ast::Reference,
         param: &ast::LayoutParameter,
     ) -> Result<ast::ConstantValue, ()> {
-        Ok(ast::ConstantValue::Uint32(0))
+        let size = match param {
+            ast::LayoutParameter::Literal(param) => Self::literal_as_size(&param.literal.literal),
+            ast::LayoutParameter::Identifier(param) => self.reference_as_size(&param.reference),
+            ast::LayoutParameter::Type(param) => self.reference_as_size(&param.type_ctor.layout),
+        };
+
+        match size {
+            Some(size) if size != u32::MAX => Ok(ast::ConstantValue::Uint32(size)),
+            _ => panic!("ErrExpectedValueButGotType {:?}", layout.span),
+        }
+    }
+
+    /// Resolves the bound of a string or vector: a literal, a `const` or `MAX`. Sizes that do
+    /// not fit a uint32 are unbounded.
+    pub fn resolve_as_size(&self, constant: &ast::Constant) -> Option<u32> {
+        match constant {
+            ast::Constant::Literal(literal) => Self::literal_as_size(&literal.literal),
+            ast::Constant::Identifier(identifier) => self.reference_as_size(&identifier.reference),
+            ast::Constant::BinaryOperator(_) => None,
+        }
+    }
+
+    fn literal_as_size(literal: &ast::Literal) -> Option<u32> {
+        let ast::Literal::NumericValue(value, _) = literal else {
+            return None;
+        };
+
+        let size = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+            Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+            None => value.parse::<u64>().ok()?,
+        };
+
+        Some(u32::try_from(size).unwrap_or(u32::MAX))
+    }
+
+    fn reference_as_size(&self, reference: &ast::Reference) -> Option<u32> {
+        match reference.resolved()?.element() {
+            ast::Element::Builtin { inner } if inner.borrow().id == ast::BuiltinIdentity::MAX => Some(u32::MAX),
+            ast::Element::Const { inner } => {
+                self.compile_decl(&mut ast::Declaration::Const { decl: inner.clone() });
+
+                let const_decl = inner.borrow();
+                if !const_decl.value.is_resolved() {
+                    return None;
+                }
+
+                let size = const_decl.value.value().as_size()?;
+                Some(u32::try_from(size).unwrap_or(u32::MAX))
+            }
+            _ => None,
+        }
+    }
+
+    /// Resolves the object type of a handle, e.g. the `VMO` in `fx.Handle:VMO`, which names a
+    /// member of the `subtype` enum of the resource.
+    pub fn resolve_as_handle_subtype(&self, constant: &ast::Constant) -> Option<u32> {
+        let ast::Constant::Identifier(identifier) = constant else {
+            return None;
+        };
+
+        let resolved = identifier.reference.resolved()?;
+        let ast::Element::EnumMember { inner } = resolved.element() else {
+            return None;
+        };
+
+        self.compile_decl(&mut resolved.element_or_parent_decl());
+
+        let member = inner.borrow();
+        if !member.value.is_resolved() {
+            return None;
+        }
+
+        member.value.value().as_size().and_then(|v| u32::try_from(v).ok())
+    }
+
+    /// Resolves the rights of a handle: a member of the `rights` bits of the resource, or a
+    /// `const` of that type.
+    pub fn resolve_as_handle_rights(&self, constant: &ast::Constant) -> Option<u32> {
+        let ast::Constant::Identifier(identifier) = constant else {
+            return None;
+        };
+
+        let resolved = identifier.reference.resolved()?;
+        let value = match resolved.element() {
+            ast::Element::BitsMember { inner } => {
+                self.compile_decl(&mut resolved.element_or_parent_decl());
+
+                let member = inner.borrow();
+                if !member.value.is_resolved() {
+                    return None;
+                }
+                member.value.value()
+            }
+            ast::Element::Const { inner } => {
+                self.compile_decl(&mut ast::Declaration::Const { decl: inner.clone() });
+
+                let const_decl = inner.borrow();
+                if !const_decl.value.is_resolved() {
+                    return None;
+                }
+                const_decl.value.value()
+            }
+            _ => return None,
+        };
+
+        value.as_size().and_then(|v| u32::try_from(v).ok())
     }
 
     pub fn resolve_as_protocol(&self, constant: &ast::Constant) -> Option<Rc<RefCell<ast::Protocol>>> {
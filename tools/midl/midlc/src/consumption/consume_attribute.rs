@@ -61,6 +61,18 @@ pub(crate) fn consume_attribute(token: Pair<'_>, ctx: &mut ParsingContext<'_>) -
                     }
                 }
             }
+            // A single positional argument, e.g. `@discoverable("meshx.io.Node")`, is named
+            // `value`, just like the text of a doc comment.
+            Rule::constant => {
+                let arg_span = ast::Span::from_pest(current.as_span(), ctx.source_id);
+                let value = consume_constant(current, ctx);
+
+                arguments.push(AttributeArg::new(
+                    Name::create_intrinsic(Some(ctx.library.clone()), "value"),
+                    arg_span,
+                    value,
+                ));
+            }
             _ => consume_catch_all(&current, "attribute"),
         }
     }
@@ -3,11 +3,12 @@ use std::rc::Rc;
 
 use super::consume_identifier;
 use super::consume_type::consume_type_constructor;
-use super::helpers::consume_catch_all;
+use super::helpers::{consume_catch_all, consume_modifier, Modifiers};
 use super::{helpers::Pair, Rule};
 
 use crate::ast;
 use crate::compiler::ParsingContext;
+use crate::consumption::consume_attribute_list;
 use crate::consumption::consume_comments::{consume_comment_block, consume_trailing_comment};
 use crate::consumption::consume_const::consume_constant;
 use crate::consumption::identifier_type_for_decl;
@@ -23,7 +24,7 @@ fn consume_enum_member(
 
     let pair_span = pair.as_span();
     let mut name = None;
-    let attributes = Vec::new();
+    let mut attributes = Vec::new();
     let mut comment = block_comment.and_then(consume_comment_block);
     let mut member_value = None;
 
@@ -33,7 +34,8 @@ fn consume_enum_member(
             Rule::constant => {
                 member_value = Some(consume_constant(current, ctx));
             }
-            Rule::block_attribute_list => {},
+            Rule::comment_block => comment = consume_comment_block(current).or(comment),
+            Rule::block_attribute_list => attributes = consume_attribute_list(current, ctx).0,
             Rule::inline_attribute_list => {}
             Rule::trailing_comment => {
                 comment = match (comment, consume_trailing_comment(current)) {
@@ -49,7 +51,7 @@ fn consume_enum_member(
 
     Ok(ast::EnumMember {
         name: name.unwrap(),
-        documentation: None,
+        documentation: comment,
         attributes: ast::AttributeList(attributes),
         value: member_value.unwrap(),
         span: ast::Span::from_pest(pair_span, ctx.source_id),
@@ -68,6 +70,7 @@ pub(crate) fn consume_enum_layout(
 
     let attributes = ast::AttributeList(vec![]);
     let mut members = Vec::new();
+    let mut modifiers = Modifiers::default();
     let mut pending_field_comment = None;
     let mut subtype_ctor = None;
 
@@ -89,9 +92,13 @@ pub(crate) fn consume_enum_layout(
                 }
             }
             Rule::layout_subtype => {
-                // TODO: todo!() 
+                subtype_ctor = Some(consume_type_constructor(
+                    current.into_inner().next().unwrap(),
+                    &name_context,
+                    ctx,
+                ));
             }
-            Rule::declaration_modifiers => {}
+            Rule::declaration_modifiers => consume_modifier(&current, &mut modifiers),
             Rule::comment_block => pending_field_comment = Some(current),
             Rule::BLOCK_LEVEL_CATCH_ALL => ctx.diagnostics.push_error(DiagnosticsError::new_validation_error(
                 "This line is not a valid field or attribute definition.",
@@ -107,6 +114,7 @@ pub(crate) fn consume_enum_layout(
         members,
         attributes,
         documentation: None,
+        strictness: modifiers.strictness(),
         unknown_value_signed: 0,
         unknown_value_unsigned: 0,
         subtype_ctor: subtype_ctor.unwrap_or(identifier_type_for_decl(ctx.default_underlying_type.clone())),
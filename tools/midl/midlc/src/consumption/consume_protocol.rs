@@ -2,9 +2,9 @@ use core::panic;
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use super::helpers::consume_catch_all;
+use super::helpers::{consume_catch_all, consume_modifier, Modifiers};
 
-use super::{consume_identifier, identifier_type_for_decl};
+use super::{consume_compound_identifier, consume_identifier, identifier_type_for_decl};
 use super::{helpers::Pair, Rule};
 
 use crate::ast::{self, Name, Span, TypeConstructor};
@@ -58,7 +58,7 @@ fn consume_parameter_list(
                 ast::NameProvenance::GeneratedEmptySuccessStruct,
             ),
             members: vec![],
-            // resourceness: ast::Resourceness::Value,
+            resourceness: ast::Resourceness::Value,
             compiled: false,
             compiling: false,
             recursive: false,
@@ -186,7 +186,9 @@ fn create_method_result(
         members: result_members,
         attributes: ast::AttributeList(vec![]),
         documentation: None,
-        strictness: ast::Strictness::Flexible,
+        // Unknown variants are carried by `framework_err`, so the union itself is strict.
+        strictness: ast::Strictness::Strict,
+        resourceness: ast::Resourceness::Value,
         compiled: false,
         compiling: false,
         recursive: false,
@@ -209,9 +211,10 @@ fn consume_protocol_method(
     let pair_span = pair.as_span();
     let mut method_name = None;
 
-    let attributes = Vec::new();
+    let mut attributes = Vec::new();
     let mut documentation = block_comment.and_then(consume_comment_block);
-    let strictness = ast::Strictness::Flexible;
+    let is_event = pair.as_rule() == Rule::protocol_event;
+    let mut modifiers = Modifiers::default();
 
     let mut maybe_request = None;
     let mut maybe_response = None;
@@ -223,6 +226,9 @@ fn consume_protocol_method(
     // NOTE: we need to first determine what parts are present on this method
     for current in pair.clone().into_inner() {
         match current.as_rule() {
+            Rule::method_strictness => consume_modifier(&current, &mut modifiers),
+            // The payload of an event, like `-> OnFoo(...)`.
+            Rule::parameter_list if is_event => has_response = true,
             Rule::protocol_request => {
                 has_request = true;
             }
@@ -242,10 +248,17 @@ fn consume_protocol_method(
         }
     }
 
+    let strictness = modifiers.strictness();
+
     for current in pair.into_inner() {
         match current.as_rule() {
             Rule::identifier => method_name = Some(consume_identifier(&current, ctx)),
-            Rule::block_attribute_list => { /*attributes.push(parse_attribute(current, diagnostics))*/ }
+            Rule::block_attribute_list => attributes = consume_attribute_list(current, ctx).0,
+            Rule::method_strictness => {}
+            Rule::parameter_list if is_event => {
+                let event_context = protocol_context.clone().enter_event(method_name.clone().unwrap());
+                maybe_response = consume_parameter_list(current, &event_context, true, ctx);
+            }
             Rule::protocol_request => {
                 let protocol_context = protocol_context.clone();
 
@@ -343,8 +356,8 @@ fn consume_protocol_method(
 
                     maybe_response = Some(response_payload);
                 } else {
-                    let response_payload = consume_parameter_list(param_list_token, &response_context, true, ctx);
-                    maybe_response = Some(response_payload.unwrap());
+                    // `None` for an empty response, like `Foo() -> ()`.
+                    maybe_response = consume_parameter_list(param_list_token, &response_context, true, ctx);
                 }
             }
             Rule::trailing_comment => {
@@ -363,6 +376,7 @@ fn consume_protocol_method(
         name: method_name.unwrap(),
         documentation,
         strictness,
+        generated_ordinal64: 0,
         attributes,
         request_payload: None,
         response_payload: None,
@@ -375,7 +389,29 @@ fn consume_protocol_method(
     })
 }
 
-fn consume_compose(ctx: &mut ParsingContext<'_>) {}
+fn consume_compose(pair: Pair<'_>, ctx: &mut ParsingContext<'_>) -> ast::ProtocolCompose {
+    debug_assert!(pair.as_rule() == Rule::protocol_compose);
+
+    let span = ast::Span::from_pest(pair.as_span(), ctx.source_id);
+    let mut attributes = ast::AttributeList(vec![]);
+    let mut reference = None;
+
+    for current in pair.into_inner() {
+        match current.as_rule() {
+            Rule::block_attribute_list => attributes = consume_attribute_list(current, ctx),
+            Rule::compound_identifier => {
+                reference = Some(ast::Reference::new_sourced(consume_compound_identifier(&current, ctx)));
+            }
+            _ => consume_catch_all(&current, "protocol compose"),
+        }
+    }
+
+    ast::ProtocolCompose {
+        attributes,
+        reference: reference.unwrap(),
+        span,
+    }
+}
 
 pub(crate) fn consume_protocol_declaration(
     pair: Pair<'_>,
@@ -385,15 +421,24 @@ pub(crate) fn consume_protocol_declaration(
 
     let mut name = None;
     let mut name_context = None;
+    let mut openness = ast::Openness::Open;
 
     let mut pending_field_comment = None;
     let mut methods = Vec::new();
     let mut attributes = None;
-    let composes = Vec::new();
+    let mut composes = Vec::new();
 
     for current in pair.into_inner() {
         match current.as_rule() {
-            Rule::PROTOCOL_KEYWORD | Rule::BLOCK_OPEN | Rule::BLOCK_CLOSE => {}
+            Rule::protocol_openness => {
+                openness = match current.as_str() {
+                    "closed" => ast::Openness::Closed,
+                    "ajar" => ast::Openness::Ajar,
+                    "open" => ast::Openness::Open,
+                    other => unreachable!("the grammar does not allow `{other} protocol`"),
+                };
+            }
+            Rule::BLOCK_OPEN | Rule::BLOCK_CLOSE => {}
             Rule::identifier => {
                 let name_span = current.as_span();
                 let name_span = ast::Span::from_pest(name_span, ctx.source_id);
@@ -405,7 +450,7 @@ pub(crate) fn consume_protocol_declaration(
             Rule::block_attribute_list => {
                 attributes = Some(consume_attribute_list(current, ctx));
             }
-            Rule::protocol_method => {
+            Rule::protocol_method | Rule::protocol_event => {
                 let name_context = name_context.as_ref().unwrap().clone();
 
                 match consume_protocol_method(
@@ -421,8 +466,10 @@ pub(crate) fn consume_protocol_declaration(
                     Err(err) => ctx.diagnostics.push_error(err),
                 }
             }
-            Rule::protocol_event => {}
-            Rule::protocol_compose => consume_compose(ctx),
+            Rule::protocol_compose => {
+                pending_field_comment = None;
+                composes.push(consume_compose(current, ctx));
+            }
             Rule::comment_block => pending_field_comment = Some(current),
             Rule::BLOCK_LEVEL_CATCH_ALL => ctx.diagnostics.push_error(DiagnosticsError::new_validation_error(
                 "This line is not a valid field or attribute definition.",
@@ -434,6 +481,7 @@ pub(crate) fn consume_protocol_declaration(
 
     Ok(ast::Protocol {
         name: name.unwrap(),
+        openness,
         methods,
         composes,
         attributes: attributes.unwrap(),
@@ -444,3 +492,52 @@ pub(crate) fn consume_protocol_declaration(
         recursive: false,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::ast::{self, Declaration};
+    use crate::compiler::{Compiler, Libraries};
+    use crate::source_file::{SourceFile, SourceId};
+
+    /// Consumes `protocol` and returns the openness of the protocol it declares, or the
+    /// error messages.
+    fn openness(protocol: &str) -> Result<ast::Openness, Vec<String>> {
+        let compiler = Compiler::new(Rc::new(RefCell::new(Libraries::new()))).unwrap();
+        let source = format!("library test.openness;\n\n{protocol}\n");
+        let diagnostics = compiler.consume_file(SourceId(0), &SourceFile::from_str("test.midl", &source));
+
+        if diagnostics.has_errors() {
+            return Err(diagnostics.errors().iter().map(|e| e.message().to_owned()).collect());
+        }
+
+        let declarations = compiler.library.declarations.borrow();
+        match declarations.protocols.as_slice() {
+            [Declaration::Protocol { decl }] => Ok(decl.borrow().openness),
+            protocols => panic!("expected one protocol, got {}", protocols.len()),
+        }
+    }
+
+    #[test]
+    fn protocol_openness() {
+        assert_eq!(openness("closed protocol P {};"), Ok(ast::Openness::Closed));
+        assert_eq!(openness("ajar protocol P {};"), Ok(ast::Openness::Ajar));
+        assert_eq!(openness("open protocol P {};"), Ok(ast::Openness::Open));
+        assert_eq!(
+            openness("@discoverable\nclosed protocol P {};"),
+            Ok(ast::Openness::Closed)
+        );
+    }
+
+    #[test]
+    fn unknown_protocol_modifier_is_an_error() {
+        assert_eq!(
+            openness("flexible protocol P {};"),
+            Err(vec![
+                "Error validating: This line is not a valid declaration.".to_owned()
+            ])
+        );
+    }
+}
@@ -3,11 +3,12 @@ use std::rc::Rc;
 
 use super::consume_identifier;
 use super::consume_type::consume_type_constructor;
-use super::helpers::consume_catch_all;
+use super::helpers::{consume_catch_all, consume_modifier, Modifiers};
 use super::{helpers::Pair, Rule};
 
 use crate::ast;
 use crate::compiler::ParsingContext;
+use crate::consumption::consume_attribute_list;
 use crate::consumption::consume_comments::{consume_comment_block, consume_trailing_comment};
 use crate::diagnotics::DiagnosticsError;
 
@@ -21,7 +22,7 @@ fn consume_struct_member(
 
     let pair_span = pair.as_span();
     let mut name = None;
-    let attributes = Vec::new();
+    let mut attributes = Vec::new();
     let mut comment = block_comment.and_then(consume_comment_block);
     let mut type_ctor = None;
 
@@ -37,7 +38,8 @@ fn consume_struct_member(
                     ctx,
                 ))
             }
-            Rule::block_attribute_list => {}
+            Rule::comment_block => comment = consume_comment_block(current).or(comment),
+            Rule::block_attribute_list => attributes = consume_attribute_list(current, ctx).0,
             Rule::trailing_comment => {
                 comment = match (comment, consume_trailing_comment(current)) {
                     (c, None) | (None, c) => c,
@@ -53,7 +55,7 @@ fn consume_struct_member(
     match (name, type_ctor) {
         (Some(name), Some(type_ctor)) => Ok(ast::StructMember {
             name,
-            documentation: None,
+            documentation: comment,
             attributes: ast::AttributeList(attributes),
             type_ctor,
             span: ast::Span::from_pest(pair_span, ctx.source_id),
@@ -75,6 +77,7 @@ pub(crate) fn consume_struct_layout(
 
     let attributes = ast::AttributeList(vec![]);
     let mut members = Vec::new();
+    let mut modifiers = Modifiers::default();
     let mut pending_field_comment = None;
 
     for current in token.into_inner() {
@@ -89,7 +92,7 @@ pub(crate) fn consume_struct_layout(
                     Err(err) => ctx.diagnostics.push_error(err),
                 }
             }
-            Rule::declaration_modifiers => {}
+            Rule::declaration_modifiers => consume_modifier(&current, &mut modifiers),
             Rule::comment_block => pending_field_comment = Some(current),
             Rule::BLOCK_LEVEL_CATCH_ALL => ctx.diagnostics.push_error(DiagnosticsError::new_validation_error(
                 "This line is not a valid field or attribute definition.",
@@ -105,6 +108,7 @@ pub(crate) fn consume_struct_layout(
         members,
         attributes,
         documentation: None,
+        resourceness: modifiers.resourceness(),
         compiled: false,
         compiling: false,
         recursive: false,
@@ -3,11 +3,12 @@ use std::rc::Rc;
 
 use super::consume_identifier;
 use super::consume_type::consume_type_constructor;
-use super::helpers::consume_catch_all;
+use super::helpers::{consume_catch_all, consume_modifier, Modifiers};
 use super::{helpers::Pair, Rule};
 
 use crate::ast::{self, NamingContext, Strictness};
 use crate::compiler::ParsingContext;
+use crate::consumption::consume_attribute_list;
 use crate::consumption::consume_comments::{consume_comment_block, consume_trailing_comment};
 use crate::consumption::helpers::consume_ordinal64;
 use crate::diagnotics::DiagnosticsError;
@@ -22,7 +23,7 @@ fn consume_table_member(
 
     let pair_span = pair.as_span();
     let mut name = None;
-    let attributes = Vec::new();
+    let mut attributes = Vec::new();
     let mut comment = block_comment.and_then(consume_comment_block);
     let mut ordinal = None;
     let mut type_ctor = None;
@@ -34,8 +35,17 @@ fn consume_table_member(
             Rule::ordinal => {
                 ordinal = Some(consume_ordinal64(current, ctx)?);
             }
-            Rule::type_constructor => type_ctor = Some(consume_type_constructor(current, &name_context, ctx)),
-            Rule::block_attribute_list => {}
+            Rule::type_constructor => {
+                let name_context = name_context.clone();
+
+                type_ctor = Some(consume_type_constructor(
+                    current,
+                    &name_context.enter_member(name.as_ref().unwrap().clone()),
+                    ctx,
+                ))
+            }
+            Rule::comment_block => comment = consume_comment_block(current).or(comment),
+            Rule::block_attribute_list => attributes = consume_attribute_list(current, ctx).0,
             Rule::RESERVED_KEYWORD => {
                 reserved = true;
             }
@@ -53,7 +63,7 @@ fn consume_table_member(
 
     if !reserved {
         Ok(ast::TableMember {
-            documentation: None,
+            documentation: comment.clone(),
             attributes: ast::AttributeList(attributes),
             ordinal: ordinal.unwrap(),
             maybe_used: Some(ast::TableMemberUsed {
@@ -64,7 +74,7 @@ fn consume_table_member(
         })
     } else {
         Ok(ast::TableMember {
-            documentation: None,
+            documentation: comment,
             ordinal: ordinal.unwrap(),
             attributes: ast::AttributeList(attributes),
             span: ast::Span::from_pest(pair_span, ctx.source_id),
@@ -85,12 +95,13 @@ pub(crate) fn consume_table_layout(
 
     let attributes = ast::AttributeList(vec![]);
     let mut members = Vec::new();
+    let mut modifiers = Modifiers::default();
     let mut pending_field_comment = None;
 
     for current in token.into_inner() {
         match current.as_rule() {
             Rule::STRUCT_KEYWORD | Rule::BLOCK_OPEN | Rule::BLOCK_CLOSE => {}
-            Rule::declaration_modifiers => {},
+            Rule::declaration_modifiers => consume_modifier(&current, &mut modifiers),
             Rule::inline_attribute_list => { /*attributes.push(parse_attribute(current, diagnostics)) */ }
             Rule::ordinal_layout_member => {
                 match consume_table_member(current, pending_field_comment.take(), name_context.clone(), ctx) {
@@ -114,7 +125,9 @@ pub(crate) fn consume_table_layout(
         members,
         attributes,
         documentation: None,
+        // Tables are always flexible.
         strictness: Strictness::Flexible,
+        resourceness: modifiers.resourceness(),
         span: table_span,
         compiled: false,
         compiling: false,
@@ -5,6 +5,7 @@ use crate::ast::Constant;
 use crate::ast::Reference;
 use crate::ast::Target;
 use crate::compiler::ParsingContext;
+use crate::consumption::consume_attribute::consume_attribute_list;
 use crate::consumption::consume_bits::consume_bits_layout;
 use crate::consumption::consume_const::consume_constant;
 use crate::consumption::consume_enum::consume_enum_layout;
@@ -73,6 +74,30 @@ fn consume_layout_parameters(
     params
 }
 
+/// Applies `@generated_name("Name")` on an inline layout to the naming context of the layout, so
+/// it is declared under that name instead of the one derived from its position.
+fn consume_generated_name(
+    pair: &Pair<'_>,
+    name_context: &Rc<ast::NamingContext>,
+    ctx: &mut ParsingContext<'_>,
+) {
+    let Some(attributes) = pair.clone().into_inner().find(|p| p.as_rule() == Rule::inline_attribute_list) else {
+        return;
+    };
+
+    for attribute in consume_attribute_list(attributes, ctx).0 {
+        if attribute.name.decl_name() != "generated_name" {
+            continue;
+        }
+
+        if let Some(Constant::Literal(constant)) = attribute.arguments.first().map(|arg| &arg.value) {
+            if let Some((value, _)) = constant.literal.as_string_value() {
+                name_context.set_name_override(value.trim_matches('"').to_string());
+            }
+        }
+    }
+}
+
 enum Layout {
     Inline(ast::Declaration),
     Named,
@@ -104,26 +129,31 @@ pub(crate) fn consume_type_constructor(
                 layout = Some(ast::Reference::new_sourced(name));
             }
             Rule::inline_struct_layout => {
+                consume_generated_name(&current, name_context, ctx);
                 let decl = consume_struct_layout(current, name_context.clone(), ctx).unwrap();
                 ctx.library.declarations.borrow_mut().insert(decl.clone());
                 layout = Some(ast::Reference::new_synthetic(Target::new(decl)));
             }
             Rule::inline_enum_layout => {
+                consume_generated_name(&current, name_context, ctx);
                 let decl = consume_enum_layout(current, name_context.clone(), ctx).unwrap();
                 ctx.library.declarations.borrow_mut().insert(decl.clone());
                 layout = Some(ast::Reference::new_synthetic(Target::new(decl)));
             }
             Rule::inline_table_layout => {
+                consume_generated_name(&current, name_context, ctx);
                 let decl = consume_table_layout(current, name_context.clone(), ctx).unwrap();
                 ctx.library.declarations.borrow_mut().insert(decl.clone());
                 layout = Some(ast::Reference::new_synthetic(Target::new(decl)));
             }
             Rule::inline_union_layout => {
+                consume_generated_name(&current, name_context, ctx);
                 let decl = consume_union_layout(current, name_context.clone(), ctx).unwrap();
                 ctx.library.declarations.borrow_mut().insert(decl.clone());
                 layout = Some(ast::Reference::new_synthetic(Target::new(decl)));
             }
             Rule::inline_bits_layout => {
+                consume_generated_name(&current, name_context, ctx);
                 let decl = consume_bits_layout(current, name_context.clone(), ctx).unwrap();
                 ctx.library.declarations.borrow_mut().insert(decl.clone());
                 layout = Some(ast::Reference::new_synthetic(Target::new(decl)));
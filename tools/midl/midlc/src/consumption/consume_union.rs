@@ -3,11 +3,12 @@ use std::rc::Rc;
 
 use super::consume_identifier;
 use super::consume_type::consume_type_constructor;
-use super::helpers::consume_catch_all;
+use super::helpers::{consume_catch_all, consume_modifier, Modifiers};
 use super::{helpers::Pair, Rule};
 
 use crate::ast::{self, Strictness};
 use crate::compiler::ParsingContext;
+use crate::consumption::consume_attribute_list;
 use crate::consumption::consume_comments::{consume_comment_block, consume_trailing_comment};
 use crate::consumption::helpers::consume_ordinal64;
 use crate::diagnotics::DiagnosticsError;
@@ -22,7 +23,7 @@ fn consume_union_member(
 
     let pair_span = pair.as_span();
     let mut name = None;
-    let attributes = Vec::new();
+    let mut attributes = Vec::new();
     let mut comment = block_comment.and_then(consume_comment_block);
     let mut ordinal = None;
     let mut type_ctor = None;
@@ -42,7 +43,8 @@ fn consume_union_member(
                     ctx,
                 ))
             }
-            Rule::block_attribute_list => {}
+            Rule::comment_block => comment = consume_comment_block(current).or(comment),
+            Rule::block_attribute_list => attributes = consume_attribute_list(current, ctx).0,
             Rule::RESERVED_KEYWORD => {
                 reserved = true;
             }
@@ -60,7 +62,7 @@ fn consume_union_member(
 
     if !reserved {
         Ok(ast::UnionMember {
-            documentation: None,
+            documentation: comment.clone(),
             attributes: ast::AttributeList(attributes),
             ordinal: ordinal.unwrap(),
             maybe_used: Some(ast::UnionMemberUsed {
@@ -71,7 +73,7 @@ fn consume_union_member(
         })
     } else {
         Ok(ast::UnionMember {
-            documentation: None,
+            documentation: comment,
             ordinal: ordinal.unwrap(),
             attributes: ast::AttributeList(attributes),
             span: ast::Span::from_pest(pair_span, ctx.source_id),
@@ -92,12 +94,13 @@ pub(crate) fn consume_union_layout(
 
     let attributes = ast::AttributeList(vec![]);
     let mut members = Vec::new();
+    let mut modifiers = Modifiers::default();
     let mut pending_field_comment = None;
 
     for current in token.into_inner() {
         match current.as_rule() {
             Rule::STRUCT_KEYWORD | Rule::BLOCK_OPEN | Rule::BLOCK_CLOSE => {}
-            Rule::declaration_modifiers => {},
+            Rule::declaration_modifiers => consume_modifier(&current, &mut modifiers),
             Rule::inline_attribute_list => { /*attributes.push(parse_attribute(current, diagnostics)) */ }
             Rule::ordinal_layout_member => {
                 match consume_union_member(current, pending_field_comment.take(), &name_context, ctx) {
@@ -121,7 +124,8 @@ pub(crate) fn consume_union_layout(
         members,
         attributes,
         documentation: None,
-        strictness: Strictness::Flexible,
+        strictness: modifiers.strictness(),
+        resourceness: modifiers.resourceness(),
         span: union_span,
         compiled: false,
         compiling: false,
@@ -47,6 +47,35 @@ pub fn consume_ordinal64(pair: Pair<'_>, ctx: &mut ParsingContext<'_>) -> Result
     Ok(ast::RawOrdinal64 { value: ordinal, span })
 }
 
+/// The `strict`, `flexible` and `resource` modifiers of a layout. Unset modifiers take the
+/// default of the layout kind.
+#[derive(Debug, Default)]
+pub struct Modifiers {
+    pub strictness: Option<ast::Strictness>,
+    pub resourceness: Option<ast::Resourceness>,
+}
+
+impl Modifiers {
+    pub fn strictness(&self) -> ast::Strictness {
+        self.strictness.unwrap_or(ast::Strictness::Flexible)
+    }
+
+    pub fn resourceness(&self) -> ast::Resourceness {
+        self.resourceness.unwrap_or(ast::Resourceness::Value)
+    }
+}
+
+pub fn consume_modifier(pair: &Pair<'_>, modifiers: &mut Modifiers) {
+    debug_assert!(pair.as_rule() == Rule::declaration_modifiers || pair.as_rule() == Rule::method_strictness);
+
+    match pair.as_str() {
+        "strict" => modifiers.strictness = Some(ast::Strictness::Strict),
+        "flexible" => modifiers.strictness = Some(ast::Strictness::Flexible),
+        "resource" => modifiers.resourceness = Some(ast::Resourceness::Resource),
+        other => unreachable!("the grammar does not allow the `{other}` modifier"),
+    }
+}
+
 #[track_caller]
 pub fn consume_catch_all(token: &Pair<'_>, kind: &str) {
     match token.as_rule() {
@@ -19,6 +19,7 @@ mod parser;
 use consume_alias::consume_alias_declaration;
 use consume_attribute::consume_attribute_list;
 use consume_bits::consume_bits_layout;
+use consume_comments::consume_comment_block;
 use consume_const::consume_constant_declaration;
 use consume_enum::consume_enum_layout;
 use consume_import::consume_import;
@@ -34,7 +35,34 @@ use self::helpers::consume_catch_all;
 use super::ast;
 use crate::{ast::Name, compiler::ParsingContext, diagnotics::DiagnosticsError};
 pub use parser::{MIDLParser, Rule};
+use pest::error::InputLocation;
 use pest::iterators::{Pair, Pairs};
+use pest::Parser;
+
+use crate::source_file::SourceId;
+
+/// Parses a whole MIDL source file, mapping a parser failure to a diagnostic.
+pub(crate) fn parse_library(source: &str, source_id: SourceId) -> Result<Pair<'_, Rule>, DiagnosticsError> {
+    match MIDLParser::parse(Rule::library, source) {
+        Ok(mut pairs) => Ok(pairs.next().unwrap()),
+        Err(err) => {
+            let (start, end) = match err.location {
+                InputLocation::Pos(pos) => (pos, pos),
+                InputLocation::Span((start, end)) => (start, end),
+            };
+
+            Err(DiagnosticsError::new(
+                format!("failed to parse: {}", err.variant.message()),
+                ast::Span::new(start, end, source_id),
+            ))
+        }
+    }
+}
+
+/// Returns the offset right after the last non-whitespace character of a pair.
+pub(crate) fn content_end(pair: &Pair<'_, Rule>) -> usize {
+    pair.as_span().start() + pair.as_str().trim_end().len()
+}
 
 pub(crate) fn consume_identifier(pair: &Pair<'_, Rule>, ctx: &mut ParsingContext<'_>) -> ast::Span {
     debug_assert!(pair.as_rule() == Rule::identifier);
@@ -84,6 +112,8 @@ pub(crate) fn consume_layout_declaration(
     let span = token.as_span();
 
     let mut name_context = None;
+    let mut attributes = ast::AttributeList(vec![]);
+    let mut layout = None;
 
     for current in token.into_inner() {
         match current.as_rule() {
@@ -95,41 +125,64 @@ pub(crate) fn consume_layout_declaration(
 
                 name_context = Some(ast::NamingContext::create(&sourced));
             }
-            Rule::block_attribute_list => { /*attributes.push(parse_attribute(current, diagnostics)) */ }
+            Rule::block_attribute_list => attributes = consume_attribute_list(current, ctx),
             Rule::inline_struct_layout => {
-                return consume_struct_layout(current, name_context.unwrap(), ctx);
+                layout = Some(consume_struct_layout(current, name_context.clone().unwrap(), ctx));
             }
             Rule::inline_enum_layout => {
-                return consume_enum_layout(current, name_context.unwrap(), ctx);
+                layout = Some(consume_enum_layout(current, name_context.clone().unwrap(), ctx));
             }
             Rule::inline_union_layout => {
-                return consume_union_layout(current, name_context.unwrap(), ctx);
+                layout = Some(consume_union_layout(current, name_context.clone().unwrap(), ctx));
             }
             Rule::inline_table_layout => {
-                return consume_table_layout(current, name_context.unwrap(), ctx);
+                layout = Some(consume_table_layout(current, name_context.clone().unwrap(), ctx));
             }
             Rule::inline_bits_layout => {
-                return consume_bits_layout(current, name_context.unwrap(), ctx);
+                layout = Some(consume_bits_layout(current, name_context.clone().unwrap(), ctx));
             }
             Rule::CATCH_ALL => consume_catch_all(&current, "layout_declaration"),
             _ => todo!(),
         }
     }
 
-    Err(DiagnosticsError::new("", ast::Span::from_pest(span, ctx.source_id)))
+    match layout {
+        Some(Ok(decl)) => {
+            decl.set_attributes(attributes);
+            Ok(decl)
+        }
+        Some(Err(err)) => Err(err),
+        None => Err(DiagnosticsError::new("", ast::Span::from_pest(span, ctx.source_id))),
+    }
 }
 
 pub(crate) fn consume_source(pairs: Pairs<'_, Rule>, ctx: &mut ParsingContext<'_>) {
     // initial parsing
     for pair in pairs {
         if let Rule::library = pair.as_rule() {
+            // The doc comment of a declaration is a separate comment block in front of it.
+            let mut pending_comment = None;
+
             for declaration_pair in pair.into_inner() {
+                let documentation = match declaration_pair.as_rule() {
+                    Rule::comment_block => {
+                        pending_comment = Some(declaration_pair);
+                        continue;
+                    }
+                    Rule::empty_lines => None,
+                    _ => pending_comment.take().and_then(consume_comment_block),
+                };
+
                 match declaration_pair.as_rule() {
                     Rule::layout_declaration => {
                         let layout_declaration = consume_layout_declaration(declaration_pair, ctx);
 
                         match layout_declaration {
-                            Ok(decl) => ctx.library.declarations.borrow_mut().insert(decl.into()),
+                            Ok(decl) => {
+                                let decl: ast::Declaration = decl.into();
+                                decl.set_documentation(documentation);
+                                ctx.library.declarations.borrow_mut().insert(decl)
+                            }
                             Err(err) => ctx.diagnostics.push_error(err),
                         }
                     }
@@ -137,7 +190,11 @@ pub(crate) fn consume_source(pairs: Pairs<'_, Rule>, ctx: &mut ParsingContext<'_
                         let const_declaration = consume_constant_declaration(declaration_pair, ctx);
 
                         match const_declaration {
-                            Ok(decl) => ctx.library.declarations.borrow_mut().insert(decl.into()),
+                            Ok(decl) => {
+                                let decl: ast::Declaration = decl.into();
+                                decl.set_documentation(documentation);
+                                ctx.library.declarations.borrow_mut().insert(decl)
+                            }
                             Err(err) => ctx.diagnostics.push_error(err),
                         }
                     }
@@ -145,7 +202,11 @@ pub(crate) fn consume_source(pairs: Pairs<'_, Rule>, ctx: &mut ParsingContext<'_
                         let alias_declaration = consume_alias_declaration(declaration_pair, ctx);
 
                         match alias_declaration {
-                            Ok(decl) => ctx.library.declarations.borrow_mut().insert(decl.into()),
+                            Ok(decl) => {
+                                let decl: ast::Declaration = decl.into();
+                                decl.set_documentation(documentation);
+                                ctx.library.declarations.borrow_mut().insert(decl)
+                            }
                             Err(err) => ctx.diagnostics.push_error(err),
                         }
                     }
@@ -153,7 +214,11 @@ pub(crate) fn consume_source(pairs: Pairs<'_, Rule>, ctx: &mut ParsingContext<'_
                         let alias_declaration = consume_resource_declaration(declaration_pair, ctx);
 
                         match alias_declaration {
-                            Ok(decl) => ctx.library.declarations.borrow_mut().insert(decl.into()),
+                            Ok(decl) => {
+                                let decl: ast::Declaration = decl.into();
+                                decl.set_documentation(documentation);
+                                ctx.library.declarations.borrow_mut().insert(decl)
+                            }
                             Err(err) => ctx.diagnostics.push_error(err),
                         }
                     }
@@ -161,7 +226,11 @@ pub(crate) fn consume_source(pairs: Pairs<'_, Rule>, ctx: &mut ParsingContext<'_
                         let protocol_declaration = consume_protocol_declaration(declaration_pair, ctx);
 
                         match protocol_declaration {
-                            Ok(decl) => ctx.library.declarations.borrow_mut().insert(decl.into()),
+                            Ok(decl) => {
+                                let decl: ast::Declaration = decl.into();
+                                decl.set_documentation(documentation);
+                                ctx.library.declarations.borrow_mut().insert(decl)
+                            }
                             Err(err) => ctx.diagnostics.push_error(err),
                         }
                     }
@@ -177,6 +246,10 @@ pub(crate) fn consume_source(pairs: Pairs<'_, Rule>, ctx: &mut ParsingContext<'_
                         consume_import(&declaration_pair, ctx);
                     }
                     Rule::EOI => {}
+                    Rule::CATCH_ALL => ctx.diagnostics.push_error(DiagnosticsError::new_validation_error(
+                        "This line is not a valid declaration.",
+                        ast::Span::from_pest(declaration_pair.as_span(), ctx.source_id),
+                    )),
                     _ => consume_catch_all(&declaration_pair, "declaration"),
                 }
             }
@@ -14,6 +14,7 @@ pub(crate) struct DiagnosticsError {
 pub(crate) enum Error {
     StrictUnionMustHaveNonReservedMember { span: Span },
     DuplicateUnionMemberOrdinal { span: Span, prev: Span },
+    DuplicateTableFieldOrdinal { span: Span, prev: Span },
     NonDenseOrdinal { span: Span, ordinal: u64 },
     OrdinalOutOfBound { span: Span },
     OrdinalsMustStartAtOne { span: Span }
@@ -39,6 +40,14 @@ impl From<Error> for DiagnosticsError {
                 .into(),
                 span,
             },
+            Error::DuplicateTableFieldOrdinal { span, prev } => DiagnosticsError {
+                message: format!(
+                    "multiple table fields with the same ordinal; previous was at {}",
+                    prev.data
+                )
+                .into(),
+                span,
+            },
             Error::OrdinalOutOfBound { span } => DiagnosticsError {
                 message: format!("ordinal out-of-bound").into(),
                 span,
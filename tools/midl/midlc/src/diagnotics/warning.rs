@@ -1,2 +1,50 @@
-#[derive(Debug)]
-pub(crate) struct DiagnosticsWarning;
\ No newline at end of file
+use colored::{ColoredString, Colorize};
+
+use crate::ast::Span;
+use std::borrow::Cow;
+
+use super::pretty_print::{pretty_print, DiagnosticColorer};
+
+#[derive(Debug, Clone)]
+pub(crate) struct DiagnosticsWarning {
+    span: Span,
+    message: Cow<'static, str>,
+}
+
+impl DiagnosticsWarning {
+    pub fn new(message: impl Into<Cow<'static, str>>, span: Span) -> Self {
+        let message = message.into();
+        DiagnosticsWarning { message, span }
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn pretty_print(&self, f: &mut dyn std::io::Write, file_name: &str, text: &str) -> std::io::Result<()> {
+        pretty_print(
+            f,
+            file_name,
+            text,
+            self.span(),
+            self.message.as_ref(),
+            &DiagnosticsWarningColorer {},
+        )
+    }
+}
+
+struct DiagnosticsWarningColorer {}
+
+impl DiagnosticColorer for DiagnosticsWarningColorer {
+    fn title(&self) -> &'static str {
+        "warning"
+    }
+
+    fn primary_color(&self, token: &'_ str) -> ColoredString {
+        token.yellow()
+    }
+}
@@ -1,54 +1,106 @@
+use std::cell::RefCell;
+
 use midlgen::ir::{self, EncodedCompoundIdentifier, HandleRights, Resourceness};
 
+pub(crate) mod type_shape;
+
 use crate::{
-    ast::{self, ConstantTrait, NullabilityTrait, ProtocolTrait, WithName},
+    ast::{self, ConstantTrait, HandleTrait, NullabilityTrait, ProtocolTrait, WithName},
     compiler, ExperimentalFlags,
 };
 
-pub struct JSONGenerator {
-    compilation: compiler::Compilation,
-}
+use type_shape::TypeShapeCalculator;
 
-enum TypeKind {
-    Concrete,
-    Parameterized,
-    RequestPayload,
-    ResponsePayload,
-}
+/// Rights of a handle whose type does not restrict them: the sender's rights are kept.
+const SAME_RIGHTS: u32 = 0x8000_0000;
 
-fn should_expose_alias_of_parametrized_type(r#type: &ast::Type) -> bool {
-    match r#type {
-        ast::Type::Array(_) => true,
-        ast::Type::Vector(_) => true,
-        ast::Type::TransportSide(transport_side) => transport_side.end == ast::TransportSide::Server,
-        _ => false,
-    }
+pub struct JSONGenerator {
+    compilation: compiler::Compilation,
+    shapes: RefCell<TypeShapeCalculator>,
 }
 
 impl JSONGenerator {
     pub fn new(compilation: compiler::Compilation, flags: ExperimentalFlags) -> Self {
-        Self { compilation }
+        Self {
+            compilation,
+            shapes: RefCell::default(),
+        }
     }
 
     pub fn produce(&self) -> serde_json::Value {
-        // &self.compilation.library_name
-
         let ir = ir::Root {
-            name: ir::EncodedLibraryIdentifier("TODO".to_string()),
+            name: ir::EncodedLibraryIdentifier(self.compilation.library_name.join(".")),
+            maybe_attributes: vec![],
             const_declarations: self.generate_const_declarations(&self.compilation.declarations.consts),
             enum_declarations: self.generate_enum_declarations(&self.compilation.declarations.enums),
             struct_declarations: self.generate_struct_declarations(&self.compilation.declarations.structs),
+            external_struct_declarations: self
+                .compilation
+                .external_structs
+                .iter()
+                .map(|decl| self.generate_struct(decl.borrow().clone()))
+                .collect(),
             union_declarations: self.generate_union_declarations(&self.compilation.declarations.unions),
             protocol_declarations: self.generate_protocol_declarations(&self.compilation.declarations.protocols),
-            table_declarations: vec![],
-            bits_declarations: vec![],
+            table_declarations: self.generate_table_declarations(&self.compilation.declarations.tables),
+            bits_declarations: self.generate_bits_declarations(&self.compilation.declarations.bits),
             experiments: vec![],
-            library_dependencies: vec![],
+            library_dependencies: self
+                .compilation
+                .direct_and_composed_dependencies
+                .iter()
+                .map(|dependency| self.generate_library_dependency(dependency))
+                .collect(),
         };
 
         serde_json::to_value(ir).unwrap()
     }
 
+    fn generate_library_dependency(&self, dependency: &compiler::Dependency) -> ir::Library {
+        let declarations = &dependency.declarations;
+        let mut ir_declarations = ir::DeclInfoMap::new();
+
+        for decl in declarations
+            .bits
+            .iter()
+            .chain(declarations.consts.iter())
+            .chain(declarations.enums.iter())
+            .chain(declarations.protocols.iter())
+            .chain(declarations.structs.iter())
+            .chain(declarations.tables.iter())
+            .chain(declarations.unions.iter())
+        {
+            ir_declarations.insert(self.generate_name(&decl.name()), self.generate_decl_info(decl));
+        }
+
+        ir::Library {
+            name: ir::EncodedLibraryIdentifier(dependency.library.name.get().unwrap().join(".")),
+            declarations: ir_declarations,
+        }
+    }
+
+    fn generate_decl_info(&self, decl: &ast::Declaration) -> ir::DeclInfo {
+        let resource = |resourceness: ast::Resourceness| Some(Resourceness(resourceness == ast::Resourceness::Resource));
+
+        let (r#type, resource) = match decl {
+            ast::Declaration::Bits { .. } => (ir::DeclType::BitsDecl, None),
+            ast::Declaration::Const { .. } => (ir::DeclType::ConstDecl, None),
+            ast::Declaration::Enum { .. } => (ir::DeclType::EnumDecl, None),
+            ast::Declaration::Protocol { .. } => (ir::DeclType::ProtocolDecl, None),
+            ast::Declaration::Struct { decl } => (ir::DeclType::StructDecl, resource(decl.borrow().resourceness)),
+            ast::Declaration::Table { decl } => (ir::DeclType::TableDecl, resource(decl.borrow().resourceness)),
+            ast::Declaration::Union { decl } => (ir::DeclType::UnionDecl, resource(decl.borrow().resourceness)),
+            ast::Declaration::Alias { .. } => (ir::DeclType::AliasDecl, None),
+            ast::Declaration::Resource { .. } => (ir::DeclType::ExperimentalResourceDecl, None),
+            ast::Declaration::Overlay => (ir::DeclType::OverlayDecl, None),
+            ast::Declaration::Builtin { .. } | ast::Declaration::NewType => {
+                panic!("unexpected library declaration: {:?}", decl.name())
+            }
+        };
+
+        ir::DeclInfo { r#type, resource }
+    }
+
     fn generate_const_declarations(&self, decls: &Vec<ast::Declaration>) -> Vec<ir::Const> {
         let mut ir_decls = vec![];
 
@@ -100,6 +152,32 @@ impl JSONGenerator {
             .collect()
     }
 
+    fn generate_table_declarations(&self, decls: &[ast::Declaration]) -> Vec<ir::Table> {
+        decls
+            .iter()
+            .map(|decl| {
+                if let ast::Declaration::Table { decl } = decl {
+                    self.generate_table(decl.borrow().clone())
+                } else {
+                    panic!("")
+                }
+            })
+            .collect()
+    }
+
+    fn generate_bits_declarations(&self, decls: &[ast::Declaration]) -> Vec<ir::Bits> {
+        decls
+            .iter()
+            .map(|decl| {
+                if let ast::Declaration::Bits { decl } = decl {
+                    self.generate_bits(decl.borrow().clone())
+                } else {
+                    panic!("")
+                }
+            })
+            .collect()
+    }
+
     fn generate_protocol_declarations(&self, decls: &Vec<ast::Declaration>) -> Vec<ir::Protocol> {
         decls
             .into_iter()
@@ -174,36 +252,73 @@ impl JSONGenerator {
         }
     }
 
-    fn generate_type_shape(&self) -> ir::TypeShape {
-        ir::TypeShape {
-            inline_size: 0,
-            alignment: 0,
-            depth: 0,
-            max_handles: 0,
-            max_out_of_line: 0,
-            has_padding: false,
-            has_flexible_envelope: false,
+    fn generate_handle_subtype(&self, obj_type: Option<u32>) -> ir::HandleSubtype {
+        // The values of `fx.ObjType`.
+        match obj_type {
+            None | Some(0) => ir::HandleSubtype::None,
+            Some(1) => ir::HandleSubtype::Process,
+            Some(2) => ir::HandleSubtype::Thread,
+            Some(3) => ir::HandleSubtype::Vmo,
+            Some(4) => ir::HandleSubtype::Channel,
+            Some(5) => ir::HandleSubtype::Event,
+            Some(6) => ir::HandleSubtype::Port,
+            Some(9) => ir::HandleSubtype::Interrupt,
+            Some(11) => ir::HandleSubtype::PciDevice,
+            Some(12) => ir::HandleSubtype::Debuglog,
+            Some(14) => ir::HandleSubtype::Socket,
+            Some(15) => ir::HandleSubtype::Resource,
+            Some(16) => ir::HandleSubtype::EventPair,
+            Some(17) => ir::HandleSubtype::Job,
+            Some(18) => ir::HandleSubtype::Vmar,
+            Some(19) => ir::HandleSubtype::Fifo,
+            Some(20) => ir::HandleSubtype::Guest,
+            Some(21) => ir::HandleSubtype::Vcpu,
+            Some(22) => ir::HandleSubtype::Timer,
+            Some(23) => ir::HandleSubtype::Iommu,
+            Some(24) => ir::HandleSubtype::Bti,
+            Some(25) => ir::HandleSubtype::Profile,
+            Some(26) => ir::HandleSubtype::Pmt,
+            Some(27) => ir::HandleSubtype::SuspendToken,
+            Some(28) => ir::HandleSubtype::Pager,
+            Some(29) => ir::HandleSubtype::Exception,
+            Some(30) => ir::HandleSubtype::Clock,
+            Some(31) => ir::HandleSubtype::Stream,
+            Some(32) => ir::HandleSubtype::Msi,
+            Some(other) => panic!("unknown handle subtype: {}", other),
         }
     }
 
-    fn generate_field_shape(&self) -> ir::FieldShape {
-        ir::FieldShape { offset: 0, padding: 0 }
+    fn generate_type_shape(&self, r#type: &ast::Type) -> ir::TypeShape {
+        self.shapes.borrow_mut().of_type(r#type)
     }
 
     fn generate_type(&self, value: ast::Type) -> ir::Type {
+        let type_shape_v2 = self.generate_type_shape(&value);
+
         match value {
-            ast::Type::Box(r#type) => {
-                self.generate_type(r#type.boxed_type.clone())
-            }
+            // Boxes only hold structs, and are represented as optional structs.
+            ast::Type::Box(r#type) => match &r#type.boxed_type {
+                ast::Type::Identifier(boxed) => ir::Type::IdentifierType {
+                    identifier: self.generate_name(&boxed.name),
+                    nullable: true,
+                    type_shape_v2,
+                },
+                boxed => self.generate_type(boxed.clone()),
+            },
             ast::Type::Vector(r#type) => ir::Type::VectorType {
                 element_type: Box::from(self.generate_type(r#type.element_type.clone())),
-                element_count: Some(r#type.element_size()),
+                element_count: Some(r#type.element_size()).filter(|size| *size < u32::MAX),
                 nullable: self.generate_nullable(&r#type.constraints.nullability()),
             },
+            ast::Type::Array(r#type) => ir::Type::ArrayType {
+                element_type: Box::from(self.generate_type(r#type.element_type.clone())),
+                element_count: r#type.size_value.as_size().expect("array size is resolved") as u32,
+                type_shape_v2,
+            },
             ast::Type::Identifier(ref r#type) => ir::Type::IdentifierType {
                 identifier: self.generate_name(&r#type.name),
                 nullable: self.generate_nullable(&r#type.constraints.nullabilty()),
-                type_shape_v2: self.generate_type_shape(),
+                type_shape_v2,
             },
             ast::Type::String(ref r#type) => {
                 let mut element_count = None;
@@ -215,25 +330,18 @@ impl JSONGenerator {
                 ir::Type::StringType {
                     element_count,
                     nullable: self.generate_nullable(&r#type.constraints.nullability()),
-                    type_shape_v2: self.generate_type_shape(),
-                }
-            }
-            ast::Type::Handle(r#type) => {
-                //GenerateObjectMember("obj_type", r#type.subtype);
-                //GenerateObjectMember("subtype", r#type.subtype);
-                //GenerateObjectMember("rights", r#type.rights.value);
-                //GenerateObjectMember("nullable", r#type.nullability);
-                //GenerateObjectMember("resource_identifier", NameFlatName(r#type.resource_decl.name));
-                ir::Type::HandleType {
-                    handle_subtype: ir::HandleSubtype::Channel,
-                    handle_rights: ir::HandleRights::READ,
-                    nullable: self.generate_nullable(&r#type.constraints.nullability()),
-                    type_shape_v2: self.generate_type_shape(),
+                    type_shape_v2,
                 }
             }
+            ast::Type::Handle(r#type) => ir::Type::HandleType {
+                handle_subtype: self.generate_handle_subtype(r#type.constraints.subtype()),
+                handle_rights: HandleRights::from_bits_retain(r#type.constraints.rights().unwrap_or(SAME_RIGHTS)),
+                nullable: self.generate_nullable(&r#type.constraints.nullability()),
+                type_shape_v2,
+            },
             ast::Type::Internal(r#type) => ir::Type::InternalType {
                 internal_subtype: self.generate_internal_subtype(&r#type.subtype),
-                type_shape_v2: self.generate_type_shape(),
+                type_shape_v2,
             },
             ast::Type::Primitive(r#type) => ir::Type::PrimitiveType {
                 primitive_subtype: self.generate_primitive_subtype(&r#type.subtype),
@@ -243,40 +351,33 @@ impl JSONGenerator {
             // the JSON IR.
             // TODO(https://fxbug.dev/42149402): clean up client/server end representation in the IR
             ast::Type::TransportSide(r#type) => {
-                // This code path should only apply to client ends. The server end code
-                // path is colocated with the parameterized types.
-                assert!(matches!(r#type.end, ast::TransportSide::Client));
-
-                ir::Type::ClientEnd {
-                    identifier: self.generate_name(r#type.constraints.protocol().unwrap().borrow().name()),
-                    protocol_transport: r#type.protocol_transport,
-                    nullable: self.generate_nullable(&r#type.constraints.nullability()),
+                let protocol = self.generate_name(r#type.constraints.protocol().unwrap().borrow().name());
+                let nullable = self.generate_nullable(&r#type.constraints.nullability());
+
+                match r#type.end {
+                    ast::TransportSide::Client => ir::Type::ClientEnd {
+                        identifier: protocol,
+                        protocol_transport: r#type.protocol_transport,
+                        nullable,
+                    },
+                    ast::TransportSide::Server => ir::Type::ServerEnd {
+                        subtype: protocol,
+                        nullable,
+                        type_shape_v2,
+                    },
                 }
             }
-            _ => panic!("unsupported type: {:?}", value),
+            ast::Type::RequestType { nullable, subtype } => ir::Type::ServerEnd {
+                subtype: EncodedCompoundIdentifier(subtype),
+                nullable,
+                type_shape_v2,
+            },
+            ast::Type::UntypedNumeric(_) => panic!("unsupported type: {:?}", value),
         }
     }
 
-    fn generate_type_and_from_alias(&self, parent_type_kind: TypeKind, value: ast::TypeConstructor) -> ir::Type {        
-        let r#type = value.r#type;
-        let r#type = r#type.unwrap();
-        // let invocation = value.resolved_params;
-
-        if should_expose_alias_of_parametrized_type(&r#type) {
-            //        if invocation.from_alias {
-            //            GenerateParameterizedType(parent_type_kind, type, invocation.from_alias.partial_type_ctor.get());
-            //        } else {
-            //            GenerateParameterizedType(parent_type_kind, type, value);
-            //        }
-            //    GenerateExperimentalMaybeFromAlias(invocation);
-            //
-            //    return;
-            return ir::Type::PrimitiveType {
-                primitive_subtype: ir::PrimitiveSubtype::Bool,
-            };
-        }
-
-        self.generate_type(r#type)
+    fn generate_type_ctor(&self, value: ast::TypeConstructor) -> ir::Type {
+        self.generate_type(value.r#type.expect("type constructor is compiled"))
     }
 
     fn generate_literal(&self, value: ast::LiteralConstant) -> ir::Literal {
@@ -287,6 +388,75 @@ impl JSONGenerator {
         }
     }
 
+    /// Attributes as written in the source, with the doc comment first as a `doc` attribute.
+    /// Arguments are emitted as written since attribute arguments are not resolved.
+    fn generate_attributes(
+        &self,
+        attributes: &ast::AttributeList,
+        documentation: &Option<ast::Comment>,
+        span: &ast::Span,
+    ) -> Vec<ir::Attribute> {
+        let mut ir_attributes = vec![];
+
+        if let Some(documentation) = documentation {
+            // The text following each `///`, as it was written.
+            let value: String = documentation
+                .text
+                .lines()
+                .map(|line| if line.is_empty() { "\n".to_string() } else { format!(" {}\n", line) })
+                .collect();
+
+            ir_attributes.push(ir::Attribute {
+                name: ast::DOC_COMMENT_NAME.to_string(),
+                arguments: vec![ir::AttributeArg {
+                    name: "value".to_string(),
+                    value: ir::Constant::LiteralConstant {
+                        expression: value.lines().map(|line| format!("///{}\n", line)).collect(),
+                        literal: ir::Literal::StringLiteral { value: value.clone() },
+                        value,
+                    },
+                }],
+                location: self.generate_location(span.clone()),
+            });
+        }
+
+        for attribute in attributes.0.iter() {
+            ir_attributes.push(ir::Attribute {
+                name: attribute.name.decl_name(),
+                arguments: attribute
+                    .arguments
+                    .iter()
+                    .map(|arg| ir::AttributeArg {
+                        name: arg.name().decl_name(),
+                        value: self.generate_attribute_value(&arg.value),
+                    })
+                    .collect(),
+                location: self.generate_location(attribute.span.clone()),
+            });
+        }
+
+        ir_attributes
+    }
+
+    fn generate_attribute_value(&self, value: &ast::Constant) -> ir::Constant {
+        let expression = value.span().data.clone();
+
+        match value {
+            ast::Constant::Literal(literal) => ir::Constant::LiteralConstant {
+                value: match &literal.literal {
+                    ast::Literal::StringValue(value, _) => value.trim_matches('"').to_string(),
+                    _ => expression.clone(),
+                },
+                expression,
+                literal: self.generate_literal(literal.clone()),
+            },
+            _ => ir::Constant::BinaryOperator {
+                value: expression.clone(),
+                expression,
+            },
+        }
+    }
+
     fn generate_ordinal64(&self, value: ast::RawOrdinal64) -> u64 {
         value.value
     }
@@ -312,13 +482,18 @@ impl JSONGenerator {
 
     fn generate_const(&self, value: ast::Const) -> ir::Const {
         ir::Const {
+            maybe_attributes: self.generate_attributes(&value.attributes, &value.documentation, &value.span),
             name: self.generate_name(&value.name),
             location: self.generate_location(value.span),
-            r#type: self.generate_type_and_from_alias(TypeKind::Concrete, value.type_ctor),
+            r#type: self.generate_type_ctor(value.type_ctor),
             value: self.generate_constant(value.value),
         }
     }
 
+    fn generate_resourceness(&self, value: ast::Resourceness) -> Resourceness {
+        Resourceness(value == ast::Resourceness::Resource)
+    }
+
     fn generate_union(&self, value: ast::Union) -> ir::Union {
         let mut members = vec![];
 
@@ -328,10 +503,11 @@ impl JSONGenerator {
             if let Some(ref used) = member.maybe_used {
                 members.push(ir::UnionMember {
                     name: Some(self.generate_identifier(used.name.clone())),
-                    r#type: Some(self.generate_type_and_from_alias(TypeKind::Concrete, used.type_ctor.clone())),
+                    r#type: Some(self.generate_type_ctor(used.type_ctor.clone())),
                     //reserved: false,
                     ordinal: self.generate_ordinal64(member.ordinal.clone()),
                     max_out_of_line: 0,
+                    maybe_attributes: self.generate_attributes(&member.attributes, &member.documentation, &member.span),
                 })
             } else {
                 members.push(ir::UnionMember {
@@ -340,44 +516,107 @@ impl JSONGenerator {
                     //reserved: true,
                     ordinal: self.generate_ordinal64(member.ordinal.clone()),
                     max_out_of_line: 0,
+                    maybe_attributes: self.generate_attributes(&member.attributes, &member.documentation, &member.span),
                 })
             }
         }
 
         ir::Union {
+            maybe_attributes: self.generate_attributes(&value.attributes, &value.documentation, &value.span),
             members,
-            strict: true,
+            strict: value.strictness == ast::Strictness::Strict,
             name: self.generate_name(&value.name),
             location: self.generate_location(value.span),
-            resourceness: Resourceness(false),
+            resourceness: self.generate_resourceness(value.resourceness),
+        }
+    }
+
+    fn generate_table(&self, value: ast::Table) -> ir::Table {
+        let mut members = vec![];
+
+        for member in value.members.iter() {
+            let member = member.borrow();
+            let used = member.maybe_used.as_ref();
+
+            members.push(ir::TableMember {
+                name: used.map(|used| self.generate_identifier(used.name.clone())),
+                reserved: used.is_none(),
+                r#type: used.map(|used| self.generate_type_ctor(used.type_ctor.clone())),
+                ordinal: member.ordinal.value as i64,
+                maybe_default_value: None,
+                max_out_of_line: 0,
+                maybe_attributes: self.generate_attributes(&member.attributes, &member.documentation, &member.span),
+            })
+        }
+
+        ir::Table {
+            name: self.generate_name(&value.name),
+            location: self.generate_location(value.span.clone()),
+            resource: self.generate_resourceness(value.resourceness),
+            members,
+            type_shape_v2: self.shapes.borrow_mut().of_decl(&value.clone().into()),
+            maybe_attributes: self.generate_attributes(&value.attributes, &value.documentation, &value.span),
+        }
+    }
+
+    fn generate_bits(&self, value: ast::Bits) -> ir::Bits {
+        let mut members = vec![];
+
+        for member in value.members.iter() {
+            let member = member.borrow();
+
+            members.push(ir::BitsMember {
+                name: self.generate_identifier(member.name.clone()),
+                location: self.generate_location(member.span.clone()),
+                value: self.generate_constant(member.value.clone()),
+                maybe_attributes: self.generate_attributes(&member.attributes, &member.documentation, &member.span),
+            })
+        }
+
+        ir::Bits {
+            name: self.generate_name(&value.name),
+            location: self.generate_location(value.span.clone()),
+            maybe_attributes: self.generate_attributes(&value.attributes, &value.documentation, &value.span),
+            r#type: self.generate_type_ctor(value.subtype_ctor),
+            mask: value.mask.to_string(),
+            members,
+            is_strict: value.strictness == ast::Strictness::Strict,
         }
     }
 
     fn generate_struct(&self, value: ast::Struct) -> ir::Struct {
         let mut members = vec![];
+        let field_shapes = self.shapes.borrow_mut().struct_field_shapes(&value);
+        let type_shape_v2 = self.shapes.borrow_mut().of_decl(&value.clone().into());
 
-        for member in value.members {
+        for (member, field_shape_v2) in value.members.iter().zip(field_shapes) {
             let member = member.borrow();
 
             members.push(ir::StructMember {
                 name: self.generate_identifier(member.name.clone()),
                 location: self.generate_location(member.span.clone()),
-                r#type: self.generate_type_and_from_alias(TypeKind::Concrete, member.type_ctor.clone()),
-                field_shape_v2: self.generate_field_shape(),
+                r#type: self.generate_type_ctor(member.type_ctor.clone()),
+                field_shape_v2,
+                maybe_attributes: self.generate_attributes(&member.attributes, &member.documentation, &member.span),
                 // value: self.generate_constant(member.value.clone()),
             })
         }
 
+        let is_empty_success_struct = value
+            .name
+            .as_anonymous()
+            .is_some_and(|name| *name.provenance() == ast::NameProvenance::GeneratedEmptySuccessStruct);
+
         ir::Struct {
             name: self.generate_name(&value.name),
             naming_context: self.generate_naming_context(value.name),
+            maybe_attributes: Some(self.generate_attributes(&value.attributes, &value.documentation, &value.span)),
             location: self.generate_location(value.span),
-            resourceness: ir::Resourceness(false),
-            maybe_attributes: None,
-            is_empty_success_struct: false,
+            resourceness: self.generate_resourceness(value.resourceness),
+            is_empty_success_struct,
             members,
-            max_handles: None,
-            type_shape_v2: self.generate_type_shape(),
+            max_handles: Some(type_shape_v2.max_handles),
+            type_shape_v2,
         }
     }
 
@@ -388,63 +627,125 @@ impl JSONGenerator {
     fn generate_enum(&self, value: ast::Enum) -> ir::Enum {
         let mut members = vec![];
 
-        for member in value.members {
+        for member in value.members.iter() {
             let member = member.borrow();
 
             members.push(ir::EnumMember {
                 name: self.generate_identifier(member.name.clone()),
                 value: self.generate_constant(member.value.clone()),
+                maybe_attributes: self.generate_attributes(&member.attributes, &member.documentation, &member.span),
             })
         }
 
+        let r#type = match &value.subtype_ctor.r#type {
+            Some(ast::Type::Primitive(primitive)) => self.generate_primitive_subtype(&primitive.subtype),
+            _ => ir::PrimitiveSubtype::Uint32,
+        };
+
+        // Only flexible enums have a value standing in for unknown members.
+        let raw_unknown_value = match (value.strictness, &r#type) {
+            (ast::Strictness::Strict, _) => None,
+            (
+                _,
+                ir::PrimitiveSubtype::Int8
+                | ir::PrimitiveSubtype::Int16
+                | ir::PrimitiveSubtype::Int32
+                | ir::PrimitiveSubtype::Int64,
+            ) => Some(value.unknown_value_signed as u64),
+            _ => Some(value.unknown_value_unsigned),
+        };
+
         ir::Enum {
             name: self.generate_name(&value.name),
+            maybe_attributes: self.generate_attributes(&value.attributes, &value.documentation, &value.span),
             location: self.generate_location(value.span),
-            maybe_attributes: vec![],
-            r#type: ir::PrimitiveSubtype::Uint32,
+            r#type,
             members,
-            is_strict: false,
-            raw_unknown_value: None,
+            is_strict: value.strictness == ast::Strictness::Strict,
+            raw_unknown_value,
         }
     }
 
-    fn generate_protocol(&self, value: ast::Protocol) -> ir::Protocol {
-        let mut methods = vec![];
+    /// The success and error types of a method whose response is a result union.
+    fn generate_result_types(&self, response: &ast::TypeConstructor) -> (Option<ir::Type>, Option<ir::Type>) {
+        let Some(ast::Type::Identifier(identifier)) = &response.r#type else {
+            return (None, None);
+        };
+        let ast::Declaration::Union { decl } = &identifier.decl else {
+            return (None, None);
+        };
 
-        for method in value.methods {
-            let method = method.borrow();
+        let decl = decl.borrow();
+        let is_result_union = decl
+            .name
+            .as_anonymous()
+            .is_some_and(|name| *name.provenance() == ast::NameProvenance::GeneratedResultUnion);
+        if !is_result_union {
+            return (None, None);
+        }
 
-            let mut maybe_request_payload = None;
-            let mut maybe_response_payload = None;
+        let variant = |ordinal: u64| {
+            decl.members
+                .iter()
+                .map(|member| member.borrow())
+                .find(|member| member.ordinal.value == ordinal)
+                .and_then(|member| member.maybe_used.as_ref().map(|used| self.generate_type_ctor(used.type_ctor.clone())))
+        };
 
-            if let Some(typ) = &method.maybe_request {
-                maybe_request_payload = Some(self.generate_type_and_from_alias(TypeKind::RequestPayload, typ.clone()));
-            }
+        (variant(1), variant(2))
+    }
 
-            if let Some(typ) = &method.maybe_response {
-                maybe_response_payload =
-                    Some(self.generate_type_and_from_alias(TypeKind::ResponsePayload, typ.clone()));
-            }
+    fn generate_method(&self, method: &ast::ProtocolMethod) -> ir::ProtocolMethod {
+        let maybe_request_payload = method.maybe_request.clone().map(|typ| self.generate_type_ctor(typ));
+        let maybe_response_payload = method.maybe_response.clone().map(|typ| self.generate_type_ctor(typ));
+        let (success_type, error_type) = match &method.maybe_response {
+            Some(response) => self.generate_result_types(response),
+            None => (None, None),
+        };
 
-            methods.push(ir::ProtocolMethod {
-                name: self.generate_identifier(method.name.clone()),
-                ordinal: 0,
-                has_response: false,
-                has_request: false,
-                has_error: false,
-                kind: todo!(),
-                deprecated: todo!(),
-                strict: todo!(),
-                success_type: todo!(),
-                error_type: todo!(),
-                request_payload: maybe_request_payload,
-                response_payload:maybe_response_payload,
-            })
+        ir::ProtocolMethod {
+            name: self.generate_identifier(method.name.clone()),
+            ordinal: method.generated_ordinal64,
+            has_response: method.has_response,
+            has_request: method.has_request,
+            has_error: method.has_error,
+            kind: match (method.has_request, method.has_response) {
+                (true, false) => ir::MethodKind::Oneway,
+                (true, true) => ir::MethodKind::Twoway,
+                (false, _) => ir::MethodKind::Event,
+            },
+            deprecated: false,
+            strict: method.strictness == ast::Strictness::Strict,
+            success_type,
+            error_type,
+            request_payload: maybe_request_payload,
+            response_payload: maybe_response_payload,
+            maybe_attributes: self.generate_attributes(
+                &ast::AttributeList(method.attributes.clone()),
+                &method.documentation,
+                &method.span,
+            ),
         }
+    }
+
+    fn generate_protocol(&self, value: ast::Protocol) -> ir::Protocol {
+        // Composed methods are listed with the protocol's own, as if they had been declared
+        // in it.
+        let methods = value
+            .all_methods()
+            .into_iter()
+            .map(|(_, method)| self.generate_method(&method.borrow()))
+            .collect();
 
         ir::Protocol {
+            maybe_attributes: self.generate_attributes(&value.attributes, &value.documentation, &value.span),
             location: self.generate_location(value.span),
             name: self.generate_name(&value.name),
+            openness: match value.openness {
+                ast::Openness::Closed => ir::Openness::Closed,
+                ast::Openness::Ajar => ir::Openness::Ajar,
+                ast::Openness::Open => ir::Openness::Open,
+            },
             methods,
         }
     }
@@ -0,0 +1,333 @@
+//! Wire format shapes of compiled types, following the rules of the FIDL v2 wire format:
+//! out-of-line objects are 8 byte aligned, strings and vectors have 16 byte headers, tables
+//! and unions are 16 byte envelopes whose values are inlined when they fit in 4 bytes, and
+//! handles take 4 bytes. Counts saturate at `u32::MAX`, which also stands for "unbounded".
+
+use std::collections::BTreeSet;
+
+use midlgen::ir;
+
+use crate::ast;
+
+const ENVELOPE_SIZE: u32 = 8;
+
+fn align8(size: u32) -> u32 {
+    size.checked_add(7).map_or(u32::MAX, |size| size & !7)
+}
+
+fn align(offset: u32, alignment: u32) -> u32 {
+    let alignment = alignment.max(1);
+    offset.div_ceil(alignment).saturating_mul(alignment)
+}
+
+/// The number of out-of-line bytes an envelope needs to hold a value of `shape`.
+fn envelope_out_of_line(shape: &ir::TypeShape) -> u32 {
+    if shape.inline_size <= 4 {
+        shape.max_out_of_line
+    } else {
+        align8(shape.inline_size).saturating_add(shape.max_out_of_line)
+    }
+}
+
+fn primitive_shape(size: u32) -> ir::TypeShape {
+    ir::TypeShape {
+        inline_size: size,
+        alignment: size,
+        ..Default::default()
+    }
+}
+
+fn primitive_size(subtype: &ast::PrimitiveSubtype) -> u32 {
+    match subtype {
+        ast::PrimitiveSubtype::Bool | ast::PrimitiveSubtype::Int8 | ast::PrimitiveSubtype::Uint8 => 1,
+        ast::PrimitiveSubtype::Int16 | ast::PrimitiveSubtype::Uint16 => 2,
+        ast::PrimitiveSubtype::Int32 | ast::PrimitiveSubtype::Uint32 | ast::PrimitiveSubtype::Float32 => 4,
+        ast::PrimitiveSubtype::Int64 | ast::PrimitiveSubtype::Uint64 | ast::PrimitiveSubtype::Float64 => 8,
+    }
+}
+
+const HANDLE: ir::TypeShape = ir::TypeShape {
+    inline_size: 4,
+    alignment: 4,
+    depth: 0,
+    max_handles: 1,
+    max_out_of_line: 0,
+    has_padding: false,
+    has_flexible_envelope: false,
+};
+
+/// Computes shapes. Declarations currently being computed are tracked so that recursive
+/// types, which are only legal behind a box, an optional or a vector, terminate with
+/// unbounded depth and size.
+#[derive(Default)]
+pub(crate) struct TypeShapeCalculator {
+    visiting: BTreeSet<String>,
+}
+
+impl TypeShapeCalculator {
+    pub fn of_type(&mut self, r#type: &ast::Type) -> ir::TypeShape {
+        match r#type {
+            ast::Type::Primitive(primitive) => primitive_shape(primitive_size(&primitive.subtype)),
+            ast::Type::Internal(internal) => match internal.subtype {
+                // `framework_err` is an int32 enum.
+                ast::InternalSubtype::FrameworkErr => primitive_shape(4),
+            },
+            ast::Type::Handle(_) | ast::Type::TransportSide(_) | ast::Type::RequestType { .. } => HANDLE,
+            ast::Type::String(string) => {
+                let max_size = string.max_size();
+
+                ir::TypeShape {
+                    inline_size: 16,
+                    alignment: 8,
+                    depth: 1,
+                    max_handles: 0,
+                    max_out_of_line: align8(max_size),
+                    has_padding: true,
+                    has_flexible_envelope: false,
+                }
+            }
+            ast::Type::Vector(vector) => {
+                let element = self.of_type(&vector.element_type);
+                let count = vector.element_size();
+
+                ir::TypeShape {
+                    inline_size: 16,
+                    alignment: 8,
+                    depth: element.depth.saturating_add(1),
+                    max_handles: element.max_handles.saturating_mul(count),
+                    max_out_of_line: align8(element.inline_size.saturating_mul(count))
+                        .saturating_add(element.max_out_of_line.saturating_mul(count)),
+                    has_padding: element.has_padding || !element.inline_size.is_multiple_of(8),
+                    has_flexible_envelope: element.has_flexible_envelope,
+                }
+            }
+            ast::Type::Array(array) => {
+                let element = self.of_type(&array.element_type);
+                let count = array.size_value.as_size().map_or(u32::MAX, |count| count.min(u32::MAX as u64) as u32);
+
+                ir::TypeShape {
+                    inline_size: element.inline_size.saturating_mul(count),
+                    alignment: element.alignment,
+                    depth: element.depth,
+                    max_handles: element.max_handles.saturating_mul(count),
+                    max_out_of_line: element.max_out_of_line.saturating_mul(count),
+                    has_padding: element.has_padding,
+                    has_flexible_envelope: element.has_flexible_envelope,
+                }
+            }
+            ast::Type::Box(boxed) => {
+                let shape = self.of_type(&boxed.boxed_type);
+                self.boxed(shape)
+            }
+            ast::Type::Identifier(identifier) => {
+                let shape = self.of_decl(&identifier.decl);
+
+                match &identifier.decl {
+                    ast::Declaration::Struct { .. }
+                        if identifier.constraints.nullabilty() == ast::Nullability::Nullable =>
+                    {
+                        self.boxed(shape)
+                    }
+                    _ => shape,
+                }
+            }
+            ast::Type::UntypedNumeric(_) => ir::TypeShape::default(),
+        }
+    }
+
+    /// The shape of a pointer to an out-of-line object of `shape`.
+    fn boxed(&self, shape: ir::TypeShape) -> ir::TypeShape {
+        ir::TypeShape {
+            inline_size: 8,
+            alignment: 8,
+            depth: shape.depth.saturating_add(1),
+            max_handles: shape.max_handles,
+            max_out_of_line: align8(shape.inline_size).saturating_add(shape.max_out_of_line),
+            has_padding: shape.has_padding || !shape.inline_size.is_multiple_of(8),
+            has_flexible_envelope: shape.has_flexible_envelope,
+        }
+    }
+
+    pub fn of_decl(&mut self, decl: &ast::Declaration) -> ir::TypeShape {
+        let key = ast::name_flat_name(&decl.name());
+
+        if !self.visiting.insert(key.clone()) {
+            return ir::TypeShape {
+                inline_size: 0,
+                alignment: 8,
+                depth: u32::MAX,
+                max_handles: if decl_is_resource(decl) { u32::MAX } else { 0 },
+                max_out_of_line: u32::MAX,
+                has_padding: false,
+                has_flexible_envelope: false,
+            };
+        }
+
+        let shape = match decl {
+            ast::Declaration::Struct { decl } => self.of_struct(&decl.borrow()),
+            ast::Declaration::Table { decl } => self.of_table(&decl.borrow()),
+            ast::Declaration::Union { decl } => self.of_union(&decl.borrow()),
+            ast::Declaration::Enum { decl } => self.of_subtype(&decl.borrow().subtype_ctor),
+            ast::Declaration::Bits { decl } => self.of_subtype(&decl.borrow().subtype_ctor),
+            _ => ir::TypeShape::default(),
+        };
+
+        self.visiting.remove(&key);
+        shape
+    }
+
+    fn of_subtype(&mut self, subtype_ctor: &ast::TypeConstructor) -> ir::TypeShape {
+        subtype_ctor
+            .r#type
+            .as_ref()
+            .map_or_else(|| primitive_shape(4), |r#type| self.of_type(r#type))
+    }
+
+    fn of_struct(&mut self, decl: &ast::Struct) -> ir::TypeShape {
+        let member_shapes: Vec<_> = decl
+            .members
+            .iter()
+            .filter_map(|member| member.borrow().type_ctor.r#type.clone())
+            .map(|r#type| self.of_type(&r#type))
+            .collect();
+
+        let field_shapes = struct_field_shapes(&member_shapes);
+        let alignment = member_shapes.iter().map(|shape| shape.alignment).max().unwrap_or(1);
+        let inline_size = match (member_shapes.last(), field_shapes.last()) {
+            (Some(shape), Some(field)) => align(field.offset + shape.inline_size, alignment),
+            // Empty structs are encoded as a single zero byte.
+            _ => 1,
+        };
+
+        ir::TypeShape {
+            inline_size,
+            alignment,
+            depth: member_shapes.iter().map(|shape| shape.depth).max().unwrap_or(0),
+            max_handles: member_shapes
+                .iter()
+                .fold(0u32, |sum, shape| sum.saturating_add(shape.max_handles)),
+            max_out_of_line: member_shapes
+                .iter()
+                .fold(0u32, |sum, shape| sum.saturating_add(shape.max_out_of_line)),
+            has_padding: member_shapes.iter().any(|shape| shape.has_padding)
+                || field_shapes.iter().any(|field| field.padding != 0),
+            has_flexible_envelope: member_shapes.iter().any(|shape| shape.has_flexible_envelope),
+        }
+    }
+
+    /// The offset and trailing padding of each member of `decl`, in declaration order.
+    pub fn struct_field_shapes(&mut self, decl: &ast::Struct) -> Vec<ir::FieldShape> {
+        let member_shapes: Vec<_> = decl
+            .members
+            .iter()
+            .filter_map(|member| member.borrow().type_ctor.r#type.clone())
+            .map(|r#type| self.of_type(&r#type))
+            .collect();
+
+        struct_field_shapes(&member_shapes)
+    }
+
+    fn of_table(&mut self, decl: &ast::Table) -> ir::TypeShape {
+        let mut max_ordinal = 0;
+        let mut depth = 0u32;
+        let mut max_handles = 0u32;
+        let mut max_out_of_line = 0u32;
+        let mut has_padding = false;
+
+        for member in decl.members.iter() {
+            let member = member.borrow();
+            let Some(used) = member.maybe_used.as_ref() else {
+                continue;
+            };
+            let Some(r#type) = used.type_ctor.r#type.as_ref() else {
+                continue;
+            };
+
+            let shape = self.of_type(r#type);
+            max_ordinal = max_ordinal.max(member.ordinal.value as u32);
+            depth = depth.max(shape.depth);
+            max_handles = max_handles.saturating_add(shape.max_handles);
+            max_out_of_line = max_out_of_line.saturating_add(envelope_out_of_line(&shape));
+            has_padding |= shape.has_padding || !shape.inline_size.is_multiple_of(8);
+        }
+
+        ir::TypeShape {
+            inline_size: 16,
+            alignment: 8,
+            depth: depth.saturating_add(2),
+            max_handles,
+            max_out_of_line: max_out_of_line.saturating_add(max_ordinal.saturating_mul(ENVELOPE_SIZE)),
+            has_padding,
+            has_flexible_envelope: true,
+        }
+    }
+
+    fn of_union(&mut self, decl: &ast::Union) -> ir::TypeShape {
+        let mut depth = 0u32;
+        let mut max_handles = 0u32;
+        let mut max_out_of_line = 0u32;
+        let mut has_padding = false;
+        let mut has_flexible_envelope = decl.strictness == ast::Strictness::Flexible;
+
+        for member in decl.members.iter() {
+            let member = member.borrow();
+            let Some(r#type) = member.maybe_used.as_ref().and_then(|used| used.type_ctor.r#type.as_ref()) else {
+                continue;
+            };
+
+            let shape = self.of_type(r#type);
+            depth = depth.max(shape.depth);
+            max_handles = max_handles.max(shape.max_handles);
+            max_out_of_line = max_out_of_line.max(envelope_out_of_line(&shape));
+            has_padding |= shape.has_padding || !shape.inline_size.is_multiple_of(8);
+            has_flexible_envelope |= shape.has_flexible_envelope;
+        }
+
+        ir::TypeShape {
+            inline_size: 16,
+            alignment: 8,
+            depth: depth.saturating_add(1),
+            max_handles,
+            max_out_of_line,
+            has_padding,
+            has_flexible_envelope,
+        }
+    }
+}
+
+fn struct_field_shapes(member_shapes: &[ir::TypeShape]) -> Vec<ir::FieldShape> {
+    let alignment = member_shapes.iter().map(|shape| shape.alignment).max().unwrap_or(1);
+    let mut offsets = vec![];
+    let mut offset = 0;
+
+    for shape in member_shapes {
+        offset = align(offset, shape.alignment);
+        offsets.push(offset);
+        offset = offset.saturating_add(shape.inline_size);
+    }
+
+    let size = align(offset, alignment);
+
+    member_shapes
+        .iter()
+        .enumerate()
+        .map(|(idx, shape)| {
+            let end = offsets[idx].saturating_add(shape.inline_size);
+            let next = offsets.get(idx + 1).copied().unwrap_or(size);
+
+            ir::FieldShape {
+                offset: offsets[idx],
+                padding: next.saturating_sub(end),
+            }
+        })
+        .collect()
+}
+
+fn decl_is_resource(decl: &ast::Declaration) -> bool {
+    match decl {
+        ast::Declaration::Struct { decl } => decl.borrow().resourceness == ast::Resourceness::Resource,
+        ast::Declaration::Table { decl } => decl.borrow().resourceness == ast::Resourceness::Resource,
+        ast::Declaration::Union { decl } => decl.borrow().resourceness == ast::Resourceness::Resource,
+        _ => false,
+    }
+}
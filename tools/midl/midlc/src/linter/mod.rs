@@ -0,0 +1,644 @@
+//! Lint checks for MIDL sources.
+//!
+//! Like the formatter, the linter walks the pest parse tree rather than the compiled AST: it
+//! needs doc comments and attribute lists, and it should run on a library even when its
+//! dependencies are not at hand. All findings are reported as warnings.
+
+mod rules;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use pest::iterators::Pair;
+
+use crate::ast;
+use crate::consumption::{content_end, parse_library, Rule};
+use crate::diagnotics::{Diagnostics, DiagnosticsWarning};
+use crate::source_file::{SourceId, SourceManager};
+
+pub(crate) use rules::LintRule;
+use rules::*;
+
+/// Attribute used to silence rules for a library, declaration or member:
+/// `@allow_lint("snake-case-members, missing-doc-comment")`.
+const ALLOW_ATTRIBUTE: &str = "allow_lint";
+
+/// Run-wide lint configuration.
+#[derive(Debug, Default)]
+pub(crate) struct LintConfig {
+    allowed: BTreeSet<LintRule>,
+}
+
+impl LintConfig {
+    /// Disables a rule for every file of the run.
+    pub(crate) fn allow(&mut self, rule: LintRule) {
+        self.allowed.insert(rule);
+    }
+}
+
+/// Lints all files of a library. Parse failures are reported as errors, rule violations as
+/// warnings.
+pub(crate) fn lint(sources: &SourceManager<'_>, config: &LintConfig, diagnostics: &Diagnostics) {
+    let mut files = vec![];
+
+    for (source_id, source) in sources.iter() {
+        match parse_library(source.as_str(), source_id) {
+            Ok(library) => files.push((source_id, library)),
+            Err(err) => diagnostics.push_error(err),
+        }
+    }
+
+    let mut linter = Linter::new(config, diagnostics);
+
+    for (source_id, library) in &files {
+        linter.collect_layouts(*source_id, library.clone());
+    }
+
+    for (source_id, library) in files {
+        linter.library(source_id, library);
+    }
+}
+
+/// Returns the words the grammar matches as anonymous literals between two offsets, e.g.
+/// the `closed protocol` in front of a protocol name.
+fn keywords_between<'i>(pair: &Pair<'i, Rule>, start: usize, end: usize) -> Vec<&'i str> {
+    pair.get_input()[start..end].split_whitespace().collect()
+}
+
+fn find_child<'i>(pair: &Pair<'i, Rule>, rule: Rule) -> Option<Pair<'i, Rule>> {
+    pair.clone().into_inner().find(|p| p.as_rule() == rule)
+}
+
+fn is_inline_layout(rule: Rule) -> bool {
+    matches!(
+        rule,
+        Rule::inline_struct_layout
+            | Rule::inline_table_layout
+            | Rule::inline_union_layout
+            | Rule::inline_enum_layout
+            | Rule::inline_bits_layout
+    )
+}
+
+fn has_attribute(attributes: Option<&Pair<'_, Rule>>, name: &str) -> bool {
+    attributes.is_some_and(|list| {
+        list.clone()
+            .into_inner()
+            .filter_map(|attr| find_child(&attr, Rule::compound_identifier))
+            .any(|ident| ident.as_str() == name)
+    })
+}
+
+struct Linter<'a, 'src> {
+    config: &'a LintConfig,
+    diagnostics: &'a Diagnostics,
+    source_id: SourceId,
+
+    /// Top-level layout declarations of the library by name, used to follow payload types.
+    layouts: BTreeMap<String, (SourceId, Pair<'src, Rule>)>,
+
+    /// Rules allowed by the attributes of the enclosing file, declaration and member.
+    scopes: Vec<BTreeSet<LintRule>>,
+
+    /// Findings already reported, since a type can be reachable from several payloads.
+    reported: BTreeSet<(SourceId, usize, LintRule)>,
+}
+
+impl<'a, 'src> Linter<'a, 'src> {
+    fn new(config: &'a LintConfig, diagnostics: &'a Diagnostics) -> Self {
+        Self {
+            config,
+            diagnostics,
+            source_id: SourceId(0),
+            layouts: BTreeMap::new(),
+            scopes: vec![],
+            reported: BTreeSet::new(),
+        }
+    }
+
+    fn report(&mut self, rule: LintRule, pair: &Pair<'_, Rule>, message: String) {
+        if self.config.allowed.contains(&rule) || self.scopes.iter().any(|scope| scope.contains(&rule)) {
+            return;
+        }
+
+        let span = ast::Span::from_pest(pair.as_span(), self.source_id);
+        if !self.reported.insert((span.source, span.start, rule)) {
+            return;
+        }
+
+        self.diagnostics
+            .push_warning(DiagnosticsWarning::new(format!("{message} [{}]", rule.name()), span));
+    }
+
+    fn push_scope(&mut self, attributes: Option<&Pair<'_, Rule>>) {
+        let mut allowed = BTreeSet::new();
+
+        for attribute in attributes.into_iter().flat_map(|list| list.clone().into_inner()) {
+            if !find_child(&attribute, Rule::compound_identifier).is_some_and(|i| i.as_str() == ALLOW_ATTRIBUTE) {
+                continue;
+            }
+
+            let names = attribute
+                .clone()
+                .into_inner()
+                .flatten()
+                .filter(|p| p.as_rule() == Rule::string_content)
+                .flat_map(|p| p.as_str().split(','))
+                .map(str::trim)
+                .filter(|name| !name.is_empty());
+
+            for name in names {
+                match LintRule::from_name(name) {
+                    Some(rule) => {
+                        allowed.insert(rule);
+                    }
+                    None => self.diagnostics.push_warning(DiagnosticsWarning::new(
+                        format!("unknown lint rule `{name}`"),
+                        ast::Span::from_pest(attribute.as_span(), self.source_id),
+                    )),
+                }
+            }
+        }
+
+        self.scopes.push(allowed);
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn collect_layouts(&mut self, source_id: SourceId, library: Pair<'src, Rule>) {
+        for decl in library.into_inner().filter(|p| p.as_rule() == Rule::layout_declaration) {
+            if let Some(name) = find_child(&decl, Rule::identifier) {
+                self.layouts.insert(name.as_str().to_owned(), (source_id, decl));
+            }
+        }
+    }
+
+    /// Whether `prev` is a doc comment directly attached to `item`.
+    fn is_doc_comment_for(prev: &Pair<'_, Rule>, item: &Pair<'_, Rule>) -> bool {
+        if prev.as_rule() != Rule::comment_block {
+            return false;
+        }
+
+        let has_doc = prev.clone().into_inner().any(|p| p.as_rule() == Rule::doc_comment);
+        let gap = &item.get_input()[content_end(prev)..item.as_span().start()];
+
+        has_doc && gap.matches('\n').count() <= 1
+    }
+
+    fn check_case(&mut self, rule: LintRule, what: &str, name: &Pair<'_, Rule>) {
+        let text = name.as_str();
+
+        let (ok, case, suggestion) = match rule {
+            LintRule::UpperCamelCaseNames => (is_upper_camel_case(text), "UpperCamelCase", to_upper_camel_case(text)),
+            LintRule::SnakeCaseMembers => (is_snake_case(text), "snake_case", to_snake_case(text)),
+            LintRule::ScreamingCaseConstants => (
+                is_screaming_case(text),
+                "SCREAMING_SNAKE_CASE",
+                to_screaming_case(text),
+            ),
+            _ => unreachable!("{:?} is not a naming rule", rule),
+        };
+
+        if !ok {
+            self.report(
+                rule,
+                name,
+                format!("{what} `{text}` should be {case}, e.g. `{suggestion}`"),
+            );
+        }
+    }
+
+    fn library(&mut self, source_id: SourceId, library: Pair<'src, Rule>) {
+        debug_assert!(library.as_rule() == Rule::library);
+        self.source_id = source_id;
+
+        let items: Vec<_> = library
+            .into_inner()
+            .filter(|p| !matches!(p.as_rule(), Rule::empty_lines | Rule::EOI | Rule::CATCH_ALL))
+            .collect();
+
+        // Attributes on the library declaration apply to the whole file.
+        let file_attributes = items
+            .iter()
+            .find(|p| p.as_rule() == Rule::library_declaration)
+            .and_then(|p| find_child(p, Rule::block_attribute_list));
+        self.push_scope(file_attributes.as_ref());
+
+        let mut prev: Option<Pair<'src, Rule>> = None;
+        for item in items {
+            let documented = prev.as_ref().is_some_and(|prev| Self::is_doc_comment_for(prev, &item));
+
+            match item.as_rule() {
+                Rule::layout_declaration
+                | Rule::const_declaration
+                | Rule::alias_declaration
+                | Rule::resource_declaration
+                | Rule::protocol_declaration => self.declaration(item.clone(), documented),
+                _ => {}
+            }
+
+            prev = Some(item);
+        }
+
+        self.pop_scope();
+    }
+
+    fn declaration(&mut self, decl: Pair<'src, Rule>, documented: bool) {
+        let attributes = find_child(&decl, Rule::block_attribute_list);
+        self.push_scope(attributes.as_ref());
+
+        if let Some(name) = find_child(&decl, Rule::identifier) {
+            if !documented {
+                self.report(
+                    LintRule::MissingDocComment,
+                    &name,
+                    format!("declaration `{}` is missing a doc comment", name.as_str()),
+                );
+            }
+
+            match decl.as_rule() {
+                Rule::const_declaration => self.check_case(LintRule::ScreamingCaseConstants, "constant", &name),
+                _ => self.check_case(LintRule::UpperCamelCaseNames, "declaration", &name),
+            }
+        }
+
+        match decl.as_rule() {
+            Rule::layout_declaration => {
+                if let Some(layout) = decl.clone().into_inner().find(|p| is_inline_layout(p.as_rule())) {
+                    self.layout_members(layout);
+                }
+            }
+            Rule::resource_declaration => {
+                for property in decl.clone().into_inner().filter(|p| p.as_rule() == Rule::resource_property) {
+                    if let Some(name) = find_child(&property, Rule::identifier) {
+                        self.check_case(LintRule::SnakeCaseMembers, "resource property", &name);
+                    }
+                }
+            }
+            Rule::protocol_declaration => self.protocol(decl, attributes),
+            _ => {}
+        }
+
+        self.pop_scope();
+    }
+
+    fn layout_members(&mut self, layout: Pair<'src, Rule>) {
+        let is_value_layout = matches!(layout.as_rule(), Rule::inline_enum_layout | Rule::inline_bits_layout);
+
+        for member in layout.into_inner().filter(|p| {
+            matches!(
+                p.as_rule(),
+                Rule::value_layout_member | Rule::struct_layout_member | Rule::ordinal_layout_member
+            )
+        }) {
+            self.push_scope(find_child(&member, Rule::block_attribute_list).as_ref());
+
+            if let Some(name) = find_child(&member, Rule::identifier) {
+                if is_value_layout {
+                    self.check_case(LintRule::ScreamingCaseConstants, "member", &name);
+                } else {
+                    self.check_case(LintRule::SnakeCaseMembers, "member", &name);
+                }
+            }
+
+            if let Some(type_ctor) = find_child(&member, Rule::type_constructor) {
+                self.nested_layouts(type_ctor);
+            }
+
+            self.pop_scope();
+        }
+    }
+
+    /// Checks the members of anonymous layouts declared inline in a type constructor.
+    fn nested_layouts(&mut self, type_ctor: Pair<'src, Rule>) {
+        for current in type_ctor.into_inner() {
+            match current.as_rule() {
+                rule if is_inline_layout(rule) => self.layout_members(current),
+                Rule::layout_parameters => {
+                    for param in current.into_inner().filter(|p| p.as_rule() == Rule::type_constructor) {
+                        self.nested_layouts(param);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn protocol(&mut self, decl: Pair<'src, Rule>, attributes: Option<Pair<'src, Rule>>) {
+        let Some(protocol_name) = find_child(&decl, Rule::identifier) else {
+            return;
+        };
+
+        let discoverable = has_attribute(attributes.as_ref(), "discoverable");
+
+        if discoverable && find_child(&decl, Rule::protocol_openness).is_some_and(|o| o.as_str() == "closed") {
+            self.report(
+                LintRule::DiscoverableNotFlexible,
+                &protocol_name,
+                format!(
+                    "discoverable protocol `{}` is closed and cannot evolve with flexible methods; consider `ajar`",
+                    protocol_name.as_str()
+                ),
+            );
+        }
+
+        let mut prev: Option<Pair<'src, Rule>> = None;
+        for member in decl.clone().into_inner() {
+            match member.as_rule() {
+                Rule::protocol_method | Rule::protocol_event => {
+                    let documented = prev.as_ref().is_some_and(|prev| Self::is_doc_comment_for(prev, &member));
+                    self.method(&protocol_name, member.clone(), discoverable, documented);
+                }
+                Rule::comment_block | Rule::protocol_compose => {}
+                _ => continue,
+            }
+
+            prev = Some(member);
+        }
+    }
+
+    fn method(&mut self, protocol_name: &Pair<'src, Rule>, method: Pair<'src, Rule>, discoverable: bool, documented: bool) {
+        let attributes = find_child(&method, Rule::block_attribute_list);
+        self.push_scope(attributes.as_ref());
+
+        if let Some(name) = find_child(&method, Rule::identifier) {
+            let qualified = format!("{}.{}", protocol_name.as_str(), name.as_str());
+
+            if !documented {
+                self.report(
+                    LintRule::MissingDocComment,
+                    &name,
+                    format!("method `{qualified}` is missing a doc comment"),
+                );
+            }
+
+            self.check_case(LintRule::UpperCamelCaseNames, "method", &name);
+
+            let keywords_start = attributes.as_ref().map_or(method.as_span().start(), |a| a.as_span().end());
+            if discoverable && keywords_between(&method, keywords_start, name.as_span().start()).contains(&"strict") {
+                self.report(
+                    LintRule::DiscoverableNotFlexible,
+                    &name,
+                    format!("method `{qualified}` of a discoverable protocol should be flexible"),
+                );
+            }
+
+            let parameter_lists = method.clone().into_inner().flat_map(|p| match p.as_rule() {
+                Rule::parameter_list => vec![p],
+                Rule::protocol_request | Rule::protocol_response => {
+                    p.into_inner().filter(|p| p.as_rule() == Rule::parameter_list).collect()
+                }
+                _ => vec![],
+            });
+
+            let mut visited = BTreeSet::new();
+            for parameter_list in parameter_lists.collect::<Vec<_>>() {
+                if let Some(payload) = find_child(&parameter_list, Rule::type_constructor) {
+                    self.nested_layouts(payload.clone());
+                    self.payload_bounds(payload, &qualified, &mut visited);
+                }
+            }
+        }
+
+        self.pop_scope();
+    }
+
+    /// Reports vectors and strings without a size bound that are reachable from a method
+    /// payload, following references to layouts declared in the same library.
+    fn payload_bounds(&mut self, type_ctor: Pair<'src, Rule>, method: &str, visited: &mut BTreeSet<String>) {
+        let mut layout = None;
+        let mut constraints = vec![];
+
+        for current in type_ctor.clone().into_inner() {
+            match current.as_rule() {
+                Rule::compound_identifier => layout = Some(current.as_str().split_whitespace().collect::<String>()),
+                rule if is_inline_layout(rule) => self.payload_members(current, method, visited),
+                Rule::layout_parameters => {
+                    for param in current.into_inner().filter(|p| p.as_rule() == Rule::type_constructor) {
+                        self.payload_bounds(param, method, visited);
+                    }
+                }
+                Rule::type_constraints => {
+                    constraints = current.into_inner().map(|c| c.as_str().trim().to_owned()).collect();
+                }
+                _ => {}
+            }
+        }
+
+        match layout.as_deref() {
+            Some(kind @ ("vector" | "string")) => {
+                if constraints.iter().all(|c| c == "optional") {
+                    self.report(
+                        LintRule::UnboundedPayload,
+                        &type_ctor,
+                        format!("{kind} reachable from the payload of `{method}` has no size bound"),
+                    );
+                }
+            }
+            Some(name) if self.layouts.contains_key(name) && visited.insert(name.to_owned()) => {
+                let (source_id, decl) = self.layouts[name].clone();
+                let caller_source_id = std::mem::replace(&mut self.source_id, source_id);
+                self.push_scope(find_child(&decl, Rule::block_attribute_list).as_ref());
+
+                if let Some(layout) = decl.into_inner().find(|p| is_inline_layout(p.as_rule())) {
+                    self.payload_members(layout, method, visited);
+                }
+
+                self.pop_scope();
+                self.source_id = caller_source_id;
+            }
+            _ => {}
+        }
+    }
+
+    fn payload_members(&mut self, layout: Pair<'src, Rule>, method: &str, visited: &mut BTreeSet<String>) {
+        for member in layout.into_inner() {
+            if let Some(type_ctor) = find_child(&member, Rule::type_constructor) {
+                self.push_scope(find_child(&member, Rule::block_attribute_list).as_ref());
+                self.payload_bounds(type_ctor, method, visited);
+                self.pop_scope();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_file::SourceFile;
+
+    /// Lints `source` and returns the messages of all warnings.
+    fn lint_source(source: &str, config: &LintConfig) -> Vec<String> {
+        let sources = SourceManager::from(vec![SourceFile::from_str("test.midl", source)]);
+        let diagnostics = Diagnostics::new();
+
+        lint(&sources, config, &diagnostics);
+
+        assert!(!diagnostics.has_errors(), "{:?}", diagnostics.errors());
+        let warnings = diagnostics.warnings().iter().map(|w| w.message().to_owned()).collect();
+        warnings
+    }
+
+    /// Lints `declarations` with every rule but `rule` allowed.
+    fn findings(rule: LintRule, declarations: &str) -> Vec<String> {
+        let mut config = LintConfig::default();
+        for other in LintRule::ALL.into_iter().filter(|other| *other != rule) {
+            config.allow(other);
+        }
+
+        let warnings = lint_source(&format!("library test.lint;\n\n{declarations}"), &config);
+        for warning in &warnings {
+            assert!(warning.ends_with(&format!("[{}]", rule.name())), "{warning}");
+        }
+        warnings
+    }
+
+    #[test]
+    fn upper_camel_case_names() {
+        let warnings = findings(
+            LintRule::UpperCamelCaseNames,
+            "type my_struct = struct {};\nclosed protocol Store {\n    strict get_entry();\n};\n",
+        );
+        assert_eq!(
+            warnings,
+            [
+                "declaration `my_struct` should be UpperCamelCase, e.g. `MyStruct` [upper-camel-case-names]",
+                "method `get_entry` should be UpperCamelCase, e.g. `GetEntry` [upper-camel-case-names]",
+            ]
+        );
+
+        let warnings = findings(
+            LintRule::UpperCamelCaseNames,
+            "type MyStruct = struct {};\nclosed protocol Store {\n    strict GetEntry();\n};\n",
+        );
+        assert_eq!(warnings, Vec::<String>::new());
+    }
+
+    #[test]
+    fn snake_case_members() {
+        let warnings = findings(
+            LintRule::SnakeCaseMembers,
+            "type Entry = struct {\n    EntryName string;\n};\n",
+        );
+        assert_eq!(
+            warnings,
+            ["member `EntryName` should be snake_case, e.g. `entry_name` [snake-case-members]"]
+        );
+
+        let warnings = findings(
+            LintRule::SnakeCaseMembers,
+            "type Entry = table {\n    1: entry_name string;\n};\n",
+        );
+        assert_eq!(warnings, Vec::<String>::new());
+    }
+
+    #[test]
+    fn screaming_case_constants() {
+        let warnings = findings(
+            LintRule::ScreamingCaseConstants,
+            "const max_size uint32 = 10;\ntype Color = enum {\n    Red = 1;\n};\n",
+        );
+        assert_eq!(
+            warnings,
+            [
+                "constant `max_size` should be SCREAMING_SNAKE_CASE, e.g. `MAX_SIZE` [screaming-case-constants]",
+                "member `Red` should be SCREAMING_SNAKE_CASE, e.g. `RED` [screaming-case-constants]",
+            ]
+        );
+
+        let warnings = findings(
+            LintRule::ScreamingCaseConstants,
+            "const MAX_SIZE uint32 = 10;\ntype Color = enum {\n    RED = 1;\n};\n",
+        );
+        assert_eq!(warnings, Vec::<String>::new());
+    }
+
+    #[test]
+    fn missing_doc_comment() {
+        let warnings = findings(
+            LintRule::MissingDocComment,
+            "type Entry = struct {};\n\n/// A store.\nclosed protocol Store {\n    strict Get();\n};\n",
+        );
+        assert_eq!(
+            warnings,
+            [
+                "declaration `Entry` is missing a doc comment [missing-doc-comment]",
+                "method `Store.Get` is missing a doc comment [missing-doc-comment]",
+            ]
+        );
+
+        let warnings = findings(
+            LintRule::MissingDocComment,
+            "/// An entry.\ntype Entry = struct {};\n\n/// A store.\nclosed protocol Store {\n    /// Gets it.\n    strict Get();\n};\n",
+        );
+        assert_eq!(warnings, Vec::<String>::new());
+    }
+
+    #[test]
+    fn unbounded_payload() {
+        let warnings = findings(
+            LintRule::UnboundedPayload,
+            "type Entry = struct {\n    data vector<uint8>;\n};\n\nclosed protocol Store {\n    strict Get() -> (struct {\n        entry Entry;\n    });\n};\n",
+        );
+        assert_eq!(
+            warnings,
+            ["vector reachable from the payload of `Store.Get` has no size bound [unbounded-payload]"]
+        );
+
+        let warnings = findings(
+            LintRule::UnboundedPayload,
+            "type Entry = struct {\n    data vector<uint8>:64;\n    name string:optional;\n};\n\nclosed protocol Store {\n    strict Put(struct {\n        entry Entry;\n    });\n};\n",
+        );
+        assert_eq!(
+            warnings,
+            ["string reachable from the payload of `Store.Put` has no size bound [unbounded-payload]"]
+        );
+
+        let warnings = findings(
+            LintRule::UnboundedPayload,
+            "type Entry = struct {\n    data vector<uint8>:64;\n    name string:<32, optional>;\n};\n\nclosed protocol Store {\n    strict Get() -> (struct {\n        entry Entry;\n    });\n};\n",
+        );
+        assert_eq!(warnings, Vec::<String>::new());
+    }
+
+    #[test]
+    fn discoverable_not_flexible() {
+        let warnings = findings(
+            LintRule::DiscoverableNotFlexible,
+            "@discoverable\nclosed protocol Store {\n    strict Get();\n};\n\n@discoverable\nopen protocol Cache {\n    strict Get();\n};\n",
+        );
+        assert_eq!(
+            warnings,
+            [
+                "discoverable protocol `Store` is closed and cannot evolve with flexible methods; consider `ajar` [discoverable-not-flexible]",
+                "method `Store.Get` of a discoverable protocol should be flexible [discoverable-not-flexible]",
+                "method `Cache.Get` of a discoverable protocol should be flexible [discoverable-not-flexible]",
+            ]
+        );
+
+        let warnings = findings(
+            LintRule::DiscoverableNotFlexible,
+            "@discoverable\nopen protocol Cache {\n    flexible Get();\n};\n\nclosed protocol Store {\n    strict Get();\n};\n",
+        );
+        assert_eq!(warnings, Vec::<String>::new());
+    }
+
+    #[test]
+    fn allow_lint_attribute() {
+        let warnings = lint_source(
+            "@allow_lint(\"missing-doc-comment\")\nlibrary test.lint;\n\ntype Entry = struct {\n    @allow_lint(\"snake-case-members\")\n    EntryName uint32;\n    OtherName uint32;\n};\n",
+            &LintConfig::default(),
+        );
+        assert_eq!(
+            warnings,
+            ["member `OtherName` should be snake_case, e.g. `other_name` [snake-case-members]"]
+        );
+
+        let warnings = lint_source(
+            "@allow_lint(\"no-such-rule\")\nlibrary test.lint;\n",
+            &LintConfig::default(),
+        );
+        assert_eq!(warnings, ["unknown lint rule `no-such-rule`"]);
+    }
+}
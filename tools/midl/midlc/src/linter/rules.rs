@@ -0,0 +1,75 @@
+use convert_case::{Case, Casing};
+
+/// A single lint check. Every rule can be disabled for a whole run with `--allow`, or for a
+/// declaration, member or file with an `@allow_lint("rule-name")` attribute.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum LintRule {
+    /// Type, protocol, alias, resource and method names must be UpperCamelCase.
+    UpperCamelCaseNames,
+    /// Struct, table and union members must be snake_case.
+    SnakeCaseMembers,
+    /// Constants and enum/bits members must be SCREAMING_SNAKE_CASE.
+    ScreamingCaseConstants,
+    /// Top-level declarations and protocol methods must be documented with `///`.
+    MissingDocComment,
+    /// Vectors and strings reachable from a protocol payload must declare a size bound.
+    UnboundedPayload,
+    /// Protocols marked `@discoverable` must be able to evolve with flexible methods.
+    DiscoverableNotFlexible,
+}
+
+impl LintRule {
+    pub(crate) const ALL: [LintRule; 6] = [
+        LintRule::UpperCamelCaseNames,
+        LintRule::SnakeCaseMembers,
+        LintRule::ScreamingCaseConstants,
+        LintRule::MissingDocComment,
+        LintRule::UnboundedPayload,
+        LintRule::DiscoverableNotFlexible,
+    ];
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            LintRule::UpperCamelCaseNames => "upper-camel-case-names",
+            LintRule::SnakeCaseMembers => "snake-case-members",
+            LintRule::ScreamingCaseConstants => "screaming-case-constants",
+            LintRule::MissingDocComment => "missing-doc-comment",
+            LintRule::UnboundedPayload => "unbounded-payload",
+            LintRule::DiscoverableNotFlexible => "discoverable-not-flexible",
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<LintRule> {
+        LintRule::ALL.into_iter().find(|rule| rule.name() == name)
+    }
+}
+
+pub(crate) fn is_upper_camel_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase()) && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+pub(crate) fn is_snake_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+pub(crate) fn is_screaming_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+pub(crate) fn to_upper_camel_case(name: &str) -> String {
+    name.to_case(Case::UpperCamel)
+}
+
+pub(crate) fn to_snake_case(name: &str) -> String {
+    name.to_case(Case::Snake)
+}
+
+pub(crate) fn to_screaming_case(name: &str) -> String {
+    name.to_case(Case::ScreamingSnake)
+}
@@ -0,0 +1,506 @@
+use pest::iterators::Pair;
+
+use crate::ast::Span;
+use crate::consumption::{content_end, parse_library, Rule};
+use crate::diagnotics::{Diagnostics, DiagnosticsError};
+use crate::source_file::SourceId;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum SymbolKind {
+    Const,
+    Alias,
+    Struct,
+    Table,
+    Union,
+    Enum,
+    Bits,
+    Resource,
+    Protocol,
+    Field,
+    Value,
+    Property,
+    Method,
+    Event,
+}
+
+impl SymbolKind {
+    fn of_layout(rule: Rule) -> Option<SymbolKind> {
+        match rule {
+            Rule::inline_struct_layout => Some(SymbolKind::Struct),
+            Rule::inline_table_layout => Some(SymbolKind::Table),
+            Rule::inline_union_layout => Some(SymbolKind::Union),
+            Rule::inline_enum_layout => Some(SymbolKind::Enum),
+            Rule::inline_bits_layout => Some(SymbolKind::Bits),
+            _ => None,
+        }
+    }
+}
+
+/// A declaration or a member of one.
+#[derive(Debug, Clone)]
+pub(crate) struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+
+    /// The whole item, including its doc comment and attributes.
+    pub span: Span,
+    pub name_span: Span,
+
+    pub doc: Option<String>,
+
+    /// The item as written, without attributes, body and trailing `;`, e.g.
+    /// `type Color = strict enum : uint32` or `1: name string:MAX_NAME`.
+    pub signature: String,
+
+    /// The member type, alias target, const type or layout subtype.
+    pub type_ctor: Option<TypeCtor>,
+
+    /// The value of a constant or an enum/bits member, as written.
+    pub value: Option<String>,
+
+    pub members: Vec<Symbol>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Layout {
+    Named(String),
+    Inline {
+        kind: SymbolKind,
+        subtype: Option<Box<TypeCtor>>,
+        members: Vec<Symbol>,
+    },
+}
+
+/// A parameter of a type constructor. `array<T, N>` parses `N` as a type constructor when
+/// it is a named constant, so the distinction is only made for literals.
+#[derive(Debug, Clone)]
+pub(crate) enum TypeParam {
+    Type(TypeCtor),
+    Constant(String),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TypeCtor {
+    pub layout: Layout,
+    pub parameters: Vec<TypeParam>,
+    pub constraints: Vec<String>,
+}
+
+impl TypeCtor {
+    pub fn is_optional(&self) -> bool {
+        self.constraints.iter().any(|c| c == "optional")
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum ReferenceKind {
+    /// The layout of a type constructor; must resolve.
+    Type,
+    /// A composed protocol; must resolve.
+    Compose,
+    /// An identifier in a constant expression or constraint. These also name handle
+    /// subtypes and rights, which are not declarations, so they are resolved best-effort.
+    Constant,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Reference {
+    pub name: String,
+    pub kind: ReferenceKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Import {
+    pub library: String,
+    pub alias: Option<String>,
+    pub span: Span,
+}
+
+/// What the language server knows about one file: its declarations and every name it
+/// refers to. Like the formatter and the linter, this is built from the parse tree, so it is
+/// available even when the library does not compile.
+#[derive(Debug, Default)]
+pub(crate) struct FileIndex {
+    pub library: Option<(String, Span)>,
+    pub imports: Vec<Import>,
+    pub symbols: Vec<Symbol>,
+    pub references: Vec<Reference>,
+}
+
+impl FileIndex {
+    /// Indexes a file. Parse failures and unrecognized syntax are reported to `diagnostics`.
+    pub fn build(source: &str, source_id: SourceId, diagnostics: &Diagnostics) -> FileIndex {
+        let mut builder = Builder {
+            source_id,
+            index: FileIndex::default(),
+        };
+
+        match parse_library(source, source_id) {
+            Ok(library) => builder.library(library, diagnostics),
+            Err(err) => diagnostics.push_error(err),
+        }
+
+        builder.index
+    }
+}
+
+fn find_child<'i>(pair: &Pair<'i, Rule>, rule: Rule) -> Option<Pair<'i, Rule>> {
+    pair.clone().into_inner().find(|p| p.as_rule() == rule)
+}
+
+fn compact(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Joins the `///` lines of a comment block, or None if it has none.
+fn doc_text(comment_block: &Pair<'_, Rule>) -> Option<String> {
+    let lines: Vec<_> = comment_block
+        .clone()
+        .into_inner()
+        .filter(|p| p.as_rule() == Rule::doc_comment)
+        .filter_map(|p| find_child(&p, Rule::doc_content))
+        .map(|content| {
+            let text = content.as_str();
+            text.strip_prefix(' ').unwrap_or(text).trim_end().to_owned()
+        })
+        .collect();
+
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// The doc comment of `item`, if `prev` is a comment block directly in front of it.
+fn doc_before(prev: Option<&Pair<'_, Rule>>, item: &Pair<'_, Rule>) -> Option<String> {
+    let prev = prev.filter(|p| p.as_rule() == Rule::comment_block)?;
+    let gap = &item.get_input()[content_end(prev)..item.as_span().start()];
+
+    if gap.matches('\n').count() > 1 {
+        return None;
+    }
+
+    doc_text(prev)
+}
+
+struct Builder {
+    source_id: SourceId,
+    index: FileIndex,
+}
+
+impl Builder {
+    fn span(&self, pair: &Pair<'_, Rule>) -> Span {
+        Span::from_pest(pair.as_span(), self.source_id)
+    }
+
+    /// The text of an item from after its comments and attributes up to `end`, with the
+    /// whitespace collapsed.
+    fn signature(&self, item: &Pair<'_, Rule>, end: usize) -> String {
+        let start = item
+            .clone()
+            .into_inner()
+            .take_while(|p| matches!(p.as_rule(), Rule::comment_block | Rule::block_attribute_list))
+            .last()
+            .map_or(item.as_span().start(), |p| p.as_span().end());
+
+        compact(item.get_input()[start..end.max(start)].trim_end().trim_end_matches(';'))
+    }
+
+    /// The end of an item, excluding a trailing comment.
+    fn item_end(item: &Pair<'_, Rule>) -> usize {
+        find_child(item, Rule::trailing_comment).map_or(content_end(item), |c| c.as_span().start())
+    }
+
+    /// The offset of the first `{` of an item, which ends the signature of declarations.
+    fn body_start(item: &Pair<'_, Rule>) -> usize {
+        item.clone()
+            .into_inner()
+            .flatten()
+            .find(|p| p.as_rule() == Rule::BLOCK_OPEN)
+            .map_or(content_end(item), |p| p.as_span().start())
+    }
+
+    fn library(&mut self, library: Pair<'_, Rule>, diagnostics: &Diagnostics) {
+        let mut prev: Option<Pair<'_, Rule>> = None;
+
+        for item in library.into_inner() {
+            let doc = doc_before(prev.as_ref(), &item);
+
+            match item.as_rule() {
+                Rule::library_declaration => {
+                    if let Some(name) = find_child(&item, Rule::compound_identifier) {
+                        self.index.library = Some((compact_identifier(name.as_str()), self.span(&name)));
+                    }
+                }
+                Rule::import_declaration => {
+                    if let Some(name) = find_child(&item, Rule::compound_identifier) {
+                        let alias = find_child(&item, Rule::import_alias)
+                            .and_then(|alias| find_child(&alias, Rule::identifier))
+                            .map(|alias| alias.as_str().to_owned());
+
+                        self.index.imports.push(Import {
+                            library: compact_identifier(name.as_str()),
+                            alias,
+                            span: self.span(&name),
+                        });
+                    }
+                }
+                Rule::layout_declaration
+                | Rule::const_declaration
+                | Rule::alias_declaration
+                | Rule::resource_declaration
+                | Rule::protocol_declaration => {
+                    if let Some(symbol) = self.declaration(item.clone(), doc) {
+                        self.index.symbols.push(symbol);
+                    }
+                }
+                Rule::CATCH_ALL => diagnostics.push_error(DiagnosticsError::new(
+                    "unrecognized syntax",
+                    Span::new(item.as_span().start(), content_end(&item), self.source_id),
+                )),
+                _ => {}
+            }
+
+            if !matches!(item.as_rule(), Rule::empty_lines) {
+                prev = Some(item);
+            }
+        }
+    }
+
+    fn declaration(&mut self, decl: Pair<'_, Rule>, doc: Option<String>) -> Option<Symbol> {
+        let name = find_child(&decl, Rule::identifier)?;
+
+        let mut symbol = Symbol {
+            name: name.as_str().to_owned(),
+            kind: SymbolKind::Const,
+            span: self.span(&decl),
+            name_span: self.span(&name),
+            doc,
+            signature: String::new(),
+            type_ctor: None,
+            value: None,
+            members: vec![],
+        };
+
+        match decl.as_rule() {
+            Rule::layout_declaration => {
+                symbol.signature = self.signature(&decl, Self::body_start(&decl));
+
+                let layout = decl
+                    .clone()
+                    .into_inner()
+                    .find(|p| SymbolKind::of_layout(p.as_rule()).is_some())?;
+                let Layout::Inline { kind, subtype, members } = self.inline_layout(layout) else {
+                    unreachable!();
+                };
+
+                symbol.kind = kind;
+                symbol.type_ctor = subtype.map(|subtype| *subtype);
+                symbol.members = members;
+            }
+            Rule::const_declaration => {
+                symbol.signature = self.signature(&decl, content_end(&decl));
+                symbol.type_ctor = find_child(&decl, Rule::type_constructor).map(|t| self.type_ctor(t));
+                symbol.value = find_child(&decl, Rule::constant).map(|c| self.constant(c));
+            }
+            Rule::alias_declaration => {
+                symbol.kind = SymbolKind::Alias;
+                symbol.signature = self.signature(&decl, content_end(&decl));
+                symbol.type_ctor = find_child(&decl, Rule::type_constructor).map(|t| self.type_ctor(t));
+            }
+            Rule::resource_declaration => {
+                symbol.kind = SymbolKind::Resource;
+                symbol.signature = self.signature(&decl, Self::body_start(&decl));
+                symbol.type_ctor = find_child(&decl, Rule::layout_subtype)
+                    .and_then(|s| find_child(&s, Rule::type_constructor))
+                    .map(|t| self.type_ctor(t));
+                symbol.members = self.members(decl, SymbolKind::Property);
+            }
+            Rule::protocol_declaration => {
+                symbol.kind = SymbolKind::Protocol;
+                symbol.signature = self.signature(&decl, Self::body_start(&decl));
+                symbol.members = self.protocol_members(decl);
+            }
+            _ => return None,
+        }
+
+        Some(symbol)
+    }
+
+    fn inline_layout(&mut self, layout: Pair<'_, Rule>) -> Layout {
+        let kind = SymbolKind::of_layout(layout.as_rule()).expect("an inline layout");
+
+        let subtype = find_child(&layout, Rule::layout_subtype)
+            .and_then(|s| find_child(&s, Rule::type_constructor))
+            .map(|t| Box::new(self.type_ctor(t)));
+
+        let member_kind = match kind {
+            SymbolKind::Enum | SymbolKind::Bits => SymbolKind::Value,
+            _ => SymbolKind::Field,
+        };
+
+        Layout::Inline {
+            kind,
+            subtype,
+            members: self.members(layout, member_kind),
+        }
+    }
+
+    /// Members of layouts and resources. Their doc comments are part of the member pair.
+    fn members(&mut self, parent: Pair<'_, Rule>, kind: SymbolKind) -> Vec<Symbol> {
+        let mut members = vec![];
+        let mut prev: Option<Pair<'_, Rule>> = None;
+
+        for member in parent.into_inner() {
+            let rule = member.as_rule();
+
+            if matches!(
+                rule,
+                Rule::value_layout_member
+                    | Rule::struct_layout_member
+                    | Rule::ordinal_layout_member
+                    | Rule::resource_property
+            ) {
+                // Reserved ordinals have no name.
+                if let Some(name) = find_child(&member, Rule::identifier) {
+                    let doc = find_child(&member, Rule::comment_block)
+                        .and_then(|c| doc_text(&c))
+                        .or_else(|| doc_before(prev.as_ref(), &member));
+
+                    members.push(Symbol {
+                        name: name.as_str().to_owned(),
+                        kind,
+                        span: self.span(&member),
+                        name_span: self.span(&name),
+                        doc,
+                        signature: self.signature(&member, Self::item_end(&member)),
+                        type_ctor: find_child(&member, Rule::type_constructor).map(|t| self.type_ctor(t)),
+                        value: find_child(&member, Rule::constant).map(|c| self.constant(c)),
+                        members: vec![],
+                    });
+                }
+            }
+
+            if !matches!(rule, Rule::empty_lines) {
+                prev = Some(member);
+            }
+        }
+
+        members
+    }
+
+    fn protocol_members(&mut self, protocol: Pair<'_, Rule>) -> Vec<Symbol> {
+        let mut members = vec![];
+        let mut prev: Option<Pair<'_, Rule>> = None;
+
+        for member in protocol.into_inner() {
+            let kind = match member.as_rule() {
+                Rule::protocol_method => Some(SymbolKind::Method),
+                Rule::protocol_event => Some(SymbolKind::Event),
+                Rule::protocol_compose => {
+                    if let Some(name) = find_child(&member, Rule::compound_identifier) {
+                        self.reference(&name, ReferenceKind::Compose);
+                    }
+                    None
+                }
+                _ => None,
+            };
+
+            if let Some(kind) = kind {
+                if let Some(name) = find_child(&member, Rule::identifier) {
+                    let doc = doc_before(prev.as_ref(), &member);
+
+                    for payload in member
+                        .clone()
+                        .into_inner()
+                        .flatten()
+                        .filter(|p| p.as_rule() == Rule::parameter_list || p.as_rule() == Rule::protocol_response)
+                        .flat_map(|p| p.into_inner())
+                        .filter(|p| p.as_rule() == Rule::type_constructor)
+                    {
+                        self.type_ctor(payload);
+                    }
+
+                    members.push(Symbol {
+                        name: name.as_str().to_owned(),
+                        kind,
+                        span: self.span(&member),
+                        name_span: self.span(&name),
+                        doc,
+                        signature: self.signature(&member, content_end(&member)),
+                        type_ctor: None,
+                        value: None,
+                        members: vec![],
+                    });
+                }
+            }
+
+            if !matches!(member.as_rule(), Rule::empty_lines) {
+                prev = Some(member);
+            }
+        }
+
+        members
+    }
+
+    fn type_ctor(&mut self, type_ctor: Pair<'_, Rule>) -> TypeCtor {
+        let mut layout = Layout::Named(String::new());
+        let mut parameters = vec![];
+        let mut constraints = vec![];
+
+        for current in type_ctor.into_inner() {
+            match current.as_rule() {
+                Rule::compound_identifier => {
+                    layout = Layout::Named(compact_identifier(current.as_str()));
+                    self.reference(&current, ReferenceKind::Type);
+                }
+                rule if SymbolKind::of_layout(rule).is_some() => layout = self.inline_layout(current),
+                Rule::layout_parameters => {
+                    for param in current.into_inner() {
+                        match param.as_rule() {
+                            Rule::type_constructor => parameters.push(TypeParam::Type(self.type_ctor(param))),
+                            Rule::constant => parameters.push(TypeParam::Constant(self.constant(param))),
+                            _ => {}
+                        }
+                    }
+                }
+                Rule::type_constraints => {
+                    for constraint in current.into_inner().filter(|p| p.as_rule() == Rule::constant) {
+                        constraints.push(self.constant(constraint));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        TypeCtor {
+            layout,
+            parameters,
+            constraints,
+        }
+    }
+
+    /// Records the identifiers of a constant expression and returns its text.
+    fn constant(&mut self, constant: Pair<'_, Rule>) -> String {
+        for ident in constant
+            .clone()
+            .into_inner()
+            .filter(|p| p.as_rule() == Rule::compound_identifier)
+        {
+            self.reference(&ident, ReferenceKind::Constant);
+        }
+
+        compact(constant.as_str())
+    }
+
+    fn reference(&mut self, name: &Pair<'_, Rule>, kind: ReferenceKind) {
+        self.index.references.push(Reference {
+            name: compact_identifier(name.as_str()),
+            kind,
+            span: self.span(name),
+        });
+    }
+}
+
+/// `fx . Handle` and `fx.Handle` are the same name.
+fn compact_identifier(text: &str) -> String {
+    text.split_whitespace().collect()
+}
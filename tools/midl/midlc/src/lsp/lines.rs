@@ -0,0 +1,47 @@
+use lsp_types::{Position, Range};
+
+use crate::ast::Span;
+
+/// Converts between byte offsets and LSP positions, whose columns count UTF-16 code units.
+#[derive(Debug, Default)]
+pub(crate) struct LineIndex {
+    /// Byte offset of the start of each line.
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(idx, _)| idx + 1));
+        Self { line_starts }
+    }
+
+    pub fn position(&self, text: &str, offset: usize) -> Position {
+        let offset = offset.min(text.len());
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let character = text[line_start..offset].encode_utf16().count();
+
+        Position::new(line as u32, character as u32)
+    }
+
+    pub fn offset(&self, text: &str, position: Position) -> usize {
+        let Some(line_start) = self.line_starts.get(position.line as usize) else {
+            return text.len();
+        };
+
+        let mut units = 0;
+        for (idx, c) in text[*line_start..].char_indices() {
+            if units >= position.character as usize || c == '\n' {
+                return line_start + idx;
+            }
+            units += c.len_utf16();
+        }
+
+        text.len()
+    }
+
+    pub fn range(&self, text: &str, span: &Span) -> Range {
+        Range::new(self.position(text, span.start), self.position(text, span.end))
+    }
+}
@@ -0,0 +1,808 @@
+//! A language server for MIDL, spoken over stdin/stdout.
+//!
+//! The server indexes every `.midl` file under the workspace folders so that names from
+//! `using`d libraries can be resolved, and keeps the editor's unsaved contents for open
+//! files. Diagnostics are published when a file is opened or saved; hover, go-to-definition,
+//! completion and document symbols work on the current contents.
+
+mod index;
+mod lines;
+mod workspace;
+
+use std::error::Error;
+use std::path::PathBuf;
+
+use lsp_server::{Connection, ExtractError, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse, Diagnostic,
+    DiagnosticSeverity, DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams,
+    GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability, InitializeParams, Location,
+    MarkupContent, MarkupKind, OneOf, Position, PublishDiagnosticsParams, SaveOptions, ServerCapabilities,
+    TextDocumentPositionParams, TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, Url,
+};
+
+use midlgen::ir;
+
+use crate::ast;
+use crate::generator::type_shape::TypeShapeCalculator;
+use crate::source_file::SourceId;
+
+use self::index::{Symbol, SymbolKind};
+use self::workspace::{Resolution, Workspace, BUILTINS};
+
+/// Attributes offered by completion after `@`.
+const ATTRIBUTES: [(&str, &str); 9] = [
+    ("available", "versioning: `added`, `deprecated`, `removed`"),
+    ("allow_lint", "silences `midlc lint` rules"),
+    ("deprecated", "marks an element as deprecated"),
+    ("discoverable", "makes a protocol discoverable by name"),
+    ("doc", "documentation, usually written as `///`"),
+    ("generated_name", "names an anonymous layout"),
+    ("selector", "overrides the name used to compute a method ordinal"),
+    ("transitional", "marks a method as being added or removed"),
+    ("transport", "selects the transport of a protocol"),
+];
+
+type ServerResult<T> = Result<T, Box<dyn Error + Sync + Send>>;
+
+/// Serves a single client on stdin/stdout until it asks to shut down.
+pub(crate) fn run() -> ServerResult<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    serve(connection)?;
+
+    // The writer thread exits once the last sender, owned by the connection, is dropped.
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Serves a single client on `connection`, from the initialize handshake to shutdown.
+fn serve(connection: Connection) -> ServerResult<()> {
+    let capabilities = serde_json::to_value(ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+            open_close: Some(true),
+            change: Some(TextDocumentSyncKind::FULL),
+            save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                include_text: Some(true),
+            })),
+            ..Default::default()
+        })),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![".".to_owned(), "@".to_owned()]),
+            ..Default::default()
+        }),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    })?;
+
+    let params: InitializeParams = serde_json::from_value(connection.initialize(capabilities)?)?;
+
+    let mut server = Server {
+        connection,
+        workspace: Workspace::default(),
+    };
+    server.scan(&params);
+    server.main_loop()
+}
+
+fn cast<R: lsp_types::request::Request>(req: Request) -> Result<(RequestId, R::Params), ExtractError<Request>> {
+    req.extract(R::METHOD)
+}
+
+fn cast_notification<N: lsp_types::notification::Notification>(
+    not: Notification,
+) -> Result<N::Params, ExtractError<Notification>> {
+    not.extract(N::METHOD)
+}
+
+struct Server {
+    connection: Connection,
+    workspace: Workspace,
+}
+
+/// The element under the cursor.
+enum Target<'a> {
+    Decl {
+        source: SourceId,
+        decl: &'a Symbol,
+        member: Option<&'a Symbol>,
+    },
+    Library(String),
+}
+
+impl Server {
+    fn scan(&mut self, params: &InitializeParams) {
+        #[allow(deprecated)]
+        let roots: Vec<Url> = match &params.workspace_folders {
+            Some(folders) => folders.iter().map(|folder| folder.uri.clone()).collect(),
+            None => params.root_uri.iter().cloned().collect(),
+        };
+
+        for root in roots {
+            if let Ok(path) = root.to_file_path() {
+                log::info!("indexing {}", path.display());
+                self.workspace.scan(&path);
+            }
+        }
+    }
+
+    fn main_loop(&mut self) -> ServerResult<()> {
+        let receiver = self.connection.receiver.clone();
+
+        for msg in &receiver {
+            match msg {
+                Message::Request(req) => {
+                    if self.connection.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+                    self.request(req)?;
+                }
+                Message::Notification(not) => self.notification(not)?,
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn respond<R: serde::Serialize>(&self, id: RequestId, result: R) -> ServerResult<()> {
+        let response = Response::new_ok(id, result);
+        self.connection.sender.send(Message::Response(response))?;
+        Ok(())
+    }
+
+    fn request(&mut self, req: Request) -> ServerResult<()> {
+        let req = match cast::<HoverRequest>(req) {
+            Ok((id, params)) => return self.respond(id, self.hover(params)),
+            Err(ExtractError::MethodMismatch(req)) => req,
+            Err(err) => return Err(err.into()),
+        };
+
+        let req = match cast::<GotoDefinition>(req) {
+            Ok((id, params)) => return self.respond(id, self.definition(params)),
+            Err(ExtractError::MethodMismatch(req)) => req,
+            Err(err) => return Err(err.into()),
+        };
+
+        let req = match cast::<Completion>(req) {
+            Ok((id, params)) => return self.respond(id, self.completion(params)),
+            Err(ExtractError::MethodMismatch(req)) => req,
+            Err(err) => return Err(err.into()),
+        };
+
+        let req = match cast::<DocumentSymbolRequest>(req) {
+            Ok((id, params)) => return self.respond(id, self.document_symbols(params)),
+            Err(ExtractError::MethodMismatch(req)) => req,
+            Err(err) => return Err(err.into()),
+        };
+
+        let response = Response::new_err(
+            req.id,
+            lsp_server::ErrorCode::MethodNotFound as i32,
+            format!("unsupported request {}", req.method),
+        );
+        self.connection.sender.send(Message::Response(response))?;
+
+        Ok(())
+    }
+
+    fn notification(&mut self, not: Notification) -> ServerResult<()> {
+        let not = match cast_notification::<DidOpenTextDocument>(not) {
+            Ok(params) => {
+                let path = params.text_document.uri.to_file_path().map_err(|_| "not a file")?;
+                self.workspace.update(path, params.text_document.text, true);
+                return self.publish_diagnostics();
+            }
+            Err(ExtractError::MethodMismatch(not)) => not,
+            Err(err) => return Err(err.into()),
+        };
+
+        let not = match cast_notification::<DidChangeTextDocument>(not) {
+            Ok(params) => {
+                let path = params.text_document.uri.to_file_path().map_err(|_| "not a file")?;
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.workspace.update(path, change.text, true);
+                }
+                return Ok(());
+            }
+            Err(ExtractError::MethodMismatch(not)) => not,
+            Err(err) => return Err(err.into()),
+        };
+
+        let not = match cast_notification::<DidSaveTextDocument>(not) {
+            Ok(params) => {
+                let path = params.text_document.uri.to_file_path().map_err(|_| "not a file")?;
+                if let Some(text) = params.text {
+                    self.workspace.update(path, text, true);
+                }
+                return self.publish_diagnostics();
+            }
+            Err(ExtractError::MethodMismatch(not)) => not,
+            Err(err) => return Err(err.into()),
+        };
+
+        match cast_notification::<DidCloseTextDocument>(not) {
+            Ok(params) => {
+                let path = params.text_document.uri.to_file_path().map_err(|_| "not a file")?;
+                self.workspace.close(&path);
+                self.send_diagnostics(params.text_document.uri, vec![])
+            }
+            Err(ExtractError::MethodMismatch(_)) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Publishes diagnostics for every open document, since a change to one file can
+    /// resolve or break names in the others.
+    fn publish_diagnostics(&self) -> ServerResult<()> {
+        for (source, document) in self.workspace.documents().filter(|(_, document)| document.open) {
+            let diagnostics = self.workspace.diagnostics(source);
+            let (errors, warnings) = (diagnostics.errors(), diagnostics.warnings());
+
+            let errors = errors.iter().map(|e| Diagnostic {
+                range: document.lines.range(&document.text, e.span()),
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("midlc".to_owned()),
+                message: e.message().to_owned(),
+                ..Default::default()
+            });
+
+            let warnings = warnings.iter().map(|w| Diagnostic {
+                range: document.lines.range(&document.text, w.span()),
+                severity: Some(DiagnosticSeverity::WARNING),
+                source: Some("midlc".to_owned()),
+                message: w.message().to_owned(),
+                ..Default::default()
+            });
+
+            let uri = Url::from_file_path(&document.path).map_err(|_| "not a file")?;
+            self.send_diagnostics(uri, errors.chain(warnings).collect())?;
+        }
+
+        Ok(())
+    }
+
+    fn send_diagnostics(&self, uri: Url, diagnostics: Vec<Diagnostic>) -> ServerResult<()> {
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        };
+
+        let not = Notification::new(PublishDiagnostics::METHOD.to_owned(), params);
+        self.connection.sender.send(Message::Notification(not))?;
+        Ok(())
+    }
+
+    fn locate(&self, params: &TextDocumentPositionParams) -> Option<(SourceId, usize)> {
+        let path: PathBuf = params.text_document.uri.to_file_path().ok()?;
+        let source = self.workspace.lookup(&path)?;
+        let document = &self.workspace[source];
+
+        Some((source, document.lines.offset(&document.text, params.position)))
+    }
+
+    fn target_at(&self, source: SourceId, offset: usize) -> Option<Target<'_>> {
+        let index = &self.workspace[source].index;
+
+        if let Some(reference) = index.references.iter().find(|r| r.span.contains(offset)) {
+            return match self.workspace.resolve(source, &reference.name) {
+                Resolution::Decl { source, decl, member } => Some(Target::Decl { source, decl, member }),
+                _ => None,
+            };
+        }
+
+        if let Some(import) = index.imports.iter().find(|i| i.span.contains(offset)) {
+            return Some(Target::Library(import.library.clone()));
+        }
+
+        for decl in index.symbols.iter() {
+            if decl.name_span.contains(offset) {
+                return Some(Target::Decl {
+                    source,
+                    decl,
+                    member: None,
+                });
+            }
+
+            if let Some(member) = decl.members.iter().find(|m| m.name_span.contains(offset)) {
+                return Some(Target::Decl {
+                    source,
+                    decl,
+                    member: Some(member),
+                });
+            }
+        }
+
+        None
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let (source, offset) = self.locate(&params.text_document_position_params)?;
+
+        let Target::Decl { source, decl, member } = self.target_at(source, offset)? else {
+            return None;
+        };
+
+        // Layouts come from midlc itself, so they are only shown once the library compiles.
+        let library = self
+            .workspace
+            .library_of(source)
+            .and_then(|library| self.workspace.compile(library));
+        let compiled = library
+            .as_ref()
+            .and_then(|library| library.declarations.borrow().all.get(&decl.name).cloned());
+
+        let mut shapes = TypeShapeCalculator::default();
+        let mut sections = vec![];
+
+        match member {
+            None => {
+                sections.push(format!("```midl\n{}\n```", decl.signature));
+                sections.extend(decl.doc.clone());
+
+                if let Some(shape) = compiled.and_then(|compiled| decl_shape(&mut shapes, &compiled)) {
+                    sections.push(format!(
+                        "inline size: {} bytes, alignment: {}",
+                        shape.inline_size, shape.alignment
+                    ));
+                }
+            }
+            Some(member) => {
+                sections.push(format!("```midl\n{}\n```", member.signature));
+                sections.push(format!("member of `{}`", decl.name));
+                sections.extend(member.doc.clone());
+
+                if let Some((shape, offset)) =
+                    compiled.and_then(|compiled| member_shape(&mut shapes, &compiled, &member.name))
+                {
+                    let mut layout = format!(
+                        "inline size: {} bytes, alignment: {}",
+                        shape.inline_size, shape.alignment
+                    );
+
+                    if let Some(offset) = offset {
+                        layout.push_str(&format!(", offset: {}", offset));
+                    }
+
+                    sections.push(layout);
+                }
+            }
+        }
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: sections.join("\n\n---\n\n"),
+            }),
+            range: None,
+        })
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let (source, offset) = self.locate(&params.text_document_position_params)?;
+
+        let (source, span) = match self.target_at(source, offset)? {
+            Target::Decl { source, decl, member } => (source, member.unwrap_or(decl).name_span.clone()),
+            Target::Library(library) => self.workspace.documents().find_map(|(source, document)| {
+                document
+                    .index
+                    .library
+                    .as_ref()
+                    .filter(|(name, _)| *name == library)
+                    .map(|(_, span)| (source, span.clone()))
+            })?,
+        };
+
+        let document = &self.workspace[source];
+        Some(GotoDefinitionResponse::Scalar(Location {
+            uri: Url::from_file_path(&document.path).ok()?,
+            range: document.lines.range(&document.text, &span),
+        }))
+    }
+
+    fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let (source, offset) = self.locate(&params.text_document_position)?;
+        let text = &self.workspace[source].text;
+
+        let before = &text[..offset];
+        let word_start = before
+            .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+            .map_or(0, |idx| idx + 1);
+        let word = &before[word_start..];
+
+        if before[..word_start].ends_with('@') {
+            let items = ATTRIBUTES
+                .iter()
+                .map(|(name, detail)| CompletionItem {
+                    label: name.to_string(),
+                    kind: Some(CompletionItemKind::PROPERTY),
+                    detail: Some(detail.to_string()),
+                    ..Default::default()
+                })
+                .collect();
+            return Some(CompletionResponse::Array(items));
+        }
+
+        let mut items = vec![];
+
+        match word.rsplit_once('.') {
+            Some((qualifier, _)) => {
+                if let Some(library) = self.workspace.visible_library(source, qualifier) {
+                    items.extend(self.workspace.declarations(library).map(|(_, decl)| decl_item(decl)));
+                } else if let Resolution::Decl { decl, member: None, .. } = self.workspace.resolve(source, qualifier) {
+                    items.extend(decl.members.iter().map(decl_item));
+                }
+            }
+            None => {
+                items.extend(BUILTINS.iter().map(|name| CompletionItem {
+                    label: name.to_string(),
+                    kind: Some(CompletionItemKind::KEYWORD),
+                    ..Default::default()
+                }));
+
+                if let Some(library) = self.workspace.library_of(source) {
+                    items.extend(self.workspace.declarations(library).map(|(_, decl)| decl_item(decl)));
+                }
+
+                for import in self.workspace[source].index.imports.iter() {
+                    items.push(CompletionItem {
+                        label: import.alias.clone().unwrap_or_else(|| import.library.clone()),
+                        kind: Some(CompletionItemKind::MODULE),
+                        detail: Some(import.library.clone()),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        Some(CompletionResponse::Array(items))
+    }
+
+    fn document_symbols(&self, params: DocumentSymbolParams) -> Option<DocumentSymbolResponse> {
+        let path = params.text_document.uri.to_file_path().ok()?;
+        let document = &self.workspace[self.workspace.lookup(&path)?];
+
+        #[allow(deprecated)]
+        fn convert(document: &workspace::Document, symbol: &Symbol) -> DocumentSymbol {
+            DocumentSymbol {
+                name: symbol.name.clone(),
+                detail: Some(symbol.signature.clone()),
+                kind: lsp_symbol_kind(symbol.kind),
+                tags: None,
+                deprecated: None,
+                range: document.lines.range(&document.text, &symbol.span),
+                selection_range: document.lines.range(&document.text, &symbol.name_span),
+                children: Some(symbol.members.iter().map(|m| convert(document, m)).collect()),
+            }
+        }
+
+        Some(DocumentSymbolResponse::Nested(
+            document.index.symbols.iter().map(|s| convert(document, s)).collect(),
+        ))
+    }
+}
+
+fn decl_item(symbol: &Symbol) -> CompletionItem {
+    CompletionItem {
+        label: symbol.name.clone(),
+        kind: Some(match symbol.kind {
+            SymbolKind::Const => CompletionItemKind::CONSTANT,
+            SymbolKind::Enum | SymbolKind::Bits => CompletionItemKind::ENUM,
+            SymbolKind::Protocol => CompletionItemKind::INTERFACE,
+            SymbolKind::Value => CompletionItemKind::ENUM_MEMBER,
+            SymbolKind::Field | SymbolKind::Property => CompletionItemKind::FIELD,
+            SymbolKind::Method | SymbolKind::Event => CompletionItemKind::METHOD,
+            _ => CompletionItemKind::STRUCT,
+        }),
+        detail: Some(symbol.signature.clone()),
+        documentation: symbol.doc.clone().map(lsp_types::Documentation::String),
+        ..Default::default()
+    }
+}
+
+fn lsp_symbol_kind(kind: SymbolKind) -> lsp_types::SymbolKind {
+    match kind {
+        SymbolKind::Const => lsp_types::SymbolKind::CONSTANT,
+        SymbolKind::Alias => lsp_types::SymbolKind::TYPE_PARAMETER,
+        SymbolKind::Struct | SymbolKind::Table | SymbolKind::Union => lsp_types::SymbolKind::STRUCT,
+        SymbolKind::Enum | SymbolKind::Bits => lsp_types::SymbolKind::ENUM,
+        SymbolKind::Resource => lsp_types::SymbolKind::CLASS,
+        SymbolKind::Protocol => lsp_types::SymbolKind::INTERFACE,
+        SymbolKind::Field => lsp_types::SymbolKind::FIELD,
+        SymbolKind::Value => lsp_types::SymbolKind::ENUM_MEMBER,
+        SymbolKind::Property => lsp_types::SymbolKind::PROPERTY,
+        SymbolKind::Method => lsp_types::SymbolKind::METHOD,
+        SymbolKind::Event => lsp_types::SymbolKind::EVENT,
+    }
+}
+
+/// The shape of a declaration that has a layout.
+fn decl_shape(shapes: &mut TypeShapeCalculator, decl: &ast::Declaration) -> Option<ir::TypeShape> {
+    match decl {
+        ast::Declaration::Struct { .. }
+        | ast::Declaration::Table { .. }
+        | ast::Declaration::Union { .. }
+        | ast::Declaration::Enum { .. }
+        | ast::Declaration::Bits { .. } => Some(shapes.of_decl(decl)),
+        ast::Declaration::Alias { decl } => {
+            let decl = decl.borrow();
+            decl.partial_type_ctor
+                .r#type
+                .as_ref()
+                .map(|r#type| shapes.of_type(r#type))
+        }
+        _ => None,
+    }
+}
+
+/// The shape of the member `name` of a layout, and its offset when it is a struct member.
+fn member_shape(
+    shapes: &mut TypeShapeCalculator,
+    decl: &ast::Declaration,
+    name: &str,
+) -> Option<(ir::TypeShape, Option<u32>)> {
+    match decl {
+        ast::Declaration::Struct { decl } => {
+            let decl = decl.borrow();
+            let position = decl.members.iter().position(|m| m.borrow().name.data == name)?;
+            let r#type = decl.members[position].borrow().type_ctor.r#type.clone()?;
+            let offset = shapes.struct_field_shapes(&decl).get(position)?.offset;
+
+            Some((shapes.of_type(&r#type), Some(offset)))
+        }
+        ast::Declaration::Table { decl } => decl.borrow().members.iter().find_map(|member| {
+            let member = member.borrow();
+            let used = member.maybe_used.as_ref().filter(|used| used.name.data == name)?;
+            used.type_ctor
+                .r#type
+                .as_ref()
+                .map(|r#type| (shapes.of_type(r#type), None))
+        }),
+        ast::Declaration::Union { decl } => decl.borrow().members.iter().find_map(|member| {
+            let member = member.borrow();
+            let used = member.maybe_used.as_ref().filter(|used| used.name.data == name)?;
+            used.type_ctor
+                .r#type
+                .as_ref()
+                .map(|r#type| (shapes.of_type(r#type), None))
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::JoinHandle;
+    use std::time::Duration;
+
+    use lsp_types::notification::{Exit, Initialized};
+    use lsp_types::request::{Initialize, Shutdown};
+    use lsp_types::{DidOpenTextDocumentParams, InitializedParams, Range, TextDocumentIdentifier, TextDocumentItem};
+
+    const TYPES: &str = "library example;
+
+/// A point in the plane.
+type Point = struct {
+    x int32;
+    /// The vertical coordinate.
+    y int64;
+};
+";
+
+    const SHAPES: &str = "library example;
+
+type Shape = struct {
+    origin Point;
+    corner Corner;
+};
+";
+
+    /// A client talking to a server running on another thread over an in-memory transport.
+    struct Client {
+        connection: Connection,
+        server: Option<JoinHandle<()>>,
+        next_id: i32,
+    }
+
+    impl Client {
+        fn start() -> Client {
+            let (server, connection) = Connection::memory();
+            let server = std::thread::spawn(move || serve(server).unwrap());
+
+            let mut client = Client {
+                connection,
+                server: Some(server),
+                next_id: 0,
+            };
+
+            let result = client.request::<Initialize>(InitializeParams::default());
+            assert_eq!(
+                result.capabilities.hover_provider,
+                Some(HoverProviderCapability::Simple(true))
+            );
+            client.notify::<Initialized>(InitializedParams {});
+
+            client
+        }
+
+        fn request<R: lsp_types::request::Request>(&mut self, params: R::Params) -> R::Result {
+            self.next_id += 1;
+            let id = RequestId::from(self.next_id);
+
+            let request = Request::new(id.clone(), R::METHOD.to_owned(), params);
+            self.connection.sender.send(Message::Request(request)).unwrap();
+
+            match self.receive() {
+                Message::Response(response) => {
+                    assert_eq!(response.id, id);
+                    assert!(response.error.is_none(), "{:?}", response.error);
+                    serde_json::from_value(response.result.unwrap()).unwrap()
+                }
+                msg => panic!("expected a response to {}, got {msg:?}", R::METHOD),
+            }
+        }
+
+        fn notify<N: lsp_types::notification::Notification>(&self, params: N::Params) {
+            let not = Notification::new(N::METHOD.to_owned(), params);
+            self.connection.sender.send(Message::Notification(not)).unwrap();
+        }
+
+        fn receive(&self) -> Message {
+            self.connection.receiver.recv_timeout(Duration::from_secs(10)).unwrap()
+        }
+
+        fn receive_diagnostics(&self) -> PublishDiagnosticsParams {
+            match self.receive() {
+                Message::Notification(not) => cast_notification::<PublishDiagnostics>(not).unwrap(),
+                msg => panic!("expected diagnostics, got {msg:?}"),
+            }
+        }
+
+        /// Opens a document and returns the diagnostics published for it.
+        fn open(&self, name: &str, text: &str) -> Vec<Diagnostic> {
+            let uri = uri(name);
+            self.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+                text_document: TextDocumentItem::new(uri.clone(), "midl".to_owned(), 1, text.to_owned()),
+            });
+
+            // Diagnostics are republished for every open document.
+            loop {
+                let params = self.receive_diagnostics();
+                if params.uri == uri {
+                    return params.diagnostics;
+                }
+            }
+        }
+
+        fn position(name: &str, line: u32, character: u32) -> TextDocumentPositionParams {
+            TextDocumentPositionParams::new(TextDocumentIdentifier::new(uri(name)), Position::new(line, character))
+        }
+
+        fn shutdown(mut self) {
+            self.request::<Shutdown>(());
+            self.notify::<Exit>(());
+            self.server.take().unwrap().join().unwrap();
+        }
+    }
+
+    fn uri(name: &str) -> Url {
+        Url::from_file_path(std::env::temp_dir().join("midlc-lsp-test").join(name)).unwrap()
+    }
+
+    fn hover_text(hover: Option<Hover>) -> String {
+        match hover.expect("no hover").contents {
+            HoverContents::Markup(markup) => markup.value,
+            contents => panic!("unexpected hover contents {contents:?}"),
+        }
+    }
+
+    #[test]
+    fn diagnostics() {
+        let client = Client::start();
+
+        assert_eq!(client.open("types.midl", TYPES), vec![]);
+
+        let diagnostics = client.open("shapes.midl", SHAPES);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(diagnostics[0].message, "cannot find `Corner`");
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(4, 11), Position::new(4, 17))
+        );
+
+        let diagnostics = client.open("broken.midl", "library example;\n\ntype = struct {};\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn hover() {
+        let mut client = Client::start();
+        client.open("types.midl", TYPES);
+
+        // The name of `type Point`.
+        let hover = client.request::<HoverRequest>(HoverParams {
+            text_document_position_params: Client::position("types.midl", 3, 6),
+            work_done_progress_params: Default::default(),
+        });
+        assert_eq!(
+            hover_text(hover),
+            "```midl\ntype Point = struct\n```\n\n---\n\nA point in the plane.\n\n---\n\n\
+             inline size: 16 bytes, alignment: 8"
+        );
+
+        // The `y` member.
+        let hover = client.request::<HoverRequest>(HoverParams {
+            text_document_position_params: Client::position("types.midl", 6, 4),
+            work_done_progress_params: Default::default(),
+        });
+        assert_eq!(
+            hover_text(hover),
+            "```midl\ny int64\n```\n\n---\n\nmember of `Point`\n\n---\n\nThe vertical coordinate.\n\n---\n\n\
+             inline size: 8 bytes, alignment: 8, offset: 8"
+        );
+
+        // Whitespace.
+        let hover = client.request::<HoverRequest>(HoverParams {
+            text_document_position_params: Client::position("types.midl", 1, 0),
+            work_done_progress_params: Default::default(),
+        });
+        assert_eq!(hover, None);
+
+        // Once the library stops compiling, hover still works but shows no layout.
+        client.open("shapes.midl", SHAPES);
+        let hover = client.request::<HoverRequest>(HoverParams {
+            text_document_position_params: Client::position("shapes.midl", 3, 12),
+            work_done_progress_params: Default::default(),
+        });
+        assert_eq!(
+            hover_text(hover),
+            "```midl\ntype Point = struct\n```\n\n---\n\nA point in the plane."
+        );
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn definition() {
+        let mut client = Client::start();
+        client.open("types.midl", TYPES);
+        client.open("shapes.midl", SHAPES);
+
+        let definition = client.request::<GotoDefinition>(GotoDefinitionParams {
+            text_document_position_params: Client::position("shapes.midl", 3, 12),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        assert_eq!(
+            definition,
+            Some(GotoDefinitionResponse::Scalar(Location::new(
+                uri("types.midl"),
+                Range::new(Position::new(3, 5), Position::new(3, 10)),
+            )))
+        );
+
+        // Names that do not resolve have no definition.
+        let definition = client.request::<GotoDefinition>(GotoDefinitionParams {
+            text_document_position_params: Client::position("shapes.midl", 4, 12),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        assert_eq!(definition, None);
+
+        client.shutdown();
+    }
+}
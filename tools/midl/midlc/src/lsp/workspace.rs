@@ -0,0 +1,373 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::ast;
+use crate::compiler::{Compiler, Libraries};
+use crate::diagnotics::{Diagnostics, DiagnosticsError, DiagnosticsWarning};
+use crate::source_file::{SourceFile, SourceId};
+
+use super::index::{FileIndex, ReferenceKind, Symbol};
+use super::lines::LineIndex;
+
+/// Layouts and constants every library can use without a `using`.
+pub(crate) const BUILTINS: [&str; 22] = [
+    "bool",
+    "int8",
+    "int16",
+    "int32",
+    "int64",
+    "uint8",
+    "uint16",
+    "uint32",
+    "uint64",
+    "float32",
+    "float64",
+    "string",
+    "string_array",
+    "box",
+    "array",
+    "vector",
+    "client_end",
+    "server_end",
+    "byte",
+    "optional",
+    "MAX",
+    "HEAD",
+];
+
+/// A MIDL file known to the server, either read from disk or open in the editor.
+#[derive(Debug)]
+pub(crate) struct Document {
+    pub path: PathBuf,
+    pub text: String,
+    pub lines: LineIndex,
+    pub index: FileIndex,
+    pub open: bool,
+
+    /// Errors found while indexing the file, i.e. parse errors.
+    errors: Vec<DiagnosticsError>,
+}
+
+/// What a name refers to.
+#[derive(Debug)]
+pub(crate) enum Resolution<'a> {
+    Builtin,
+    Decl {
+        source: SourceId,
+        decl: &'a Symbol,
+        member: Option<&'a Symbol>,
+    },
+    /// The name is qualified with a library that is not part of the workspace.
+    UnknownLibrary(String),
+    NotFound,
+}
+
+/// All MIDL files under the workspace roots. Documents are identified by a `SourceId` so
+/// that the spans of their index point back at them; ids stay valid for the whole session.
+#[derive(Debug, Default)]
+pub(crate) struct Workspace {
+    documents: Vec<Document>,
+    by_path: HashMap<PathBuf, SourceId>,
+}
+
+impl Workspace {
+    /// Indexes every `.midl` file below `root`, skipping hidden and build directories.
+    pub fn scan(&mut self, root: &Path) {
+        let Ok(entries) = std::fs::read_dir(root) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+
+            if path.is_dir() {
+                if !name.starts_with('.') && name != "target" {
+                    self.scan(&path);
+                }
+            } else if path.extension().is_some_and(|ext| ext == "midl") {
+                match std::fs::read_to_string(&path) {
+                    Ok(text) => {
+                        self.update(path, text, false);
+                    }
+                    Err(e) => log::warn!("couldn't read {}: {}", path.display(), e),
+                }
+            }
+        }
+    }
+
+    /// Replaces the contents of a document, adding it if it is new.
+    pub fn update(&mut self, path: PathBuf, text: String, open: bool) -> SourceId {
+        let source_id = match self.by_path.get(&path) {
+            Some(source_id) => *source_id,
+            None => {
+                let source_id = SourceId(self.documents.len());
+                self.by_path.insert(path.clone(), source_id);
+                self.documents.push(Document {
+                    path,
+                    text: String::new(),
+                    lines: LineIndex::default(),
+                    index: FileIndex::default(),
+                    open,
+                    errors: vec![],
+                });
+                source_id
+            }
+        };
+
+        let diagnostics = Diagnostics::new();
+        let index = FileIndex::build(&text, source_id, &diagnostics);
+
+        let document = &mut self.documents[source_id.0];
+        document.lines = LineIndex::new(&text);
+        document.text = text;
+        document.index = index;
+        document.open = open;
+        document.errors = diagnostics.errors().clone();
+
+        source_id
+    }
+
+    /// Falls back to the contents on disk once the editor closes a document.
+    pub fn close(&mut self, path: &Path) {
+        match std::fs::read_to_string(path) {
+            Ok(text) => {
+                self.update(path.to_owned(), text, false);
+            }
+            Err(_) => {
+                // The file was never saved; forget what it declared.
+                if let Some(source_id) = self.by_path.get(path) {
+                    let document = &mut self.documents[source_id.0];
+                    document.open = false;
+                    document.index = FileIndex::default();
+                    document.errors.clear();
+                }
+            }
+        }
+    }
+
+    pub fn lookup(&self, path: &Path) -> Option<SourceId> {
+        self.by_path.get(path).copied()
+    }
+
+    pub fn documents(&self) -> impl Iterator<Item = (SourceId, &Document)> {
+        self.documents
+            .iter()
+            .enumerate()
+            .map(|(idx, document)| (SourceId(idx), document))
+    }
+
+    pub fn library_of(&self, source: SourceId) -> Option<&str> {
+        self[source].index.library.as_ref().map(|(name, _)| name.as_str())
+    }
+
+    pub fn has_library(&self, library: &str) -> bool {
+        self.documents()
+            .any(|(source, _)| self.library_of(source) == Some(library))
+    }
+
+    /// Top-level declarations of a library across all of its files.
+    pub fn declarations<'a>(&'a self, library: &'a str) -> impl Iterator<Item = (SourceId, &'a Symbol)> + 'a {
+        self.documents()
+            .filter(move |(source, _)| self.library_of(*source) == Some(library))
+            .flat_map(|(source, document)| document.index.symbols.iter().map(move |symbol| (source, symbol)))
+    }
+
+    /// Maps the library part of a qualified name, an import alias or full library name, to
+    /// the library it stands for in `from`.
+    pub fn visible_library<'a>(&'a self, from: SourceId, prefix: &str) -> Option<&'a str> {
+        let index = &self[from].index;
+
+        index
+            .imports
+            .iter()
+            .find(|import| import.alias.as_deref() == Some(prefix) || import.library == prefix)
+            .map(|import| import.library.as_str())
+            .or_else(|| self.library_of(from).filter(|library| *library == prefix))
+    }
+
+    /// Resolves a possibly qualified name, e.g. `Point`, `Color.RED`, `fx.Handle` or
+    /// `meshx.io.Node.Close`, as seen from a file.
+    pub fn resolve(&self, from: SourceId, name: &str) -> Resolution<'_> {
+        let parts: Vec<&str> = name.split('.').collect();
+
+        if parts.len() == 1 && BUILTINS.contains(&name) {
+            return Resolution::Builtin;
+        }
+
+        for split in (1..parts.len()).rev() {
+            let prefix = parts[..split].join(".");
+
+            if let Some(library) = self.visible_library(from, &prefix) {
+                return self.resolve_in(library, &parts[split..]);
+            }
+        }
+
+        match self.library_of(from) {
+            Some(library) => self.resolve_in(library, &parts),
+            None => Resolution::NotFound,
+        }
+    }
+
+    fn resolve_in(&self, library: &str, parts: &[&str]) -> Resolution<'_> {
+        if !self.has_library(library) {
+            return Resolution::UnknownLibrary(library.to_owned());
+        }
+
+        let (decl_name, member_name) = match parts {
+            [decl] => (*decl, None),
+            [decl, member] => (*decl, Some(*member)),
+            _ => return Resolution::NotFound,
+        };
+
+        let found = self
+            .documents()
+            .filter(|(source, _)| self.library_of(*source) == Some(library))
+            .find_map(|(source, document)| {
+                document
+                    .index
+                    .symbols
+                    .iter()
+                    .find(|decl| decl.name == decl_name)
+                    .map(|decl| (source, decl))
+            });
+
+        let Some((source, decl)) = found else {
+            return Resolution::NotFound;
+        };
+
+        match member_name {
+            None => Resolution::Decl {
+                source,
+                decl,
+                member: None,
+            },
+            Some(member_name) => match decl.members.iter().find(|m| m.name == member_name) {
+                Some(member) => Resolution::Decl {
+                    source,
+                    decl,
+                    member: Some(member),
+                },
+                None => Resolution::NotFound,
+            },
+        }
+    }
+
+    /// Compiles `library` with midlc, after the libraries it imports. `None` when a library
+    /// is missing from the workspace, imports itself, or fails to parse or compile.
+    pub fn compile(&self, library: &str) -> Option<Rc<ast::Library>> {
+        let mut order = vec![];
+        self.dependency_order(library, &mut vec![], &mut order)?;
+
+        // The compiler still panics on some unsupported constructs; an editor keystroke must
+        // not take the server down with it.
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            let all_libraries = Rc::new(RefCell::new(Libraries::new()));
+            let mut compiled = None;
+
+            for library in order.iter() {
+                let compiler = Compiler::new(all_libraries.clone())?;
+
+                for (source, document) in self.documents() {
+                    if self.library_of(source) != Some(library.as_str()) {
+                        continue;
+                    }
+
+                    if !document.errors.is_empty() {
+                        return None;
+                    }
+
+                    let filename = document.path.to_string_lossy();
+                    let file = SourceFile::from_str(&filename, &document.text);
+                    if compiler.consume_file(source, &file).has_errors() {
+                        return None;
+                    }
+                }
+
+                if !compiler.compile(&mut Diagnostics::new()) {
+                    return None;
+                }
+
+                compiled = Some(compiler.library.clone());
+            }
+
+            compiled
+        }))
+        .ok()
+        .flatten()
+    }
+
+    /// Appends `library` to `order` after everything it imports.
+    fn dependency_order(&self, library: &str, visiting: &mut Vec<String>, order: &mut Vec<String>) -> Option<()> {
+        if order.iter().any(|l| l == library) {
+            return Some(());
+        }
+
+        if !self.has_library(library) || visiting.iter().any(|l| l == library) {
+            return None;
+        }
+
+        visiting.push(library.to_owned());
+
+        let imports: Vec<&str> = self
+            .documents()
+            .filter(|(source, _)| self.library_of(*source) == Some(library))
+            .flat_map(|(_, document)| document.index.imports.iter().map(|import| import.library.as_str()))
+            .collect();
+
+        for import in imports {
+            self.dependency_order(import, visiting, order)?;
+        }
+
+        visiting.pop();
+        order.push(library.to_owned());
+
+        Some(())
+    }
+
+    /// Parse errors, unresolved names and imports of libraries missing from the workspace.
+    pub fn diagnostics(&self, source: SourceId) -> Diagnostics {
+        let document = &self[source];
+        let diagnostics = Diagnostics::new();
+
+        for error in document.errors.iter() {
+            diagnostics.push_error(error.clone());
+        }
+
+        for import in document.index.imports.iter() {
+            if !self.has_library(&import.library) {
+                diagnostics.push_warning(DiagnosticsWarning::new(
+                    format!("library `{}` was not found in the workspace", import.library),
+                    import.span.clone(),
+                ));
+            }
+        }
+
+        for reference in document.index.references.iter() {
+            if reference.kind == ReferenceKind::Constant {
+                continue;
+            }
+
+            if let Resolution::NotFound = self.resolve(source, &reference.name) {
+                diagnostics.push_error(DiagnosticsError::new(
+                    format!("cannot find `{}`", reference.name),
+                    reference.span.clone(),
+                ));
+            }
+        }
+
+        diagnostics
+    }
+}
+
+impl std::ops::Index<SourceId> for Workspace {
+    type Output = Document;
+
+    fn index(&self, index: SourceId) -> &Self::Output {
+        &self.documents[index.0]
+    }
+}
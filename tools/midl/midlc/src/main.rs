@@ -18,7 +18,10 @@ mod compiler;
 mod consumption;
 mod diagnotics;
 mod error;
+mod formatter;
 mod generator;
+mod linter;
+mod lsp;
 mod source_file;
 
 use clap::{ArgAction, Command};
@@ -33,7 +36,8 @@ use std::rc::Rc;
 use crate::compiler::{Compiler, Libraries};
 use crate::diagnotics::{pretty_print_error_text, Diagnostics};
 use crate::error::ErrorColorer;
-use crate::source_file::{SourceFile, SourceManager};
+use crate::linter::{LintConfig, LintRule};
+use crate::source_file::{SourceFile, SourceId, SourceManager};
 
 fn cli() -> Command {
     Command::new("midlc")
@@ -62,6 +66,59 @@ fn cli() -> Command {
                         .action(ArgAction::Append),
                 ),
         )
+        .subcommand(
+            Command::new("format")
+                .about("formats MIDL files in place")
+                .arg_required_else_help(true)
+                .arg(
+                    clap::Arg::new("CHECK")
+                        .long("check")
+                        .help("only report files that are not formatted, without rewriting them")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    clap::Arg::new("FILES")
+                        .long("files")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .value_delimiter(' ')
+                        .num_args(1..)
+                        .required(true)
+                        .action(ArgAction::Append),
+                ),
+        )
+        .subcommand(
+            Command::new("lint")
+                .about("checks the files of a MIDL library for style and API review issues")
+                .arg_required_else_help(true)
+                .arg(
+                    clap::Arg::new("ALLOW")
+                        .long("allow")
+                        .help("disables a lint rule for all files")
+                        .value_parser(clap::builder::PossibleValuesParser::new(
+                            LintRule::ALL.map(|rule| rule.name()),
+                        ))
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    clap::Arg::new("DENY_WARNINGS")
+                        .long("deny-warnings")
+                        .help("exits with an error if any lint is reported")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    clap::Arg::new("FILES")
+                        .long("files")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .value_delimiter(' ')
+                        .num_args(1..)
+                        .required(true)
+                        .action(ArgAction::Append),
+                ),
+        )
+        .subcommand(
+            Command::new("lsp")
+                .about("runs a language server for editors, speaking LSP on stdin/stdout"),
+        )
 }
 
 struct ExperimentalFlags;
@@ -149,6 +206,66 @@ fn compile(
     Ok(())
 }
 
+/// Formats the given files in place, or with `check` only reports the ones that would change.
+/// Returns false if any file failed to format or, in check mode, is not formatted.
+fn format(paths: &[PathBuf], check: bool) -> Result<bool, std::io::Error> {
+    let mut success = true;
+    let mut stderr = Box::new(std::io::stderr()) as Box<dyn Write>;
+
+    for (idx, path) in paths.iter().enumerate() {
+        let source = SourceFile::new(path.as_path())?;
+
+        let formatted = match formatter::format_source(source.as_str(), SourceId(idx)) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                success = false;
+                e.pretty_print(&mut stderr, source.filename(), source.as_str())?;
+                continue;
+            }
+        };
+
+        if formatted == source.as_str() {
+            continue;
+        }
+
+        if check {
+            success = false;
+            writeln!(stderr, "{} is not formatted", path.display())?;
+        } else {
+            log::info!("formatting {:?}", path);
+            std::fs::write(path, formatted)?;
+        }
+    }
+
+    Ok(success)
+}
+
+/// Lints the files of a single library and prints the findings. Returns false if a file
+/// could not be parsed or, with `deny_warnings`, if anything was reported.
+fn lint(source_manager: &SourceManager<'_>, config: &LintConfig, deny_warnings: bool) -> Result<bool, std::io::Error> {
+    let mut stderr = Box::new(std::io::stderr()) as Box<dyn Write>;
+    let diagnostics = Diagnostics::new();
+
+    linter::lint(source_manager, config, &diagnostics);
+
+    for e in diagnostics.errors().iter() {
+        let source = &source_manager[e.span().source];
+        e.pretty_print(&mut stderr, source.filename(), source.as_str())?;
+    }
+
+    for w in diagnostics.warnings().iter() {
+        let source = &source_manager[w.span().source];
+        w.pretty_print(&mut stderr, source.filename(), source.as_str())?;
+    }
+
+    let num_warnings = diagnostics.warnings().len();
+    if num_warnings > 0 {
+        writeln!(stderr, "{num_warnings} lint warning(s) found")?;
+    }
+
+    Ok(!diagnostics.has_errors() && !(deny_warnings && num_warnings > 0))
+}
+
 fn main() -> std::io::Result<()> {
     env_logger::init();
     // unsafe { backtrace_on_stack_overflow::enable() };
@@ -198,6 +315,82 @@ fn main() -> std::io::Result<()> {
 
             Ok(())
         }
+        Some(("format", sub_matches)) => {
+            let check = sub_matches.get_flag("CHECK");
+            let paths = sub_matches
+                .get_many::<PathBuf>("FILES")
+                .unwrap()
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if !format(&paths, check)? {
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+        Some(("lint", sub_matches)) => {
+            let deny_warnings = sub_matches.get_flag("DENY_WARNINGS");
+            let paths = sub_matches
+                .get_many::<PathBuf>("FILES")
+                .unwrap()
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let mut config = LintConfig::default();
+            for name in sub_matches.get_many::<String>("ALLOW").into_iter().flatten() {
+                config.allow(LintRule::from_name(name).unwrap());
+            }
+
+            let sources = paths
+                .iter()
+                .map(|path| SourceFile::new(path.as_path()))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if !lint(&SourceManager::from(sources), &config, deny_warnings)? {
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+        Some(("lsp", _)) => lsp::run().map_err(io::Error::other),
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachabe!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::error::ErrorKind;
+
+    #[test]
+    fn cli_is_valid() {
+        cli().debug_assert();
+    }
+
+    #[test]
+    fn format_requires_files() {
+        let err = cli().try_get_matches_from(["midlc", "format", "--check"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+
+        let matches = cli()
+            .try_get_matches_from(["midlc", "format", "--check", "--files", "a.midl"])
+            .unwrap();
+        let (_, format) = matches.subcommand().unwrap();
+        assert_eq!(format.get_many::<PathBuf>("FILES").unwrap().count(), 1);
+    }
+
+    #[test]
+    fn lint_requires_files() {
+        let err = cli()
+            .try_get_matches_from(["midlc", "lint", "--allow", "missing-doc-comment"])
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+
+        let matches = cli()
+            .try_get_matches_from(["midlc", "lint", "--allow", "missing-doc-comment", "--files", "a.midl"])
+            .unwrap();
+        let (_, lint) = matches.subcommand().unwrap();
+        assert_eq!(lint.get_many::<PathBuf>("FILES").unwrap().count(), 1);
+    }
+}
@@ -20,6 +20,14 @@ impl<'src> SourceFile<'src> {
         }
     }
 
+    /// A source that does not live on disk.
+    pub(crate) fn from_str(filename: &'src str, contents: &str) -> Self {
+        Self {
+            filename,
+            contents: contents.to_owned(),
+        }
+    }
+
     pub fn as_str(&self) -> &str {
         self.contents.as_str()
     }
@@ -0,0 +1,438 @@
+// Copyright 2024 MeshX Authors. All rights reserved.
+
+//! Decoding of V2 wire format bodies driven by JSON IR types.
+//!
+//! Layouts are computed from the types themselves rather than taken from the
+//! IR's type shapes. Neither padding nor length bounds are validated, so that
+//! messages which the generated bindings reject can still be inspected.
+
+use midlgen::ir::{PrimitiveSubtype, Type};
+
+use crate::{DecodeError, Libraries, Value};
+
+const ALLOC_PRESENT: u64 = u64::MAX;
+const ALLOC_ABSENT: u64 = 0;
+const HANDLE_PRESENT: u32 = u32::MAX;
+const HANDLE_ABSENT: u32 = 0;
+
+/// The inline size and alignment of a type.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Layout {
+    pub(crate) size: usize,
+    pub(crate) align: usize,
+}
+
+impl Layout {
+    const fn new(size: usize, align: usize) -> Self {
+        Layout { size, align }
+    }
+}
+
+fn round_up(n: usize, align: usize) -> usize {
+    n.div_ceil(align) * align
+}
+
+fn primitive_size(subtype: &PrimitiveSubtype) -> usize {
+    match subtype {
+        PrimitiveSubtype::Bool | PrimitiveSubtype::Int8 | PrimitiveSubtype::Uint8 => 1,
+        PrimitiveSubtype::Int16 | PrimitiveSubtype::Uint16 => 2,
+        PrimitiveSubtype::Int32 | PrimitiveSubtype::Uint32 | PrimitiveSubtype::Float32 => 4,
+        PrimitiveSubtype::Int64 | PrimitiveSubtype::Uint64 | PrimitiveSubtype::Float64 => 8,
+    }
+}
+
+impl Libraries {
+    pub(crate) fn layout(&self, ty: &Type) -> Result<Layout, DecodeError> {
+        Ok(match ty {
+            Type::PrimitiveType { primitive_subtype } => {
+                let size = primitive_size(primitive_subtype);
+                Layout::new(size, size)
+            }
+            Type::StringType { .. } | Type::VectorType { .. } => Layout::new(16, 8),
+            Type::ArrayType {
+                element_type,
+                element_count,
+                ..
+            }
+            | Type::StringArray {
+                element_type,
+                element_count,
+                ..
+            } => {
+                let element = self.layout(element_type)?;
+                Layout::new(element.size * *element_count as usize, element.align)
+            }
+            Type::HandleType { .. } | Type::ClientEnd { .. } | Type::ServerEnd { .. } => Layout::new(4, 4),
+            Type::InternalType { .. } => Layout::new(4, 4),
+            Type::IdentifierType {
+                identifier, nullable, ..
+            } => {
+                if let Some(decl) = self.structs.get(identifier) {
+                    if *nullable {
+                        Layout::new(8, 8)
+                    } else {
+                        self.struct_layout(decl)?.1
+                    }
+                } else if let Some(decl) = self.enums.get(identifier) {
+                    let size = primitive_size(&decl.r#type);
+                    Layout::new(size, size)
+                } else if self.unions.contains_key(identifier) || self.tables.contains_key(identifier) {
+                    Layout::new(16, 8)
+                } else {
+                    return Err(DecodeError::UnknownDeclaration(identifier.0.clone()));
+                }
+            }
+        })
+    }
+
+    /// Returns the offsets of the members of `decl` and its layout.
+    pub(crate) fn struct_layout(&self, decl: &midlgen::ir::Struct) -> Result<(Vec<usize>, Layout), DecodeError> {
+        if decl.members.is_empty() {
+            return Ok((vec![], Layout::new(1, 1)));
+        }
+        let mut offsets = Vec::with_capacity(decl.members.len());
+        let mut size = 0;
+        let mut align = 1;
+        for member in decl.members.iter() {
+            let layout = self.layout(&member.r#type)?;
+            size = round_up(size, layout.align);
+            offsets.push(size);
+            size += layout.size;
+            align = align.max(layout.align);
+        }
+        Ok((offsets, Layout::new(round_up(size, align), align)))
+    }
+}
+
+/// Decodes one message body.
+pub(crate) struct Decoder<'a> {
+    libs: &'a Libraries,
+    buf: &'a [u8],
+    next_out_of_line: usize,
+    next_handle: u32,
+}
+
+impl<'a> Decoder<'a> {
+    /// Decodes `buf` as a value of type `ty`, or as an empty payload if `ty`
+    /// is `None`.
+    pub(crate) fn decode_body(libs: &'a Libraries, buf: &'a [u8], ty: Option<&Type>) -> Result<Value, DecodeError> {
+        let Some(ty) = ty else {
+            if !buf.is_empty() {
+                return Err(DecodeError::Invalid {
+                    offset: 0,
+                    message: "expected an empty body".to_string(),
+                });
+            }
+            return Ok(Value::Struct(vec![]));
+        };
+        let next_out_of_line = round_up(libs.layout(ty)?.size, 8);
+        if next_out_of_line > buf.len() {
+            return Err(DecodeError::OutOfRange {
+                offset: 0,
+                len: next_out_of_line,
+            });
+        }
+        let mut decoder = Decoder {
+            libs,
+            buf,
+            next_out_of_line,
+            next_handle: 0,
+        };
+        let value = decoder.decode(ty, 0)?;
+        if decoder.next_out_of_line != buf.len() {
+            return Err(DecodeError::Invalid {
+                offset: decoder.next_out_of_line,
+                message: format!("{} trailing bytes", buf.len() - decoder.next_out_of_line),
+            });
+        }
+        Ok(value)
+    }
+
+    fn invalid<T>(offset: usize, message: impl Into<String>) -> Result<T, DecodeError> {
+        Err(DecodeError::Invalid {
+            offset,
+            message: message.into(),
+        })
+    }
+
+    fn read<const N: usize>(&self, offset: usize) -> Result<[u8; N], DecodeError> {
+        self.buf
+            .get(offset..offset + N)
+            .map(|bytes| bytes.try_into().unwrap())
+            .ok_or(DecodeError::OutOfRange { offset, len: N })
+    }
+
+    fn read_u16(&self, offset: usize) -> Result<u16, DecodeError> {
+        Ok(u16::from_le_bytes(self.read(offset)?))
+    }
+
+    fn read_u32(&self, offset: usize) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.read(offset)?))
+    }
+
+    fn read_u64(&self, offset: usize) -> Result<u64, DecodeError> {
+        Ok(u64::from_le_bytes(self.read(offset)?))
+    }
+
+    /// Claims the next out-of-line block of `len` bytes and returns its offset.
+    fn claim(&mut self, len: usize) -> Result<usize, DecodeError> {
+        let offset = self.next_out_of_line;
+        let end = offset + round_up(len, 8);
+        if end > self.buf.len() {
+            return Err(DecodeError::OutOfRange { offset, len });
+        }
+        self.next_out_of_line = end;
+        Ok(offset)
+    }
+
+    fn take_handle(&mut self) -> Value {
+        let index = self.next_handle;
+        self.next_handle += 1;
+        Value::Handle(index)
+    }
+
+    fn decode(&mut self, ty: &Type, offset: usize) -> Result<Value, DecodeError> {
+        match ty {
+            Type::PrimitiveType { primitive_subtype } => self.decode_primitive(primitive_subtype, offset),
+            Type::StringType { nullable, .. } => {
+                let Some((len, data)) = self.decode_vector_header(offset, *nullable, 1)? else {
+                    return Ok(Value::Null);
+                };
+                match std::str::from_utf8(&self.buf[data..data + len]) {
+                    Ok(s) => Ok(Value::String(s.to_string())),
+                    Err(_) => Self::invalid(data, "string is not valid UTF-8"),
+                }
+            }
+            Type::VectorType {
+                element_type, nullable, ..
+            } => {
+                let stride = self.libs.layout(element_type)?.size;
+                let Some((len, data)) = self.decode_vector_header(offset, *nullable, stride)? else {
+                    return Ok(Value::Null);
+                };
+                self.decode_elements(element_type, data, len, stride)
+            }
+            Type::ArrayType {
+                element_type,
+                element_count,
+                ..
+            }
+            | Type::StringArray {
+                element_type,
+                element_count,
+                ..
+            } => {
+                let stride = self.libs.layout(element_type)?.size;
+                self.decode_elements(element_type, offset, *element_count as usize, stride)
+            }
+            Type::HandleType { nullable, .. } | Type::ServerEnd { nullable, .. } | Type::ClientEnd { nullable, .. } => {
+                match self.read_u32(offset)? {
+                    HANDLE_PRESENT => Ok(self.take_handle()),
+                    HANDLE_ABSENT if *nullable => Ok(Value::Null),
+                    HANDLE_ABSENT => Self::invalid(offset, "required handle is absent"),
+                    _ => Self::invalid(offset, "invalid handle presence indicator"),
+                }
+            }
+            Type::InternalType { .. } => Ok(Value::Int(i32::from_le_bytes(self.read(offset)?) as i64)),
+            Type::IdentifierType {
+                identifier, nullable, ..
+            } => {
+                let libs = self.libs;
+                if let Some(decl) = libs.structs.get(identifier) {
+                    if !*nullable {
+                        return self.decode_struct(decl, offset);
+                    }
+                    match self.read_u64(offset)? {
+                        ALLOC_PRESENT => {
+                            let size = libs.struct_layout(decl)?.1.size;
+                            let offset = self.claim(size)?;
+                            self.decode_struct(decl, offset)
+                        }
+                        ALLOC_ABSENT => Ok(Value::Null),
+                        _ => Self::invalid(offset, "invalid presence indicator"),
+                    }
+                } else if let Some(decl) = libs.enums.get(identifier) {
+                    let value = match self.decode_primitive(&decl.r#type, offset)? {
+                        Value::Int(v) => v.to_string(),
+                        Value::Uint(v) => v.to_string(),
+                        _ => return Self::invalid(offset, "enum with non-integer underlying type"),
+                    };
+                    let member = decl
+                        .members
+                        .iter()
+                        .find(|m| m.value.value() == value)
+                        .map(|m| m.name.0.clone());
+                    Ok(Value::Enum { member, value })
+                } else if let Some(decl) = libs.unions.get(identifier) {
+                    self.decode_union(decl, *nullable, offset)
+                } else if let Some(decl) = libs.tables.get(identifier) {
+                    self.decode_table(decl, offset)
+                } else {
+                    Err(DecodeError::UnknownDeclaration(identifier.0.clone()))
+                }
+            }
+        }
+    }
+
+    fn decode_primitive(&self, subtype: &PrimitiveSubtype, offset: usize) -> Result<Value, DecodeError> {
+        Ok(match subtype {
+            PrimitiveSubtype::Bool => match self.read::<1>(offset)?[0] {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                _ => return Self::invalid(offset, "invalid bool"),
+            },
+            PrimitiveSubtype::Int8 => Value::Int(i8::from_le_bytes(self.read(offset)?) as i64),
+            PrimitiveSubtype::Int16 => Value::Int(i16::from_le_bytes(self.read(offset)?) as i64),
+            PrimitiveSubtype::Int32 => Value::Int(i32::from_le_bytes(self.read(offset)?) as i64),
+            PrimitiveSubtype::Int64 => Value::Int(i64::from_le_bytes(self.read(offset)?)),
+            PrimitiveSubtype::Uint8 => Value::Uint(self.read::<1>(offset)?[0] as u64),
+            PrimitiveSubtype::Uint16 => Value::Uint(self.read_u16(offset)? as u64),
+            PrimitiveSubtype::Uint32 => Value::Uint(self.read_u32(offset)? as u64),
+            PrimitiveSubtype::Uint64 => Value::Uint(self.read_u64(offset)?),
+            PrimitiveSubtype::Float32 => Value::Float(f32::from_le_bytes(self.read(offset)?) as f64),
+            PrimitiveSubtype::Float64 => Value::Float(f64::from_le_bytes(self.read(offset)?)),
+        })
+    }
+
+    /// Decodes a string or vector header and claims its out-of-line block.
+    /// Returns the element count and block offset, or `None` if absent.
+    fn decode_vector_header(
+        &mut self,
+        offset: usize,
+        nullable: bool,
+        stride: usize,
+    ) -> Result<Option<(usize, usize)>, DecodeError> {
+        let len = self.read_u64(offset)?;
+        match self.read_u64(offset + 8)? {
+            ALLOC_PRESENT => {}
+            ALLOC_ABSENT if len != 0 => return Self::invalid(offset, "absent vector with non-zero length"),
+            ALLOC_ABSENT if nullable => return Ok(None),
+            ALLOC_ABSENT => return Self::invalid(offset, "required vector or string is absent"),
+            _ => return Self::invalid(offset + 8, "invalid presence indicator"),
+        }
+        let len = usize::try_from(len).map_err(|_| DecodeError::OutOfRange {
+            offset,
+            len: usize::MAX,
+        })?;
+        let bytes = len.checked_mul(stride).ok_or(DecodeError::OutOfRange {
+            offset,
+            len: usize::MAX,
+        })?;
+        Ok(Some((len, self.claim(bytes)?)))
+    }
+
+    fn decode_elements(&mut self, ty: &Type, offset: usize, len: usize, stride: usize) -> Result<Value, DecodeError> {
+        let mut items = Vec::with_capacity(len);
+        for i in 0..len {
+            items.push(self.decode(ty, offset + i * stride)?);
+        }
+        Ok(Value::List(items))
+    }
+
+    fn decode_struct(&mut self, decl: &midlgen::ir::Struct, offset: usize) -> Result<Value, DecodeError> {
+        let (offsets, _) = self.libs.struct_layout(decl)?;
+        let mut fields = Vec::with_capacity(decl.members.len());
+        for (member, member_offset) in decl.members.iter().zip(offsets) {
+            fields.push((
+                member.name.0.clone(),
+                self.decode(&member.r#type, offset + member_offset)?,
+            ));
+        }
+        Ok(Value::Struct(fields))
+    }
+
+    fn decode_union(&mut self, decl: &midlgen::ir::Union, nullable: bool, offset: usize) -> Result<Value, DecodeError> {
+        let ordinal = self.read_u64(offset)?;
+        if ordinal == 0 {
+            return match self.decode_envelope(None, 0, offset + 8)? {
+                None if nullable => Ok(Value::Null),
+                None => Self::invalid(offset, "required union is absent"),
+                Some(_) => Self::invalid(offset, "union with ordinal 0 has a value"),
+            };
+        }
+        let member = decl.members.iter().find(|m| m.ordinal == ordinal);
+        let ty = member.and_then(|m| m.r#type.as_ref());
+        let Some(value) = self.decode_envelope(ty, ordinal, offset + 8)? else {
+            return Self::invalid(offset + 8, "union envelope is empty");
+        };
+        match member.and_then(|m| m.name.as_ref()) {
+            Some(name) if ty.is_some() => Ok(Value::Union {
+                member: name.0.clone(),
+                value: Box::new(value),
+            }),
+            _ => Ok(value),
+        }
+    }
+
+    fn decode_table(&mut self, decl: &midlgen::ir::Table, offset: usize) -> Result<Value, DecodeError> {
+        let Some((len, envelopes)) = self.decode_vector_header(offset, false, 8)? else {
+            unreachable!("tables are not nullable");
+        };
+        let mut fields = vec![];
+        for i in 0..len {
+            let ordinal = i as u64 + 1;
+            let member = decl.members.iter().find(|m| m.ordinal as u64 == ordinal && !m.reserved);
+            let ty = member.and_then(|m| m.r#type.as_ref());
+            if let Some(value) = self.decode_envelope(ty, ordinal, envelopes + i * 8)? {
+                let name = match member.and_then(|m| m.name.as_ref()) {
+                    Some(name) if ty.is_some() => name.0.clone(),
+                    _ => ordinal.to_string(),
+                };
+                fields.push((name, value));
+            }
+        }
+        Ok(Value::Table(fields))
+    }
+
+    /// Decodes the envelope at `offset`, or skips over it if `ty` is `None`.
+    /// Returns `None` if the envelope is empty.
+    fn decode_envelope(
+        &mut self,
+        ty: Option<&Type>,
+        ordinal: u64,
+        offset: usize,
+    ) -> Result<Option<Value>, DecodeError> {
+        let num_bytes = self.read_u32(offset)?;
+        let num_handles = self.read_u16(offset + 4)? as u32;
+        let inlined = match self.read_u16(offset + 6)? {
+            0 => false,
+            1 => true,
+            _ => return Self::invalid(offset + 6, "invalid envelope flags"),
+        };
+        if !inlined && num_bytes == 0 && num_handles == 0 {
+            return Ok(None);
+        }
+        if !inlined && num_bytes % 8 != 0 {
+            return Self::invalid(offset, "envelope size is not a multiple of 8");
+        }
+        let Some(ty) = ty else {
+            if !inlined {
+                self.claim(num_bytes as usize)?;
+            }
+            self.next_handle += num_handles;
+            let num_bytes = if inlined { 4 } else { num_bytes };
+            return Ok(Some(Value::Unknown {
+                ordinal,
+                num_bytes,
+                num_handles,
+            }));
+        };
+        if inlined {
+            return self.decode(ty, offset).map(Some);
+        }
+        let start = self.next_out_of_line;
+        let value_offset = self.claim(self.libs.layout(ty)?.size)?;
+        let value = self.decode(ty, value_offset)?;
+        if self.next_out_of_line - start != num_bytes as usize {
+            return Self::invalid(
+                offset,
+                format!(
+                    "envelope claims {} bytes but its value used {}",
+                    num_bytes,
+                    self.next_out_of_line - start
+                ),
+            );
+        }
+        Ok(Some(value))
+    }
+}
@@ -0,0 +1,331 @@
+// Copyright 2024 MeshX Authors. All rights reserved.
+
+//! Decoding of MIDL messages at runtime from midlc JSON IR, without generated
+//! bindings.
+//!
+//! [`Libraries`] indexes the declarations of one or more libraries and decodes
+//! transactional messages into [`Value`]s by looking up their ordinal. It also
+//! implements `midl::trace::MessageFormatter`, so a host program can log the
+//! messages its clients and servers exchange in readable form:
+//!
+//! ```
+//! # fn main() -> Result<(), midlcat::DecodeError> {
+//! let libs = midlcat::Libraries::load(&["testdata/store.test.json"])?;
+//! midl::trace::set_message_formatter(libs);
+//! # Ok(())
+//! # }
+//! ```
+
+mod decode;
+mod value;
+
+use std::{collections::HashMap, fmt, path::Path};
+
+use midl::{encoding::TransactionHeader, trace::MessageKind};
+use midlgen::ir;
+use thiserror::Error;
+
+pub use value::Value;
+
+#[derive(Error, Debug)]
+pub enum DecodeError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid JSON IR")]
+    Json(#[from] serde_json::Error),
+
+    #[error("invalid transaction header: {0}")]
+    Header(#[source] midl::Error),
+
+    #[error("no loaded library has a method with ordinal {0:#x}")]
+    UnknownOrdinal(u64),
+
+    #[error("no loaded library declares {0}")]
+    UnknownDeclaration(String),
+
+    #[error("offset {offset}: message too short to read {len} bytes")]
+    OutOfRange { offset: usize, len: usize },
+
+    #[error("offset {offset}: {message}")]
+    Invalid { offset: usize, message: String },
+}
+
+/// A method and the protocol declaring it.
+struct Method {
+    protocol: ir::EncodedCompoundIdentifier,
+    method: ir::ProtocolMethod,
+}
+
+/// The declarations of a set of MIDL libraries.
+#[derive(Default)]
+pub struct Libraries {
+    structs: HashMap<ir::EncodedCompoundIdentifier, ir::Struct>,
+    enums: HashMap<ir::EncodedCompoundIdentifier, ir::Enum>,
+    unions: HashMap<ir::EncodedCompoundIdentifier, ir::Union>,
+    tables: HashMap<ir::EncodedCompoundIdentifier, ir::Table>,
+    methods: HashMap<u64, Method>,
+}
+
+/// A decoded transactional message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    /// The method, as `library/Protocol.Method`.
+    pub method: String,
+    pub kind: MessageKind,
+    pub tx_id: u32,
+    pub ordinal: u64,
+    pub body: Value,
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} {} (tx_id {}, ordinal {:#x})",
+            self.method, self.kind, self.tx_id, self.ordinal
+        )?;
+        write!(f, "{}", self.body)
+    }
+}
+
+impl Libraries {
+    /// Indexes the declarations of `roots`.
+    pub fn new(roots: impl IntoIterator<Item = ir::Root>) -> Self {
+        let mut libs = Libraries::default();
+        for root in roots {
+            libs.structs
+                .extend(root.struct_declarations.into_iter().map(|d| (d.name.clone(), d)));
+            libs.enums
+                .extend(root.enum_declarations.into_iter().map(|d| (d.name.clone(), d)));
+            libs.unions
+                .extend(root.union_declarations.into_iter().map(|d| (d.name.clone(), d)));
+            libs.tables
+                .extend(root.table_declarations.into_iter().map(|d| (d.name.clone(), d)));
+            for protocol in root.protocol_declarations {
+                for method in protocol.methods {
+                    libs.methods.insert(
+                        method.ordinal,
+                        Method {
+                            protocol: protocol.name.clone(),
+                            method,
+                        },
+                    );
+                }
+            }
+        }
+        libs
+    }
+
+    /// Reads and indexes the JSON IR files at `paths`.
+    pub fn load(paths: &[impl AsRef<Path>]) -> Result<Self, DecodeError> {
+        let mut roots = Vec::with_capacity(paths.len());
+        for path in paths {
+            let file = std::fs::File::open(path)?;
+            roots.push(serde_json::from_reader(std::io::BufReader::new(file))?);
+        }
+        Ok(Self::new(roots))
+    }
+
+    /// Decodes a whole transactional message. `kind` tells requests from
+    /// responses of two-way methods; messages of events are always decoded as
+    /// events.
+    pub fn decode_message(&self, bytes: &[u8], kind: MessageKind) -> Result<Message, DecodeError> {
+        let (header, body) = midl::encoding::decode_transaction_header(bytes).map_err(DecodeError::Header)?;
+        self.decode_body(&header, body, kind)
+    }
+
+    /// Decodes the body of a transactional message with the given header.
+    pub fn decode_body(
+        &self,
+        header: &TransactionHeader,
+        body: &[u8],
+        kind: MessageKind,
+    ) -> Result<Message, DecodeError> {
+        let Method { protocol, method } = self
+            .methods
+            .get(&header.ordinal())
+            .ok_or(DecodeError::UnknownOrdinal(header.ordinal()))?;
+        let (kind, payload) = match (&method.kind, kind) {
+            (ir::MethodKind::Event, _) => (MessageKind::Event, method.response_payload.as_ref()),
+            (_, MessageKind::Request) => (MessageKind::Request, method.request_payload.as_ref()),
+            (_, kind) => (kind, method.response_payload.as_ref()),
+        };
+        Ok(Message {
+            method: format!("{}.{}", protocol.0, method.name),
+            kind,
+            tx_id: header.tx_id(),
+            ordinal: header.ordinal(),
+            body: decode::Decoder::decode_body(self, body, payload)?,
+        })
+    }
+}
+
+impl midl::trace::MessageFormatter for Libraries {
+    fn format(&self, _protocol: &str, kind: MessageKind, header: &TransactionHeader, body: &[u8]) -> Option<String> {
+        match self.decode_body(header, body, kind) {
+            Ok(message) => Some(message.body.to_string()),
+            Err(DecodeError::UnknownOrdinal(_)) => None,
+            Err(e) => Some(format!("<{}>\n{}", e, midl::trace::hex_dump(body))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use midl::trace::MessageFormatter;
+
+    const ADD: u64 = 0x1a2b3c4d5e6f7081;
+    const FIND: u64 = 0x2b3c4d5e6f708192;
+    const ON_EMPTIED: u64 = 0x3c4d5e6f708192a3;
+
+    fn libraries() -> Libraries {
+        Libraries::load(&[concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/store.test.json")]).unwrap()
+    }
+
+    /// A V2 transactional message with the given header fields and body.
+    fn message(tx_id: u32, ordinal: u64, body: &[u8]) -> Vec<u8> {
+        let mut bytes = tx_id.to_le_bytes().to_vec();
+        bytes.extend([2, 0, 0, 1]);
+        bytes.extend(ordinal.to_le_bytes());
+        bytes.extend(body);
+        bytes
+    }
+
+    fn string_header(len: u64) -> Vec<u8> {
+        [len.to_le_bytes(), u64::MAX.to_le_bytes()].concat()
+    }
+
+    fn padded(bytes: &[u8]) -> Vec<u8> {
+        let mut out = bytes.to_vec();
+        out.resize(bytes.len().div_ceil(8) * 8, 0);
+        out
+    }
+
+    /// `Add` with an item named "apple" and details with the note "fresh".
+    fn add_body() -> Vec<u8> {
+        let mut body = string_header(5);
+        body.extend(3u32.to_le_bytes());
+        body.extend([2, 0, 0, 0]);
+        body.extend(string_header(1));
+        body.extend(padded(b"apple"));
+        body.extend([24, 0, 0, 0, 0, 0, 0, 0]);
+        body.extend(string_header(5));
+        body.extend(padded(b"fresh"));
+        body
+    }
+
+    #[test]
+    fn request() {
+        let message = libraries()
+            .decode_message(&message(0, ADD, &add_body()), MessageKind::Request)
+            .unwrap();
+
+        assert_eq!(message.method, "test.store/Store.Add");
+        assert_eq!(message.kind, MessageKind::Request);
+        assert_eq!(
+            message.to_string(),
+            "test.store/Store.Add request (tx_id 0, ordinal 0x1a2b3c4d5e6f7081)\n\
+             {\n    \
+                 item: {\n        \
+                     name: \"apple\",\n        \
+                     count: 3,\n        \
+                     color: GREEN,\n    \
+                 },\n    \
+                 details: {\n        \
+                     note: \"fresh\",\n    \
+                 },\n\
+             }"
+        );
+    }
+
+    #[test]
+    fn response_of_two_way_method() {
+        let libs = libraries();
+
+        // The error variant of the result union, inlined in its envelope.
+        let mut body = 2u64.to_le_bytes().to_vec();
+        body.extend(7u32.to_le_bytes());
+        body.extend([0, 0, 1, 0]);
+
+        let response = libs
+            .decode_message(&message(5, FIND, &body), MessageKind::Response)
+            .unwrap();
+        assert_eq!(response.tx_id, 5);
+        assert_eq!(
+            response.body,
+            Value::Union {
+                member: "err".to_string(),
+                value: Box::new(Value::Uint(7)),
+            }
+        );
+
+        // The same ordinal decodes as the request when asked to.
+        let mut body = string_header(4);
+        body.extend(padded(b"pear"));
+        let request = libs
+            .decode_message(&message(5, FIND, &body), MessageKind::Request)
+            .unwrap();
+        assert_eq!(
+            request.body,
+            Value::Struct(vec![("name".to_string(), Value::String("pear".to_string()))])
+        );
+    }
+
+    #[test]
+    fn event() {
+        let message = libraries()
+            .decode_message(&message(0, ON_EMPTIED, &[]), MessageKind::Response)
+            .unwrap();
+
+        assert_eq!(message.kind, MessageKind::Event);
+        assert_eq!(message.body, Value::Struct(vec![]));
+    }
+
+    #[test]
+    fn errors() {
+        let libs = libraries();
+
+        assert!(matches!(
+            libs.decode_message(&message(0, 0x42, &[]), MessageKind::Request),
+            Err(DecodeError::UnknownOrdinal(0x42))
+        ));
+        assert!(matches!(
+            libs.decode_message(&[0; 8], MessageKind::Request),
+            Err(DecodeError::Header(_))
+        ));
+
+        let mut body = add_body();
+        body.extend([0; 8]);
+        assert!(matches!(
+            libs.decode_message(&message(0, ADD, &body), MessageKind::Request),
+            Err(DecodeError::Invalid { offset: 80, .. })
+        ));
+
+        let body = add_body();
+        assert!(matches!(
+            libs.decode_message(&message(0, ADD, &body[..72]), MessageKind::Request),
+            Err(DecodeError::OutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn formatter() {
+        let libs = libraries();
+        let format = |ordinal: u64, body: &[u8]| {
+            let bytes = message(0, ordinal, body);
+            let (header, body) = midl::encoding::decode_transaction_header(&bytes).unwrap();
+            libs.format("test.store.Store", MessageKind::Request, &header, body)
+        };
+
+        assert_eq!(format(FIND, &string_header(0)).unwrap(), "{\n    name: \"\",\n}");
+        assert_eq!(format(0x42, &[]), None);
+
+        let invalid = format(ON_EMPTIED, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        assert!(
+            invalid.starts_with("<offset 0: expected an empty body>\n01 02 03"),
+            "{invalid}"
+        );
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright 2024 MeshX Authors. All rights reserved.
+
+//! midlcat pretty-prints a MIDL transactional message from a hex dump, using
+//! the JSON IR of the library declaring it:
+//!
+//! ```text
+//! midlcat --json meshx.data.json dump.txt
+//! echo "01 00 00 00 ..." | midlcat --json meshx.data.json --response
+//! ```
+//!
+//! Bytes may be separated by whitespace or commas and prefixed with `0x`, so
+//! the output of `midl::trace::hex_dump` and `xxd -p` are both accepted.
+
+use std::{io::Read, path::PathBuf};
+
+use clap::Parser;
+use midl::trace::MessageKind;
+use midlcat::{DecodeError, Libraries};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+enum CliError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Decode(#[from] DecodeError),
+
+    #[error("invalid hex: {0:?}")]
+    Hex(String),
+}
+
+#[derive(clap::Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+#[command(name = "MIDL message decoder")]
+struct Args {
+    /// JSON IR of the libraries declaring the message's protocol
+    #[arg(short, long, num_args = 1.., required = true)]
+    json: Vec<PathBuf>,
+
+    /// Decode a two-way method's message as its response rather than its request
+    #[arg(long)]
+    response: bool,
+
+    /// File containing the hex dump; read from stdin if omitted
+    input: Option<PathBuf>,
+}
+
+fn parse_hex(text: &str) -> Result<Vec<u8>, CliError> {
+    let mut bytes = vec![];
+    for token in text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|t| !t.is_empty())
+    {
+        let digits = token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_prefix("0X"))
+            .unwrap_or(token);
+        if digits.len() % 2 != 0 || !digits.is_ascii() {
+            return Err(CliError::Hex(token.to_string()));
+        }
+        for i in (0..digits.len()).step_by(2) {
+            let byte = u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| CliError::Hex(token.to_string()))?;
+            bytes.push(byte);
+        }
+    }
+    Ok(bytes)
+}
+
+fn main() -> Result<(), CliError> {
+    let args = Args::parse();
+
+    let libs = Libraries::load(&args.json)?;
+    let text = match &args.input {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text)?;
+            text
+        }
+    };
+    let kind = if args.response {
+        MessageKind::Response
+    } else {
+        MessageKind::Request
+    };
+    let message = libs.decode_message(&parse_hex(&text)?, kind)?;
+    println!("{}", message);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex() {
+        let dump = midl::trace::hex_dump(&[0x01, 0xab, 0x00]);
+        assert_eq!(parse_hex(&dump).unwrap(), [0x01, 0xab, 0x00]);
+        assert_eq!(parse_hex("0x01,0XAB\n00ff").unwrap(), [0x01, 0xab, 0x00, 0xff]);
+        assert!(matches!(parse_hex("01 2"), Err(CliError::Hex(token)) if token == "2"));
+        assert!(matches!(parse_hex("zz"), Err(CliError::Hex(_))));
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2024 MeshX Authors. All rights reserved.
+
+use std::fmt;
+
+/// A decoded MIDL value, independent of any generated bindings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// An absent optional string, vector, box, union or handle.
+    Null,
+    Bool(bool),
+    Int(i64),
+    Uint(u64),
+    Float(f64),
+    String(String),
+    /// An enum value, with the name of its member if it has one.
+    Enum {
+        member: Option<String>,
+        value: String,
+    },
+    /// A handle, as its index in the message's handle array.
+    Handle(u32),
+    List(Vec<Value>),
+    Struct(Vec<(String, Value)>),
+    /// The fields of a table which are present.
+    Table(Vec<(String, Value)>),
+    Union {
+        member: String,
+        value: Box<Value>,
+    },
+    /// A union variant or table field the IR does not know about.
+    Unknown {
+        ordinal: u64,
+        num_bytes: u32,
+        num_handles: u32,
+    },
+}
+
+impl Value {
+    fn is_scalar(&self) -> bool {
+        !matches!(
+            self,
+            Value::List(_) | Value::Struct(_) | Value::Table(_) | Value::Union { .. }
+        )
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("null"),
+            Value::Bool(v) => write!(f, "{}", v),
+            Value::Int(v) => write!(f, "{}", v),
+            Value::Uint(v) => write!(f, "{}", v),
+            Value::Float(v) => write!(f, "{}", v),
+            Value::String(v) => write!(f, "{:?}", v),
+            Value::Enum {
+                member: Some(member), ..
+            } => f.write_str(member),
+            Value::Enum { member: None, value } => write!(f, "<unknown {}>", value),
+            Value::Handle(index) => write!(f, "handle#{}", index),
+            Value::Unknown {
+                ordinal,
+                num_bytes,
+                num_handles,
+            } => {
+                write!(
+                    f,
+                    "<unknown ordinal {}: {} bytes, {} handles>",
+                    ordinal, num_bytes, num_handles
+                )
+            }
+            Value::List(items) if items.iter().all(Value::is_scalar) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    item.write(f, indent)?;
+                }
+                f.write_str("]")
+            }
+            Value::List(items) => {
+                f.write_str("[\n")?;
+                for item in items {
+                    write!(f, "{:width$}", "", width = (indent + 1) * 4)?;
+                    item.write(f, indent + 1)?;
+                    f.write_str(",\n")?;
+                }
+                write!(f, "{:width$}]", "", width = indent * 4)
+            }
+            Value::Struct(fields) | Value::Table(fields) => write_fields(f, fields.iter().map(|(k, v)| (k, v)), indent),
+            Value::Union { member, value } => write_fields(f, std::iter::once((member, value.as_ref())), indent),
+        }
+    }
+}
+
+fn write_fields<'a>(
+    f: &mut fmt::Formatter<'_>,
+    fields: impl ExactSizeIterator<Item = (&'a String, &'a Value)>,
+    indent: usize,
+) -> fmt::Result {
+    if fields.len() == 0 {
+        return f.write_str("{}");
+    }
+    f.write_str("{\n")?;
+    for (name, value) in fields {
+        write!(f, "{:width$}{}: ", "", name, width = (indent + 1) * 4)?;
+        value.write(f, indent + 1)?;
+        f.write_str(",\n")?;
+    }
+    write!(f, "{:width$}}}", "", width = indent * 4)
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
@@ -0,0 +1,92 @@
+use std::cell::Cell;
+use std::path::Path;
+
+use crate::types::{BreakKind, Finding};
+use crate::CompatError;
+
+/// An accepted breaking change, written as `<kind> <target>` on its own line, e.g.
+/// `method-removed meshx.io/Directory.Rewind`. Targets name a declaration or one of its
+/// members; an entry for a declaration does not cover its members.
+#[derive(Debug)]
+struct Entry {
+    kind: BreakKind,
+    target: String,
+    line: usize,
+    used: Cell<bool>,
+}
+
+/// The set of breaking changes a release has knowingly accepted.
+#[derive(Debug, Default)]
+pub struct Allowlist {
+    entries: Vec<Entry>,
+}
+
+impl Allowlist {
+    pub fn load(path: &Path) -> Result<Self, CompatError> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::parse(&contents)
+    }
+
+    /// Parses an allowlist. Blank lines and everything after a `#` are ignored.
+    pub fn parse(contents: &str) -> Result<Self, CompatError> {
+        let mut entries = vec![];
+
+        for (idx, line) in contents.lines().enumerate() {
+            let line_number = idx + 1;
+            let line = line.split('#').next().unwrap().trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let (Some(kind), Some(target), None) = (parts.next(), parts.next(), parts.next()) else {
+                return Err(CompatError::Allowlist {
+                    line: line_number,
+                    reason: "expected `<kind> <target>`".to_owned(),
+                });
+            };
+
+            let Some(kind) = BreakKind::from_name(kind) else {
+                return Err(CompatError::Allowlist {
+                    line: line_number,
+                    reason: format!("unknown kind `{kind}`"),
+                });
+            };
+
+            entries.push(Entry {
+                kind,
+                target: target.to_owned(),
+                line: line_number,
+                used: Cell::new(false),
+            });
+        }
+
+        Ok(Allowlist { entries })
+    }
+
+    /// Marks the findings accepted by an entry as allowed.
+    pub fn apply(&self, findings: &mut [Finding]) {
+        for finding in findings.iter_mut() {
+            let target = finding.target();
+
+            if let Some(entry) = self
+                .entries
+                .iter()
+                .find(|entry| entry.kind == finding.kind && entry.target == target)
+            {
+                entry.used.set(true);
+                finding.allowed = true;
+            }
+        }
+    }
+
+    /// Entries that did not match any finding; they are usually left over from an
+    /// earlier release and can be deleted.
+    pub fn unused(&self) -> impl Iterator<Item = String> + '_ {
+        self.entries
+            .iter()
+            .filter(|entry| !entry.used.get())
+            .map(|entry| format!("line {}: {} {}", entry.line, entry.kind.name(), entry.target))
+    }
+}
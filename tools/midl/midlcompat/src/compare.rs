@@ -0,0 +1,686 @@
+use std::collections::BTreeMap;
+
+use midlgen::ir;
+
+use crate::types::{BreakKind, Finding, Severity};
+
+/// A declaration of any kind, looked up by its fully-qualified name.
+#[derive(Copy, Clone)]
+enum Decl<'a> {
+    Const(&'a ir::Const),
+    Enum(&'a ir::Enum),
+    Bits(&'a ir::Bits),
+    Struct(&'a ir::Struct),
+    Table(&'a ir::Table),
+    Union(&'a ir::Union),
+    Protocol(&'a ir::Protocol),
+}
+
+impl Decl<'_> {
+    fn kind(&self) -> &'static str {
+        match self {
+            Decl::Const(_) => "const",
+            Decl::Enum(_) => "enum",
+            Decl::Bits(_) => "bits",
+            Decl::Struct(_) => "struct",
+            Decl::Table(_) => "table",
+            Decl::Union(_) => "union",
+            Decl::Protocol(_) => "protocol",
+        }
+    }
+}
+
+fn declarations(root: &ir::Root) -> BTreeMap<&str, Decl<'_>> {
+    let mut decls = BTreeMap::new();
+
+    for decl in root.const_declarations.iter() {
+        decls.insert(decl.name.0.as_str(), Decl::Const(decl));
+    }
+    for decl in root.enum_declarations.iter() {
+        decls.insert(decl.name.0.as_str(), Decl::Enum(decl));
+    }
+    for decl in root.bits_declarations.iter() {
+        decls.insert(decl.name.0.as_str(), Decl::Bits(decl));
+    }
+    for decl in root.struct_declarations.iter() {
+        decls.insert(decl.name.0.as_str(), Decl::Struct(decl));
+    }
+    for decl in root.table_declarations.iter() {
+        decls.insert(decl.name.0.as_str(), Decl::Table(decl));
+    }
+    for decl in root.union_declarations.iter() {
+        decls.insert(decl.name.0.as_str(), Decl::Union(decl));
+    }
+    for decl in root.protocol_declarations.iter() {
+        decls.insert(decl.name.0.as_str(), Decl::Protocol(decl));
+    }
+
+    decls
+}
+
+/// Reports every change between `old` and `new` that breaks peers or code built against `old`.
+/// Additions are never reported; they are compatible by construction.
+pub fn compare(old: &ir::Root, new: &ir::Root) -> Vec<Finding> {
+    let mut checker = Checker { findings: vec![] };
+
+    let new_decls = declarations(new);
+
+    for (name, old_decl) in declarations(old) {
+        let Some(new_decl) = new_decls.get(name) else {
+            let severity = match old_decl {
+                Decl::Protocol(_) => Severity::Abi,
+                _ => Severity::Source,
+            };
+            checker.report(
+                BreakKind::DeclRemoved,
+                severity,
+                name,
+                None,
+                format!("{} was removed", old_decl.kind()),
+            );
+            continue;
+        };
+
+        match (old_decl, *new_decl) {
+            (Decl::Const(old), Decl::Const(new)) => checker.compare_const(name, old, new),
+            (Decl::Enum(old), Decl::Enum(new)) => checker.compare_enum(name, old, new),
+            (Decl::Bits(old), Decl::Bits(new)) => checker.compare_bits(name, old, new),
+            (Decl::Struct(old), Decl::Struct(new)) => checker.compare_struct(name, old, new),
+            (Decl::Table(old), Decl::Table(new)) => checker.compare_table(name, old, new),
+            (Decl::Union(old), Decl::Union(new)) => checker.compare_union(name, old, new),
+            (Decl::Protocol(old), Decl::Protocol(new)) => checker.compare_protocol(name, old, new),
+            (old_decl, new_decl) => checker.report(
+                BreakKind::DeclKindChanged,
+                Severity::Abi,
+                name,
+                None,
+                format!("changed from {} to {}", old_decl.kind(), new_decl.kind()),
+            ),
+        }
+    }
+
+    let mut findings = checker.findings;
+    findings.sort_by(|a, b| (&a.decl, &a.member, a.kind).cmp(&(&b.decl, &b.member, b.kind)));
+    findings
+}
+
+struct Checker {
+    findings: Vec<Finding>,
+}
+
+impl Checker {
+    fn report(&mut self, kind: BreakKind, severity: Severity, decl: &str, member: Option<&str>, message: String) {
+        self.findings.push(Finding {
+            kind,
+            severity,
+            decl: decl.to_owned(),
+            member: member.map(str::to_owned),
+            message,
+            allowed: false,
+        });
+    }
+
+    fn compare_resourceness(&mut self, decl: &str, old: ir::Resourceness, new: ir::Resourceness) {
+        if old.is_resource_type() != new.is_resource_type() {
+            self.report(
+                BreakKind::ResourcenessChanged,
+                Severity::Source,
+                decl,
+                None,
+                format!("changed from {} to {}", resourceness(old), resourceness(new)),
+            );
+        }
+    }
+
+    /// Tightening to strict makes old peers' unknown values fatal; loosening to flexible only
+    /// changes the generated bindings.
+    fn compare_strictness(&mut self, decl: &str, old: bool, new: bool) {
+        if old != new {
+            self.report(
+                BreakKind::StrictnessChanged,
+                if new { Severity::Abi } else { Severity::Source },
+                decl,
+                None,
+                format!("changed from {} to {}", strictness(old), strictness(new)),
+            );
+        }
+    }
+
+    fn compare_const(&mut self, decl: &str, old: &ir::Const, new: &ir::Const) {
+        let (old_type, new_type) = (describe(&old.r#type), describe(&new.r#type));
+        if old_type != new_type {
+            self.report(
+                BreakKind::ConstTypeChanged,
+                Severity::Source,
+                decl,
+                None,
+                format!("type changed from {old_type} to {new_type}"),
+            );
+        }
+
+        let (old_value, new_value) = (constant_value(&old.value), constant_value(&new.value));
+        if old_value != new_value {
+            self.report(
+                BreakKind::ConstValueChanged,
+                Severity::Source,
+                decl,
+                None,
+                format!("value changed from {old_value} to {new_value}"),
+            );
+        }
+    }
+
+    fn compare_enum(&mut self, decl: &str, old: &ir::Enum, new: &ir::Enum) {
+        if old.r#type != new.r#type {
+            self.report(
+                BreakKind::EnumTypeChanged,
+                Severity::Abi,
+                decl,
+                None,
+                format!(
+                    "underlying type changed from {} to {}",
+                    primitive(&old.r#type),
+                    primitive(&new.r#type)
+                ),
+            );
+        }
+
+        self.compare_strictness(decl, old.is_strict, new.is_strict);
+
+        for old_member in old.members.iter() {
+            let name = old_member.name.0.as_str();
+
+            match new.members.iter().find(|m| m.name.0 == name) {
+                None => self.report(
+                    BreakKind::EnumMemberRemoved,
+                    if old.is_strict { Severity::Abi } else { Severity::Source },
+                    decl,
+                    Some(name),
+                    format!("member of {} enum was removed", strictness(old.is_strict)),
+                ),
+                Some(new_member) => {
+                    let (old_value, new_value) = (constant_value(&old_member.value), constant_value(&new_member.value));
+                    if old_value != new_value {
+                        self.report(
+                            BreakKind::EnumMemberValueChanged,
+                            Severity::Abi,
+                            decl,
+                            Some(name),
+                            format!("value changed from {old_value} to {new_value}"),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Bits mirror enums, except that a flexible bits value keeps unknown bits rather than
+    /// carrying an unknown member, so removing a flexible member only breaks the bindings.
+    fn compare_bits(&mut self, decl: &str, old: &ir::Bits, new: &ir::Bits) {
+        let (old_type, new_type) = (describe(&old.r#type), describe(&new.r#type));
+        if old_type != new_type {
+            self.report(
+                BreakKind::BitsTypeChanged,
+                Severity::Abi,
+                decl,
+                None,
+                format!("underlying type changed from {old_type} to {new_type}"),
+            );
+        }
+
+        self.compare_strictness(decl, old.is_strict, new.is_strict);
+
+        for old_member in old.members.iter() {
+            let name = old_member.name.0.as_str();
+
+            match new.members.iter().find(|m| m.name.0 == name) {
+                None => self.report(
+                    BreakKind::BitsMemberRemoved,
+                    if old.is_strict { Severity::Abi } else { Severity::Source },
+                    decl,
+                    Some(name),
+                    format!("member of {} bits was removed", strictness(old.is_strict)),
+                ),
+                Some(new_member) => {
+                    let (old_value, new_value) = (constant_value(&old_member.value), constant_value(&new_member.value));
+                    if old_value != new_value {
+                        self.report(
+                            BreakKind::BitsMemberValueChanged,
+                            Severity::Abi,
+                            decl,
+                            Some(name),
+                            format!("value changed from {old_value} to {new_value}"),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Structs are inline on the wire, so any change to size, alignment, member offsets or
+    /// member types changes the encoding. Members are matched by name, so a reordering is
+    /// reported as changed offsets; names only matter to the bindings.
+    fn compare_struct(&mut self, decl: &str, old: &ir::Struct, new: &ir::Struct) {
+        self.compare_resourceness(decl, old.resourceness, new.resourceness);
+
+        let (old_shape, new_shape) = (&old.type_shape_v2, &new.type_shape_v2);
+        if old_shape.inline_size != new_shape.inline_size || old_shape.alignment != new_shape.alignment {
+            self.report(
+                BreakKind::StructLayoutChanged,
+                Severity::Abi,
+                decl,
+                None,
+                format!(
+                    "inline size {} -> {}, alignment {} -> {}",
+                    old_shape.inline_size, new_shape.inline_size, old_shape.alignment, new_shape.alignment
+                ),
+            );
+        }
+
+        if old.members.len() != new.members.len() {
+            self.report(
+                BreakKind::StructLayoutChanged,
+                Severity::Abi,
+                decl,
+                None,
+                format!(
+                    "member count changed from {} to {}",
+                    old.members.len(),
+                    new.members.len()
+                ),
+            );
+        }
+
+        for old_member in old.members.iter() {
+            let name = old_member.name.0.as_str();
+
+            let Some(new_member) = new.members.iter().find(|m| m.name.0 == name) else {
+                // A member that kept its offset and type under a name the old struct did not
+                // have was renamed; anything else is a removal.
+                let renamed = new.members.iter().find(|m| {
+                    m.field_shape_v2.offset == old_member.field_shape_v2.offset
+                        && describe(&m.r#type) == describe(&old_member.r#type)
+                        && !old.members.iter().any(|o| o.name.0 == m.name.0)
+                });
+
+                match renamed {
+                    Some(new_member) => self.report(
+                        BreakKind::StructMemberRenamed,
+                        Severity::Source,
+                        decl,
+                        Some(name),
+                        format!("renamed to {}", new_member.name),
+                    ),
+                    None => self.report(
+                        BreakKind::StructLayoutChanged,
+                        Severity::Abi,
+                        decl,
+                        Some(name),
+                        format!("member at offset {} was removed", old_member.field_shape_v2.offset),
+                    ),
+                }
+                continue;
+            };
+
+            let (old_offset, new_offset) = (old_member.field_shape_v2.offset, new_member.field_shape_v2.offset);
+            if old_offset != new_offset {
+                self.report(
+                    BreakKind::StructLayoutChanged,
+                    Severity::Abi,
+                    decl,
+                    Some(name),
+                    format!("offset changed from {old_offset} to {new_offset}"),
+                );
+            }
+
+            let (old_type, new_type) = (describe(&old_member.r#type), describe(&new_member.r#type));
+            if old_type != new_type {
+                self.report(
+                    BreakKind::StructLayoutChanged,
+                    Severity::Abi,
+                    decl,
+                    Some(name),
+                    format!("type changed from {old_type} to {new_type}"),
+                );
+            }
+        }
+    }
+
+    /// Table members are identified by ordinal. Removing one is compatible on the wire as
+    /// long as the ordinal is never given a different type.
+    fn compare_table(&mut self, decl: &str, old: &ir::Table, new: &ir::Table) {
+        self.compare_resourceness(decl, old.resource, new.resource);
+
+        for old_member in old.members.iter().filter(|m| !m.reserved) {
+            let (Some(old_name), Some(old_type)) = (&old_member.name, &old_member.r#type) else {
+                continue;
+            };
+            let name = old_name.0.as_str();
+
+            let new_member = new
+                .members
+                .iter()
+                .find(|m| m.ordinal == old_member.ordinal && !m.reserved);
+
+            let Some((new_name, new_type)) = new_member.and_then(|m| m.name.as_ref().zip(m.r#type.as_ref())) else {
+                self.report(
+                    BreakKind::TableMemberRemoved,
+                    Severity::Source,
+                    decl,
+                    Some(name),
+                    format!("member @{} was removed", old_member.ordinal),
+                );
+                continue;
+            };
+
+            let (old_type, new_type) = (describe(old_type), describe(new_type));
+            if old_type != new_type {
+                self.report(
+                    BreakKind::TableOrdinalReused,
+                    Severity::Abi,
+                    decl,
+                    Some(name),
+                    format!(
+                        "ordinal @{} changed from {old_type} to {new_name} {new_type}",
+                        old_member.ordinal
+                    ),
+                );
+            } else if old_name.0 != new_name.0 {
+                self.report(
+                    BreakKind::TableMemberRenamed,
+                    Severity::Source,
+                    decl,
+                    Some(name),
+                    format!("renamed to {new_name}"),
+                );
+            }
+        }
+    }
+
+    fn compare_union(&mut self, decl: &str, old: &ir::Union, new: &ir::Union) {
+        self.compare_resourceness(decl, old.resourceness, new.resourceness);
+        self.compare_strictness(decl, old.strict, new.strict);
+
+        for old_member in old.members.iter() {
+            let (Some(old_name), Some(old_type)) = (&old_member.name, &old_member.r#type) else {
+                continue;
+            };
+            let name = old_name.0.as_str();
+
+            let new_member = new.members.iter().find(|m| m.ordinal == old_member.ordinal);
+
+            let Some((new_name, new_type)) = new_member.and_then(|m| m.name.as_ref().zip(m.r#type.as_ref())) else {
+                self.report(
+                    BreakKind::UnionMemberRemoved,
+                    if old.strict { Severity::Abi } else { Severity::Source },
+                    decl,
+                    Some(name),
+                    format!(
+                        "member @{} of {} union was removed",
+                        old_member.ordinal,
+                        strictness(old.strict)
+                    ),
+                );
+                continue;
+            };
+
+            let (old_type, new_type) = (describe(old_type), describe(new_type));
+            if old_type != new_type {
+                self.report(
+                    BreakKind::UnionMemberTypeChanged,
+                    Severity::Abi,
+                    decl,
+                    Some(name),
+                    format!("type changed from {old_type} to {new_type}"),
+                );
+            }
+
+            if old_name.0 != new_name.0 {
+                self.report(
+                    BreakKind::UnionMemberRenamed,
+                    Severity::Source,
+                    decl,
+                    Some(name),
+                    format!("renamed to {new_name}"),
+                );
+            }
+        }
+    }
+
+    /// Methods are matched by name so that a changed ordinal is reported as such rather
+    /// than as a removal plus an addition.
+    fn compare_protocol(&mut self, decl: &str, old: &ir::Protocol, new: &ir::Protocol) {
+        for old_method in old.methods.iter() {
+            let name = old_method.name.0.as_str();
+
+            let Some(new_method) = new.methods.iter().find(|m| m.name.0 == name) else {
+                self.report(
+                    BreakKind::MethodRemoved,
+                    Severity::Abi,
+                    decl,
+                    Some(name),
+                    "method was removed".to_owned(),
+                );
+                continue;
+            };
+
+            if old_method.ordinal != new_method.ordinal {
+                self.report(
+                    BreakKind::MethodOrdinalChanged,
+                    Severity::Abi,
+                    decl,
+                    Some(name),
+                    format!(
+                        "ordinal changed from {:#x} to {:#x}",
+                        old_method.ordinal, new_method.ordinal
+                    ),
+                );
+            }
+
+            let (old_direction, new_direction) = (direction(old_method), direction(new_method));
+            if old_direction != new_direction {
+                self.report(
+                    BreakKind::MethodDirectionChanged,
+                    Severity::Abi,
+                    decl,
+                    Some(name),
+                    format!("changed from {old_direction} to {new_direction}"),
+                );
+            }
+
+            if old_method.strict != new_method.strict {
+                self.report(
+                    BreakKind::MethodStrictnessChanged,
+                    Severity::Abi,
+                    decl,
+                    Some(name),
+                    format!(
+                        "changed from {} to {}",
+                        strictness(old_method.strict),
+                        strictness(new_method.strict)
+                    ),
+                );
+            }
+
+            let payloads = [
+                ("request", &old_method.request_payload, &new_method.request_payload),
+                ("response", &old_method.response_payload, &new_method.response_payload),
+                ("success", &old_method.success_type, &new_method.success_type),
+                ("error", &old_method.error_type, &new_method.error_type),
+            ];
+
+            for (what, old_payload, new_payload) in payloads {
+                let (old_payload, new_payload) = (describe_payload(old_payload), describe_payload(new_payload));
+                if old_payload != new_payload {
+                    self.report(
+                        BreakKind::MethodPayloadChanged,
+                        Severity::Abi,
+                        decl,
+                        Some(name),
+                        format!("{what} type changed from {old_payload} to {new_payload}"),
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn resourceness(resourceness: ir::Resourceness) -> &'static str {
+    if resourceness.is_resource_type() {
+        "resource"
+    } else {
+        "value"
+    }
+}
+
+fn strictness(strict: bool) -> &'static str {
+    if strict {
+        "strict"
+    } else {
+        "flexible"
+    }
+}
+
+fn direction(method: &ir::ProtocolMethod) -> &'static str {
+    match method.kind {
+        ir::MethodKind::Event => "event",
+        _ if method.has_response => "two-way method",
+        _ => "one-way method",
+    }
+}
+
+fn constant_value(constant: &ir::Constant) -> &str {
+    match constant {
+        ir::Constant::Identifier { value, .. }
+        | ir::Constant::LiteralConstant { value, .. }
+        | ir::Constant::BinaryOperator { value, .. } => value,
+    }
+}
+
+fn primitive(subtype: &ir::PrimitiveSubtype) -> String {
+    format!("{:?}", subtype).to_lowercase()
+}
+
+fn nullable(nullable: bool) -> &'static str {
+    if nullable {
+        "?"
+    } else {
+        ""
+    }
+}
+
+fn describe_payload(ty: &Option<ir::Type>) -> String {
+    ty.as_ref().map(describe).unwrap_or_else(|| "none".to_owned())
+}
+
+/// Renders a type in a canonical MIDL-like form. Two types are considered the same on the
+/// wire when their descriptions are equal; layouts they refer to by name are compared
+/// separately as declarations of their own.
+fn describe(ty: &ir::Type) -> String {
+    match ty {
+        ir::Type::VectorType {
+            element_type,
+            element_count,
+            nullable: is_nullable,
+        } => {
+            let bound = element_count.map(|count| format!(":{count}")).unwrap_or_default();
+            format!("vector<{}>{}{}", describe(element_type), bound, nullable(*is_nullable))
+        }
+        ir::Type::ArrayType {
+            element_type,
+            element_count,
+            ..
+        } => format!("array<{}, {}>", describe(element_type), element_count),
+        ir::Type::StringType {
+            element_count,
+            nullable: is_nullable,
+            ..
+        } => {
+            let bound = element_count.map(|count| format!(":{count}")).unwrap_or_default();
+            format!("string{}{}", bound, nullable(*is_nullable))
+        }
+        ir::Type::StringArray { element_count, .. } => format!("string_array<{element_count}>"),
+        ir::Type::PrimitiveType { primitive_subtype } => primitive(primitive_subtype),
+        ir::Type::ClientEnd {
+            identifier,
+            nullable: is_nullable,
+            ..
+        } => format!("client_end:{}{}", identifier.0, nullable(*is_nullable)),
+        ir::Type::ServerEnd {
+            subtype,
+            nullable: is_nullable,
+            ..
+        } => format!("server_end:{}{}", subtype.0, nullable(*is_nullable)),
+        ir::Type::HandleType {
+            handle_subtype,
+            handle_rights,
+            nullable: is_nullable,
+            ..
+        } => format!(
+            "handle:<{}, {:#x}>{}",
+            format!("{:?}", handle_subtype).to_lowercase(),
+            handle_rights.bits(),
+            nullable(*is_nullable)
+        ),
+        ir::Type::IdentifierType {
+            identifier,
+            nullable: is_nullable,
+            ..
+        } => format!("{}{}", identifier.0, nullable(*is_nullable)),
+        ir::Type::InternalType { internal_subtype, .. } => format!("{:?}", internal_subtype).to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    /// Each break kind has a directory with an `old.json` and `new.json` IR pair and the
+    /// findings expected from comparing them in `expected.txt`.
+    fn fixture(kind: BreakKind) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join(kind.name())
+    }
+
+    fn load(path: &Path) -> ir::Root {
+        let contents = std::fs::read_to_string(path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+        serde_json::from_str(&contents).unwrap_or_else(|e| panic!("{}: {e}", path.display()))
+    }
+
+    #[test]
+    fn fixtures() {
+        for kind in BreakKind::ALL {
+            let dir = fixture(kind);
+            let (old, new) = (load(&dir.join("old.json")), load(&dir.join("new.json")));
+
+            let findings = compare(&old, &new);
+            assert!(
+                findings.iter().any(|finding| finding.kind == kind),
+                "{} is not reported for its fixture",
+                kind.name()
+            );
+
+            let actual: String = findings.iter().map(|finding| format!("{finding}\n")).collect();
+            let expected = std::fs::read_to_string(dir.join("expected.txt")).unwrap();
+            assert_eq!(actual, expected, "findings for {}", kind.name());
+        }
+    }
+
+    #[test]
+    fn unchanged_library_is_compatible() {
+        for kind in BreakKind::ALL {
+            let dir = fixture(kind);
+
+            for name in ["old.json", "new.json"] {
+                let ir = load(&dir.join(name));
+                assert!(compare(&ir, &ir).is_empty(), "{}/{name}", kind.name());
+            }
+        }
+    }
+
+    #[test]
+    fn additions_are_compatible() {
+        let dir = fixture(BreakKind::MethodRemoved);
+        assert!(compare(&load(&dir.join("new.json")), &load(&dir.join("old.json"))).is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright 2024 MeshX Authors. All rights reserved.
+
+mod allowlist;
+mod compare;
+mod types;
+
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use thiserror::Error;
+
+use midlgen::ir;
+
+use crate::allowlist::Allowlist;
+
+#[derive(Error, Debug)]
+pub enum CompatError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+
+    #[error("serialization error")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("allowlist line {line}: {reason}")]
+    Allowlist { line: usize, reason: String },
+}
+
+#[derive(clap::ValueEnum, Debug, Copy, Clone)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(clap::Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+#[command(name = "MIDL compatibility checker")]
+struct Args {
+    /// JSON IR of the previously released library
+    #[arg(long)]
+    old: PathBuf,
+
+    /// JSON IR of the library about to be released
+    #[arg(long)]
+    new: PathBuf,
+
+    /// File listing accepted breaking changes, one `<kind> <target>` per line
+    #[arg(long)]
+    allowlist: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+fn load(path: &Path) -> Result<ir::Root, CompatError> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str::<ir::Root>(contents.as_str())?)
+}
+
+fn main() -> Result<(), CompatError> {
+    let args = Args::parse();
+
+    let old = load(&args.old)?;
+    let new = load(&args.new)?;
+
+    let allowlist = match &args.allowlist {
+        Some(path) => Allowlist::load(path)?,
+        None => Allowlist::default(),
+    };
+
+    let mut findings = compare::compare(&old, &new);
+    allowlist.apply(&mut findings);
+
+    match args.format {
+        OutputFormat::Text => {
+            for finding in findings.iter() {
+                if finding.allowed {
+                    println!("{finding} (allowed)");
+                } else {
+                    println!("{finding}");
+                }
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&findings)?),
+    }
+
+    for entry in allowlist.unused() {
+        eprintln!("warning: allowlist entry matches nothing and can be removed ({entry})");
+    }
+
+    let breaking = findings.iter().filter(|finding| !finding.allowed).count();
+    if breaking > 0 {
+        eprintln!("{breaking} breaking change(s) found");
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
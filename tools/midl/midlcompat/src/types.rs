@@ -0,0 +1,158 @@
+use serde::Serialize;
+
+/// How a change affects existing users of a library.
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Peers built against the old and the new IR can no longer exchange messages.
+    #[serde(rename = "abi")]
+    Abi,
+    /// Code written against the old bindings no longer compiles against the new ones.
+    #[serde(rename = "source")]
+    Source,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Abi => write!(f, "ABI"),
+            Severity::Source => write!(f, "source"),
+        }
+    }
+}
+
+/// The kind of a breaking change. The kebab-case name is what allowlist entries refer to.
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum BreakKind {
+    DeclRemoved,
+    DeclKindChanged,
+    ResourcenessChanged,
+    StrictnessChanged,
+    StructLayoutChanged,
+    StructMemberRenamed,
+    EnumTypeChanged,
+    EnumMemberRemoved,
+    EnumMemberValueChanged,
+    BitsTypeChanged,
+    BitsMemberRemoved,
+    BitsMemberValueChanged,
+    UnionMemberRemoved,
+    UnionMemberTypeChanged,
+    UnionMemberRenamed,
+    TableMemberRemoved,
+    TableMemberRenamed,
+    TableOrdinalReused,
+    MethodRemoved,
+    MethodOrdinalChanged,
+    MethodDirectionChanged,
+    MethodStrictnessChanged,
+    MethodPayloadChanged,
+    ConstTypeChanged,
+    ConstValueChanged,
+}
+
+impl BreakKind {
+    pub const ALL: [BreakKind; 25] = [
+        BreakKind::DeclRemoved,
+        BreakKind::DeclKindChanged,
+        BreakKind::ResourcenessChanged,
+        BreakKind::StrictnessChanged,
+        BreakKind::StructLayoutChanged,
+        BreakKind::StructMemberRenamed,
+        BreakKind::EnumTypeChanged,
+        BreakKind::EnumMemberRemoved,
+        BreakKind::EnumMemberValueChanged,
+        BreakKind::BitsTypeChanged,
+        BreakKind::BitsMemberRemoved,
+        BreakKind::BitsMemberValueChanged,
+        BreakKind::UnionMemberRemoved,
+        BreakKind::UnionMemberTypeChanged,
+        BreakKind::UnionMemberRenamed,
+        BreakKind::TableMemberRemoved,
+        BreakKind::TableMemberRenamed,
+        BreakKind::TableOrdinalReused,
+        BreakKind::MethodRemoved,
+        BreakKind::MethodOrdinalChanged,
+        BreakKind::MethodDirectionChanged,
+        BreakKind::MethodStrictnessChanged,
+        BreakKind::MethodPayloadChanged,
+        BreakKind::ConstTypeChanged,
+        BreakKind::ConstValueChanged,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BreakKind::DeclRemoved => "decl-removed",
+            BreakKind::DeclKindChanged => "decl-kind-changed",
+            BreakKind::ResourcenessChanged => "resourceness-changed",
+            BreakKind::StrictnessChanged => "strictness-changed",
+            BreakKind::StructLayoutChanged => "struct-layout-changed",
+            BreakKind::StructMemberRenamed => "struct-member-renamed",
+            BreakKind::EnumTypeChanged => "enum-type-changed",
+            BreakKind::EnumMemberRemoved => "enum-member-removed",
+            BreakKind::EnumMemberValueChanged => "enum-member-value-changed",
+            BreakKind::BitsTypeChanged => "bits-type-changed",
+            BreakKind::BitsMemberRemoved => "bits-member-removed",
+            BreakKind::BitsMemberValueChanged => "bits-member-value-changed",
+            BreakKind::UnionMemberRemoved => "union-member-removed",
+            BreakKind::UnionMemberTypeChanged => "union-member-type-changed",
+            BreakKind::UnionMemberRenamed => "union-member-renamed",
+            BreakKind::TableMemberRemoved => "table-member-removed",
+            BreakKind::TableMemberRenamed => "table-member-renamed",
+            BreakKind::TableOrdinalReused => "table-ordinal-reused",
+            BreakKind::MethodRemoved => "method-removed",
+            BreakKind::MethodOrdinalChanged => "method-ordinal-changed",
+            BreakKind::MethodDirectionChanged => "method-direction-changed",
+            BreakKind::MethodStrictnessChanged => "method-strictness-changed",
+            BreakKind::MethodPayloadChanged => "method-payload-changed",
+            BreakKind::ConstTypeChanged => "const-type-changed",
+            BreakKind::ConstValueChanged => "const-value-changed",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<BreakKind> {
+        BreakKind::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// A single breaking change between two IR snapshots.
+#[derive(Serialize, Debug, Clone)]
+pub struct Finding {
+    pub kind: BreakKind,
+    pub severity: Severity,
+
+    /// The affected declaration, e.g. `meshx.io/Directory`.
+    pub decl: String,
+
+    /// The affected member or method, if the change is not about the declaration itself.
+    pub member: Option<String>,
+
+    pub message: String,
+
+    /// Set when an allowlist entry accepts this change.
+    pub allowed: bool,
+}
+
+impl Finding {
+    /// The name allowlist entries use for the affected element, e.g.
+    /// `meshx.io/Directory.Open`.
+    pub fn target(&self) -> String {
+        match &self.member {
+            Some(member) => format!("{}.{}", self.decl, member),
+            None => self.decl.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:<6} {:<26} {}: {}",
+            self.severity.to_string(),
+            self.kind.name(),
+            self.target(),
+            self.message
+        )
+    }
+}
@@ -450,8 +450,8 @@ pub enum HandleSubtype {
 /// Library represents a MIDL dependency on a separate library.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Library {
-    name: EncodedLibraryIdentifier,
-    declarations: DeclInfoMap,
+    pub name: EncodedLibraryIdentifier,
+    pub declarations: DeclInfoMap,
 }
 
 /// Root is the top-level object for a MIDL library.
@@ -461,9 +461,15 @@ pub struct Root {
     pub name: EncodedLibraryIdentifier,
     pub experiments: Vec<String>,
 
+    #[serde(default)]
+    pub maybe_attributes: Vec<Attribute>,
+
     pub const_declarations: Vec<Const>,
     pub enum_declarations: Vec<Enum>,
     pub struct_declarations: Vec<Struct>,
+    /// Payload structs of methods composed from other libraries.
+    #[serde(default)]
+    pub external_struct_declarations: Vec<Struct>,
     pub protocol_declarations: Vec<Protocol>,
     pub table_declarations: Vec<Table>,
     pub union_declarations: Vec<Union>,
@@ -539,6 +545,8 @@ impl Root {
 pub struct EnumMember {
     pub name: Identifier,
     pub value: Constant,
+    #[serde(default)]
+    pub maybe_attributes: Vec<Attribute>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -547,13 +555,15 @@ pub struct StructMember {
     pub name: Identifier,
     pub r#type: Type,
     pub field_shape_v2: FieldShape,
+    #[serde(default)]
+    pub maybe_attributes: Vec<Attribute>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, Default)]
 pub struct Resourceness(pub bool);
 
 pub const RESOURCE_TYPE: Resourceness = Resourceness(true);
-pub const VALUE_TYPE: Resourceness = Resourceness(true);
+pub const VALUE_TYPE: Resourceness = Resourceness(false);
 
 impl Resourceness {
     pub fn is_resource_type(&self) -> bool {
@@ -568,9 +578,37 @@ impl Resourceness {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Attribute {
     pub name: String,
+    #[serde(default)]
+    pub arguments: Vec<AttributeArg>,
     pub location: Location,
 }
 
+/// AttributeArg is a single argument of an attribute. Positional arguments, e.g. the text
+/// of a doc comment, are named "value".
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AttributeArg {
+    pub name: String,
+    pub value: Constant,
+}
+
+impl Attribute {
+    /// The argument with the given name, if any.
+    pub fn lookup_arg(&self, name: &str) -> Option<&AttributeArg> {
+        self.arguments.iter().find(|arg| arg.name == name)
+    }
+}
+
+/// The attribute with the given name, if any. Attribute names are case insensitive.
+pub fn lookup_attribute<'a>(attributes: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+    attributes.iter().find(|attr| attr.name.eq_ignore_ascii_case(name))
+}
+
+/// The doc comment carried by a list of attributes, i.e. the text of `///` lines.
+pub fn doc_comment(attributes: &[Attribute]) -> Option<String> {
+    let arg = lookup_attribute(attributes, "doc")?.lookup_arg("value")?;
+    Some(arg.value.value().to_owned())
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Struct {
     #[serde(rename = "resource")]
@@ -604,10 +642,28 @@ impl Decl for Struct {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Bits {
     pub name: EncodedCompoundIdentifier,
     pub location: Location,
+    #[serde(default)]
+    pub maybe_attributes: Vec<Attribute>,
+
+    pub r#type: Type,
+    pub mask: String,
+    pub members: Vec<BitsMember>,
+
+    #[serde(rename = "strict")]
+    pub is_strict: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BitsMember {
+    pub name: Identifier,
+    pub location: Location,
+    pub value: Constant,
+    #[serde(default)]
+    pub maybe_attributes: Vec<Attribute>,
 }
 
 impl Decl for Bits {
@@ -633,6 +689,8 @@ pub struct UnionMember {
     pub max_out_of_line: i64,
     #[serde(rename = "type")]
     pub r#type: Option<Type>,
+    #[serde(default)]
+    pub maybe_attributes: Vec<Attribute>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -647,6 +705,9 @@ pub struct Union {
 
     #[serde(rename = "resource")]
     pub resourceness: Resourceness,
+
+    #[serde(default)]
+    pub maybe_attributes: Vec<Attribute>,
 }
 
 impl Decl for Union {
@@ -741,6 +802,26 @@ pub enum Constant {
     BinaryOperator { value: String, expression: String },
 }
 
+impl Constant {
+    /// The resolved value of the constant.
+    pub fn value(&self) -> &str {
+        match self {
+            Constant::Identifier { value, .. }
+            | Constant::LiteralConstant { value, .. }
+            | Constant::BinaryOperator { value, .. } => value,
+        }
+    }
+
+    /// The constant as written in the source, e.g. `MAX_SIZE * 2`.
+    pub fn expression(&self) -> &str {
+        match self {
+            Constant::Identifier { expression, .. }
+            | Constant::LiteralConstant { expression, .. }
+            | Constant::BinaryOperator { expression, .. } => expression,
+        }
+    }
+}
+
 impl Default for Constant {
     fn default() -> Self {
         Constant::LiteralConstant {
@@ -757,6 +838,8 @@ pub struct Const {
     pub location: Location,
     pub r#type: Type,
     pub value: Constant,
+    #[serde(default)]
+    pub maybe_attributes: Vec<Attribute>,
 }
 
 impl Decl for Const {
@@ -833,6 +916,9 @@ pub struct ProtocolMethod {
     pub request_payload: Option<Type>,
     #[serde(rename = "maybe_response_payload")]
     pub response_payload: Option<Type>,
+
+    #[serde(default)]
+    pub maybe_attributes: Vec<Attribute>,
 }
 
 impl ProtocolMethod {
@@ -841,15 +927,32 @@ impl ProtocolMethod {
     }
 
     pub fn has_framework_error(&self) -> bool {
-        false
+        self.has_result_union() && !self.strict
     }
 }
 
+/// Which unknown interactions a protocol accepts: none when closed, one-way
+/// methods when ajar, and one-way and two-way methods when open.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Openness {
+    #[serde(rename = "closed")]
+    Closed,
+    #[serde(rename = "ajar")]
+    Ajar,
+    #[default]
+    #[serde(rename = "open")]
+    Open,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Protocol {
     pub location: Location,
     pub name: EncodedCompoundIdentifier,
+    #[serde(default)]
+    pub openness: Openness,
     pub methods: Vec<ProtocolMethod>,
+    #[serde(default)]
+    pub maybe_attributes: Vec<Attribute>,
 }
 
 impl Protocol {
@@ -895,6 +998,8 @@ pub struct Table {
     // resourceableLayoutDecl
     pub members: Vec<TableMember>,
     pub type_shape_v2: TypeShape, // Strictness: `json:"strict"`
+    #[serde(default)]
+    pub maybe_attributes: Vec<Attribute>,
 }
 
 // TableMember represents the declaration of a field in a FIDL table.
@@ -908,6 +1013,8 @@ pub struct TableMember {
     pub maybe_default_value: Option<Constant>,
     #[serde(default)]
     pub max_out_of_line: i64,
+    #[serde(default)]
+    pub maybe_attributes: Vec<Attribute>,
 }
 
 impl Decl for Table {
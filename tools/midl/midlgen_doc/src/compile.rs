@@ -0,0 +1,639 @@
+use std::collections::{HashMap, HashSet};
+
+use midlgen::ir;
+
+use crate::types;
+
+/// Where a declaration is documented.
+struct Target {
+    library: String,
+    anchor: String,
+    kind: ir::DeclType,
+    /// Anonymous payload structs and result unions are shown as part of their method.
+    inlined: bool,
+}
+
+/// The declarations of every library being documented, so that types can link across
+/// library pages. Declarations of other dependencies are shown without a link.
+pub struct Links {
+    targets: HashMap<String, Target>,
+}
+
+impl Links {
+    pub fn new(roots: &[ir::Root]) -> Self {
+        let mut targets = HashMap::new();
+
+        for root in roots {
+            let inlined = inlined_decls(root);
+            let mut add = |name: &ir::EncodedCompoundIdentifier, kind: ir::DeclType| {
+                let (library, short_name) = split_name(name);
+
+                targets.insert(
+                    name.0.clone(),
+                    Target {
+                        library: library.to_owned(),
+                        anchor: anchor(short_name),
+                        kind,
+                        inlined: inlined.contains(&name.0),
+                    },
+                );
+            };
+
+            root.const_declarations
+                .iter()
+                .for_each(|d| add(&d.name, ir::DeclType::ConstDecl));
+            root.enum_declarations
+                .iter()
+                .for_each(|d| add(&d.name, ir::DeclType::EnumDecl));
+            root.bits_declarations
+                .iter()
+                .for_each(|d| add(&d.name, ir::DeclType::BitsDecl));
+            root.struct_declarations
+                .iter()
+                .for_each(|d| add(&d.name, ir::DeclType::StructDecl));
+            root.table_declarations
+                .iter()
+                .for_each(|d| add(&d.name, ir::DeclType::TableDecl));
+            root.union_declarations
+                .iter()
+                .for_each(|d| add(&d.name, ir::DeclType::UnionDecl));
+            root.protocol_declarations
+                .iter()
+                .for_each(|d| add(&d.name, ir::DeclType::ProtocolDecl));
+        }
+
+        Self { targets }
+    }
+
+    pub fn is_documented(&self, library: &str) -> bool {
+        self.targets.values().any(|target| target.library == library)
+    }
+}
+
+/// Splits `my.library/Decl` into the library and declaration name.
+fn split_name(name: &ir::EncodedCompoundIdentifier) -> (&str, &str) {
+    name.0.split_once('/').unwrap_or(("", name.0.as_str()))
+}
+
+fn anchor(name: &str) -> String {
+    name.to_lowercase()
+}
+
+/// The Markdown file documenting a library.
+pub fn library_file(library: &str) -> String {
+    format!("{}.md", library)
+}
+
+/// Whether a union is the result union midlc wraps the response of a method in, with the
+/// success payload at ordinal 1 and the error at ordinal 2.
+fn is_result_union(union: &ir::Union) -> bool {
+    let first = union.members.first().and_then(|m| m.name.as_ref());
+    union.name.0.ends_with("_Result") && first.is_some_and(|name| name.0 == "response")
+}
+
+fn identifier_of(r#type: &ir::Type) -> Option<&ir::EncodedCompoundIdentifier> {
+    match r#type {
+        ir::Type::IdentifierType { identifier, .. } => Some(identifier),
+        _ => None,
+    }
+}
+
+/// Payload structs and result unions synthesized for the methods of a library.
+fn inlined_decls(root: &ir::Root) -> HashSet<String> {
+    let structs: HashMap<&str, &ir::Struct> = root
+        .struct_declarations
+        .iter()
+        .map(|s| (s.name.0.as_str(), s))
+        .collect();
+    let unions: HashMap<&str, &ir::Union> = root.union_declarations.iter().map(|u| (u.name.0.as_str(), u)).collect();
+
+    let mut inlined = HashSet::new();
+    let inline_struct = |r#type: Option<&ir::Type>, inlined: &mut HashSet<String>| {
+        if let Some(name) = r#type.and_then(identifier_of) {
+            if structs.get(name.0.as_str()).is_some_and(|s| s.naming_context.len() > 1) {
+                inlined.insert(name.0.clone());
+            }
+        }
+    };
+
+    for method in root.protocol_declarations.iter().flat_map(|p| p.methods.iter()) {
+        inline_struct(method.request_payload.as_ref(), &mut inlined);
+
+        let response = method.response_payload.as_ref().and_then(identifier_of);
+        match response.and_then(|name| unions.get(name.0.as_str())) {
+            Some(union) if is_result_union(union) => {
+                inlined.insert(union.name.0.clone());
+                inline_struct(union.members[0].r#type.as_ref(), &mut inlined);
+            }
+            _ => inline_struct(method.response_payload.as_ref(), &mut inlined),
+        }
+    }
+
+    inlined
+}
+
+fn primitive_name(subtype: &ir::PrimitiveSubtype) -> &'static str {
+    match subtype {
+        ir::PrimitiveSubtype::Bool => "bool",
+        ir::PrimitiveSubtype::Float32 => "float32",
+        ir::PrimitiveSubtype::Float64 => "float64",
+        ir::PrimitiveSubtype::Uint64 => "uint64",
+        ir::PrimitiveSubtype::Uint32 => "uint32",
+        ir::PrimitiveSubtype::Uint16 => "uint16",
+        ir::PrimitiveSubtype::Uint8 => "uint8",
+        ir::PrimitiveSubtype::Int8 => "int8",
+        ir::PrimitiveSubtype::Int64 => "int64",
+        ir::PrimitiveSubtype::Int32 => "int32",
+        ir::PrimitiveSubtype::Int16 => "int16",
+    }
+}
+
+fn handle_name(subtype: &ir::HandleSubtype) -> String {
+    serde_json::to_value(subtype)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_owned))
+        .unwrap_or_else(|| "handle".to_owned())
+}
+
+/// Renders the `@available` attribute, e.g. "Added in 1, deprecated in 3: use Point3".
+fn availability(attributes: &[ir::Attribute]) -> Option<String> {
+    let attribute = ir::lookup_attribute(attributes, "available")?;
+    let mut parts = vec![];
+
+    if let Some(platform) = attribute.lookup_arg("platform") {
+        parts.push(format!("platform `{}`", platform.value.value()));
+    }
+
+    for (arg, label) in [
+        ("added", "added in"),
+        ("deprecated", "deprecated in"),
+        ("removed", "removed in"),
+        ("replaced", "replaced in"),
+    ] {
+        if let Some(arg) = attribute.lookup_arg(arg) {
+            parts.push(format!("{} {}", label, arg.value.value()));
+        }
+    }
+
+    if parts.is_empty() {
+        return None;
+    }
+
+    let mut text = parts.join(", ");
+    text[..1].make_ascii_uppercase();
+
+    if let Some(note) = attribute.lookup_arg("note") {
+        text = format!("{}: {}", text, note.value.value());
+    }
+
+    Some(text)
+}
+
+/// Attributes worth showing besides the doc comment and availability, as written in MIDL.
+fn other_attributes(attributes: &[ir::Attribute]) -> Vec<String> {
+    attributes
+        .iter()
+        .filter(|attr| !attr.name.eq_ignore_ascii_case("doc") && !attr.name.eq_ignore_ascii_case("available"))
+        .map(|attr| {
+            let args: Vec<String> = attr
+                .arguments
+                .iter()
+                .map(|arg| match arg.name.as_str() {
+                    "value" => arg.value.expression().to_owned(),
+                    name => format!("{}={}", name, arg.value.expression()),
+                })
+                .collect();
+
+            if args.is_empty() {
+                format!("@{}", attr.name)
+            } else {
+                format!("@{}({})", attr.name, args.join(", "))
+            }
+        })
+        .collect()
+}
+
+/// The text of the doc comment, without the space after each `///` and the trailing
+/// newline. midlc already trims the lines, fidlc style IR keeps them as written.
+fn doc_comment(attributes: &[ir::Attribute]) -> Option<String> {
+    let doc = ir::doc_comment(attributes)?;
+    let doc = doc.trim_end_matches('\n');
+
+    if !doc.lines().all(|line| line.is_empty() || line.starts_with(' ')) {
+        return Some(doc.to_owned());
+    }
+
+    let lines: Vec<&str> = doc.lines().map(|line| line.strip_prefix(' ').unwrap_or(line)).collect();
+    Some(lines.join("\n"))
+}
+
+/// The first paragraph of a doc comment, on a single line.
+pub fn summary(doc: &str) -> String {
+    let paragraph = doc.split("\n\n").next().unwrap_or_default();
+    paragraph.lines().map(str::trim).collect::<Vec<_>>().join(" ")
+}
+
+struct Compiler<'a> {
+    library: String,
+    links: &'a Links,
+    structs: HashMap<&'a str, &'a ir::Struct>,
+    unions: HashMap<&'a str, &'a ir::Union>,
+}
+
+impl<'a> Compiler<'a> {
+    /// A reference to a declaration; a link unless it is not documented or shown inline.
+    fn reference(&self, name: &ir::EncodedCompoundIdentifier, markdown: bool) -> String {
+        let (library, short_name) = split_name(name);
+        let qualified = if library == self.library {
+            short_name.to_owned()
+        } else {
+            name.0.clone()
+        };
+
+        if !markdown {
+            return qualified;
+        }
+
+        match self.links.targets.get(&name.0) {
+            Some(target) if !target.inlined => {
+                if target.library == self.library {
+                    format!("[{}](#{})", qualified, target.anchor)
+                } else {
+                    format!("[{}]({}#{})", qualified, library_file(&target.library), target.anchor)
+                }
+            }
+            _ => qualified,
+        }
+    }
+
+    /// Renders a type in MIDL syntax. As Markdown, declarations are links and angle
+    /// brackets are escaped so they are not taken for HTML.
+    fn describe(&self, r#type: &ir::Type, markdown: bool) -> String {
+        let (open, close) = if markdown { ("&lt;", "&gt;") } else { ("<", ">") };
+        let optional = |nullable: bool| if nullable { ":optional" } else { "" };
+        let bound = |count: &Option<u32>| count.map(|count| format!(":{}", count)).unwrap_or_default();
+
+        match r#type {
+            ir::Type::PrimitiveType { primitive_subtype } => primitive_name(primitive_subtype).to_owned(),
+            ir::Type::StringType {
+                element_count,
+                nullable,
+                ..
+            } => format!("string{}{}", bound(element_count), optional(*nullable)),
+            ir::Type::StringArray { element_count, .. } => format!("string_array:{}", element_count),
+            ir::Type::VectorType {
+                element_type,
+                element_count,
+                nullable,
+            } => format!(
+                "vector{}{}{}{}{}",
+                open,
+                self.describe(element_type, markdown),
+                close,
+                bound(element_count),
+                optional(*nullable)
+            ),
+            ir::Type::ArrayType {
+                element_type,
+                element_count,
+                ..
+            } => format!(
+                "array{}{}, {}{}",
+                open,
+                self.describe(element_type, markdown),
+                element_count,
+                close
+            ),
+            ir::Type::ClientEnd {
+                identifier, nullable, ..
+            } => format!(
+                "client_end:{}{}",
+                self.reference(identifier, markdown),
+                optional(*nullable)
+            ),
+            ir::Type::ServerEnd { subtype, nullable, .. } => {
+                format!(
+                    "server_end:{}{}",
+                    self.reference(subtype, markdown),
+                    optional(*nullable)
+                )
+            }
+            ir::Type::HandleType {
+                handle_subtype,
+                nullable,
+                ..
+            } => format!("handle:{}{}", handle_name(handle_subtype), optional(*nullable)),
+            ir::Type::IdentifierType {
+                identifier, nullable, ..
+            } => {
+                let reference = self.reference(identifier, markdown);
+                let is_struct = self
+                    .links
+                    .targets
+                    .get(&identifier.0)
+                    .is_some_and(|target| matches!(target.kind, ir::DeclType::StructDecl));
+
+                match (nullable, is_struct) {
+                    (true, true) => format!("box{}{}{}", open, reference, close),
+                    (true, false) => format!("{}:optional", reference),
+                    (false, _) => reference,
+                }
+            }
+            ir::Type::InternalType { .. } => "framework_err".to_owned(),
+        }
+    }
+
+    fn member(&self, name: &ir::Identifier, r#type: Option<&ir::Type>, attributes: &[ir::Attribute]) -> types::Member {
+        types::Member {
+            name: name.0.clone(),
+            r#type: r#type.map(|t| self.describe(t, true)),
+            doc: doc_comment(attributes),
+            availability: availability(attributes),
+            ..Default::default()
+        }
+    }
+
+    fn decl(
+        &self,
+        kind: &str,
+        name: &ir::EncodedCompoundIdentifier,
+        signature: String,
+        attributes: &[ir::Attribute],
+    ) -> types::Decl {
+        let (_, short_name) = split_name(name);
+
+        types::Decl {
+            kind: kind.to_owned(),
+            name: short_name.to_owned(),
+            anchor: anchor(short_name),
+            signature,
+            doc: doc_comment(attributes),
+            availability: availability(attributes),
+            attributes: other_attributes(attributes),
+            members: vec![],
+            methods: vec![],
+        }
+    }
+
+    fn compile_const(&self, val: &ir::Const) -> types::Decl {
+        let (_, name) = split_name(&val.name);
+        let signature = format!(
+            "const {} {} = {}",
+            name,
+            self.describe(&val.r#type, false),
+            val.value.expression()
+        );
+
+        self.decl("const", &val.name, signature, &val.maybe_attributes)
+    }
+
+    fn compile_enum(&self, val: &ir::Enum) -> types::Decl {
+        let (_, name) = split_name(&val.name);
+        let strictness = if val.is_strict { "strict" } else { "flexible" };
+        let signature = format!("type {} = {} enum : {}", name, strictness, primitive_name(&val.r#type));
+
+        let mut decl = self.decl("enum", &val.name, signature, &val.maybe_attributes);
+        decl.members = val
+            .members
+            .iter()
+            .map(|member| types::Member {
+                value: Some(member.value.value().to_owned()),
+                ..self.member(&member.name, None, &member.maybe_attributes)
+            })
+            .collect();
+
+        decl
+    }
+
+    fn compile_bits(&self, val: &ir::Bits) -> types::Decl {
+        let (_, name) = split_name(&val.name);
+        let strictness = if val.is_strict { "strict" } else { "flexible" };
+        let signature = format!(
+            "type {} = {} bits : {}",
+            name,
+            strictness,
+            self.describe(&val.r#type, false)
+        );
+
+        let mut decl = self.decl("bits", &val.name, signature, &val.maybe_attributes);
+        decl.members = val
+            .members
+            .iter()
+            .map(|member| types::Member {
+                value: Some(member.value.value().to_owned()),
+                ..self.member(&member.name, None, &member.maybe_attributes)
+            })
+            .collect();
+
+        decl
+    }
+
+    fn compile_struct(&self, val: &ir::Struct) -> types::Decl {
+        let (_, name) = split_name(&val.name);
+        let resource = if val.resourceness.is_resource_type() {
+            "resource "
+        } else {
+            ""
+        };
+        let signature = format!("type {} = {}struct", name, resource);
+        let attributes = val.maybe_attributes.as_deref().unwrap_or_default();
+
+        let mut decl = self.decl("struct", &val.name, signature, attributes);
+        decl.members = self.struct_members(val);
+        decl
+    }
+
+    fn struct_members(&self, val: &ir::Struct) -> Vec<types::Member> {
+        val.members
+            .iter()
+            .map(|member| self.member(&member.name, Some(&member.r#type), &member.maybe_attributes))
+            .collect()
+    }
+
+    fn compile_table(&self, val: &ir::Table) -> types::Decl {
+        let (_, name) = split_name(&val.name);
+        let resource = if val.resource.is_resource_type() {
+            "resource "
+        } else {
+            ""
+        };
+        let signature = format!("type {} = {}table", name, resource);
+
+        let mut decl = self.decl("table", &val.name, signature, &val.maybe_attributes);
+        decl.members = val
+            .members
+            .iter()
+            .map(|member| types::Member {
+                ordinal: Some(member.ordinal as u64),
+                reserved: member.reserved,
+                ..match &member.name {
+                    Some(name) => self.member(name, member.r#type.as_ref(), &member.maybe_attributes),
+                    None => types::Member::default(),
+                }
+            })
+            .collect();
+
+        decl
+    }
+
+    fn compile_union(&self, val: &ir::Union) -> types::Decl {
+        let (_, name) = split_name(&val.name);
+        let strictness = if val.strict { "strict" } else { "flexible" };
+        let resource = if val.resourceness.is_resource_type() {
+            " resource"
+        } else {
+            ""
+        };
+        let signature = format!("type {} = {}{} union", name, strictness, resource);
+
+        let mut decl = self.decl("union", &val.name, signature, &val.maybe_attributes);
+        decl.members = val
+            .members
+            .iter()
+            .map(|member| types::Member {
+                ordinal: Some(member.ordinal),
+                reserved: member.name.is_none(),
+                ..match &member.name {
+                    Some(name) => self.member(name, member.r#type.as_ref(), &member.maybe_attributes),
+                    None => types::Member::default(),
+                }
+            })
+            .collect();
+
+        decl
+    }
+
+    /// A request or response, listing the parameters of an anonymous payload struct.
+    fn payload(&self, r#type: &ir::Type) -> types::Payload {
+        let inlined = identifier_of(r#type)
+            .filter(|name| self.links.targets.get(&name.0).is_some_and(|t| t.inlined))
+            .and_then(|name| self.structs.get(name.0.as_str()));
+
+        match inlined {
+            Some(val) => types::Payload {
+                r#type: None,
+                parameters: self.struct_members(val),
+            },
+            None => types::Payload {
+                r#type: Some(self.describe(r#type, true)),
+                parameters: vec![],
+            },
+        }
+    }
+
+    fn compile_method(&self, protocol_anchor: &str, val: &ir::ProtocolMethod) -> types::Method {
+        let result_union = val
+            .response_payload
+            .as_ref()
+            .and_then(identifier_of)
+            .and_then(|name| self.unions.get(name.0.as_str()))
+            .filter(|union| is_result_union(union));
+
+        let (response, error) = match result_union {
+            Some(union) => (
+                union.members[0].r#type.as_ref().map(|t| self.payload(t)),
+                union
+                    .members
+                    .iter()
+                    .find(|m| m.ordinal == 2 && m.name.is_some())
+                    .and_then(|m| m.r#type.as_ref())
+                    .map(|t| self.describe(t, true)),
+            ),
+            None => (
+                val.response_payload.as_ref().map(|t| self.payload(t)),
+                val.error_type.as_ref().map(|t| self.describe(t, true)),
+            ),
+        };
+
+        let kind = match val.kind {
+            ir::MethodKind::Oneway => "one-way",
+            ir::MethodKind::Twoway => "two-way",
+            ir::MethodKind::Event => "event",
+        };
+
+        types::Method {
+            name: val.name.0.clone(),
+            anchor: format!("{}.{}", protocol_anchor, anchor(&val.name.0)),
+            kind: kind.to_owned(),
+            strict: val.strict,
+            doc: doc_comment(&val.maybe_attributes),
+            availability: availability(&val.maybe_attributes)
+                .or_else(|| val.deprecated.then(|| "Deprecated".to_owned())),
+            attributes: other_attributes(&val.maybe_attributes),
+            request: val.request_payload.as_ref().map(|t| self.payload(t)),
+            response,
+            error,
+        }
+    }
+
+    fn compile_protocol(&self, val: &ir::Protocol) -> types::Decl {
+        let (_, name) = split_name(&val.name);
+
+        let mut decl = self.decl(
+            "protocol",
+            &val.name,
+            format!("protocol {}", name),
+            &val.maybe_attributes,
+        );
+        decl.methods = val
+            .methods
+            .iter()
+            .map(|method| self.compile_method(&decl.anchor, method))
+            .collect();
+
+        decl
+    }
+}
+
+pub fn compile(ir: &ir::Root, links: &Links) -> types::Library {
+    let c = Compiler {
+        library: ir.name.0.clone(),
+        links,
+        structs: ir.struct_declarations.iter().map(|s| (s.name.0.as_str(), s)).collect(),
+        unions: ir.union_declarations.iter().map(|u| (u.name.0.as_str(), u)).collect(),
+    };
+
+    let is_shown = |name: &ir::EncodedCompoundIdentifier| !links.targets.get(&name.0).is_some_and(|t| t.inlined);
+    let sorted = |mut decls: Vec<types::Decl>| {
+        decls.sort_by(|a, b| a.name.cmp(&b.name));
+        decls
+    };
+
+    let dependencies = ir
+        .library_dependencies
+        .iter()
+        .map(|dep| {
+            if links.is_documented(&dep.name.0) {
+                format!("[{}]({})", dep.name.0, library_file(&dep.name.0))
+            } else {
+                dep.name.0.clone()
+            }
+        })
+        .collect();
+
+    types::Library {
+        name: ir.name.0.clone(),
+        doc: doc_comment(&ir.maybe_attributes),
+        availability: availability(&ir.maybe_attributes),
+        dependencies,
+        protocols: sorted(ir.protocol_declarations.iter().map(|d| c.compile_protocol(d)).collect()),
+        structs: sorted(
+            ir.struct_declarations
+                .iter()
+                .filter(|d| is_shown(&d.name))
+                .map(|d| c.compile_struct(d))
+                .collect(),
+        ),
+        tables: sorted(ir.table_declarations.iter().map(|d| c.compile_table(d)).collect()),
+        unions: sorted(
+            ir.union_declarations
+                .iter()
+                .filter(|d| is_shown(&d.name))
+                .map(|d| c.compile_union(d))
+                .collect(),
+        ),
+        enums: sorted(ir.enum_declarations.iter().map(|d| c.compile_enum(d)).collect()),
+        bits: sorted(ir.bits_declarations.iter().map(|d| c.compile_bits(d)).collect()),
+        consts: sorted(ir.const_declarations.iter().map(|d| c.compile_const(d)).collect()),
+    }
+}
@@ -0,0 +1,133 @@
+mod compile;
+mod types;
+
+use std::fs::File;
+use std::path::PathBuf;
+
+use clap::Parser;
+use midlgen::ir;
+use tera::{Context, Tera};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum GeneratorError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+
+    #[error("serialization error")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("render error")]
+    TemplateError(#[from] tera::Error),
+}
+
+#[derive(clap::Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+#[command(name = "MIDL documentation generator")]
+struct Args {
+    /// JSON IR of the libraries to document; types link across all of them
+    #[arg(short, long, num_args = 1.., required = true)]
+    json: Vec<PathBuf>,
+
+    /// Directory to write one Markdown page per library and an index.md to
+    #[arg(short, long, action = clap::ArgAction::Set)]
+    out: PathBuf,
+}
+
+struct Generator {
+    out_dir: PathBuf,
+    tera: Tera,
+}
+
+static LIBRARY_TEMPLATE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/library.md.jinja"));
+static DECLS_TEMPLATE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/decls.md.jinja"));
+static INDEX_TEMPLATE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/index.md.jinja"));
+
+impl Generator {
+    fn new(out_dir: PathBuf) -> Result<Self, GeneratorError> {
+        let mut tera = Tera::default();
+
+        tera.add_raw_template("decls.md.jinja", DECLS_TEMPLATE)?;
+        tera.add_raw_template("library.md.jinja", LIBRARY_TEMPLATE)?;
+        tera.add_raw_template("index.md.jinja", INDEX_TEMPLATE)?;
+
+        Ok(Generator { out_dir, tera })
+    }
+
+    fn generate_docs(&self, roots: Vec<ir::Root>) -> Result<(), GeneratorError> {
+        std::fs::create_dir_all(&self.out_dir)?;
+
+        let links = compile::Links::new(&roots);
+        let mut libraries = vec![];
+
+        for root in roots.iter() {
+            let library = compile::compile(root, &links);
+            let file = compile::library_file(&library.name);
+
+            let out = File::create(self.out_dir.join(&file))?;
+            self.tera
+                .render_to("library.md.jinja", &Context::from_serialize(&library)?, out)?;
+
+            libraries.push(types::LibrarySummary {
+                summary: library.doc.as_deref().map(compile::summary),
+                name: library.name,
+                file,
+            });
+        }
+
+        libraries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let index = types::Index { libraries };
+        let out = File::create(self.out_dir.join("index.md"))?;
+        self.tera
+            .render_to("index.md.jinja", &Context::from_serialize(&index)?, out)?;
+
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), GeneratorError> {
+    let args = Args::parse();
+
+    let mut roots = vec![];
+    for path in args.json.iter() {
+        let contents = std::fs::read_to_string(path)?;
+        roots.push(serde_json::from_str::<ir::Root>(contents.as_str())?);
+    }
+
+    let generator = Generator::new(args.out)?;
+    generator.generate_docs(roots)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// `testdata` holds the IR of two libraries, one using the other, and the pages
+    /// expected for them in `testdata/golden`.
+    #[test]
+    fn golden() {
+        let testdata = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata");
+        let out = std::env::temp_dir().join(format!("midlgen_doc_golden_{}", std::process::id()));
+
+        let roots = ["canvas.test.json", "geometry.test.json"]
+            .iter()
+            .map(|name| {
+                let contents = std::fs::read_to_string(testdata.join(name)).unwrap();
+                serde_json::from_str::<ir::Root>(&contents).unwrap()
+            })
+            .collect();
+        Generator::new(out.clone()).unwrap().generate_docs(roots).unwrap();
+
+        for page in ["index.md", "test.canvas.md", "test.geometry.md"] {
+            let expected = std::fs::read_to_string(testdata.join("golden").join(page)).unwrap();
+            let actual = std::fs::read_to_string(out.join(page)).unwrap();
+            assert_eq!(actual, expected, "{page} differs from testdata/golden");
+        }
+
+        std::fs::remove_dir_all(out).unwrap();
+    }
+}
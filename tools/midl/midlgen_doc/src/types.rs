@@ -0,0 +1,86 @@
+use serde::Serialize;
+
+/// The landing page, listing every documented library.
+#[derive(Debug, Serialize)]
+pub struct Index {
+    pub libraries: Vec<LibrarySummary>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LibrarySummary {
+    pub name: String,
+    pub file: String,
+    /// The first paragraph of the library documentation.
+    pub summary: Option<String>,
+}
+
+/// The reference page of a single library.
+#[derive(Debug, Serialize)]
+pub struct Library {
+    pub name: String,
+    pub doc: Option<String>,
+    pub availability: Option<String>,
+    /// Links to the libraries this one depends on.
+    pub dependencies: Vec<String>,
+
+    pub protocols: Vec<Decl>,
+    pub structs: Vec<Decl>,
+    pub tables: Vec<Decl>,
+    pub unions: Vec<Decl>,
+    pub enums: Vec<Decl>,
+    pub bits: Vec<Decl>,
+    pub consts: Vec<Decl>,
+}
+
+/// A declaration as shown in the reference. Types are already rendered to Markdown, with
+/// links to the declarations they refer to.
+#[derive(Debug, Serialize)]
+pub struct Decl {
+    pub kind: String,
+    pub name: String,
+    pub anchor: String,
+    /// The declaration as written in MIDL, without its body, e.g. `type Color = strict enum : uint32`.
+    pub signature: String,
+    pub doc: Option<String>,
+    pub availability: Option<String>,
+    /// Attributes other than docs and availability, e.g. `@discoverable`.
+    pub attributes: Vec<String>,
+
+    pub members: Vec<Member>,
+    pub methods: Vec<Method>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Member {
+    pub name: String,
+    pub ordinal: Option<u64>,
+    pub r#type: Option<String>,
+    pub value: Option<String>,
+    pub doc: Option<String>,
+    pub availability: Option<String>,
+    pub reserved: bool,
+}
+
+/// The request or response of a method. Anonymous payload structs are shown as their
+/// parameters, anything else as a type.
+#[derive(Debug, Default, Serialize)]
+pub struct Payload {
+    pub r#type: Option<String>,
+    pub parameters: Vec<Member>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Method {
+    pub name: String,
+    pub anchor: String,
+    /// `one-way`, `two-way` or `event`.
+    pub kind: String,
+    pub strict: bool,
+    pub doc: Option<String>,
+    pub availability: Option<String>,
+    pub attributes: Vec<String>,
+
+    pub request: Option<Payload>,
+    pub response: Option<Payload>,
+    pub error: Option<String>,
+}
@@ -0,0 +1,2066 @@
+
+// WARNING: This file is machine generated by midlgen.
+
+#![allow(
+    unused_parens, // one-element-tuple-case is not a tuple
+    unused_mut, // not all args require mutation, but many do
+    unused_imports, // not every library needs every import
+    nonstandard_style, // auto-caps does its best, but is not always successful
+    clippy::all, // the templates favour uniform output over idiomatic code
+)]
+
+#![recursion_limit="512"]
+
+use fiber as fx;
+
+use {
+    bitflags::bitflags,
+    fiber_status as fx_status,
+    futures::future::{self, MaybeDone, TryFutureExt},
+    midl::{
+        endpoints::{ControlHandle as _, Responder as _},
+        client::QueryResponseFut,
+    },
+};
+
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(u32)]
+pub enum StoreError {
+    NotFound = 1,
+    Full = 2,
+}
+
+
+impl StoreError {
+    #[inline]
+    pub fn from_primitive(prim: u32) -> Option<Self> {
+        match prim {
+            1 => Some(Self::NotFound),
+            2 => Some(Self::Full),
+            _ => None,
+        }
+    }
+    #[inline]
+    pub const fn into_primitive(self) -> u32 {
+        self as u32
+    }
+
+    #[deprecated = "Strict enums should not use `is_unknown`"]
+    #[inline]
+    pub fn is_unknown(&self) -> bool {
+        false
+    }
+}
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Kind {
+    Small,
+    Large,
+    #[doc(hidden)]
+    __SourceBreaking { unknown_ordinal: u8 },
+}
+
+/// Pattern that matches an unknown `Kind` member.
+#[macro_export]
+macro_rules! KindUnknown {
+    () => { _ };
+}
+
+impl Kind {
+    #[inline]
+    pub fn from_primitive(prim: u8) -> Option<Self> {
+        match prim {
+            1 => Some(Self::Small),
+            2 => Some(Self::Large),
+            _ => None,
+        }
+    }
+    #[inline]
+    pub fn from_primitive_allow_unknown(prim: u8) -> Self {
+        match prim {
+            1 => Self::Small,
+            2 => Self::Large,
+            unknown_ordinal => Self::__SourceBreaking { unknown_ordinal },
+        }
+    }
+
+    #[inline]
+    pub fn unknown() -> Self {
+        Self::__SourceBreaking { unknown_ordinal: 255 }
+    }
+
+    #[inline]
+    pub const fn into_primitive(self) -> u8 {
+        match self {
+            Self::Small => 1,
+            Self::Large => 2,
+            Self::__SourceBreaking { unknown_ordinal } => unknown_ordinal,
+        }
+    }
+
+    #[inline]
+    pub fn is_unknown(&self) -> bool {
+        match self {
+            Self::__SourceBreaking { unknown_ordinal: _ } => true,
+            _ => false,
+        }
+    }
+}
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry{
+    pub id: u64,
+    pub name: String,
+}
+
+impl midl::encoding::Persistable for Entry {}
+
+/// Borrowed view of [`Entry`], decoded with
+/// `midl::encoding::Decoder::decode_view` without copying strings or vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryView<'de> {
+    pub id: <u64 as midl::encoding::ViewMarker<'de>>::View,
+    pub name: <midl::encoding::BoundedString<64> as midl::encoding::ViewMarker<'de>>::View,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoreGetRequest{
+    pub id: u64,
+}
+
+impl midl::encoding::Persistable for StoreGetRequest {}
+
+/// Borrowed view of [`StoreGetRequest`], decoded with
+/// `midl::encoding::Decoder::decode_view` without copying strings or vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoreGetRequestView<'de> {
+    pub id: <u64 as midl::encoding::ViewMarker<'de>>::View,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoreGetResponse{
+    pub entry: Entry,
+}
+
+impl midl::encoding::Persistable for StoreGetResponse {}
+
+/// Borrowed view of [`StoreGetResponse`], decoded with
+/// `midl::encoding::Decoder::decode_view` without copying strings or vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoreGetResponseView<'de> {
+    pub entry: <Entry as midl::encoding::ViewMarker<'de>>::View,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorePutRequest{
+    pub entry: Entry,
+}
+
+impl midl::encoding::Persistable for StorePutRequest {}
+
+/// Borrowed view of [`StorePutRequest`], decoded with
+/// `midl::encoding::Decoder::decode_view` without copying strings or vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorePutRequestView<'de> {
+    pub entry: <Entry as midl::encoding::ViewMarker<'de>>::View,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoreOnChangedRequest{
+    pub id: u64,
+}
+
+impl midl::encoding::Persistable for StoreOnChangedRequest {}
+
+/// Borrowed view of [`StoreOnChangedRequest`], decoded with
+/// `midl::encoding::Decoder::decode_view` without copying strings or vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoreOnChangedRequestView<'de> {
+    pub id: <u64 as midl::encoding::ViewMarker<'de>>::View,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheLookupRequest{
+    pub id: u64,
+}
+
+impl midl::encoding::Persistable for CacheLookupRequest {}
+
+/// Borrowed view of [`CacheLookupRequest`], decoded with
+/// `midl::encoding::Decoder::decode_view` without copying strings or vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheLookupRequestView<'de> {
+    pub id: <u64 as midl::encoding::ViewMarker<'de>>::View,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheLookupResponse{
+    pub entry: Entry,
+}
+
+impl midl::encoding::Persistable for CacheLookupResponse {}
+
+/// Borrowed view of [`CacheLookupResponse`], decoded with
+/// `midl::encoding::Decoder::decode_view` without copying strings or vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheLookupResponseView<'de> {
+    pub entry: <Entry as midl::encoding::ViewMarker<'de>>::View,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct StoreMarker;
+
+impl midl::endpoints::ProtocolMarker for StoreMarker {
+    type Proxy = StoreProxy;
+    type RequestStream = StoreRequestStream;
+    type SynchronousProxy = StoreSynchronousProxy;
+
+    const DEBUG_NAME: &'static str = "test.golden.Store";
+}
+
+impl midl::endpoints::DiscoverableProtocolMarker for StoreMarker {}
+
+pub type StoreGetResult = Result<Entry, StoreError>;
+
+pub trait StoreProxyInterface: Send + Sync {
+    type GetResponseFut: std::future::Future<Output = Result<StoreGetResult, midl::Error>> + Send;
+
+    fn r#get(&self,
+        id: u64,
+    )
+    -> Self::GetResponseFut;
+
+    fn r#put(&self,
+        entry: &Entry,
+    )
+    -> Result<(), midl::Error>;
+    type PingResponseFut: std::future::Future<Output = Result<(), midl::Error>> + Send;
+
+    fn r#ping(&self,
+    )
+    -> Self::PingResponseFut;
+
+}
+
+#[derive(Debug)]
+pub struct StoreSynchronousProxy {
+    client: midl::client::sync::Client,
+}
+
+impl midl::endpoints::SynchronousProxy for StoreSynchronousProxy {
+    type Proxy = StoreProxy;
+    type Protocol = StoreMarker;
+
+    fn from_channel(inner: midl::Channel) -> Self {
+        Self::new(inner)
+    }
+
+    fn into_channel(self) -> midl::Channel {
+        self.client.into_channel()
+    }
+
+    fn as_channel(&self) -> &midl::Channel {
+        self.client.as_channel()
+    }
+}
+
+impl StoreSynchronousProxy {
+    pub fn new(channel: midl::Channel) -> Self {
+        let protocol_name = <StoreMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME;
+        Self { client: midl::client::sync::Client::new(channel, protocol_name) }
+    }
+
+    pub fn into_channel(self) -> midl::Channel {
+        self.client.into_channel()
+    }
+
+    /// Waits until an event arrives and returns it. It is safe for other
+    /// threads to make concurrent requests while waiting for an event.
+    pub fn wait_for_event(&self, deadline: fx::Time) -> Result<StoreEvent, midl::Error> {
+        StoreEvent::decode(self.client.wait_for_event(deadline)?)
+    }
+
+    /// Looks up an entry.
+    pub fn r#get(
+        &self,
+        mut id: u64,
+        ___deadline: fx::Time,
+    ) -> Result<StoreGetResult, midl::Error> {
+        let out = self.client.send_query::<
+            StoreGetRequest,
+            midl::encoding::ResultType<StoreGetResponse, StoreError>,
+            false,
+            false,
+        >(
+            (id,),
+            3192224406944254481,
+            midl::encoding::DynamicFlags::empty(),
+            ___deadline,
+        )?
+;
+        Ok(out.map(|out| out.entry))
+    }
+    pub fn r#put(
+        &self,
+        mut entry: &Entry,
+    ) -> Result<(), midl::Error> {
+        self.client.send::<StorePutRequest, false>(
+            (entry,),
+            7646614623719804757,
+            midl::encoding::DynamicFlags::empty(),
+        )
+    }
+    pub fn r#ping(
+        &self,
+        ___deadline: fx::Time,
+    ) -> Result<(), midl::Error> {
+        let out = self.client.send_query::<
+            midl::encoding::EmptyPayload,
+            midl::encoding::EmptyPayload,
+            false,
+            false,
+        >(
+            (),
+            2260413647541849696,
+            midl::encoding::DynamicFlags::empty(),
+            ___deadline,
+        )?
+;
+        Ok(out)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StoreProxy {
+    client: midl::client::Client,
+}
+
+impl midl::endpoints::Proxy for StoreProxy {
+    type Protocol = StoreMarker;
+
+    fn from_channel(inner: midl::AsyncChannel) -> Self {
+        Self::new(inner)
+    }
+
+    fn into_channel(self) -> Result<::midl::AsyncChannel, Self> {
+        self.client.into_channel().map_err(|client| Self { client })
+    }
+
+    fn as_channel(&self) -> &::midl::AsyncChannel {
+        self.client.as_channel()
+    }
+}
+
+impl StoreProxy {
+    /// Create a new Proxy for test.golden.Store.
+    pub fn new(channel: midl::AsyncChannel) -> Self {
+        let protocol_name = <StoreMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME;
+        Self { client: midl::client::Client::new(channel, protocol_name) }
+    }
+
+    /// Get a Stream of events from the remote end of the protocol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the event stream was already taken.
+    pub fn take_event_stream(&self) -> StoreEventStream {
+        StoreEventStream {
+            event_receiver: self.client.take_event_receiver(),
+        }
+    }
+
+    /// Looks up an entry.
+    pub fn r#get(
+        &self,
+        mut id: u64,
+    )
+    -> midl::client::QueryResponseFut<StoreGetResult> {
+        StoreProxyInterface::r#get(self,
+            id,
+        )
+    }
+    pub fn r#put(
+        &self,
+        mut entry: &Entry,
+    )
+    -> Result<(), midl::Error> {
+        StoreProxyInterface::r#put(self,
+            entry,
+        )
+    }
+    pub fn r#ping(
+        &self,
+    )
+    -> midl::client::QueryResponseFut<()> {
+        StoreProxyInterface::r#ping(self,
+        )
+    }
+}
+
+impl StoreProxyInterface for StoreProxy {
+    type GetResponseFut = midl::client::QueryResponseFut<StoreGetResult>;
+    fn r#get(
+        &self,
+        mut id: u64,
+    ) -> Self::GetResponseFut {
+        fn _decode(mut _buf: Result<midl::MessageBufEtc, midl::Error>) -> Result<StoreGetResult, midl::Error> {
+            let out = midl::client::decode_transaction_body::<
+                midl::encoding::ResultType<StoreGetResponse, StoreError>,
+                false,
+            >(_buf?)?
+;
+            Ok(out.map(|out| out.entry))
+        }
+        self.client.send_query_and_decode::<
+            StoreGetRequest,
+            StoreGetResult,
+            false,
+        >(
+            (id,),
+            3192224406944254481,
+            midl::encoding::DynamicFlags::empty(),
+            _decode,
+        )
+    }
+    fn r#put(
+        &self,
+        mut entry: &Entry,
+    ) -> Result<(), midl::Error> {
+        self.client.send::<StorePutRequest, false>(
+            (entry,),
+            7646614623719804757,
+            midl::encoding::DynamicFlags::empty(),
+        )
+    }
+    type PingResponseFut = midl::client::QueryResponseFut<()>;
+    fn r#ping(
+        &self,
+    ) -> Self::PingResponseFut {
+        fn _decode(mut _buf: Result<midl::MessageBufEtc, midl::Error>) -> Result<(), midl::Error> {
+            let out = midl::client::decode_transaction_body::<
+                midl::encoding::EmptyPayload,
+                false,
+            >(_buf?)?
+;
+            Ok(out)
+        }
+        self.client.send_query_and_decode::<
+            midl::encoding::EmptyPayload,
+            (),
+            false,
+        >(
+            (),
+            2260413647541849696,
+            midl::encoding::DynamicFlags::empty(),
+            _decode,
+        )
+    }
+}
+
+pub struct StoreEventStream {
+    event_receiver: midl::client::EventReceiver,
+}
+
+impl std::marker::Unpin for StoreEventStream {}
+
+impl futures::stream::FusedStream for StoreEventStream {
+    fn is_terminated(&self) -> bool {
+        self.event_receiver.is_terminated()
+    }
+}
+
+impl futures::Stream for StoreEventStream {
+    type Item = Result<StoreEvent, midl::Error>;
+
+    fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>)
+        -> std::task::Poll<Option<Self::Item>>
+    {
+        match futures::ready!(
+            futures::stream::StreamExt::poll_next_unpin(&mut self.event_receiver, cx)?
+        ) {
+            Some(buf) => std::task::Poll::Ready(Some(StoreEvent::decode(buf))),
+            None => std::task::Poll::Ready(None),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum StoreEvent {
+    OnChanged {
+        id: u64,
+    },
+
+}
+
+impl StoreEvent {
+    #[allow(irrefutable_let_patterns)]
+    pub fn into_on_changed(self) -> Option<u64> {
+        if let StoreEvent::OnChanged {
+            id,
+        } = self {
+            Some((
+                id
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Decodes a message buffer as a [`StoreEvent`].
+    fn decode(mut buf: midl::MessageBufEtc) -> Result<StoreEvent, midl::Error> {
+        let (bytes, _handles) = buf.split_mut();
+        let (tx_header, _body_bytes) = midl::encoding::decode_transaction_header(bytes)?;
+        debug_assert_eq!(tx_header.tx_id(), 0);
+        match tx_header.ordinal() {
+            4290836836681525828 => {
+                let mut out = midl::new_empty!(StoreOnChangedRequest);
+                midl::encoding::Decoder::decode_into::<StoreOnChangedRequest>(&tx_header, _body_bytes, _handles, &mut out)?;
+                Ok(StoreEvent::OnChanged {
+                    id: out.id,
+                })
+            }
+            _ => Err(midl::Error::UnknownOrdinal {
+                ordinal: tx_header.ordinal(),
+                protocol_name: <StoreMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME,
+            })
+        }
+    }
+}
+
+/// A Stream of incoming requests for test.golden.Store.
+pub struct StoreRequestStream {
+    inner: std::sync::Arc<midl::ServeInner>,
+    is_terminated: bool,
+}
+
+impl std::marker::Unpin for StoreRequestStream {}
+
+impl futures::stream::FusedStream for StoreRequestStream {
+    fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+}
+
+impl midl::endpoints::RequestStream for StoreRequestStream {
+    type Protocol = StoreMarker;
+    type ControlHandle = StoreControlHandle;
+
+    fn from_channel(channel: midl::AsyncChannel) -> Self {
+        Self {
+            inner: std::sync::Arc::new(midl::ServeInner::new(channel, <StoreMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME)),
+            is_terminated: false,
+        }
+    }
+
+    fn control_handle(&self) -> Self::ControlHandle {
+        StoreControlHandle { inner: self.inner.clone() }
+    }
+
+    fn into_inner(self) -> (::std::sync::Arc<midl::ServeInner>, bool) {
+        (self.inner, self.is_terminated)
+    }
+
+    fn from_inner(inner: std::sync::Arc<midl::ServeInner>, is_terminated: bool) -> Self {
+        Self { inner, is_terminated }
+    }
+}
+
+impl futures::Stream for StoreRequestStream {
+    type Item = Result<StoreRequest, midl::Error>;
+
+    fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>)
+        -> std::task::Poll<Option<Self::Item>>
+    {
+        let this = &mut *self;
+        if this.inner.poll_shutdown(cx) {
+            this.is_terminated = true;
+            return std::task::Poll::Ready(None);
+        }
+        if this.is_terminated {
+            panic!("polled StoreRequestStream after completion");
+        }
+        midl::encoding::with_tls_decode_buf(|bytes, handles| {
+            match this.inner.poll_read_request(cx, bytes, handles) {
+                std::task::Poll::Ready(Ok(())) => {},
+                std::task::Poll::Pending => return std::task::Poll::Pending,
+                std::task::Poll::Ready(Err(fx_status::Status::PEER_CLOSED)) => {
+                    this.is_terminated = true;
+                    return std::task::Poll::Ready(None);
+                }
+                std::task::Poll::Ready(Err(e)) => return std::task::Poll::Ready(Some(Err(midl::Error::ServerRequestRead(e)))),
+            }
+
+            // A message has been received from the channel
+            let (header, _body_bytes) = midl::encoding::decode_transaction_header(bytes)?;
+
+            std::task::Poll::Ready(Some(match header.ordinal() {
+                3192224406944254481 => {
+                    header.validate_request_tx_id(midl::encoding::MethodType::TwoWay)?;
+                    let mut out = midl::new_empty!(StoreGetRequest);
+                    midl::encoding::Decoder::decode_into::<StoreGetRequest>(&header, _body_bytes, handles, &mut out)?;
+                    let control_handle = StoreControlHandle {
+                        inner: this.inner.clone(),
+                    };
+                    Ok(StoreRequest::Get {
+                        id: out.id,
+                        responder: StoreGetResponder {
+                            control_handle: std::mem::ManuallyDrop::new(control_handle),
+                            tx_id: header.tx_id(),
+                        },
+                    })
+                }
+                7646614623719804757 => {
+                    header.validate_request_tx_id(midl::encoding::MethodType::OneWay)?;
+                    let mut out = midl::new_empty!(StorePutRequest);
+                    midl::encoding::Decoder::decode_into::<StorePutRequest>(&header, _body_bytes, handles, &mut out)?;
+                    let control_handle = StoreControlHandle {
+                        inner: this.inner.clone(),
+                    };
+                    Ok(StoreRequest::Put {
+                        entry: out.entry,
+                        control_handle,
+                    })
+                }
+                2260413647541849696 => {
+                    header.validate_request_tx_id(midl::encoding::MethodType::TwoWay)?;
+                    let mut out = midl::new_empty!(midl::encoding::EmptyPayload);
+                    midl::encoding::Decoder::decode_into::<midl::encoding::EmptyPayload>(&header, _body_bytes, handles, &mut out)?;
+                    let control_handle = StoreControlHandle {
+                        inner: this.inner.clone(),
+                    };
+                    Ok(StoreRequest::Ping {
+                        
+                        responder: StorePingResponder {
+                            control_handle: std::mem::ManuallyDrop::new(control_handle),
+                            tx_id: header.tx_id(),
+                        },
+                    })
+                }
+                _ => Err(midl::Error::UnknownOrdinal {
+                    ordinal: header.ordinal(),
+                    protocol_name: <StoreMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME,
+                }),
+            }))
+        })
+    }
+}
+
+/// Keeps entries by id.
+#[derive(Debug)]
+pub enum StoreRequest {
+    /// Looks up an entry.
+    Get {
+        id: u64,
+        responder: StoreGetResponder,
+    },
+    Put {
+        entry: Entry,
+        control_handle: StoreControlHandle,
+    },
+    Ping {
+        responder: StorePingResponder,
+    },
+
+}
+
+impl StoreRequest {
+    #[allow(irrefutable_let_patterns)]
+    pub fn into_get(self) -> Option<(
+        u64,
+        StoreGetResponder
+    )> {
+        if let StoreRequest::Get {
+            id,
+            responder,
+        } = self {
+            Some((
+                id,
+                responder
+            ))
+        } else {
+            None
+        }
+    }
+    #[allow(irrefutable_let_patterns)]
+    pub fn into_put(self) -> Option<(
+        Entry,
+        StoreControlHandle
+    )> {
+        if let StoreRequest::Put {
+            entry,
+            control_handle,
+        } = self {
+            Some((
+                entry,
+                control_handle
+            ))
+        } else {
+            None
+        }
+    }
+    #[allow(irrefutable_let_patterns)]
+    pub fn into_ping(self) -> Option<(
+        StorePingResponder
+    )> {
+        if let StoreRequest::Ping {
+            responder,
+        } = self {
+            Some((
+                responder
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Name of the method defined in MIDL
+    pub fn method_name(&self) -> &'static str {
+        match *self {
+            StoreRequest::Get { .. } => "get",
+            StoreRequest::Put { .. } => "put",
+            StoreRequest::Ping { .. } => "ping",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StoreControlHandle {
+    inner: std::sync::Arc<midl::ServeInner>,
+}
+
+impl midl::endpoints::ControlHandle for StoreControlHandle {
+    fn shutdown(&self) {
+        self.inner.shutdown()
+    }
+
+    fn shutdown_with_epitaph(&self, status: fx_status::Status) {
+        self.inner.shutdown_with_epitaph(status)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.channel().is_closed()
+    }
+
+    fn on_closed<'a>(&'a self) -> midl::OnSignals<'a> {
+        self.inner.channel().on_closed()
+    }
+}
+
+impl StoreControlHandle {
+    pub fn send_on_changed(&self,
+        mut id: u64,
+    ) -> Result<(), midl::Error> {
+        self.inner.send::<StoreOnChangedRequest, false>(
+            (id,),
+            0,
+            4290836836681525828,
+            midl::encoding::DynamicFlags::empty(),
+        )
+    }
+}
+
+#[must_use = "MIDL methods require a response to be sent"]
+#[derive(Debug)]
+pub struct StoreGetResponder {
+    control_handle: std::mem::ManuallyDrop<StoreControlHandle>,
+    tx_id: u32,
+}
+
+/// Set the the channel to be shutdown (see [`StoreControlHandle::shutdown`])
+/// if the responder is dropped without sending a response, so that the client
+/// doesn't hang. To prevent this behavior, call `drop_without_shutdown`.
+impl std::ops::Drop for StoreGetResponder {
+    fn drop(&mut self) {
+        self.control_handle.shutdown();
+        // Safety: drops once, never accessed again
+        unsafe { std::mem::ManuallyDrop::drop(&mut self.control_handle) };
+    }
+}
+
+impl midl::endpoints::Responder for StoreGetResponder {
+    type ControlHandle = StoreControlHandle;
+
+    fn control_handle(&self) -> &StoreControlHandle {
+        &self.control_handle
+    }
+
+    fn drop_without_shutdown(mut self) {
+        // Safety: drops once, never accessed again due to mem::forget
+        unsafe { std::mem::ManuallyDrop::drop(&mut self.control_handle) };
+        // Prevent Drop from running (which would shut down the channel)
+        std::mem::forget(self);
+    }
+}
+
+impl StoreGetResponder {
+    /// Sends a response to the MIDL transaction.
+    ///
+    /// Sets the channel to shutdown if an error occurs.
+    pub fn send(self,
+        mut result: Result<&Entry, StoreError>,
+    ) -> Result<(), midl::Error> {
+        let _result = self.send_raw(
+            result,
+        );
+        if _result.is_err() {
+            self.control_handle.shutdown();
+        }
+        self.drop_without_shutdown();
+        _result
+    }
+
+    /// Similar to "send" but does not shutdown the channel if an error occurs.
+    pub fn send_no_shutdown_on_err(self,
+        mut result: Result<&Entry, StoreError>,
+    ) -> Result<(), midl::Error> {
+        let _result = self.send_raw(
+            result,
+        );
+        self.drop_without_shutdown();
+        _result
+    }
+
+    fn send_raw(
+        &self,
+        mut result: Result<&Entry, StoreError>,
+    ) -> Result<(), midl::Error> {
+        self.control_handle.inner.send::<midl::encoding::ResultType<StoreGetResponse, StoreError>, false>(
+            result.map(|entry| (entry,)),
+            self.tx_id,
+            3192224406944254481,
+            midl::encoding::DynamicFlags::empty(),
+        )
+    }
+}
+#[must_use = "MIDL methods require a response to be sent"]
+#[derive(Debug)]
+pub struct StorePingResponder {
+    control_handle: std::mem::ManuallyDrop<StoreControlHandle>,
+    tx_id: u32,
+}
+
+/// Set the the channel to be shutdown (see [`StoreControlHandle::shutdown`])
+/// if the responder is dropped without sending a response, so that the client
+/// doesn't hang. To prevent this behavior, call `drop_without_shutdown`.
+impl std::ops::Drop for StorePingResponder {
+    fn drop(&mut self) {
+        self.control_handle.shutdown();
+        // Safety: drops once, never accessed again
+        unsafe { std::mem::ManuallyDrop::drop(&mut self.control_handle) };
+    }
+}
+
+impl midl::endpoints::Responder for StorePingResponder {
+    type ControlHandle = StoreControlHandle;
+
+    fn control_handle(&self) -> &StoreControlHandle {
+        &self.control_handle
+    }
+
+    fn drop_without_shutdown(mut self) {
+        // Safety: drops once, never accessed again due to mem::forget
+        unsafe { std::mem::ManuallyDrop::drop(&mut self.control_handle) };
+        // Prevent Drop from running (which would shut down the channel)
+        std::mem::forget(self);
+    }
+}
+
+impl StorePingResponder {
+    /// Sends a response to the MIDL transaction.
+    ///
+    /// Sets the channel to shutdown if an error occurs.
+    pub fn send(self,
+    ) -> Result<(), midl::Error> {
+        let _result = self.send_raw(
+        );
+        if _result.is_err() {
+            self.control_handle.shutdown();
+        }
+        self.drop_without_shutdown();
+        _result
+    }
+
+    /// Similar to "send" but does not shutdown the channel if an error occurs.
+    pub fn send_no_shutdown_on_err(self,
+    ) -> Result<(), midl::Error> {
+        let _result = self.send_raw(
+        );
+        self.drop_without_shutdown();
+        _result
+    }
+
+    fn send_raw(
+        &self,
+    ) -> Result<(), midl::Error> {
+        self.control_handle.inner.send::<midl::encoding::EmptyPayload, false>(
+            (),
+            self.tx_id,
+            2260413647541849696,
+            midl::encoding::DynamicFlags::empty(),
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CacheMarker;
+
+impl midl::endpoints::ProtocolMarker for CacheMarker {
+    type Proxy = CacheProxy;
+    type RequestStream = CacheRequestStream;
+    type SynchronousProxy = CacheSynchronousProxy;
+
+    const DEBUG_NAME: &'static str = "test.golden.Cache";
+}
+
+
+
+pub trait CacheProxyInterface: Send + Sync {
+    type LookupResponseFut: std::future::Future<Output = Result<Entry, midl::Error>> + Send;
+
+    fn r#lookup(&self,
+        id: u64,
+    )
+    -> Self::LookupResponseFut;
+}
+
+#[derive(Debug)]
+pub struct CacheSynchronousProxy {
+    client: midl::client::sync::Client,
+}
+
+impl midl::endpoints::SynchronousProxy for CacheSynchronousProxy {
+    type Proxy = CacheProxy;
+    type Protocol = CacheMarker;
+
+    fn from_channel(inner: midl::Channel) -> Self {
+        Self::new(inner)
+    }
+
+    fn into_channel(self) -> midl::Channel {
+        self.client.into_channel()
+    }
+
+    fn as_channel(&self) -> &midl::Channel {
+        self.client.as_channel()
+    }
+}
+
+impl CacheSynchronousProxy {
+    pub fn new(channel: midl::Channel) -> Self {
+        let protocol_name = <CacheMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME;
+        Self { client: midl::client::sync::Client::new(channel, protocol_name) }
+    }
+
+    pub fn into_channel(self) -> midl::Channel {
+        self.client.into_channel()
+    }
+
+    /// Waits until an event arrives and returns it. It is safe for other
+    /// threads to make concurrent requests while waiting for an event.
+    pub fn wait_for_event(&self, deadline: fx::Time) -> Result<CacheEvent, midl::Error> {
+        CacheEvent::decode(self.client.wait_for_event(deadline)?)
+    }
+
+    pub fn r#lookup(
+        &self,
+        mut id: u64,
+        ___deadline: fx::Time,
+    ) -> Result<Entry, midl::Error> {
+        let out = self.client.send_query::<
+            CacheLookupRequest,
+            midl::encoding::FlexibleType<CacheLookupResponse>,
+            false,
+            false,
+        >(
+            (id,),
+            972261416049252405,
+            midl::encoding::DynamicFlags::FLEXIBLE,
+            ___deadline,
+        )?
+        .into_result::<CacheMarker>("lookup")?
+        ;
+        Ok(out.entry)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheProxy {
+    client: midl::client::Client,
+}
+
+impl midl::endpoints::Proxy for CacheProxy {
+    type Protocol = CacheMarker;
+
+    fn from_channel(inner: midl::AsyncChannel) -> Self {
+        Self::new(inner)
+    }
+
+    fn into_channel(self) -> Result<::midl::AsyncChannel, Self> {
+        self.client.into_channel().map_err(|client| Self { client })
+    }
+
+    fn as_channel(&self) -> &::midl::AsyncChannel {
+        self.client.as_channel()
+    }
+}
+
+impl CacheProxy {
+    /// Create a new Proxy for test.golden.Cache.
+    pub fn new(channel: midl::AsyncChannel) -> Self {
+        let protocol_name = <CacheMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME;
+        Self { client: midl::client::Client::new(channel, protocol_name) }
+    }
+
+    /// Get a Stream of events from the remote end of the protocol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the event stream was already taken.
+    pub fn take_event_stream(&self) -> CacheEventStream {
+        CacheEventStream {
+            event_receiver: self.client.take_event_receiver(),
+        }
+    }
+
+    pub fn r#lookup(
+        &self,
+        mut id: u64,
+    )
+    -> midl::client::QueryResponseFut<Entry> {
+        CacheProxyInterface::r#lookup(self,
+            id,
+        )
+    }
+}
+
+impl CacheProxyInterface for CacheProxy {
+    type LookupResponseFut = midl::client::QueryResponseFut<Entry>;
+    fn r#lookup(
+        &self,
+        mut id: u64,
+    ) -> Self::LookupResponseFut {
+        fn _decode(mut _buf: Result<midl::MessageBufEtc, midl::Error>) -> Result<Entry, midl::Error> {
+            let out = midl::client::decode_transaction_body::<
+                midl::encoding::FlexibleType<CacheLookupResponse>,
+                false,
+            >(_buf?)?
+            .into_result::<CacheMarker>("lookup")?
+            ;
+            Ok(out.entry)
+        }
+        self.client.send_query_and_decode::<
+            CacheLookupRequest,
+            Entry,
+            false,
+        >(
+            (id,),
+            972261416049252405,
+            midl::encoding::DynamicFlags::FLEXIBLE,
+            _decode,
+        )
+    }
+}
+
+pub struct CacheEventStream {
+    event_receiver: midl::client::EventReceiver,
+}
+
+impl std::marker::Unpin for CacheEventStream {}
+
+impl futures::stream::FusedStream for CacheEventStream {
+    fn is_terminated(&self) -> bool {
+        self.event_receiver.is_terminated()
+    }
+}
+
+impl futures::Stream for CacheEventStream {
+    type Item = Result<CacheEvent, midl::Error>;
+
+    fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>)
+        -> std::task::Poll<Option<Self::Item>>
+    {
+        match futures::ready!(
+            futures::stream::StreamExt::poll_next_unpin(&mut self.event_receiver, cx)?
+        ) {
+            Some(buf) => std::task::Poll::Ready(Some(CacheEvent::decode(buf))),
+            None => std::task::Poll::Ready(None),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CacheEvent {
+
+    #[non_exhaustive]
+    _UnknownEvent {
+        /// Ordinal of the event that was sent.
+        ordinal: u64,
+    },
+}
+
+impl CacheEvent {
+
+    /// Decodes a message buffer as a [`CacheEvent`].
+    fn decode(mut buf: midl::MessageBufEtc) -> Result<CacheEvent, midl::Error> {
+        let (bytes, _handles) = buf.split_mut();
+        let (tx_header, _body_bytes) = midl::encoding::decode_transaction_header(bytes)?;
+        debug_assert_eq!(tx_header.tx_id(), 0);
+        match tx_header.ordinal() {
+            _ if tx_header.dynamic_flags().contains(midl::encoding::DynamicFlags::FLEXIBLE) => {
+                Ok(CacheEvent::_UnknownEvent {
+                    ordinal: tx_header.ordinal(),
+                })
+            }
+            _ => Err(midl::Error::UnknownOrdinal {
+                ordinal: tx_header.ordinal(),
+                protocol_name: <CacheMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME,
+            })
+        }
+    }
+}
+
+/// A Stream of incoming requests for test.golden.Cache.
+pub struct CacheRequestStream {
+    inner: std::sync::Arc<midl::ServeInner>,
+    is_terminated: bool,
+}
+
+impl std::marker::Unpin for CacheRequestStream {}
+
+impl futures::stream::FusedStream for CacheRequestStream {
+    fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+}
+
+impl midl::endpoints::RequestStream for CacheRequestStream {
+    type Protocol = CacheMarker;
+    type ControlHandle = CacheControlHandle;
+
+    fn from_channel(channel: midl::AsyncChannel) -> Self {
+        Self {
+            inner: std::sync::Arc::new(midl::ServeInner::new(channel, <CacheMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME)),
+            is_terminated: false,
+        }
+    }
+
+    fn control_handle(&self) -> Self::ControlHandle {
+        CacheControlHandle { inner: self.inner.clone() }
+    }
+
+    fn into_inner(self) -> (::std::sync::Arc<midl::ServeInner>, bool) {
+        (self.inner, self.is_terminated)
+    }
+
+    fn from_inner(inner: std::sync::Arc<midl::ServeInner>, is_terminated: bool) -> Self {
+        Self { inner, is_terminated }
+    }
+}
+
+impl futures::Stream for CacheRequestStream {
+    type Item = Result<CacheRequest, midl::Error>;
+
+    fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>)
+        -> std::task::Poll<Option<Self::Item>>
+    {
+        let this = &mut *self;
+        if this.inner.poll_shutdown(cx) {
+            this.is_terminated = true;
+            return std::task::Poll::Ready(None);
+        }
+        if this.is_terminated {
+            panic!("polled CacheRequestStream after completion");
+        }
+        midl::encoding::with_tls_decode_buf(|bytes, handles| {
+            match this.inner.poll_read_request(cx, bytes, handles) {
+                std::task::Poll::Ready(Ok(())) => {},
+                std::task::Poll::Pending => return std::task::Poll::Pending,
+                std::task::Poll::Ready(Err(fx_status::Status::PEER_CLOSED)) => {
+                    this.is_terminated = true;
+                    return std::task::Poll::Ready(None);
+                }
+                std::task::Poll::Ready(Err(e)) => return std::task::Poll::Ready(Some(Err(midl::Error::ServerRequestRead(e)))),
+            }
+
+            // A message has been received from the channel
+            let (header, _body_bytes) = midl::encoding::decode_transaction_header(bytes)?;
+
+            std::task::Poll::Ready(Some(match header.ordinal() {
+                972261416049252405 => {
+                    header.validate_request_tx_id(midl::encoding::MethodType::TwoWay)?;
+                    let mut out = midl::new_empty!(CacheLookupRequest);
+                    midl::encoding::Decoder::decode_into::<CacheLookupRequest>(&header, _body_bytes, handles, &mut out)?;
+                    let control_handle = CacheControlHandle {
+                        inner: this.inner.clone(),
+                    };
+                    Ok(CacheRequest::Lookup {
+                        id: out.id,
+                        responder: CacheLookupResponder {
+                            control_handle: std::mem::ManuallyDrop::new(control_handle),
+                            tx_id: header.tx_id(),
+                        },
+                    })
+                }
+                _ if header.tx_id() == 0 && header.dynamic_flags().contains(midl::encoding::DynamicFlags::FLEXIBLE) => {
+                    Ok(CacheRequest::_UnknownMethod {
+                        ordinal: header.ordinal(),
+                        control_handle: CacheControlHandle { inner: this.inner.clone() },
+                        method_type: midl::endpoints::UnknownMethodType::OneWay,
+                    })
+                }
+                _ if header.dynamic_flags().contains(midl::encoding::DynamicFlags::FLEXIBLE) => {
+                    this.inner.send_framework_err(
+                        midl::encoding::FrameworkErr::UnknownMethod,
+                        header.tx_id(),
+                        header.ordinal(),
+                        header.dynamic_flags(),
+                    )?;
+                    Ok(CacheRequest::_UnknownMethod {
+                        ordinal: header.ordinal(),
+                        control_handle: CacheControlHandle { inner: this.inner.clone() },
+                        method_type: midl::endpoints::UnknownMethodType::TwoWay,
+                    })
+                }
+                _ => Err(midl::Error::UnknownOrdinal {
+                    ordinal: header.ordinal(),
+                    protocol_name: <CacheMarker as midl::endpoints::ProtocolMarker>::DEBUG_NAME,
+                }),
+            }))
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum CacheRequest {
+    Lookup {
+        id: u64,
+        responder: CacheLookupResponder,
+    },
+
+    /// An interaction was received which does not match any known method.
+    #[non_exhaustive]
+    _UnknownMethod {
+        /// Ordinal of the method that was called.
+        ordinal: u64,
+        control_handle: CacheControlHandle,
+        method_type: midl::endpoints::UnknownMethodType,
+    },
+}
+
+impl CacheRequest {
+    #[allow(irrefutable_let_patterns)]
+    pub fn into_lookup(self) -> Option<(
+        u64,
+        CacheLookupResponder
+    )> {
+        if let CacheRequest::Lookup {
+            id,
+            responder,
+        } = self {
+            Some((
+                id,
+                responder
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Name of the method defined in MIDL
+    pub fn method_name(&self) -> &'static str {
+        match *self {
+            CacheRequest::Lookup { .. } => "lookup",
+            CacheRequest::_UnknownMethod {
+                method_type: midl::endpoints::UnknownMethodType::OneWay, ..
+            } => "unknown one-way method",
+            CacheRequest::_UnknownMethod {
+                method_type: midl::endpoints::UnknownMethodType::TwoWay, ..
+            } => "unknown two-way method",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheControlHandle {
+    inner: std::sync::Arc<midl::ServeInner>,
+}
+
+impl midl::endpoints::ControlHandle for CacheControlHandle {
+    fn shutdown(&self) {
+        self.inner.shutdown()
+    }
+
+    fn shutdown_with_epitaph(&self, status: fx_status::Status) {
+        self.inner.shutdown_with_epitaph(status)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.channel().is_closed()
+    }
+
+    fn on_closed<'a>(&'a self) -> midl::OnSignals<'a> {
+        self.inner.channel().on_closed()
+    }
+}
+
+impl CacheControlHandle {
+}
+
+#[must_use = "MIDL methods require a response to be sent"]
+#[derive(Debug)]
+pub struct CacheLookupResponder {
+    control_handle: std::mem::ManuallyDrop<CacheControlHandle>,
+    tx_id: u32,
+}
+
+/// Set the the channel to be shutdown (see [`CacheControlHandle::shutdown`])
+/// if the responder is dropped without sending a response, so that the client
+/// doesn't hang. To prevent this behavior, call `drop_without_shutdown`.
+impl std::ops::Drop for CacheLookupResponder {
+    fn drop(&mut self) {
+        self.control_handle.shutdown();
+        // Safety: drops once, never accessed again
+        unsafe { std::mem::ManuallyDrop::drop(&mut self.control_handle) };
+    }
+}
+
+impl midl::endpoints::Responder for CacheLookupResponder {
+    type ControlHandle = CacheControlHandle;
+
+    fn control_handle(&self) -> &CacheControlHandle {
+        &self.control_handle
+    }
+
+    fn drop_without_shutdown(mut self) {
+        // Safety: drops once, never accessed again due to mem::forget
+        unsafe { std::mem::ManuallyDrop::drop(&mut self.control_handle) };
+        // Prevent Drop from running (which would shut down the channel)
+        std::mem::forget(self);
+    }
+}
+
+impl CacheLookupResponder {
+    /// Sends a response to the MIDL transaction.
+    ///
+    /// Sets the channel to shutdown if an error occurs.
+    pub fn send(self,
+        mut entry: &Entry,
+    ) -> Result<(), midl::Error> {
+        let _result = self.send_raw(
+            entry,
+        );
+        if _result.is_err() {
+            self.control_handle.shutdown();
+        }
+        self.drop_without_shutdown();
+        _result
+    }
+
+    /// Similar to "send" but does not shutdown the channel if an error occurs.
+    pub fn send_no_shutdown_on_err(self,
+        mut entry: &Entry,
+    ) -> Result<(), midl::Error> {
+        let _result = self.send_raw(
+            entry,
+        );
+        self.drop_without_shutdown();
+        _result
+    }
+
+    fn send_raw(
+        &self,
+        mut entry: &Entry,
+    ) -> Result<(), midl::Error> {
+        self.control_handle.inner.send::<midl::encoding::FlexibleType<CacheLookupResponse>, false>(
+            midl::encoding::Flexible::new((entry,)),
+            self.tx_id,
+            972261416049252405,
+            midl::encoding::DynamicFlags::FLEXIBLE,
+        )
+    }
+}
+
+mod internal {
+    use super::*;
+
+    
+    unsafe impl midl::encoding::TypeMarker for Entry {
+        type Owned = Self;
+    
+        #[inline(always)]
+        fn inline_align(_context: midl::encoding::Context) -> usize {
+            8
+        }
+    
+        #[inline(always)]
+        fn inline_size(_context: midl::encoding::Context) -> usize {
+            24
+        }
+    
+}
+    
+impl midl::encoding::ValueTypeMarker for Entry {
+        type Borrowed<'a> = &'a Self;
+        fn borrow<'a>(value: &'a <Self as midl::encoding::TypeMarker>::Owned) -> Self::Borrowed<'a> {
+            value
+        }
+    }
+
+    unsafe impl midl::encoding::Encode<Entry> for &Entry {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<Entry>(offset);
+            // Delegate to tuple encoding.
+                midl::encoding::Encode::<Entry>::encode(
+                    (
+                    <u64 as midl::encoding::ValueTypeMarker>::borrow(&self.id),
+                    <midl::encoding::BoundedString<64> as midl::encoding::ValueTypeMarker>::borrow(&self.name),
+                ),
+                    encoder, offset, _depth
+                )
+    }
+    }
+    
+unsafe impl< T0: midl::encoding::Encode<u64>, T1: midl::encoding::Encode<midl::encoding::BoundedString<64>>,>
+        midl::encoding::Encode<Entry> for ( T0, T1,)
+    {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<Entry>(offset);
+            // Zero out padding regions. There's no need to apply masks
+            // because the unmasked parts will be overwritten by fields.
+        // Write the fields.
+        self.0.encode(encoder, offset + 0, depth)?;
+        self.1.encode(encoder, offset + 8, depth)?;
+        Ok(())
+        }
+    }
+
+    impl midl::encoding::Decode<Self> for Entry {
+        #[inline(always)]
+        fn new_empty() -> Self {
+            Self  {
+            id: midl::new_empty!(u64),
+            name: midl::new_empty!(midl::encoding::BoundedString<64>),
+        }
+    }
+    
+        #[inline]
+        unsafe fn decode(&mut self, decoder: &mut midl::encoding::Decoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+            midl::decode!(u64, &mut self.id, decoder, offset + 0, _depth)?;
+            midl::decode!(midl::encoding::BoundedString<64>, &mut self.name, decoder, offset + 8, _depth)?;
+            Ok(())
+    }
+    }
+    
+impl<'de> midl::encoding::ViewMarker<'de> for Entry {
+        type View = EntryView<'de>;
+    
+        #[inline]
+        unsafe fn decode_view(decoder: &mut midl::encoding::Decoder<'de>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<Self::View> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+        Ok(EntryView {
+            id: <u64 as midl::encoding::ViewMarker<'de>>::decode_view(decoder, offset + 0, _depth)?,
+            name: <midl::encoding::BoundedString<64> as midl::encoding::ViewMarker<'de>>::decode_view(decoder, offset + 8, _depth)?,
+        })
+        }
+    }
+    
+    unsafe impl midl::encoding::TypeMarker for StoreGetRequest {
+        type Owned = Self;
+    
+        #[inline(always)]
+        fn inline_align(_context: midl::encoding::Context) -> usize {
+            8
+        }
+    
+        #[inline(always)]
+        fn inline_size(_context: midl::encoding::Context) -> usize {
+            8
+        }
+    
+}
+    
+impl midl::encoding::ValueTypeMarker for StoreGetRequest {
+        type Borrowed<'a> = &'a Self;
+        fn borrow<'a>(value: &'a <Self as midl::encoding::TypeMarker>::Owned) -> Self::Borrowed<'a> {
+            value
+        }
+    }
+
+    unsafe impl midl::encoding::Encode<StoreGetRequest> for &StoreGetRequest {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<StoreGetRequest>(offset);
+            // Delegate to tuple encoding.
+                midl::encoding::Encode::<StoreGetRequest>::encode(
+                    (
+                    <u64 as midl::encoding::ValueTypeMarker>::borrow(&self.id),
+                ),
+                    encoder, offset, _depth
+                )
+    }
+    }
+    
+unsafe impl< T0: midl::encoding::Encode<u64>,>
+        midl::encoding::Encode<StoreGetRequest> for ( T0,)
+    {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<StoreGetRequest>(offset);
+            // Zero out padding regions. There's no need to apply masks
+            // because the unmasked parts will be overwritten by fields.
+        // Write the fields.
+        self.0.encode(encoder, offset + 0, depth)?;
+        Ok(())
+        }
+    }
+
+    impl midl::encoding::Decode<Self> for StoreGetRequest {
+        #[inline(always)]
+        fn new_empty() -> Self {
+            Self  {
+            id: midl::new_empty!(u64),
+        }
+    }
+    
+        #[inline]
+        unsafe fn decode(&mut self, decoder: &mut midl::encoding::Decoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+            midl::decode!(u64, &mut self.id, decoder, offset + 0, _depth)?;
+            Ok(())
+    }
+    }
+    
+impl<'de> midl::encoding::ViewMarker<'de> for StoreGetRequest {
+        type View = StoreGetRequestView<'de>;
+    
+        #[inline]
+        unsafe fn decode_view(decoder: &mut midl::encoding::Decoder<'de>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<Self::View> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+        Ok(StoreGetRequestView {
+            id: <u64 as midl::encoding::ViewMarker<'de>>::decode_view(decoder, offset + 0, _depth)?,
+        })
+        }
+    }
+    
+    unsafe impl midl::encoding::TypeMarker for StoreGetResponse {
+        type Owned = Self;
+    
+        #[inline(always)]
+        fn inline_align(_context: midl::encoding::Context) -> usize {
+            8
+        }
+    
+        #[inline(always)]
+        fn inline_size(_context: midl::encoding::Context) -> usize {
+            24
+        }
+    
+}
+    
+impl midl::encoding::ValueTypeMarker for StoreGetResponse {
+        type Borrowed<'a> = &'a Self;
+        fn borrow<'a>(value: &'a <Self as midl::encoding::TypeMarker>::Owned) -> Self::Borrowed<'a> {
+            value
+        }
+    }
+
+    unsafe impl midl::encoding::Encode<StoreGetResponse> for &StoreGetResponse {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<StoreGetResponse>(offset);
+            // Delegate to tuple encoding.
+                midl::encoding::Encode::<StoreGetResponse>::encode(
+                    (
+                    <Entry as midl::encoding::ValueTypeMarker>::borrow(&self.entry),
+                ),
+                    encoder, offset, _depth
+                )
+    }
+    }
+    
+unsafe impl< T0: midl::encoding::Encode<Entry>,>
+        midl::encoding::Encode<StoreGetResponse> for ( T0,)
+    {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<StoreGetResponse>(offset);
+            // Zero out padding regions. There's no need to apply masks
+            // because the unmasked parts will be overwritten by fields.
+        // Write the fields.
+        self.0.encode(encoder, offset + 0, depth)?;
+        Ok(())
+        }
+    }
+
+    impl midl::encoding::Decode<Self> for StoreGetResponse {
+        #[inline(always)]
+        fn new_empty() -> Self {
+            Self  {
+            entry: midl::new_empty!(Entry),
+        }
+    }
+    
+        #[inline]
+        unsafe fn decode(&mut self, decoder: &mut midl::encoding::Decoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+            midl::decode!(Entry, &mut self.entry, decoder, offset + 0, _depth)?;
+            Ok(())
+    }
+    }
+    
+impl<'de> midl::encoding::ViewMarker<'de> for StoreGetResponse {
+        type View = StoreGetResponseView<'de>;
+    
+        #[inline]
+        unsafe fn decode_view(decoder: &mut midl::encoding::Decoder<'de>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<Self::View> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+        Ok(StoreGetResponseView {
+            entry: <Entry as midl::encoding::ViewMarker<'de>>::decode_view(decoder, offset + 0, _depth)?,
+        })
+        }
+    }
+    
+    unsafe impl midl::encoding::TypeMarker for StorePutRequest {
+        type Owned = Self;
+    
+        #[inline(always)]
+        fn inline_align(_context: midl::encoding::Context) -> usize {
+            8
+        }
+    
+        #[inline(always)]
+        fn inline_size(_context: midl::encoding::Context) -> usize {
+            24
+        }
+    
+}
+    
+impl midl::encoding::ValueTypeMarker for StorePutRequest {
+        type Borrowed<'a> = &'a Self;
+        fn borrow<'a>(value: &'a <Self as midl::encoding::TypeMarker>::Owned) -> Self::Borrowed<'a> {
+            value
+        }
+    }
+
+    unsafe impl midl::encoding::Encode<StorePutRequest> for &StorePutRequest {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<StorePutRequest>(offset);
+            // Delegate to tuple encoding.
+                midl::encoding::Encode::<StorePutRequest>::encode(
+                    (
+                    <Entry as midl::encoding::ValueTypeMarker>::borrow(&self.entry),
+                ),
+                    encoder, offset, _depth
+                )
+    }
+    }
+    
+unsafe impl< T0: midl::encoding::Encode<Entry>,>
+        midl::encoding::Encode<StorePutRequest> for ( T0,)
+    {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<StorePutRequest>(offset);
+            // Zero out padding regions. There's no need to apply masks
+            // because the unmasked parts will be overwritten by fields.
+        // Write the fields.
+        self.0.encode(encoder, offset + 0, depth)?;
+        Ok(())
+        }
+    }
+
+    impl midl::encoding::Decode<Self> for StorePutRequest {
+        #[inline(always)]
+        fn new_empty() -> Self {
+            Self  {
+            entry: midl::new_empty!(Entry),
+        }
+    }
+    
+        #[inline]
+        unsafe fn decode(&mut self, decoder: &mut midl::encoding::Decoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+            midl::decode!(Entry, &mut self.entry, decoder, offset + 0, _depth)?;
+            Ok(())
+    }
+    }
+    
+impl<'de> midl::encoding::ViewMarker<'de> for StorePutRequest {
+        type View = StorePutRequestView<'de>;
+    
+        #[inline]
+        unsafe fn decode_view(decoder: &mut midl::encoding::Decoder<'de>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<Self::View> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+        Ok(StorePutRequestView {
+            entry: <Entry as midl::encoding::ViewMarker<'de>>::decode_view(decoder, offset + 0, _depth)?,
+        })
+        }
+    }
+    
+    unsafe impl midl::encoding::TypeMarker for StoreOnChangedRequest {
+        type Owned = Self;
+    
+        #[inline(always)]
+        fn inline_align(_context: midl::encoding::Context) -> usize {
+            8
+        }
+    
+        #[inline(always)]
+        fn inline_size(_context: midl::encoding::Context) -> usize {
+            8
+        }
+    
+}
+    
+impl midl::encoding::ValueTypeMarker for StoreOnChangedRequest {
+        type Borrowed<'a> = &'a Self;
+        fn borrow<'a>(value: &'a <Self as midl::encoding::TypeMarker>::Owned) -> Self::Borrowed<'a> {
+            value
+        }
+    }
+
+    unsafe impl midl::encoding::Encode<StoreOnChangedRequest> for &StoreOnChangedRequest {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<StoreOnChangedRequest>(offset);
+            // Delegate to tuple encoding.
+                midl::encoding::Encode::<StoreOnChangedRequest>::encode(
+                    (
+                    <u64 as midl::encoding::ValueTypeMarker>::borrow(&self.id),
+                ),
+                    encoder, offset, _depth
+                )
+    }
+    }
+    
+unsafe impl< T0: midl::encoding::Encode<u64>,>
+        midl::encoding::Encode<StoreOnChangedRequest> for ( T0,)
+    {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<StoreOnChangedRequest>(offset);
+            // Zero out padding regions. There's no need to apply masks
+            // because the unmasked parts will be overwritten by fields.
+        // Write the fields.
+        self.0.encode(encoder, offset + 0, depth)?;
+        Ok(())
+        }
+    }
+
+    impl midl::encoding::Decode<Self> for StoreOnChangedRequest {
+        #[inline(always)]
+        fn new_empty() -> Self {
+            Self  {
+            id: midl::new_empty!(u64),
+        }
+    }
+    
+        #[inline]
+        unsafe fn decode(&mut self, decoder: &mut midl::encoding::Decoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+            midl::decode!(u64, &mut self.id, decoder, offset + 0, _depth)?;
+            Ok(())
+    }
+    }
+    
+impl<'de> midl::encoding::ViewMarker<'de> for StoreOnChangedRequest {
+        type View = StoreOnChangedRequestView<'de>;
+    
+        #[inline]
+        unsafe fn decode_view(decoder: &mut midl::encoding::Decoder<'de>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<Self::View> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+        Ok(StoreOnChangedRequestView {
+            id: <u64 as midl::encoding::ViewMarker<'de>>::decode_view(decoder, offset + 0, _depth)?,
+        })
+        }
+    }
+    
+    unsafe impl midl::encoding::TypeMarker for CacheLookupRequest {
+        type Owned = Self;
+    
+        #[inline(always)]
+        fn inline_align(_context: midl::encoding::Context) -> usize {
+            8
+        }
+    
+        #[inline(always)]
+        fn inline_size(_context: midl::encoding::Context) -> usize {
+            8
+        }
+    
+}
+    
+impl midl::encoding::ValueTypeMarker for CacheLookupRequest {
+        type Borrowed<'a> = &'a Self;
+        fn borrow<'a>(value: &'a <Self as midl::encoding::TypeMarker>::Owned) -> Self::Borrowed<'a> {
+            value
+        }
+    }
+
+    unsafe impl midl::encoding::Encode<CacheLookupRequest> for &CacheLookupRequest {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<CacheLookupRequest>(offset);
+            // Delegate to tuple encoding.
+                midl::encoding::Encode::<CacheLookupRequest>::encode(
+                    (
+                    <u64 as midl::encoding::ValueTypeMarker>::borrow(&self.id),
+                ),
+                    encoder, offset, _depth
+                )
+    }
+    }
+    
+unsafe impl< T0: midl::encoding::Encode<u64>,>
+        midl::encoding::Encode<CacheLookupRequest> for ( T0,)
+    {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<CacheLookupRequest>(offset);
+            // Zero out padding regions. There's no need to apply masks
+            // because the unmasked parts will be overwritten by fields.
+        // Write the fields.
+        self.0.encode(encoder, offset + 0, depth)?;
+        Ok(())
+        }
+    }
+
+    impl midl::encoding::Decode<Self> for CacheLookupRequest {
+        #[inline(always)]
+        fn new_empty() -> Self {
+            Self  {
+            id: midl::new_empty!(u64),
+        }
+    }
+    
+        #[inline]
+        unsafe fn decode(&mut self, decoder: &mut midl::encoding::Decoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+            midl::decode!(u64, &mut self.id, decoder, offset + 0, _depth)?;
+            Ok(())
+    }
+    }
+    
+impl<'de> midl::encoding::ViewMarker<'de> for CacheLookupRequest {
+        type View = CacheLookupRequestView<'de>;
+    
+        #[inline]
+        unsafe fn decode_view(decoder: &mut midl::encoding::Decoder<'de>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<Self::View> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+        Ok(CacheLookupRequestView {
+            id: <u64 as midl::encoding::ViewMarker<'de>>::decode_view(decoder, offset + 0, _depth)?,
+        })
+        }
+    }
+    
+    unsafe impl midl::encoding::TypeMarker for CacheLookupResponse {
+        type Owned = Self;
+    
+        #[inline(always)]
+        fn inline_align(_context: midl::encoding::Context) -> usize {
+            8
+        }
+    
+        #[inline(always)]
+        fn inline_size(_context: midl::encoding::Context) -> usize {
+            24
+        }
+    
+}
+    
+impl midl::encoding::ValueTypeMarker for CacheLookupResponse {
+        type Borrowed<'a> = &'a Self;
+        fn borrow<'a>(value: &'a <Self as midl::encoding::TypeMarker>::Owned) -> Self::Borrowed<'a> {
+            value
+        }
+    }
+
+    unsafe impl midl::encoding::Encode<CacheLookupResponse> for &CacheLookupResponse {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<CacheLookupResponse>(offset);
+            // Delegate to tuple encoding.
+                midl::encoding::Encode::<CacheLookupResponse>::encode(
+                    (
+                    <Entry as midl::encoding::ValueTypeMarker>::borrow(&self.entry),
+                ),
+                    encoder, offset, _depth
+                )
+    }
+    }
+    
+unsafe impl< T0: midl::encoding::Encode<Entry>,>
+        midl::encoding::Encode<CacheLookupResponse> for ( T0,)
+    {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<CacheLookupResponse>(offset);
+            // Zero out padding regions. There's no need to apply masks
+            // because the unmasked parts will be overwritten by fields.
+        // Write the fields.
+        self.0.encode(encoder, offset + 0, depth)?;
+        Ok(())
+        }
+    }
+
+    impl midl::encoding::Decode<Self> for CacheLookupResponse {
+        #[inline(always)]
+        fn new_empty() -> Self {
+            Self  {
+            entry: midl::new_empty!(Entry),
+        }
+    }
+    
+        #[inline]
+        unsafe fn decode(&mut self, decoder: &mut midl::encoding::Decoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+            midl::decode!(Entry, &mut self.entry, decoder, offset + 0, _depth)?;
+            Ok(())
+    }
+    }
+    
+impl<'de> midl::encoding::ViewMarker<'de> for CacheLookupResponse {
+        type View = CacheLookupResponseView<'de>;
+    
+        #[inline]
+        unsafe fn decode_view(decoder: &mut midl::encoding::Decoder<'de>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<Self::View> {
+            decoder.debug_check_bounds::<Self>(offset);
+            // Verify that padding bytes are zero.
+        Ok(CacheLookupResponseView {
+            entry: <Entry as midl::encoding::ViewMarker<'de>>::decode_view(decoder, offset + 0, _depth)?,
+        })
+        }
+    }
+    
+    unsafe impl midl::encoding::TypeMarker for StoreError {
+        type Owned = Self;
+    
+        #[inline(always)]
+        fn inline_align(_context: midl::encoding::Context) -> usize {
+            std::mem::align_of::<u32>()
+        }
+    
+        #[inline(always)]
+        fn inline_size(_context: midl::encoding::Context) -> usize {
+            std::mem::size_of::<u32>()
+        }
+    
+        #[inline(always)]
+        fn encode_is_copy() -> bool {
+            true
+        }
+    
+        #[inline(always)]
+        fn decode_is_copy() -> bool {
+            false
+        }
+    }
+    
+    impl midl::encoding::ValueTypeMarker for StoreError {
+        type Borrowed<'a> = Self;
+        #[inline(always)]
+        fn borrow<'a>(value: &'a <Self as midl::encoding::TypeMarker>::Owned) -> Self::Borrowed<'a> {
+            *value
+        }
+    }
+    
+    unsafe impl midl::encoding::Encode<Self> for StoreError {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<Self>(offset);
+            encoder.write_num(self.into_primitive(), offset);
+            Ok(())
+        }
+    }
+    
+    impl midl::encoding::Decode<Self> for StoreError {
+        #[inline(always)]
+        fn new_empty() -> Self {
+        Self::NotFound
+    }
+    
+        #[inline]
+        unsafe fn decode(&mut self, decoder: &mut midl::encoding::Decoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            decoder.debug_check_bounds::<Self>(offset);
+            let prim = decoder.read_num::<u32>(offset);
+        *self = Self::from_primitive(prim).ok_or(midl::Error::InvalidEnumValue)?;
+        Ok(())
+        }
+    }
+    
+    midl::impl_view_by_copy!(StoreError);
+    
+    unsafe impl midl::encoding::TypeMarker for Kind {
+        type Owned = Self;
+    
+        #[inline(always)]
+        fn inline_align(_context: midl::encoding::Context) -> usize {
+            std::mem::align_of::<u8>()
+        }
+    
+        #[inline(always)]
+        fn inline_size(_context: midl::encoding::Context) -> usize {
+            std::mem::size_of::<u8>()
+        }
+    
+        #[inline(always)]
+        fn encode_is_copy() -> bool {
+            false
+        }
+    
+        #[inline(always)]
+        fn decode_is_copy() -> bool {
+            false
+        }
+    }
+    
+    impl midl::encoding::ValueTypeMarker for Kind {
+        type Borrowed<'a> = Self;
+        #[inline(always)]
+        fn borrow<'a>(value: &'a <Self as midl::encoding::TypeMarker>::Owned) -> Self::Borrowed<'a> {
+            *value
+        }
+    }
+    
+    unsafe impl midl::encoding::Encode<Self> for Kind {
+        #[inline]
+        unsafe fn encode(self, encoder: &mut midl::encoding::Encoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            encoder.debug_check_bounds::<Self>(offset);
+            encoder.write_num(self.into_primitive(), offset);
+            Ok(())
+        }
+    }
+    
+    impl midl::encoding::Decode<Self> for Kind {
+        #[inline(always)]
+        fn new_empty() -> Self {
+        Self::unknown()
+    }
+    
+        #[inline]
+        unsafe fn decode(&mut self, decoder: &mut midl::encoding::Decoder<'_>, offset: usize, _depth: midl::encoding::Depth) -> midl::Result<()> {
+            decoder.debug_check_bounds::<Self>(offset);
+            let prim = decoder.read_num::<u8>(offset);
+        *self = Self::from_primitive_allow_unknown(prim);
+        Ok(())
+        }
+    }
+    
+    midl::impl_view_by_copy!(Kind);
+}
\ No newline at end of file
@@ -0,0 +1,196 @@
+// Copyright 2024 MeshX Authors. All rights reserved.
+
+use fiber::AsHandleRef;
+use futures::TryStreamExt;
+use midl::encoding::{
+    decode_transaction_header, decode_transaction_view, DynamicFlags, EmptyPayload, Encode, Encoder, ResultType,
+    TransactionHeader, TransactionMessage, TransactionMessageType, TypeMarker,
+};
+use midl::endpoints::{ControlHandle, ProtocolMarker};
+use midlgen_rust_goldens::*;
+
+#[test]
+fn enums() {
+    assert_eq!(StoreError::from_primitive(2), Some(StoreError::Full));
+    assert_eq!(StoreError::NotFound.into_primitive(), 1);
+    assert_eq!(StoreError::from_primitive(3), None);
+
+    assert_eq!(Kind::from_primitive_allow_unknown(2), Kind::Large);
+    assert!(Kind::from_primitive_allow_unknown(7).is_unknown());
+    assert_eq!(Kind::from_primitive_allow_unknown(7).into_primitive(), 7);
+    assert_eq!(Kind::unknown().into_primitive(), 255);
+}
+
+#[test]
+fn structs_round_trip() {
+    let entry = Entry {
+        id: 7,
+        name: "seven".to_string(),
+    };
+    let bytes = midl::encoding::persist(&entry).unwrap();
+    assert_eq!(midl::encoding::unpersist::<Entry>(&bytes).unwrap(), entry);
+}
+
+/// Encodes `body` as the body of a transaction message with `tx_id` and `ordinal`.
+fn encode_message<T: TypeMarker>(tx_id: u32, ordinal: u64, body: impl Encode<T>) -> Vec<u8> {
+    let header = TransactionHeader::new(tx_id, ordinal, DynamicFlags::empty());
+    let mut bytes = Vec::new();
+    Encoder::encode::<TransactionMessageType<T>>(&mut bytes, &mut Vec::new(), TransactionMessage { header, body })
+        .unwrap();
+    bytes
+}
+
+#[test]
+fn message_views() {
+    let entry = Entry {
+        id: 7,
+        name: "seven".to_string(),
+    };
+    let bytes = encode_message::<StorePutRequest>(1, 0x1234, &StorePutRequest { entry });
+
+    let (header, view): (_, StorePutRequestView<'_>) = decode_transaction_view::<StorePutRequest>(&bytes).unwrap();
+    assert_eq!(header.ordinal(), 0x1234);
+    let EntryView { id, name } = view.entry;
+    assert_eq!(id, 7);
+    assert_eq!(name, "seven");
+    // The name points into the message instead of being copied out of it.
+    assert!(bytes.as_ptr_range().contains(&name.as_ptr()));
+}
+
+#[test]
+fn views_validate_like_owned_decoding() {
+    let entry = Entry {
+        id: 7,
+        name: "seven".to_string(),
+    };
+    let mut bytes = encode_message::<StoreGetResponse>(1, 1, &StoreGetResponse { entry });
+
+    // Make the name invalid UTF-8.
+    let name = bytes.len() - 8;
+    bytes[name] = 0xff;
+
+    assert!(matches!(
+        decode_transaction_view::<StoreGetResponse>(&bytes),
+        Err(midl::Error::Utf8Error)
+    ));
+    assert!(matches!(
+        midl::encoding::decode_transaction_header(&bytes).and_then(|(header, body)| {
+            midl::encoding::Decoder::decode_into::<StoreGetResponse>(
+                &header,
+                body,
+                &mut [],
+                &mut StoreGetResponse {
+                    entry: Entry {
+                        id: 0,
+                        name: String::new(),
+                    },
+                },
+            )
+        }),
+        Err(midl::Error::Utf8Error)
+    ));
+}
+
+/// Reads the next message on `channel`, blocking until one arrives, and returns its header.
+fn read_request(channel: &fiber::Channel) -> TransactionHeader {
+    channel
+        .wait_handle(fiber::Signals::CHANNEL_READABLE, fiber::Time::INFINITE)
+        .unwrap();
+    let mut buf = fiber::MessageBufEtc::new();
+    channel.read_etc(&mut buf).unwrap();
+    decode_transaction_header(buf.bytes()).unwrap().0
+}
+
+#[test]
+fn synchronous_proxy_calls() {
+    fiber_host::install();
+    let (client, server) = fiber::Channel::create();
+    let server = std::thread::spawn(move || {
+        let header = read_request(&server);
+        assert_eq!(header.ordinal(), 3192224406944254481);
+        let entry = Entry {
+            id: 7,
+            name: "seven".to_string(),
+        };
+        let reply = encode_message::<ResultType<StoreGetResponse, StoreError>>(
+            header.tx_id(),
+            header.ordinal(),
+            Ok::<_, StoreError>((&entry,)),
+        );
+        server.write(&reply, &mut []).unwrap();
+
+        let header = read_request(&server);
+        let reply = encode_message::<EmptyPayload>(header.tx_id(), header.ordinal(), ());
+        server.write(&reply, &mut []).unwrap();
+    });
+
+    let proxy = StoreSynchronousProxy::new(client);
+    let entry = proxy.get(7, fiber::Time::INFINITE).unwrap().unwrap();
+    assert_eq!(entry.name, "seven");
+    proxy.ping(fiber::Time::INFINITE).unwrap();
+    server.join().unwrap();
+}
+
+#[test]
+fn synchronous_proxy_waits_for_events() {
+    fiber_host::install();
+    let (client, server) = fiber::Channel::create();
+    let proxy = StoreSynchronousProxy::new(client);
+    let event = encode_message::<StoreOnChangedRequest>(0, 4290836836681525828, (7,));
+    server.write(&event, &mut []).unwrap();
+
+    let event = proxy.wait_for_event(fiber::Time::INFINITE).unwrap();
+    assert_eq!(event.into_on_changed(), Some(7));
+}
+
+#[test]
+fn synchronous_proxy_deadlines_expire() {
+    fiber_host::install();
+    let (client, _server) = fiber::Channel::create();
+    let proxy = StoreSynchronousProxy::new(client);
+    let deadline = fiber::Time::get_monotonic() + fiber::Duration::from_millis(10);
+    assert!(proxy.ping(deadline).unwrap_err().is_timed_out());
+    assert!(proxy
+        .wait_for_event(fiber::Time::INFINITE_PAST)
+        .unwrap_err()
+        .is_timed_out());
+}
+
+#[test]
+fn markers() {
+    assert_eq!(StoreMarker::DEBUG_NAME, "test.golden.Store");
+}
+
+// Never run: checks that a server can be written against the generated
+// request stream, responders and control handle.
+#[allow(dead_code)]
+async fn serve_store(mut stream: StoreRequestStream) -> Result<(), midl::Error> {
+    while let Some(request) = stream.try_next().await? {
+        match request {
+            StoreRequest::Get { id, responder } => {
+                let entry = Entry {
+                    id,
+                    name: String::new(),
+                };
+                responder.send(Ok(&entry))?;
+            }
+            StoreRequest::Put { entry, control_handle } => control_handle.send_on_changed(entry.id)?,
+            StoreRequest::Ping { responder } => responder.send()?,
+        }
+    }
+    Ok(())
+}
+
+#[allow(dead_code)]
+async fn serve_cache(mut stream: CacheRequestStream) -> Result<(), midl::Error> {
+    while let Some(request) = stream.try_next().await? {
+        match request {
+            CacheRequest::Lookup { id, responder } => responder.send(&Entry {
+                id,
+                name: String::new(),
+            })?,
+            CacheRequest::_UnknownMethod { control_handle, .. } => control_handle.shutdown(),
+        }
+    }
+    Ok(())
+}
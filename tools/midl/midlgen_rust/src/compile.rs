@@ -1,5 +1,6 @@
 use convert_case::Casing;
-use std::collections::{HashMap, HashSet};
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use crate::types::{self, Derives, Root, Struct, StructMember, Type};
 use midlgen::ir::{
@@ -10,6 +11,8 @@ struct Compiler {
     decls: midlgen::ir::DeclInfoMap,
     library: midlgen::ir::LibraryIdentifier,
     structs: HashMap<midlgen::ir::EncodedCompoundIdentifier, midlgen::ir::Struct>,
+    /// Crates of the other libraries referred to by the generated code.
+    extern_crates: RefCell<BTreeSet<String>>,
 }
 
 lazy_static::lazy_static! {
@@ -34,33 +37,10 @@ lazy_static::lazy_static! {
     static ref HANDLE_SUBTYPES: HashMap<midlgen::ir::HandleSubtype, String> = {
         let mut m = HashMap::new();
         m.insert(midlgen::ir::HandleSubtype::None, "midl::Handle".to_string());
-         m.insert(midlgen::ir::HandleSubtype::Channel, "midl::Channel".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Event, "midl::Event".to_string());
-        m.insert(midlgen::ir::HandleSubtype::EventPair, "midl::EventPair".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Exception, "midl::Exception".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Guest, "midl::Guest".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Fifo, "midl::Fifo".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Bti, "midl::Bti".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Clock, "midl::Clock".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Debuglog, "midl::Debuglog".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Interrupt, "midl::Interrupt".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Iommu, "midl::Iommu".to_string());
+        m.insert(midlgen::ir::HandleSubtype::Channel, "midl::Channel".to_string());
         m.insert(midlgen::ir::HandleSubtype::Job, "midl::Job".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Msi, "midl::Msi".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Pager, "midl::Pager".to_string());
-        m.insert(midlgen::ir::HandleSubtype::PciDevice, "midl::PciDevice".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Pmt, "midl::Pmt".to_string());
         m.insert(midlgen::ir::HandleSubtype::Port, "midl::Port".to_string());
         m.insert(midlgen::ir::HandleSubtype::Process, "midl::Process".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Profile, "midl::Profile".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Resource, "midl::Resource".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Socket, "midl::Socket".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Stream, "midl::Stream".to_string());
-        m.insert(midlgen::ir::HandleSubtype::SuspendToken, "midl::SuspendToken".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Thread, "midl::Thread".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Timer, "midl::Timer".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Vcpu, "midl::Vcpu".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Vmar, "midl::Vmar".to_string());
         m.insert(midlgen::ir::HandleSubtype::Vmo, "midl::Vmo".to_string());
 
         m
@@ -72,32 +52,9 @@ lazy_static::lazy_static! {
         let mut m = HashMap::new();
         m.insert(midlgen::ir::HandleSubtype::None, "midl::ObjectType::NONE".to_string());
         m.insert(midlgen::ir::HandleSubtype::Channel, "midl::ObjectType::CHANNEL".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Event, "midl::ObjectType::EVENT".to_string());
-        m.insert(midlgen::ir::HandleSubtype::EventPair, "midl::ObjectType::EVENTPAIR".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Exception, "midl::ObjectType::EXCEPTION".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Guest, "midl::ObjectType::GUEST".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Fifo, "midl::ObjectType::FIFO".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Bti, "midl::ObjectType::BTI".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Clock, "midl::ObjectType::CLOCK".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Debuglog, "midl::ObjectType::DEBUGLOG".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Interrupt, "midl::ObjectType::INTERRUPT".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Iommu, "midl::ObjectType::IOMMU".to_string());
         m.insert(midlgen::ir::HandleSubtype::Job, "midl::ObjectType::JOB".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Msi, "midl::ObjectType::MSI".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Pager, "midl::ObjectType::PAGER".to_string());
-        m.insert(midlgen::ir::HandleSubtype::PciDevice, "midl::ObjectType::PCIDEVICE".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Pmt, "midl::ObjectType::PMT".to_string());
         m.insert(midlgen::ir::HandleSubtype::Port, "midl::ObjectType::PORT".to_string());
         m.insert(midlgen::ir::HandleSubtype::Process, "midl::ObjectType::PROCESS".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Profile, "midl::ObjectType::PROFILE".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Resource, "midl::ObjectType::RESOURCE".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Socket, "midl::ObjectType::SOCKET".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Stream, "midl::ObjectType::STREAM".to_string());
-        m.insert(midlgen::ir::HandleSubtype::SuspendToken, "midl::ObjectType::SUSPENDTOKEN".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Thread, "midl::ObjectType::THREAD".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Timer, "midl::ObjectType::TIMER".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Vcpu, "midl::ObjectType::VCPU".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Vmar, "midl::ObjectType::VMAR".to_string());
         m.insert(midlgen::ir::HandleSubtype::Vmo, "midl::ObjectType::VMO".to_string());
 
         m
@@ -212,25 +169,23 @@ impl Compiler {
 
         match typ {
             midlgen::ir::Type::ArrayType { element_type, .. } | midlgen::ir::Type::StringArray { element_type, .. } => {
-                return self.compute_use_midl_struct_copy(*element_type.clone())
+                self.compute_use_midl_struct_copy(*element_type.clone())
             }
             midlgen::ir::Type::VectorType { .. }
             | midlgen::ir::Type::StringType { .. }
             | midlgen::ir::Type::HandleType { .. }
-            | midlgen::ir::Type::ServerEnd { .. } => return false,
-            midlgen::ir::Type::PrimitiveType { primitive_subtype, .. } => {
-                return match primitive_subtype {
-                    midlgen::ir::PrimitiveSubtype::Bool
+            | midlgen::ir::Type::ServerEnd { .. } => false,
+            midlgen::ir::Type::PrimitiveType { primitive_subtype, .. } => matches!(
+                primitive_subtype,
+                midlgen::ir::PrimitiveSubtype::Bool
                     | midlgen::ir::PrimitiveSubtype::Float32
-                    | midlgen::ir::PrimitiveSubtype::Float64 => true,
-                    _ => false,
-                }
-            }
+                    | midlgen::ir::PrimitiveSubtype::Float64
+            ),
             midlgen::ir::Type::IdentifierType { .. } => {
                 // TODO
-                return false;
+                false
             }
-            _ => panic!("unknown type kind: {:?}", typ),
+            midlgen::ir::Type::ClientEnd { .. } | midlgen::ir::Type::InternalType { .. } => false,
         }
 
         /*switch typ.Kind {
@@ -290,9 +245,9 @@ impl Compiler {
         name = self.change_if_reserved(name);
 
         if self.in_external_library(val) {
-            //	let r#crate = compile_library_name(ci.library);
-            //	extern_crates[crate] = struct{}{}
-            //	return fmt.Sprintf("%s::%s", crate, name)
+            let krate = compile_library_name(&val.library_name());
+            self.extern_crates.borrow_mut().insert(krate.clone());
+            return format!("{}::{}", krate, name);
         }
 
         name
@@ -378,17 +333,19 @@ impl Compiler {
         }
     }
 
+    /// Subtypes without a Fiber object, such as events or streams, are carried as untyped
+    /// handles.
     fn compile_handle_subtype(&self, val: &HandleSubtype) -> String {
         HANDLE_SUBTYPES
             .get(val)
-            .expect(format!("unknown handle type: {:?}", val).as_str())
+            .unwrap_or(&HANDLE_SUBTYPES[&HandleSubtype::None])
             .clone()
     }
 
     fn compile_object_type_const(&self, val: &HandleSubtype) -> String {
         OBJECT_TYPES
             .get(val)
-            .expect(format!("unknown handle type: {:?}", val).as_str())
+            .unwrap_or(&OBJECT_TYPES[&HandleSubtype::None])
             .clone()
     }
 
@@ -522,7 +479,7 @@ impl Compiler {
                 if *nullable {
                     t.midl = format!("midl::encoding::Optional<{}>", t.midl);
                     t.owned = format!("Option<{}>", t.owned);
-                    t.param = format!("Option<%{}>", t.param);
+                    t.param = format!("Option<{}>", t.param);
                 }
             }
             midlgen::ir::Type::ClientEnd {
@@ -580,10 +537,19 @@ impl Compiler {
                         t.owned = name.clone();
 
                         if resourceness.is_resource_type() {
-                            t.param = name;
+                            t.param = name.clone();
                         } else {
                             t.param = format!("&{name}");
                         }
+
+                        if *nullable {
+                            t.midl = match decl_info.r#type {
+                                midlgen::ir::DeclType::UnionDecl => format!("midl::encoding::OptionalUnion<{name}>"),
+                                _ => format!("midl::encoding::Boxed<{name}>"),
+                            };
+                            t.owned = format!("Option<Box<{name}>>");
+                            t.param = format!("Option<{}>", t.param);
+                        }
                     }
                     /*midlgen::ir::DeclType::ProtocolDecl => {
                         let s = format!("midl::endpoints::ClientEnd<{}Marker>", name);
@@ -622,6 +588,38 @@ impl Compiler {
         return true;
     }
 
+    /// Returns true if `midl::encoding::ViewMarker` is implemented for the
+    /// marker type of `typ`, i.e. it can be decoded without copying.
+    fn compute_has_view(&self, typ: &midlgen::ir::Type) -> bool {
+        match typ {
+            midlgen::ir::Type::PrimitiveType { .. } | midlgen::ir::Type::StringType { .. } => true,
+            midlgen::ir::Type::ArrayType { element_type, .. }
+            | midlgen::ir::Type::StringArray { element_type, .. }
+            | midlgen::ir::Type::VectorType { element_type, .. } => self.compute_has_view(element_type),
+            midlgen::ir::Type::IdentifierType { identifier, nullable, .. } => {
+                if self.in_external_library(identifier) {
+                    return false;
+                }
+                match self.lookup_decl_info(identifier).r#type {
+                    midlgen::ir::DeclType::EnumDecl => true,
+                    // Boxed structs are skipped so that recursive structs
+                    // terminate.
+                    midlgen::ir::DeclType::StructDecl if !nullable => {
+                        self.structs.get(identifier).is_some_and(|st| self.compute_has_view_for_struct(st))
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn compute_has_view_for_struct(&self, st: &midlgen::ir::Struct) -> bool {
+        !st.members.is_empty()
+            && !st.resourceness.is_resource_type()
+            && st.members.iter().all(|member| self.compute_has_view(&member.r#type))
+    }
+
     fn compile_struct_member(&self, val: midlgen::ir::StructMember) -> StructMember {
         let ir = val.clone();
 
@@ -750,26 +748,31 @@ impl Compiler {
     }
 
     fn compile_union(&self, val: midlgen::ir::Union) -> types::Union {
+        let ir = val.clone();
         let mut members = vec![];
 
         for v in val.members {
             let ir = v.clone();
 
-            //if v.reserved {
-            //    continue;
-            //}
+            // Reserved members carry neither a name nor a type.
+            let Some(r#type) = v.r#type else {
+                continue;
+            };
 
             members.push(types::UnionMember {
                 ir,
-                r#type: self.compile_type(&v.r#type.unwrap()),
+                r#type: self.compile_type(&r#type),
                 name: self.compile_camel_identifier(v.name.expect("not reserved")),
                 ordinal: v.ordinal,
             })
         }
 
         types::Union {
+            ir,
             name: self.compile_decl_identifier(&val.name),
             members,
+            is_value_type: val.resourceness.is_value_type(),
+            is_flexible: !val.strict,
         }
     }
 
@@ -797,6 +800,7 @@ impl Compiler {
             name: self.compile_decl_identifier(&val.name),
             eci: val.name,
             members,
+            is_value_type: val.resource.is_value_type(),
         }
     }
 
@@ -858,13 +862,49 @@ impl Compiler {
         }
 
         let min_member = self.find_min_enum_value(&val.r#type, &members).clone();
+        let underlying_type = self.compile_primitive_subtype(&val.r#type);
+        let unknown_value = match val.raw_unknown_value {
+            Some(value) => value.to_string(),
+            None => format!("{}::MAX", underlying_type),
+        };
 
         types::Enum {
             ir,
             name: self.compile_decl_identifier(&val.name),
-            underlying_type: self.compile_primitive_subtype(&val.r#type),
+            underlying_type,
             members,
             min_member,
+            unknown_value,
+        }
+    }
+
+    fn compile_bits(&self, val: midlgen::ir::Bits) -> types::Bits {
+        let ir = val.clone();
+        let midlgen::ir::Type::PrimitiveType { primitive_subtype } = &val.r#type else {
+            panic!("bits must have a primitive subtype: {:?}", val.name);
+        };
+
+        let members = val
+            .members
+            .into_iter()
+            .map(|v| {
+                let (midlgen::ir::Constant::Identifier { value, .. }
+                | midlgen::ir::Constant::LiteralConstant { value, .. }
+                | midlgen::ir::Constant::BinaryOperator { value, .. }) = v.value.clone();
+
+                types::BitsMember {
+                    name: self.compile_screaming_snake_identifier(v.name.clone()),
+                    ir: v,
+                    value,
+                }
+            })
+            .collect();
+
+        types::Bits {
+            ir,
+            name: self.compile_decl_identifier(&val.name),
+            underlying_type: self.compile_primitive_subtype(primitive_subtype),
+            members,
         }
     }
 
@@ -881,6 +921,7 @@ impl Compiler {
         }
 
         let use_midl_struct_copy = self.compute_use_midl_struct_copy_for_struct(val.clone());
+        let has_view = self.compute_has_view_for_struct(&val);
 
         types::Struct {
             ir,
@@ -897,10 +938,323 @@ impl Compiler {
             alignment_v2: val.type_shape_v2.alignment,
             has_padding,
             use_midl_struct_copy,
+            has_view,
+            is_value_type: val.resourceness.is_value_type(),
         }
     }
 }
 
+/// The members of a result union, as generated by midlc for two-way methods
+/// that use error syntax or are flexible.
+struct ResultUnion {
+    success: midlgen::ir::Type,
+    error: Option<midlgen::ir::Type>,
+    framework_err: bool,
+}
+
+/// How the parameters of a payload map onto its wire type.
+enum PayloadShape {
+    /// No payload at all, e.g. `Foo()`.
+    Empty,
+    /// A struct flattened into one parameter per member.
+    Struct(Vec<types::Parameter>),
+    /// A table or union passed as a single `payload` parameter.
+    Value(types::Parameter),
+}
+
+/// Messages larger than this must be sent with overflow handling.
+const MAX_MESSAGE_BYTES: u32 = 65536 - 16;
+
+impl Compiler {
+    /// The result union of a method's response, for methods with error syntax and
+    /// flexible two-way methods.
+    fn lookup_result_union(&self, method: &midlgen::ir::ProtocolMethod) -> Option<ResultUnion> {
+        Some(ResultUnion {
+            success: method.success_type.clone()?,
+            error: method.error_type.clone(),
+            framework_err: method.has_framework_error(),
+        })
+    }
+
+    fn compile_parameter(&self, name: String, typ: &midlgen::ir::Type) -> types::Parameter {
+        let compiled = self.compile_type(typ);
+
+        types::Parameter {
+            og_type: typ.clone(),
+            name,
+            r#type: compiled.param,
+            owned_type: compiled.owned,
+        }
+    }
+
+    fn compile_payload_shape(&self, payload: Option<&midlgen::ir::Type>) -> PayloadShape {
+        let Some(payload) = payload else {
+            return PayloadShape::Empty;
+        };
+
+        if let midlgen::ir::Type::IdentifierType { identifier, .. } = payload {
+            if let Some(st) = self.structs.get(identifier) {
+                return PayloadShape::Struct(
+                    st.members
+                        .iter()
+                        .map(|m| self.compile_parameter(self.compile_snake_identifier(m.name.clone()), &m.r#type))
+                        .collect(),
+                );
+            }
+        }
+
+        PayloadShape::Value(self.compile_parameter("payload".to_string(), payload))
+    }
+
+    /// Expression passing a parameter to an encoder: resources are moved out,
+    /// values are already borrowed.
+    fn encode_parameter(&self, param: &types::Parameter) -> String {
+        if let midlgen::ir::Type::IdentifierType {
+            identifier,
+            nullable: true,
+            ..
+        } = &param.og_type
+        {
+            if self.lookup_resourceness(&param.og_type).is_resource_type() {
+                return format!(
+                    "{}.as_mut().map(<{} as midl::encoding::ResourceTypeMarker>::take_or_borrow)",
+                    param.name,
+                    self.compile_decl_identifier(identifier)
+                );
+            }
+        }
+
+        if self.lookup_resourceness(&param.og_type).is_resource_type() {
+            format!(
+                "<{} as midl::encoding::ResourceTypeMarker>::take_or_borrow(&mut {})",
+                self.compile_type(&param.og_type).midl,
+                param.name
+            )
+        } else {
+            param.name.clone()
+        }
+    }
+
+    fn compile_plain_payload(&self, payload: Option<&midlgen::ir::Type>) -> types::Payload {
+        match self.compile_payload_shape(payload) {
+            PayloadShape::Empty => types::Payload {
+                midl_type: "midl::encoding::EmptyPayload".to_string(),
+                encode_expr: "()".to_string(),
+                tuple_type: "()".to_string(),
+                // The empty payload decodes to `()` already.
+                convert_to_tuple: "out".to_string(),
+                ..Default::default()
+            },
+            PayloadShape::Struct(parameters) => types::Payload {
+                midl_type: self.compile_type(payload.unwrap()).midl,
+                encode_expr: format!(
+                    "({})",
+                    parameters
+                        .iter()
+                        .map(|p| format!("{},", self.encode_parameter(p)))
+                        .collect::<String>()
+                ),
+                tuple_type: tuple_of(parameters.iter().map(|p| p.owned_type.clone()).collect()),
+                convert_to_tuple: struct_to_tuple(&parameters, "out"),
+                convert_to_fields: parameters.iter().map(|p| format!("{0}: out.{0},", p.name)).collect(),
+                parameters,
+                ..Default::default()
+            },
+            PayloadShape::Value(parameter) => types::Payload {
+                midl_type: self.compile_type(payload.unwrap()).midl,
+                encode_expr: self.encode_parameter(&parameter),
+                tuple_type: parameter.owned_type.clone(),
+                convert_to_tuple: "out".to_string(),
+                convert_to_fields: format!("{}: out,", parameter.name),
+                parameters: vec![parameter],
+                ..Default::default()
+            },
+        }
+    }
+
+    fn compile_result_payload(&self, result: ResultUnion, alias: String) -> types::Payload {
+        let success = self.compile_plain_payload(Some(&result.success));
+        let success_midl = if success.parameters.is_empty() {
+            "midl::encoding::EmptyStruct".to_string()
+        } else {
+            success.midl_type.clone()
+        };
+
+        let Some(error) = result.error else {
+            // Flexible method without error syntax: the caller only ever sees
+            // the success variant, framework errors become a `midl::Error`.
+            return types::Payload {
+                midl_type: format!("midl::encoding::FlexibleType<{}>", success_midl),
+                encode_expr: format!("midl::encoding::Flexible::new({})", success.encode_expr),
+                ..success
+            };
+        };
+
+        let error = self.compile_type(&error);
+        let success_tuple = if success.parameters.is_empty() {
+            "()".to_string()
+        } else {
+            success.tuple_type.clone()
+        };
+
+        // Map the success parameters back into the shape the encoder expects.
+        let (pattern, body) = match success.parameters.len() {
+            0 => (String::new(), String::new()),
+            1 if success.convert_to_tuple == "out" => (String::new(), String::new()),
+            _ => {
+                let names: Vec<String> = success.parameters.iter().map(|p| p.name.clone()).collect();
+                (tuple_of(names.clone()), format!("({})", names.iter().map(|n| format!("{n},")).collect::<String>()))
+            }
+        };
+        let mapped = if pattern.is_empty() {
+            match success.parameters.first() {
+                // Resources are encoded through a mutable borrow; errors are always `Copy`.
+                Some(param) if self.lookup_resourceness(&param.og_type).is_resource_type() => {
+                    "result.as_mut().map_err(|e| *e)".to_string()
+                }
+                _ => "result".to_string(),
+            }
+        } else {
+            format!("result.map(|{}| {})", pattern, body)
+        };
+
+        let convert = match success.parameters.len() {
+            0 => "out".to_string(),
+            _ if success.convert_to_tuple == "out" => "out".to_string(),
+            _ => format!("out.map(|out| {})", success.convert_to_tuple),
+        };
+
+        let param_type = format!(
+            "Result<{}, {}>",
+            tuple_of(success.parameters.iter().map(|p| p.r#type.clone()).collect()),
+            error.owned
+        );
+
+        types::Payload {
+            parameters: vec![types::Parameter {
+                og_type: result.success,
+                name: "result".to_string(),
+                r#type: param_type,
+                owned_type: alias.clone(),
+            }],
+            midl_type: if result.framework_err {
+                format!("midl::encoding::FlexibleResultType<{}, {}>", success_midl, error.midl)
+            } else {
+                format!("midl::encoding::ResultType<{}, {}>", success_midl, error.midl)
+            },
+            encode_expr: if result.framework_err {
+                format!("midl::encoding::FlexibleResult::new({})", mapped)
+            } else {
+                mapped
+            },
+            tuple_type: alias,
+            tuple_type_alias_rhs: format!("Result<{}, {}>", success_tuple, error.owned),
+            convert_to_tuple: convert.clone(),
+            convert_to_fields: format!("result: {},", convert),
+        }
+    }
+
+    fn is_overflowable(&self, payload: Option<&midlgen::ir::Type>) -> bool {
+        payload
+            .and_then(|p| p.get_type_shape_v2())
+            .is_some_and(|shape| shape.inline_size.saturating_add(shape.max_out_of_line) > MAX_MESSAGE_BYTES)
+    }
+
+    fn compile_method(&self, protocol: &str, val: midlgen::ir::ProtocolMethod) -> types::Method {
+        let name = self.compile_snake_identifier(val.name.clone());
+        let camel_name = self.compile_camel_identifier(val.name.clone());
+
+        let request = self.compile_plain_payload(val.request_payload.as_ref());
+        let result = self.lookup_result_union(&val);
+        let is_flexible = result.as_ref().is_some_and(|r| r.framework_err);
+        let has_error = result.as_ref().is_some_and(|r| r.error.is_some());
+        let response = match result {
+            Some(result) => self.compile_result_payload(result, format!("{}{}Result", protocol, camel_name)),
+            None => self.compile_plain_payload(val.response_payload.as_ref()),
+        };
+
+        let dynamic_flags = if val.strict {
+            "midl::encoding::DynamicFlags::empty()"
+        } else {
+            "midl::encoding::DynamicFlags::FLEXIBLE"
+        };
+
+        let overflowable = types::Overflowable {
+            on_request_encode: self.is_overflowable(val.request_payload.as_ref()),
+            on_request_decode: self.is_overflowable(val.request_payload.as_ref()),
+            on_response_encode: self.is_overflowable(val.response_payload.as_ref()),
+            on_response_decode: self.is_overflowable(val.response_payload.as_ref()),
+        };
+
+        types::Method {
+            doc_comments: doc_comments(&val.maybe_attributes),
+            ordinal: val.ordinal,
+            dynamic_flags: dynamic_flags.to_string(),
+            is_flexible,
+            has_request: val.has_request,
+            has_response: val.has_response,
+            has_error,
+            request,
+            response,
+            responder: format!("{}{}Responder", protocol, camel_name),
+            response_fut: format!("{}ResponseFut", camel_name),
+            overflowable,
+            name,
+            camel_name,
+            base: val,
+        }
+    }
+
+    fn compile_protocol(&self, val: midlgen::ir::Protocol) -> types::Protocol {
+        let name = self.compile_decl_identifier(&val.name);
+        let methods = val
+            .methods
+            .iter()
+            .map(|m| self.compile_method(&name, m.clone()))
+            .collect();
+
+        types::Protocol {
+            eci: val.name.clone(),
+            methods,
+            protocol_name: val.name.0.replace('/', "."),
+            discoverable: midlgen::ir::lookup_attribute(&val.maybe_attributes, "discoverable").is_some(),
+            doc_comments: doc_comments(&val.maybe_attributes),
+            marker: format!("{}Marker", name),
+            proxy: format!("{}Proxy", name),
+            proxy_interface: format!("{}ProxyInterface", name),
+            synchronous_proxy: format!("{}SynchronousProxy", name),
+            request: format!("{}Request", name),
+            request_stream: format!("{}RequestStream", name),
+            event: format!("{}Event", name),
+            event_stream: format!("{}EventStream", name),
+            control_handle: format!("{}ControlHandle", name),
+            one_way_unknown_interactions: val.openness != midlgen::ir::Openness::Closed,
+            two_way_unknown_interactions: val.openness == midlgen::ir::Openness::Open,
+            name,
+            base: val,
+        }
+    }
+}
+
+/// Splits a doc attribute into the lines of a `///` comment.
+fn doc_comments(attributes: &[midlgen::ir::Attribute]) -> Vec<String> {
+    midlgen::ir::doc_comment(attributes)
+        .map(|doc| doc.lines().map(|line| line.trim_end().to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// A Rust tuple type or pattern, without the parentheses for a single element.
+fn tuple_of(elements: Vec<String>) -> String {
+    match elements.len() {
+        1 => elements.into_iter().next().unwrap(),
+        _ => format!("({})", elements.join(", ")),
+    }
+}
+
+fn struct_to_tuple(parameters: &[types::Parameter], var: &str) -> String {
+    tuple_of(parameters.iter().map(|p| format!("{}.{}", var, p.name)).collect())
+}
+
 pub fn compile(ir: midlgen::ir::Root) -> Root {
     // TODO: add ir compilation
 
@@ -909,24 +1263,51 @@ pub fn compile(ir: midlgen::ir::Root) -> Root {
     let compiler = Compiler {
         decls: ir.decl_info(),
         library: this_lib_parsed,
-        structs: HashMap::new(),
+        structs: ir
+            .struct_declarations
+            .iter()
+            .chain(ir.external_struct_declarations.iter())
+            .map(|s| (s.name.clone(), s.clone()))
+            .collect(),
+        extern_crates: RefCell::default(),
     };
 
+    // Result unions are not generated as types, methods use `Result` instead.
+    let result_unions: HashSet<_> = ir
+        .protocol_declarations
+        .iter()
+        .flat_map(|p| p.methods.iter())
+        .filter(|m| m.has_result_union())
+        .filter_map(|m| match &m.response_payload {
+            Some(midlgen::ir::Type::IdentifierType { identifier, .. }) => Some(identifier.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let mut bits = vec![];
     let mut consts = vec![];
     let mut unions = vec![];
     let mut enums = vec![];
     let mut structs = vec![];
     let mut tables = vec![];
+    let mut protocols = vec![];
 
     for const_decl in ir.const_declarations {
         consts.push(compiler.compile_const(const_decl));
     }
 
+    for bits_decl in ir.bits_declarations {
+        bits.push(compiler.compile_bits(bits_decl));
+    }
+
     for enum_decl in ir.enum_declarations {
         enums.push(compiler.compile_enum(enum_decl));
     }
 
     for union_decl in ir.union_declarations {
+        if result_unions.contains(&union_decl.name) {
+            continue;
+        }
         unions.push(compiler.compile_union(union_decl));
     }
 
@@ -938,18 +1319,28 @@ pub fn compile(ir: midlgen::ir::Root) -> Root {
         tables.push(compiler.compile_table(table_decl));
     }
 
+    for protocol_decl in ir.protocol_declarations {
+        protocols.push(compiler.compile_protocol(protocol_decl));
+    }
+
     // println!("{:#?}", consts);
     // println!("{:#?}", enums);
     // println!("{:#?}", structs);
 
     Root {
+        extern_crates: compiler.extern_crates.take().into_iter().collect(),
+        bits,
         consts,
         enums,
         structs,
         unions,
         tables,
-        extern_crates: vec![],
         external_structs: vec![],
-        protocols: vec![],
+        protocols,
     }
 }
+
+/// The crate holding the bindings of `library`, e.g. `midl_meshx_io` for `meshx.io`.
+fn compile_library_name(library: &midlgen::ir::EncodedLibraryIdentifier) -> String {
+    format!("midl_{}", library.0.replace('.', "_"))
+}
@@ -54,14 +54,12 @@ handlebars::handlebars_helper!(reverse: |arg0: Value| {
     Value::Array(array)
 });
 
-handlebars::handlebars_helper!(unknown_value_for_tmpl: |*args| {
-    String::from("")
-});
-
 impl Generator {
     fn new() -> Self {
         let mut registry = Handlebars::new();
         registry.set_strict_mode(true);
+        // The output is Rust, not HTML: types like `Vec<u8>` must come out as written.
+        registry.register_escape_fn(handlebars::no_escape);
 
         let protocol_declaration_tpl = include_str!("./templates/protocol.hbs");
 
@@ -69,7 +67,6 @@ impl Generator {
         registry.register_helper("printf", Box::new(PrintfHelper {}));
         registry.register_helper("reverse", Box::new(reverse));
         registry.register_helper("doc_comments", Box::new(doc_comments));
-        registry.register_helper("unknown_value_for_tmpl", Box::new(unknown_value_for_tmpl));
 
         registry
             .register_template_string("GenerateSourceFile", include_str!("./templates/sourcefile.hbs"))
@@ -83,6 +80,14 @@ impl Generator {
             .register_partial("Enum", include_str!("./templates/enum.hbs"))
             .unwrap();
 
+        registry
+            .register_partial("Bits", include_str!("./templates/bits.hbs"))
+            .unwrap();
+
+        registry
+            .register_partial("BitsInternal", include_str!("./templates/bits_internal.hbs"))
+            .unwrap();
+
         registry
             .register_partial("Union", include_str!("./templates/union.hbs"))
             .unwrap();
@@ -172,3 +177,26 @@ fn main() -> Result<(), GeneratorError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The bindings checked in under goldens/ are what the generator produces
+    /// today; the goldens crate makes sure they also compile.
+    #[test]
+    fn golden() {
+        let json = include_str!("../goldens/golden.test.json");
+        let root = serde_json::from_str::<ir::Root>(json).unwrap();
+
+        let generated = Generator::new()
+            .execute_template("GenerateSourceFile", compile::compile(root))
+            .unwrap();
+
+        assert!(
+            generated == include_str!("../goldens/src/lib.rs"),
+            "goldens/src/lib.rs is stale, regenerate it with \
+             `cargo run -p midlgen_rust -- --json goldens/golden.test.json --out goldens/src/lib.rs`"
+        );
+    }
+}
@@ -56,8 +56,26 @@ pub struct UnionMember {
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Union {
+    pub ir: ir::Union,
     pub name: String,
     pub members: Vec<UnionMember>,
+    pub is_value_type: bool,
+    pub is_flexible: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Bits {
+    pub ir: ir::Bits,
+    pub name: String,
+    pub underlying_type: String,
+    pub members: Vec<BitsMember>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BitsMember {
+    pub ir: ir::BitsMember,
+    pub name: String,
+    pub value: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -69,6 +87,8 @@ pub struct Enum {
     /// Member name with the minimum value, used as an arbitrary default value
     /// in Decodable::new_empty for strict enums.
     pub min_member: String,
+    /// Value used by `unknown()` for flexible enums.
+    pub unknown_value: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -130,6 +150,7 @@ pub struct Table {
     pub eci: ir::EncodedCompoundIdentifier,
     pub name: String,
     pub members: Vec<TableMember>,
+    pub is_value_type: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -154,29 +175,10 @@ pub struct Struct {
     pub(crate) has_padding: bool,
     // True if the struct should be encoded and decoded by memcpy.
     pub(crate) use_midl_struct_copy: bool,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ResultOkEntry {
-    pub og_type: ir::Type,
-    pub r#type: String,
-    pub has_handle_metadata: bool,
-    pub handle_wrapper_name: String,
-}
-
-/// A Result is the result type used for a method that is flexible or uses error syntax.
-#[derive(Serialize, Deserialize, Debug)]
-pub struct CallResult {
-    /// Compound identifier for the result type, used for lookups.
-    pub eci: ir::EncodedCompoundIdentifier,
-    pub derives: Derives,
-    /// Rust UpperCamelCase name for the result type used when generating or
-    /// referencing it.
-    pub name: String,
-    pub ok: Vec<ResultOkEntry>,
-    pub err_og_type: Option<ir::Type>,
-    pub err_type: Option<String>,
-    pub has_transport_error: bool,
+    // True if the struct gets a borrowed `{name}View<'de>` counterpart.
+    pub(crate) has_view: bool,
+    // True if the struct holds no handles, i.e. it is not a resource type.
+    pub(crate) is_value_type: bool,
 }
 
 /// Protocol is the definition of a protocol in the library being compiled.
@@ -192,12 +194,32 @@ pub struct Protocol {
     /// is just the CamelCase name of the protocol.
     pub name: String,
     /// List of methods that are part of this protocol. Processed from
-    /// fidlgen.Protocol to add Rust-specific fields.
+    /// midlgen.Protocol to add Rust-specific fields.
     pub methods: Vec<Method>,
-    /// Name of this protocol for legacy (pre-RFC-0041) service discovery, if the
-    /// protocol is marked as discoverable. This value does not include enclosing
-    /// quote marks.
+    /// Name of this protocol used for debugging and, if the protocol is marked
+    /// as discoverable, service discovery. This value does not include
+    /// enclosing quote marks.
     pub protocol_name: String,
+    pub discoverable: bool,
+    pub doc_comments: Vec<String>,
+
+    // Names of the types generated for this protocol.
+    pub marker: String,
+    pub proxy: String,
+    pub proxy_interface: String,
+    pub synchronous_proxy: String,
+    pub request: String,
+    pub request_stream: String,
+    pub event: String,
+    pub event_stream: String,
+    pub control_handle: String,
+
+    /// True if unknown one-way methods and events are surfaced to the user
+    /// rather than closing the channel, i.e. the protocol is ajar or open.
+    pub one_way_unknown_interactions: bool,
+    /// True if unknown two-way methods are answered with a framework error,
+    /// i.e. the protocol is open.
+    pub two_way_unknown_interactions: bool,
 }
 
 /// Overflowable stores information about a method's payloads, indicating whether
@@ -237,55 +259,74 @@ pub struct Method {
     /// Name of the method converted to CamelCase. Used when generating
     /// rust-types associated with this method, such as responders.
     pub camel_name: String,
+    pub ordinal: u64,
+    /// Rust expression for the `midl::encoding::DynamicFlags` of messages sent
+    /// for this method.
+    pub dynamic_flags: String,
+    /// True if the response is wrapped in a result union carrying a framework
+    /// error, so that the client can tell the server did not know the method.
+    pub is_flexible: bool,
+    pub has_request: bool,
+    pub has_response: bool,
+    pub has_error: bool,
+    pub doc_comments: Vec<String>,
     /// Parameters to this method extracted from the request type struct.
-    pub request: Vec<Parameter>,
+    pub request: Payload,
     /// Arguments used for method responses. If error syntax is used, this will
-    /// contain a single element for the Result enum used in rust generated code.
-    /// For methods which do not use error syntax, this will contain fields
-    /// extracted from the response struct.
-    ///
-    /// Note that since methods being strict vs flexible is not exposed in the
-    /// client API, this field does not reflect whether the method is strict or
-    /// flexible. For flexible, the value is still either fields extracted from
-    /// the response struct or the Rust Result enum, depending only on whether
-    /// error syntax was used.  In the case of flexible methods without error
-    /// syntax, the parameters are extracted from the success variant of the
-    /// underlying result union.
-    pub response: Vec<Parameter>,
-    /// If error syntax was used, this will contain information about the result
-    /// union.
-    pub result: CallResult,
+    /// contain a single `result` parameter for the Rust Result enum. For methods
+    /// which do not use error syntax, this will contain fields extracted from
+    /// the response struct, or from the success variant of the result union
+    /// for flexible methods.
+    pub response: Payload,
+    /// Name of the responder type generated for two-way methods.
+    pub responder: String,
+    /// Name of the response future associated type in the proxy interface.
+    pub response_fut: String,
     /// Stores overflowing information for this method's payloads.
     pub overflowable: Overflowable,
 }
 
-/// A Parameter to either the requset or response of a method. Contains
-/// information to assist in generating code using borrowed types and handle
-/// wrappers.
+/// A request or response payload, with the Rust expressions needed to move it
+/// between its parameters and its wire type.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Payload {
+    pub parameters: Vec<Parameter>,
+    /// The marker type that implements midl::encoding::TypeMarker for the
+    /// whole message body.
+    pub midl_type: String,
+    /// Expression encoding the parameters as `midl_type`.
+    pub encode_expr: String,
+    /// Owned type returned to the caller, e.g. `(u32, String)`. For methods
+    /// with error syntax this is the name of the alias for the Result.
+    pub tuple_type: String,
+    /// Right hand side of the Result alias for methods with error syntax.
+    pub tuple_type_alias_rhs: String,
+    /// Expression converting the decoded body `out` into `tuple_type`.
+    pub convert_to_tuple: String,
+    /// Struct expression fields initialising the parameters from the decoded
+    /// body `out`.
+    pub convert_to_fields: String,
+}
+
+/// A Parameter to either the request or response of a method.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Parameter {
-    /// The raw fidlgen type of the parameter.
+    /// The raw midlgen type of the parameter.
     pub og_type: ir::Type,
-    /// String representing the type to use for this parameter when handling it
-    /// by-value.
-    pub r#type: String,
-    /// String representing the type to use for this parameter when receiving it
-    /// as a possibly-borrowed method argument.
-    pub borrowed_type: String,
     /// Snake-case name to use for the parameter.
     pub name: String,
-    /// Name of the wrapper type that should be used for handle validation, if
-    /// HasHandleMetadata is true.
-    pub handle_wrapper_name: String,
-    /// True if the type of the parameter has handle metadata and so requires
-    /// validation.
-    pub has_handle_metadata: bool,
+    /// String representing the type to use for this parameter when receiving it
+    /// as a possibly-borrowed method argument.
+    pub r#type: String,
+    /// String representing the type to use for this parameter when handling it
+    /// by-value.
+    pub owned_type: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Root {
     pub extern_crates: Vec<String>,
-    // bits: Vec<Bits>,
+    pub bits: Vec<Bits>,
     pub consts: Vec<Const>,
     pub enums: Vec<Enum>,
     pub unions: Vec<Union>,
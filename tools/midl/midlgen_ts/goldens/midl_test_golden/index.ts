@@ -0,0 +1,260 @@
+// WARNING: This file is machine generated by midlgen.
+
+/* eslint-disable @typescript-eslint/no-inferrable-types */
+
+
+
+import * as midl from "@meshx-org/midl"
+import * as fiber from "@meshx-org/fiber-ts"
+import * as fx from "@meshx-org/fiber-types"
+
+
+export class Permissions extends midl.Bits<number> {
+    public static readonly READ = new Permissions(1)
+    public static readonly WRITE = new Permissions(2)
+    public static readonly EXECUTE = new Permissions(4)
+
+    /// All known bits.
+    public static readonly $mask: number = 7
+
+    public static create(value: number): Permissions {
+        return new Permissions(value)
+    }
+
+    public has(other: Permissions): boolean {
+        return ((this.$value & other.$value) >>> 0) === other.$value
+    }
+
+    public or(other: Permissions): Permissions {
+        return new Permissions((this.$value | other.$value) >>> 0)
+    }
+
+    public and(other: Permissions): Permissions {
+        return new Permissions((this.$value & other.$value) >>> 0)
+    }
+
+    /// The bits that are not members of Permissions.
+    public get $unknownBits(): number {
+        return (this.$value & ~Permissions.$mask) >>> 0
+    }
+}
+
+export const _PermissionsType = new midl.BitsType<Permissions, number>(
+    new midl.UInt8Type(),
+    (value) => Permissions.create(value),
+    Permissions.$mask
+)
+
+
+export class Features extends midl.Bits<number> {
+    public static readonly FAST = new Features(1)
+    public static readonly SMALL = new Features(256)
+
+    /// All known bits.
+    public static readonly $mask: number = 257
+
+    public static create(value: number): Features {
+        return new Features(value)
+    }
+
+    public has(other: Features): boolean {
+        return ((this.$value & other.$value) >>> 0) === other.$value
+    }
+
+    public or(other: Features): Features {
+        return new Features((this.$value | other.$value) >>> 0)
+    }
+
+    public and(other: Features): Features {
+        return new Features((this.$value & other.$value) >>> 0)
+    }
+
+    /// The bits that are not members of Features.
+    public get $unknownBits(): number {
+        return (this.$value & ~Features.$mask) >>> 0
+    }
+}
+
+export const _FeaturesType = new midl.BitsType<Features, number>(
+    new midl.UInt32Type(),
+    (value) => Features.create(value),
+    null
+)
+
+
+enum GoldenUnionTag {
+    $unknown,
+    Small = 1,
+    Big = 2,
+    
+}
+
+const _GoldenUnion_map: Map<number, GoldenUnionTag> = new Map<number, GoldenUnionTag>([
+    [1, GoldenUnionTag.Small],
+    [2, GoldenUnionTag.Big],
+    
+])
+
+export class GoldenUnion extends midl.Union {
+    #ordinal: number
+    #data: any
+
+    constructor(ordinal: number, data: any) {
+        super()
+        this.#ordinal = ordinal
+        this.#data = data
+    }
+
+    
+    static withSmall(value: number): GoldenUnion {
+        return new GoldenUnion(1, value)
+    }
+    
+    static withBig(value: bigint): GoldenUnion {
+        return new GoldenUnion(2, value)
+    }
+    
+
+    static with$UnknownData(ordinal: number, data: midl.UnknownRawData): GoldenUnion {
+        return new GoldenUnion(ordinal, data)
+    }
+
+    get $tag(): GoldenUnionTag {
+        return _GoldenUnion_map.get(this.#ordinal) ?? GoldenUnionTag.$unknown
+    }
+
+    
+    get Small(): number | null {
+        if (this.#ordinal !== 1) return null
+        return this.#data as number
+    }
+    
+    get Big(): bigint | null {
+        if (this.#ordinal !== 2) return null
+        return this.#data as bigint
+    }
+    
+
+    get $unknownData(): midl.UnknownRawData | null {
+        if (_GoldenUnion_map.has(this.#ordinal)) {
+            return null
+        }
+        return this.#data as midl.UnknownRawData
+    }
+
+    override get $ordinal(): number {
+        return this.#ordinal
+    }
+
+    override get $data(): unknown {
+        return this.#data
+    }
+
+    public override valueOf() {
+        return this.#data
+    }
+
+    static ctor(ordinal: number, data: object): GoldenUnion {
+        return new GoldenUnion(ordinal, data)
+    }
+}
+
+const _GoldenUnionMembers = {
+    1: new midl.UInt32Type(),
+    2: new midl.UInt64Type(),
+    
+}
+
+export const _GoldenUnionType = new midl.UnionType<GoldenUnion>(
+    _GoldenUnionMembers,
+    GoldenUnion.ctor,
+    false,
+    false
+)
+
+export const _GoldenUnionNullableType = new midl.NullableUnionType<GoldenUnion>(
+    _GoldenUnionMembers,
+    GoldenUnion.ctor,
+    false,
+    false
+)
+
+
+export class GoldenTable extends midl.Table {
+    constructor(
+        public a: number | null = null,
+        public b: bigint | null = null,
+        public c: string | null = null,
+        public $unknownData: Map<number, midl.UnknownRawData> = new Map(),
+    ) {
+        super()
+    }
+
+    override get $fields(): Map<number, unknown> {
+        const fields = new Map<number, unknown>(this.$unknownData)
+        if (this.a !== null) fields.set(1, this.a)
+        if (this.b !== null) fields.set(2, this.b)
+        if (this.c !== null) fields.set(3, this.c)
+        return fields
+    }
+
+    static ctor(fields: Map<number, unknown>): GoldenTable {
+        const unknownData = new Map<number, midl.UnknownRawData>()
+        for (const [ordinal, data] of fields) {
+            if (!_GoldenTableMembers.hasOwnProperty(ordinal)) {
+                unknownData.set(ordinal, data as midl.UnknownRawData)
+            }
+        }
+
+        return new GoldenTable(
+            (fields.get(1) ?? null) as number | null,
+            (fields.get(2) ?? null) as bigint | null,
+            (fields.get(3) ?? null) as string | null,
+            unknownData,
+        )
+    }
+}
+
+const _GoldenTableMembers = {
+    1: new midl.UInt32Type(),
+    2: new midl.UInt64Type(),
+    3: new midl.StringType(),
+    
+}
+
+export const _GoldenTableType = new midl.TableType<GoldenTable>(
+    _GoldenTableMembers,
+    GoldenTable.ctor,
+    false
+)
+
+
+export class Foo implements midl.Struct {
+    static #fieldType0 = new midl.UInt8Type();
+    static #fieldType1 = new midl.UInt64Type();
+    static #fieldType2 = new midl.StringType(5);
+    
+    constructor(
+        public byte_: number, public bignum: bigint, public string: string, 
+    ) {}
+
+    $encode(encoder: midl.Encoder, offset: number, depth: number): void {
+        Foo.#fieldType0.encode(encoder, this.byte_, offset + 0, depth);
+        Foo.#fieldType1.encode(encoder, this.bignum, offset + 8, depth);
+        Foo.#fieldType2.encode(encoder, this.string, offset + 16, depth);
+    }
+
+    static $decode(decoder: midl.Decoder, offset: number, depth: number) {
+        decoder.checkPadding(offset + 1, 7);
+        return new Foo(
+            Foo.#fieldType0.decode(decoder, offset + 0, depth),
+            Foo.#fieldType1.decode(decoder, offset + 8, depth),
+            Foo.#fieldType2.decode(decoder, offset + 16, depth),
+        )
+    }
+}
+
+export const _FooType = new midl.StructType<Foo>(32, Foo.$decode)
+
+
+
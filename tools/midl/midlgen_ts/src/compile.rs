@@ -181,37 +181,15 @@ lazy_static::lazy_static! {
         m
     };
 
+    // Object types known to the TypeScript kernel. Handles of any other
+    // subtype are not type-checked on transfer.
     static ref OBJECT_TYPES: HashMap<midlgen::ir::HandleSubtype, String> = {
         let mut m = HashMap::new();
-        m.insert(midlgen::ir::HandleSubtype::None, "FX_OBJECT_TYPE_NONE".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Channel, "FX_OBJECT_TYPE_CHANNEL".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Event, "FX_OBJECT_TYPE_EVENT".to_string());
-        m.insert(midlgen::ir::HandleSubtype::EventPair, "FX_OBJECT_TYPE_EVENTPAIR".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Exception, "midl.ObjectType.EXCEPTION".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Guest, "midl.ObjectType.GUEST".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Fifo, "midl.ObjectType.FIFO".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Bti, "midl.ObjectType.BTI".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Clock, "midl.ObjectType.CLOCK".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Debuglog, "midl.ObjectType.DEBUGLOG".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Interrupt, "midl.ObjectType.INTERRUPT".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Iommu, "midl.ObjectType.IOMMU".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Job, "midl.ObjectType.JOB".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Msi, "midl.ObjectType.MSI".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Pager, "midl.ObjectType.PAGER".to_string());
-        m.insert(midlgen::ir::HandleSubtype::PciDevice, "midl.ObjectType.PCIDEVICE".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Pmt, "midl.ObjectType.PMT".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Port, "midl.ObjectType.PORT".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Process, "midl.ObjectType.PROCESS".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Profile, "midl.ObjectType.PROFILE".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Resource, "midl.ObjectType.RESOURCE".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Socket, "FX_OBJECT_TYPE_SOCKET".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Stream, "midl.ObjectType.STREAM".to_string());
-        m.insert(midlgen::ir::HandleSubtype::SuspendToken, "midl.ObjectType.SUSPENDTOKEN".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Thread, "midl.ObjectType.THREAD".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Timer, "midl.ObjectType.TIMER".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Vcpu, "midl.ObjectType.VCPU".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Vmar, "midl.ObjectType.VMAR".to_string());
-        m.insert(midlgen::ir::HandleSubtype::Vmo, "midl.ObjectType.VMO".to_string());
+        m.insert(midlgen::ir::HandleSubtype::None, "fx.FX_OBJ_TYPE_NONE".to_string());
+        m.insert(midlgen::ir::HandleSubtype::Channel, "fx.FX_OBJ_TYPE_CHANNEL".to_string());
+        m.insert(midlgen::ir::HandleSubtype::Process, "fx.FX_OBJ_TYPE_PROCESS".to_string());
+        m.insert(midlgen::ir::HandleSubtype::Job, "fx.FX_OBJ_TYPE_JOB".to_string());
+        m.insert(midlgen::ir::HandleSubtype::Port, "fx.FX_OBJ_TYPE_PORT".to_string());
 
         m
     };
@@ -353,8 +331,8 @@ impl Compiler {
     fn compile_object_type_const(&self, val: &ir::HandleSubtype) -> String {
         OBJECT_TYPES
             .get(val)
-            .expect(format!("unknown handle type: {:?}", val).as_str())
-            .clone()
+            .cloned()
+            .unwrap_or_else(|| "fx.FX_OBJ_TYPE_NONE".to_string())
     }
 
     fn compile_handle_subtype(&self, val: &ir::HandleSubtype) -> String {
@@ -388,6 +366,7 @@ impl Compiler {
                 t.value_type = prim.clone();
                 t.param = prim;
                 t.ctor = format!("new {}()", midl);
+                t.kind = types::TypeKind::PrimitiveType;
                 t.primitive_subtype = Some(primitive_subtype.clone());
             }
             midlgen::ir::Type::InternalType { internal_subtype, .. } => match internal_subtype {
@@ -409,14 +388,12 @@ impl Compiler {
                 ..
             } => {
                 let el = self.compile_type(element_type);
-                t.element_type = Some(Box::from(el.clone()));
-                t.midl = format!("midl.ArrayType<{}, {}>", el.midl, element_count);
-
-                if resourceness.is_resource_type() {
-                    t.param = t.value_type.clone();
-                } else {
-                    t.param = format!("&{}", t.value_type);
-                }
+                t.kind = types::TypeKind::ArrayType;
+                t.midl = "midl.ArrayType".to_string();
+                t.ctor = format!("new {}({}, {})", t.midl, el.ctor, element_count);
+                t.value_type = format!("{}[]", el.value_type);
+                t.param = t.value_type.clone();
+                t.element_type = Some(Box::from(el));
             }
             midlgen::ir::Type::VectorType {
                 element_type,
@@ -425,43 +402,40 @@ impl Compiler {
                 ..
             } => {
                 let el = self.compile_type(&element_type);
-                t.element_type = Some(Box::from(el.clone()));
+                let max_length = element_count.map(|count| format!(", {count}")).unwrap_or_default();
 
-                if element_count.is_none() {
-                    t.midl = format!("midl.UnboundedVectorType<{}>", el.midl);
-                } else {
-                    t.midl = format!("midl.VectorType<{}, {}>", el.midl, element_count.expect("to be some"));
-                }
-
-                t.param = format!("{}[]", el.value_type);
+                t.kind = types::TypeKind::VectorType;
+                t.nullable = *nullable;
+                t.midl = "midl.VectorType".to_string();
+                t.value_type = format!("{}[]", el.value_type);
+                t.param = t.value_type.clone();
 
                 if *nullable {
-                    t.midl = format!("midl.OptionalType<{}>", t.midl);
-                    t.ctor = format!("new {}({})", t.midl, t.ctor);
+                    t.midl = "midl.NullableVectorType".to_string();
                     t.param = format!("{} | null", t.param);
                 }
+
+                t.ctor = format!("new {}({}{})", t.midl, el.ctor, max_length);
+                t.element_type = Some(Box::from(el));
             }
             midlgen::ir::Type::StringType {
                 element_count,
                 nullable,
                 ..
             } => {
-                if element_count.is_none() {
-                    t.midl = "midl.StringType".to_string();
-                } else {
-                    let element_count = element_count.unwrap();
-                    t.midl = format!("midl.StringType<{element_count}>");
-                }
+                let max_length = element_count.map(|count| count.to_string()).unwrap_or_default();
 
-                t.param = "string".to_string();
                 t.nullable = *nullable;
-                t.ctor = format!("new {}()", &t.midl);
+                t.midl = "midl.StringType".to_string();
+                t.value_type = "string".to_string();
+                t.param = t.value_type.clone();
 
                 if *nullable {
-                    t.midl = format!("midl.OptionalType<{}>", t.midl);
-                    t.ctor = format!("new {}({})", t.midl, t.ctor);
+                    t.midl = "midl.NullableStringType".to_string();
                     t.param = format!("{} | null", t.param);
                 }
+
+                t.ctor = format!("new {}({})", t.midl, max_length);
             }
             midlgen::ir::Type::HandleType {
                 handle_subtype,
@@ -469,19 +443,20 @@ impl Compiler {
                 handle_rights,
                 ..
             } => {
-                let s = self.compile_handle_subtype(handle_subtype);
                 let obj_type = self.compile_object_type_const(handle_subtype);
 
+                t.kind = types::TypeKind::HandleType;
                 t.nullable = *nullable;
-                t.midl = format!("midl.HandleType");
-                t.ctor = format!("new {}({}, {})", t.midl, obj_type, handle_rights.bits());
-                t.param = s;
+                t.midl = "midl.HandleType".to_string();
+                t.value_type = "fiber.Handle".to_string();
+                t.param = t.value_type.clone();
 
                 if *nullable {
-                    t.midl = format!("midl.OptionalType<{}>", t.midl);
-                    t.ctor = format!("new {}({})", t.midl, t.ctor);
+                    t.midl = "midl.NullableHandleType".to_string();
                     t.param = format!("{} | null", t.param);
                 }
+
+                t.ctor = format!("new {}({}, {})", t.midl, obj_type, handle_rights.bits());
             }
             midlgen::ir::Type::ClientEnd {
                 identifier, nullable, ..
@@ -519,31 +494,42 @@ impl Compiler {
                 t.identifier = Some(identifier.clone());
                 t.nullable = *nullable;
 
+                t.kind = types::TypeKind::IdentifierType;
+                t.value_type = name.clone();
+                t.param = name.clone();
+
                 match decl_info.r#type {
                     midlgen::ir::DeclType::EnumDecl => {
-                        t.midl = format!("midl.EnumType<{}>", name.clone());
-                        t.ctor = format!("_{}Type", name.clone());
-                        t.param = name;
+                        t.midl = "midl.EnumType".to_string();
+                        t.ctor = format!("_{}Type", name);
                     }
                     midlgen::ir::DeclType::BitsDecl => {
-                        t.midl = format!("midl.BitsType<{}>", name.clone());
-                        t.ctor = format!("_{}Type", name.clone());
-                        t.param = name;
+                        t.midl = "midl.BitsType".to_string();
+                        t.ctor = format!("_{}Type", name);
                     }
                     midlgen::ir::DeclType::StructDecl => {
-                        t.midl = format!("midl.StructType<{}>", name.clone());
-                        t.ctor = format!("_{}Type", name.clone());
-                        t.param = name;
+                        t.midl = "midl.StructType".to_string();
+                        t.ctor = format!("_{}Type", name);
+
+                        if *nullable {
+                            t.midl = "midl.BoxType".to_string();
+                            t.ctor = format!("new {}(_{}Type)", t.midl, name);
+                            t.param = format!("{} | null", t.param);
+                        }
                     }
                     midlgen::ir::DeclType::TableDecl => {
-                        t.midl = format!("midl.TableType<{}>", name.clone());
-                        t.ctor = format!("_{}Type", name.clone());
-                        t.param = name;
+                        t.midl = "midl.TableType".to_string();
+                        t.ctor = format!("_{}Type", name);
                     }
                     midlgen::ir::DeclType::UnionDecl => {
-                        t.midl = format!("midl.UnionType<{}>", name.clone());
-                        t.ctor = format!("_{}Type", name.clone());
-                        t.param = name;
+                        t.midl = "midl.UnionType".to_string();
+                        t.ctor = format!("_{}Type", name);
+
+                        if *nullable {
+                            t.midl = "midl.NullableUnionType".to_string();
+                            t.ctor = format!("_{}NullableType", name);
+                            t.param = format!("{} | null", t.param);
+                        }
                     }
                     midlgen::ir::DeclType::ProtocolDecl => {
                         let s = format!("midl.ClientEnd<{}Marker>", name);
@@ -723,10 +709,15 @@ impl Compiler {
         for v in val.members {
             let ir = v.clone();
 
+            // Reserved members have neither a name nor a type.
+            let (Some(name), Some(r#type)) = (v.name, v.r#type) else {
+                continue;
+            };
+
             members.push(types::UnionMember {
                 ir,
-                r#type: self.compile_type(&v.r#type.unwrap()),
-                name: self.compile_camel_identifier(&v.name.expect("not reserved")),
+                r#type: self.compile_type(&r#type),
+                name: self.compile_camel_identifier(&name),
                 ordinal: v.ordinal,
             })
         }
@@ -738,6 +729,35 @@ impl Compiler {
         }
     }
 
+    fn compile_table(&self, val: ir::Table) -> types::Table {
+        let ir = val.clone();
+        let mut members = vec![];
+
+        for v in val.members {
+            let ir = v.clone();
+
+            // Reserved members have neither a name nor a type.
+            let (Some(name), Some(r#type)) = (v.name, v.r#type) else {
+                continue;
+            };
+
+            members.push(types::TableMember {
+                ir,
+                r#type: self.compile_type(&r#type),
+                name: self.compile_snake_identifier(&name),
+                ordinal: v.ordinal as u64,
+            })
+        }
+
+        members.sort_by_key(|m| m.ordinal);
+
+        types::Table {
+            ir,
+            name: self.compile_decl_identifier(&val.name),
+            members,
+        }
+    }
+
     fn compile_enum(&self, val: ir::Enum) -> types::Enum {
         let ir = val.clone();
         let mut members = vec![];
@@ -767,6 +787,46 @@ impl Compiler {
         }
     }
 
+    fn compile_bits(&self, val: ir::Bits) -> types::Bits {
+        let ir::Type::PrimitiveType { primitive_subtype, .. } = &val.r#type else {
+            panic!("bits must have a primitive type: {:?}", val.r#type);
+        };
+        let (value_type, underlying_type) = self.compile_primitive_subtype(primitive_subtype);
+
+        // 64 bit values do not fit a number, so their literals are bigints.
+        let literal = |value: &String| {
+            if value_type == "bigint" {
+                format!("{}n", value)
+            } else {
+                value.clone()
+            }
+        };
+
+        let mut members = vec![];
+
+        for v in val.members.iter() {
+            let (ir::Constant::Identifier { value, .. }
+            | ir::Constant::LiteralConstant { value, .. }
+            | ir::Constant::BinaryOperator { value, .. }) = &v.value;
+
+            members.push(types::BitsMember {
+                ir: v.clone(),
+                name: self.compile_screaming_snake_identifier(&v.name),
+                value: literal(value),
+            })
+        }
+
+        types::Bits {
+            name: self.compile_decl_identifier(&val.name),
+            mask: literal(&val.mask),
+            strict: val.is_strict,
+            underlying_type,
+            value_type,
+            members,
+            ir: val,
+        }
+    }
+
     fn compile_struct_member(&self, val: midlgen::ir::StructMember) -> types::StructMember {
         let ir = val.clone();
 
@@ -775,6 +835,8 @@ impl Compiler {
             r#type: self.compile_type(&val.r#type),
             name: self.compile_snake_identifier(&val.name),
             offset_v2: val.field_shape_v2.offset,
+            padding_offset_v2: 0,
+            padding_v2: val.field_shape_v2.padding,
         }
     }
 
@@ -790,6 +852,14 @@ impl Compiler {
             has_padding = has_padding || (v.field_shape_v2.padding != 0);
         }
 
+        // Padding trails each member, so it ends where the next member (or the
+        // struct itself) begins.
+        let mut end = val.type_shape_v2.inline_size;
+        for member in members.iter_mut().rev() {
+            member.padding_offset_v2 = end - member.padding_v2;
+            end = member.offset_v2;
+        }
+
         types::Struct {
             ir,
             eci: val.name.clone(),
@@ -1124,10 +1194,18 @@ pub fn compile(ir: ir::Root) -> Root {
         enums.push(compiler.compile_enum(enum_decl));
     }
 
+    for bits_decl in ir.bits_declarations {
+        bits.push(compiler.compile_bits(bits_decl));
+    }
+
     for union_decl in ir.union_declarations {
         unions.push(compiler.compile_union(union_decl));
     }
 
+    for table_decl in ir.table_declarations {
+        tables.push(compiler.compile_table(table_decl));
+    }
+
     for struct_decl in ir.struct_declarations {
         structs.push(compiler.compile_struct(struct_decl));
     }
@@ -43,30 +43,34 @@ struct Generator {
     tera: Tera,
 }
 
-static LIBRARY_TEMPLATE: &'static str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/library.jinja"));
-static CONTST_TEMPLATE: &'static str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/const.jinja"));
-static ENUM_TEMPLATE: &'static str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/enum.jinja"));
-static UNION_TEMPLATE: &'static str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/union.jinja"));
-static STRUCT_TEMPLATE: &'static str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/struct.jinja"));
-static PROTOCOL_TEMPLATE: &'static str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/protocol.jinja"));
-static PACKAGE_JSON_TEMPLATE: &'static str =
+static LIBRARY_TEMPLATE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/library.jinja"));
+static CONTST_TEMPLATE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/const.jinja"));
+static ENUM_TEMPLATE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/enum.jinja"));
+static BITS_TEMPLATE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/bits.jinja"));
+static UNION_TEMPLATE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/union.jinja"));
+static TABLE_TEMPLATE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/table.jinja"));
+static STRUCT_TEMPLATE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/struct.jinja"));
+static PROTOCOL_TEMPLATE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/protocol.jinja"));
+static PACKAGE_JSON_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/package.json.jinja"));
-static TSCONFIG_JSON_TEMPLATE: &'static str =
+static TSCONFIG_JSON_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/tsconfig.json.jinja"));
 
 impl Generator {
     fn new(out_dir: PathBuf) -> Result<Self, GeneratorError> {
         let mut tera = Tera::default();
 
-        tera.add_raw_template("const.jinja", &CONTST_TEMPLATE)?;
-        tera.add_raw_template("enum.jinja", &ENUM_TEMPLATE)?;
-        tera.add_raw_template("union.jinja", &UNION_TEMPLATE)?;
-        tera.add_raw_template("struct.jinja", &STRUCT_TEMPLATE)?;
-        tera.add_raw_template("protocol.jinja", &PROTOCOL_TEMPLATE)?;
-        tera.add_raw_template("package.json.jinja", &PACKAGE_JSON_TEMPLATE)?;
-        tera.add_raw_template("tsconfig.json.jinja", &TSCONFIG_JSON_TEMPLATE)?;
+        tera.add_raw_template("const.jinja", CONTST_TEMPLATE)?;
+        tera.add_raw_template("enum.jinja", ENUM_TEMPLATE)?;
+        tera.add_raw_template("bits.jinja", BITS_TEMPLATE)?;
+        tera.add_raw_template("union.jinja", UNION_TEMPLATE)?;
+        tera.add_raw_template("table.jinja", TABLE_TEMPLATE)?;
+        tera.add_raw_template("struct.jinja", STRUCT_TEMPLATE)?;
+        tera.add_raw_template("protocol.jinja", PROTOCOL_TEMPLATE)?;
+        tera.add_raw_template("package.json.jinja", PACKAGE_JSON_TEMPLATE)?;
+        tera.add_raw_template("tsconfig.json.jinja", TSCONFIG_JSON_TEMPLATE)?;
 
-        tera.add_raw_template("library.jinja", &LIBRARY_TEMPLATE)?;
+        tera.add_raw_template("library.jinja", LIBRARY_TEMPLATE)?;
 
         Ok(Generator { out_dir, tera })
     }
@@ -80,27 +84,24 @@ impl Generator {
         //let path = PathBuf::from(filename.clone());
         //let dir = path.parent().unwrap();
         //std::fs::create_dir_all(dir)?;
-        std::fs::create_dir_all(format!("{}/{}", self.out_dir.display(), &data.library_name))?;
+        std::fs::create_dir_all(format!("{}/{}", self.out_dir.display(), data.library_name))?;
 
-        println!(
-            "{}",
-            format!("{}/{}/index.ts", self.out_dir.display(), &data.library_name)
-        );
-        let file = File::create(format!("{}/{}/index.ts", self.out_dir.display(), &data.library_name))?;
+        println!("{}/{}/index.ts", self.out_dir.display(), data.library_name);
+        let file = File::create(format!("{}/{}/index.ts", self.out_dir.display(), data.library_name))?;
         let context = Context::from_serialize(&data)?;
         self.tera.render_to("library.jinja", &context, file)?;
 
         let package_json = File::create(format!(
             "{}/{}/package.json",
             self.out_dir.display(),
-            &data.library_name
+            data.library_name
         ))?;
         self.tera.render_to("package.json.jinja", &context, package_json)?;
 
         let tsconfig_json = File::create(format!(
             "{}/{}/tsconfig.json",
             self.out_dir.display(),
-            &data.library_name
+            data.library_name
         ))?;
         self.tera.render_to("tsconfig.json.jinja", &context, tsconfig_json)?;
 
@@ -132,3 +133,28 @@ fn main() -> Result<(), GeneratorError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The bindings checked in under goldens/ are what the generator produces
+    /// today; golden.spec.ts in @meshx-org/midl runs the wire format goldens
+    /// over them.
+    #[test]
+    fn golden() {
+        let json = include_str!("../goldens/golden.test.json");
+        let root = serde_json::from_str::<ir::Root>(json).unwrap();
+
+        let generator = Generator::new(PathBuf::new()).unwrap();
+        let context = Context::from_serialize(compile::compile(root)).unwrap();
+        let generated = generator.tera.render("library.jinja", &context).unwrap();
+
+        assert!(
+            generated == include_str!("../goldens/midl_test_golden/index.ts"),
+            "goldens/midl_test_golden/index.ts is stale, regenerate it with \
+             `cargo run -p midlgen_ts -- --json goldens/golden.test.json --out <dir>` \
+             and copy <dir>/midl_test_golden/index.ts"
+        );
+    }
+}
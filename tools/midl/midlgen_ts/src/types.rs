@@ -30,8 +30,26 @@ pub struct EnumMember {
     pub value: String,
 }
 
-#[derive(Serialize, Default, Debug)]
-pub struct Bits;
+#[derive(Serialize, Debug)]
+pub struct Bits {
+    pub ir: ir::Bits,
+    pub name: String,
+    /// The midl.MidlType of the underlying primitive, e.g. `midl.UInt8Type`.
+    pub underlying_type: String,
+    /// `number`, or `bigint` for 64 bit bits.
+    pub value_type: String,
+    /// All known bits as a TypeScript literal.
+    pub mask: String,
+    pub strict: bool,
+    pub members: Vec<BitsMember>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BitsMember {
+    pub ir: ir::BitsMember,
+    pub name: String,
+    pub value: String,
+}
 
 // A request parameter
 #[derive(Serialize, Debug)]
@@ -165,8 +183,20 @@ pub struct Overflowable {
     pub on_response_decode: bool,
 }
 
-#[derive(Serialize, Default, Debug)]
-pub struct Table;
+#[derive(Serialize, Debug)]
+pub struct TableMember {
+    pub ir: ir::TableMember,
+    pub name: String,
+    pub r#type: Type,
+    pub ordinal: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Table {
+    pub ir: ir::Table,
+    pub name: String,
+    pub members: Vec<TableMember>,
+}
 
 #[derive(Debug, Serialize)]
 pub struct StructMember {
@@ -174,6 +204,8 @@ pub struct StructMember {
     pub(crate) r#type: Type,
     pub(crate) name: String,
     pub(crate) offset_v2: u32,
+    pub(crate) padding_offset_v2: u32,
+    pub(crate) padding_v2: u32,
 }
 
 // Struct represents a struct declaration.